@@ -119,6 +119,7 @@ fn parse_glsl(stage: naga::ShaderStage, inputs: &Inputs) {
     let options = naga::front::glsl::Options {
         stage,
         defines: Default::default(),
+        include_resolver: None,
     };
     for input in &inputs.inner {
         parser
@@ -417,7 +418,10 @@ fn backends(c: &mut Criterion) {
                 version: naga::back::glsl::Version::new_gles(320),
                 writer_flags: naga::back::glsl::WriterFlags::empty(),
                 binding_map: Default::default(),
+                external_texture_binding_map: Default::default(),
                 zero_initialize_workgroup_memory: true,
+                source_map: false,
+                line_directives: None,
             };
             for input in &inputs.inner {
                 let module = input.module.as_ref().unwrap();
@@ -161,6 +161,13 @@ impl DeviceInterface for CustomDevice {
         unimplemented!()
     }
 
+    fn create_render_pipeline_async(
+        &self,
+        _desc: &wgpu::RenderPipelineDescriptor<'_>,
+    ) -> Pin<Box<dyn wgpu::custom::CreateRenderPipelineFuture>> {
+        unimplemented!()
+    }
+
     fn create_mesh_pipeline(
         &self,
         _desc: &wgpu::MeshPipelineDescriptor<'_>,
@@ -176,6 +183,13 @@ impl DeviceInterface for CustomDevice {
         wgpu::custom::DispatchComputePipeline::custom(CustomComputePipeline(module.0.clone()))
     }
 
+    fn create_compute_pipeline_async(
+        &self,
+        _desc: &wgpu::ComputePipelineDescriptor<'_>,
+    ) -> Pin<Box<dyn wgpu::custom::CreateComputePipelineFuture>> {
+        unimplemented!()
+    }
+
     unsafe fn create_pipeline_cache(
         &self,
         _desc: &wgpu::PipelineCacheDescriptor<'_>,
@@ -183,6 +197,13 @@ impl DeviceInterface for CustomDevice {
         unimplemented!()
     }
 
+    fn derive_bind_group_layouts(
+        &self,
+        _entry_points: &[(&wgpu::ShaderModule, &str)],
+    ) -> Result<Vec<Vec<wgpu::BindGroupLayoutEntry>>, wgpu::Error> {
+        unimplemented!()
+    }
+
     fn create_buffer(&self, _desc: &wgpu::BufferDescriptor<'_>) -> wgpu::custom::DispatchBuffer {
         unimplemented!()
     }
@@ -222,6 +243,24 @@ impl DeviceInterface for CustomDevice {
         unimplemented!()
     }
 
+    fn create_heap(&self, _desc: &wgpu::HeapDescriptor<'_>) -> wgpu::custom::DispatchHeap {
+        unimplemented!()
+    }
+
+    fn get_buffer_memory_requirements(
+        &self,
+        _desc: &wgpu::BufferDescriptor<'_>,
+    ) -> Option<wgpu::MemoryRequirements> {
+        unimplemented!()
+    }
+
+    fn get_texture_memory_requirements(
+        &self,
+        _desc: &wgpu::TextureDescriptor<'_>,
+    ) -> Option<wgpu::MemoryRequirements> {
+        unimplemented!()
+    }
+
     fn create_command_encoder(
         &self,
         _desc: &wgpu::CommandEncoderDescriptor<'_>,
@@ -236,6 +275,10 @@ impl DeviceInterface for CustomDevice {
         unimplemented!()
     }
 
+    fn create_queue(&self, _desc: &wgpu::QueueDescriptor<'_>) -> Option<DispatchQueue> {
+        unimplemented!()
+    }
+
     fn set_device_lost_callback(&self, _device_lost_callback: wgpu::custom::BoxDeviceLostCallback) {
         unimplemented!()
     }
@@ -337,6 +380,14 @@ impl QueueInterface for CustomQueue {
         unimplemented!()
     }
 
+    fn update_sparse_residency(
+        &self,
+        _buffers: &[wgpu::SparseBufferBinding<'_>],
+        _textures: &[wgpu::SparseTextureBinding<'_>],
+    ) {
+        unimplemented!()
+    }
+
     fn submit(
         &self,
         _command_buffers: &mut dyn Iterator<Item = wgpu::custom::DispatchCommandBuffer>,
@@ -344,6 +395,17 @@ impl QueueInterface for CustomQueue {
         unimplemented!()
     }
 
+    fn create_command_encoder(
+        &self,
+        _desc: &wgpu::CommandEncoderDescriptor<'_>,
+    ) -> wgpu::custom::DispatchCommandEncoder {
+        unimplemented!()
+    }
+
+    fn wait_for_submission(&self, _other: &DispatchQueue, _submission_index: u64) {
+        unimplemented!()
+    }
+
     fn get_timestamp_period(&self) -> f32 {
         unimplemented!()
     }
@@ -4,6 +4,9 @@ use anyhow::{anyhow, Context as _};
 use std::fs;
 use std::{error::Error, fmt, io::Read, path::Path, str::FromStr};
 
+#[path = "naga/reflect.rs"]
+mod reflect;
+
 /// Translate shaders to different formats.
 #[derive(argh::FromArgs, Debug, Clone)]
 struct Args {
@@ -91,7 +94,8 @@ struct Args {
     #[argh(option)]
     stdin_file_path: Option<String>,
 
-    /// generate debug symbols, only works for spv-out for now
+    /// generate debug symbols for spv-out, and `#line` directives referring
+    /// to the input for hlsl-out, msl-out and glsl-out
     #[argh(switch, short = 'g')]
     generate_debug_symbols: bool,
 
@@ -112,6 +116,21 @@ struct Args {
     #[argh(option)]
     before_compaction: Option<String>,
 
+    /// inline small functions into entry points, fold constant expressions
+    /// and remove dead code before compacting the module.
+    ///
+    /// This implies `--compact`.
+    #[argh(switch)]
+    optimize: bool,
+
+    /// print a JSON description of the module's interface: entry points,
+    /// their inputs and outputs, resource bindings, push constants and
+    /// overrides.
+    ///
+    /// Output files with a `.json` extension receive the same description.
+    #[argh(switch)]
+    reflect: bool,
+
     /// bulk validation mode: all filenames are inputs to read and validate.
     #[argh(switch)]
     bulk_validate: bool,
@@ -403,7 +422,7 @@ fn run() -> anyhow::Result<()> {
     let args = {
         let mut args: Args = argh::from_env();
 
-        if args.before_compaction.is_some() {
+        if args.before_compaction.is_some() || args.optimize {
             args.compact = true;
         }
 
@@ -518,7 +537,14 @@ fn run() -> anyhow::Result<()> {
                 source_code: input_text,
                 file_name: input_path.into(),
                 language,
-            })
+            });
+            let line_directives = naga::back::LineDirectives {
+                source_code: input_text.clone(),
+                file_name: Some(input_path.display().to_string()),
+            };
+            params.msl.line_directives = Some(line_directives.clone());
+            params.glsl.line_directives = Some(line_directives.clone());
+            params.hlsl.line_directives = Some(line_directives);
         } else {
             eprintln!(
                 "warning: `--generate-debug-symbols` was passed, \
@@ -539,7 +565,7 @@ fn run() -> anyhow::Result<()> {
                 let missing = match Path::new(path).extension().and_then(|ex| ex.to_str()) {
                     Some("wgsl") => C::CLIP_DISTANCE | C::CULL_DISTANCE,
                     Some("metal") => C::CULL_DISTANCE | C::TEXTURE_EXTERNAL,
-                    Some("hlsl") => C::empty(),
+                    Some("hlsl" | "json") => C::empty(),
                     _ => C::TEXTURE_EXTERNAL,
                 };
                 caps & !missing
@@ -564,19 +590,25 @@ fn run() -> anyhow::Result<()> {
         }
     };
 
-    // Compact the module, if requested.
+    // Optimize and compact the module, if requested.
     //
     // Note that when output is to a non-WGSL shader language, we will call
     // `process_overrides`, which does its own compaction even if it is not
     // explicitly requested on the command line.
     let info = if args.compact {
-        // Compact only if validation succeeded. Otherwise, compaction may panic.
+        // Only transform the module if validation succeeded. Otherwise, the
+        // passes may panic.
         if info.is_some() {
             // Write out the module state before compaction, if requested.
             if let Some(ref before_compaction) = args.before_compaction {
                 write_output(&module, &info, &params, before_compaction)?;
             }
 
+            if args.optimize {
+                naga::proc::inline(&mut module, &naga::proc::InlineOptions::default());
+                naga::proc::optimize(&mut module);
+            }
+
             naga::compact::compact(&mut module, KeepUnused::No);
 
             // Re-validate the IR after compaction.
@@ -604,11 +636,22 @@ fn run() -> anyhow::Result<()> {
         info
     };
 
+    if args.reflect {
+        let info = info.as_ref().ok_or(CliError(
+            "Reflection requires validation to succeed, and it failed in a previous step",
+        ))?;
+        let reflection = reflect::Reflection::new(&module, info);
+        println!("{}", serde_json::to_string_pretty(&reflection)?);
+    }
+
     // If no output was requested, then report validation results and stop here.
     //
     // If the user asked for output, don't stop: some output formats (".txt",
     // ".dot", ".bin") can be generated even without a `ModuleInfo`.
     if output_paths.clone().next().is_none() {
+        if args.reflect {
+            return Ok(());
+        }
         if info.is_some() {
             println!("Validation successful");
             return Ok(());
@@ -696,6 +739,7 @@ fn parse_input(input_path: &Path, input: Vec<u8>, params: &Parameters) -> anyhow
                         &naga::front::glsl::Options {
                             stage: shader_stage.0,
                             defines: params.defines.clone(),
+                            include_resolver: None,
                         },
                         &input,
                     )
@@ -905,6 +949,16 @@ fn write_output(
             .unwrap_pretty();
             fs::write(output_path, wgsl)?;
         }
+        "json" => {
+            let info = info.as_ref().ok_or(CliError(
+                "Generating reflection output requires validation to \
+                 succeed, and it failed in a previous step",
+            ))?;
+
+            let reflection = reflect::Reflection::new(module, info);
+            let mut file = fs::File::create(output_path)?;
+            serde_json::to_writer_pretty(&mut file, &reflection)?;
+        }
         other => {
             println!("Unknown output extension: {other}");
         }
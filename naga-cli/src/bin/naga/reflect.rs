@@ -0,0 +1,439 @@
+//! Machine-readable reflection of a module's interface.
+//!
+//! This collects what a pipeline needs to know about a module to use it:
+//! entry points and their inputs and outputs, resource bindings and the stages
+//! that use them, push constants, and pipeline-overridable constants. It is
+//! written out as JSON for `.json` output files and `--reflect`.
+
+use naga::common::wgsl::{ToWgsl as _, TryToWgsl as _, TypeContext as _};
+use naga::valid::{GlobalUse, ModuleInfo};
+use naga::{AddressSpace, Handle, ImageClass, Module, ShaderStage, StorageAccess, TypeInner};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct Reflection {
+    entry_points: Vec<EntryPoint>,
+    resources: Vec<Resource>,
+    push_constants: Vec<PushConstants>,
+    overrides: Vec<Override>,
+}
+
+#[derive(Serialize)]
+struct EntryPoint {
+    name: String,
+    stage: &'static str,
+    /// The workgroup size of compute, task and mesh shaders. Dimensions set
+    /// by an override expression are `null`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workgroup_size: Option<[Option<u32>; 3]>,
+    inputs: Vec<Varying>,
+    outputs: Vec<Varying>,
+    /// Resources used by this entry point, and how it uses them.
+    resources: Vec<ResourceUse>,
+    /// The name of the push constant variable this entry point uses, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    push_constants: Option<String>,
+}
+
+/// An entry point argument or result, or a member of one.
+#[derive(Serialize)]
+struct Varying {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(rename = "type")]
+    ty: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    builtin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interpolation: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sampling: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+struct ResourceUse {
+    group: u32,
+    binding: u32,
+    /// Some of `read`, `write`, `query` and `atomic`.
+    usage: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct Resource {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    group: u32,
+    binding: u32,
+    #[serde(rename = "type")]
+    ty: String,
+    /// The number of elements in a binding array. `null` for runtime-sized
+    /// binding arrays; absent if this is not a binding array.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<Option<u32>>,
+    #[serde(flatten)]
+    kind: ResourceKind,
+    /// The stages of the entry points that use this resource.
+    visibility: Vec<&'static str>,
+}
+
+/// The binding type of a resource, named as in WebGPU's
+/// `GPUBindGroupLayoutEntry`.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ResourceKind {
+    Buffer {
+        /// `uniform`, `storage` or `read-only-storage`.
+        buffer_type: &'static str,
+        /// The size of the variable's type, with runtime-sized arrays
+        /// holding one element.
+        min_binding_size: u32,
+    },
+    Texture {
+        /// `float`, `sint`, `uint` or `depth`.
+        sample_type: &'static str,
+        view_dimension: String,
+        multisampled: bool,
+    },
+    StorageTexture {
+        format: &'static str,
+        /// `read-only`, `write-only` or `read-write`.
+        access: &'static str,
+        view_dimension: String,
+    },
+    ExternalTexture,
+    Sampler {
+        comparison: bool,
+    },
+    AccelerationStructure,
+}
+
+#[derive(Serialize)]
+struct PushConstants {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(rename = "type")]
+    ty: String,
+    size: u32,
+    visibility: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct Override {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u16>,
+    #[serde(rename = "type")]
+    ty: String,
+    /// The default value, if it is a literal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<serde_json::Value>,
+}
+
+impl Reflection {
+    pub fn new(module: &Module, info: &ModuleInfo) -> Self {
+        let gctx = module.to_ctx();
+
+        let entry_points: Vec<_> = module
+            .entry_points
+            .iter()
+            .enumerate()
+            .map(|(index, ep)| {
+                let ep_info = info.get_entry_point(index);
+
+                let workgroup_size = matches!(
+                    ep.stage,
+                    ShaderStage::Compute | ShaderStage::Task | ShaderStage::Mesh
+                )
+                .then(|| {
+                    let overrides = ep.workgroup_size_overrides.unwrap_or_default();
+                    [0, 1, 2].map(|i| overrides[i].is_none().then_some(ep.workgroup_size[i]))
+                });
+
+                let mut inputs = Vec::new();
+                for argument in ep.function.arguments.iter() {
+                    varyings(
+                        module,
+                        argument.name.as_ref(),
+                        argument.ty,
+                        argument.binding.as_ref(),
+                        &mut inputs,
+                    );
+                }
+                let mut outputs = Vec::new();
+                if let Some(ref result) = ep.function.result {
+                    varyings(
+                        module,
+                        None,
+                        result.ty,
+                        result.binding.as_ref(),
+                        &mut outputs,
+                    );
+                }
+
+                let mut resources = Vec::new();
+                let mut push_constants = None;
+                for (handle, var) in module.global_variables.iter() {
+                    let usage = ep_info[handle];
+                    if usage.is_empty() {
+                        continue;
+                    }
+                    if let Some(ref binding) = var.binding {
+                        resources.push(ResourceUse {
+                            group: binding.group,
+                            binding: binding.binding,
+                            usage: usage_names(usage),
+                        });
+                    } else if var.space == AddressSpace::PushConstant {
+                        push_constants = var.name.clone();
+                    }
+                }
+
+                EntryPoint {
+                    name: ep.name.clone(),
+                    stage: stage_name(ep.stage),
+                    workgroup_size,
+                    inputs,
+                    outputs,
+                    resources,
+                    push_constants,
+                }
+            })
+            .collect();
+
+        // The stages of the entry points that use the global `handle`.
+        let visibility = |handle: Handle<naga::GlobalVariable>| {
+            let mut stages = Vec::new();
+            for (index, ep) in module.entry_points.iter().enumerate() {
+                let stage = stage_name(ep.stage);
+                if !info.get_entry_point(index)[handle].is_empty() && !stages.contains(&stage) {
+                    stages.push(stage);
+                }
+            }
+            stages
+        };
+
+        let mut resources = Vec::new();
+        let mut push_constants = Vec::new();
+        for (handle, var) in module.global_variables.iter() {
+            let ty = gctx.type_to_string(var.ty);
+            if let Some(ref binding) = var.binding {
+                let (count, base) = match module.types[var.ty].inner {
+                    TypeInner::BindingArray { base, size } => {
+                        let count = match size {
+                            naga::ArraySize::Constant(count) => Some(count.get()),
+                            naga::ArraySize::Pending(_) | naga::ArraySize::Dynamic => None,
+                        };
+                        (Some(count), base)
+                    }
+                    _ => (None, var.ty),
+                };
+                let Some(kind) = resource_kind(module, var.space, base) else {
+                    continue;
+                };
+                resources.push(Resource {
+                    name: var.name.clone(),
+                    group: binding.group,
+                    binding: binding.binding,
+                    ty,
+                    count,
+                    kind,
+                    visibility: visibility(handle),
+                });
+            } else if var.space == AddressSpace::PushConstant {
+                push_constants.push(PushConstants {
+                    name: var.name.clone(),
+                    ty,
+                    size: module.types[var.ty].inner.size(gctx),
+                    visibility: visibility(handle),
+                });
+            }
+        }
+        resources.sort_by_key(|resource| (resource.group, resource.binding));
+
+        let overrides = module
+            .overrides
+            .iter()
+            .map(|(_, o)| Override {
+                name: o.name.clone(),
+                id: o.id,
+                ty: gctx.type_to_string(o.ty),
+                default: o
+                    .init
+                    .and_then(|init| match module.global_expressions[init] {
+                        naga::Expression::Literal(literal) => Some(literal_to_json(literal)),
+                        _ => None,
+                    }),
+            })
+            .collect();
+
+        Self {
+            entry_points,
+            resources,
+            push_constants,
+            overrides,
+        }
+    }
+}
+
+/// Add the varyings for an entry point argument or result of type `ty` to
+/// `out`, looking into structs for the bindings of their members.
+fn varyings(
+    module: &Module,
+    name: Option<&String>,
+    ty: Handle<naga::Type>,
+    binding: Option<&naga::Binding>,
+    out: &mut Vec<Varying>,
+) {
+    let gctx = module.to_ctx();
+    match (binding, &module.types[ty].inner) {
+        (Some(binding), _) => {
+            let mut varying = Varying {
+                name: name.cloned(),
+                ty: gctx.type_to_string(ty),
+                location: None,
+                builtin: None,
+                interpolation: None,
+                sampling: None,
+            };
+            match *binding {
+                naga::Binding::BuiltIn(builtin) => {
+                    varying.builtin = Some(match builtin.try_to_wgsl() {
+                        Some(name) => name.to_string(),
+                        None => format!("{builtin:?}"),
+                    });
+                }
+                naga::Binding::Location {
+                    location,
+                    interpolation,
+                    sampling,
+                    ..
+                } => {
+                    varying.location = Some(location);
+                    varying.interpolation = interpolation.map(|i| i.to_wgsl());
+                    varying.sampling = sampling.map(|s| s.to_wgsl());
+                }
+            }
+            out.push(varying);
+        }
+        (None, TypeInner::Struct { members, .. }) => {
+            for member in members {
+                varyings(
+                    module,
+                    member.name.as_ref(),
+                    member.ty,
+                    member.binding.as_ref(),
+                    out,
+                );
+            }
+        }
+        (None, _) => {}
+    }
+}
+
+/// Describe the binding type of a resource in `space`, with type `ty`.
+///
+/// Return `None` for variables that are not resources.
+fn resource_kind(
+    module: &Module,
+    space: AddressSpace,
+    ty: Handle<naga::Type>,
+) -> Option<ResourceKind> {
+    let inner = &module.types[ty].inner;
+    Some(match space {
+        AddressSpace::Uniform | AddressSpace::Storage { .. } => ResourceKind::Buffer {
+            buffer_type: match space {
+                AddressSpace::Storage { access } if access.contains(StorageAccess::STORE) => {
+                    "storage"
+                }
+                AddressSpace::Storage { .. } => "read-only-storage",
+                _ => "uniform",
+            },
+            min_binding_size: inner.size(module.to_ctx()),
+        },
+        AddressSpace::Handle => match *inner {
+            TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            } => {
+                let view_dimension = if arrayed {
+                    format!("{}-array", dim.to_wgsl())
+                } else {
+                    dim.to_wgsl().to_string()
+                };
+                match class {
+                    ImageClass::Sampled { kind, multi } => ResourceKind::Texture {
+                        sample_type: match kind {
+                            naga::ScalarKind::Sint => "sint",
+                            naga::ScalarKind::Uint => "uint",
+                            _ => "float",
+                        },
+                        view_dimension,
+                        multisampled: multi,
+                    },
+                    ImageClass::Depth { multi } => ResourceKind::Texture {
+                        sample_type: "depth",
+                        view_dimension,
+                        multisampled: multi,
+                    },
+                    ImageClass::Storage { format, access } => ResourceKind::StorageTexture {
+                        format: format.to_wgsl(),
+                        access: if !access.contains(StorageAccess::STORE) {
+                            "read-only"
+                        } else if !access.contains(StorageAccess::LOAD) {
+                            "write-only"
+                        } else {
+                            "read-write"
+                        },
+                        view_dimension,
+                    },
+                    ImageClass::External => ResourceKind::ExternalTexture,
+                }
+            }
+            TypeInner::Sampler { comparison } => ResourceKind::Sampler { comparison },
+            TypeInner::AccelerationStructure { .. } => ResourceKind::AccelerationStructure,
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+const fn stage_name(stage: ShaderStage) -> &'static str {
+    match stage {
+        ShaderStage::Vertex => "vertex",
+        ShaderStage::Fragment => "fragment",
+        ShaderStage::Compute => "compute",
+        ShaderStage::Task => "task",
+        ShaderStage::Mesh => "mesh",
+    }
+}
+
+fn usage_names(usage: GlobalUse) -> Vec<&'static str> {
+    [
+        (GlobalUse::READ, "read"),
+        (GlobalUse::WRITE, "write"),
+        (GlobalUse::QUERY, "query"),
+        (GlobalUse::ATOMIC, "atomic"),
+    ]
+    .into_iter()
+    .filter(|&(flag, _)| usage.contains(flag))
+    .map(|(_, name)| name)
+    .collect()
+}
+
+fn literal_to_json(literal: naga::Literal) -> serde_json::Value {
+    use naga::Literal as L;
+    match literal {
+        L::F64(v) | L::AbstractFloat(v) => v.into(),
+        L::F32(v) => v.into(),
+        L::F16(v) => f32::from(v).into(),
+        L::U32(v) => v.into(),
+        L::I32(v) => v.into(),
+        L::U64(v) => v.into(),
+        L::I64(v) | L::AbstractInt(v) => v.into(),
+        L::Bool(v) => v.into(),
+    }
+}
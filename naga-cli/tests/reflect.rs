@@ -0,0 +1,235 @@
+//! Tests of the JSON reflection of `naga --reflect` and `.json` outputs.
+
+use std::{fs, path::PathBuf, process::Command};
+
+use serde_json::{json, Value};
+
+const NAGA: &str = env!("CARGO_BIN_EXE_naga");
+
+const SHADER: &str = r#"
+struct Params {
+    scale: f32,
+    offset: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) @interpolate(flat) index: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> data: array<u32>;
+@group(0) @binding(2) var<storage, read> input: array<u32>;
+@group(1) @binding(0) var color: texture_2d<f32>;
+@group(1) @binding(1) var color_sampler: sampler;
+
+override block_size: u32 = 64;
+
+@vertex
+fn vs_main(@location(0) position: vec2<f32>, @builtin(instance_index) instance: u32) -> VertexOutput {
+    return VertexOutput(vec4(position * params.scale + params.offset, 0.0, 1.0), instance);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(color, color_sampler, vec2(0.5));
+}
+
+@compute @workgroup_size(8, 4)
+fn fixed(@builtin(global_invocation_id) id: vec3<u32>) {
+    data[id.x] = id.y;
+}
+
+@compute @workgroup_size(block_size)
+fn dynamic(@builtin(local_invocation_index) index: u32) {
+    data[index] = input[index];
+}
+"#;
+
+/// Writes [`SHADER`] to a directory named `name`, returning its path.
+fn write_shader(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("reflect")
+        .join(name);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("shader.wgsl");
+    fs::write(&path, SHADER).unwrap();
+    path
+}
+
+/// Runs `naga` with `args`, returning its standard output.
+fn naga(args: &[&dyn AsRef<std::ffi::OsStr>]) -> String {
+    let output = Command::new(NAGA)
+        .args(args.iter().map(|arg| arg.as_ref()))
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}\n{stderr}", output.status);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn entry_point<'a>(reflection: &'a Value, name: &str) -> &'a Value {
+    reflection["entry_points"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|ep| ep["name"] == name)
+        .unwrap_or_else(|| panic!("No entry point {name:?} in:\n{reflection:#}"))
+}
+
+#[test]
+fn entry_points() {
+    let path = write_shader("entry-points");
+    let reflection: Value = serde_json::from_str(&naga(&[&"--reflect", &path])).unwrap();
+
+    let names = reflection["entry_points"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|ep| (ep["name"].as_str().unwrap(), ep["stage"].as_str().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            ("vs_main", "vertex"),
+            ("fs_main", "fragment"),
+            ("fixed", "compute"),
+            ("dynamic", "compute"),
+        ]
+    );
+
+    let vs_main = entry_point(&reflection, "vs_main");
+    assert_eq!(vs_main.get("workgroup_size"), None);
+    // Float varyings get the default interpolation.
+    assert_eq!(
+        vs_main["inputs"],
+        json!([
+            {
+                "name": "position",
+                "type": "vec2<f32>",
+                "location": 0,
+                "interpolation": "perspective",
+                "sampling": "center",
+            },
+            { "name": "instance", "type": "u32", "builtin": "instance_index" },
+        ])
+    );
+    // The members of a struct result are listed individually.
+    assert_eq!(
+        vs_main["outputs"],
+        json!([
+            { "name": "position", "type": "vec4<f32>", "builtin": "position" },
+            { "name": "index", "type": "u32", "location": 0, "interpolation": "flat" },
+        ])
+    );
+    assert_eq!(
+        vs_main["resources"],
+        json!([{ "group": 0, "binding": 0, "usage": ["read"] }])
+    );
+
+    let fs_main = entry_point(&reflection, "fs_main");
+    assert_eq!(
+        fs_main["resources"],
+        json!([
+            { "group": 1, "binding": 0, "usage": ["read"] },
+            { "group": 1, "binding": 1, "usage": ["read"] },
+        ])
+    );
+
+    let fixed = entry_point(&reflection, "fixed");
+    assert_eq!(fixed["workgroup_size"], json!([8, 4, 1]));
+    assert_eq!(
+        fixed["resources"],
+        json!([{ "group": 0, "binding": 1, "usage": ["write"] }])
+    );
+
+    // Dimensions set by an override are unknown until pipeline creation.
+    let dynamic = entry_point(&reflection, "dynamic");
+    assert_eq!(dynamic["workgroup_size"], json!([null, 1, 1]));
+    assert_eq!(
+        dynamic["resources"],
+        json!([
+            { "group": 0, "binding": 1, "usage": ["write"] },
+            { "group": 0, "binding": 2, "usage": ["read"] },
+        ])
+    );
+}
+
+#[test]
+fn bindings() {
+    let path = write_shader("bindings");
+    let reflection: Value = serde_json::from_str(&naga(&[&"--reflect", &path])).unwrap();
+
+    assert_eq!(
+        reflection["resources"],
+        json!([
+            {
+                "name": "params",
+                "group": 0,
+                "binding": 0,
+                "type": "Params",
+                "kind": "buffer",
+                "buffer_type": "uniform",
+                "min_binding_size": 16,
+                "visibility": ["vertex"],
+            },
+            {
+                "name": "data",
+                "group": 0,
+                "binding": 1,
+                "type": "array<u32>",
+                "kind": "buffer",
+                "buffer_type": "storage",
+                "min_binding_size": 4,
+                "visibility": ["compute"],
+            },
+            {
+                "name": "input",
+                "group": 0,
+                "binding": 2,
+                "type": "array<u32>",
+                "kind": "buffer",
+                "buffer_type": "read-only-storage",
+                "min_binding_size": 4,
+                "visibility": ["compute"],
+            },
+            {
+                "name": "color",
+                "group": 1,
+                "binding": 0,
+                "type": "texture_2d<f32>",
+                "kind": "texture",
+                "sample_type": "float",
+                "view_dimension": "2d",
+                "multisampled": false,
+                "visibility": ["fragment"],
+            },
+            {
+                "name": "color_sampler",
+                "group": 1,
+                "binding": 1,
+                "type": "sampler",
+                "kind": "sampler",
+                "comparison": false,
+                "visibility": ["fragment"],
+            },
+        ])
+    );
+    assert_eq!(reflection["push_constants"], json!([]));
+    assert_eq!(
+        reflection["overrides"],
+        json!([{ "name": "block_size", "type": "u32", "default": 64 }])
+    );
+}
+
+#[test]
+fn json_output() {
+    let path = write_shader("json-output");
+    let output = path.with_extension("json");
+    let _ = fs::remove_file(&output);
+    assert_eq!(naga(&[&path, &output]), "");
+
+    let written: Value = serde_json::from_slice(&fs::read(&output).unwrap()).unwrap();
+    let printed: Value = serde_json::from_str(&naga(&[&"--reflect", &path])).unwrap();
+    assert_eq!(written, printed);
+}
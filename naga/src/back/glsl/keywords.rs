@@ -492,6 +492,15 @@ pub const RESERVED_KEYWORDS: &[&str] = &[
     // Naga utilities:
     super::MODF_FUNCTION,
     super::FREXP_FUNCTION,
+    super::PACK_SNORM_4X8_FUNCTION,
+    super::PACK_UNORM_4X8_FUNCTION,
+    super::PACK_SNORM_2X16_FUNCTION,
+    super::PACK_UNORM_2X16_FUNCTION,
+    super::PACK_HALF_2X16_FUNCTION,
+    super::UNPACK_HALF_2X16_FUNCTION,
+    super::IMAGE_SAMPLE_EXTERNAL_FUNCTION,
+    super::IMAGE_LOAD_EXTERNAL_FUNCTION,
+    super::IMAGE_SIZE_EXTERNAL_FUNCTION,
     super::FIRST_INSTANCE_BINDING,
 ];
 
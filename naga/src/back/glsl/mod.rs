@@ -17,6 +17,27 @@ to output a [`Module`](crate::Module) into glsl
 - 300
 - 310
 
+# External textures
+
+Support for [`crate::ImageClass::External`] textures is implemented by lowering
+each external texture global variable to 3 `sampler2D`s, and a `std140`
+uniform block holding a `NagaExternalTextureParams` struct. This provides up to
+3 planes of texture data (for example single planar RGBA, or separate Y, Cb,
+and Cr planes), and the parameters describing how to handle these correctly.
+The binding locations to use for each of these are specified via
+[`Options::external_texture_binding_map`], and reported through
+[`TextureMapping::external_plane`] and [`ReflectionInfo::uniforms`] for
+versions without explicit binding locations.
+
+WGSL's `textureDimensions()`, `textureLoad()`, and
+`textureSampleBaseClampToEdge()` built-in functions are implemented for
+external textures using helper functions, which take the three planes and the
+parameters struct as separate arguments. Likewise, user-defined functions
+taking an external texture argument are declared with four consecutive
+arguments, and an external texture expression, which can only ever be a global
+variable or function argument, is written as a comma-separated list of the four
+names.
+
 [glsl]: https://www.khronos.org/registry/OpenGL/index_gl.php
 */
 
@@ -64,7 +85,7 @@ use thiserror::Error;
 use crate::{
     back::{self, Baked},
     common,
-    proc::{self, NameKey},
+    proc::{self, ExternalTextureNameKey, NameKey},
     valid, Handle, ShaderStage, TypeInner,
 };
 use features::FeaturesManager;
@@ -85,6 +106,15 @@ const CLAMPED_LOD_SUFFIX: &str = "_clamped_lod";
 
 pub(crate) const MODF_FUNCTION: &str = "naga_modf";
 pub(crate) const FREXP_FUNCTION: &str = "naga_frexp";
+pub(crate) const PACK_SNORM_4X8_FUNCTION: &str = "naga_packSnorm4x8";
+pub(crate) const PACK_UNORM_4X8_FUNCTION: &str = "naga_packUnorm4x8";
+pub(crate) const PACK_SNORM_2X16_FUNCTION: &str = "naga_packSnorm2x16";
+pub(crate) const PACK_UNORM_2X16_FUNCTION: &str = "naga_packUnorm2x16";
+pub(crate) const PACK_HALF_2X16_FUNCTION: &str = "naga_packHalf2x16";
+pub(crate) const UNPACK_HALF_2X16_FUNCTION: &str = "naga_unpackHalf2x16";
+pub(crate) const IMAGE_SAMPLE_EXTERNAL_FUNCTION: &str = "naga_textureSampleBaseClampToEdge";
+pub(crate) const IMAGE_LOAD_EXTERNAL_FUNCTION: &str = "naga_textureLoadExternal";
+pub(crate) const IMAGE_SIZE_EXTERNAL_FUNCTION: &str = "naga_textureDimensionsExternal";
 
 // Must match code in glsl_built_in
 pub const FIRST_INSTANCE_BINDING: &str = "naga_vs_first_instance";
@@ -115,6 +145,57 @@ where
 /// Mapping between resources and bindings.
 pub type BindingMap = alloc::collections::BTreeMap<crate::ResourceBinding, u8>;
 
+/// GLSL binding information for a Naga [`External`] image global variable.
+///
+/// See the module documentation's section on [External textures][mod] for details.
+///
+/// [`External`]: crate::ir::ImageClass::External
+/// [mod]: #external-textures
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct ExternalTextureBindTarget {
+    /// Binding locations of the `sampler2D`s holding each plane of data for
+    /// the external texture.
+    pub planes: [u8; 3],
+
+    /// Binding location of the uniform block holding the
+    /// `NagaExternalTextureParams` struct, which describes how to combine
+    /// the data in [`planes`] to get the result required by the spec.
+    ///
+    /// [`planes`]: Self::planes
+    pub params: u8,
+}
+
+#[cfg(any(feature = "serialize", feature = "deserialize"))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+struct ExternalTextureBindingMapSerialization {
+    resource_binding: crate::ResourceBinding,
+    bind_target: ExternalTextureBindTarget,
+}
+
+#[cfg(feature = "deserialize")]
+fn deserialize_external_texture_binding_map<'de, D>(
+    deserializer: D,
+) -> Result<ExternalTextureBindingMap, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::Deserialize;
+
+    let vec = Vec::<ExternalTextureBindingMapSerialization>::deserialize(deserializer)?;
+    let mut map = ExternalTextureBindingMap::default();
+    for item in vec {
+        map.insert(item.resource_binding, item.bind_target);
+    }
+    Ok(map)
+}
+
+/// Mapping between external texture resources and bindings.
+pub type ExternalTextureBindingMap =
+    alloc::collections::BTreeMap<crate::ResourceBinding, ExternalTextureBindTarget>;
+
 impl crate::AtomicFunction {
     const fn to_glsl(self) -> &'static str {
         match self {
@@ -325,8 +406,25 @@ pub struct Options {
         serde(deserialize_with = "deserialize_binding_map")
     )]
     pub binding_map: BindingMap,
+    /// Binding locations for [`External`] image global variables.
+    ///
+    /// See [`ExternalTextureBindTarget`] for details.
+    ///
+    /// [`External`]: crate::ir::ImageClass::External
+    #[cfg_attr(
+        feature = "deserialize",
+        serde(deserialize_with = "deserialize_external_texture_binding_map")
+    )]
+    pub external_texture_binding_map: ExternalTextureBindingMap,
     /// Should workgroup variables be zero initialized (by polyfilling)?
     pub zero_initialize_workgroup_memory: bool,
+    /// Return a [`SourceMap`] of the output in [`ReflectionInfo::source_map`].
+    ///
+    /// [`SourceMap`]: back::SourceMap
+    pub source_map: bool,
+    /// If set, emit `#line` directives referring to the source the module
+    /// was parsed from.
+    pub line_directives: Option<back::LineDirectives>,
 }
 
 impl Default for Options {
@@ -335,7 +433,10 @@ impl Default for Options {
             version: Version::new_gles(310),
             writer_flags: WriterFlags::ADJUST_COORDINATE_SPACE,
             binding_map: BindingMap::default(),
+            external_texture_binding_map: ExternalTextureBindingMap::default(),
             zero_initialize_workgroup_memory: true,
+            source_map: false,
+            line_directives: None,
         }
     }
 }
@@ -372,6 +473,11 @@ pub struct ReflectionInfo {
     /// Mapping between texture names and variables/samplers.
     pub texture_mapping: crate::FastHashMap<String, TextureMapping>,
     /// Mapping between uniform variables and names.
+    ///
+    /// For [`External`] image global variables, this is the name of the
+    /// uniform block holding the external texture's parameters.
+    ///
+    /// [`External`]: crate::ir::ImageClass::External
     pub uniforms: crate::FastHashMap<Handle<crate::GlobalVariable>, String>,
     /// Mapping between names and attribute locations.
     pub varying: crate::FastHashMap<String, VaryingLocation>,
@@ -379,6 +485,9 @@ pub struct ReflectionInfo {
     pub push_constant_items: Vec<PushConstantItem>,
     /// Number of user-defined clip planes. Only applicable to vertex shaders.
     pub clip_distance_count: u32,
+    /// The lines of the output generated from each span of the module, if
+    /// requested by [`Options::source_map`].
+    pub source_map: Option<back::SourceMap>,
 }
 
 /// Mapping between a texture and its sampler, if it exists.
@@ -390,12 +499,19 @@ pub struct ReflectionInfo {
 ///
 /// [`Storage`](crate::ImageClass::Storage) images produce `gimageN` and don't have an associated sampler,
 /// so the [`sampler`](Self::sampler) field will be [`None`].
+///
+/// [`External`](crate::ImageClass::External) images produce one `sampler2D` per
+/// plane, each with its own entry whose [`external_plane`](Self::external_plane)
+/// field holds the index of the plane.
 #[derive(Debug, Clone)]
 pub struct TextureMapping {
     /// Handle to the image global variable.
     pub texture: Handle<crate::GlobalVariable>,
     /// Handle to the associated sampler global variable, if it exists.
     pub sampler: Option<Handle<crate::GlobalVariable>>,
+    /// The index of the plane this texture holds, if the image global
+    /// variable is an external texture.
+    pub external_plane: Option<usize>,
 }
 
 /// All information to bind a single uniform value to the shader.
@@ -589,7 +705,7 @@ pub struct Writer<'a, W> {
     /// The module analysis.
     info: &'a valid::ModuleInfo,
     /// The output writer.
-    out: W,
+    out: back::source_map::SourceMapWriter<W>,
     /// User defined configuration to be used.
     options: &'a Options,
     /// The bound checking policies to be used
@@ -676,7 +792,7 @@ impl<'a, W: Write> Writer<'a, W> {
         let mut this = Self {
             module,
             info,
-            out,
+            out: back::source_map::SourceMapWriter::new(out),
             options,
             policies,
 
@@ -709,6 +825,12 @@ impl<'a, W: Write> Writer<'a, W> {
     /// # Panics
     /// Might panic if the module is invalid
     pub fn write(&mut self) -> Result<ReflectionInfo, Error> {
+        self.out.reset(
+            self.options.source_map,
+            self.options.line_directives.as_ref(),
+            false,
+        );
+
         // We use `writeln!(self.out)` throughout the write to add newlines
         // to make the output more readable
 
@@ -872,6 +994,9 @@ impl<'a, W: Write> Writer<'a, W> {
             }
         }
 
+        // Write polyfills for the data packing functions this version lacks.
+        self.write_pack_unpack_polyfills()?;
+
         // Write all named constants
         let mut constants = self
             .module
@@ -906,6 +1031,14 @@ impl<'a, W: Write> Writer<'a, W> {
             }
 
             match self.module.types[global.ty].inner {
+                // External textures are lowered to several globals
+                TypeInner::Image {
+                    class: crate::ImageClass::External,
+                    ..
+                } => {
+                    self.write_global_external_texture(handle, global)?;
+                    writeln!(self.out)?;
+                }
                 // We treat images separately because they might require
                 // writing the storage format
                 TypeInner::Image {
@@ -992,6 +1125,20 @@ impl<'a, W: Write> Writer<'a, W> {
         }
         writeln!(self.out)?;
 
+        // Write the helper functions for external textures, if any are used
+        let uses_external_textures = self.module.global_variables.iter().any(|(handle, global)| {
+            match self.module.types[global.ty].inner {
+                TypeInner::Image {
+                    class: crate::ImageClass::External,
+                    ..
+                } => include_unused || !ep_info[handle].is_empty(),
+                _ => false,
+            }
+        });
+        if uses_external_textures {
+            self.write_external_texture_functions()?;
+        }
+
         // Write all regular functions
         for (handle, function) in self.module.functions.iter() {
             // Check that the function doesn't use globals that aren't supported
@@ -1016,6 +1163,7 @@ impl<'a, W: Write> Writer<'a, W> {
             }
 
             // Write the function
+            self.out.mark(self.module.functions.get_span(handle))?;
             self.write_function(back::FunctionType::Function(handle), function, fun_info)?;
 
             writeln!(self.out)?;
@@ -1034,6 +1182,168 @@ impl<'a, W: Write> Writer<'a, W> {
         self.collect_reflection_info()
     }
 
+    /// Returns the name of the helper function emulating the data packing
+    /// or unpacking function `fun`, if this version lacks the builtin.
+    fn pack_unpack_polyfill(&self, fun: crate::MathFunction) -> Option<&'static str> {
+        use crate::MathFunction as Mf;
+
+        let version = self.options.version;
+        match fun {
+            Mf::Pack4x8snorm if !version.supports_pack_unpack_4x8() => {
+                Some(PACK_SNORM_4X8_FUNCTION)
+            }
+            Mf::Pack4x8unorm if !version.supports_pack_unpack_4x8() => {
+                Some(PACK_UNORM_4X8_FUNCTION)
+            }
+            Mf::Pack2x16snorm if !version.supports_pack_unpack_snorm_2x16() => {
+                Some(PACK_SNORM_2X16_FUNCTION)
+            }
+            Mf::Pack2x16unorm if !version.supports_pack_unpack_unorm_2x16() => {
+                Some(PACK_UNORM_2X16_FUNCTION)
+            }
+            Mf::Pack2x16float if !version.supports_pack_unpack_half_2x16() => {
+                Some(PACK_HALF_2X16_FUNCTION)
+            }
+            Mf::Unpack2x16float if !version.supports_pack_unpack_half_2x16() => {
+                Some(UNPACK_HALF_2X16_FUNCTION)
+            }
+            _ => None,
+        }
+    }
+
+    /// Write the helper functions emulating the data packing and unpacking
+    /// functions used by the module that this version lacks.
+    ///
+    /// Like WGSL, these round to the nearest integer with `floor(0.5 + x)`.
+    /// The `binary16` ones use `floatBitsToUint` and `uintBitsToFloat`, so
+    /// on GLSL 140 and 150 they need `GL_ARB_shader_bit_encoding`, like
+    /// bitcasts do.
+    fn write_pack_unpack_polyfills(&mut self) -> BackendResult {
+        use crate::MathFunction as Mf;
+
+        let mut polyfills = crate::FastHashSet::default();
+        let functions = self.module.functions.iter().map(|(_, function)| function);
+        for function in functions.chain(core::iter::once(&self.entry_point.function)) {
+            for (_, expr) in function.expressions.iter() {
+                if let crate::Expression::Math { fun, .. } = *expr {
+                    if self.pack_unpack_polyfill(fun).is_some() {
+                        polyfills.insert(fun);
+                    }
+                }
+            }
+        }
+
+        // Write them in a fixed order, to keep the output deterministic.
+        for fun in [
+            Mf::Pack4x8snorm,
+            Mf::Pack4x8unorm,
+            Mf::Pack2x16snorm,
+            Mf::Pack2x16unorm,
+            Mf::Pack2x16float,
+            Mf::Unpack2x16float,
+        ] {
+            if !polyfills.contains(&fun) {
+                continue;
+            }
+
+            writeln!(self.out)?;
+            match fun {
+                Mf::Pack4x8snorm => writeln!(
+                    self.out,
+                    "uint {PACK_SNORM_4X8_FUNCTION}(vec4 value) {{
+    uvec4 bytes = uvec4(ivec4(floor(0.5 + clamp(value, -1.0, 1.0) * 127.0)) & 0xFF);
+    return bytes.x | bytes.y << 8 | bytes.z << 16 | bytes.w << 24;
+}}"
+                )?,
+                Mf::Pack4x8unorm => writeln!(
+                    self.out,
+                    "uint {PACK_UNORM_4X8_FUNCTION}(vec4 value) {{
+    uvec4 bytes = uvec4(floor(0.5 + clamp(value, 0.0, 1.0) * 255.0));
+    return bytes.x | bytes.y << 8 | bytes.z << 16 | bytes.w << 24;
+}}"
+                )?,
+                Mf::Pack2x16snorm => writeln!(
+                    self.out,
+                    "uint {PACK_SNORM_2X16_FUNCTION}(vec2 value) {{
+    uvec2 halves = uvec2(ivec2(floor(0.5 + clamp(value, -1.0, 1.0) * 32767.0)) & 0xFFFF);
+    return halves.x | halves.y << 16;
+}}"
+                )?,
+                Mf::Pack2x16unorm => writeln!(
+                    self.out,
+                    "uint {PACK_UNORM_2X16_FUNCTION}(vec2 value) {{
+    uvec2 halves = uvec2(floor(0.5 + clamp(value, 0.0, 1.0) * 65535.0));
+    return halves.x | halves.y << 16;
+}}"
+                )?,
+                // Converts each component to binary16, rounding to nearest
+                // even. Values too large for binary16 become infinities, and
+                // values too small become zero.
+                Mf::Pack2x16float => writeln!(
+                    self.out,
+                    "uint {PACK_HALF_2X16_FUNCTION}(vec2 value) {{
+    uint result = 0u;
+    for (int i = 0; i < 2; i++) {{
+        uint bits = floatBitsToUint(value[i]);
+        uint sign_bit = (bits >> 16) & 0x8000u;
+        int exponent = int((bits >> 23) & 0xFFu) - 112;
+        uint mantissa = bits & 0x7FFFFFu;
+        uint half_bits;
+        if (exponent >= 31) {{
+            half_bits = (exponent == 143 && mantissa != 0u) ? 0x7E00u : 0x7C00u;
+        }} else if (exponent <= 0) {{
+            half_bits = 0u;
+            if (exponent >= -10) {{
+                mantissa |= 0x800000u;
+                uint shift = uint(14 - exponent);
+                uint halfway = 1u << (shift - 1u);
+                uint remainder = mantissa & ((1u << shift) - 1u);
+                half_bits = mantissa >> shift;
+                if (remainder > halfway || (remainder == halfway && (half_bits & 1u) != 0u)) {{
+                    half_bits += 1u;
+                }}
+            }}
+        }} else {{
+            uint remainder = mantissa & 0x1FFFu;
+            half_bits = uint(exponent) << 10 | mantissa >> 13;
+            if (remainder > 0x1000u || (remainder == 0x1000u && (half_bits & 1u) != 0u)) {{
+                half_bits += 1u;
+            }}
+        }}
+        result |= (sign_bit | half_bits) << (16 * i);
+    }}
+    return result;
+}}"
+                )?,
+                Mf::Unpack2x16float => writeln!(
+                    self.out,
+                    "vec2 {UNPACK_HALF_2X16_FUNCTION}(uint value) {{
+    vec2 result;
+    for (int i = 0; i < 2; i++) {{
+        uint half_bits = (value >> (16 * i)) & 0xFFFFu;
+        uint sign_bit = (half_bits & 0x8000u) << 16;
+        uint exponent = (half_bits >> 10) & 0x1Fu;
+        uint mantissa = half_bits & 0x3FFu;
+        uint bits;
+        if (exponent == 0u) {{
+            bits = sign_bit | floatBitsToUint(float(mantissa) * exp2(-24.0));
+        }} else if (exponent == 31u) {{
+            bits = sign_bit | 0x7F800000u | mantissa << 13;
+        }} else {{
+            bits = sign_bit | (exponent + 112u) << 23 | mantissa << 13;
+        }}
+        result[i] = uintBitsToFloat(bits);
+    }}
+    return result;
+}}"
+                )?,
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(())
+    }
+
     fn write_array_size(
         &mut self,
         base: Handle<crate::Type>,
@@ -1176,7 +1486,11 @@ impl<'a, W: Write> Writer<'a, W> {
             Ic::Depth { multi: true } => ("sampler", float, "MS", ""),
             Ic::Depth { multi: false } => ("sampler", float, "", "Shadow"),
             Ic::Storage { format, .. } => ("image", format.into(), "", ""),
-            Ic::External => unimplemented!(),
+            Ic::External => {
+                return Err(Error::Custom(
+                    "External images are lowered to several `sampler2D`s".to_string(),
+                ))
+            }
         };
 
         let precision = if self.options.version.is_es() {
@@ -1398,6 +1712,317 @@ impl<'a, W: Write> Writer<'a, W> {
         Ok(())
     }
 
+    /// Write the `sampler2D`s and the uniform block that an [`External`] image
+    /// global variable is lowered to.
+    ///
+    /// See the module documentation's section on [External textures][mod] for details.
+    ///
+    /// [`External`]: crate::ImageClass::External
+    /// [mod]: self#external-textures
+    fn write_global_external_texture(
+        &mut self,
+        handle: Handle<crate::GlobalVariable>,
+        global: &crate::GlobalVariable,
+    ) -> BackendResult {
+        if !self.options.version.supports_std140_layout() {
+            return Err(Error::Custom(
+                "External textures require std140 layout support".to_string(),
+            ));
+        }
+
+        let bind_target = if self.options.version.supports_explicit_locations() {
+            let br = global.binding.as_ref().unwrap();
+            self.options.external_texture_binding_map.get(br).copied()
+        } else {
+            None
+        };
+
+        for i in 0..3 {
+            if let Some(ref bind_target) = bind_target {
+                write!(self.out, "layout(binding = {}) ", bind_target.planes[i])?;
+            }
+            write!(self.out, "uniform ")?;
+            self.write_image_type(
+                crate::ImageDimension::D2,
+                false,
+                crate::ImageClass::Sampled {
+                    kind: crate::ScalarKind::Float,
+                    multi: false,
+                },
+            )?;
+            let plane_name =
+                self.get_external_texture_global_name(global, ExternalTextureNameKey::Plane(i));
+            writeln!(self.out, " {plane_name};")?;
+        }
+
+        write!(self.out, "layout(std140")?;
+        if let Some(ref bind_target) = bind_target {
+            write!(self.out, ", binding = {}", bind_target.params)?;
+        }
+        write!(self.out, ") uniform ")?;
+
+        // The parameters are written as the sole member of an interface
+        // block, named in the same way as `write_interface_block`.
+        let ty_name =
+            &self.names[&NameKey::Type(self.module.special_types.external_texture_params.unwrap())];
+        let block_name = format!(
+            "{}_block_{}{:?}",
+            ty_name.trim_end_matches('_'),
+            self.block_id.generate(),
+            self.entry_point.stage,
+        );
+        let params_name =
+            self.get_external_texture_global_name(global, ExternalTextureNameKey::Params);
+        writeln!(self.out, "{block_name} {{ {ty_name} {params_name}; }};")?;
+        self.reflection_names_globals.insert(handle, block_name);
+
+        Ok(())
+    }
+
+    /// Write the helper functions implementing `textureSampleBaseClampToEdge()`,
+    /// `textureLoad()` and `textureDimensions()` for [`External`] images.
+    ///
+    /// Each of them takes the three planes and the parameters struct an
+    /// external texture is lowered to as its first arguments. See the module
+    /// documentation's section on [External textures][mod] for details.
+    ///
+    /// [`External`]: crate::ImageClass::External
+    /// [mod]: self#external-textures
+    fn write_external_texture_functions(&mut self) -> BackendResult {
+        let l1 = back::Level(1);
+        let l2 = l1.next();
+        let l3 = l2.next();
+        let params_ty_name = self.names
+            [&NameKey::Type(self.module.special_types.external_texture_params.unwrap())]
+            .clone();
+        let sampler = if self.options.version.is_es() {
+            "highp sampler2D"
+        } else {
+            "sampler2D"
+        };
+        let write_arguments = |this: &mut Self, coords_ty: &str| -> BackendResult {
+            writeln!(this.out, "{l1}{sampler} plane0,")?;
+            writeln!(this.out, "{l1}{sampler} plane1,")?;
+            writeln!(this.out, "{l1}{sampler} plane2,")?;
+            writeln!(this.out, "{l1}{params_ty_name} params,")?;
+            writeln!(this.out, "{l1}{coords_ty} coords)")?;
+            Ok(())
+        };
+
+        writeln!(self.out, "vec4 {IMAGE_SAMPLE_EXTERNAL_FUNCTION}(")?;
+        write_arguments(self, "vec2")?;
+        writeln!(self.out, "{{")?;
+        writeln!(
+            self.out,
+            "{l1}vec2 plane0_size = vec2(textureSize(plane0, 0));"
+        )?;
+        writeln!(self.out, "{l1}mat3x2 sample_transform = mat3x2(")?;
+        writeln!(self.out, "{l2}params.sample_transform_0,")?;
+        writeln!(self.out, "{l2}params.sample_transform_1,")?;
+        writeln!(self.out, "{l2}params.sample_transform_2")?;
+        writeln!(self.out, "{l1});")?;
+        writeln!(
+            self.out,
+            "{l1}coords = sample_transform * vec3(coords, 1.0);"
+        )?;
+        // Calculate the sample bounds. The purported size of the texture
+        // (params.size) is irrelevant here as we are dealing with normalized
+        // coordinates. Usually we would clamp to (0,0)..(1,1). However, we
+        // must apply the sample transformation to that, also bearing in mind
+        // that it may contain a flip on either axis. We calculate and adjust
+        // for the half-texel separately for each plane as it depends on the
+        // texture size which may vary between planes.
+        writeln!(
+            self.out,
+            "{l1}vec2 bounds_min = sample_transform * vec3(0.0, 0.0, 1.0);"
+        )?;
+        writeln!(
+            self.out,
+            "{l1}vec2 bounds_max = sample_transform * vec3(1.0, 1.0, 1.0);"
+        )?;
+        writeln!(
+            self.out,
+            "{l1}vec4 bounds = vec4(min(bounds_min, bounds_max), max(bounds_min, bounds_max));"
+        )?;
+        writeln!(
+            self.out,
+            "{l1}vec2 plane0_half_texel = vec2(0.5, 0.5) / plane0_size;"
+        )?;
+        writeln!(self.out, "{l1}vec2 plane0_coords = clamp(coords, bounds.xy + plane0_half_texel, bounds.zw - plane0_half_texel);")?;
+        writeln!(self.out, "{l1}if (params.num_planes == 1u) {{")?;
+        // For single plane, simply sample from plane0
+        writeln!(
+            self.out,
+            "{l2}return textureLod(plane0, plane0_coords, 0.0);"
+        )?;
+        writeln!(self.out, "{l1}}} else {{")?;
+        writeln!(
+            self.out,
+            "{l2}vec2 plane1_size = vec2(textureSize(plane1, 0));"
+        )?;
+        writeln!(
+            self.out,
+            "{l2}vec2 plane1_half_texel = vec2(0.5, 0.5) / plane1_size;"
+        )?;
+        writeln!(self.out, "{l2}vec2 plane1_coords = clamp(coords, bounds.xy + plane1_half_texel, bounds.zw - plane1_half_texel);")?;
+        // For multi-plane, sample the Y value from plane 0
+        writeln!(
+            self.out,
+            "{l2}float y = textureLod(plane0, plane0_coords, 0.0).x;"
+        )?;
+        writeln!(self.out, "{l2}vec2 uv;")?;
+        writeln!(self.out, "{l2}if (params.num_planes == 2u) {{")?;
+        // Sample UV from interleaved plane 1
+        writeln!(
+            self.out,
+            "{l3}uv = textureLod(plane1, plane1_coords, 0.0).xy;"
+        )?;
+        writeln!(self.out, "{l2}}} else {{")?;
+        // Sample U and V from planes 1 and 2 respectively
+        writeln!(
+            self.out,
+            "{l3}vec2 plane2_size = vec2(textureSize(plane2, 0));"
+        )?;
+        writeln!(
+            self.out,
+            "{l3}vec2 plane2_half_texel = vec2(0.5, 0.5) / plane2_size;"
+        )?;
+        writeln!(self.out, "{l3}vec2 plane2_coords = clamp(coords, bounds.xy + plane2_half_texel, bounds.zw - plane2_half_texel);")?;
+        writeln!(self.out, "{l3}uv = vec2(textureLod(plane1, plane1_coords, 0.0).x, textureLod(plane2, plane2_coords, 0.0).x);")?;
+        writeln!(self.out, "{l2}}}")?;
+        self.write_convert_yuv_to_rgb_and_return(l2)?;
+        writeln!(self.out, "{l1}}}")?;
+        writeln!(self.out, "}}")?;
+        writeln!(self.out)?;
+
+        writeln!(self.out, "vec4 {IMAGE_LOAD_EXTERNAL_FUNCTION}(")?;
+        write_arguments(self, "uvec2")?;
+        writeln!(self.out, "{{")?;
+        writeln!(
+            self.out,
+            "{l1}uvec2 plane0_size = uvec2(textureSize(plane0, 0));"
+        )?;
+        // Clamp coords to provided size of external texture to prevent OOB
+        // read. If params.size is zero then clamp to the actual size of the
+        // texture.
+        writeln!(self.out, "{l1}uvec2 cropped_size = any(notEqual(params.size, uvec2(0u))) ? params.size : plane0_size;")?;
+        writeln!(self.out, "{l1}coords = min(coords, cropped_size - 1u);")?;
+        writeln!(self.out, "{l1}mat3x2 load_transform = mat3x2(")?;
+        writeln!(self.out, "{l2}params.load_transform_0,")?;
+        writeln!(self.out, "{l2}params.load_transform_1,")?;
+        writeln!(self.out, "{l2}params.load_transform_2")?;
+        writeln!(self.out, "{l1});")?;
+        writeln!(
+            self.out,
+            "{l1}uvec2 plane0_coords = uvec2(round(load_transform * vec3(coords, 1.0)));"
+        )?;
+        writeln!(self.out, "{l1}if (params.num_planes == 1u) {{")?;
+        // For single plane, simply read from plane0
+        writeln!(
+            self.out,
+            "{l2}return texelFetch(plane0, ivec2(plane0_coords), 0);"
+        )?;
+        writeln!(self.out, "{l1}}} else {{")?;
+        // Chroma planes may be subsampled so we must scale the coords
+        // accordingly.
+        writeln!(
+            self.out,
+            "{l2}uvec2 plane1_size = uvec2(textureSize(plane1, 0));"
+        )?;
+        writeln!(self.out, "{l2}uvec2 plane1_coords = uvec2(floor(vec2(plane0_coords) * vec2(plane1_size) / vec2(plane0_size)));")?;
+        // For multi-plane, read the Y value from plane 0
+        writeln!(
+            self.out,
+            "{l2}float y = texelFetch(plane0, ivec2(plane0_coords), 0).x;"
+        )?;
+        writeln!(self.out, "{l2}vec2 uv;")?;
+        writeln!(self.out, "{l2}if (params.num_planes == 2u) {{")?;
+        // Read UV from interleaved plane 1
+        writeln!(
+            self.out,
+            "{l3}uv = texelFetch(plane1, ivec2(plane1_coords), 0).xy;"
+        )?;
+        writeln!(self.out, "{l2}}} else {{")?;
+        // Read U and V from planes 1 and 2 respectively
+        writeln!(
+            self.out,
+            "{l3}uvec2 plane2_size = uvec2(textureSize(plane2, 0));"
+        )?;
+        writeln!(self.out, "{l3}uvec2 plane2_coords = uvec2(floor(vec2(plane0_coords) * vec2(plane2_size) / vec2(plane0_size)));")?;
+        writeln!(self.out, "{l3}uv = vec2(texelFetch(plane1, ivec2(plane1_coords), 0).x, texelFetch(plane2, ivec2(plane2_coords), 0).x);")?;
+        writeln!(self.out, "{l2}}}")?;
+        self.write_convert_yuv_to_rgb_and_return(l2)?;
+        writeln!(self.out, "{l1}}}")?;
+        writeln!(self.out, "}}")?;
+        writeln!(self.out)?;
+
+        // The size is params.size if it is non-zero, otherwise the size of
+        // the first plane.
+        writeln!(self.out, "uvec2 {IMAGE_SIZE_EXTERNAL_FUNCTION}(")?;
+        writeln!(self.out, "{l1}{sampler} plane0,")?;
+        writeln!(self.out, "{l1}{sampler} plane1,")?;
+        writeln!(self.out, "{l1}{sampler} plane2,")?;
+        writeln!(self.out, "{l1}{params_ty_name} params)")?;
+        writeln!(self.out, "{{")?;
+        writeln!(
+            self.out,
+            "{l1}if (any(notEqual(params.size, uvec2(0u)))) {{"
+        )?;
+        writeln!(self.out, "{l2}return params.size;")?;
+        writeln!(self.out, "{l1}}} else {{")?;
+        writeln!(self.out, "{l2}return uvec2(textureSize(plane0, 0));")?;
+        writeln!(self.out, "{l1}}}")?;
+        writeln!(self.out, "}}")?;
+        writeln!(self.out)?;
+
+        Ok(())
+    }
+
+    /// Helper method used by [`Self::write_external_texture_functions`] to
+    /// write the YUV to RGB conversion shared by the sample and load
+    /// functions. Expects the preceding code to declare the Y component as a
+    /// `float` named `y`, the UV components as a `vec2` named `uv`, and the
+    /// external texture parameters as `params`. The emitted code returns the
+    /// result.
+    fn write_convert_yuv_to_rgb_and_return(&mut self, level: back::Level) -> BackendResult {
+        let l1 = level;
+        let l2 = l1.next();
+
+        // Convert from YUV to non-linear RGB in the source color space.
+        writeln!(
+            self.out,
+            "{l1}vec3 srcGammaRgb = (params.yuv_conversion_matrix * vec4(y, uv, 1.0)).rgb;"
+        )?;
+        // Apply the inverse of the source transfer function to convert to
+        // linear RGB in the source color space. `mix` with a boolean vector
+        // selects between the two pieces of the function per component.
+        writeln!(self.out, "{l1}vec3 srcLinearRgb = mix(")?;
+        writeln!(self.out, "{l2}pow((srcGammaRgb + params.src_tf.a - 1.0) / params.src_tf.a, vec3(params.src_tf.g)),")?;
+        writeln!(self.out, "{l2}srcGammaRgb / params.src_tf.k,")?;
+        writeln!(
+            self.out,
+            "{l2}lessThan(srcGammaRgb, vec3(params.src_tf.k * params.src_tf.b)));"
+        )?;
+        // Multiply by the gamut conversion matrix to convert to linear RGB in
+        // the destination color space.
+        writeln!(
+            self.out,
+            "{l1}vec3 dstLinearRgb = params.gamut_conversion_matrix * srcLinearRgb;"
+        )?;
+        // Finally, apply the dest transfer function to convert to non-linear
+        // RGB in the destination color space, and return the result.
+        writeln!(self.out, "{l1}vec3 dstGammaRgb = mix(")?;
+        writeln!(self.out, "{l2}params.dst_tf.a * pow(dstLinearRgb, vec3(1.0 / params.dst_tf.g)) - (params.dst_tf.a - 1.0),")?;
+        writeln!(self.out, "{l2}params.dst_tf.k * dstLinearRgb,")?;
+        writeln!(
+            self.out,
+            "{l2}lessThan(dstLinearRgb, vec3(params.dst_tf.b)));"
+        )?;
+        writeln!(self.out, "{l1}return vec4(dstGammaRgb, 1.0);")?;
+
+        Ok(())
+    }
+
     /// Helper method used to find which expressions of a given function require baking
     ///
     /// # Notes
@@ -1448,26 +2073,22 @@ impl<'a, W: Write> Writer<'a, W> {
                     | crate::MathFunction::QuantizeToF16 => {
                         self.need_bake_expressions.insert(arg);
                     }
-                    /* crate::MathFunction::Pack4x8unorm | */
                     crate::MathFunction::Unpack4x8snorm
                         if !self.options.version.supports_pack_unpack_4x8() =>
                     {
                         // We have a fallback if the platform doesn't natively support these
                         self.need_bake_expressions.insert(arg);
                     }
-                    /* crate::MathFunction::Pack4x8unorm | */
                     crate::MathFunction::Unpack4x8unorm
                         if !self.options.version.supports_pack_unpack_4x8() =>
                     {
                         self.need_bake_expressions.insert(arg);
                     }
-                    /* crate::MathFunction::Pack2x16snorm |  */
                     crate::MathFunction::Unpack2x16snorm
                         if !self.options.version.supports_pack_unpack_snorm_2x16() =>
                     {
                         self.need_bake_expressions.insert(arg);
                     }
-                    /* crate::MathFunction::Pack2x16unorm | */
                     crate::MathFunction::Unpack2x16unorm
                         if !self.options.version.supports_pack_unpack_unorm_2x16() =>
                     {
@@ -1531,6 +2152,32 @@ impl<'a, W: Write> Writer<'a, W> {
         }
     }
 
+    /// Helper method used to get the name of one of the globals an [`External`]
+    /// image global variable is lowered to.
+    ///
+    /// These are named like [`get_global_name`], with `_planeN` or `_params`
+    /// inserted before the stage suffix.
+    ///
+    /// [`External`]: crate::ImageClass::External
+    /// [`get_global_name`]: Self::get_global_name
+    fn get_external_texture_global_name(
+        &self,
+        global: &crate::GlobalVariable,
+        key: ExternalTextureNameKey,
+    ) -> String {
+        let br = global.binding.as_ref().unwrap();
+        let stage = self.entry_point.stage.to_str();
+        match key {
+            ExternalTextureNameKey::Plane(i) => format!(
+                "_group_{}_binding_{}_plane{i}_{stage}",
+                br.group, br.binding
+            ),
+            ExternalTextureNameKey::Params => {
+                format!("_group_{}_binding_{}_params_{stage}", br.group, br.binding)
+            }
+        }
+    }
+
     /// Helper method used to write a name for a global without additional heap allocation
     fn write_global_name(
         &mut self,
@@ -1814,6 +2461,40 @@ impl<'a, W: Write> Writer<'a, W> {
             })
             .collect();
         self.write_slice(&arguments, |this, _, &(i, arg)| {
+            // External textures are expanded into separate arguments for each
+            // plane and the parameters.
+            if let TypeInner::Image {
+                class: crate::ImageClass::External,
+                ..
+            } = this.module.types[arg.ty].inner
+            {
+                for plane in 0..3 {
+                    this.write_image_type(
+                        crate::ImageDimension::D2,
+                        false,
+                        crate::ImageClass::Sampled {
+                            kind: crate::ScalarKind::Float,
+                            multi: false,
+                        },
+                    )?;
+                    let key = ctx.external_texture_argument_key(
+                        i as u32,
+                        ExternalTextureNameKey::Plane(plane),
+                    );
+                    write!(this.out, " {}, ", &this.names[&key])?;
+                }
+                let params_ty = this.module.special_types.external_texture_params.unwrap();
+                let key =
+                    ctx.external_texture_argument_key(i as u32, ExternalTextureNameKey::Params);
+                write!(
+                    this.out,
+                    "{} {}",
+                    &this.names[&NameKey::Type(params_ty)],
+                    &this.names[&key]
+                )?;
+                return Ok(());
+            }
+
             // Write the argument type
             match this.module.types[arg.ty].inner {
                 // We treat images separately because they might require
@@ -1952,11 +2633,9 @@ impl<'a, W: Write> Writer<'a, W> {
         }
 
         // Write the function body (statement list)
-        for sta in func.body.iter() {
-            // Write a statement, the indentation should always be 1 when writing the function body
-            // `write_stmt` adds a newline
-            self.write_stmt(sta, &ctx, back::Level(1))?;
-        }
+        // Write the statements, the indentation should always be 1 when writing the function body
+        // `write_stmt` adds a newline
+        self.write_block(&func.body, &ctx, back::Level(1))?;
 
         // Close braces and add a newline
         writeln!(self.out, "}}")?;
@@ -2114,6 +2793,24 @@ impl<'a, W: Write> Writer<'a, W> {
                     // Newline is important
                     writeln!(self.out, ";")?;
                 }
+                // std140 would pad each column of the `mat3x2` members of
+                // the external texture parameters to 16 bytes, so we write
+                // them as separate `vec2`s instead, and rebuild the matrices
+                // in the functions that use them.
+                TypeInner::Matrix {
+                    columns,
+                    rows: rows @ crate::VectorSize::Bi,
+                    scalar,
+                } if Some(handle) == self.module.special_types.external_texture_params => {
+                    let name = self.names[&NameKey::StructMember(handle, idx as u32)].clone();
+                    for column in 0..columns as u8 {
+                        if column != 0 {
+                            write!(self.out, "{}", back::INDENT)?;
+                        }
+                        self.write_value_type(&TypeInner::Vector { size: rows, scalar })?;
+                        writeln!(self.out, " {name}_{column};")?;
+                    }
+                }
                 _ => {
                     // Write the member type
                     // Adds no trailing space
@@ -2135,6 +2832,21 @@ impl<'a, W: Write> Writer<'a, W> {
         Ok(())
     }
 
+    /// Helper method used to write the statements of a block, marking them
+    /// in the source map
+    fn write_block(
+        &mut self,
+        block: &crate::Block,
+        ctx: &back::FunctionCtx,
+        level: back::Level,
+    ) -> BackendResult {
+        for (sta, &span) in block.span_iter() {
+            self.out.mark(span)?;
+            self.write_stmt(sta, ctx, level)?;
+        }
+        Ok(())
+    }
+
     /// Helper method used to write statements
     ///
     /// # Notes
@@ -2202,10 +2914,7 @@ impl<'a, W: Write> Writer<'a, W> {
             Statement::Block(ref block) => {
                 write!(self.out, "{level}")?;
                 writeln!(self.out, "{{")?;
-                for sta in block.iter() {
-                    // Increase the indentation to help with readability
-                    self.write_stmt(sta, ctx, level.next())?
-                }
+                self.write_block(block, ctx, level.next())?;
                 writeln!(self.out, "{level}}}")?
             }
             // Ifs are written as in C:
@@ -2226,20 +2935,14 @@ impl<'a, W: Write> Writer<'a, W> {
                 self.write_expr(condition, ctx)?;
                 writeln!(self.out, ") {{")?;
 
-                for sta in accept {
-                    // Increase indentation to help with readability
-                    self.write_stmt(sta, ctx, level.next())?;
-                }
+                self.write_block(accept, ctx, level.next())?;
 
                 // If there are no statements in the reject block we skip writing it
                 // This is only for readability
                 if !reject.is_empty() {
                     writeln!(self.out, "{level}}} else {{")?;
 
-                    for sta in reject {
-                        // Increase indentation to help with readability
-                        self.write_stmt(sta, ctx, level.next())?;
-                    }
+                    self.write_block(reject, ctx, level.next())?;
                 }
 
                 writeln!(self.out, "{level}}}")?
@@ -2290,9 +2993,7 @@ impl<'a, W: Write> Writer<'a, W> {
 
                     // Body
                     if let Some(case) = cases.last() {
-                        for sta in case.body.iter() {
-                            self.write_stmt(sta, ctx, l2)?;
-                        }
+                        self.write_block(&case.body, ctx, l2)?;
                     }
                     // End do-while
                     writeln!(self.out, "{level}}} while(false);")?;
@@ -2335,9 +3036,7 @@ impl<'a, W: Write> Writer<'a, W> {
                             writeln!(self.out)?;
                         }
 
-                        for sta in case.body.iter() {
-                            self.write_stmt(sta, ctx, l2.next())?;
-                        }
+                        self.write_block(&case.body, ctx, l2.next())?;
 
                         if !case.fall_through && case.body.last().is_none_or(|s| !s.is_terminator())
                         {
@@ -2375,9 +3074,7 @@ impl<'a, W: Write> Writer<'a, W> {
                     let l2 = level.next();
                     let l3 = l2.next();
                     writeln!(self.out, "{l2}if (!{gate_name}) {{")?;
-                    for sta in continuing {
-                        self.write_stmt(sta, ctx, l3)?;
-                    }
+                    self.write_block(continuing, ctx, l3)?;
                     if let Some(condition) = break_if {
                         write!(self.out, "{l3}if (")?;
                         self.write_expr(condition, ctx)?;
@@ -2390,9 +3087,7 @@ impl<'a, W: Write> Writer<'a, W> {
                 } else {
                     writeln!(self.out, "{level}while(true) {{")?;
                 }
-                for sta in body {
-                    self.write_stmt(sta, ctx, level.next())?;
-                }
+                self.write_block(body, ctx, level.next())?;
                 writeln!(self.out, "{level}}}")?;
                 self.continue_ctx.exit_loop();
             }
@@ -3024,14 +3719,52 @@ impl<'a, W: Write> Writer<'a, W> {
                 }
             }
             // Function arguments are written as the argument name
+            //
+            // External texture arguments have been expanded into separate
+            // consecutive arguments for each plane and the parameters, and
+            // can only ever be passed on to another function, so we write all
+            // of them in a comma-separated list.
             Expression::FunctionArgument(pos) => {
-                write!(self.out, "{}", &self.names[&ctx.argument_key(pos)])?
+                match *ctx.resolve_type(expr, &self.module.types) {
+                    TypeInner::Image {
+                        class: crate::ImageClass::External,
+                        ..
+                    } => {
+                        let [plane0, plane1, plane2, params] = [
+                            ExternalTextureNameKey::Plane(0),
+                            ExternalTextureNameKey::Plane(1),
+                            ExternalTextureNameKey::Plane(2),
+                            ExternalTextureNameKey::Params,
+                        ]
+                        .map(|key| &self.names[&ctx.external_texture_argument_key(pos, key)]);
+                        write!(self.out, "{plane0}, {plane1}, {plane2}, {params}")?
+                    }
+                    _ => write!(self.out, "{}", &self.names[&ctx.argument_key(pos)])?,
+                }
             }
             // Global variables need some special work for their name but
             // `get_global_name` does the work for us
+            //
+            // External textures are written as a comma-separated list, in the
+            // same way as function arguments.
             Expression::GlobalVariable(handle) => {
                 let global = &self.module.global_variables[handle];
-                self.write_global_name(handle, global)?
+                if let TypeInner::Image {
+                    class: crate::ImageClass::External,
+                    ..
+                } = self.module.types[global.ty].inner
+                {
+                    let [plane0, plane1, plane2, params] = [
+                        ExternalTextureNameKey::Plane(0),
+                        ExternalTextureNameKey::Plane(1),
+                        ExternalTextureNameKey::Plane(2),
+                        ExternalTextureNameKey::Params,
+                    ]
+                    .map(|key| self.get_external_texture_global_name(global, key));
+                    write!(self.out, "{plane0}, {plane1}, {plane2}, {params}")?
+                } else {
+                    self.write_global_name(handle, global)?
+                }
             }
             // A local is written as it's name
             Expression::LocalVariable(handle) => {
@@ -3068,6 +3801,18 @@ impl<'a, W: Write> Writer<'a, W> {
                     } => (dim, class, arrayed),
                     _ => unreachable!(),
                 };
+
+                // External textures are sampled through a helper function,
+                // which always samples the base level and clamps to the edge.
+                if let crate::ImageClass::External = class {
+                    write!(self.out, "{IMAGE_SAMPLE_EXTERNAL_FUNCTION}(")?;
+                    self.write_expr(image, ctx)?;
+                    write!(self.out, ", ")?;
+                    self.write_expr(coordinate, ctx)?;
+                    write!(self.out, ")")?;
+                    return Ok(());
+                }
+
                 let mut err = None;
                 if dim == crate::ImageDimension::Cube {
                     if offset.is_some() {
@@ -3258,6 +4003,13 @@ impl<'a, W: Write> Writer<'a, W> {
                     crate::ImageDimension::Cube => 2,
                 };
 
+                if class == ImageClass::External && !matches!(query, crate::ImageQuery::Size { .. })
+                {
+                    return Err(Error::Custom(
+                        "External images only support `Size` queries".into(),
+                    ));
+                }
+
                 if let crate::ImageQuery::Size { .. } = query {
                     match components {
                         1 => write!(self.out, "uint(")?,
@@ -3303,7 +4055,10 @@ impl<'a, W: Write> Writer<'a, W> {
                                 write!(self.out, "imageSize(")?;
                                 self.write_expr(image, ctx)?;
                             }
-                            ImageClass::External => unimplemented!(),
+                            ImageClass::External => {
+                                write!(self.out, "{IMAGE_SIZE_EXTERNAL_FUNCTION}(")?;
+                                self.write_expr(image, ctx)?;
+                            }
                         }
                         write!(self.out, ")")?;
                         if components != 1 || self.options.version.is_es() {
@@ -3319,7 +4074,7 @@ impl<'a, W: Write> Writer<'a, W> {
                         let fun_name = match class {
                             ImageClass::Sampled { .. } | ImageClass::Depth { .. } => "textureSize",
                             ImageClass::Storage { .. } => "imageSize",
-                            ImageClass::External => unimplemented!(),
+                            ImageClass::External => unreachable!(),
                         };
                         write!(self.out, "{fun_name}(")?;
                         self.write_expr(image, ctx)?;
@@ -3339,7 +4094,7 @@ impl<'a, W: Write> Writer<'a, W> {
                                 "textureSamples"
                             }
                             ImageClass::Storage { .. } => "imageSamples",
-                            ImageClass::External => unimplemented!(),
+                            ImageClass::External => unreachable!(),
                         };
                         write!(self.out, "{fun_name}(")?;
                         self.write_expr(image, ctx)?;
@@ -3964,42 +4719,13 @@ impl<'a, W: Write> Writer<'a, W> {
                     Mf::FirstTrailingBit => "findLSB",
                     Mf::FirstLeadingBit => "findMSB",
                     // data packing
-                    Mf::Pack4x8snorm => {
-                        if self.options.version.supports_pack_unpack_4x8() {
-                            "packSnorm4x8"
-                        } else {
-                            // polyfill should go here. Needs a corresponding entry in `need_bake_expression`
-                            return Err(Error::UnsupportedExternal("packSnorm4x8".into()));
-                        }
-                    }
-                    Mf::Pack4x8unorm => {
-                        if self.options.version.supports_pack_unpack_4x8() {
-                            "packUnorm4x8"
-                        } else {
-                            return Err(Error::UnsupportedExternal("packUnorm4x8".to_owned()));
-                        }
-                    }
-                    Mf::Pack2x16snorm => {
-                        if self.options.version.supports_pack_unpack_snorm_2x16() {
-                            "packSnorm2x16"
-                        } else {
-                            return Err(Error::UnsupportedExternal("packSnorm2x16".to_owned()));
-                        }
-                    }
-                    Mf::Pack2x16unorm => {
-                        if self.options.version.supports_pack_unpack_unorm_2x16() {
-                            "packUnorm2x16"
-                        } else {
-                            return Err(Error::UnsupportedExternal("packUnorm2x16".to_owned()));
-                        }
-                    }
-                    Mf::Pack2x16float => {
-                        if self.options.version.supports_pack_unpack_half_2x16() {
-                            "packHalf2x16"
-                        } else {
-                            return Err(Error::UnsupportedExternal("packHalf2x16".to_owned()));
-                        }
-                    }
+                    // Versions lacking these get the polyfills written by
+                    // `write_pack_unpack_polyfills`.
+                    Mf::Pack4x8snorm => self.pack_unpack_polyfill(fun).unwrap_or("packSnorm4x8"),
+                    Mf::Pack4x8unorm => self.pack_unpack_polyfill(fun).unwrap_or("packUnorm4x8"),
+                    Mf::Pack2x16snorm => self.pack_unpack_polyfill(fun).unwrap_or("packSnorm2x16"),
+                    Mf::Pack2x16unorm => self.pack_unpack_polyfill(fun).unwrap_or("packUnorm2x16"),
+                    Mf::Pack2x16float => self.pack_unpack_polyfill(fun).unwrap_or("packHalf2x16"),
 
                     fun @ (Mf::Pack4xI8 | Mf::Pack4xU8 | Mf::Pack4xI8Clamp | Mf::Pack4xU8Clamp) => {
                         let was_signed = matches!(fun, Mf::Pack4xI8 | Mf::Pack4xI8Clamp);
@@ -4039,11 +4765,7 @@ impl<'a, W: Write> Writer<'a, W> {
                     }
                     // data unpacking
                     Mf::Unpack2x16float => {
-                        if self.options.version.supports_pack_unpack_half_2x16() {
-                            "unpackHalf2x16"
-                        } else {
-                            return Err(Error::UnsupportedExternal("unpackHalf2x16".into()));
-                        }
+                        self.pack_unpack_polyfill(fun).unwrap_or("unpackHalf2x16")
                     }
                     Mf::Unpack2x16snorm => {
                         if self.options.version.supports_pack_unpack_snorm_2x16() {
@@ -4595,6 +5317,17 @@ impl<'a, W: Write> Writer<'a, W> {
             _ => unreachable!(),
         };
 
+        // External textures are loaded through a helper function, which
+        // clamps the coordinates to the size of the texture itself.
+        if let crate::ImageClass::External = class {
+            write!(self.out, "{IMAGE_LOAD_EXTERNAL_FUNCTION}(")?;
+            self.write_expr(image, ctx)?;
+            write!(self.out, ", uvec2(")?;
+            self.write_expr(coordinate, ctx)?;
+            write!(self.out, "))")?;
+            return Ok(());
+        }
+
         // Get the name of the function to be used for the load operation
         // and the policy to be used with it.
         let (fun_name, policy) = match class {
@@ -4622,7 +5355,7 @@ impl<'a, W: Write> Writer<'a, W> {
                     "WGSL `textureLoad` from depth textures is not supported in GLSL".to_string(),
                 ))
             }
-            crate::ImageClass::External => unimplemented!(),
+            crate::ImageClass::External => unreachable!(),
         };
 
         // openGL es doesn't have 1D images so we need workaround it
@@ -4998,19 +5731,20 @@ impl<'a, W: Write> Writer<'a, W> {
         let mut uniforms = crate::FastHashMap::default();
 
         for sampling in info.sampling_set.iter() {
-            let tex_name = self.reflection_names_globals[&sampling.image].clone();
-
-            match texture_mapping.entry(tex_name) {
-                hash_map::Entry::Vacant(v) => {
-                    v.insert(TextureMapping {
-                        texture: sampling.image,
-                        sampler: Some(sampling.sampler),
-                    });
-                }
-                hash_map::Entry::Occupied(e) => {
-                    if e.get().sampler != Some(sampling.sampler) {
-                        log::error!("Conflicting samplers for {}", e.key());
-                        return Err(Error::ImageMultipleSamplers);
+            for (tex_name, external_plane) in self.reflection_texture_names(sampling.image) {
+                match texture_mapping.entry(tex_name) {
+                    hash_map::Entry::Vacant(v) => {
+                        v.insert(TextureMapping {
+                            texture: sampling.image,
+                            sampler: Some(sampling.sampler),
+                            external_plane,
+                        });
+                    }
+                    hash_map::Entry::Occupied(e) => {
+                        if e.get().sampler != Some(sampling.sampler) {
+                            log::error!("Conflicting samplers for {}", e.key());
+                            return Err(Error::ImageMultipleSamplers);
+                        }
                     }
                 }
             }
@@ -5022,19 +5756,26 @@ impl<'a, W: Write> Writer<'a, W> {
                 continue;
             }
             match self.module.types[var.ty].inner {
-                TypeInner::Image { .. } => {
-                    let tex_name = self.reflection_names_globals[&handle].clone();
-                    match texture_mapping.entry(tex_name) {
-                        hash_map::Entry::Vacant(v) => {
-                            v.insert(TextureMapping {
-                                texture: handle,
-                                sampler: None,
-                            });
-                        }
-                        hash_map::Entry::Occupied(_) => {
-                            // already used with a sampler, do nothing
+                TypeInner::Image { class, .. } => {
+                    for (tex_name, external_plane) in self.reflection_texture_names(handle) {
+                        match texture_mapping.entry(tex_name) {
+                            hash_map::Entry::Vacant(v) => {
+                                v.insert(TextureMapping {
+                                    texture: handle,
+                                    sampler: None,
+                                    external_plane,
+                                });
+                            }
+                            hash_map::Entry::Occupied(_) => {
+                                // already used with a sampler, do nothing
+                            }
                         }
                     }
+                    // The parameters of external textures are in a uniform block
+                    if class == crate::ImageClass::External {
+                        let name = self.reflection_names_globals[&handle].clone();
+                        uniforms.insert(handle, name);
+                    }
                 }
                 _ => match var.space {
                     crate::AddressSpace::Uniform | crate::AddressSpace::Storage { .. } => {
@@ -5080,9 +5821,32 @@ impl<'a, W: Write> Writer<'a, W> {
             varying: mem::take(&mut self.varying),
             push_constant_items,
             clip_distance_count: self.clip_distance_count,
+            source_map: self.out.take_source_map(),
         })
     }
 
+    /// Returns the names of the textures an image global variable is written
+    /// as, together with the plane each holds for external textures.
+    fn reflection_texture_names(
+        &self,
+        handle: Handle<crate::GlobalVariable>,
+    ) -> Vec<(String, Option<usize>)> {
+        let global = &self.module.global_variables[handle];
+        match self.module.types[global.ty].inner {
+            TypeInner::Image {
+                class: crate::ImageClass::External,
+                ..
+            } => (0..3)
+                .map(|i| {
+                    let name = self
+                        .get_external_texture_global_name(global, ExternalTextureNameKey::Plane(i));
+                    (name, Some(i))
+                })
+                .collect(),
+            _ => vec![(self.reflection_names_globals[&handle].clone(), None)],
+        }
+    }
+
     fn collect_push_constant_items(
         &mut self,
         ty: Handle<crate::Type>,
@@ -2085,6 +2085,62 @@ impl<W: Write> super::Writer<'_, W> {
         Ok(())
     }
 
+    /// Writes out the buffer of descriptor heap base indices if it hasn't been written yet.
+    pub(super) fn write_descriptor_heap_bases(&mut self) -> BackendResult {
+        if self.wrapped.descriptor_heap_bases {
+            return Ok(());
+        }
+
+        let bind_target = self
+            .options
+            .dynamic_resources
+            .as_ref()
+            .unwrap()
+            .base_indices;
+        writeln!(
+            self.out,
+            "StructuredBuffer<uint2> {} : register(t{}, space{});",
+            super::writer::DESCRIPTOR_HEAP_BASES_VAR,
+            bind_target.register,
+            bind_target.space
+        )?;
+
+        self.wrapped.descriptor_heap_bases = true;
+
+        Ok(())
+    }
+
+    /// Writes out the function loading binding array elements of type `ty`
+    /// from `heap`, if it hasn't been written yet.
+    ///
+    /// The descriptor is assigned to a local variable of type `ty` before
+    /// being returned, which is what gives the untyped heap access a type.
+    pub(super) fn write_wrapped_descriptor_heap_load(
+        &mut self,
+        module: &crate::Module,
+        ty: Handle<crate::Type>,
+        heap: super::DescriptorHeap,
+    ) -> BackendResult {
+        if self.wrapped.descriptor_heap_loads.contains_key(&ty) {
+            return Ok(());
+        }
+
+        use crate::back::INDENT;
+
+        let name = self.namer.call("nagaDescriptorHeapLoad");
+        self.write_type(module, ty)?;
+        writeln!(self.out, " {name}(uint index) {{")?;
+        write!(self.out, "{INDENT}")?;
+        self.write_type(module, ty)?;
+        writeln!(self.out, " descriptor = {}[index];", heap.to_hlsl_str())?;
+        writeln!(self.out, "{INDENT}return descriptor;")?;
+        writeln!(self.out, "}}")?;
+
+        self.wrapped.descriptor_heap_loads.insert(ty, name);
+
+        Ok(())
+    }
+
     pub(super) fn write_texture_coordinates(
         &mut self,
         kind: &str,
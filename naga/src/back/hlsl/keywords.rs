@@ -687,6 +687,8 @@ pub const RESERVED: &[&str] = &[
     "CheckAccessFullyMapped",
     "AddUint64",
     "NonUniformResourceIndex",
+    "ResourceDescriptorHeap",
+    "SamplerDescriptorHeap",
     "WaveIsFirstLane",
     "WaveGetLaneIndex",
     "WaveGetLaneCount",
@@ -826,6 +828,7 @@ pub const RESERVED: &[&str] = &[
     super::writer::INSERT_BITS_FUNCTION,
     super::writer::SAMPLER_HEAP_VAR,
     super::writer::COMPARISON_SAMPLER_HEAP_VAR,
+    super::writer::DESCRIPTOR_HEAP_BASES_VAR,
     super::writer::SAMPLE_EXTERNAL_TEXTURE_FUNCTION,
     super::writer::ABS_FUNCTION,
     super::writer::DIV_FUNCTION,
@@ -106,6 +106,30 @@ index buffer for each bind group. This buffer is accessed in the shader to get t
 sampler index within the heap. See the wgpu_hal dx12 backend documentation for more
 information.
 
+# Dynamic resources
+
+Starting with shader model 6.6, HLSL can access descriptors directly through
+the `ResourceDescriptorHeap` and `SamplerDescriptorHeap` arrays, instead of
+through registers bound by the root signature. When
+[`Options::dynamic_resources`] is set, binding arrays of textures and samplers,
+and the other texture and sampler globals selected there, are accessed this way.
+This lifts the limits that register ranges put on the size of binding arrays.
+
+Naga decides where the descriptors of each such global are, relative to the
+base index of its bind group in each heap, and reports this in
+[`ReflectionInfo::descriptor_heap_layout`]: within each bind group, lowered
+textures take consecutive indices in the resource heap, and lowered samplers in
+the sampler heap, in increasing order of binding. The base indices themselves
+are read from a `StructuredBuffer<uint2>` with an element per bind group, bound
+at [`DynamicResources::base_indices`].
+
+A single texture or sampler is declared as a `static const` initialized from
+the heap. For a binding array, we declare a `static const uint` holding the
+index of its first descriptor, and access elements through a helper function
+that loads the descriptor at that index plus the element index from the heap.
+
+Binding arrays of buffers are still accessed through registers.
+
 # External textures
 
 Support for [`crate::ImageClass::External`] textures is implemented by lowering
@@ -451,6 +475,67 @@ where
 pub type ExternalTextureBindingMap =
     alloc::collections::BTreeMap<crate::ResourceBinding, ExternalTextureBindTarget>;
 
+/// Which globals to access through the shader model 6.6 descriptor heaps.
+///
+/// See the module documentation's section on [Dynamic resources][mod] for details.
+///
+/// [mod]: #dynamic-resources
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+#[cfg_attr(feature = "deserialize", serde(default))]
+pub struct DynamicResources {
+    /// Access all binding arrays of textures and samplers through the heaps.
+    pub binding_arrays: bool,
+    /// The other texture and sampler globals to access through the heaps.
+    pub globals: alloc::collections::BTreeSet<crate::ResourceBinding>,
+    /// HLSL binding information for the buffer of the base indices of each
+    /// bind group's descriptors.
+    ///
+    /// This should refer to a `StructuredBuffer<uint2>` with an element per
+    /// bind group, holding the index of its first descriptor in the resource
+    /// heap in `x`, and in the sampler heap in `y`.
+    pub base_indices: BindTarget,
+}
+
+/// A shader model 6.6 descriptor heap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum DescriptorHeap {
+    /// `ResourceDescriptorHeap`, holding the descriptors of textures.
+    Resource,
+    /// `SamplerDescriptorHeap`.
+    Sampler,
+}
+
+impl DescriptorHeap {
+    const fn to_hlsl_str(self) -> &'static str {
+        match self {
+            Self::Resource => "ResourceDescriptorHeap",
+            Self::Sampler => "SamplerDescriptorHeap",
+        }
+    }
+}
+
+/// Where the descriptors of a global accessed through a descriptor heap are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct DescriptorHeapRange {
+    pub heap: DescriptorHeap,
+    /// The index of the first descriptor, relative to the base index of the
+    /// bind group in [`heap`](Self::heap).
+    pub offset: u32,
+    /// The number of descriptors, or `None` for an unbounded binding array
+    /// whose size isn't overridden by [`BindTarget::binding_array_size`].
+    pub count: Option<u32>,
+}
+
+/// The descriptors of each global accessed through a descriptor heap.
+pub type DescriptorHeapLayout =
+    alloc::collections::BTreeMap<crate::ResourceBinding, DescriptorHeapRange>;
+
 /// Shorthand result used internally by the backend
 type BackendResult = Result<(), Error>;
 
@@ -535,6 +620,16 @@ pub struct Options {
     /// [`External`]: crate::ir::ImageClass::External
     pub external_texture_binding_map: ExternalTextureBindingMap,
 
+    /// Access binding arrays and the selected globals through the shader model
+    /// 6.6 descriptor heaps, rather than registers.
+    ///
+    /// This requires [`ShaderModel::V6_6`] or later. The globals must still be
+    /// in [`binding_map`], but only the [`BindTarget::binding_array_size`] of
+    /// their bind target is used.
+    ///
+    /// [`binding_map`]: Self::binding_map
+    pub dynamic_resources: Option<DynamicResources>,
+
     /// Should workgroup variables be zero initialized (by polyfilling)?
     pub zero_initialize_workgroup_memory: bool,
     /// Should we restrict indexing of vectors, matrices and arrays?
@@ -542,6 +637,13 @@ pub struct Options {
     /// If set, loops will have code injected into them, forcing the compiler
     /// to think the number of iterations is bounded.
     pub force_loop_bounding: bool,
+    /// Return a [`SourceMap`] of the output in [`ReflectionInfo::source_map`].
+    ///
+    /// [`SourceMap`]: back::SourceMap
+    pub source_map: bool,
+    /// If set, emit `#line` directives referring to the source the module
+    /// was parsed from.
+    pub line_directives: Option<back::LineDirectives>,
 }
 
 impl Default for Options {
@@ -556,9 +658,12 @@ impl Default for Options {
             push_constants_target: None,
             dynamic_storage_buffer_offsets_targets: alloc::collections::BTreeMap::new(),
             external_texture_binding_map: ExternalTextureBindingMap::default(),
+            dynamic_resources: None,
             zero_initialize_workgroup_memory: true,
             restrict_indexing: true,
             force_loop_bounding: true,
+            source_map: false,
+            line_directives: None,
         }
     }
 }
@@ -615,6 +720,12 @@ pub struct ReflectionInfo {
     ///
     /// Note: Some entry points may fail translation because of missing bindings.
     pub entry_point_names: Vec<Result<String, EntryPointError>>,
+    /// The lines of the output generated from each span of the module, if
+    /// requested by [`Options::source_map`].
+    pub source_map: Option<back::SourceMap>,
+    /// Where the descriptors of the globals accessed through the descriptor
+    /// heaps are, if [`Options::dynamic_resources`] is set.
+    pub descriptor_heap_layout: DescriptorHeapLayout,
 }
 
 /// A subset of options that are meant to be changed per pipeline.
@@ -675,6 +786,11 @@ struct Wrapped {
     sampler_heaps: bool,
     // Mapping from SamplerIndexBufferKey to the name the namer returned.
     sampler_index_buffers: crate::FastHashMap<SamplerIndexBufferKey, String>,
+    /// If true, the buffer of descriptor heap base indices has been written out.
+    descriptor_heap_bases: bool,
+    /// Mapping from the type of the elements of a binding array accessed
+    /// through a descriptor heap to the name of the function loading them.
+    descriptor_heap_loads: crate::FastHashMap<crate::Handle<crate::Type>, String>,
 }
 
 impl Wrapped {
@@ -684,6 +800,8 @@ impl Wrapped {
 
     fn clear(&mut self) {
         self.types.clear();
+        self.descriptor_heap_bases = false;
+        self.descriptor_heap_loads.clear();
     }
 }
 
@@ -717,7 +835,7 @@ impl<'a> FragmentEntryPoint<'a> {
 }
 
 pub struct Writer<'a, W> {
-    out: W,
+    out: back::source_map::SourceMapWriter<W>,
     names: crate::FastHashMap<proc::NameKey, String>,
     namer: proc::Namer,
     /// HLSL backend options
@@ -752,4 +870,7 @@ pub struct Writer<'a, W> {
     /// [`AccessIndex`]: crate::Expression::AccessIndex
     temp_access_chain: Vec<storage::SubAccess>,
     need_bake_expressions: back::NeedBakeExpressions,
+    /// The globals accessed through the descriptor heaps, computed by
+    /// [`Writer::write`] from [`Options::dynamic_resources`].
+    descriptor_heap_layout: DescriptorHeapLayout,
 }
@@ -56,7 +56,10 @@ correct type if necessary.
 */
 
 use alloc::format;
-use core::{fmt, mem};
+use core::{
+    fmt::{self, Write as _},
+    mem,
+};
 
 use super::{super::FunctionCtx, BackendResult, Error};
 use crate::{
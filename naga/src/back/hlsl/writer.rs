@@ -3,7 +3,10 @@ use alloc::{
     string::{String, ToString},
     vec::Vec,
 };
-use core::{fmt, mem};
+use core::{
+    fmt::{self, Write as _},
+    mem,
+};
 
 use super::{
     help,
@@ -34,6 +37,7 @@ pub(crate) const EXTRACT_BITS_FUNCTION: &str = "naga_extractBits";
 pub(crate) const INSERT_BITS_FUNCTION: &str = "naga_insertBits";
 pub(crate) const SAMPLER_HEAP_VAR: &str = "nagaSamplerHeap";
 pub(crate) const COMPARISON_SAMPLER_HEAP_VAR: &str = "nagaComparisonSamplerHeap";
+pub(crate) const DESCRIPTOR_HEAP_BASES_VAR: &str = "nagaDescriptorHeapBases";
 pub(crate) const SAMPLE_EXTERNAL_TEXTURE_FUNCTION: &str = "nagaSampleExternalTexture";
 pub(crate) const ABS_FUNCTION: &str = "naga_abs";
 pub(crate) const DIV_FUNCTION: &str = "naga_div";
@@ -131,10 +135,18 @@ struct BindingArraySamplerInfo {
     binding_array_base_index_name: String,
 }
 
+/// Information for how to generate an access to a binding array in a descriptor heap.
+struct DescriptorHeapArrayInfo {
+    /// Name of the function loading an element from the heap
+    load_function_name: String,
+    /// Variable name of the index of the array's first element in the heap
+    base_index_name: String,
+}
+
 impl<'a, W: fmt::Write> super::Writer<'a, W> {
     pub fn new(out: W, options: &'a Options, pipeline_options: &'a PipelineOptions) -> Self {
         Self {
-            out,
+            out: back::source_map::SourceMapWriter::new(out),
             names: crate::FastHashMap::default(),
             namer: proc::Namer::default(),
             options,
@@ -147,6 +159,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             continue_ctx: back::continue_forward::ContinueCtx::default(),
             temp_access_chain: Vec::new(),
             need_bake_expressions: Default::default(),
+            descriptor_heap_layout: Default::default(),
         }
     }
 
@@ -166,6 +179,11 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         self.written_candidate_intersection = false;
         self.continue_ctx.clear();
         self.need_bake_expressions.clear();
+        self.out.reset(
+            self.options.source_map,
+            self.options.line_directives.as_ref(),
+            true,
+        );
     }
 
     /// Generates statements to be inserted immediately before and at the very
@@ -296,6 +314,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         fragment_entry_point: Option<&FragmentEntryPoint<'_>>,
     ) -> Result<super::ReflectionInfo, Error> {
         self.reset(module);
+        self.descriptor_heap_layout = self.compute_descriptor_heap_layout(module)?;
 
         // Write special constants, if needed
         if let Some(ref bt) = self.options.special_constants_binding {
@@ -461,6 +480,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
 
             self.write_wrapped_functions(module, &ctx)?;
 
+            self.out.mark(module.functions.get_span(handle))?;
             self.write_function(module, name.as_str(), function, &ctx, info)?;
 
             writeln!(self.out)?;
@@ -529,9 +549,114 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
 
         Ok(super::ReflectionInfo {
             entry_point_names: translated_ep_names,
+            source_map: self.out.take_source_map(),
+            descriptor_heap_layout: self.descriptor_heap_layout.clone(),
         })
     }
 
+    /// Decides where the descriptors of the globals accessed through the
+    /// descriptor heaps are.
+    ///
+    /// See the module documentation's section on [Dynamic resources][mod].
+    ///
+    /// [mod]: super#dynamic-resources
+    fn compute_descriptor_heap_layout(
+        &self,
+        module: &Module,
+    ) -> Result<super::DescriptorHeapLayout, Error> {
+        use super::{DescriptorHeap, DescriptorHeapRange};
+
+        let mut layout = super::DescriptorHeapLayout::new();
+        let Some(ref dynamic) = self.options.dynamic_resources else {
+            return Ok(layout);
+        };
+        if self.options.shader_model < ShaderModel::V6_6 {
+            return Err(Error::Custom(
+                "dynamic resources require shader model 6.6 or later".into(),
+            ));
+        }
+
+        let mut globals = Vec::new();
+        for (_, global) in module.global_variables.iter() {
+            let Some(binding) = global.binding else {
+                continue;
+            };
+            let (handle_ty, size) = match module.types[global.ty].inner {
+                TypeInner::BindingArray { base, size } => (&module.types[base].inner, Some(size)),
+                ref inner => (inner, None),
+            };
+            let selected = dynamic.globals.contains(&binding);
+            let lowered_array = dynamic.binding_arrays && size.is_some();
+            if !(selected || lowered_array) {
+                continue;
+            }
+            let heap = match *handle_ty {
+                TypeInner::Image {
+                    class: crate::ImageClass::External,
+                    ..
+                } => None,
+                TypeInner::Image { .. } => Some(DescriptorHeap::Resource),
+                TypeInner::Sampler { .. } => Some(DescriptorHeap::Sampler),
+                _ => None,
+            };
+            let heap = match heap {
+                Some(heap) => heap,
+                // Binding arrays of other resources stay in registers.
+                None if !selected => continue,
+                None => {
+                    return Err(Error::Custom(format!(
+                        "only textures and samplers can be accessed through a descriptor heap, \
+                        but {binding:?} is {:?}",
+                        global.name
+                    )))
+                }
+            };
+            let count = match size {
+                None => Some(1),
+                Some(crate::ArraySize::Constant(size)) => Some(size.get()),
+                Some(crate::ArraySize::Pending(_)) => return Err(Error::Override),
+                Some(crate::ArraySize::Dynamic) => self
+                    .options
+                    .resolve_resource_binding(&binding)
+                    .ok()
+                    .and_then(|bt| bt.binding_array_size),
+            };
+            globals.push((binding, heap, count));
+        }
+        globals.sort_by_key(|&(binding, ..)| binding);
+
+        // The next free index of each heap in each bind group, or the binding
+        // of the unbounded binding array that takes the rest of it.
+        let mut next = crate::FastHashMap::<_, Result<u32, _>>::default();
+        for (binding, heap, count) in globals {
+            let offset = match *next.entry((binding.group, heap)).or_insert(Ok(0)) {
+                Ok(offset) => offset,
+                Err(unbounded) => {
+                    return Err(Error::Custom(format!(
+                        "{binding:?} can't follow {unbounded:?} in the descriptor heap, \
+                        as it is an unbounded binding array"
+                    )))
+                }
+            };
+            let end = match count {
+                Some(count) => Ok(offset.checked_add(count).ok_or_else(|| {
+                    Error::Custom(format!("too many descriptors in group {}", binding.group))
+                })?),
+                None => Err(binding),
+            };
+            next.insert((binding.group, heap), end);
+            layout.insert(
+                binding,
+                DescriptorHeapRange {
+                    heap,
+                    offset,
+                    count,
+                },
+            );
+        }
+        Ok(layout)
+    }
+
     fn write_modifier(&mut self, binding: &crate::Binding) -> BackendResult {
         match *binding {
             crate::Binding::BuiltIn(crate::BuiltIn::Position { invariant: true }) => {
@@ -947,6 +1072,14 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             }
         }
 
+        // So are the globals accessed through the descriptor heaps.
+        let heap_range = global
+            .binding
+            .and_then(|binding| self.descriptor_heap_layout.get(&binding).copied());
+        if let Some(range) = heap_range {
+            return self.write_global_descriptor_heap(module, handle, global, range);
+        }
+
         // Samplers are handled entirely differently, so defer entirely to that method.
         let is_sampler = matches!(*handle_ty, TypeInner::Sampler { .. });
 
@@ -1159,6 +1292,49 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         Ok(())
     }
 
+    /// Write the declaration of a global accessed through a descriptor heap.
+    ///
+    /// See the module documentation's section on [Dynamic resources][mod].
+    ///
+    /// [mod]: super#dynamic-resources
+    fn write_global_descriptor_heap(
+        &mut self,
+        module: &Module,
+        handle: Handle<crate::GlobalVariable>,
+        global: &crate::GlobalVariable,
+        range: super::DescriptorHeapRange,
+    ) -> BackendResult {
+        self.write_descriptor_heap_bases()?;
+
+        let group = global.binding.as_ref().unwrap().group;
+        let component = match range.heap {
+            super::DescriptorHeap::Resource => "x",
+            super::DescriptorHeap::Sampler => "y",
+        };
+        let index = format!(
+            "{DESCRIPTOR_HEAP_BASES_VAR}[{group}].{component} + {}",
+            range.offset
+        );
+
+        match module.types[global.ty].inner {
+            TypeInner::BindingArray { base, .. } => {
+                // As for sampler binding arrays, we declare the index of the
+                // first descriptor, to which accesses add the element index.
+                self.write_wrapped_descriptor_heap_load(module, base, range.heap)?;
+                let name = &self.names[&NameKey::GlobalVariable(handle)];
+                writeln!(self.out, "static const uint {name} = {index};")?;
+            }
+            _ => {
+                write!(self.out, "static const ")?;
+                self.write_type(module, global.ty)?;
+                let name = &self.names[&NameKey::GlobalVariable(handle)];
+                writeln!(self.out, " {name} = {}[{index}];", range.heap.to_hlsl_str())?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Write the declarations for an external texture global variable.
     /// These are emitted as multiple global variables: Three `Texture2D`s
     /// (one for each plane) and a parameters cbuffer.
@@ -1678,10 +1854,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         }
 
         // Write the function body (statement list)
-        for sta in func.body.iter() {
-            // The indentation should always be 1 when writing the function body
-            self.write_stmt(module, sta, func_ctx, back::Level(1))?;
-        }
+        // The indentation should always be 1 when writing the function body
+        self.write_block(module, &func.body, func_ctx, back::Level(1))?;
 
         writeln!(self.out, "}}")?;
 
@@ -1831,9 +2005,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
 
             // Body
             if let Some(case) = cases.last() {
-                for sta in case.body.iter() {
-                    self.write_stmt(module, sta, func_ctx, indent_level_1)?;
-                }
+                self.write_block(module, &case.body, func_ctx, indent_level_1)?;
             }
             // End do-while
             writeln!(self.out, "{level}}} while(false);")?;
@@ -1897,9 +2069,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     for case in &cases[i..=end_case_idx] {
                         writeln!(self.out, "{indent_level_2}{{")?;
                         let prev_len = self.named_expressions.len();
-                        for sta in case.body.iter() {
-                            self.write_stmt(module, sta, func_ctx, indent_level_3)?;
-                        }
+                        self.write_block(module, &case.body, func_ctx, indent_level_3)?;
                         // Clear all named expressions that were previously inserted by the statements in the block
                         self.named_expressions.truncate(prev_len);
                         writeln!(self.out, "{indent_level_2}}}")?;
@@ -1910,9 +2080,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                         writeln!(self.out, "{indent_level_2}break;")?;
                     }
                 } else {
-                    for sta in case.body.iter() {
-                        self.write_stmt(module, sta, func_ctx, indent_level_2)?;
-                    }
+                    self.write_block(module, &case.body, func_ctx, indent_level_2)?;
                     if !case.fall_through && case.body.last().is_none_or(|s| !s.is_terminator()) {
                         writeln!(self.out, "{indent_level_2}break;")?;
                     }
@@ -1959,6 +2127,22 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         Ok(())
     }
 
+    /// Helper method used to write the statements of a block, marking them
+    /// in the source map
+    fn write_block(
+        &mut self,
+        module: &Module,
+        block: &crate::Block,
+        func_ctx: &back::FunctionCtx<'_>,
+        level: back::Level,
+    ) -> BackendResult {
+        for (sta, &span) in block.span_iter() {
+            self.out.mark(span)?;
+            self.write_stmt(module, sta, func_ctx, level)?;
+        }
+        Ok(())
+    }
+
     /// Helper method used to write statements
     ///
     /// # Notes
@@ -2003,10 +2187,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             Statement::Block(ref block) => {
                 write!(self.out, "{level}")?;
                 writeln!(self.out, "{{")?;
-                for sta in block.iter() {
-                    // Increase the indentation to help with readability
-                    self.write_stmt(module, sta, func_ctx, level.next())?
-                }
+                self.write_block(module, block, func_ctx, level.next())?;
                 writeln!(self.out, "{level}}}")?
             }
             // TODO: copy-paste from glsl-out
@@ -2021,20 +2202,14 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 writeln!(self.out, ") {{")?;
 
                 let l2 = level.next();
-                for sta in accept {
-                    // Increase indentation to help with readability
-                    self.write_stmt(module, sta, func_ctx, l2)?;
-                }
+                self.write_block(module, accept, func_ctx, l2)?;
 
                 // If there are no statements in the reject block we skip writing it
                 // This is only for readability
                 if !reject.is_empty() {
                     writeln!(self.out, "{level}}} else {{")?;
 
-                    for sta in reject {
-                        // Increase indentation to help with readability
-                        self.write_stmt(module, sta, func_ctx, l2)?;
-                    }
+                    self.write_block(module, reject, func_ctx, l2)?;
                 }
 
                 writeln!(self.out, "{level}}}")?
@@ -2336,9 +2511,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 if let Some(gate_name) = gate_name {
                     writeln!(self.out, "{l2}if (!{gate_name}) {{")?;
                     let l3 = l2.next();
-                    for sta in continuing.iter() {
-                        self.write_stmt(module, sta, func_ctx, l3)?;
-                    }
+                    self.write_block(module, continuing, func_ctx, l3)?;
                     if let Some(condition) = break_if {
                         write!(self.out, "{l3}if (")?;
                         self.write_expr(module, condition, func_ctx)?;
@@ -2350,9 +2523,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     writeln!(self.out, "{l2}{gate_name} = false;")?;
                 }
 
-                for sta in body.iter() {
-                    self.write_stmt(module, sta, func_ctx, l2)?;
-                }
+                self.write_block(module, body, func_ctx, l2)?;
 
                 writeln!(self.out, "{level}}}")?;
                 self.continue_ctx.exit_loop();
@@ -3046,6 +3217,22 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                         return Ok(());
                     }
 
+                    if let Some(info) = self.descriptor_heap_array_info(module, func_ctx, base) {
+                        let non_uniform =
+                            func_ctx.info[index].uniformity.non_uniform_result.is_some();
+                        write!(self.out, "{}(", info.load_function_name)?;
+                        if non_uniform {
+                            write!(self.out, "NonUniformResourceIndex(")?;
+                        }
+                        write!(self.out, "{} + ", info.base_index_name)?;
+                        self.write_expr(module, index, func_ctx)?;
+                        if non_uniform {
+                            write!(self.out, ")")?;
+                        }
+                        write!(self.out, ")")?;
+                        return Ok(());
+                    }
+
                     let resolved = func_ctx.resolve_type(base, &module.types);
 
                     let (indexing_binding_array, non_uniform_qualifier) = match *resolved {
@@ -3200,6 +3387,15 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                         }
                     }
 
+                    if let Some(info) = self.descriptor_heap_array_info(module, func_ctx, base) {
+                        write!(
+                            self.out,
+                            "{}({} + {index})",
+                            info.load_function_name, info.base_index_name
+                        )?;
+                        return Ok(());
+                    }
+
                     let array_sampler_info = self.sampler_binding_array_info_from_expression(
                         module, func_ctx, base, resolved,
                     );
@@ -4361,6 +4557,34 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
 
     /// Find the [`BindingArraySamplerInfo`] from an expression so that such an access
     /// can be generated later.
+    /// Returns how to access the elements of `base`, if it is a binding array
+    /// accessed through a descriptor heap.
+    fn descriptor_heap_array_info(
+        &self,
+        module: &Module,
+        func_ctx: &back::FunctionCtx<'_>,
+        base: Handle<crate::Expression>,
+    ) -> Option<DescriptorHeapArrayInfo> {
+        let crate::Expression::GlobalVariable(handle) = func_ctx.expressions[base] else {
+            return None;
+        };
+        let variable = &module.global_variables[handle];
+        let TypeInner::BindingArray { base: ty, .. } = module.types[variable.ty].inner else {
+            return None;
+        };
+        if !self
+            .descriptor_heap_layout
+            .contains_key(variable.binding.as_ref()?)
+        {
+            return None;
+        }
+
+        Some(DescriptorHeapArrayInfo {
+            load_function_name: self.wrapped.descriptor_heap_loads[&ty].clone(),
+            base_index_name: self.names[&NameKey::GlobalVariable(handle)].clone(),
+        })
+    }
+
     fn sampler_binding_array_info_from_expression(
         &mut self,
         module: &Module,
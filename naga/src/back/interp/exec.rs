@@ -0,0 +1,969 @@
+/*!
+Execution of statements and expressions.
+
+Each invocation keeps an explicit call stack of [`Frame`]s, each holding a
+stack of [`Cursor`]s into the blocks being executed, rather than recursing
+on the Rust stack. That lets an invocation stop at a barrier and resume later,
+after the rest of its workgroup has caught up.
+*/
+
+use alloc::{borrow::Cow, vec, vec::Vec};
+
+use super::memory::{self, Memory, Pointer, Region};
+use super::value::{self, Value};
+use super::{Buffer, Error, Interpreter};
+use crate::arena::Handle;
+use crate::proc::BoundsCheckPolicy;
+use crate::valid::FunctionInfo;
+use crate::{
+    AddressSpace, AtomicFunction, BinaryOperator, Binding, Block, BuiltIn, EntryPoint, Expression,
+    Function, Literal, Module, Statement, SwitchCase, SwitchValue, TypeInner,
+};
+
+/// Evaluate an expression in [`Module::global_expressions`], given the values
+/// of the expressions before it.
+///
+/// Returns `None` for expressions that depend on overrides.
+pub(super) fn eval_global_expression(
+    module: &Module,
+    previous: &[Option<Value>],
+    expression: &Expression,
+) -> Option<Value> {
+    let operand = |handle: Handle<Expression>| previous[handle.index()].clone();
+    match *expression {
+        Expression::Literal(literal) => Some(Value::Scalar(literal)),
+        Expression::Constant(constant) => operand(module.constants[constant].init),
+        Expression::ZeroValue(ty) => value::zero(&module.types, &module.types[ty].inner).ok(),
+        Expression::Compose { ty, ref components } => components
+            .iter()
+            .map(|&component| operand(component))
+            .collect::<Option<Vec<_>>>()
+            .map(|components| compose(&module.types[ty].inner, components)),
+        Expression::Splat { size, value } => {
+            operand(value).map(|value| Value::Composite(vec![value; size as usize]))
+        }
+        _ => None,
+    }
+}
+
+/// Build a value of type `ty` from `components`.
+///
+/// Vectors can be composed from smaller vectors, so their components are
+/// flattened.
+fn compose(ty: &TypeInner, components: Vec<Value>) -> Value {
+    match *ty {
+        TypeInner::Vector { .. } => Value::Composite(
+            components
+                .into_iter()
+                .flat_map(|component| match component {
+                    Value::Composite(components) => components,
+                    scalar => vec![scalar],
+                })
+                .collect(),
+        ),
+        _ => Value::Composite(components),
+    }
+}
+
+/// Apply `policy` to an `index` into something with `length` elements.
+///
+/// Returns `None` if the access should read zero and skip writes.
+fn restrict_index(
+    index: i64,
+    length: u32,
+    policy: BoundsCheckPolicy,
+) -> Result<Option<u32>, Error> {
+    if (0..i64::from(length)).contains(&index) {
+        return Ok(Some(index as u32));
+    }
+    match policy {
+        // Like the code the other backends generate, treat the index as
+        // unsigned, so negative indices select the last element.
+        BoundsCheckPolicy::Restrict if length > 0 => Ok(Some((index as u32).min(length - 1))),
+        BoundsCheckPolicy::Restrict | BoundsCheckPolicy::ReadZeroSkipWrite => Ok(None),
+        BoundsCheckPolicy::Unchecked => Err(Error::OutOfBounds { index, length }),
+    }
+}
+
+/// A statement list being executed, and what to do when it runs out.
+#[derive(Clone, Copy)]
+struct Cursor<'a> {
+    block: &'a Block,
+    /// The index of the next statement to execute in `block`.
+    next: usize,
+    kind: CursorKind<'a>,
+}
+
+#[derive(Clone, Copy)]
+enum CursorKind<'a> {
+    /// The body of a [`Block`] statement, an [`If`] branch, or a function.
+    ///
+    /// [`Block`]: Statement::Block
+    /// [`If`]: Statement::If
+    Plain,
+    /// The body of `cases[index]` of a [`Switch`] statement.
+    ///
+    /// [`Switch`]: Statement::Switch
+    Switch {
+        cases: &'a [SwitchCase],
+        index: usize,
+    },
+    /// The body or continuing block of a [`Loop`] statement.
+    ///
+    /// [`Loop`]: Statement::Loop
+    Loop {
+        body: &'a Block,
+        continuing: &'a Block,
+        break_if: Option<Handle<Expression>>,
+        in_continuing: bool,
+    },
+}
+
+impl<'a> Cursor<'a> {
+    const fn new(block: &'a Block, kind: CursorKind<'a>) -> Self {
+        Self {
+            block,
+            next: 0,
+            kind,
+        }
+    }
+}
+
+/// A function call in progress.
+struct Frame<'a> {
+    function: &'a Function,
+    info: &'a FunctionInfo,
+    arguments: Vec<Value>,
+    /// Values of the function's expressions, indexed by handle.
+    values: Vec<Option<Value>>,
+    blocks: Vec<Cursor<'a>>,
+    /// The [`CallResult`] expression in the caller that receives the value
+    /// this call returns.
+    ///
+    /// [`CallResult`]: Expression::CallResult
+    result: Option<Handle<Expression>>,
+    /// The index of this frame in the call stack.
+    depth: usize,
+}
+
+/// The state of one invocation of the entry point.
+struct Invocation<'a> {
+    frames: Vec<Frame<'a>>,
+    /// Private variables, indexed by global variable handle.
+    private: Vec<Vec<u8>>,
+    /// Local variables of each frame in `frames`.
+    locals: Vec<Vec<Vec<u8>>>,
+    /// Whether the [`WorkGroupUniformLoad`] being executed has passed its
+    /// first barrier.
+    ///
+    /// [`WorkGroupUniformLoad`]: Statement::WorkGroupUniformLoad
+    uniform_load_pending: bool,
+}
+
+/// Why an invocation stopped running.
+enum Status {
+    Barrier,
+    Done,
+}
+
+/// The builtin values identifying an invocation.
+struct InvocationIds {
+    workgroup_id: [u32; 3],
+    local_id: [u32; 3],
+    local_index: u32,
+}
+
+/// A dispatch of an entry point in progress.
+pub(super) struct Dispatch<'a> {
+    pub interpreter: &'a Interpreter<'a>,
+    pub entry_point: &'a EntryPoint,
+    pub entry_point_info: &'a FunctionInfo,
+    /// Indices into the dispatch's buffers of the buffer bound to each
+    /// global variable, indexed by handle.
+    pub buffer_indices: Vec<Option<usize>>,
+    pub workgroups: [u32; 3],
+    /// The number of statements executed so far.
+    pub steps: u64,
+}
+
+impl<'a> Dispatch<'a> {
+    const fn module(&self) -> &'a Module {
+        self.interpreter.module
+    }
+
+    /// Run all invocations of the workgroup `workgroup_id` to completion.
+    pub fn run_workgroup(
+        &mut self,
+        workgroup_id: [u32; 3],
+        buffers: &mut [Buffer<'_>],
+    ) -> Result<(), Error> {
+        let module = self.module();
+        let gctx = module.to_ctx();
+
+        // Workgroup variables are zero-initialized.
+        let mut workgroup: Vec<Vec<u8>> = module
+            .global_variables
+            .iter()
+            .map(|(_, var)| match var.space {
+                AddressSpace::WorkGroup => vec![0; module.types[var.ty].inner.size(gctx) as usize],
+                _ => Vec::new(),
+            })
+            .collect();
+
+        let [size_x, size_y, size_z] = self.entry_point.workgroup_size;
+        let mut invocations = Vec::new();
+        for z in 0..size_z {
+            for y in 0..size_y {
+                for x in 0..size_x {
+                    let ids = InvocationIds {
+                        workgroup_id,
+                        local_id: [x, y, z],
+                        local_index: invocations.len() as u32,
+                    };
+                    invocations.push(self.start_invocation(&ids, buffers, &mut workgroup)?);
+                }
+            }
+        }
+
+        // Run each invocation until it reaches a barrier, and repeat until
+        // they have all finished.
+        let mut running = vec![true; invocations.len()];
+        while running.contains(&true) {
+            for (invocation, running) in invocations.iter_mut().zip(running.iter_mut()) {
+                if !*running {
+                    continue;
+                }
+                match self.run(invocation, buffers, &mut workgroup)? {
+                    Status::Barrier => {}
+                    Status::Done => *running = false,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn start_invocation(
+        &self,
+        ids: &InvocationIds,
+        buffers: &mut [Buffer<'_>],
+        workgroup: &mut [Vec<u8>],
+    ) -> Result<Invocation<'a>, Error> {
+        let module = self.module();
+        let gctx = module.to_ctx();
+
+        let mut private = Vec::with_capacity(module.global_variables.len());
+        for (_, var) in module.global_variables.iter() {
+            if var.space != AddressSpace::Private {
+                private.push(Vec::new());
+                continue;
+            }
+            let inner = &module.types[var.ty].inner;
+            let mut bytes = vec![0; inner.size(gctx) as usize];
+            if let Some(init) = var.init {
+                let value = self.interpreter.global_expression(init)?;
+                memory::write(&module.types, &mut bytes, inner, &value)?;
+            }
+            private.push(bytes);
+        }
+
+        let function = &self.entry_point.function;
+        let arguments = function
+            .arguments
+            .iter()
+            .map(|argument| self.entry_point_argument(argument.ty, argument.binding.as_ref(), ids))
+            .collect::<Result<_, _>>()?;
+
+        let mut invocation = Invocation {
+            frames: Vec::new(),
+            private,
+            locals: Vec::new(),
+            uniform_load_pending: false,
+        };
+        let mut memory = Memory {
+            buffers,
+            workgroup,
+            private: &mut invocation.private,
+            locals: &mut invocation.locals,
+        };
+        self.push_frame(
+            &mut invocation.frames,
+            &mut memory,
+            function,
+            self.entry_point_info,
+            arguments,
+            None,
+        )?;
+        Ok(invocation)
+    }
+
+    fn entry_point_argument(
+        &self,
+        ty: Handle<crate::Type>,
+        binding: Option<&Binding>,
+        ids: &InvocationIds,
+    ) -> Result<Value, Error> {
+        let module = self.module();
+        match (binding, &module.types[ty].inner) {
+            (Some(&Binding::BuiltIn(built_in)), _) => self.built_in(built_in, ids),
+            (None, &TypeInner::Struct { ref members, .. }) => members
+                .iter()
+                .map(|member| self.entry_point_argument(member.ty, member.binding.as_ref(), ids))
+                .collect::<Result<_, _>>()
+                .map(Value::Composite),
+            (binding, _) => Err(Error::unsupported("argument binding", binding)),
+        }
+    }
+
+    fn built_in(&self, built_in: BuiltIn, ids: &InvocationIds) -> Result<Value, Error> {
+        let uvec3 = |v: [u32; 3]| {
+            Value::Composite(v.iter().map(|&c| Value::Scalar(Literal::U32(c))).collect())
+        };
+        let size = self.entry_point.workgroup_size;
+        Ok(match built_in {
+            BuiltIn::GlobalInvocationId => {
+                uvec3([0, 1, 2].map(|i| ids.workgroup_id[i] * size[i] + ids.local_id[i]))
+            }
+            BuiltIn::LocalInvocationId => uvec3(ids.local_id),
+            BuiltIn::LocalInvocationIndex => Value::Scalar(Literal::U32(ids.local_index)),
+            BuiltIn::WorkGroupId => uvec3(ids.workgroup_id),
+            BuiltIn::WorkGroupSize => uvec3(size),
+            BuiltIn::NumWorkGroups => uvec3(self.workgroups),
+            _ => return Err(Error::unsupported("built-in", built_in)),
+        })
+    }
+
+    /// Call `function`, pushing a new frame onto `frames`.
+    fn push_frame(
+        &self,
+        frames: &mut Vec<Frame<'a>>,
+        memory: &mut Memory,
+        function: &'a Function,
+        info: &'a FunctionInfo,
+        arguments: Vec<Value>,
+        result: Option<Handle<Expression>>,
+    ) -> Result<(), Error> {
+        let module = self.module();
+        let gctx = module.to_ctx();
+        let depth = frames.len();
+
+        // Local variables without an initializer are zero-initialized, as
+        // WGSL requires.
+        memory.locals.push(
+            function
+                .local_variables
+                .iter()
+                .map(|(_, local)| vec![0; module.types[local.ty].inner.size(gctx) as usize])
+                .collect(),
+        );
+
+        let mut frame = Frame {
+            function,
+            info,
+            arguments,
+            values: vec![None; function.expressions.len()],
+            blocks: vec![Cursor::new(&function.body, CursorKind::Plain)],
+            result,
+            depth,
+        };
+        for (handle, local) in function.local_variables.iter() {
+            if let Some(init) = local.init {
+                let value = self.eval(&mut frame, memory, init)?;
+                let pointer = Pointer {
+                    region: Region::Local {
+                        frame: depth,
+                        variable: handle,
+                    },
+                    offset: 0,
+                    in_bounds: true,
+                };
+                memory.store(gctx, pointer, &module.types[local.ty].inner, &value)?;
+            }
+        }
+        frames.push(frame);
+        Ok(())
+    }
+
+    /// Return from the innermost call in `frames`, passing `value` to the
+    /// caller.
+    fn pop_frame(frames: &mut Vec<Frame<'a>>, memory: &mut Memory, value: Option<Value>) {
+        let Some(frame) = frames.pop() else {
+            return;
+        };
+        memory.locals.pop();
+        if let (Some(caller), Some(result)) = (frames.last_mut(), frame.result) {
+            caller.values[result.index()] = value;
+        }
+    }
+
+    fn count_step(&mut self) -> Result<(), Error> {
+        self.steps += 1;
+        match self.interpreter.options.step_limit {
+            Some(limit) if self.steps > limit => Err(Error::StepLimitExceeded(limit)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Run `invocation` until it reaches a barrier or finishes.
+    fn run(
+        &mut self,
+        invocation: &mut Invocation<'a>,
+        buffers: &mut [Buffer<'_>],
+        workgroup: &mut [Vec<u8>],
+    ) -> Result<Status, Error> {
+        let module = self.module();
+        let gctx = module.to_ctx();
+        let Invocation {
+            ref mut frames,
+            ref mut private,
+            ref mut locals,
+            ref mut uniform_load_pending,
+        } = *invocation;
+        let mut memory = Memory {
+            buffers,
+            workgroup,
+            private,
+            locals,
+        };
+
+        loop {
+            let Some(frame) = frames.last_mut() else {
+                return Ok(Status::Done);
+            };
+            let Some(cursor) = frame.blocks.last_mut() else {
+                // Reaching the end of a function returns nothing.
+                Self::pop_frame(frames, &mut memory, None);
+                continue;
+            };
+            let block = cursor.block;
+            let Some(statement) = block.get(cursor.next) else {
+                self.end_block(frame, &memory)?;
+                continue;
+            };
+            cursor.next += 1;
+            self.count_step()?;
+
+            match *statement {
+                Statement::Emit(ref range) => {
+                    for handle in range.clone() {
+                        let value = self.compute(frame, &memory, handle)?;
+                        frame.values[handle.index()] = Some(value);
+                    }
+                }
+                Statement::Block(ref block) => {
+                    frame.blocks.push(Cursor::new(block, CursorKind::Plain));
+                }
+                Statement::If {
+                    condition,
+                    ref accept,
+                    ref reject,
+                } => {
+                    let block = if self.eval(frame, &memory, condition)?.bool()? {
+                        accept
+                    } else {
+                        reject
+                    };
+                    frame.blocks.push(Cursor::new(block, CursorKind::Plain));
+                }
+                Statement::Switch {
+                    selector,
+                    ref cases,
+                } => {
+                    let selector = self.eval(frame, &memory, selector)?.scalar()?;
+                    let index = cases
+                        .iter()
+                        .position(|case| match (case.value, selector) {
+                            (SwitchValue::I32(value), Literal::I32(selector)) => value == selector,
+                            (SwitchValue::U32(value), Literal::U32(selector)) => value == selector,
+                            _ => false,
+                        })
+                        .or_else(|| {
+                            cases
+                                .iter()
+                                .position(|case| case.value == SwitchValue::Default)
+                        })
+                        .ok_or_else(|| Error::unsupported("switch selector", selector))?;
+                    frame.blocks.push(Cursor::new(
+                        &cases[index].body,
+                        CursorKind::Switch { cases, index },
+                    ));
+                }
+                Statement::Loop {
+                    ref body,
+                    ref continuing,
+                    break_if,
+                } => {
+                    frame.blocks.push(Cursor::new(
+                        body,
+                        CursorKind::Loop {
+                            body,
+                            continuing,
+                            break_if,
+                            in_continuing: false,
+                        },
+                    ));
+                }
+                Statement::Break => loop {
+                    match frame.blocks.pop() {
+                        Some(Cursor {
+                            kind: CursorKind::Switch { .. } | CursorKind::Loop { .. },
+                            ..
+                        }) => break,
+                        Some(_) => {}
+                        None => return Err(Error::unsupported("statement", statement)),
+                    }
+                },
+                Statement::Continue => loop {
+                    match frame.blocks.last_mut() {
+                        Some(&mut Cursor {
+                            ref mut block,
+                            ref mut next,
+                            kind:
+                                CursorKind::Loop {
+                                    continuing,
+                                    ref mut in_continuing,
+                                    ..
+                                },
+                        }) => {
+                            *block = continuing;
+                            *next = 0;
+                            *in_continuing = true;
+                            break;
+                        }
+                        Some(_) => {
+                            frame.blocks.pop();
+                        }
+                        None => return Err(Error::unsupported("statement", statement)),
+                    }
+                },
+                Statement::Return { value } => {
+                    let value = value
+                        .map(|value| self.eval(frame, &memory, value))
+                        .transpose()?;
+                    Self::pop_frame(frames, &mut memory, value);
+                }
+                Statement::ControlBarrier(_) => return Ok(Status::Barrier),
+                // Invocations run one at a time, so memory is always coherent.
+                Statement::MemoryBarrier(_) => {}
+                Statement::Store { pointer, value } => {
+                    let target = self.eval(frame, &memory, pointer)?.pointer()?;
+                    let value = self.eval(frame, &memory, value)?;
+                    let ty = self.pointee(frame, pointer)?;
+                    memory.store(gctx, target, &ty, &value)?;
+                }
+                Statement::Atomic {
+                    pointer,
+                    ref fun,
+                    value,
+                    result,
+                } => {
+                    let target = self.eval(frame, &memory, pointer)?.pointer()?;
+                    let operand = self.eval(frame, &memory, value)?.scalar()?;
+                    let ty = self.pointee(frame, pointer)?;
+                    let old = memory.load(gctx, target, &ty)?.scalar()?;
+
+                    let binary = |op| value::binary_scalar(op, old, operand);
+                    let mut exchanged = None;
+                    let new = match *fun {
+                        AtomicFunction::Add => binary(BinaryOperator::Add)?,
+                        AtomicFunction::Subtract => binary(BinaryOperator::Subtract)?,
+                        AtomicFunction::And => binary(BinaryOperator::And)?,
+                        AtomicFunction::ExclusiveOr => binary(BinaryOperator::ExclusiveOr)?,
+                        AtomicFunction::InclusiveOr => binary(BinaryOperator::InclusiveOr)?,
+                        AtomicFunction::Min => match binary(BinaryOperator::Less)? {
+                            Literal::Bool(true) => old,
+                            _ => operand,
+                        },
+                        AtomicFunction::Max => match binary(BinaryOperator::Greater)? {
+                            Literal::Bool(true) => old,
+                            _ => operand,
+                        },
+                        AtomicFunction::Exchange { compare: None } => operand,
+                        AtomicFunction::Exchange {
+                            compare: Some(compare),
+                        } => {
+                            let compare = self.eval(frame, &memory, compare)?.scalar()?;
+                            let equal = old == compare;
+                            exchanged = Some(equal);
+                            if equal {
+                                operand
+                            } else {
+                                old
+                            }
+                        }
+                    };
+                    memory.store(gctx, target, &ty, &Value::Scalar(new))?;
+
+                    if let Some(result) = result {
+                        let old = Value::Scalar(old);
+                        frame.values[result.index()] = Some(match exchanged {
+                            Some(exchanged) => {
+                                Value::Composite(vec![old, Value::Scalar(Literal::Bool(exchanged))])
+                            }
+                            None => old,
+                        });
+                    }
+                }
+                Statement::WorkGroupUniformLoad { pointer, result } => {
+                    // This has the same barriers as `workgroupUniformLoad` in
+                    // WGSL: one before the load, and one after.
+                    if !*uniform_load_pending {
+                        *uniform_load_pending = true;
+                        if let Some(cursor) = frame.blocks.last_mut() {
+                            cursor.next -= 1;
+                        }
+                        return Ok(Status::Barrier);
+                    }
+                    *uniform_load_pending = false;
+                    let source = self.eval(frame, &memory, pointer)?.pointer()?;
+                    let ty = frame.info[result].ty.inner_with(&module.types);
+                    let value = memory.load(gctx, source, ty)?;
+                    frame.values[result.index()] = Some(value);
+                    return Ok(Status::Barrier);
+                }
+                Statement::Call {
+                    function,
+                    ref arguments,
+                    result,
+                } => {
+                    let arguments = arguments
+                        .iter()
+                        .map(|&argument| self.eval(frame, &memory, argument))
+                        .collect::<Result<_, _>>()?;
+                    self.push_frame(
+                        frames,
+                        &mut memory,
+                        &module.functions[function],
+                        &self.interpreter.info[function],
+                        arguments,
+                        result,
+                    )?;
+                }
+                Statement::Kill
+                | Statement::ImageStore { .. }
+                | Statement::ImageAtomic { .. }
+                | Statement::RayQuery { .. }
+                | Statement::SubgroupBallot { .. }
+                | Statement::SubgroupGather { .. }
+                | Statement::SubgroupCollectiveOperation { .. } => {
+                    return Err(Error::unsupported("statement", statement))
+                }
+            }
+        }
+    }
+
+    /// Handle reaching the end of the innermost block of `frame`.
+    fn end_block(&self, frame: &mut Frame<'a>, memory: &Memory) -> Result<(), Error> {
+        let Some(&cursor) = frame.blocks.last() else {
+            return Ok(());
+        };
+        let next = match cursor.kind {
+            CursorKind::Plain => None,
+            CursorKind::Switch { cases, index } => cases
+                .get(index + 1)
+                .filter(|_| cases[index].fall_through)
+                .map(|case| {
+                    Cursor::new(
+                        &case.body,
+                        CursorKind::Switch {
+                            cases,
+                            index: index + 1,
+                        },
+                    )
+                }),
+            CursorKind::Loop {
+                body,
+                continuing,
+                break_if,
+                in_continuing: false,
+            } => Some(Cursor::new(
+                continuing,
+                CursorKind::Loop {
+                    body,
+                    continuing,
+                    break_if,
+                    in_continuing: true,
+                },
+            )),
+            CursorKind::Loop {
+                body,
+                continuing,
+                break_if,
+                in_continuing: true,
+            } => {
+                let exit = match break_if {
+                    Some(condition) => self.eval(frame, memory, condition)?.bool()?,
+                    None => false,
+                };
+                (!exit).then(|| {
+                    Cursor::new(
+                        body,
+                        CursorKind::Loop {
+                            body,
+                            continuing,
+                            break_if,
+                            in_continuing: false,
+                        },
+                    )
+                })
+            }
+        };
+
+        frame.blocks.pop();
+        frame.blocks.extend(next);
+        Ok(())
+    }
+
+    /// Return the type that the pointer `expression` points to.
+    fn pointee(
+        &self,
+        frame: &Frame<'a>,
+        expression: Handle<Expression>,
+    ) -> Result<Cow<'a, TypeInner>, Error> {
+        let types = &self.module().types;
+        memory::pointee(types, frame.info[expression].ty.inner_with(types))
+    }
+
+    /// Return the value of `handle`, computing it if it has not been emitted.
+    fn eval(
+        &self,
+        frame: &mut Frame<'a>,
+        memory: &Memory,
+        handle: Handle<Expression>,
+    ) -> Result<Value, Error> {
+        if let Some(ref value) = frame.values[handle.index()] {
+            return Ok(value.clone());
+        }
+        let value = self.compute(frame, memory, handle)?;
+        frame.values[handle.index()] = Some(value.clone());
+        Ok(value)
+    }
+
+    fn compute(
+        &self,
+        frame: &mut Frame<'a>,
+        memory: &Memory,
+        handle: Handle<Expression>,
+    ) -> Result<Value, Error> {
+        let module = self.module();
+        let types = &module.types;
+        let expression = &frame.function.expressions[handle];
+
+        Ok(match *expression {
+            Expression::Literal(literal) => Value::Scalar(literal),
+            Expression::Constant(constant) => self
+                .interpreter
+                .global_expression(module.constants[constant].init)?,
+            Expression::ZeroValue(ty) => value::zero(types, &types[ty].inner)?,
+            Expression::Compose { ty, ref components } => {
+                let components = components
+                    .iter()
+                    .map(|&component| self.eval(frame, memory, component))
+                    .collect::<Result<_, _>>()?;
+                compose(&types[ty].inner, components)
+            }
+            Expression::Access { base, index } => {
+                let index = self.eval(frame, memory, index)?.index()?;
+                self.access(frame, memory, handle, base, index)?
+            }
+            Expression::AccessIndex { base, index } => {
+                self.access(frame, memory, handle, base, index.into())?
+            }
+            Expression::Splat { size, value } => {
+                Value::Composite(vec![self.eval(frame, memory, value)?; size as usize])
+            }
+            Expression::Swizzle {
+                size,
+                vector,
+                pattern,
+            } => {
+                let vector = self.eval(frame, memory, vector)?;
+                let components = vector.components()?;
+                Value::Composite(
+                    pattern[..size as usize]
+                        .iter()
+                        .map(|component| components[component.index() as usize].clone())
+                        .collect(),
+                )
+            }
+            Expression::FunctionArgument(index) => frame.arguments[index as usize].clone(),
+            Expression::GlobalVariable(global) => {
+                let var = &module.global_variables[global];
+                let region = match var.space {
+                    AddressSpace::Uniform | AddressSpace::Storage { .. } => {
+                        match (self.buffer_indices[global.index()], var.binding) {
+                            (Some(index), _) => Region::Buffer(index),
+                            (None, Some(binding)) => return Err(Error::MissingBinding(binding)),
+                            (None, None) => return Err(Error::unsupported("global variable", var)),
+                        }
+                    }
+                    AddressSpace::WorkGroup => Region::Workgroup(global),
+                    AddressSpace::Private => Region::Private(global),
+                    space => return Err(Error::unsupported("address space", space)),
+                };
+                Value::Pointer(Pointer {
+                    region,
+                    offset: 0,
+                    in_bounds: true,
+                })
+            }
+            Expression::LocalVariable(variable) => Value::Pointer(Pointer {
+                region: Region::Local {
+                    frame: frame.depth,
+                    variable,
+                },
+                offset: 0,
+                in_bounds: true,
+            }),
+            Expression::Load { pointer } => {
+                let source = self.eval(frame, memory, pointer)?.pointer()?;
+                memory.load(
+                    module.to_ctx(),
+                    source,
+                    frame.info[handle].ty.inner_with(types),
+                )?
+            }
+            Expression::Unary { op, expr } => value::unary(op, &self.eval(frame, memory, expr)?)?,
+            Expression::Binary { op, left, right } => {
+                let left_value = self.eval(frame, memory, left)?;
+                let right_value = self.eval(frame, memory, right)?;
+                value::binary(
+                    op,
+                    &left_value,
+                    frame.info[left].ty.inner_with(types),
+                    &right_value,
+                    frame.info[right].ty.inner_with(types),
+                )?
+            }
+            Expression::Select {
+                condition,
+                accept,
+                reject,
+            } => value::select(
+                &self.eval(frame, memory, condition)?,
+                &self.eval(frame, memory, accept)?,
+                &self.eval(frame, memory, reject)?,
+            )?,
+            Expression::Relational { fun, argument } => {
+                value::relational(fun, &self.eval(frame, memory, argument)?)?
+            }
+            Expression::Math {
+                fun,
+                arg,
+                arg1,
+                arg2,
+                arg3,
+            } => {
+                let args = [Some(arg), arg1, arg2, arg3]
+                    .into_iter()
+                    .flatten()
+                    .map(|arg| self.eval(frame, memory, arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                value::math(fun, &args)?
+            }
+            Expression::As {
+                expr,
+                kind,
+                convert,
+            } => value::cast(&self.eval(frame, memory, expr)?, kind, convert)?,
+            Expression::ArrayLength(array) => {
+                let pointer = self.eval(frame, memory, array)?.pointer()?;
+                let pointee = self.pointee(frame, array)?;
+                let elements = memory::elements(types, &pointee)?;
+                // Runtime-sized arrays extend to the end of their buffer.
+                let size = memory.region(pointer.region).len() as u32;
+                let length = size.saturating_sub(pointer.offset) / elements.stride;
+                Value::Scalar(Literal::U32(length))
+            }
+            Expression::Override(_)
+            | Expression::ImageSample { .. }
+            | Expression::ImageLoad { .. }
+            | Expression::ImageQuery { .. }
+            | Expression::Derivative { .. }
+            | Expression::CallResult(_)
+            | Expression::AtomicResult { .. }
+            | Expression::WorkGroupUniformLoadResult { .. }
+            | Expression::RayQueryVertexPositions { .. }
+            | Expression::RayQueryProceedResult
+            | Expression::RayQueryGetIntersection { .. }
+            | Expression::SubgroupBallotResult
+            | Expression::SubgroupOperationResult { .. } => {
+                // Result expressions get their values from the statements
+                // that produce them, so reaching here means that statement
+                // did not run.
+                return Err(Error::unsupported("expression", expression));
+            }
+        })
+    }
+
+    /// Evaluate an [`Access`] or [`AccessIndex`] expression `handle`, applying
+    /// `index` to `base`.
+    ///
+    /// [`Access`]: Expression::Access
+    /// [`AccessIndex`]: Expression::AccessIndex
+    fn access(
+        &self,
+        frame: &mut Frame<'a>,
+        memory: &Memory,
+        handle: Handle<Expression>,
+        base: Handle<Expression>,
+        index: i64,
+    ) -> Result<Value, Error> {
+        let types = &self.module().types;
+        let base_value = self.eval(frame, memory, base)?;
+        let base_ty = frame.info[base].ty.inner_with(types);
+        let policy = self
+            .interpreter
+            .options
+            .bounds_check_policies
+            .choose_policy(base, types, frame.info);
+
+        match base_value {
+            Value::Pointer(pointer) => {
+                let pointee = memory::pointee(types, base_ty)?;
+                if let TypeInner::Struct { ref members, .. } = *pointee {
+                    let member = members
+                        .get(index as usize)
+                        .ok_or_else(|| Error::unsupported("struct member", index))?;
+                    return Ok(Value::Pointer(Pointer {
+                        offset: pointer.offset + member.offset,
+                        ..pointer
+                    }));
+                }
+                let elements = memory::elements(types, &pointee)?;
+                let length = match elements.length {
+                    Some(length) => length,
+                    None => {
+                        let size = memory.region(pointer.region).len() as u32;
+                        size.saturating_sub(pointer.offset) / elements.stride
+                    }
+                };
+                Ok(Value::Pointer(
+                    match restrict_index(index, length, policy)? {
+                        Some(index) => Pointer {
+                            offset: pointer.offset + index * elements.stride,
+                            ..pointer
+                        },
+                        None => Pointer {
+                            in_bounds: false,
+                            ..pointer
+                        },
+                    },
+                ))
+            }
+            Value::Composite(mut components) => {
+                let length = components.len() as u32;
+                let index = if let TypeInner::Struct { .. } = *base_ty {
+                    Some(index as u32).filter(|&index| index < length)
+                } else {
+                    restrict_index(index, length, policy)?
+                };
+                match index {
+                    Some(index) => Ok(components.swap_remove(index as usize)),
+                    None => value::zero(types, frame.info[handle].ty.inner_with(types)),
+                }
+            }
+            Value::Scalar(_) => Err(Error::unsupported("indexed value", base_value)),
+        }
+    }
+}
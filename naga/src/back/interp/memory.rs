@@ -0,0 +1,303 @@
+/*!
+Memory that pointers refer to, and the byte encoding of values stored in it.
+
+Every variable is a byte array laid out like the variable's type, so the
+same pointer arithmetic works for host buffers and for the interpreter's own
+workgroup, private and function variables.
+*/
+
+use alloc::{borrow::Cow, vec::Vec};
+use core::ops::Range;
+
+use half::f16;
+
+use super::{value::Value, Buffer, Error};
+use crate::proc::{Alignment, GlobalCtx};
+use crate::{
+    arena::Handle, ArraySize, GlobalVariable, Literal, LocalVariable, Scalar, ScalarKind, Type,
+    TypeInner, UniqueArena,
+};
+
+/// The variable a [`Pointer`] points into.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Region {
+    /// The [`Buffer`] at this index in the slice passed to `dispatch`.
+    Buffer(usize),
+    Workgroup(Handle<GlobalVariable>),
+    Private(Handle<GlobalVariable>),
+    /// A local variable of the function at `frame` in the call stack.
+    Local {
+        frame: usize,
+        variable: Handle<LocalVariable>,
+    },
+}
+
+/// A pointer value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Pointer {
+    pub region: Region,
+    /// Byte offset of the pointee in `region`.
+    pub offset: u32,
+    /// False if an index applied to get this pointer was out of bounds,
+    /// under the [`ReadZeroSkipWrite`] policy.
+    ///
+    /// Loads through such pointers produce zero and stores are skipped.
+    ///
+    /// [`ReadZeroSkipWrite`]: crate::proc::BoundsCheckPolicy::ReadZeroSkipWrite
+    pub in_bounds: bool,
+}
+
+/// All the memory an invocation can access.
+pub(super) struct Memory<'m, 'b> {
+    pub buffers: &'m mut [Buffer<'b>],
+    /// Workgroup variables, indexed by global variable handle.
+    pub workgroup: &'m mut [Vec<u8>],
+    /// Private variables, indexed by global variable handle.
+    pub private: &'m mut [Vec<u8>],
+    /// Local variables of each frame in the call stack, indexed by handle.
+    pub locals: &'m mut Vec<Vec<Vec<u8>>>,
+}
+
+impl Memory<'_, '_> {
+    pub fn region(&self, region: Region) -> &[u8] {
+        match region {
+            Region::Buffer(index) => self.buffers[index].data,
+            Region::Workgroup(handle) => &self.workgroup[handle.index()],
+            Region::Private(handle) => &self.private[handle.index()],
+            Region::Local { frame, variable } => &self.locals[frame][variable.index()],
+        }
+    }
+
+    fn region_mut(&mut self, region: Region) -> &mut [u8] {
+        match region {
+            Region::Buffer(index) => self.buffers[index].data,
+            Region::Workgroup(handle) => &mut self.workgroup[handle.index()],
+            Region::Private(handle) => &mut self.private[handle.index()],
+            Region::Local { frame, variable } => &mut self.locals[frame][variable.index()],
+        }
+    }
+
+    /// Read the value of type `ty` that `pointer` points to.
+    pub fn load(&self, gctx: GlobalCtx, pointer: Pointer, ty: &TypeInner) -> Result<Value, Error> {
+        if !pointer.in_bounds {
+            return super::value::zero(gctx.types, ty);
+        }
+        let bytes = self.region(pointer.region);
+        let range = access_range(bytes.len(), pointer.offset, ty.size(gctx))?;
+        read(gctx.types, &bytes[range], ty)
+    }
+
+    /// Write `value`, of type `ty`, where `pointer` points to.
+    pub fn store(
+        &mut self,
+        gctx: GlobalCtx,
+        pointer: Pointer,
+        ty: &TypeInner,
+        value: &Value,
+    ) -> Result<(), Error> {
+        if !pointer.in_bounds {
+            return Ok(());
+        }
+        let bytes = self.region_mut(pointer.region);
+        let range = access_range(bytes.len(), pointer.offset, ty.size(gctx))?;
+        write(gctx.types, &mut bytes[range], ty, value)
+    }
+}
+
+/// Return the range of a region of `region_size` bytes covered by an access
+/// of `size` bytes at `offset`.
+const fn access_range(region_size: usize, offset: u32, size: u32) -> Result<Range<usize>, Error> {
+    let start = offset as usize;
+    let end = start + size as usize;
+    if end > region_size {
+        return Err(Error::OutOfMemoryBounds {
+            offset,
+            size,
+            region_size: region_size as u32,
+        });
+    }
+    Ok(start..end)
+}
+
+/// Return the type a pointer of type `ty` points to.
+pub(super) fn pointee<'t>(
+    types: &'t UniqueArena<Type>,
+    ty: &'t TypeInner,
+) -> Result<Cow<'t, TypeInner>, Error> {
+    match *ty {
+        TypeInner::Pointer { base, .. } => Ok(Cow::Borrowed(&types[base].inner)),
+        TypeInner::ValuePointer {
+            size: Some(size),
+            scalar,
+            ..
+        } => Ok(Cow::Owned(TypeInner::Vector { size, scalar })),
+        TypeInner::ValuePointer {
+            size: None, scalar, ..
+        } => Ok(Cow::Owned(TypeInner::Scalar(scalar))),
+        _ => Err(Error::unsupported("pointer type", ty)),
+    }
+}
+
+/// How to find the elements of an indexable type in memory.
+pub(super) struct Elements<'t> {
+    /// The element type.
+    pub ty: Cow<'t, TypeInner>,
+    /// The distance in bytes between elements.
+    pub stride: u32,
+    /// The number of elements, or `None` for runtime-sized arrays.
+    pub length: Option<u32>,
+}
+
+/// Describe the elements of `ty`, which must be a vector, matrix or array.
+pub(super) fn elements<'t>(
+    types: &'t UniqueArena<Type>,
+    ty: &'t TypeInner,
+) -> Result<Elements<'t>, Error> {
+    Ok(match *ty {
+        TypeInner::Vector { size, scalar } => Elements {
+            ty: Cow::Owned(TypeInner::Scalar(scalar)),
+            stride: scalar.width.into(),
+            length: Some(size as u32),
+        },
+        TypeInner::Matrix {
+            columns,
+            rows,
+            scalar,
+        } => Elements {
+            ty: Cow::Owned(TypeInner::Vector { size: rows, scalar }),
+            stride: Alignment::from(rows) * u32::from(scalar.width),
+            length: Some(columns as u32),
+        },
+        TypeInner::Array { base, size, stride } => Elements {
+            ty: Cow::Borrowed(&types[base].inner),
+            stride,
+            length: match size {
+                ArraySize::Constant(length) => Some(length.get()),
+                ArraySize::Dynamic => None,
+                ArraySize::Pending(_) => return Err(Error::unsupported("array size", size)),
+            },
+        },
+        _ => return Err(Error::unsupported("indexed type", ty)),
+    })
+}
+
+/// Return the part of `bytes` starting at `offset`.
+///
+/// Callers check that the whole value fits before reading or writing it, so
+/// this only fails for types whose size does not cover their contents.
+fn tail(bytes: &[u8], offset: u32) -> Result<&[u8], Error> {
+    bytes
+        .get(offset as usize..)
+        .ok_or(Error::OutOfMemoryBounds {
+            offset,
+            size: 0,
+            region_size: bytes.len() as u32,
+        })
+}
+
+fn tail_mut(bytes: &mut [u8], offset: u32) -> Result<&mut [u8], Error> {
+    let region_size = bytes.len() as u32;
+    bytes
+        .get_mut(offset as usize..)
+        .ok_or(Error::OutOfMemoryBounds {
+            offset,
+            size: 0,
+            region_size,
+        })
+}
+
+fn read_scalar(bytes: &[u8], scalar: Scalar) -> Result<Literal, Error> {
+    let width = usize::from(scalar.width);
+    let mut raw = [0; 8];
+    raw[..width].copy_from_slice(&bytes[..width]);
+    let bits = u64::from_le_bytes(raw);
+    Ok(match (scalar.kind, scalar.width) {
+        (ScalarKind::Bool, _) => Literal::Bool(bits != 0),
+        (ScalarKind::Sint, 4) => Literal::I32(bits as u32 as i32),
+        (ScalarKind::Uint, 4) => Literal::U32(bits as u32),
+        (ScalarKind::Float, 4) => Literal::F32(f32::from_bits(bits as u32)),
+        (ScalarKind::Sint, 8) => Literal::I64(bits as i64),
+        (ScalarKind::Uint, 8) => Literal::U64(bits),
+        (ScalarKind::Float, 8) => Literal::F64(f64::from_bits(bits)),
+        (ScalarKind::Float, 2) => Literal::F16(f16::from_bits(bits as u16)),
+        _ => return Err(Error::unsupported("scalar type", scalar)),
+    })
+}
+
+fn write_scalar(bytes: &mut [u8], literal: Literal) -> Result<(), Error> {
+    let bits = match literal {
+        Literal::Bool(v) => u64::from(v),
+        Literal::I32(v) => u64::from(v as u32),
+        Literal::U32(v) => u64::from(v),
+        Literal::F32(v) => u64::from(v.to_bits()),
+        Literal::I64(v) => v as u64,
+        Literal::U64(v) => v,
+        Literal::F64(v) => v.to_bits(),
+        Literal::F16(v) => u64::from(v.to_bits()),
+        Literal::AbstractInt(_) | Literal::AbstractFloat(_) => {
+            return Err(Error::unsupported("stored value", literal))
+        }
+    };
+    let width = usize::from(literal.width());
+    bytes[..width].copy_from_slice(&bits.to_le_bytes()[..width]);
+    Ok(())
+}
+
+/// Decode a value of type `ty` from the start of `bytes`.
+pub(super) fn read(
+    types: &UniqueArena<Type>,
+    bytes: &[u8],
+    ty: &TypeInner,
+) -> Result<Value, Error> {
+    Ok(match *ty {
+        TypeInner::Scalar(scalar) | TypeInner::Atomic(scalar) => {
+            Value::Scalar(read_scalar(bytes, scalar)?)
+        }
+        TypeInner::Vector { .. } | TypeInner::Matrix { .. } | TypeInner::Array { .. } => {
+            let elements = elements(types, ty)?;
+            let length = elements
+                .length
+                .ok_or_else(|| Error::unsupported("loaded type", ty))?;
+            Value::Composite(
+                (0..length)
+                    .map(|i| read(types, tail(bytes, i * elements.stride)?, &elements.ty))
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        TypeInner::Struct { ref members, .. } => Value::Composite(
+            members
+                .iter()
+                .map(|member| read(types, tail(bytes, member.offset)?, &types[member.ty].inner))
+                .collect::<Result<_, _>>()?,
+        ),
+        _ => return Err(Error::unsupported("loaded type", ty)),
+    })
+}
+
+/// Encode `value`, of type `ty`, at the start of `bytes`.
+pub(super) fn write(
+    types: &UniqueArena<Type>,
+    bytes: &mut [u8],
+    ty: &TypeInner,
+    value: &Value,
+) -> Result<(), Error> {
+    match *ty {
+        TypeInner::Scalar(_) | TypeInner::Atomic(_) => write_scalar(bytes, value.scalar()?),
+        TypeInner::Vector { .. } | TypeInner::Matrix { .. } | TypeInner::Array { .. } => {
+            let elements = elements(types, ty)?;
+            for (i, component) in value.components()?.iter().enumerate() {
+                let bytes = tail_mut(bytes, i as u32 * elements.stride)?;
+                write(types, bytes, &elements.ty, component)?;
+            }
+            Ok(())
+        }
+        TypeInner::Struct { ref members, .. } => {
+            for (member, component) in members.iter().zip(value.components()?) {
+                let bytes = tail_mut(bytes, member.offset)?;
+                write(types, bytes, &types[member.ty].inner, component)?;
+            }
+            Ok(())
+        }
+        _ => Err(Error::unsupported("stored type", ty)),
+    }
+}
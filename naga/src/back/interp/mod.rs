@@ -0,0 +1,210 @@
+/*!
+A CPU interpreter for compute shaders.
+
+[`Interpreter`] executes a compute [`EntryPoint`] of a validated [`Module`]
+directly, reading and writing storage and uniform buffers supplied by the
+host. This makes it possible to test shader logic, or compare the output of
+other backends against a reference, on machines without a GPU.
+
+Invocations of a workgroup run one at a time, each until it finishes or
+reaches a barrier, so results do not depend on any scheduling the hardware
+might choose. Indexing follows the [`BoundsCheckPolicies`] given in
+[`Options`]: with the [`Unchecked`] policy, out-of-bounds accesses are
+reported as [`Error::OutOfBounds`] rather than being undefined.
+
+Images, samplers, ray queries, subgroup operations and pipeline-overridable
+constants are not supported. Use [`process_overrides`] to resolve overrides
+before interpreting a module that has them.
+
+[`EntryPoint`]: crate::EntryPoint
+[`Module`]: crate::Module
+[`Unchecked`]: crate::proc::BoundsCheckPolicy::Unchecked
+[`process_overrides`]: super::pipeline_constants::process_overrides
+*/
+
+mod exec;
+mod memory;
+mod value;
+
+use alloc::{format, string::String, vec::Vec};
+
+use thiserror::Error;
+
+use crate::arena::Handle;
+use crate::proc::BoundsCheckPolicies;
+use crate::valid::ModuleInfo;
+use crate::{AddressSpace, Module, ResourceBinding, ShaderStage};
+
+use value::Value;
+
+/// Errors that can occur while interpreting a module.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum Error {
+    #[error("entry point {0:?} not found")]
+    EntryPointNotFound(String),
+    #[error("entry point {0:?} is not a compute shader")]
+    NotCompute(String),
+    #[error("no buffer bound at group {}, binding {}", .0.group, .0.binding)]
+    MissingBinding(ResourceBinding),
+    #[error("index {index} is out of bounds for length {length}")]
+    OutOfBounds { index: i64, length: u32 },
+    #[error("{size}-byte access at offset {offset} is outside of a {region_size}-byte variable")]
+    OutOfMemoryBounds {
+        offset: u32,
+        size: u32,
+        region_size: u32,
+    },
+    #[error("execution exceeded the limit of {0} steps")]
+    StepLimitExceeded(u64),
+    #[error("unsupported {kind}: {value}")]
+    Unsupported {
+        /// What kind of unsupported thing this is: expression, statement, etc.
+        kind: &'static str,
+
+        /// The debug form of the Naga IR value that can't be interpreted.
+        value: String,
+    },
+}
+
+impl Error {
+    /// Produce an [`Unsupported`] error for `value`.
+    ///
+    /// [`Unsupported`]: Error::Unsupported
+    fn unsupported<T: core::fmt::Debug>(kind: &'static str, value: T) -> Error {
+        Error::Unsupported {
+            kind,
+            value: format!("{value:?}"),
+        }
+    }
+}
+
+/// Configuration options for the [`Interpreter`].
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// How out-of-bounds indices are handled.
+    pub bounds_check_policies: BoundsCheckPolicies,
+
+    /// Maximum number of statements to execute in a single dispatch, across
+    /// all invocations. `None` means no limit.
+    ///
+    /// This turns shaders that never terminate into an
+    /// [`Error::StepLimitExceeded`].
+    pub step_limit: Option<u64>,
+}
+
+/// A host buffer bound to a storage or uniform global variable.
+///
+/// The contents use the layout of the variable's type in the module, which
+/// for WGSL is the layout described in [WGSL §4.3.7, "Memory Layout"].
+///
+/// [WGSL §4.3.7, "Memory Layout"]: https://gpuweb.github.io/gpuweb/wgsl/#memory-layouts
+#[derive(Debug)]
+pub struct Buffer<'a> {
+    pub binding: ResourceBinding,
+    pub data: &'a mut [u8],
+}
+
+/// Executes compute entry points of a [`Module`] on the CPU.
+pub struct Interpreter<'a> {
+    module: &'a Module,
+    info: &'a ModuleInfo,
+    options: Options,
+    /// Values of [`Module::global_expressions`], indexed by handle.
+    global_expressions: Vec<Option<Value>>,
+}
+
+impl<'a> Interpreter<'a> {
+    /// Prepare to interpret `module`, which must have been validated to
+    /// produce `info`.
+    pub fn new(module: &'a Module, info: &'a ModuleInfo, options: Options) -> Self {
+        let mut global_expressions = Vec::with_capacity(module.global_expressions.len());
+        for (_, expression) in module.global_expressions.iter() {
+            // Overrides, and anything computed from them, only get a value
+            // once they are used.
+            let value = exec::eval_global_expression(module, &global_expressions, expression);
+            global_expressions.push(value);
+        }
+
+        Self {
+            module,
+            info,
+            options,
+            global_expressions,
+        }
+    }
+
+    /// Run the compute entry point named `entry_point` over a grid of
+    /// `workgroups`, with `buffers` bound to the module's storage and uniform
+    /// global variables.
+    ///
+    /// Every buffer global used by the entry point must have a buffer in
+    /// `buffers` with a matching [`ResourceBinding`].
+    pub fn dispatch(
+        &self,
+        entry_point: &str,
+        workgroups: [u32; 3],
+        buffers: &mut [Buffer<'_>],
+    ) -> Result<(), Error> {
+        let (index, ep) = self
+            .module
+            .entry_points
+            .iter()
+            .enumerate()
+            .find(|&(_, ep)| ep.name == entry_point)
+            .ok_or_else(|| Error::EntryPointNotFound(entry_point.into()))?;
+        if ep.stage != ShaderStage::Compute {
+            return Err(Error::NotCompute(entry_point.into()));
+        }
+        if ep.workgroup_size_overrides.is_some() {
+            return Err(Error::unsupported(
+                "workgroup size",
+                "overridable workgroup size",
+            ));
+        }
+        let ep_info = self.info.get_entry_point(index);
+
+        let mut buffer_indices = Vec::with_capacity(self.module.global_variables.len());
+        for (handle, var) in self.module.global_variables.iter() {
+            let buffer = match var.space {
+                AddressSpace::Uniform | AddressSpace::Storage { .. } => var.binding,
+                _ => None,
+            }
+            .filter(|_| !ep_info[handle].is_empty())
+            .map(|binding| {
+                buffers
+                    .iter()
+                    .position(|buffer| buffer.binding == binding)
+                    .ok_or(Error::MissingBinding(binding))
+            })
+            .transpose()?;
+            buffer_indices.push(buffer);
+        }
+
+        let mut dispatch = exec::Dispatch {
+            interpreter: self,
+            entry_point: ep,
+            entry_point_info: ep_info,
+            buffer_indices,
+            workgroups,
+            steps: 0,
+        };
+
+        for z in 0..workgroups[2] {
+            for y in 0..workgroups[1] {
+                for x in 0..workgroups[0] {
+                    dispatch.run_workgroup([x, y, z], buffers)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn global_expression(&self, handle: Handle<crate::Expression>) -> Result<Value, Error> {
+        self.global_expressions[handle.index()]
+            .clone()
+            .ok_or_else(|| {
+                Error::unsupported("expression", &self.module.global_expressions[handle])
+            })
+    }
+}
@@ -0,0 +1,975 @@
+/*!
+Values computed by the interpreter, and the operations on them.
+*/
+
+use alloc::{vec, vec::Vec};
+
+use half::f16;
+use num_traits::{real::Real, AsPrimitive};
+
+use super::{memory::Pointer, Error};
+use crate::{
+    ArraySize, BinaryOperator, Literal, MathFunction, RelationalFunction, Scalar, ScalarKind, Type,
+    TypeInner, UnaryOperator, UniqueArena,
+};
+
+/// A value produced by an expression.
+///
+/// Vectors hold one [`Scalar`] per component, matrices hold one vector per
+/// column, and arrays and structs hold one value per element or member.
+///
+/// Floating-point scalars may be NaN or infinite, unlike the [`Literal`]s
+/// found in a module.
+///
+/// [`Scalar`]: Value::Scalar
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Value {
+    Scalar(Literal),
+    Composite(Vec<Value>),
+    Pointer(Pointer),
+}
+
+impl Value {
+    pub fn scalar(&self) -> Result<Literal, Error> {
+        match *self {
+            Value::Scalar(literal) => Ok(literal),
+            _ => Err(Error::unsupported("scalar operand", self)),
+        }
+    }
+
+    pub fn components(&self) -> Result<&[Value], Error> {
+        match *self {
+            Value::Composite(ref components) => Ok(components),
+            _ => Err(Error::unsupported("composite operand", self)),
+        }
+    }
+
+    pub fn pointer(&self) -> Result<Pointer, Error> {
+        match *self {
+            Value::Pointer(pointer) => Ok(pointer),
+            _ => Err(Error::unsupported("pointer operand", self)),
+        }
+    }
+
+    pub fn bool(&self) -> Result<bool, Error> {
+        match self.scalar()? {
+            Literal::Bool(b) => Ok(b),
+            other => Err(Error::unsupported("boolean operand", other)),
+        }
+    }
+
+    /// Return this integer scalar as an `i64`, for use as an index.
+    pub fn index(&self) -> Result<i64, Error> {
+        match self.scalar()? {
+            Literal::I32(i) => Ok(i.into()),
+            Literal::U32(u) => Ok(u.into()),
+            Literal::I64(i) | Literal::AbstractInt(i) => Ok(i),
+            Literal::U64(u) => Ok(i64::try_from(u).unwrap_or(i64::MAX)),
+            other => Err(Error::unsupported("index", other)),
+        }
+    }
+}
+
+/// Return the zero value of `scalar`.
+pub(super) fn zero_scalar(scalar: Scalar) -> Result<Literal, Error> {
+    match scalar {
+        Scalar::F16 => Ok(Literal::F16(f16::ZERO)),
+        _ => Literal::zero(scalar).ok_or_else(|| Error::unsupported("scalar type", scalar)),
+    }
+}
+
+/// Return the zero value of a type.
+pub(super) fn zero(types: &UniqueArena<Type>, inner: &TypeInner) -> Result<Value, Error> {
+    Ok(match *inner {
+        TypeInner::Scalar(scalar) | TypeInner::Atomic(scalar) => {
+            Value::Scalar(zero_scalar(scalar)?)
+        }
+        TypeInner::Vector { size, scalar } => {
+            Value::Composite(vec![Value::Scalar(zero_scalar(scalar)?); size as usize])
+        }
+        TypeInner::Matrix {
+            columns,
+            rows,
+            scalar,
+        } => {
+            let column = Value::Composite(vec![Value::Scalar(zero_scalar(scalar)?); rows as usize]);
+            Value::Composite(vec![column; columns as usize])
+        }
+        TypeInner::Array {
+            base,
+            size: ArraySize::Constant(length),
+            ..
+        } => {
+            let element = zero(types, &types[base].inner)?;
+            Value::Composite(vec![element; length.get() as usize])
+        }
+        TypeInner::Struct { ref members, .. } => Value::Composite(
+            members
+                .iter()
+                .map(|member| zero(types, &types[member.ty].inner))
+                .collect::<Result<_, _>>()?,
+        ),
+        _ => return Err(Error::unsupported("type", inner)),
+    })
+}
+
+/// Apply `f` to corresponding scalars of `args`.
+///
+/// Composite arguments must all have the same number of components. Scalar
+/// arguments are used as-is for every component, so `mix(vec, vec, scalar)`
+/// and `vec * scalar` work as expected.
+pub(super) fn component_wise(
+    args: &[Value],
+    f: &mut impl FnMut(&[Literal]) -> Result<Literal, Error>,
+) -> Result<Value, Error> {
+    let Some(length) = args.iter().find_map(|arg| match *arg {
+        Value::Composite(ref components) => Some(components.len()),
+        _ => None,
+    }) else {
+        let scalars = args
+            .iter()
+            .map(Value::scalar)
+            .collect::<Result<Vec<_>, _>>()?;
+        return f(&scalars).map(Value::Scalar);
+    };
+
+    let components = (0..length)
+        .map(|i| {
+            let column = args
+                .iter()
+                .map(|arg| match *arg {
+                    Value::Composite(ref components) => components
+                        .get(i)
+                        .cloned()
+                        .ok_or_else(|| Error::unsupported("operand shape", arg)),
+                    ref scalar => Ok(scalar.clone()),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            component_wise(&column, f)
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Value::Composite(components))
+}
+
+/// Apply `f` to each scalar of `value`.
+fn map(
+    value: &Value,
+    mut f: impl FnMut(Literal) -> Result<Literal, Error>,
+) -> Result<Value, Error> {
+    component_wise(core::slice::from_ref(value), &mut |args| f(args[0]))
+}
+
+pub(super) fn unary(op: UnaryOperator, value: &Value) -> Result<Value, Error> {
+    map(value, |literal| {
+        Ok(match (op, literal) {
+            (UnaryOperator::Negate, Literal::F64(v)) => Literal::F64(-v),
+            (UnaryOperator::Negate, Literal::F32(v)) => Literal::F32(-v),
+            (UnaryOperator::Negate, Literal::F16(v)) => Literal::F16(-v),
+            (UnaryOperator::Negate, Literal::AbstractFloat(v)) => Literal::AbstractFloat(-v),
+            (UnaryOperator::Negate, Literal::I32(v)) => Literal::I32(v.wrapping_neg()),
+            (UnaryOperator::Negate, Literal::I64(v)) => Literal::I64(v.wrapping_neg()),
+            (UnaryOperator::Negate, Literal::AbstractInt(v)) => {
+                Literal::AbstractInt(v.wrapping_neg())
+            }
+            (UnaryOperator::LogicalNot, Literal::Bool(v)) => Literal::Bool(!v),
+            (UnaryOperator::BitwiseNot, Literal::I32(v)) => Literal::I32(!v),
+            (UnaryOperator::BitwiseNot, Literal::U32(v)) => Literal::U32(!v),
+            (UnaryOperator::BitwiseNot, Literal::I64(v)) => Literal::I64(!v),
+            (UnaryOperator::BitwiseNot, Literal::U64(v)) => Literal::U64(!v),
+            (UnaryOperator::BitwiseNot, Literal::AbstractInt(v)) => Literal::AbstractInt(!v),
+            _ => return Err(Error::unsupported("unary operation", (op, literal))),
+        })
+    })
+}
+
+/// Apply a binary operator to two scalars.
+///
+/// Integer arithmetic wraps on overflow. Following WGSL, integer division by
+/// zero produces the left operand, and the remainder of such a division is
+/// zero. Shift amounts are taken modulo the bit width of the left operand.
+pub(super) fn binary_scalar(
+    op: BinaryOperator,
+    left: Literal,
+    right: Literal,
+) -> Result<Literal, Error> {
+    use BinaryOperator as Bo;
+
+    macro_rules! compare {
+        ($a:expr, $b:expr) => {
+            Literal::Bool(match op {
+                Bo::Equal => $a == $b,
+                Bo::NotEqual => $a != $b,
+                Bo::Less => $a < $b,
+                Bo::LessEqual => $a <= $b,
+                Bo::Greater => $a > $b,
+                Bo::GreaterEqual => $a >= $b,
+                _ => return Err(Error::unsupported("binary operation", (op, left, right))),
+            })
+        };
+    }
+
+    macro_rules! integer {
+        ($variant:ident, $a:expr, $b:expr) => {{
+            let (a, b) = ($a, $b);
+            match op {
+                Bo::Add => Literal::$variant(a.wrapping_add(b)),
+                Bo::Subtract => Literal::$variant(a.wrapping_sub(b)),
+                Bo::Multiply => Literal::$variant(a.wrapping_mul(b)),
+                Bo::Divide => Literal::$variant(if b == 0 { a } else { a.wrapping_div(b) }),
+                Bo::Modulo => Literal::$variant(if b == 0 { 0 } else { a.wrapping_rem(b) }),
+                Bo::And => Literal::$variant(a & b),
+                Bo::ExclusiveOr => Literal::$variant(a ^ b),
+                Bo::InclusiveOr => Literal::$variant(a | b),
+                _ => compare!(a, b),
+            }
+        }};
+    }
+
+    macro_rules! float {
+        ($variant:ident, $a:expr, $b:expr) => {{
+            let (a, b) = ($a, $b);
+            match op {
+                Bo::Add => Literal::$variant(a + b),
+                Bo::Subtract => Literal::$variant(a - b),
+                Bo::Multiply => Literal::$variant(a * b),
+                Bo::Divide => Literal::$variant(a / b),
+                Bo::Modulo => Literal::$variant(a % b),
+                _ => compare!(a, b),
+            }
+        }};
+    }
+
+    macro_rules! shift {
+        ($variant:ident, $a:expr, $b:expr) => {
+            Literal::$variant(match op {
+                Bo::ShiftLeft => $a.wrapping_shl($b),
+                _ => $a.wrapping_shr($b),
+            })
+        };
+    }
+
+    Ok(match (left, right) {
+        (Literal::I32(a), Literal::U32(b)) if matches!(op, Bo::ShiftLeft | Bo::ShiftRight) => {
+            shift!(I32, a, b)
+        }
+        (Literal::U32(a), Literal::U32(b)) if matches!(op, Bo::ShiftLeft | Bo::ShiftRight) => {
+            shift!(U32, a, b)
+        }
+        (Literal::I64(a), Literal::U32(b)) if matches!(op, Bo::ShiftLeft | Bo::ShiftRight) => {
+            shift!(I64, a, b)
+        }
+        (Literal::U64(a), Literal::U32(b)) if matches!(op, Bo::ShiftLeft | Bo::ShiftRight) => {
+            shift!(U64, a, b)
+        }
+        (Literal::Bool(a), Literal::Bool(b)) => Literal::Bool(match op {
+            Bo::Equal => a == b,
+            Bo::NotEqual | Bo::ExclusiveOr => a != b,
+            Bo::And | Bo::LogicalAnd => a && b,
+            Bo::InclusiveOr | Bo::LogicalOr => a || b,
+            _ => return Err(Error::unsupported("binary operation", (op, left, right))),
+        }),
+        (Literal::I32(a), Literal::I32(b)) => integer!(I32, a, b),
+        (Literal::U32(a), Literal::U32(b)) => integer!(U32, a, b),
+        (Literal::I64(a), Literal::I64(b)) => integer!(I64, a, b),
+        (Literal::U64(a), Literal::U64(b)) => integer!(U64, a, b),
+        (Literal::AbstractInt(a), Literal::AbstractInt(b)) => integer!(AbstractInt, a, b),
+        (Literal::F16(a), Literal::F16(b)) => float!(F16, a, b),
+        (Literal::F32(a), Literal::F32(b)) => float!(F32, a, b),
+        (Literal::F64(a), Literal::F64(b)) => float!(F64, a, b),
+        (Literal::AbstractFloat(a), Literal::AbstractFloat(b)) => float!(AbstractFloat, a, b),
+        _ => return Err(Error::unsupported("binary operation", (op, left, right))),
+    })
+}
+
+/// Apply a binary operator to two values of the given types.
+pub(super) fn binary(
+    op: BinaryOperator,
+    left: &Value,
+    left_ty: &TypeInner,
+    right: &Value,
+    right_ty: &TypeInner,
+) -> Result<Value, Error> {
+    if op == BinaryOperator::Multiply {
+        match (left_ty, right_ty) {
+            (&TypeInner::Matrix { .. }, &TypeInner::Vector { .. }) => {
+                return matrix_times_vector(left, right)
+            }
+            (&TypeInner::Vector { .. }, &TypeInner::Matrix { .. }) => {
+                return right
+                    .components()?
+                    .iter()
+                    .map(|column| dot(left, column).map(Value::Scalar))
+                    .collect::<Result<_, _>>()
+                    .map(Value::Composite)
+            }
+            (&TypeInner::Matrix { .. }, &TypeInner::Matrix { .. }) => {
+                return right
+                    .components()?
+                    .iter()
+                    .map(|column| matrix_times_vector(left, column))
+                    .collect::<Result<_, _>>()
+                    .map(Value::Composite)
+            }
+            _ => {}
+        }
+    }
+
+    component_wise(&[left.clone(), right.clone()], &mut |args| {
+        binary_scalar(op, args[0], args[1])
+    })
+}
+
+fn matrix_times_vector(matrix: &Value, vector: &Value) -> Result<Value, Error> {
+    let mut sum: Option<Value> = None;
+    for (column, component) in matrix.components()?.iter().zip(vector.components()?) {
+        let term = component_wise(&[column.clone(), component.clone()], &mut |args| {
+            binary_scalar(BinaryOperator::Multiply, args[0], args[1])
+        })?;
+        sum = Some(match sum {
+            None => term,
+            Some(sum) => component_wise(&[sum, term], &mut |args| {
+                binary_scalar(BinaryOperator::Add, args[0], args[1])
+            })?,
+        });
+    }
+    sum.ok_or_else(|| Error::unsupported("matrix operand", matrix))
+}
+
+fn dot(left: &Value, right: &Value) -> Result<Literal, Error> {
+    let mut sum = None;
+    for (a, b) in left.components()?.iter().zip(right.components()?) {
+        let product = binary_scalar(BinaryOperator::Multiply, a.scalar()?, b.scalar()?)?;
+        sum = Some(match sum {
+            None => product,
+            Some(sum) => binary_scalar(BinaryOperator::Add, sum, product)?,
+        });
+    }
+    sum.ok_or_else(|| Error::unsupported("vector operand", left))
+}
+
+/// Build a float literal of the same type as `like`.
+fn float_like(like: Literal, value: f64) -> Result<Literal, Error> {
+    Ok(match like {
+        Literal::F16(_) => Literal::F16(f16::from_f64(value)),
+        Literal::F32(_) => Literal::F32(value as f32),
+        Literal::F64(_) => Literal::F64(value),
+        Literal::AbstractFloat(_) => Literal::AbstractFloat(value),
+        _ => return Err(Error::unsupported("float operand", like)),
+    })
+}
+
+fn to_f64(literal: Literal) -> Result<f64, Error> {
+    match literal {
+        Literal::F16(v) => Ok(v.into()),
+        Literal::F32(v) => Ok(v.into()),
+        Literal::F64(v) | Literal::AbstractFloat(v) => Ok(v),
+        _ => Err(Error::unsupported("float operand", literal)),
+    }
+}
+
+pub(super) fn select(condition: &Value, accept: &Value, reject: &Value) -> Result<Value, Error> {
+    match *condition {
+        Value::Scalar(_) => Ok(if condition.bool()? { accept } else { reject }.clone()),
+        Value::Composite(_) => component_wise(
+            &[condition.clone(), accept.clone(), reject.clone()],
+            &mut |args| match args[0] {
+                Literal::Bool(true) => Ok(args[1]),
+                Literal::Bool(false) => Ok(args[2]),
+                other => Err(Error::unsupported("boolean operand", other)),
+            },
+        ),
+        Value::Pointer(_) => Err(Error::unsupported("boolean operand", condition)),
+    }
+}
+
+pub(super) fn relational(fun: RelationalFunction, argument: &Value) -> Result<Value, Error> {
+    match fun {
+        RelationalFunction::All | RelationalFunction::Any => {
+            let mut components = argument.components()?.iter().map(Value::bool);
+            let result = if fun == RelationalFunction::All {
+                components.try_fold(true, |acc, b| b.map(|b| acc && b))?
+            } else {
+                components.try_fold(false, |acc, b| b.map(|b| acc || b))?
+            };
+            Ok(Value::Scalar(Literal::Bool(result)))
+        }
+        RelationalFunction::IsNan | RelationalFunction::IsInf => map(argument, |literal| {
+            let value = to_f64(literal)?;
+            Ok(Literal::Bool(if fun == RelationalFunction::IsNan {
+                value.is_nan()
+            } else {
+                value.is_infinite()
+            }))
+        }),
+    }
+}
+
+/// Convert or bitcast a scalar, as for [`Expression::As`].
+///
+/// [`Expression::As`]: crate::Expression::As
+pub(super) fn cast(
+    value: &Value,
+    kind: ScalarKind,
+    convert: Option<crate::Bytes>,
+) -> Result<Value, Error> {
+    map(value, |literal| match convert {
+        Some(width) => convert_scalar(literal, Scalar { kind, width }),
+        None => bitcast_scalar(literal, kind),
+    })
+}
+
+fn convert_scalar(literal: Literal, target: Scalar) -> Result<Literal, Error> {
+    // Float to integer conversions saturate, as WGSL requires.
+    macro_rules! to {
+        ($ty:ty) => {{
+            let value: $ty = match literal {
+                Literal::F64(v) | Literal::AbstractFloat(v) => v.as_(),
+                Literal::F32(v) => v.as_(),
+                Literal::F16(v) => f32::from(v).as_(),
+                Literal::U32(v) => v.as_(),
+                Literal::I32(v) => v.as_(),
+                Literal::U64(v) => v.as_(),
+                Literal::I64(v) | Literal::AbstractInt(v) => v.as_(),
+                Literal::Bool(v) => u8::from(v).as_(),
+            };
+            value
+        }};
+    }
+
+    Ok(match target {
+        Scalar::BOOL => Literal::Bool(match literal {
+            Literal::Bool(v) => v,
+            _ => to!(f64) != 0.0,
+        }),
+        Scalar::I32 => Literal::I32(to!(i32)),
+        Scalar::U32 => Literal::U32(to!(u32)),
+        Scalar::I64 => Literal::I64(to!(i64)),
+        Scalar::U64 => Literal::U64(to!(u64)),
+        Scalar::F16 => Literal::F16(f16::from_f64(to!(f64))),
+        Scalar::F32 => Literal::F32(to!(f32)),
+        Scalar::F64 => Literal::F64(to!(f64)),
+        _ => return Err(Error::unsupported("conversion", (literal, target))),
+    })
+}
+
+fn bitcast_scalar(literal: Literal, kind: ScalarKind) -> Result<Literal, Error> {
+    let bits = match literal {
+        Literal::U32(v) => u64::from(v),
+        Literal::I32(v) => u64::from(v as u32),
+        Literal::F32(v) => u64::from(v.to_bits()),
+        Literal::U64(v) => v,
+        Literal::I64(v) => v as u64,
+        Literal::F64(v) => v.to_bits(),
+        _ => return Err(Error::unsupported("bitcast", (literal, kind))),
+    };
+    Ok(match (kind, literal.width()) {
+        (ScalarKind::Uint, 4) => Literal::U32(bits as u32),
+        (ScalarKind::Sint, 4) => Literal::I32(bits as u32 as i32),
+        (ScalarKind::Float, 4) => Literal::F32(f32::from_bits(bits as u32)),
+        (ScalarKind::Uint, 8) => Literal::U64(bits),
+        (ScalarKind::Sint, 8) => Literal::I64(bits as i64),
+        (ScalarKind::Float, 8) => Literal::F64(f64::from_bits(bits)),
+        _ => return Err(Error::unsupported("bitcast", (literal, kind))),
+    })
+}
+
+/// Round to the nearest integer, with ties going to the even one.
+fn round_ties_even<T: Real>(x: T) -> T {
+    let rounded = x.round();
+    let two = T::one() + T::one();
+    if (x - x.trunc()).abs() == T::one() / two {
+        two * (x / two).round()
+    } else {
+        rounded
+    }
+}
+
+/// Apply a floating-point operation to corresponding scalars of `args`.
+///
+/// `f16` operands are computed in `f32` and rounded back.
+macro_rules! float_fn {
+    ($args:expr, |$($arg:ident),+| $body:expr) => {
+        component_wise($args, &mut |literals| match *literals {
+            [$(Literal::F32($arg)),+] => Ok(Literal::F32($body)),
+            [$(Literal::F64($arg)),+] => Ok(Literal::F64($body)),
+            [$(Literal::AbstractFloat($arg)),+] => Ok(Literal::AbstractFloat($body)),
+            [$(Literal::F16($arg)),+] => {
+                $(let $arg = f32::from($arg);)+
+                Ok(Literal::F16(f16::from_f32($body)))
+            }
+            _ => Err(Error::unsupported("float operands", literals)),
+        })
+    };
+}
+
+/// Apply an integer operation to corresponding scalars of `args`.
+macro_rules! int_fn {
+    ($args:expr, |$($arg:ident),+| $body:expr) => {
+        component_wise($args, &mut |literals| match *literals {
+            [$(Literal::I32($arg)),+] => Ok(Literal::I32($body)),
+            [$(Literal::U32($arg)),+] => Ok(Literal::U32($body)),
+            [$(Literal::I64($arg)),+] => Ok(Literal::I64($body)),
+            [$(Literal::U64($arg)),+] => Ok(Literal::U64($body)),
+            [$(Literal::AbstractInt($arg)),+] => Ok(Literal::AbstractInt($body)),
+            _ => Err(Error::unsupported("integer operands", literals)),
+        })
+    };
+}
+
+/// Apply an operation that is written the same way for any numeric type.
+macro_rules! numeric_fn {
+    ($args:expr, |$($arg:ident),+| $body:expr) => {
+        if $args.iter().all(|arg| is_float(arg)) {
+            float_fn!($args, |$($arg),+| $body)
+        } else {
+            int_fn!($args, |$($arg),+| $body)
+        }
+    };
+}
+
+fn is_float(value: &Value) -> bool {
+    match *value {
+        Value::Scalar(literal) => matches!(
+            literal.scalar_kind(),
+            ScalarKind::Float | ScalarKind::AbstractFloat
+        ),
+        Value::Composite(ref components) => components.first().is_some_and(is_float),
+        Value::Pointer(_) => false,
+    }
+}
+
+/// Return the bit pattern of a 32-bit integer, and whether it is signed.
+fn int32_bits(literal: Literal) -> Result<(u32, bool), Error> {
+    match literal {
+        Literal::U32(v) => Ok((v, false)),
+        Literal::I32(v) => Ok((v as u32, true)),
+        _ => Err(Error::unsupported("32-bit integer operand", literal)),
+    }
+}
+
+const fn int32_from_bits(bits: u32, signed: bool) -> Literal {
+    if signed {
+        Literal::I32(bits as i32)
+    } else {
+        Literal::U32(bits)
+    }
+}
+
+/// Evaluate a call to a [`MathFunction`].
+///
+/// The structures returned by `modf` and `frexp` are built with their
+/// members in declaration order: the fractional part first.
+pub(super) fn math(fun: MathFunction, args: &[Value]) -> Result<Value, Error> {
+    use MathFunction as Mf;
+
+    let arg = |i: usize| -> Result<&Value, Error> {
+        args.get(i)
+            .ok_or_else(|| Error::unsupported("math function arguments", fun))
+    };
+
+    Ok(match fun {
+        Mf::Abs => {
+            if is_float(arg(0)?) {
+                float_fn!(args, |x| x.abs())?
+            } else {
+                map(arg(0)?, |literal| {
+                    Ok(match literal {
+                        Literal::I32(v) => Literal::I32(v.wrapping_abs()),
+                        Literal::I64(v) => Literal::I64(v.wrapping_abs()),
+                        Literal::AbstractInt(v) => Literal::AbstractInt(v.wrapping_abs()),
+                        other => other,
+                    })
+                })?
+            }
+        }
+        Mf::Min => numeric_fn!(args, |a, b| if b < a { b } else { a })?,
+        Mf::Max => numeric_fn!(args, |a, b| if b > a { b } else { a })?,
+        Mf::Clamp => numeric_fn!(args, |e, low, high| {
+            let e = if e > low { e } else { low };
+            if e < high {
+                e
+            } else {
+                high
+            }
+        })?,
+        Mf::Saturate => float_fn!(args, |x| x.clamp(0.0, 1.0))?,
+        Mf::Cos => float_fn!(args, |x| x.cos())?,
+        Mf::Cosh => float_fn!(args, |x| x.cosh())?,
+        Mf::Sin => float_fn!(args, |x| x.sin())?,
+        Mf::Sinh => float_fn!(args, |x| x.sinh())?,
+        Mf::Tan => float_fn!(args, |x| x.tan())?,
+        Mf::Tanh => float_fn!(args, |x| x.tanh())?,
+        Mf::Acos => float_fn!(args, |x| x.acos())?,
+        Mf::Asin => float_fn!(args, |x| x.asin())?,
+        Mf::Atan => float_fn!(args, |x| x.atan())?,
+        Mf::Atan2 => float_fn!(args, |y, x| y.atan2(x))?,
+        Mf::Asinh => float_fn!(args, |x| x.asinh())?,
+        Mf::Acosh => float_fn!(args, |x| x.acosh())?,
+        Mf::Atanh => float_fn!(args, |x| x.atanh())?,
+        Mf::Radians => float_fn!(args, |x| x.to_radians())?,
+        Mf::Degrees => float_fn!(args, |x| x.to_degrees())?,
+        Mf::Ceil => float_fn!(args, |x| x.ceil())?,
+        Mf::Floor => float_fn!(args, |x| x.floor())?,
+        Mf::Round => float_fn!(args, |x| round_ties_even(x))?,
+        Mf::Fract => float_fn!(args, |x| x - x.floor())?,
+        Mf::Trunc => float_fn!(args, |x| x.trunc())?,
+        Mf::Modf => Value::Composite(vec![
+            float_fn!(args, |x| x - x.trunc())?,
+            float_fn!(args, |x| x.trunc())?,
+        ]),
+        Mf::Frexp => {
+            let fract = float_fn!(args, |x| frexp(x.as_()).0.as_())?;
+            let exp = map(arg(0)?, |literal| {
+                let (_, exp) = frexp(to_f64(literal)?);
+                Ok(match literal {
+                    Literal::AbstractFloat(_) => Literal::AbstractInt(exp.into()),
+                    _ => Literal::I32(exp),
+                })
+            })?;
+            Value::Composite(vec![fract, exp])
+        }
+        Mf::Ldexp => component_wise(args, &mut |literals| {
+            let exp = match literals[1] {
+                Literal::I32(exp) => exp,
+                Literal::AbstractInt(exp) => exp.clamp(i32::MIN.into(), i32::MAX.into()) as i32,
+                other => return Err(Error::unsupported("ldexp exponent", other)),
+            };
+            float_like(literals[0], libm::ldexp(to_f64(literals[0])?, exp))
+        })?,
+        Mf::Exp => float_fn!(args, |x| x.exp())?,
+        Mf::Exp2 => float_fn!(args, |x| x.exp2())?,
+        Mf::Log => float_fn!(args, |x| x.ln())?,
+        Mf::Log2 => float_fn!(args, |x| x.log2())?,
+        Mf::Pow => float_fn!(args, |x, y| x.powf(y))?,
+        Mf::Dot => Value::Scalar(dot(arg(0)?, arg(1)?)?),
+        Mf::Dot4I8Packed | Mf::Dot4U8Packed => {
+            let (Literal::U32(a), Literal::U32(b)) = (arg(0)?.scalar()?, arg(1)?.scalar()?) else {
+                return Err(Error::unsupported("packed dot product operands", args));
+            };
+            let lanes = |v: u32| v.to_le_bytes();
+            let (a, b) = (lanes(a), lanes(b));
+            if fun == Mf::Dot4I8Packed {
+                let sum = (0..4).fold(0i32, |sum, i| {
+                    sum.wrapping_add(i32::from(a[i] as i8) * i32::from(b[i] as i8))
+                });
+                Value::Scalar(Literal::I32(sum))
+            } else {
+                let sum = (0..4).fold(0u32, |sum, i| {
+                    sum.wrapping_add(u32::from(a[i]) * u32::from(b[i]))
+                });
+                Value::Scalar(Literal::U32(sum))
+            }
+        }
+        Mf::Outer => {
+            let left = arg(0)?;
+            Value::Composite(
+                arg(1)?
+                    .components()?
+                    .iter()
+                    .map(|component| {
+                        component_wise(&[left.clone(), component.clone()], &mut |args| {
+                            binary_scalar(BinaryOperator::Multiply, args[0], args[1])
+                        })
+                    })
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        Mf::Cross => {
+            let (a, b) = (arg(0)?.components()?, arg(1)?.components()?);
+            if a.len() != 3 || b.len() != 3 {
+                return Err(Error::unsupported("cross product operands", args));
+            }
+            let term = |i: usize, j: usize| -> Result<Value, Error> {
+                let mul = |x: &Value, y: &Value| {
+                    binary_scalar(BinaryOperator::Multiply, x.scalar()?, y.scalar()?)
+                };
+                let difference = binary_scalar(
+                    BinaryOperator::Subtract,
+                    mul(&a[i], &b[j])?,
+                    mul(&a[j], &b[i])?,
+                )?;
+                Ok(Value::Scalar(difference))
+            };
+            Value::Composite(vec![term(1, 2)?, term(2, 0)?, term(0, 1)?])
+        }
+        Mf::Distance => {
+            let difference = component_wise(&[arg(0)?.clone(), arg(1)?.clone()], &mut |args| {
+                binary_scalar(BinaryOperator::Subtract, args[0], args[1])
+            })?;
+            length(&difference)?
+        }
+        Mf::Length => length(arg(0)?)?,
+        Mf::Normalize => {
+            let length = length(arg(0)?)?;
+            component_wise(&[arg(0)?.clone(), length], &mut |args| {
+                binary_scalar(BinaryOperator::Divide, args[0], args[1])
+            })?
+        }
+        Mf::FaceForward => {
+            let (n, i, n_ref) = (arg(0)?, arg(1)?, arg(2)?);
+            let d = dot(n_ref, i)?;
+            if to_f64(d)? < 0.0 {
+                n.clone()
+            } else {
+                unary(UnaryOperator::Negate, n)?
+            }
+        }
+        Mf::Reflect => {
+            let (i, n) = (arg(0)?, arg(1)?);
+            let d = dot(n, i)?;
+            let factor = Value::Scalar(float_like(d, 2.0 * to_f64(d)?)?);
+            component_wise(&[i.clone(), n.clone(), factor], &mut |args| {
+                let scaled = binary_scalar(BinaryOperator::Multiply, args[2], args[1])?;
+                binary_scalar(BinaryOperator::Subtract, args[0], scaled)
+            })?
+        }
+        Mf::Refract => {
+            let (i, n, eta) = (arg(0)?, arg(1)?, arg(2)?);
+            let d = to_f64(dot(n, i)?)?;
+            let eta = to_f64(eta.scalar()?)?;
+            let k = 1.0 - eta * eta * (1.0 - d * d);
+            component_wise(&[i.clone(), n.clone()], &mut |args| {
+                let (i, n) = (to_f64(args[0])?, to_f64(args[1])?);
+                let value = if k < 0.0 {
+                    0.0
+                } else {
+                    eta * i - (eta * d + k.sqrt()) * n
+                };
+                float_like(args[0], value)
+            })?
+        }
+        Mf::Sign => {
+            if is_float(arg(0)?) {
+                float_fn!(args, |x| if x > 0.0 {
+                    1.0
+                } else if x < 0.0 {
+                    -1.0
+                } else {
+                    0.0
+                })?
+            } else {
+                map(arg(0)?, |literal| {
+                    Ok(match literal {
+                        Literal::I32(v) => Literal::I32(v.signum()),
+                        Literal::I64(v) => Literal::I64(v.signum()),
+                        Literal::AbstractInt(v) => Literal::AbstractInt(v.signum()),
+                        other => return Err(Error::unsupported("sign operand", other)),
+                    })
+                })?
+            }
+        }
+        Mf::Fma => float_fn!(args, |a, b, c| a.mul_add(b, c))?,
+        Mf::Mix => float_fn!(args, |x, y, a| x * (1.0 - a) + y * a)?,
+        Mf::Step => float_fn!(args, |edge, x| if edge <= x { 1.0 } else { 0.0 })?,
+        Mf::SmoothStep => float_fn!(args, |low, high, x| {
+            let t = ((x - low) / (high - low)).clamp(0.0, 1.0);
+            t * t * (3.0 - 2.0 * t)
+        })?,
+        Mf::Sqrt => float_fn!(args, |x| x.sqrt())?,
+        Mf::InverseSqrt => float_fn!(args, |x| 1.0 / x.sqrt())?,
+        Mf::Transpose => {
+            let columns = arg(0)?.components()?;
+            let rows = columns
+                .first()
+                .map_or(Ok(0), |c| c.components().map(<[_]>::len))?;
+            Value::Composite(
+                (0..rows)
+                    .map(|row| {
+                        columns
+                            .iter()
+                            .map(|column| Ok(column.components()?[row].clone()))
+                            .collect::<Result<_, _>>()
+                            .map(Value::Composite)
+                    })
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        Mf::Determinant => {
+            let columns = arg(0)?.components()?;
+            let mut matrix = Vec::with_capacity(columns.len());
+            let mut like = None;
+            for column in columns {
+                let column = column
+                    .components()?
+                    .iter()
+                    .map(|c| {
+                        let literal = c.scalar()?;
+                        like = Some(literal);
+                        to_f64(literal)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                matrix.push(column);
+            }
+            let like = like.ok_or_else(|| Error::unsupported("matrix operand", args))?;
+            Value::Scalar(float_like(like, determinant(&matrix))?)
+        }
+        Mf::QuantizeToF16 => float_fn!(args, |x| f16::from_f32(x.as_()).to_f32().as_())?,
+        Mf::CountTrailingZeros => int_fn!(args, |x| x.trailing_zeros().as_())?,
+        Mf::CountLeadingZeros => int_fn!(args, |x| x.leading_zeros().as_())?,
+        Mf::CountOneBits => int_fn!(args, |x| x.count_ones().as_())?,
+        Mf::ReverseBits => int_fn!(args, |x| x.reverse_bits())?,
+        Mf::ExtractBits => {
+            let (offset, count) = (arg(1)?.index()?, arg(2)?.index()?);
+            map(arg(0)?, |literal| {
+                let (bits, signed) = int32_bits(literal)?;
+                let offset = offset.clamp(0, 32) as u32;
+                let count = count.clamp(0, i64::from(32 - offset)) as u32;
+                if count == 0 {
+                    return Ok(int32_from_bits(0, signed));
+                }
+                // Move the field to the top, then shift it back down,
+                // extending the sign if `bits` is signed.
+                let top = bits << (32 - offset - count);
+                let field = if signed {
+                    ((top as i32) >> (32 - count)) as u32
+                } else {
+                    top >> (32 - count)
+                };
+                Ok(int32_from_bits(field, signed))
+            })?
+        }
+        Mf::InsertBits => {
+            let (offset, count) = (arg(2)?.index()?, arg(3)?.index()?);
+            component_wise(&args[..2], &mut |literals| {
+                let (bits, signed) = int32_bits(literals[0])?;
+                let (new_bits, _) = int32_bits(literals[1])?;
+                let offset = offset.clamp(0, 32) as u32;
+                let count = count.clamp(0, i64::from(32 - offset)) as u32;
+                let mask = if count == 32 {
+                    u32::MAX
+                } else {
+                    ((1u32 << count) - 1).wrapping_shl(offset)
+                };
+                let inserted = (bits & !mask) | (new_bits.wrapping_shl(offset) & mask);
+                Ok(int32_from_bits(inserted, signed))
+            })?
+        }
+        Mf::FirstTrailingBit => map(arg(0)?, |literal| {
+            let (bits, signed) = int32_bits(literal)?;
+            let position = if bits == 0 {
+                u32::MAX
+            } else {
+                bits.trailing_zeros()
+            };
+            Ok(int32_from_bits(position, signed))
+        })?,
+        Mf::FirstLeadingBit => map(arg(0)?, |literal| {
+            let (bits, signed) = int32_bits(literal)?;
+            // For negative signed values, find the most significant zero bit.
+            let bits = if signed && (bits as i32) < 0 {
+                !bits
+            } else {
+                bits
+            };
+            let position = if bits == 0 {
+                u32::MAX
+            } else {
+                31 - bits.leading_zeros()
+            };
+            Ok(int32_from_bits(position, signed))
+        })?,
+        Mf::Pack4x8snorm => pack(arg(0)?, 8, |x| {
+            Ok(round_ties_even(to_f64(x)?.clamp(-1.0, 1.0) * 127.0) as i64 as u32)
+        })?,
+        Mf::Pack4x8unorm => pack(arg(0)?, 8, |x| {
+            Ok(round_ties_even(to_f64(x)?.clamp(0.0, 1.0) * 255.0) as u32)
+        })?,
+        Mf::Pack2x16snorm => pack(arg(0)?, 16, |x| {
+            Ok(round_ties_even(to_f64(x)?.clamp(-1.0, 1.0) * 32767.0) as i64 as u32)
+        })?,
+        Mf::Pack2x16unorm => pack(arg(0)?, 16, |x| {
+            Ok(round_ties_even(to_f64(x)?.clamp(0.0, 1.0) * 65535.0) as u32)
+        })?,
+        Mf::Pack2x16float => pack(arg(0)?, 16, |x| {
+            Ok(f16::from_f64(to_f64(x)?).to_bits().into())
+        })?,
+        Mf::Pack4xI8 | Mf::Pack4xU8 => pack(arg(0)?, 8, |x| Ok(int32_bits(x)?.0))?,
+        Mf::Pack4xI8Clamp => pack(arg(0)?, 8, |x| match x {
+            Literal::I32(v) => Ok(v.clamp(-128, 127) as u32),
+            _ => Err(Error::unsupported("pack operand", x)),
+        })?,
+        Mf::Pack4xU8Clamp => pack(arg(0)?, 8, |x| match x {
+            Literal::U32(v) => Ok(v.min(255)),
+            _ => Err(Error::unsupported("pack operand", x)),
+        })?,
+        Mf::Unpack4x8snorm => unpack(arg(0)?, 8, |field| {
+            Literal::F32((f32::from(field as u8 as i8) / 127.0).max(-1.0))
+        })?,
+        Mf::Unpack4x8unorm => unpack(arg(0)?, 8, |field| Literal::F32(field as f32 / 255.0))?,
+        Mf::Unpack2x16snorm => unpack(arg(0)?, 16, |field| {
+            Literal::F32((f32::from(field as u16 as i16) / 32767.0).max(-1.0))
+        })?,
+        Mf::Unpack2x16unorm => unpack(arg(0)?, 16, |field| Literal::F32(field as f32 / 65535.0))?,
+        Mf::Unpack2x16float => unpack(arg(0)?, 16, |field| {
+            Literal::F32(f16::from_bits(field as u16).to_f32())
+        })?,
+        Mf::Unpack4xI8 => unpack(arg(0)?, 8, |field| {
+            Literal::I32(i32::from(field as u8 as i8))
+        })?,
+        Mf::Unpack4xU8 => unpack(arg(0)?, 8, Literal::U32)?,
+        Mf::Inverse => return Err(Error::unsupported("math function", fun)),
+    })
+}
+
+fn length(value: &Value) -> Result<Value, Error> {
+    match *value {
+        Value::Scalar(_) => math(MathFunction::Abs, core::slice::from_ref(value)),
+        _ => {
+            let squared = dot(value, value)?;
+            Ok(Value::Scalar(float_like(squared, to_f64(squared)?.sqrt())?))
+        }
+    }
+}
+
+/// Split `x` into a fraction in `[0.5, 1)` and a power of two.
+fn frexp(x: f64) -> (f64, i32) {
+    libm::frexp(x)
+}
+
+fn determinant(columns: &[Vec<f64>]) -> f64 {
+    match columns.len() {
+        0 => 1.0,
+        1 => columns[0][0],
+        n => (0..n)
+            .map(|i| {
+                // Expand along the first row.
+                let minor: Vec<Vec<f64>> = columns
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, column)| column[1..].to_vec())
+                    .collect();
+                let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+                sign * columns[i][0] * determinant(&minor)
+            })
+            .sum(),
+    }
+}
+
+/// Pack the components of `vector` into a `u32`, `bits` bits each, with the
+/// first component in the least significant bits.
+fn pack(
+    vector: &Value,
+    bits: u32,
+    mut f: impl FnMut(Literal) -> Result<u32, Error>,
+) -> Result<Value, Error> {
+    let mask = (1u32 << bits) - 1;
+    let mut packed = 0;
+    for (i, component) in vector.components()?.iter().enumerate() {
+        packed |= (f(component.scalar()?)? & mask) << (i as u32 * bits);
+    }
+    Ok(Value::Scalar(Literal::U32(packed)))
+}
+
+/// Unpack a `u32` into a vector of `32 / bits` fields, the inverse of [`pack`].
+fn unpack(value: &Value, bits: u32, f: impl Fn(u32) -> Literal) -> Result<Value, Error> {
+    let Literal::U32(packed) = value.scalar()? else {
+        return Err(Error::unsupported("unpack operand", value));
+    };
+    let mask = (1u32 << bits) - 1;
+    Ok(Value::Composite(
+        (0..32 / bits)
+            .map(|i| Value::Scalar(f((packed >> (i * bits)) & mask)))
+            .collect(),
+    ))
+}
@@ -17,6 +17,8 @@ pub mod dot;
 pub mod glsl;
 #[cfg(hlsl_out)]
 pub mod hlsl;
+#[cfg(interp)]
+pub mod interp;
 #[cfg(msl_out)]
 pub mod msl;
 #[cfg(spv_out)]
@@ -30,6 +32,11 @@ pub mod pipeline_constants;
 #[cfg(any(hlsl_out, glsl_out))]
 mod continue_forward;
 
+#[cfg(any(hlsl_out, msl_out, glsl_out))]
+mod source_map;
+#[cfg(any(hlsl_out, msl_out, glsl_out))]
+pub use source_map::{LineDirectives, SourceMap};
+
 /// Names of vector components.
 pub const COMPONENTS: &[char] = &['x', 'y', 'z', 'w'];
 /// Indent for backends.
@@ -265,6 +265,13 @@ pub struct Options {
     /// If set, loops will have code injected into them, forcing the compiler
     /// to think the number of iterations is bounded.
     pub force_loop_bounding: bool,
+    /// Return a [`SourceMap`] of the output in [`TranslationInfo::source_map`].
+    ///
+    /// [`SourceMap`]: crate::back::SourceMap
+    pub source_map: bool,
+    /// If set, emit `#line` directives referring to the source the module
+    /// was parsed from.
+    pub line_directives: Option<crate::back::LineDirectives>,
 }
 
 impl Default for Options {
@@ -278,6 +285,8 @@ impl Default for Options {
             bounds_check_policies: index::BoundsCheckPolicies::default(),
             zero_initialize_workgroup_memory: true,
             force_loop_bounding: true,
+            source_map: false,
+            line_directives: None,
         }
     }
 }
@@ -733,6 +742,9 @@ pub struct TranslationInfo {
     ///
     ///Note: Some entry points may fail translation because of missing bindings.
     pub entry_point_names: Vec<Result<String, EntryPointError>>,
+    /// The lines of the output generated from each span of the module, if
+    /// requested by [`Options::source_map`].
+    pub source_map: Option<crate::back::SourceMap>,
 }
 
 pub fn write_string(
@@ -455,7 +455,7 @@ enum WrappedFunction {
 }
 
 pub struct Writer<W> {
-    out: W,
+    out: back::source_map::SourceMapWriter<W>,
     names: FastHashMap<NameKey, String>,
     named_expressions: crate::NamedExpressions,
     /// Set of expressions that need to be baked to avoid unnecessary repetition in output
@@ -797,7 +797,7 @@ impl<W: Write> Writer<W> {
     /// Creates a new `Writer` instance.
     pub fn new(out: W) -> Self {
         Writer {
-            out,
+            out: back::source_map::SourceMapWriter::new(out),
             names: FastHashMap::default(),
             named_expressions: Default::default(),
             need_bake_expressions: Default::default(),
@@ -815,7 +815,7 @@ impl<W: Write> Writer<W> {
     // See https://github.com/rust-lang/rust-clippy/issues/4979.
     #[allow(clippy::missing_const_for_fn)]
     pub fn finish(self) -> W {
-        self.out
+        self.out.into_inner()
     }
 
     /// Generates statements to be inserted immediately before and at the very
@@ -3512,7 +3512,7 @@ impl<W: Write> Writer<W> {
     fn put_block(
         &mut self,
         level: back::Level,
-        statements: &[crate::Statement],
+        statements: &crate::Block,
         context: &StatementContext,
     ) -> BackendResult {
         // Add to the set in order to track the stack size.
@@ -3520,8 +3520,9 @@ impl<W: Write> Writer<W> {
         self.put_block_stack_pointers
             .insert(ptr::from_ref(&level).cast());
 
-        for statement in statements {
+        for (statement, &span) in statements.span_iter() {
             log::trace!("statement[{}] {:?}", level.0, statement);
+            self.out.mark(span)?;
             match *statement {
                 crate::Statement::Emit(ref range) => {
                     for handle in range.clone() {
@@ -4245,6 +4246,8 @@ impl<W: Write> Writer<W> {
         );
         self.wrapped_functions.clear();
         self.struct_member_pads.clear();
+        self.out
+            .reset(options.source_map, options.line_directives.as_ref(), true);
 
         writeln!(
             self.out,
@@ -6010,6 +6013,7 @@ template <typename A>
 
             writeln!(self.out)?;
             self.write_wrapped_functions(module, &ctx)?;
+            self.out.mark(module.functions.get_span(fun_handle))?;
 
             let fun_info = &mod_info[fun_handle];
             pass_through_globals.clear();
@@ -6121,6 +6125,7 @@ template <typename A>
 
         let mut info = TranslationInfo {
             entry_point_names: Vec::with_capacity(ep_range.len()),
+            source_map: None,
         };
 
         for ep_index in ep_range {
@@ -7037,6 +7042,7 @@ template <typename A>
             self.named_expressions.clear();
         }
 
+        info.source_map = self.out.take_source_map();
         Ok(info)
     }
 
@@ -45,13 +45,15 @@ pub enum PipelineConstantError {
 ///
 /// If no changes are needed, this just returns `Cow::Borrowed` references to
 /// `module` and `module_info`. Otherwise, it clones `module`, retains only the
-/// selected entry point, compacts the module, edits its [`global_expressions`]
-/// arena to contain only fully-evaluated expressions, and returns the
-/// simplified module and its validation results.
+/// selected entry point, [specializes] functions taking pointers to global
+/// variables, compacts the module, edits its [`global_expressions`] arena to
+/// contain only fully-evaluated expressions, and returns the simplified module
+/// and its validation results.
 ///
 /// The module returned has an empty `overrides` arena, and the
 /// `global_expressions` arena contains only fully-evaluated expressions.
 ///
+/// [specializes]: crate::proc::specialize_pointer_arguments
 /// [`global_expressions`]: Module::global_expressions
 pub fn process_overrides<'a>(
     module: &'a Module,
@@ -59,7 +61,10 @@ pub fn process_overrides<'a>(
     entry_point: Option<(ir::ShaderStage, &str)>,
     pipeline_constants: &PipelineConstants,
 ) -> Result<(Cow<'a, Module>, Cow<'a, ModuleInfo>), PipelineConstantError> {
-    if (entry_point.is_none() || module.entry_points.len() <= 1) && module.overrides.is_empty() {
+    if (entry_point.is_none() || module.entry_points.len() <= 1)
+        && module.overrides.is_empty()
+        && !crate::proc::has_unrestricted_pointer_arguments(module)
+    {
         // We skip compacting the module here mostly to reduce the risk of
         // hitting corner cases like https://github.com/gfx-rs/wgpu/issues/7793.
         // Compaction doesn't cost very much [1], so it would also be reasonable
@@ -79,6 +84,9 @@ pub fn process_overrides<'a>(
             .retain(|ep| ep.stage == ep_stage && ep.name == ep_name);
     }
 
+    // Most backends can't pass pointers to global variables to functions.
+    crate::proc::specialize_pointer_arguments(&mut module);
+
     // Compact the module to remove anything not reachable from an entry point.
     // This is necessary because we may not have values for overrides that are
     // not reachable from the/an entry point.
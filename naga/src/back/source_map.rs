@@ -0,0 +1,245 @@
+/*!
+Mapping the output of text backends back to the module's [`Span`]s.
+
+The HLSL, MSL and GLSL backends can optionally return a [`SourceMap`] from the
+lines they write to the spans of the functions and statements those lines were
+generated from, and emit `#line` directives referring to the source the module
+was parsed from, as configured by [`LineDirectives`]. This makes it possible to
+trace errors reported by driver compilers back to the original shader.
+*/
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Write};
+
+use crate::Span;
+
+/// Maps lines of a text backend's output to the [`Span`]s they were generated
+/// from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SourceMap {
+    /// The 1-based lines at which the code generated from a span starts,
+    /// sorted by line.
+    entries: Vec<(u32, Span)>,
+}
+
+impl SourceMap {
+    /// Returns the span the 1-based `line` of the output was generated from,
+    /// if any.
+    ///
+    /// Lines that aren't generated from a span of their own, like closing
+    /// braces, are attributed to the closest span before them.
+    pub fn span(&self, line: u32) -> Option<Span> {
+        let index = self.entries.partition_point(|&(start, _)| start <= line);
+        index.checked_sub(1).map(|index| self.entries[index].1)
+    }
+
+    /// Iterates over the 1-based lines at which the code generated from a span
+    /// starts, and those spans, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, Span)> + '_ {
+        self.entries.iter().copied()
+    }
+
+    /// Returns `true` if no lines were mapped.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn push(&mut self, line: u32, span: Span) {
+        match self.entries.last_mut() {
+            // The previous span didn't generate any code.
+            Some(&mut (last_line, ref mut last_span)) if last_line == line => *last_span = span,
+            _ => self.entries.push((line, span)),
+        }
+    }
+}
+
+/// Configuration for emitting `#line` directives in text backends.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct LineDirectives {
+    /// The source code the module was parsed from, which its spans refer to.
+    pub source_code: String,
+    /// The name of the source file, if any.
+    ///
+    /// The GLSL backend ignores this, as `#line` directives can't name files
+    /// in GLSL.
+    pub file_name: Option<String>,
+}
+
+/// The output of a text backend, keeping track of the line being written.
+pub(super) struct SourceMapWriter<W> {
+    inner: W,
+    /// The 1-based line being written, only tracked if `map` or `directives`
+    /// is set.
+    line: u32,
+    at_line_start: bool,
+    map: Option<SourceMap>,
+    directives: Option<Directives>,
+}
+
+struct Directives {
+    /// The offsets at which lines of the source code start.
+    line_starts: Vec<u32>,
+    file_name: Option<String>,
+    /// The last source line a directive was emitted for.
+    last_line: Option<u32>,
+}
+
+impl<W> SourceMapWriter<W> {
+    pub const fn new(inner: W) -> Self {
+        SourceMapWriter {
+            inner,
+            line: 1,
+            at_line_start: true,
+            map: None,
+            directives: None,
+        }
+    }
+
+    /// Starts tracking a new output, building a [`SourceMap`] if `source_map`
+    /// is set and emitting `#line` directives if `directives` is provided.
+    ///
+    /// `file_names` controls whether directives may name the source file.
+    pub fn reset(
+        &mut self,
+        source_map: bool,
+        directives: Option<&LineDirectives>,
+        file_names: bool,
+    ) {
+        self.line = 1;
+        self.at_line_start = true;
+        self.map = source_map.then(SourceMap::default);
+        self.directives = directives.map(|directives| Directives {
+            line_starts: core::iter::once(0)
+                .chain(
+                    directives
+                        .source_code
+                        .match_indices('\n')
+                        .map(|(offset, _)| offset as u32 + 1),
+                )
+                .collect(),
+            file_name: directives.file_name.clone().filter(|_| file_names),
+            last_line: None,
+        });
+    }
+
+    /// Returns the [`SourceMap`] built since the last [`reset`](Self::reset),
+    /// if requested.
+    pub fn take_source_map(&mut self) -> Option<SourceMap> {
+        self.map.take()
+    }
+
+    #[cfg_attr(
+        not(msl_out),
+        allow(dead_code, reason = "only the MSL writer gives back its output")
+    )]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> SourceMapWriter<W> {
+    /// Marks the code written from now on as generated from `span`.
+    pub fn mark(&mut self, span: Span) -> fmt::Result {
+        let Some(range) = span.to_range() else {
+            return Ok(());
+        };
+        if let Some(ref mut directives) = self.directives {
+            let line = directives
+                .line_starts
+                .partition_point(|&start| start as usize <= range.start)
+                as u32;
+            if self.at_line_start && directives.last_line != Some(line) {
+                directives.last_line = Some(line);
+                match directives.file_name {
+                    Some(ref file_name) => {
+                        write!(self.inner, "#line {line} \"")?;
+                        for c in file_name.chars() {
+                            if matches!(c, '"' | '\\') {
+                                self.inner.write_char('\\')?;
+                            }
+                            self.inner.write_char(c)?;
+                        }
+                        writeln!(self.inner, "\"")?;
+                    }
+                    None => writeln!(self.inner, "#line {line}")?,
+                }
+                self.line += 1;
+            }
+        }
+        if let Some(ref mut map) = self.map {
+            map.push(self.line, span);
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for SourceMapWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.is_empty() {
+            return Ok(());
+        }
+        if self.map.is_some() || self.directives.is_some() {
+            self.line += s.matches('\n').count() as u32;
+            self.at_line_start = s.ends_with('\n');
+        }
+        self.inner.write_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines() {
+        let mut writer = SourceMapWriter::new(String::new());
+        writer.reset(true, None, true);
+        writer.write_str("a\nb").unwrap();
+        writer.mark(Span::new(1, 2)).unwrap();
+        writer.write_str("c\n").unwrap();
+        writer.mark(Span::new(3, 4)).unwrap();
+        writer.mark(Span::new(5, 6)).unwrap();
+        writer.write_str("d\ne\n").unwrap();
+        let map = writer.take_source_map().unwrap();
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(2, Span::new(1, 2)), (3, Span::new(5, 6))]
+        );
+        assert_eq!(map.span(1), None);
+        assert_eq!(map.span(2), Some(Span::new(1, 2)));
+        assert_eq!(map.span(4), Some(Span::new(5, 6)));
+    }
+
+    #[test]
+    fn directives() {
+        let directives = LineDirectives {
+            source_code: "x\ny\nz\n".into(),
+            file_name: Some("dir\\a.wgsl".into()),
+        };
+        let mut writer = SourceMapWriter::new(String::new());
+        writer.reset(true, Some(&directives), true);
+        writer.mark(Span::new(2, 3)).unwrap();
+        writer.write_str("first\n").unwrap();
+        // Same source line, no directive.
+        writer.mark(Span::new(2, 3)).unwrap();
+        writer.write_str("second\n").unwrap();
+        writer.mark(Span::new(4, 5)).unwrap();
+        writer.write_str("third\n").unwrap();
+        assert_eq!(
+            writer.take_source_map().unwrap().span(5),
+            Some(Span::new(4, 5))
+        );
+        assert_eq!(
+            writer.into_inner(),
+            "#line 2 \"dir\\\\a.wgsl\"\nfirst\nsecond\n#line 3 \"dir\\\\a.wgsl\"\nthird\n"
+        );
+
+        let mut writer = SourceMapWriter::new(String::new());
+        writer.reset(false, Some(&directives), false);
+        writer.mark(Span::new(0, 1)).unwrap();
+        writer.write_str("first\n").unwrap();
+        assert_eq!(writer.into_inner(), "#line 1\nfirst\n");
+    }
+}
@@ -6,12 +6,15 @@ use alloc::{
 };
 
 use codespan_reporting::diagnostic::{Diagnostic, Label};
-use codespan_reporting::files::SimpleFile;
+use codespan_reporting::files::SimpleFiles;
 use codespan_reporting::term;
 use pp_rs::token::PreprocessorError;
 use thiserror::Error;
 
-use super::token::TokenValue;
+use super::{
+    include::{IncludeMap, IncludedSource},
+    token::TokenValue,
+};
 use crate::{error::replace_control_chars, SourceLocation};
 use crate::{error::ErrorWrite, proc::ConstantEvaluatorError, Span};
 
@@ -126,6 +129,10 @@ pub enum ErrorKind {
     /// An error was returned by the preprocessor.
     #[error("{0:?}")]
     PreprocessorError(PreprocessorError),
+    /// An `#include` directive was malformed or couldn't be resolved by the
+    /// [`IncludeResolver`](super::IncludeResolver).
+    #[error("Failed to include file: {0}")]
+    IncludeError(String),
     /// The parser entered an illegal state and exited
     ///
     /// This obviously is a bug and as such should be reported in the github issue tracker
@@ -152,33 +159,66 @@ pub struct Error {
 
 impl Error {
     /// Returns a [`SourceLocation`] for the error message.
+    ///
+    /// `meta` refers to the source after `#include` directives were expanded,
+    /// so if the shader included any files use [`ParseErrors::location`]
+    /// instead. Returns `None` if `meta` lies outside of `source`.
     pub fn location(&self, source: &str) -> Option<SourceLocation> {
-        Some(self.meta.location(source))
+        span_location(self.meta, source)
     }
 }
 
+fn span_location(span: Span, source: &str) -> Option<SourceLocation> {
+    source.get(span.to_range()?)?;
+    Some(span.location(source))
+}
+
 /// A collection of errors returned during shader parsing.
 #[derive(Clone, Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct ParseErrors {
     pub errors: Vec<Error>,
+    /// Maps the spans in `errors` back to the files pulled in by `#include`
+    /// directives, empty if the shader didn't include any.
+    pub include_map: IncludeMap,
 }
 
 impl ParseErrors {
+    /// Returns the [`SourceLocation`] of the error at `index` in
+    /// [`errors`](Self::errors), along with the included file it is located in,
+    /// or `None` if it is located in `source`, the shader that was parsed.
+    pub fn location(
+        &self,
+        index: usize,
+        source: &str,
+    ) -> Option<(Option<&IncludedSource>, SourceLocation)> {
+        let (file, meta) = self.include_map.resolve(self.errors.get(index)?.meta);
+        let source = file.map_or(source, |file| &file.source);
+        Some((file, span_location(meta, source)?))
+    }
+
     pub fn emit_to_writer(&self, writer: &mut impl ErrorWrite, source: &str) {
         self.emit_to_writer_with_path(writer, source, "glsl");
     }
 
     pub fn emit_to_writer_with_path(&self, writer: &mut impl ErrorWrite, source: &str, path: &str) {
-        let path = path.to_string();
-        let files = SimpleFile::new(path, replace_control_chars(source));
+        let mut files = SimpleFiles::new();
+        let root = files.add(path.to_string(), replace_control_chars(source));
+        let included: Vec<_> = self
+            .include_map
+            .files()
+            .iter()
+            .map(|file| files.add(file.name.clone(), replace_control_chars(&file.source)))
+            .collect();
         let config = term::Config::default();
 
         for err in &self.errors {
             let mut diagnostic = Diagnostic::error().with_message(err.kind.to_string());
 
-            if let Some(range) = err.meta.to_range() {
-                diagnostic = diagnostic.with_labels(vec![Label::primary((), range)]);
+            let (file, meta) = self.include_map.resolve_index(err.meta);
+            if let Some(range) = meta.to_range() {
+                let file_id = file.map_or(root, |file| included[file]);
+                diagnostic = diagnostic.with_labels(vec![Label::primary(file_id, range)]);
             }
 
             term::emit(writer, &config, &files, &diagnostic).expect("cannot write error");
@@ -206,6 +246,9 @@ impl core::error::Error for ParseErrors {
 
 impl From<Vec<Error>> for ParseErrors {
     fn from(errors: Vec<Error>) -> Self {
-        Self { errors }
+        Self {
+            errors,
+            include_map: IncludeMap::default(),
+        }
     }
 }
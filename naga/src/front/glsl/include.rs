@@ -0,0 +1,616 @@
+/*!
+Expansion of `#include` directives.
+
+The [`GL_GOOGLE_include_directive`] extension allows a shader to pull in other
+source strings with `#include "path"` or `#include <path>`. The preprocessor we
+use doesn't know about the directive, so before the shader is handed to it
+every include is replaced by the contents returned by the user supplied
+[`IncludeResolver`].
+
+Directives inside comments are ignored, as are those in the inactive branches
+of `#if`, `#ifdef` and `#ifndef` blocks, so that includes can be guarded by
+macros. Only `defined`, integer literals and the `!`, `&&` and `||` operators
+are understood in conditions; branches depending on anything else are treated
+as active.
+
+The expanded source is what the rest of the frontend sees, so all spans it
+produces refer to it. An [`IncludeMap`] is built alongside the expansion which
+allows translating those spans back to the file they originally came from.
+
+[`GL_GOOGLE_include_directive`]: https://github.com/KhronosGroup/GLSL/blob/main/extensions/google/GL_GOOGLE_include_directive.txt
+*/
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::error::{Error, ErrorKind};
+use crate::{FastHashMap, Span};
+
+/// Maximum nesting depth of `#include` directives.
+///
+/// This mostly exists to produce an error instead of overflowing the stack on
+/// include cycles that the resolver doesn't report as such (for example by
+/// returning a different [`IncludedSource::name`] every time).
+const MAX_INCLUDE_DEPTH: usize = 64;
+
+/// The form of an `#include` directive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IncludeType {
+    /// `#include "path"`, the path should be looked up relative to the
+    /// including file first.
+    Relative,
+    /// `#include <path>`, the path should only be looked up in the system
+    /// include directories.
+    Standard,
+}
+
+/// A source string returned by an [`IncludeResolver`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IncludedSource {
+    /// The name of the resolved file.
+    ///
+    /// This is passed as the `includer` to nested includes, used to detect
+    /// include cycles and displayed in error messages, as such it should
+    /// uniquely identify the file (i.e. be a canonical path).
+    pub name: String,
+    /// The contents of the file.
+    pub source: String,
+}
+
+/// Resolves the paths in `#include` directives to source strings.
+///
+/// An implementation is provided for closures with the same signature as
+/// [`resolve`](IncludeResolver::resolve).
+pub trait IncludeResolver: Send + Sync {
+    /// Returns the source included by `#include "path"` or `#include <path>`
+    /// depending on `ty`.
+    ///
+    /// `includer` is the [`name`](IncludedSource::name) of the file containing
+    /// the directive or the empty string for the top level source.
+    ///
+    /// The returned error message is reported as an
+    /// [`ErrorKind::IncludeError`].
+    fn resolve(
+        &self,
+        path: &str,
+        ty: IncludeType,
+        includer: &str,
+    ) -> Result<IncludedSource, String>;
+}
+
+impl<F> IncludeResolver for F
+where
+    F: Fn(&str, IncludeType, &str) -> Result<IncludedSource, String> + Send + Sync,
+{
+    fn resolve(
+        &self,
+        path: &str,
+        ty: IncludeType,
+        includer: &str,
+    ) -> Result<IncludedSource, String> {
+        self(path, ty, includer)
+    }
+}
+
+/// A contiguous range of the expanded source that comes from a single file.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+struct Segment {
+    /// Byte offset in the expanded source where the segment begins, it ends
+    /// where the next segment begins.
+    start: u32,
+    /// Index into [`IncludeMap::files`] or `None` for the top level source.
+    file: Option<usize>,
+    /// Byte offset in the original file corresponding to `start`.
+    offset: u32,
+}
+
+/// Maps spans in the include expanded source back to the files they came from.
+///
+/// Returned in [`ParseErrors`](super::ParseErrors) and available through
+/// [`Frontend::include_map`](super::Frontend::include_map) after parsing.
+///
+/// If the shader didn't include any files the map is empty and all spans refer
+/// to the top level source.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct IncludeMap {
+    files: Vec<IncludedSource>,
+    segments: Vec<Segment>,
+}
+
+impl IncludeMap {
+    /// Returns `true` if no files were included.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Returns all the files that were included, in order of first inclusion.
+    pub fn files(&self) -> &[IncludedSource] {
+        &self.files
+    }
+
+    /// Translates a span in the expanded source to the file it originated from.
+    ///
+    /// Returns the included file, or `None` for the top level source, and the
+    /// span relative to the start of that file. Spans crossing the end of an
+    /// included region are truncated to it.
+    pub fn resolve(&self, span: Span) -> (Option<&IncludedSource>, Span) {
+        let (file, span) = self.resolve_index(span);
+        (file.map(|file| &self.files[file]), span)
+    }
+
+    /// Like [`resolve`](Self::resolve) but returns the index of the file in
+    /// [`files`](Self::files).
+    pub(super) fn resolve_index(&self, span: Span) -> (Option<usize>, Span) {
+        let Some(range) = span.to_range() else {
+            return (None, span);
+        };
+
+        let index = self
+            .segments
+            .partition_point(|segment| segment.start as usize <= range.start);
+        let Some(segment) = index.checked_sub(1).map(|i| self.segments[i]) else {
+            return (None, span);
+        };
+        let segment_end = self.segments.get(index).map_or(u32::MAX, |next| next.start);
+
+        let start = range.start as u32 - segment.start + segment.offset;
+        let end = (range.end as u32).min(segment_end) - segment.start + segment.offset;
+
+        (segment.file, Span::new(start, end))
+    }
+}
+
+/// Whether a region of the source is active, according to what the expander
+/// knows about the macros it depends on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Condition {
+    True,
+    False,
+    Unknown,
+}
+
+impl Condition {
+    const fn not(self) -> Self {
+        match self {
+            Self::True => Self::False,
+            Self::False => Self::True,
+            Self::Unknown => Self::Unknown,
+        }
+    }
+
+    const fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::False, _) | (_, Self::False) => Self::False,
+            (Self::True, Self::True) => Self::True,
+            _ => Self::Unknown,
+        }
+    }
+
+    const fn or(self, other: Self) -> Self {
+        self.not().and(other.not()).not()
+    }
+}
+
+/// An `#if`, `#ifdef` or `#ifndef` block being expanded.
+struct Conditional {
+    /// Whether the region containing the block is active.
+    enclosing: Condition,
+    /// Whether one of the previous branches of the block was taken.
+    taken: Condition,
+    /// Whether the current branch is active.
+    active: Condition,
+}
+
+impl Conditional {
+    fn branch(&mut self, condition: Condition) {
+        self.active = self.enclosing.and(self.taken.not()).and(condition);
+        self.taken = self.taken.or(condition);
+    }
+}
+
+/// Appends `c` to `output` replaced with spaces, unless it's a line break.
+fn blank(output: &mut String, c: char) {
+    match c {
+        '\n' | '\r' => output.push(c),
+        _ => output.extend(core::iter::repeat_n(' ', c.len_utf8())),
+    }
+}
+
+/// Replaces the comments in `line` with spaces, keeping the byte offsets of
+/// everything else.
+///
+/// `in_comment` is whether the line starts inside a block comment, and is
+/// updated to whether it ends inside one. In that case the offset where that
+/// comment started is returned as well.
+fn strip_comments(line: &str, in_comment: &mut bool) -> (String, Option<usize>) {
+    let mut code = String::with_capacity(line.len());
+    let mut comment_start = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if *in_comment {
+            if c == '*' && chars.next_if(|&(_, c)| c == '/').is_some() {
+                *in_comment = false;
+                comment_start = None;
+                code.push_str("  ");
+            } else {
+                blank(&mut code, c);
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('/', Some(&(_, '/'))) => {
+                for (_, c) in line[i..].char_indices() {
+                    blank(&mut code, c);
+                }
+                break;
+            }
+            ('/', Some(&(_, '*'))) => {
+                chars.next();
+                *in_comment = true;
+                comment_start = Some(i);
+                code.push_str("  ");
+            }
+            _ => code.push(c),
+        }
+    }
+    (code, comment_start)
+}
+
+/// Splits `code` into the name of the directive it contains and the rest of
+/// the line.
+///
+/// Returns `None` if the line isn't a directive.
+fn parse_directive(code: &str) -> Option<(&str, &str)> {
+    let rest = code.trim_start().strip_prefix('#')?.trim_start();
+    Some(rest.split_at(identifier_len(rest)))
+}
+
+fn identifier_len(s: &str) -> usize {
+    s.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(s.len())
+}
+
+/// Returns the macro name at the start of `rest`, which must be followed by
+/// nothing but whitespace if `alone` is set.
+fn macro_name(rest: &str, alone: bool) -> Option<&str> {
+    let rest = rest.trim_start();
+    let (name, trailing) = rest.split_at(identifier_len(rest));
+    (!name.is_empty() && (!alone || trailing.trim().is_empty())).then_some(name)
+}
+
+/// Parses the rest of an `#include` directive, returning the path and its
+/// form.
+fn parse_include(rest: &str) -> Result<(&str, IncludeType), &'static str> {
+    let rest = rest.trim_start();
+    let (close, ty) = match rest.chars().next() {
+        Some('"') => ('"', IncludeType::Relative),
+        Some('<') => ('>', IncludeType::Standard),
+        _ => return Err("expected \"path\" or <path> after #include"),
+    };
+    let rest = &rest[1..];
+    let Some(end) = rest.find(close) else {
+        return Err("unterminated path in #include directive");
+    };
+
+    if !rest[end + 1..].trim().is_empty() {
+        return Err("unexpected tokens after #include directive");
+    }
+
+    Ok((&rest[..end], ty))
+}
+
+struct Expander<'a> {
+    resolver: &'a dyn IncludeResolver,
+    output: String,
+    map: IncludeMap,
+    /// Indices into [`IncludeMap::files`] of the files currently being expanded.
+    stack: Vec<usize>,
+    /// Whether the macros that the conditions can refer to are defined.
+    macros: FastHashMap<String, Condition>,
+    errors: Vec<Error>,
+}
+
+impl Expander<'_> {
+    fn push_segment(&mut self, file: Option<usize>, offset: usize) {
+        self.map.segments.push(Segment {
+            start: self.output.len() as u32,
+            file,
+            offset: offset as u32,
+        });
+    }
+
+    fn defined(&self, name: &str) -> Condition {
+        self.macros.get(name).copied().unwrap_or(Condition::False)
+    }
+
+    /// Evaluates the condition of an `#if` or `#elif` directive.
+    fn evaluate(&self, expression: &str) -> Condition {
+        let mut rest = expression.trim();
+        match self.evaluate_or(&mut rest) {
+            Some(condition) if rest.is_empty() => condition,
+            _ => Condition::Unknown,
+        }
+    }
+
+    fn evaluate_or(&self, rest: &mut &str) -> Option<Condition> {
+        let mut condition = self.evaluate_and(rest)?;
+        while let Some(next) = rest.strip_prefix("||") {
+            *rest = next.trim_start();
+            condition = condition.or(self.evaluate_and(rest)?);
+        }
+        Some(condition)
+    }
+
+    fn evaluate_and(&self, rest: &mut &str) -> Option<Condition> {
+        let mut condition = self.evaluate_unary(rest)?;
+        while let Some(next) = rest.strip_prefix("&&") {
+            *rest = next.trim_start();
+            condition = condition.and(self.evaluate_unary(rest)?);
+        }
+        Some(condition)
+    }
+
+    fn evaluate_unary(&self, rest: &mut &str) -> Option<Condition> {
+        let condition = if let Some(next) = rest.strip_prefix('!') {
+            *rest = next.trim_start();
+            self.evaluate_unary(rest)?.not()
+        } else if let Some(next) = rest.strip_prefix('(') {
+            *rest = next.trim_start();
+            let condition = self.evaluate_or(rest)?;
+            *rest = rest.strip_prefix(')')?;
+            condition
+        } else {
+            let (token, next) = rest.split_at(identifier_len(rest));
+            *rest = next.trim_start();
+            if token == "defined" {
+                let parenthesized = rest.strip_prefix('(');
+                if let Some(next) = parenthesized {
+                    *rest = next.trim_start();
+                }
+                let (name, next) = rest.split_at(identifier_len(rest));
+                *rest = next.trim_start();
+                if parenthesized.is_some() {
+                    *rest = rest.strip_prefix(')')?;
+                }
+                if name.is_empty() {
+                    return None;
+                }
+                self.defined(name)
+            } else if token.starts_with(|c: char| c.is_ascii_digit()) {
+                let digits = token.trim_end_matches(['u', 'U']);
+                let value = match digits.strip_prefix("0x").or(digits.strip_prefix("0X")) {
+                    Some(hex) => u64::from_str_radix(hex, 16),
+                    None => digits.parse(),
+                };
+                match value.ok()? {
+                    0 => Condition::False,
+                    _ => Condition::True,
+                }
+            } else {
+                // The value of a macro or an unsupported operator
+                return None;
+            }
+        };
+        *rest = rest.trim_start();
+        Some(condition)
+    }
+
+    /// Tracks the conditional blocks and the macros defined in the active
+    /// regions, for a `directive` other than `#include` in a region that is
+    /// `active`.
+    fn conditional_directive(
+        &mut self,
+        directive: &str,
+        rest: &str,
+        active: Condition,
+        conditionals: &mut Vec<Conditional>,
+    ) {
+        match directive {
+            "if" | "ifdef" | "ifndef" => {
+                let condition = match directive {
+                    "if" => self.evaluate(rest),
+                    _ => {
+                        let defined = macro_name(rest, true)
+                            .map_or(Condition::Unknown, |name| self.defined(name));
+                        match directive {
+                            "ifdef" => defined,
+                            _ => defined.not(),
+                        }
+                    }
+                };
+                let mut conditional = Conditional {
+                    enclosing: active,
+                    taken: Condition::False,
+                    active,
+                };
+                conditional.branch(condition);
+                conditionals.push(conditional);
+            }
+            "elif" => {
+                let condition = self.evaluate(rest);
+                if let Some(conditional) = conditionals.last_mut() {
+                    conditional.branch(condition);
+                }
+            }
+            "else" => {
+                if let Some(conditional) = conditionals.last_mut() {
+                    conditional.branch(Condition::True);
+                }
+            }
+            "endif" => {
+                conditionals.pop();
+            }
+            "define" | "undef" if active != Condition::False => {
+                let Some(name) = macro_name(rest, directive == "undef") else {
+                    return;
+                };
+                let defined = match (active, directive) {
+                    (Condition::True, "define") => Condition::True,
+                    (Condition::True, _) => Condition::False,
+                    _ => Condition::Unknown,
+                };
+                self.macros.insert(name.to_string(), defined);
+            }
+            _ => {}
+        }
+    }
+
+    fn expand(&mut self, file: Option<usize>, source: &str) {
+        let includer = match file {
+            Some(file) => self.map.files[file].name.clone(),
+            None => String::new(),
+        };
+
+        self.push_segment(file, 0);
+
+        let mut in_comment = false;
+        let mut conditionals: Vec<Conditional> = Vec::new();
+        let mut line_start = 0;
+        while line_start < source.len() {
+            let line_end = source[line_start..]
+                .find('\n')
+                .map_or(source.len(), |i| line_start + i + 1);
+            let line = &source[line_start..line_end];
+            let (code, comment_start) = strip_comments(line, &mut in_comment);
+
+            let active = conditionals
+                .last()
+                .map_or(Condition::True, |conditional| conditional.active);
+            let include = match parse_directive(&code) {
+                Some(("include", rest)) => {
+                    (active != Condition::False).then(|| parse_include(rest))
+                }
+                Some((directive, rest)) => {
+                    self.conditional_directive(directive, rest, active, &mut conditionals);
+                    self.output.push_str(line);
+                    line_start = line_end;
+                    continue;
+                }
+                None => {
+                    self.output.push_str(line);
+                    line_start = line_end;
+                    continue;
+                }
+            };
+
+            // Blank out the directive itself, this keeps the offsets in the
+            // expanded source in sync with the including file until the
+            // included source is inserted after the line. The preprocessor
+            // would reject the directive even in an inactive region.
+            //
+            // A comment that continues on the next lines is kept, with the
+            // included source inserted before it.
+            let indent = code.len() - code.trim_start().len();
+            let meta = Span::new(
+                (self.output.len() + indent) as u32,
+                (self.output.len() + code.trim_end().len()) as u32,
+            );
+            let rest_start = comment_start.unwrap_or(line.len());
+            for c in line[..rest_start].chars() {
+                blank(&mut self.output, c);
+            }
+            if let Some(comment_start) = comment_start {
+                // The comment is scanned again with the rest of the line.
+                in_comment = false;
+                line_start += comment_start;
+            } else {
+                line_start = line_end;
+            }
+
+            let Some(include) = include else {
+                continue;
+            };
+            let result = include.map_err(ToString::to_string).and_then(|(path, ty)| {
+                if self.stack.len() >= MAX_INCLUDE_DEPTH {
+                    return Err("maximum #include depth exceeded".to_string());
+                }
+                self.resolver.resolve(path, ty, &includer)
+            });
+
+            let included = match result {
+                Ok(included) => included,
+                Err(message) => {
+                    self.errors.push(Error {
+                        kind: ErrorKind::IncludeError(message),
+                        meta,
+                    });
+                    continue;
+                }
+            };
+
+            let index = match self
+                .map
+                .files
+                .iter()
+                .position(|other| other.name == included.name)
+            {
+                Some(index) if self.stack.contains(&index) => {
+                    self.errors.push(Error {
+                        kind: ErrorKind::IncludeError(alloc::format!(
+                            "recursive include of {}",
+                            included.name
+                        )),
+                        meta,
+                    });
+                    continue;
+                }
+                Some(index) => index,
+                None => {
+                    self.map.files.push(included.clone());
+                    self.map.files.len() - 1
+                }
+            };
+
+            if !self.output.ends_with('\n') {
+                self.output.push('\n');
+            }
+
+            self.stack.push(index);
+            self.expand(Some(index), &included.source);
+            self.stack.pop();
+
+            if !self.output.ends_with('\n') {
+                self.output.push('\n');
+            }
+            self.push_segment(file, line_start);
+        }
+    }
+}
+
+/// Replaces all `#include` directives in `source` with the sources returned by
+/// `resolver`.
+///
+/// `defines` are the macros defined before the start of `source`.
+///
+/// Returns the expanded source and the map to translate spans back to the
+/// original files, or all the errors encountered while resolving includes.
+pub fn expand(
+    source: &str,
+    defines: &FastHashMap<String, String>,
+    resolver: &dyn IncludeResolver,
+) -> Result<(String, IncludeMap), (Vec<Error>, IncludeMap)> {
+    let mut expander = Expander {
+        resolver,
+        output: String::with_capacity(source.len()),
+        map: IncludeMap::default(),
+        stack: Vec::new(),
+        macros: defines
+            .keys()
+            .map(|name| (name.clone(), Condition::True))
+            .collect(),
+        errors: Vec::new(),
+    };
+
+    expander.expand(None, source);
+
+    if expander.errors.is_empty() {
+        Ok((expander.output, expander.map))
+    } else {
+        Err((expander.errors, expander.map))
+    }
+}
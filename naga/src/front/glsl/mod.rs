@@ -14,9 +14,10 @@ To begin, take a look at the documentation for the [`Frontend`].
 
 pub use ast::{Precision, Profile};
 pub use error::{Error, ErrorKind, ExpectedToken, ParseErrors};
+pub use include::{IncludeMap, IncludeResolver, IncludeType, IncludedSource};
 pub use token::TokenValue;
 
-use alloc::{string::String, vec::Vec};
+use alloc::{string::String, sync::Arc, vec::Vec};
 
 use crate::{proc::Layouter, FastHashMap, FastHashSet, Handle, Module, ShaderStage, Span, Type};
 use ast::{EntryArg, FunctionDeclaration, GlobalLookup};
@@ -27,6 +28,7 @@ mod builtins;
 mod context;
 mod error;
 mod functions;
+mod include;
 mod lex;
 mod offset;
 mod parser;
@@ -48,7 +50,6 @@ type Result<T> = core::result::Result<T, Error>;
 /// # use naga::front::glsl::Options;
 /// Options::from(ShaderStage::Vertex);
 /// ```
-#[derive(Debug)]
 pub struct Options {
     /// The shader stage in the pipeline.
     pub stage: ShaderStage,
@@ -58,6 +59,24 @@ pub struct Options {
     /// ```
     /// for each key value pair in the map.
     pub defines: FastHashMap<String, String>,
+    /// Resolver for `#include "path"` and `#include <path>` directives, as
+    /// specified by `GL_GOOGLE_include_directive`.
+    ///
+    /// If `None`, any `#include` directive is an error.
+    pub include_resolver: Option<Arc<dyn IncludeResolver>>,
+}
+
+impl core::fmt::Debug for Options {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Options")
+            .field("stage", &self.stage)
+            .field("defines", &self.defines)
+            .field(
+                "include_resolver",
+                &self.include_resolver.as_ref().map(|_| ".."),
+            )
+            .finish()
+    }
 }
 
 impl From<ShaderStage> for Options {
@@ -65,6 +84,7 @@ impl From<ShaderStage> for Options {
         Options {
             stage,
             defines: FastHashMap::default(),
+            include_resolver: None,
         }
     }
 }
@@ -176,6 +196,8 @@ pub struct Frontend {
 
     layouter: Layouter,
 
+    include_map: IncludeMap,
+
     errors: Vec<Error>,
 }
 
@@ -188,6 +210,7 @@ impl Frontend {
         self.global_variables.clear();
         self.entry_args.clear();
         self.layouter.clear();
+        self.include_map = IncludeMap::default();
     }
 
     /// Parses a shader either outputting a shader [`Module`] or a list of
@@ -201,6 +224,24 @@ impl Frontend {
     ) -> core::result::Result<Module, ParseErrors> {
         self.reset(options.stage);
 
+        let expanded;
+        let source = match options.include_resolver {
+            Some(ref resolver) => match include::expand(source, &options.defines, &**resolver) {
+                Ok((source, include_map)) => {
+                    expanded = source;
+                    self.include_map = include_map;
+                    &expanded
+                }
+                Err((errors, include_map)) => {
+                    return Err(ParseErrors {
+                        errors,
+                        include_map,
+                    })
+                }
+            },
+            None => source,
+        };
+
         let lexer = lex::Lexer::new(source, &options.defines);
         let mut ctx = ParsingContext::new(lexer);
 
@@ -209,16 +250,23 @@ impl Frontend {
                 if self.errors.is_empty() {
                     Ok(module)
                 } else {
-                    Err(core::mem::take(&mut self.errors).into())
+                    Err(self.take_errors())
                 }
             }
             Err(e) => {
                 self.errors.push(e);
-                Err(core::mem::take(&mut self.errors).into())
+                Err(self.take_errors())
             }
         }
     }
 
+    fn take_errors(&mut self) -> ParseErrors {
+        ParseErrors {
+            errors: core::mem::take(&mut self.errors),
+            include_map: self.include_map.clone(),
+        }
+    }
+
     /// Returns additional information about the parsed shader which might not
     /// be stored in the [`Module`], see the documentation for
     /// [`ShaderMetadata`] for more information about the returned data.
@@ -231,4 +279,13 @@ impl Frontend {
     pub const fn metadata(&self) -> &ShaderMetadata {
         &self.meta
     }
+
+    /// Returns the [`IncludeMap`] of the last parsed shader, which translates
+    /// the spans in the returned [`Module`] back to the files they came from.
+    ///
+    /// The map is empty if no [`IncludeResolver`] was provided or the shader
+    /// didn't include any files.
+    pub const fn include_map(&self) -> &IncludeMap {
+        &self.include_map
+    }
 }
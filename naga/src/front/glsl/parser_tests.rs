@@ -1,4 +1,5 @@
-use alloc::{borrow::ToOwned, vec};
+use alloc::{borrow::ToOwned, sync::Arc, vec};
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 use pp_rs::token::PreprocessorError;
 
@@ -7,7 +8,7 @@ use super::{
     error::ExpectedToken,
     error::{Error, ErrorKind, ParseErrors},
     token::TokenValue,
-    Frontend, Options, Span,
+    Frontend, IncludeMap, IncludeType, IncludedSource, Options, Span,
 };
 use crate::ShaderStage;
 
@@ -32,6 +33,7 @@ fn version() {
                 kind: ErrorKind::InvalidVersion(99000),
                 meta: Span::new(9, 14)
             }],
+            include_map: IncludeMap::default(),
         },
     );
 
@@ -47,7 +49,8 @@ fn version() {
             errors: vec![Error {
                 kind: ErrorKind::InvalidVersion(449),
                 meta: Span::new(9, 12)
-            }]
+            }],
+            include_map: IncludeMap::default(),
         },
     );
 
@@ -63,7 +66,8 @@ fn version() {
             errors: vec![Error {
                 kind: ErrorKind::InvalidProfile("smart".into()),
                 meta: Span::new(13, 18),
-            }]
+            }],
+            include_map: IncludeMap::default(),
         },
     );
 
@@ -88,7 +92,8 @@ fn version() {
                     ),
                     meta: Span::new(28, 35)
                 }
-            ]
+            ],
+            include_map: IncludeMap::default(),
         },
     );
 
@@ -465,7 +470,8 @@ fn functions() {
             errors: vec![Error {
                 kind: ErrorKind::SemanticError("Function already defined".into()),
                 meta: Span::new(134, 152),
-            }]
+            }],
+            include_map: IncludeMap::default(),
         },
     );
 
@@ -644,7 +650,8 @@ fn implicit_conversions() {
             errors: vec![Error {
                 kind: ErrorKind::SemanticError("Unknown function \'test\'".into()),
                 meta: Span::new(156, 165),
-            }]
+            }],
+            include_map: IncludeMap::default(),
         },
     );
 
@@ -668,7 +675,8 @@ fn implicit_conversions() {
             errors: vec![Error {
                 kind: ErrorKind::SemanticError("Ambiguous best function for \'test\'".into()),
                 meta: Span::new(158, 165),
-            }]
+            }],
+            include_map: IncludeMap::default(),
         }
     );
 }
@@ -860,3 +868,251 @@ fn expressions() {
         )
         .unwrap();
 }
+
+#[test]
+fn includes() {
+    use alloc::{format, string::ToString, vec::Vec};
+
+    let mut frontend = Frontend::default();
+    let mut options = Options::from(ShaderStage::Vertex);
+    options.include_resolver = Some(Arc::new(
+        |path: &str, ty: IncludeType, includer: &str| match (path, ty) {
+            ("common.glsl", IncludeType::Relative) => Ok(IncludedSource {
+                name: "lib/common.glsl".to_string(),
+                source: "#include <math.glsl>\nfloat twice(float x) { return x * TWO; }"
+                    .to_string(),
+            }),
+            ("math.glsl", IncludeType::Standard) => {
+                assert_eq!(includer, "lib/common.glsl");
+                Ok(IncludedSource {
+                    name: "std/math.glsl".to_string(),
+                    source: "#define TWO 2.0\n".to_string(),
+                })
+            }
+            ("broken.glsl", _) => Ok(IncludedSource {
+                name: "broken.glsl".to_string(),
+                source: "float broken() { return x; }\n".to_string(),
+            }),
+            ("self.glsl", _) => Ok(IncludedSource {
+                name: "self.glsl".to_string(),
+                source: "#include \"self.glsl\"\n".to_string(),
+            }),
+            _ => Err(format!("{path} not found")),
+        },
+    ));
+
+    let module = frontend
+        .parse(
+            &options,
+            r#"
+        #version 450
+        #include "common.glsl"
+        void main() {
+            gl_Position = vec4(twice(1.0));
+        }
+        "#,
+        )
+        .unwrap();
+    assert!(module
+        .functions
+        .iter()
+        .any(|(_, function)| function.name.as_deref() == Some("twice")));
+    assert_eq!(
+        frontend
+            .include_map()
+            .files()
+            .iter()
+            .map(|file| file.name.as_str())
+            .collect::<Vec<_>>(),
+        ["lib/common.glsl", "std/math.glsl"]
+    );
+
+    // Errors in included files are mapped back to them
+    let source = "#version 450\n#include \"broken.glsl\"\nvoid main() {}\n";
+    let errors = frontend.parse(&options, source).unwrap_err();
+    assert_eq!(errors.errors.len(), 1);
+    let (file, span) = errors.include_map.resolve(errors.errors[0].meta);
+    assert_eq!(file.unwrap().name, "broken.glsl");
+    assert_eq!(span, Span::new(24, 25));
+    let message = errors.emit_to_string(source);
+    assert!(message.contains("broken.glsl:1:25"), "{message}");
+    let (file, location) = errors.location(0, source).unwrap();
+    assert_eq!(file.unwrap().name, "broken.glsl");
+    assert_eq!((location.line_number, location.line_position), (1, 25));
+
+    // Errors after an include are located in the shader that was parsed
+    let source = "#version 450\n#include \"common.glsl\"\nvoid main() { y; }\n";
+    let errors = frontend.parse(&options, source).unwrap_err();
+    let (file, location) = errors.location(0, source).unwrap();
+    assert!(file.is_none());
+    assert_eq!((location.line_number, location.line_position), (3, 15));
+    let message = errors.emit_to_string(source);
+    assert!(message.contains("glsl:3:15"), "{message}");
+    assert_eq!(errors.location(1, source), None);
+    // The span in the expanded source lies past the end of the shader
+    assert_eq!(errors.errors[0].location(source), None);
+
+    // Unresolved and recursive includes
+    let errors = frontend
+        .parse(&options, "#version 450\n  #include <missing.glsl>\n")
+        .unwrap_err();
+    assert_eq!(
+        errors.errors,
+        [Error {
+            kind: ErrorKind::IncludeError("missing.glsl not found".into()),
+            meta: Span::new(15, 38),
+        }]
+    );
+    let errors = frontend
+        .parse(&options, "#version 450\n#include \"self.glsl\"\n")
+        .unwrap_err();
+    assert_eq!(
+        errors.errors[0].kind,
+        ErrorKind::IncludeError("recursive include of self.glsl".into())
+    );
+
+    // Without a resolver the directive is left to the preprocessor
+    frontend
+        .parse(
+            &Options::from(ShaderStage::Vertex),
+            "#version 450\n#include \"common.glsl\"\nvoid main() {}\n",
+        )
+        .unwrap_err();
+}
+
+/// Returns options with a resolver for `common.glsl`, which has an include
+/// guard and includes `math.glsl`, and the number of times `math.glsl` was
+/// resolved.
+fn include_options() -> (Options, Arc<AtomicUsize>) {
+    use alloc::{format, string::ToString};
+
+    let resolved = Arc::new(AtomicUsize::new(0));
+    let counter = resolved.clone();
+    let mut options = Options::from(ShaderStage::Vertex);
+    options.include_resolver = Some(Arc::new(
+        move |path: &str, _: IncludeType, _: &str| match path {
+            "common.glsl" => Ok(IncludedSource {
+                name: "common.glsl".to_string(),
+                source: "#ifndef COMMON\n#define COMMON\n#include \"math.glsl\"\n#endif\n"
+                    .to_string(),
+            }),
+            "math.glsl" => {
+                counter.fetch_add(1, Ordering::Relaxed);
+                Ok(IncludedSource {
+                    name: "math.glsl".to_string(),
+                    source: "float one() { return 1.0; }\n".to_string(),
+                })
+            }
+            _ => Err(format!("{path} not found")),
+        },
+    ));
+    (options, resolved)
+}
+
+#[test]
+fn includes_in_comments() {
+    let mut frontend = Frontend::default();
+    let (options, _) = include_options();
+
+    let module = frontend
+        .parse(
+            &options,
+            r#"
+        #version 450
+        // #include "line.glsl"
+        /* #include "block.glsl"
+        #include "block.glsl"
+           #include <block.glsl> */
+        /* a comment */ #include "common.glsl" /* a comment
+        #include "block.glsl"
+        */
+        void main() {
+            gl_Position = vec4(one());
+        }
+        "#,
+        )
+        .unwrap();
+    assert!(module
+        .functions
+        .iter()
+        .any(|(_, function)| function.name.as_deref() == Some("one")));
+
+    // The span of the directive doesn't include the comments
+    let source = "#version 450\n/**/ #include \"missing.glsl\" // a comment\n";
+    let errors = frontend.parse(&options, source).unwrap_err();
+    assert_eq!(
+        errors.errors,
+        [Error {
+            kind: ErrorKind::IncludeError("missing.glsl not found".into()),
+            meta: Span::new(18, 41),
+        }]
+    );
+}
+
+#[test]
+fn includes_in_conditionals() {
+    use alloc::{string::ToString, vec::Vec};
+
+    let mut frontend = Frontend::default();
+    let (mut options, resolved) = include_options();
+    options
+        .defines
+        .insert("DEFINED".to_string(), "1".to_string());
+
+    frontend
+        .parse(
+            &options,
+            r#"
+        #version 450
+        #if 0
+        #include "missing.glsl"
+        #elif defined(UNDEFINED) || !defined DEFINED
+        #include "missing.glsl"
+        #else
+        #include "common.glsl"
+        #endif
+        #include "common.glsl"
+        #ifdef UNDEFINED
+        #  include "missing.glsl"
+        #  if 1
+        #  include "missing.glsl"
+        #  endif
+        #endif
+        #ifndef DEFINED
+        #include "missing.glsl"
+        #endif
+        #undef DEFINED
+        #if (1 && !defined(DEFINED)) && 0x0u
+        #include "missing.glsl"
+        #endif
+        void main() {
+            gl_Position = vec4(one());
+        }
+        "#,
+        )
+        .unwrap();
+    assert_eq!(
+        frontend
+            .include_map()
+            .files()
+            .iter()
+            .map(|file| file.name.as_str())
+            .collect::<Vec<_>>(),
+        ["common.glsl", "math.glsl"]
+    );
+    // The second time `common.glsl` is included, its include guard is defined
+    assert_eq!(resolved.load(Ordering::Relaxed), 1);
+
+    // Conditions depending on the values of macros can't be evaluated, so
+    // the includes they guard are expanded
+    let errors = frontend
+        .parse(
+            &options,
+            "#version 450\n#define ZERO 0\n#if ZERO\n#include \"missing.glsl\"\n#endif\n",
+        )
+        .unwrap_err();
+    assert_eq!(
+        errors.errors[0].kind,
+        ErrorKind::IncludeError("missing.glsl not found".into())
+    );
+}
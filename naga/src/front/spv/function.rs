@@ -147,6 +147,7 @@ impl<I: Iterator<Item = u32>> super::Frontend<I> {
                     let _row_id = self.next()?;
                     let _col_id = self.next()?;
                 }
+                spirv::Op::NoLine => fun_inst.expect(1)?,
                 spirv::Op::Label => {
                     // Read the label ID
                     fun_inst.expect(2)?;
@@ -613,97 +614,418 @@ impl BlockContext<'_> {
     }
 
     /// Consumes the `BlockContext` producing a Ir [`Block`](crate::Block)
+    ///
+    /// This is also where [`BodyFragment::Break`]s get resolved. Naga's
+    /// `Break` always exits the innermost `Loop` or `Switch`, while SPIR-V
+    /// allows branching to the merge block of any enclosing selection
+    /// construct, or to the merge block of the innermost loop from within a
+    /// nested switch. So:
+    ///
+    /// - Exits from a selection construct across nothing but `If`s without
+    ///   merge blocks simply fall off the end of the `If`, since there's nothing
+    ///   after those.
+    ///
+    /// - Other selection constructs that are exited from within are wrapped in
+    ///   a `Switch` with only a default case, so they can be exited with a
+    ///   `Break`.
+    ///
+    /// - Exits that need to cross another `Switch` (or wrapped selection) first
+    ///   set a flag and `Break` out of it. The flag is checked right after that
+    ///   construct, to continue on towards the target from there.
+    ///
+    /// [`BodyFragment::Break`]: super::BodyFragment::Break
     fn lower(mut self) -> crate::Block {
-        fn lower_impl(
-            blocks: &mut crate::FastHashMap<spirv::Word, crate::Block>,
-            bodies: &[super::Body],
-            body_idx: BodyIndex,
-        ) -> crate::Block {
-            let mut block = crate::Block::new();
-
-            for item in bodies[body_idx].data.iter() {
-                match *item {
-                    super::BodyFragment::BlockId(id) => block.append(blocks.get_mut(&id).unwrap()),
-                    super::BodyFragment::If {
+        let bodies = core::mem::take(&mut self.bodies);
+
+        let mut wrapped = crate::FastHashSet::default();
+        find_wrapped_selections(&bodies, 0, &mut Vec::new(), &mut wrapped);
+
+        let mut lowerer = Lowerer {
+            ctx: self,
+            bodies: &bodies,
+            wrapped,
+            constructs: Vec::new(),
+            flags: Default::default(),
+        };
+        lowerer.lower_body(0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConstructKind {
+    If,
+    Loop,
+    Switch,
+}
+
+/// A structured control flow construct enclosing the code being lowered.
+struct Construct {
+    kind: ConstructKind,
+
+    /// The label of the construct's merge block, if any.
+    merge: Option<spirv::Word>,
+
+    /// The merge blocks of enclosing constructs that code inside this one
+    /// exits to, via [`Lowerer::flags`].
+    exits: Vec<spirv::Word>,
+}
+
+/// Collects the merge blocks of the selection constructs that are exited from
+/// within other constructs with merge blocks, and so must be wrapped in a
+/// `Switch` to be able to `Break` out of them.
+fn find_wrapped_selections(
+    bodies: &[super::Body],
+    body_idx: BodyIndex,
+    constructs: &mut Vec<(ConstructKind, Option<spirv::Word>)>,
+    wrapped: &mut crate::FastHashSet<spirv::Word>,
+) {
+    for item in bodies[body_idx].data.iter() {
+        match *item {
+            super::BodyFragment::BlockId(_) | super::BodyFragment::Continue => {}
+            super::BodyFragment::If {
+                accept,
+                reject,
+                merge,
+                ..
+            } => {
+                constructs.push((ConstructKind::If, merge));
+                find_wrapped_selections(bodies, accept, constructs, wrapped);
+                find_wrapped_selections(bodies, reject, constructs, wrapped);
+                constructs.pop();
+            }
+            super::BodyFragment::Loop {
+                merge,
+                body,
+                continuing,
+                ..
+            } => {
+                constructs.push((ConstructKind::Loop, Some(merge)));
+                find_wrapped_selections(bodies, body, constructs, wrapped);
+                find_wrapped_selections(bodies, continuing, constructs, wrapped);
+                constructs.pop();
+            }
+            super::BodyFragment::Switch {
+                ref cases,
+                default,
+                merge,
+                ..
+            } => {
+                constructs.push((ConstructKind::Switch, merge));
+                for &(_, case) in cases {
+                    find_wrapped_selections(bodies, case, constructs, wrapped);
+                }
+                find_wrapped_selections(bodies, default, constructs, wrapped);
+                constructs.pop();
+            }
+            super::BodyFragment::Break(target) => {
+                let Some(index) = constructs
+                    .iter()
+                    .rposition(|&(_, merge)| merge == Some(target))
+                else {
+                    continue;
+                };
+                let falls_through = constructs[index + 1..]
+                    .iter()
+                    .all(|&(kind, merge)| kind == ConstructKind::If && merge.is_none());
+                if constructs[index].0 == ConstructKind::If && !falls_through {
+                    wrapped.insert(target);
+                }
+            }
+        }
+    }
+}
+
+/// Assembles the [`Statement`] tree from a [`BlockContext`]'s bodies.
+///
+/// See [`BlockContext::lower`] for details.
+///
+/// [`Statement`]: crate::Statement
+struct Lowerer<'a, 'function> {
+    ctx: BlockContext<'function>,
+    bodies: &'a [super::Body],
+
+    /// The merge blocks of the selection constructs to wrap in a `Switch`.
+    wrapped: crate::FastHashSet<spirv::Word>,
+
+    /// The constructs enclosing the code being lowered, innermost last.
+    constructs: Vec<Construct>,
+
+    /// Local variables flagging exits to the merge block used as key that
+    /// are in progress.
+    flags: crate::FastHashMap<spirv::Word, Handle<crate::LocalVariable>>,
+}
+
+impl Lowerer<'_, '_> {
+    fn lower_body(&mut self, body_idx: BodyIndex) -> crate::Block {
+        let mut block = crate::Block::new();
+
+        for item in self.bodies[body_idx].data.iter() {
+            match *item {
+                super::BodyFragment::BlockId(id) => {
+                    block.append(self.ctx.blocks.get_mut(&id).unwrap())
+                }
+                super::BodyFragment::If {
+                    condition,
+                    accept,
+                    reject,
+                    merge,
+                } => {
+                    self.enter(ConstructKind::If, merge);
+                    let accept = self.lower_body(accept);
+                    let reject = self.lower_body(reject);
+
+                    let mut statement = crate::Statement::If {
                         condition,
                         accept,
                         reject,
-                    } => {
-                        let accept = lower_impl(blocks, bodies, accept);
-                        let reject = lower_impl(blocks, bodies, reject);
-
-                        block.push(
-                            crate::Statement::If {
-                                condition,
-                                accept,
-                                reject,
-                            },
+                    };
+                    if merge.is_some_and(|merge| self.wrapped.contains(&merge)) {
+                        let selector = self.ctx.expressions.append(
+                            crate::Expression::Literal(crate::Literal::I32(0)),
                             crate::Span::default(),
-                        )
+                        );
+                        statement = crate::Statement::Switch {
+                            selector,
+                            cases: vec![crate::SwitchCase {
+                                value: crate::SwitchValue::Default,
+                                body: crate::Block::from_vec(vec![statement]),
+                                fall_through: false,
+                            }],
+                        };
                     }
-                    super::BodyFragment::Loop {
-                        body,
-                        continuing,
-                        break_if,
-                    } => {
-                        let body = lower_impl(blocks, bodies, body);
-                        let continuing = lower_impl(blocks, bodies, continuing);
 
-                        block.push(
-                            crate::Statement::Loop {
+                    self.exit(&mut block, statement);
+                }
+                super::BodyFragment::Loop {
+                    merge,
+                    body,
+                    continuing,
+                    break_if,
+                } => {
+                    self.enter(ConstructKind::Loop, Some(merge));
+                    let body = self.lower_body(body);
+                    let continuing = self.lower_body(continuing);
+
+                    self.exit(
+                        &mut block,
+                        crate::Statement::Loop {
+                            body,
+                            continuing,
+                            break_if,
+                        },
+                    );
+                }
+                super::BodyFragment::Switch {
+                    selector,
+                    ref cases,
+                    default,
+                    merge,
+                } => {
+                    self.enter(ConstructKind::Switch, merge);
+                    let mut ir_cases: Vec<_> = cases
+                        .iter()
+                        .map(|&(value, body_idx)| {
+                            let body = self.lower_body(body_idx);
+
+                            // Handle simple cases that would make a fallthrough statement unreachable code
+                            let fall_through = body.last().is_none_or(|s| !s.is_terminator());
+
+                            crate::SwitchCase {
+                                value: crate::SwitchValue::I32(value),
                                 body,
-                                continuing,
-                                break_if,
-                            },
-                            crate::Span::default(),
-                        )
-                    }
-                    super::BodyFragment::Switch {
-                        selector,
-                        ref cases,
-                        default,
-                    } => {
-                        let mut ir_cases: Vec<_> = cases
-                            .iter()
-                            .map(|&(value, body_idx)| {
-                                let body = lower_impl(blocks, bodies, body_idx);
-
-                                // Handle simple cases that would make a fallthrough statement unreachable code
-                                let fall_through = body.last().is_none_or(|s| !s.is_terminator());
-
-                                crate::SwitchCase {
-                                    value: crate::SwitchValue::I32(value),
-                                    body,
-                                    fall_through,
-                                }
-                            })
-                            .collect();
-                        ir_cases.push(crate::SwitchCase {
-                            value: crate::SwitchValue::Default,
-                            body: lower_impl(blocks, bodies, default),
-                            fall_through: false,
-                        });
+                                fall_through,
+                            }
+                        })
+                        .collect();
+                    ir_cases.push(crate::SwitchCase {
+                        value: crate::SwitchValue::Default,
+                        body: self.lower_body(default),
+                        fall_through: false,
+                    });
 
-                        block.push(
-                            crate::Statement::Switch {
-                                selector,
-                                cases: ir_cases,
-                            },
-                            crate::Span::default(),
-                        )
-                    }
-                    super::BodyFragment::Break => {
-                        block.push(crate::Statement::Break, crate::Span::default())
-                    }
-                    super::BodyFragment::Continue => {
-                        block.push(crate::Statement::Continue, crate::Span::default())
-                    }
+                    self.exit(
+                        &mut block,
+                        crate::Statement::Switch {
+                            selector,
+                            cases: ir_cases,
+                        },
+                    );
+                }
+                super::BodyFragment::Break(target) => {
+                    self.lower_break(target, &mut block, false);
+                }
+                super::BodyFragment::Continue => {
+                    block.push(crate::Statement::Continue, crate::Span::default())
                 }
             }
+        }
+
+        block
+    }
 
-            block
+    fn enter(&mut self, kind: ConstructKind, merge: Option<spirv::Word>) {
+        self.constructs.push(Construct {
+            kind,
+            merge,
+            exits: Vec::new(),
+        });
+    }
+
+    /// Pops the innermost construct, pushing its `statement` to `block`.
+    ///
+    /// If code inside the construct exits to an enclosing one, this also
+    /// resets the flags for those exits beforehand, and continues them
+    /// afterwards.
+    fn exit(&mut self, block: &mut crate::Block, statement: crate::Statement) {
+        let construct = self.constructs.pop().unwrap();
+
+        let mut checks = Vec::with_capacity(construct.exits.len());
+        for &target in construct.exits.iter() {
+            let mut accept = crate::Block::new();
+            let relayed = self.lower_break(target, &mut accept, true);
+            let pointer = self.flag_pointer(target);
+
+            // If the exit has to be relayed further out, the flag is reset
+            // before the outermost construct it crosses.
+            if !relayed {
+                let value = self.ctx.expressions.append(
+                    crate::Expression::Literal(crate::Literal::Bool(false)),
+                    crate::Span::default(),
+                );
+                block.push(
+                    crate::Statement::Store { pointer, value },
+                    crate::Span::default(),
+                );
+            }
+            checks.push((pointer, accept));
+        }
+
+        block.push(statement, crate::Span::default());
+
+        for (pointer, accept) in checks {
+            if accept.is_empty() {
+                continue;
+            }
+
+            let start = self.ctx.expressions.len();
+            let condition = self
+                .ctx
+                .expressions
+                .append(crate::Expression::Load { pointer }, crate::Span::default());
+            block.push(
+                crate::Statement::Emit(self.ctx.expressions.range_from(start)),
+                crate::Span::default(),
+            );
+            block.push(
+                crate::Statement::If {
+                    condition,
+                    accept,
+                    reject: crate::Block::new(),
+                },
+                crate::Span::default(),
+            );
         }
+    }
 
-        lower_impl(&mut self.blocks, &self.bodies, 0)
+    /// Lowers a [`BodyFragment::Break`] to `target`, pushing the statements
+    /// to `block`.
+    ///
+    /// If `flagged` is set, the flag for exits to `target` is already set.
+    ///
+    /// Returns `true` if this exit is relayed through the flag.
+    ///
+    /// [`BodyFragment::Break`]: super::BodyFragment::Break
+    fn lower_break(
+        &mut self,
+        target: spirv::Word,
+        block: &mut crate::Block,
+        flagged: bool,
+    ) -> bool {
+        let Some(index) = self
+            .constructs
+            .iter()
+            .rposition(|construct| construct.merge == Some(target))
+        else {
+            // A selection construct branching to its own merge block without
+            // introducing an `If` at all.
+            return false;
+        };
+
+        if self.constructs[index].kind == ConstructKind::If
+            && (index == self.constructs.len() - 1 || !self.wrapped.contains(&target))
+        {
+            // This is the end of the `If`'s `accept` or `reject` block, or
+            // there are only `If`s without merge blocks in between, so we can
+            // just fall off their ends.
+            return false;
+        }
+
+        // Find the innermost construct we could `Break` out of.
+        let wrapped = &self.wrapped;
+        let breakable = self.constructs[index + 1..]
+            .iter()
+            .rposition(|construct| match construct.kind {
+                ConstructKind::Loop | ConstructKind::Switch => true,
+                ConstructKind::If => construct
+                    .merge
+                    .is_some_and(|merge| wrapped.contains(&merge)),
+            });
+
+        let relayed = match breakable {
+            Some(breakable) => {
+                // We need to leave the construct in between first, and take
+                // it from there.
+                let construct = &mut self.constructs[index + 1 + breakable];
+                if !construct.exits.contains(&target) {
+                    construct.exits.push(target);
+                }
+                if !flagged {
+                    let pointer = self.flag_pointer(target);
+                    let value = self.ctx.expressions.append(
+                        crate::Expression::Literal(crate::Literal::Bool(true)),
+                        crate::Span::default(),
+                    );
+                    block.push(
+                        crate::Statement::Store { pointer, value },
+                        crate::Span::default(),
+                    );
+                }
+                true
+            }
+            None => false,
+        };
+
+        block.push(crate::Statement::Break, crate::Span::default());
+        relayed
+    }
+
+    /// Returns a pointer to the flag for exits to `target`.
+    fn flag_pointer(&mut self, target: spirv::Word) -> Handle<crate::Expression> {
+        let local = match self.flags.get(&target) {
+            Some(&local) => local,
+            None => {
+                let ty = self.ctx.module.types.insert(
+                    crate::Type {
+                        name: None,
+                        inner: crate::TypeInner::Scalar(crate::Scalar::BOOL),
+                    },
+                    crate::Span::UNDEFINED,
+                );
+                let local = self.ctx.local_arena.append(
+                    crate::LocalVariable {
+                        name: Some(format!("exit_{target}")),
+                        ty,
+                        init: None,
+                    },
+                    crate::Span::default(),
+                );
+                self.flags.insert(target, local);
+                local
+            }
+        };
+        self.ctx.expressions.append(
+            crate::Expression::LocalVariable(local),
+            crate::Span::default(),
+        )
     }
 }
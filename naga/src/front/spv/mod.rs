@@ -39,6 +39,7 @@ use alloc::{borrow::ToOwned, format, string::String, vec, vec::Vec};
 use core::{convert::TryInto, mem, num::NonZeroU32};
 
 use half::f16;
+use hashbrown::hash_map::Entry;
 use petgraph::graphmap::GraphMap;
 
 use super::atomic_upgrade::Upgrades;
@@ -92,6 +93,7 @@ pub const SUPPORTED_EXTENSIONS: &[&str] = &[
     "SPV_KHR_multiview",
     "SPV_EXT_shader_atomic_float_add",
     "SPV_KHR_16bit_storage",
+    "SPV_KHR_terminate_invocation",
 ];
 pub const SUPPORTED_EXT_SETS: &[&str] = &["GLSL.std.450"];
 
@@ -412,8 +414,18 @@ enum BodyFragment {
         condition: Handle<crate::Expression>,
         accept: BodyIndex,
         reject: BodyIndex,
+
+        /// The label of the selection construct's merge block, if this `If`
+        /// was introduced by an `OpSelectionMerge`.
+        ///
+        /// Conditional branches without a merge block of their own only ever
+        /// exit enclosing constructs, so nothing can follow them.
+        merge: Option<spirv::Word>,
     },
     Loop {
+        /// The label of the loop's merge block.
+        merge: spirv::Word,
+
         /// The body of the loop. Its [`Body::parent`] is the block containing
         /// this `Loop` fragment.
         body: BodyIndex,
@@ -431,8 +443,20 @@ enum BodyFragment {
         selector: Handle<crate::Expression>,
         cases: Vec<(i32, BodyIndex)>,
         default: BodyIndex,
+
+        /// The label of the switch construct's merge block.
+        merge: Option<spirv::Word>,
     },
-    Break,
+
+    /// A branch to the merge block with the given label, exiting the
+    /// structured control flow construct it belongs to.
+    ///
+    /// This is not necessarily the innermost construct: SPIR-V allows
+    /// branching to the merge block of an enclosing selection construct, or
+    /// breaking out of a loop from within a nested switch. Depending on the
+    /// constructs in between, `lower` turns this into nothing at all, a
+    /// `Break`, or a `Break` guarded by a flag. See [`BlockContext::lower`].
+    Break(spirv::Word),
     Continue,
 }
 
@@ -1474,16 +1498,16 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
     /// `block_ctx.body_for_label` to tell us which `Body` it contributes to.
     fn next_block(&mut self, block_id: spirv::Word, ctx: &mut BlockContext) -> Result<(), Error> {
         // Extend `body` with the correct form for a branch to `target`.
-        fn merger(body: &mut Body, target: &MergeBlockInformation) {
+        fn merger(body: &mut Body, target_id: spirv::Word, target: &MergeBlockInformation) {
             body.data.push(match *target {
                 MergeBlockInformation::LoopContinue => BodyFragment::Continue,
-                MergeBlockInformation::LoopMerge | MergeBlockInformation::SwitchMerge => {
-                    BodyFragment::Break
-                }
 
-                // Finishing a selection merge means just falling off the end of
-                // the `accept` or `reject` block of the `If` statement.
-                MergeBlockInformation::SelectionMerge => return,
+                // Finishing a selection merge usually means just falling off
+                // the end of the `accept` or `reject` block of the `If`
+                // statement, but `lower` takes care of that.
+                MergeBlockInformation::LoopMerge
+                | MergeBlockInformation::SwitchMerge
+                | MergeBlockInformation::SelectionMerge => BodyFragment::Break(target_id),
             })
         }
 
@@ -3307,7 +3331,8 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                         },
                     );
                 }
-                Op::Kill => {
+                // `OpTerminateInvocation` supersedes `OpKill` in SPIR-V 1.6.
+                Op::Kill | Op::TerminateInvocation => {
                     inst.expect(1)?;
                     break Some(crate::Statement::Kill);
                 }
@@ -3346,7 +3371,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                         let body = &mut ctx.bodies[body_idx];
                         body.data.push(BodyFragment::BlockId(block_id));
 
-                        merger(body, info);
+                        merger(body, target_id, info);
 
                         return Ok(());
                     }
@@ -3417,6 +3442,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                             body: loop_body_idx,
                             continuing: loop_continuing_idx,
                             break_if: ref mut break_if_slot @ None,
+                            ..
                         }] if body_idx == loop_continuing_idx => {
                             // Try both orderings of break-vs-backedge, because
                             // SPIR-V is symmetrical here, unlike WGSL `break if`.
@@ -3489,6 +3515,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                                 true => &mut ctx.bodies[body_idx],
                                 false => &mut accept_block,
                             },
+                            true_target.label_id,
                             &info,
                         )
                     } else {
@@ -3514,7 +3541,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                     let mut reject_block = Body::with_parent(body_idx);
 
                     if let Some(info) = false_target.merge_info {
-                        merger(&mut reject_block, &info)
+                        merger(&mut reject_block, false_target.label_id, &info)
                     } else {
                         let prev = ctx.body_for_label.insert(false_target.label_id, reject);
                         debug_assert!(prev.is_none());
@@ -3527,6 +3554,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                         condition,
                         accept,
                         reject,
+                        merge: selection_merge_block,
                     });
 
                     return Ok(());
@@ -3593,7 +3621,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                         let mut body = Body::with_parent(body_idx);
 
                         if let Some(info) = ctx.mergers.get(&target) {
-                            merger(&mut body, info);
+                            merger(&mut body, target, info);
                         }
 
                         ctx.bodies.push(body);
@@ -3640,6 +3668,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                         selector,
                         cases,
                         default,
+                        merge: selection_merge_block,
                     });
 
                     return Ok(());
@@ -3656,10 +3685,15 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
 
                     // Let subsequent branches to the merge block know that
                     // they've reached the end of the selection construct.
-                    ctx.mergers
-                        .insert(merge_block_id, MergeBlockInformation::SelectionMerge);
-
-                    selection_merge_block = Some(merge_block_id);
+                    //
+                    // Optimizers like to use a loop's continue target as the
+                    // merge block of a selection at the end of its body. Keep
+                    // treating branches to it as `Continue`s then, which are
+                    // correct no matter how deeply they're nested.
+                    if let Entry::Vacant(entry) = ctx.mergers.entry(merge_block_id) {
+                        entry.insert(MergeBlockInformation::SelectionMerge);
+                        selection_merge_block = Some(merge_block_id);
+                    }
                 }
                 Op::LoopMerge => {
                     inst.expect_at_least(4)?;
@@ -3696,6 +3730,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
 
                     let parent_body = &mut ctx.bodies[body_idx];
                     parent_body.data.push(BodyFragment::Loop {
+                        merge: merge_block_id,
                         body: loop_body_idx,
                         continuing: continue_idx,
                         break_if: None,
@@ -6,6 +6,8 @@ use crate::error::replace_control_chars;
 use crate::proc::{Alignment, ConstantEvaluatorError, ResolveError};
 use crate::{Scalar, SourceLocation, Span};
 
+#[cfg(feature = "wgsl-in-imports")]
+use super::import::ImportMap;
 use super::parse::directive::enable_extension::{EnableExtension, UnimplementedEnableExtension};
 use super::parse::directive::language_extension::{
     LanguageExtension, UnimplementedLanguageExtension,
@@ -46,15 +48,30 @@ impl ParseError {
         &self.message
     }
 
-    fn diagnostic(&self) -> Diagnostic<()> {
+    /// Builds the diagnostic for `source`, leaving out the labels that lie
+    /// outside of it, such as those in imported files.
+    fn diagnostic(&self, source: &str) -> Diagnostic<()> {
+        self.diagnostic_with_files(|span| {
+            span.to_range()
+                .filter(|range| source.get(range.clone()).is_some())
+                .map(|range| ((), range))
+        })
+    }
+
+    /// Builds the diagnostic, using `locate` to find the file and range of
+    /// each label.
+    fn diagnostic_with_files<FileId>(
+        &self,
+        locate: impl Fn(Span) -> Option<(FileId, Range<usize>)>,
+    ) -> Diagnostic<FileId> {
         let diagnostic = Diagnostic::error()
             .with_message(self.message.to_string())
             .with_labels(
                 self.labels
                     .iter()
-                    .filter_map(|label| label.0.to_range().map(|range| (label, range)))
-                    .map(|(label, range)| {
-                        Label::primary((), range).with_message(label.1.to_string())
+                    .filter_map(|label| locate(label.0).map(|location| (label, location)))
+                    .map(|(label, (file_id, range))| {
+                        Label::primary(file_id, range).with_message(label.1.to_string())
                     })
                     .collect(),
             )
@@ -91,8 +108,13 @@ impl ParseError {
             }
         }
 
-        term::emit(&mut writer.lock(), &config, &files, &self.diagnostic())
-            .expect("cannot write error");
+        term::emit(
+            &mut writer.lock(),
+            &config,
+            &files,
+            &self.diagnostic(source),
+        )
+        .expect("cannot write error");
     }
 
     /// Emits a summary of the error to a string.
@@ -110,17 +132,84 @@ impl ParseError {
         let config = term::Config::default();
 
         let mut writer = crate::error::DiagnosticBuffer::new();
-        term::emit(writer.inner_mut(), &config, &files, &self.diagnostic())
-            .expect("cannot write error");
+        term::emit(
+            writer.inner_mut(),
+            &config,
+            &files,
+            &self.diagnostic(source),
+        )
+        .expect("cannot write error");
+        writer.into_string()
+    }
+
+    /// Emits a summary of the error to a string, for a module composed from
+    /// multiple files.
+    ///
+    /// `source` and `path` describe the top level source, the labels in
+    /// imported files are resolved through `imports`.
+    #[cfg(feature = "wgsl-in-imports")]
+    pub fn emit_to_string_with_imports(
+        &self,
+        source: &str,
+        path: &str,
+        imports: &ImportMap,
+    ) -> String {
+        let mut files = codespan_reporting::files::SimpleFiles::new();
+        let root = files.add(path.to_string(), replace_control_chars(source));
+        let imported: Vec<_> = imports
+            .files()
+            .iter()
+            .map(|file| files.add(file.name.clone(), replace_control_chars(&file.source)))
+            .collect();
+        let config = term::Config::default();
+
+        let diagnostic = self.diagnostic_with_files(|span| {
+            let (file, span) = imports.resolve_index(span);
+            let file_id = file.map_or(root, |file| imported[file]);
+            span.to_range().map(|range| (file_id, range))
+        });
+
+        let mut writer = crate::error::DiagnosticBuffer::new();
+        term::emit(writer.inner_mut(), &config, &files, &diagnostic).expect("cannot write error");
         writer.into_string()
     }
 
     /// Returns a [`SourceLocation`] for the first label in the error message.
+    ///
+    /// Returns `None` if the label lies outside of `source`, such as in a file
+    /// imported by it, use [`location_with_imports`] for those.
+    ///
+    /// [`location_with_imports`]: Self::location_with_imports
     pub fn location(&self, source: &str) -> Option<SourceLocation> {
-        self.labels.first().map(|label| label.0.location(source))
+        span_location(self.labels.first()?.0, source)
+    }
+
+    /// Returns a [`SourceLocation`] for the first label in the error message,
+    /// for a module composed from multiple files.
+    ///
+    /// The label is resolved through `imports`, returning the imported file it
+    /// is located in, or `None` if it is located in the top level `source`.
+    #[cfg(feature = "wgsl-in-imports")]
+    pub fn location_with_imports<'a>(
+        &self,
+        source: &'a str,
+        imports: &'a ImportMap,
+    ) -> Option<(Option<&'a super::ImportedSource>, SourceLocation)> {
+        let (file, span) = imports.resolve(self.labels.first()?.0);
+        let source = file.map_or(source, |file| &file.source);
+        Some((file, span_location(span, source)?))
     }
 }
 
+/// Returns the location of `span` in `source`, or `None` if it lies outside of
+/// it.
+fn span_location(span: Span, source: &str) -> Option<SourceLocation> {
+    if let Some(range) = span.to_range() {
+        source.get(range)?;
+    }
+    Some(span.location(source))
+}
+
 impl core::fmt::Display for ParseError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.message)
@@ -388,6 +477,13 @@ pub(crate) enum Error<'a> {
         kind: UnimplementedLanguageExtension,
         span: Span,
     },
+    #[cfg(feature = "wgsl-in-imports")]
+    ImportWithoutResolver(Span),
+    #[cfg(feature = "wgsl-in-imports")]
+    ImportNotResolved {
+        span: Span,
+        message: String,
+    },
     DiagnosticInvalidSeverity {
         severity_control_name_span: Span,
     },
@@ -1247,6 +1343,20 @@ impl<'a> Error<'a> {
                     kind.tracking_issue_num()
                 )],
             },
+            #[cfg(feature = "wgsl-in-imports")]
+            Error::ImportWithoutResolver(span) => ParseError {
+                message: "`import` directives are not enabled".into(),
+                labels: vec![(span, "".into())],
+                notes: vec![
+                    "Imports are a Naga extension to WGSL, which requires setting an `ImportResolver` in the frontend's `Options`.".into(),
+                ],
+            },
+            #[cfg(feature = "wgsl-in-imports")]
+            Error::ImportNotResolved { span, ref message } => ParseError {
+                message: format!("failed to import `{}`", &source[span]),
+                labels: vec![(span, message.clone().into())],
+                notes: vec![],
+            },
             Error::DiagnosticInvalidSeverity {
                 severity_control_name_span,
             } => ParseError {
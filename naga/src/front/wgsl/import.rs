@@ -0,0 +1,230 @@
+/*!
+Composing a module from multiple WGSL files with `import` directives.
+
+This is a naga extension to WGSL, only available with the `wgsl-in-imports`
+feature and when an [`ImportResolver`] is set in
+[`Options::import_resolver`](super::Options::import_resolver).
+
+An `import` directive names another file by a module path:
+
+```wgsl
+import shared::lighting;
+import math;
+
+@fragment
+fn main() -> @location(0) vec4<f32> { return shade(clamp01(vec4(1.0))); }
+```
+
+The resolver maps each path to the source of the imported file, which may in
+turn import other files. Every file is parsed as its own translation unit, with
+its own directives, and the module scope declarations of all of them are then
+linked into a single [`Module`](crate::Module). Files are only ever included
+once, no matter how many times or from where they are imported, so cyclic
+imports are allowed. All declarations share a single namespace.
+
+All spans produced by the frontend, both in errors and in the resulting module,
+refer to the concatenation of all the files. The [`ImportMap`] returned by
+[`Frontend::import_map`](super::Frontend::import_map) translates them back.
+*/
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::ops::Range;
+
+use super::{
+    error::Error,
+    parse::{
+        directive::DirectiveKind,
+        lexer::{Lexer, Token},
+        Parser,
+    },
+    Result,
+};
+use crate::Span;
+
+/// A source file returned by an [`ImportResolver`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportedSource {
+    /// The name of the resolved file.
+    ///
+    /// This is passed as the `importer` to nested imports, used to avoid
+    /// including a file more than once and displayed in error messages, as
+    /// such it should uniquely identify the file (i.e. be a canonical path).
+    pub name: String,
+    /// The contents of the file.
+    pub source: String,
+}
+
+/// Resolves the module paths in `import` directives to source files.
+///
+/// An implementation is provided for closures with the same signature as
+/// [`resolve`](ImportResolver::resolve).
+pub trait ImportResolver: Send + Sync {
+    /// Returns the source imported by `import path;`.
+    ///
+    /// The segments of `path` are separated by `::`, without any whitespace.
+    /// `importer` is the [`name`](ImportedSource::name) of the file containing
+    /// the directive or the empty string for the top level source.
+    fn resolve(&self, path: &str, importer: &str) -> core::result::Result<ImportedSource, String>;
+}
+
+impl<F> ImportResolver for F
+where
+    F: Fn(&str, &str) -> core::result::Result<ImportedSource, String> + Send + Sync,
+{
+    fn resolve(&self, path: &str, importer: &str) -> core::result::Result<ImportedSource, String> {
+        self(path, importer)
+    }
+}
+
+/// Maps spans in a module composed from multiple files back to those files.
+///
+/// If the shader didn't import any files the map is empty and all spans refer
+/// to the top level source.
+#[derive(Clone, Debug, Default)]
+pub struct ImportMap {
+    files: Vec<ImportedSource>,
+    /// For each file in `files`, its byte offset in the composed source.
+    offsets: Vec<u32>,
+}
+
+impl ImportMap {
+    pub(super) const fn new() -> Self {
+        ImportMap {
+            files: Vec::new(),
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if no files were imported.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Returns all the files that were imported, in order of first import.
+    pub fn files(&self) -> &[ImportedSource] {
+        &self.files
+    }
+
+    /// Translates a span in the composed source to the file it originated
+    /// from.
+    ///
+    /// Returns the imported file, or `None` for the top level source, and the
+    /// span relative to the start of that file.
+    pub fn resolve(&self, span: Span) -> (Option<&ImportedSource>, Span) {
+        let (file, span) = self.resolve_index(span);
+        (file.map(|file| &self.files[file]), span)
+    }
+
+    /// Like [`resolve`](Self::resolve) but returns the index of the file in
+    /// [`files`](Self::files).
+    pub(super) fn resolve_index(&self, span: Span) -> (Option<usize>, Span) {
+        let Some(range) = span.to_range() else {
+            return (None, span);
+        };
+        let index = self
+            .offsets
+            .partition_point(|&offset| offset as usize <= range.start);
+        let Some(file) = index.checked_sub(1) else {
+            return (None, span);
+        };
+        let offset = self.offsets[file];
+        let len = self.files[file].source.len() as u32;
+        let start = range.start as u32 - offset;
+        let end = (range.end as u32 - offset).min(len);
+        (Some(file), Span::new(start, end))
+    }
+}
+
+/// The sources of a module composed from multiple files.
+pub(super) struct Composition {
+    /// The concatenation of all the files, starting with the top level source.
+    pub source: String,
+    /// The range of each file in `source`.
+    pub files: Vec<Range<usize>>,
+    pub map: ImportMap,
+}
+
+impl Composition {
+    pub fn new(root: &str) -> Self {
+        Composition {
+            source: root.to_string(),
+            files: alloc::vec![0..root.len()],
+            map: ImportMap::new(),
+        }
+    }
+
+    /// Resolves all `import` directives, transitively, appending the imported
+    /// files to the composition.
+    ///
+    /// On error, the returned [`Error`]'s spans refer to [`Self::source`].
+    pub fn resolve_imports(&mut self, resolver: &dyn ImportResolver) -> Result<'static, ()> {
+        let mut next = 0;
+        while next < self.files.len() {
+            let range = self.files[next].clone();
+            let importer = match next {
+                0 => String::new(),
+                _ => self.map.files[next - 1].name.clone(),
+            };
+            next += 1;
+
+            let file = self.source[range.clone()].to_string();
+            for (path, span) in scan_imports(&file) {
+                let imported = resolver.resolve(&path, &importer).map_err(|message| {
+                    Box::new(Error::ImportNotResolved {
+                        span: Span::new(
+                            (range.start + span.start) as u32,
+                            (range.start + span.end) as u32,
+                        ),
+                        message,
+                    })
+                })?;
+
+                if self.map.files.iter().all(|file| file.name != imported.name) {
+                    // Keep files on separate lines, so that a missing newline at the
+                    // end of a file doesn't affect the next one.
+                    self.source.push('\n');
+                    let start = self.source.len();
+                    self.source.push_str(&imported.source);
+                    self.files.push(start..self.source.len());
+                    self.map.offsets.push(start as u32);
+                    self.map.files.push(imported);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the paths, and their spans, of the `import` directives in `source`.
+///
+/// Scanning stops at the first malformed directive, which is reported when the
+/// file is parsed.
+fn scan_imports(source: &str) -> Vec<(String, Range<usize>)> {
+    let mut lexer = Lexer::new(source, true);
+    let mut imports = Vec::new();
+    while let Ok((ident, _)) = lexer.peek_ident_with_span() {
+        match DirectiveKind::from_ident(ident) {
+            Some(DirectiveKind::Import) => {
+                let _ = lexer.next_ident_with_span();
+                let Ok((path, span)) = Parser::import_path(&mut lexer) else {
+                    break;
+                };
+                imports.push((path, span.to_range().unwrap()));
+            }
+            Some(_) => {
+                // Skip over other directives, none of them contain a `;`.
+                while !lexer.skip(Token::Separator(';')) {
+                    if lexer.next().0 == Token::End {
+                        break;
+                    }
+                }
+            }
+            None => break,
+        }
+    }
+    imports
+}
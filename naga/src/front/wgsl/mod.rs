@@ -5,6 +5,8 @@ Frontend for [WGSL][wgsl] (WebGPU Shading Language).
 */
 
 mod error;
+#[cfg(feature = "wgsl-in-imports")]
+mod import;
 mod index;
 mod lower;
 mod parse;
@@ -12,15 +14,20 @@ mod parse;
 mod tests;
 
 pub use crate::front::wgsl::error::ParseError;
+#[cfg(feature = "wgsl-in-imports")]
+pub use crate::front::wgsl::import::{ImportMap, ImportResolver, ImportedSource};
 pub use crate::front::wgsl::parse::directive::language_extension::{
     ImplementedLanguageExtension, LanguageExtension, UnimplementedLanguageExtension,
 };
 pub use crate::front::wgsl::parse::Options;
 
 use alloc::boxed::Box;
+use core::ops::Range;
 use thiserror::Error;
 
 use crate::front::wgsl::error::Error;
+#[cfg(feature = "wgsl-in-imports")]
+use crate::front::wgsl::import::Composition;
 use crate::front::wgsl::lower::Lowerer;
 use crate::front::wgsl::parse::Parser;
 use crate::Scalar;
@@ -33,6 +40,8 @@ pub(crate) type Result<'a, T> = core::result::Result<T, Box<Error<'a>>>;
 pub struct Frontend {
     parser: Parser,
     options: Options,
+    #[cfg(feature = "wgsl-in-imports")]
+    import_map: ImportMap,
 }
 
 impl Frontend {
@@ -40,21 +49,65 @@ impl Frontend {
         Self {
             parser: Parser::new(),
             options: Options::new(),
+            #[cfg(feature = "wgsl-in-imports")]
+            import_map: ImportMap::new(),
         }
     }
     pub const fn new_with_options(options: Options) -> Self {
         Self {
             parser: Parser::new(),
             options,
+            #[cfg(feature = "wgsl-in-imports")]
+            import_map: ImportMap::new(),
         }
     }
 
+    /// Parses `source` into a module.
+    ///
+    /// With the `wgsl-in-imports` feature, if an `ImportResolver` was set in
+    /// the [`Options`], the files imported by `source` are parsed and linked
+    /// into the same module. The spans in the returned module and error then
+    /// refer to all of the files, use `import_map` to resolve them, for
+    /// example with `ParseError::emit_to_string_with_imports`.
     pub fn parse(&mut self, source: &str) -> core::result::Result<crate::Module, ParseError> {
-        self.inner(source).map_err(|x| x.as_parse_error(source))
+        #[cfg(feature = "wgsl-in-imports")]
+        {
+            self.import_map = ImportMap::new();
+            if let Some(resolver) = self.options.import_resolver.clone() {
+                return self.parse_with_imports(source, &*resolver);
+            }
+        }
+
+        self.inner(source, core::slice::from_ref(&(0..source.len())))
+            .map_err(|x| x.as_parse_error(source))
+    }
+
+    #[cfg(feature = "wgsl-in-imports")]
+    fn parse_with_imports(
+        &mut self,
+        source: &str,
+        resolver: &dyn ImportResolver,
+    ) -> core::result::Result<crate::Module, ParseError> {
+        let mut composition = Composition::new(source);
+        let result = composition.resolve_imports(resolver);
+        self.import_map = composition.map;
+        let source = &composition.source;
+        result
+            .and_then(|()| self.inner(source, &composition.files))
+            .map_err(|x| x.as_parse_error(source))
+    }
+
+    /// Returns the [`ImportMap`] of the last parsed module.
+    ///
+    /// The map is empty if no [`ImportResolver`] was set in the [`Options`]
+    /// or the module didn't import any files.
+    #[cfg(feature = "wgsl-in-imports")]
+    pub const fn import_map(&self) -> &ImportMap {
+        &self.import_map
     }
 
-    fn inner<'a>(&mut self, source: &'a str) -> Result<'a, crate::Module> {
-        let tu = self.parser.parse(source, &self.options)?;
+    fn inner<'a>(&mut self, source: &'a str, files: &[Range<usize>]) -> Result<'a, crate::Module> {
+        let tu = self.parser.parse(source, files, &self.options)?;
         let index = index::Index::generate(&tu)?;
         let module = Lowerer::new(&index).lower(tu)?;
 
@@ -17,12 +17,17 @@ pub(crate) enum DirectiveKind {
     Enable,
     /// A [`language_extension`].
     Requires,
+    /// An `import` of another file, see [`crate::front::wgsl::import`].
+    #[cfg(feature = "wgsl-in-imports")]
+    Import,
 }
 
 impl DirectiveKind {
     const DIAGNOSTIC: &'static str = "diagnostic";
     const ENABLE: &'static str = "enable";
     const REQUIRES: &'static str = "requires";
+    #[cfg(feature = "wgsl-in-imports")]
+    const IMPORT: &'static str = "import";
 
     /// Convert from a sentinel word in WGSL into its associated [`DirectiveKind`], if possible.
     pub fn from_ident(s: &str) -> Option<Self> {
@@ -30,6 +35,8 @@ impl DirectiveKind {
             Self::DIAGNOSTIC => Self::Diagnostic,
             Self::ENABLE => Self::Enable,
             Self::REQUIRES => Self::Requires,
+            #[cfg(feature = "wgsl-in-imports")]
+            Self::IMPORT => Self::Import,
             _ => return None,
         })
     }
@@ -101,6 +108,20 @@ error: expected global declaration, but found a global directive
   │
   = note: global directives are only allowed before global declarations; maybe hoist this closer to the top of the shader module?
 
+";
+                }
+                #[cfg(feature = "wgsl-in-imports")]
+                DirectiveKind::Import => {
+                    directive = "import shared::lighting";
+                    expected_msg = "\
+error: expected global declaration, but found a global directive
+  ┌─ wgsl:2:1
+  │
+2 │ import shared::lighting;
+  │ ^^^^^^ written after first global declaration
+  │
+  = note: global directives are only allowed before global declarations; maybe hoist this closer to the top of the shader module?
+
 ";
                 }
             }
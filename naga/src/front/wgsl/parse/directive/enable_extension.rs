@@ -35,6 +35,13 @@ impl EnableExtensions {
         *field = true;
     }
 
+    /// Add all the enable-extensions requested in `other`.
+    pub(crate) const fn extend(&mut self, other: &Self) {
+        self.dual_source_blending |= other.dual_source_blending;
+        self.f16 |= other.f16;
+        self.clip_distances |= other.clip_distances;
+    }
+
     /// Query whether an enable-extension tracked here has been requested.
     pub(crate) const fn contains(&self, ext: ImplementedEnableExtension) -> bool {
         match ext {
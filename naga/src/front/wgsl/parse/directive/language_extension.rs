@@ -29,7 +29,7 @@ impl LanguageExtension {
                 Self::Implemented(ImplementedLanguageExtension::Packed4x8IntegerDotProduct)
             }
             Self::UNRESTRICTED_POINTER_PARAMETERS => {
-                Self::Unimplemented(UnimplementedLanguageExtension::UnrestrictedPointerParameters)
+                Self::Implemented(ImplementedLanguageExtension::UnrestrictedPointerParameters)
             }
             Self::POINTER_COMPOSITE_ACCESS => {
                 Self::Implemented(ImplementedLanguageExtension::PointerCompositeAccess)
@@ -42,11 +42,7 @@ impl LanguageExtension {
     pub const fn to_ident(self) -> &'static str {
         match self {
             Self::Implemented(kind) => kind.to_ident(),
-            Self::Unimplemented(kind) => match kind {
-                UnimplementedLanguageExtension::UnrestrictedPointerParameters => {
-                    Self::UNRESTRICTED_POINTER_PARAMETERS
-                }
-            },
+            Self::Unimplemented(kind) => match kind {},
         }
     }
 }
@@ -57,6 +53,7 @@ impl LanguageExtension {
 pub enum ImplementedLanguageExtension {
     ReadOnlyAndReadWriteStorageTextures,
     Packed4x8IntegerDotProduct,
+    UnrestrictedPointerParameters,
     PointerCompositeAccess,
 }
 
@@ -65,6 +62,7 @@ impl ImplementedLanguageExtension {
     pub const VARIANTS: &'static [Self] = &[
         Self::ReadOnlyAndReadWriteStorageTextures,
         Self::Packed4x8IntegerDotProduct,
+        Self::UnrestrictedPointerParameters,
         Self::PointerCompositeAccess,
     ];
 
@@ -82,6 +80,9 @@ impl ImplementedLanguageExtension {
             ImplementedLanguageExtension::Packed4x8IntegerDotProduct => {
                 LanguageExtension::PACKED4X8_INTEGER_DOT_PRODUCT
             }
+            ImplementedLanguageExtension::UnrestrictedPointerParameters => {
+                LanguageExtension::UNRESTRICTED_POINTER_PARAMETERS
+            }
             ImplementedLanguageExtension::PointerCompositeAccess => {
                 LanguageExtension::POINTER_COMPOSITE_ACCESS
             }
@@ -101,14 +102,10 @@ fn test_manual_variants_array_is_correct() {
 
 /// A variant of [`LanguageExtension::Unimplemented`].
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum UnimplementedLanguageExtension {
-    UnrestrictedPointerParameters,
-}
+pub enum UnimplementedLanguageExtension {}
 
 impl UnimplementedLanguageExtension {
     pub(crate) const fn tracking_issue_num(self) -> u16 {
-        match self {
-            Self::UnrestrictedPointerParameters => 5158,
-        }
+        match self {}
     }
 }
@@ -270,6 +270,7 @@ pub(in crate::front::wgsl) struct Lexer<'a> {
 }
 
 impl<'a> Lexer<'a> {
+    #[cfg_attr(not(feature = "wgsl-in-imports"), allow(dead_code))]
     pub(in crate::front::wgsl) const fn new(input: &'a str, ignore_doc_comments: bool) -> Self {
         Lexer {
             input,
@@ -280,6 +281,22 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    /// Creates a lexer for `source[start..]`, whose spans are relative to the
+    /// start of `source`.
+    pub(in crate::front::wgsl) fn new_at(
+        source: &'a str,
+        start: usize,
+        ignore_doc_comments: bool,
+    ) -> Self {
+        Lexer {
+            input: &source[start..],
+            source,
+            last_end_offset: start,
+            enable_extensions: EnableExtensions::empty(),
+            ignore_doc_comments,
+        }
+    }
+
     /// Calls the function with a lexer and returns the result of the function as well as the span for everything the function parsed
     ///
     /// # Examples
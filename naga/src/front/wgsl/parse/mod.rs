@@ -1,4 +1,7 @@
 use alloc::{boxed::Box, vec::Vec};
+#[cfg(feature = "wgsl-in-imports")]
+use alloc::{string::String, sync::Arc};
+use core::ops::Range;
 use directive::enable_extension::ImplementedEnableExtension;
 
 use crate::diagnostic_filter::{
@@ -6,6 +9,8 @@ use crate::diagnostic_filter::{
     ShouldConflictOnFullDuplicate, StandardFilterableTriggeringRule,
 };
 use crate::front::wgsl::error::{DiagnosticAttributeNotSupportedPosition, Error, ExpectedToken};
+#[cfg(feature = "wgsl-in-imports")]
+use crate::front::wgsl::import::ImportResolver;
 use crate::front::wgsl::parse::directive::enable_extension::{EnableExtension, EnableExtensions};
 use crate::front::wgsl::parse::directive::language_extension::LanguageExtension;
 use crate::front::wgsl::parse::directive::DirectiveKind;
@@ -281,13 +286,21 @@ impl<'a> BindingParser<'a> {
 pub struct Options {
     /// Controls whether the parser should parse doc comments.
     pub parse_doc_comments: bool,
+    /// Resolver for `import` directives.
+    ///
+    /// If `None`, any `import` directive is an error. See
+    /// [`ImportResolver`] for details.
+    #[cfg(feature = "wgsl-in-imports")]
+    pub import_resolver: Option<Arc<dyn ImportResolver>>,
 }
 
 impl Options {
-    /// Creates a new [`Options`] without doc comments parsing.
+    /// Creates a new [`Options`] without doc comments parsing or imports.
     pub const fn new() -> Self {
         Options {
             parse_doc_comments: false,
+            #[cfg(feature = "wgsl-in-imports")]
+            import_resolver: None,
         }
     }
 }
@@ -3073,37 +3086,83 @@ impl Parser {
         }
     }
 
+    /// Parses each of the `files` in `source` as a separate translation unit,
+    /// linking all of them into a single one.
+    ///
+    /// The first file is the top level source, only its module doc comments
+    /// are kept. Multiple files are only parsed when composing a module from
+    /// `import` directives.
     pub fn parse<'a>(
         &mut self,
         source: &'a str,
+        files: &[Range<usize>],
         options: &Options,
     ) -> Result<'a, ast::TranslationUnit<'a>> {
         self.reset();
 
-        let mut lexer = Lexer::new(source, !options.parse_doc_comments);
         let mut tu = ast::TranslationUnit::default();
-        let mut enable_extensions = EnableExtensions::empty();
+        let mut all_enable_extensions = EnableExtensions::empty();
         let mut diagnostic_filters = DiagnosticFilterMap::new();
 
-        // Parse module doc comments.
-        tu.doc_comments = lexer.accumulate_module_doc_comments();
+        let mut lexers = Vec::with_capacity(files.len());
+        for (index, file) in files.iter().enumerate() {
+            let mut lexer =
+                Lexer::new_at(&source[..file.end], file.start, !options.parse_doc_comments);
 
-        // Parse directives.
-        while let Ok((ident, _directive_ident_span)) = lexer.peek_ident_with_span() {
+            // Parse module doc comments.
+            let doc_comments = lexer.accumulate_module_doc_comments();
+            if index == 0 {
+                tu.doc_comments = doc_comments;
+            }
+
+            lexer.enable_extensions =
+                self.directives(&mut lexer, options, &mut diagnostic_filters)?;
+            all_enable_extensions.extend(&lexer.enable_extensions);
+            lexers.push(lexer);
+        }
+
+        tu.enable_extensions = all_enable_extensions;
+        tu.diagnostic_filter_leaf =
+            Self::write_diagnostic_filters(&mut tu.diagnostic_filters, diagnostic_filters, None);
+
+        for mut lexer in lexers {
+            loop {
+                self.global_decl(&mut lexer, &mut tu)?;
+                if lexer.peek().0 == Token::End {
+                    break;
+                }
+            }
+        }
+
+        Ok(tu)
+    }
+
+    /// Parses the directives at the start of a file, returning the enable
+    /// extensions it enabled.
+    #[cfg_attr(not(feature = "wgsl-in-imports"), allow(unused_variables))]
+    fn directives<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        options: &Options,
+        diagnostic_filters: &mut DiagnosticFilterMap,
+    ) -> Result<'a, EnableExtensions> {
+        let mut enable_extensions = EnableExtensions::empty();
+
+        while let Ok((ident, directive_ident_span)) = lexer.peek_ident_with_span() {
             if let Some(kind) = DirectiveKind::from_ident(ident) {
-                self.push_rule_span(Rule::Directive, &mut lexer);
+                self.push_rule_span(Rule::Directive, lexer);
                 let _ = lexer.next_ident_with_span().unwrap();
                 match kind {
                     DirectiveKind::Diagnostic => {
-                        let diagnostic_filter = self.diagnostic_filter(&mut lexer)?;
-                        let span = self.peek_rule_span(&lexer);
+                        let diagnostic_filter = self.diagnostic_filter(lexer)?;
+                        let span = self.peek_rule_span(lexer);
                         diagnostic_filters
                             .add(diagnostic_filter, span, ShouldConflictOnFullDuplicate::No)
                             .map_err(|e| Box::new(e.into()))?;
                         lexer.expect(Token::Separator(';'))?;
                     }
                     DirectiveKind::Enable => {
-                        self.directive_ident_list(&mut lexer, |ident, span| {
+                        self.directive_ident_list(lexer, |ident, span| {
                             let kind = EnableExtension::from_ident(ident, span)?;
                             let extension = match kind {
                                 EnableExtension::Implemented(kind) => kind,
@@ -3119,7 +3178,7 @@ impl Parser {
                         })?;
                     }
                     DirectiveKind::Requires => {
-                        self.directive_ident_list(&mut lexer, |ident, span| {
+                        self.directive_ident_list(lexer, |ident, span| {
                             match LanguageExtension::from_ident(ident) {
                                 Some(LanguageExtension::Implemented(_kind)) => {
                                     // NOTE: No further validation is needed for an extension, so
@@ -3138,30 +3197,49 @@ impl Parser {
                             }
                         })?;
                     }
+                    #[cfg(feature = "wgsl-in-imports")]
+                    DirectiveKind::Import => {
+                        if options.import_resolver.is_none() {
+                            return Err(Box::new(Error::ImportWithoutResolver(
+                                directive_ident_span,
+                            )));
+                        }
+                        // The imported files were already resolved before
+                        // parsing, see `Composition::resolve_imports`.
+                        Self::import_path(lexer)?;
+                    }
                 }
-                self.pop_rule_span(&lexer);
+                self.pop_rule_span(lexer);
             } else {
                 break;
             }
         }
 
-        lexer.enable_extensions = enable_extensions.clone();
-        tu.enable_extensions = enable_extensions;
-        tu.diagnostic_filter_leaf =
-            Self::write_diagnostic_filters(&mut tu.diagnostic_filters, diagnostic_filters, None);
+        Ok(enable_extensions)
+    }
 
+    /// Parses the module path of an `import` directive, including the
+    /// terminating `;`.
+    ///
+    /// Returns the path with its segments separated by `::` and its span.
+    #[cfg(feature = "wgsl-in-imports")]
+    pub(in crate::front::wgsl) fn import_path<'a>(
+        lexer: &mut Lexer<'a>,
+    ) -> Result<'a, (String, Span)> {
+        let start = lexer.start_byte_offset();
+        let mut path = String::new();
         loop {
-            match self.global_decl(&mut lexer, &mut tu) {
-                Err(error) => return Err(error),
-                Ok(()) => {
-                    if lexer.peek().0 == Token::End {
-                        break;
-                    }
-                }
+            let (segment, _) = lexer.next_ident_with_span()?;
+            path.push_str(segment);
+            if !lexer.skip(Token::Separator(':')) {
+                break;
             }
+            lexer.expect(Token::Separator(':'))?;
+            path.push_str("::");
         }
-
-        Ok(tu)
+        let span = lexer.span_from(start);
+        lexer.expect(Token::Separator(';'))?;
+        Ok((path, span))
     }
 
     fn increase_brace_nesting(brace_nesting_level: u8, brace_span: Span) -> Result<'static, u8> {
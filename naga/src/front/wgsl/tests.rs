@@ -682,7 +682,7 @@ fn parse_repeated_attributes() {
         let span_end = span_start + name_length;
         let expected_span = Span::new(span_start, span_end);
 
-        let result = Frontend::new().inner(&shader);
+        let result = Frontend::new().inner(&shader, core::slice::from_ref(&(0..shader.len())));
         assert!(matches!(
             *result.unwrap_err(),
             Error::RepeatedAttribute(span) if span == expected_span
@@ -698,7 +698,7 @@ fn parse_missing_workgroup_size() {
     };
 
     let shader = "@compute fn vs() -> vec4<f32> { return vec4<f32>(0.0); }";
-    let result = Frontend::new().inner(shader);
+    let result = Frontend::new().inner(shader, core::slice::from_ref(&(0..shader.len())));
     assert!(matches!(
         *result.unwrap_err(),
         Error::MissingWorkgroupSize(span) if span == Span::new(1, 8)
@@ -865,3 +865,133 @@ error: found conflicting `diagnostic(…)` rule(s)
         }
     }
 }
+
+#[cfg(feature = "wgsl-in-imports")]
+mod imports {
+    use alloc::{format, string::ToString, sync::Arc};
+
+    use crate::front::wgsl::{Frontend, ImportedSource, Options};
+
+    fn frontend() -> Frontend {
+        let mut options = Options::new();
+        options.import_resolver = Some(Arc::new(|path: &str, _importer: &str| {
+            let source = match path {
+                "shared::lighting" => {
+                    "import math;\nfn shade(c: vec4<f32>) -> vec4<f32> { return clamp01(c) * AMBIENT; }\n"
+                }
+                "math" => {
+                    "import shared::lighting;\nenable f16;\nconst AMBIENT: f32 = 0.5;\nfn clamp01(v: vec4<f32>) -> vec4<f32> { return clamp(v, vec4(0.0), vec4(f32(1h))); }\n"
+                }
+                "broken" => "fn broken() -> f32 { return missing; }\n",
+                _ => return Err(format!("no module named `{path}`")),
+            };
+            Ok(ImportedSource {
+                name: format!("{}.wgsl", path.replace("::", "/")),
+                source: source.to_string(),
+            })
+        }));
+        Frontend::new_with_options(options)
+    }
+
+    #[test]
+    fn linked() {
+        let mut frontend = frontend();
+        let module = frontend
+            .parse(
+                "
+import shared::lighting;
+import math;
+
+@fragment
+fn main() -> @location(0) vec4<f32> { return shade(vec4(AMBIENT)); }
+",
+            )
+            .unwrap();
+
+        let names: alloc::vec::Vec<_> = module
+            .functions
+            .iter()
+            .filter_map(|(_, f)| f.name.as_deref())
+            .collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"shade") && names.contains(&"clamp01"));
+
+        let files: alloc::vec::Vec<_> = frontend
+            .import_map()
+            .files()
+            .iter()
+            .map(|file| file.name.as_str())
+            .collect();
+        assert_eq!(files, ["shared/lighting.wgsl", "math.wgsl"]);
+    }
+
+    #[test]
+    fn errors_in_imported_file() {
+        let mut frontend = frontend();
+        let source = "import broken;\nfn main() {}\n";
+        let error = frontend.parse(source).unwrap_err();
+        let (file, span) = frontend
+            .import_map()
+            .resolve(error.labels().next().unwrap().0);
+        assert_eq!(file.unwrap().name, "broken.wgsl");
+        assert_eq!(span, crate::Span::new(28, 35));
+        let (file, location) = error
+            .location_with_imports(source, frontend.import_map())
+            .unwrap();
+        assert_eq!(file.unwrap().name, "broken.wgsl");
+        assert_eq!((location.line_number, location.line_position), (1, 29));
+
+        // The label lies past the end of the top level source
+        assert!(error.location(source).is_none());
+        assert_eq!(
+            error.emit_to_string(source),
+            "error: no definition in scope for identifier: `missing`\n\n"
+        );
+        assert_eq!(
+            error.emit_to_string_with_imports(source, "main.wgsl", frontend.import_map()),
+            "\
+error: no definition in scope for identifier: `missing`
+  ┌─ broken.wgsl:1:29
+  │
+1 │ fn broken() -> f32 { return missing; }
+  │                             ^^^^^^^ unknown identifier
+
+"
+        );
+    }
+
+    #[test]
+    fn unresolved() {
+        let mut frontend = frontend();
+        let source = "import shared::shadows;\n";
+        let error = frontend.parse(source).unwrap_err();
+        assert_eq!(
+            error.emit_to_string_with_imports(source, "main.wgsl", frontend.import_map()),
+            "\
+error: failed to import `shared::shadows`
+  ┌─ main.wgsl:1:8
+  │
+1 │ import shared::shadows;
+  │        ^^^^^^^^^^^^^^^ no module named `shared::shadows`
+
+"
+        );
+    }
+
+    #[test]
+    fn not_enabled() {
+        crate::front::wgsl::assert_parse_err(
+            "import math;\n",
+            "\
+error: `import` directives are not enabled
+  ┌─ wgsl:1:1
+  │
+1 │ import math;
+  │ ^^^^^^
+  │
+  = note: Imports are a Naga extension to WGSL, which requires setting an `ImportResolver` in the frontend's `Options`.
+
+",
+        );
+    }
+}
@@ -0,0 +1,651 @@
+/*!
+Inlining of function calls into entry points.
+
+Some drivers do a poor job of inlining small helper functions, and translated
+shaders are often easier to read without them. [`inline`] replaces
+[`Statement::Call`]s in entry points with the body of the called function,
+according to the heuristics in [`InlineOptions`].
+
+[`Statement::Call`]: crate::Statement::Call
+*/
+
+use alloc::vec::Vec;
+
+use super::visit::{
+    emit_from, for_each_child_block, map_expression_operands, map_statement_operands,
+};
+use crate::arena::{Arena, Handle, HandleVec};
+use crate::{
+    Block, Expression, Function, LocalVariable, Module, NamedExpressions, Span, Statement,
+};
+
+/// Configuration for [`inline`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InlineOptions {
+    /// Functions with at most this many expressions are always inlined.
+    pub max_expressions: usize,
+    /// Inline functions called from exactly one place in the module,
+    /// regardless of their size.
+    pub single_call_site: bool,
+}
+
+impl Default for InlineOptions {
+    fn default() -> Self {
+        InlineOptions {
+            max_expressions: 32,
+            single_call_site: true,
+        }
+    }
+}
+
+/// Inline function calls into the entry points of `module`, which must be
+/// valid.
+///
+/// A call is inlined if the called function is small enough or only called
+/// once, as configured by `options`, and:
+///
+/// - it only returns at the end of its body, and
+///
+/// - it has the same diagnostic filters as the entry point.
+///
+/// Calls in the bodies of inlined functions are considered for inlining as
+/// well, so entire call trees can be flattened into entry points.
+///
+/// Only entry points are rewritten. Functions that are no longer called are
+/// left in place, use [`compact`] to remove them. Expression handles in the
+/// rewritten entry points are renumbered, so any [`ModuleInfo`] for `module`
+/// must be recomputed by validating it again.
+///
+/// [`compact`]: crate::compact::compact
+/// [`ModuleInfo`]: crate::valid::ModuleInfo
+pub fn inline(module: &mut Module, options: &InlineOptions) {
+    let mut call_sites = HandleVec::new();
+    call_sites.resize(module.functions.len(), 0usize);
+    let bodies = module
+        .functions
+        .iter()
+        .map(|(_, function)| &function.body)
+        .chain(module.entry_points.iter().map(|ep| &ep.function.body));
+    for body in bodies {
+        count_call_sites(body, &mut call_sites);
+    }
+
+    let mut inlinable = HandleVec::with_capacity(module.functions.len());
+    for (handle, function) in module.functions.iter() {
+        let small = function.expressions.len() <= options.max_expressions;
+        let single = options.single_call_site && call_sites[handle] == 1;
+        inlinable.insert(handle, (small || single) && returns_at_end(function));
+    }
+
+    for ep in module.entry_points.iter_mut() {
+        let function = &mut ep.function;
+        let diagnostic_filter_leaf = function.diagnostic_filter_leaf;
+        let candidate = |callee: Handle<Function>| {
+            inlinable[callee]
+                && module.functions[callee].diagnostic_filter_leaf == diagnostic_filter_leaf
+        };
+        if !calls_any(&function.body, &candidate) {
+            continue;
+        }
+
+        let expressions = core::mem::take(&mut function.expressions);
+        let named_expressions = core::mem::take(&mut function.named_expressions);
+        let body = core::mem::take(&mut function.body);
+
+        let initializers: Vec<_> = function
+            .local_variables
+            .iter()
+            .filter_map(|(handle, local)| Some((handle, local.init?)))
+            .collect();
+
+        let mut inliner = Inliner {
+            global: super::GlobalCtx {
+                types: &module.types,
+                constants: &module.constants,
+                overrides: &module.overrides,
+                global_expressions: &module.global_expressions,
+            },
+            functions: &module.functions,
+            candidate: &candidate,
+            expressions: Arena::new(),
+            local_variables: &mut function.local_variables,
+            named_expressions: NamedExpressions::default(),
+        };
+        let mut instance = Instance::new(&expressions, &named_expressions, None, None);
+        let new_body = inliner.block(&mut instance, &body);
+
+        for (handle, init) in initializers {
+            let init = inliner.expr(&mut instance, init);
+            inliner.local_variables.get_mut(handle).init = Some(init);
+        }
+
+        function.expressions = inliner.expressions;
+        function.named_expressions = inliner.named_expressions;
+        function.body = new_body;
+    }
+}
+
+fn count_call_sites(block: &Block, call_sites: &mut HandleVec<Function, usize>) {
+    for statement in block.iter() {
+        match *statement {
+            Statement::Call { function, .. } => call_sites[function] += 1,
+            _ => for_each_child_block(statement, |block| count_call_sites(block, call_sites)),
+        }
+    }
+}
+
+/// Returns `true` if `block` contains a call to a function accepted by
+/// `candidate`.
+fn calls_any(block: &Block, candidate: &impl Fn(Handle<Function>) -> bool) -> bool {
+    block.iter().any(|statement| match *statement {
+        Statement::Call { function, .. } => candidate(function),
+        _ => {
+            let mut found = false;
+            for_each_child_block(statement, |block| found |= calls_any(block, candidate));
+            found
+        }
+    })
+}
+
+/// Returns `true` if the only [`Statement::Return`] in `function` is the last
+/// statement of its body, and there is one if the function returns a value.
+fn returns_at_end(function: &Function) -> bool {
+    fn has_return(block: &[Statement]) -> bool {
+        block.iter().any(|statement| match *statement {
+            Statement::Return { .. } => true,
+            _ => {
+                let mut found = false;
+                for_each_child_block(statement, |block| found |= has_return(block));
+                found
+            }
+        })
+    }
+
+    let rest = match function.body.split_last() {
+        Some((&Statement::Return { value }, rest)) => {
+            if value.is_some() != function.result.is_some() {
+                return false;
+            }
+            rest
+        }
+        _ if function.result.is_some() => return false,
+        _ => &function.body[..],
+    };
+    !has_return(rest)
+}
+
+/// A function whose body is being copied into the entry point.
+pub(super) struct Instance<'a> {
+    expressions: &'a Arena<Expression>,
+    named_expressions: &'a NamedExpressions,
+    /// The copies of `expressions` in [`Inliner::expressions`] currently in
+    /// scope.
+    map: HandleVec<Expression, Option<Handle<Expression>>>,
+    /// The caller's copies of the function's local variables, or `None` for
+    /// the entry point itself.
+    local_variables: Option<HandleVec<LocalVariable, Handle<LocalVariable>>>,
+    /// The argument values passed by the caller, or `None` for the entry
+    /// point itself.
+    arguments: Option<Vec<Handle<Expression>>>,
+}
+
+impl<'a> Instance<'a> {
+    pub(super) fn new(
+        expressions: &'a Arena<Expression>,
+        named_expressions: &'a NamedExpressions,
+        local_variables: Option<HandleVec<LocalVariable, Handle<LocalVariable>>>,
+        arguments: Option<Vec<Handle<Expression>>>,
+    ) -> Self {
+        let mut map = HandleVec::new();
+        map.resize(expressions.len(), None);
+        Instance {
+            expressions,
+            named_expressions,
+            map,
+            local_variables,
+            arguments,
+        }
+    }
+}
+
+/// Rebuilds the body of an entry point, inlining calls along the way.
+///
+/// This is also used to copy function bodies with their arguments replaced,
+/// by never considering any calls for inlining.
+pub(super) struct Inliner<'a, C> {
+    pub global: super::GlobalCtx<'a>,
+    pub functions: &'a Arena<Function>,
+    pub candidate: &'a C,
+    pub expressions: Arena<Expression>,
+    pub local_variables: &'a mut Arena<LocalVariable>,
+    pub named_expressions: NamedExpressions,
+}
+
+impl<C: Fn(Handle<Function>) -> bool> Inliner<'_, C> {
+    /// Returns the copy of `handle` in scope, copying it if there's none.
+    ///
+    /// Only expressions that don't need to be emitted, or whose statement
+    /// hasn't been visited yet, are copied here.
+    pub fn expr(
+        &mut self,
+        instance: &mut Instance,
+        handle: Handle<Expression>,
+    ) -> Handle<Expression> {
+        match instance.map[handle] {
+            Some(copy) => copy,
+            None => self.copy(instance, handle),
+        }
+    }
+
+    /// Appends a copy of `handle`, with its operands replaced by their copies
+    /// in scope.
+    fn copy(&mut self, instance: &mut Instance, handle: Handle<Expression>) -> Handle<Expression> {
+        let mut expression = instance.expressions[handle].clone();
+        match expression {
+            Expression::FunctionArgument(index) => {
+                if let Some(ref arguments) = instance.arguments {
+                    let argument = arguments[index as usize];
+                    instance.map[handle] = Some(argument);
+                    return argument;
+                }
+            }
+            Expression::LocalVariable(ref mut local) => {
+                if let Some(ref locals) = instance.local_variables {
+                    *local = locals[*local];
+                }
+            }
+            _ => map_expression_operands(&mut expression, |operand| {
+                *operand = self.expr(instance, *operand)
+            }),
+        }
+
+        let span = instance.expressions.get_span(handle);
+        let copy = self.expressions.append(expression, span);
+        instance.map[handle] = Some(copy);
+        if let Some(name) = instance.named_expressions.get(&handle) {
+            self.named_expressions
+                .entry(copy)
+                .or_insert_with(|| name.clone());
+        }
+        copy
+    }
+
+    pub fn block(&mut self, instance: &mut Instance, block: &Block) -> Block {
+        let mut new_block = Block::with_capacity(block.len());
+        for (statement, &span) in block.span_iter() {
+            self.statement(instance, statement, span, &mut new_block);
+        }
+        new_block
+    }
+
+    fn statement(
+        &mut self,
+        instance: &mut Instance,
+        statement: &Statement,
+        span: Span,
+        block: &mut Block,
+    ) {
+        let statement = match *statement {
+            Statement::Emit(ref range) => {
+                let start = self.expressions.len();
+                for handle in range.clone() {
+                    // Always make a new copy, the same expression may be
+                    // emitted in more than one branch.
+                    if !instance.expressions[handle].needs_pre_emit() {
+                        self.copy(instance, handle);
+                    }
+                }
+                emit_from(&self.expressions, start, span, block);
+                return;
+            }
+            Statement::Block(ref inner) => Statement::Block(self.block(instance, inner)),
+            Statement::If {
+                condition,
+                ref accept,
+                ref reject,
+            } => Statement::If {
+                condition: self.expr(instance, condition),
+                accept: self.block(instance, accept),
+                reject: self.block(instance, reject),
+            },
+            Statement::Switch {
+                selector,
+                ref cases,
+            } => Statement::Switch {
+                selector: self.expr(instance, selector),
+                cases: cases
+                    .iter()
+                    .map(|case| crate::SwitchCase {
+                        value: case.value,
+                        body: self.block(instance, &case.body),
+                        fall_through: case.fall_through,
+                    })
+                    .collect(),
+            },
+            Statement::Loop {
+                ref body,
+                ref continuing,
+                break_if,
+            } => {
+                let body = self.block(instance, body);
+                let continuing = self.block(instance, continuing);
+                Statement::Loop {
+                    body,
+                    continuing,
+                    break_if: break_if.map(|break_if| self.expr(instance, break_if)),
+                }
+            }
+            Statement::Call {
+                function,
+                ref arguments,
+                result,
+            } => {
+                let arguments = arguments
+                    .iter()
+                    .map(|&argument| self.expr(instance, argument))
+                    .collect();
+                if (self.candidate)(function) {
+                    self.inline_call(instance, function, arguments, result, span, block);
+                    return;
+                }
+                Statement::Call {
+                    function,
+                    arguments,
+                    result: result.map(|result| self.expr(instance, result)),
+                }
+            }
+            _ => {
+                let mut statement = statement.clone();
+                map_statement_operands(&mut statement, |operand| {
+                    *operand = self.expr(instance, *operand)
+                });
+                statement
+            }
+        };
+        block.push(statement, span);
+    }
+
+    /// Replaces a call to `function` with a copy of its body.
+    ///
+    /// `arguments` are already copies in the caller's scope, while `result` is
+    /// the [`Expression::CallResult`] in `caller`, which will refer to the
+    /// copy of the returned value from now on.
+    fn inline_call(
+        &mut self,
+        caller: &mut Instance,
+        function: Handle<Function>,
+        mut arguments: Vec<Handle<Expression>>,
+        result: Option<Handle<Expression>>,
+        span: Span,
+        block: &mut Block,
+    ) {
+        let callee = &self.functions[function];
+
+        // Passing constant integers straight through could turn indexing in
+        // the callee into constant indexing, for which out of bounds accesses
+        // are validation errors instead of being handled at runtime.
+        let used_as_index = |argument: usize| {
+            callee
+                .expressions
+                .iter()
+                .any(|(_, expression)| match *expression {
+                    Expression::Access { index, .. } => matches!(
+                        callee.expressions[index],
+                        Expression::FunctionArgument(index) if index as usize == argument
+                    ),
+                    _ => false,
+                })
+        };
+        for (index, (argument, declaration)) in arguments
+            .iter_mut()
+            .zip(callee.arguments.iter())
+            .enumerate()
+        {
+            if self
+                .global
+                .eval_expr_to_literal_from(*argument, &self.expressions)
+                .is_some()
+                && used_as_index(index)
+            {
+                let local = self.local_variables.append(
+                    LocalVariable {
+                        name: declaration.name.clone(),
+                        ty: declaration.ty,
+                        init: None,
+                    },
+                    span,
+                );
+                let pointer = self
+                    .expressions
+                    .append(Expression::LocalVariable(local), span);
+                block.push(
+                    Statement::Store {
+                        pointer,
+                        value: *argument,
+                    },
+                    span,
+                );
+                let start = self.expressions.len();
+                *argument = self.expressions.append(Expression::Load { pointer }, span);
+                emit_from(&self.expressions, start, span, block);
+            }
+        }
+
+        // Every call needs fresh local variables, so initialize them with
+        // stores rather than `init`, in case the call is in a loop.
+        let mut local_variables = HandleVec::with_capacity(callee.local_variables.len());
+        let mut initializers = Vec::with_capacity(callee.local_variables.len());
+        for (handle, local) in callee.local_variables.iter() {
+            let copy = self.local_variables.append(
+                LocalVariable {
+                    name: local.name.clone(),
+                    ty: local.ty,
+                    init: None,
+                },
+                callee.local_variables.get_span(handle),
+            );
+            local_variables.insert(handle, copy);
+            initializers.push((copy, local.ty, local.init));
+        }
+
+        let mut instance = Instance::new(
+            &callee.expressions,
+            &callee.named_expressions,
+            Some(local_variables),
+            Some(arguments),
+        );
+
+        for (local, ty, init) in initializers {
+            let pointer = self
+                .expressions
+                .append(Expression::LocalVariable(local), span);
+            let start = self.expressions.len();
+            let value = match init {
+                Some(init) => self.expr(&mut instance, init),
+                None => self.expressions.append(Expression::ZeroValue(ty), span),
+            };
+            emit_from(&self.expressions, start, span, block);
+            block.push(Statement::Store { pointer, value }, span);
+        }
+
+        for (statement, &span) in callee.body.span_iter() {
+            match *statement {
+                Statement::Return { value } => {
+                    // This is the last statement, see `returns_at_end`.
+                    if let (Some(value), Some(result)) = (value, result) {
+                        caller.map[result] = Some(self.expr(&mut instance, value));
+                    }
+                }
+                _ => self.statement(&mut instance, statement, span, block),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::*;
+
+    fn inline_and_validate(source: &str, options: &InlineOptions) -> Module {
+        let validate = |module: &Module| {
+            crate::valid::Validator::new(
+                crate::valid::ValidationFlags::all(),
+                crate::valid::Capabilities::default(),
+            )
+            .validate(module)
+            .unwrap_or_else(|err| panic!("{err:?}"));
+        };
+
+        let mut module = crate::front::wgsl::parse_str(source).unwrap();
+        validate(&module);
+        inline(&mut module, options);
+        validate(&module);
+        module
+    }
+
+    fn calls(block: &Block) -> usize {
+        block
+            .iter()
+            .map(|statement| match *statement {
+                Statement::Call { .. } => 1,
+                _ => {
+                    let mut count = 0;
+                    for_each_child_block(statement, |block| count += calls(block));
+                    count
+                }
+            })
+            .sum()
+    }
+
+    #[test]
+    fn small_functions() {
+        let module = inline_and_validate(
+            "
+            fn scale(p: ptr<function, f32>, factor: f32) {
+                *p *= factor;
+            }
+
+            fn accumulate(x: f32) -> f32 {
+                var sum = 1.0;
+                for (var i = 0; i < 4; i++) {
+                    sum += x * f32(i);
+                }
+                scale(&sum, 0.5);
+                return sum;
+            }
+
+            @fragment
+            fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+                var total = 0.0;
+                for (var i = 0; i < 2; i++) {
+                    total += accumulate(x);
+                }
+                let last = accumulate(total);
+                return vec4(total, last, 0.0, 1.0);
+            }
+            ",
+            &InlineOptions::default(),
+        );
+
+        let ep = &module.entry_points[0];
+        assert_eq!(calls(&ep.function.body), 0);
+        // `sum` and `i` for each call of `accumulate`, plus its own two.
+        assert_eq!(ep.function.local_variables.len(), 6);
+    }
+
+    #[test]
+    fn nested_calls() {
+        let module = inline_and_validate(
+            "
+            fn square(x: f32) -> f32 { return x * x; }
+            fn norm2(v: vec2<f32>) -> f32 { return square(v.x) + square(v.y); }
+
+            @compute @workgroup_size(1)
+            fn main() {
+                let n = norm2(vec2(3.0, 4.0));
+            }
+            ",
+            &InlineOptions::default(),
+        );
+
+        assert_eq!(calls(&module.entry_points[0].function.body), 0);
+        // Functions other than entry points are left as they were.
+        let norm2 = module.functions.iter().nth(1).unwrap().1;
+        assert_eq!(calls(&norm2.body), 2);
+    }
+
+    #[test]
+    fn early_return() {
+        let module = inline_and_validate(
+            "
+            fn clamped(x: f32) -> f32 {
+                if x < 0.0 {
+                    return 0.0;
+                }
+                return x;
+            }
+
+            @fragment
+            fn main(@location(0) x: f32) -> @location(0) f32 {
+                return clamped(x);
+            }
+            ",
+            &InlineOptions::default(),
+        );
+
+        assert_eq!(calls(&module.entry_points[0].function.body), 1);
+    }
+
+    #[test]
+    fn options() {
+        let source = "
+            fn square(x: f32) -> f32 { return x * x; }
+            fn cube(x: f32) -> f32 { return x * x * x; }
+
+            @fragment
+            fn main(@location(0) x: f32) -> @location(0) f32 {
+                return square(x) + square(x) + cube(x);
+            }
+        ";
+
+        let module = inline_and_validate(
+            source,
+            &InlineOptions {
+                max_expressions: 0,
+                single_call_site: true,
+            },
+        );
+        assert_eq!(calls(&module.entry_points[0].function.body), 2);
+
+        let module = inline_and_validate(
+            source,
+            &InlineOptions {
+                max_expressions: 0,
+                single_call_site: false,
+            },
+        );
+        assert_eq!(calls(&module.entry_points[0].function.body), 3);
+    }
+
+    #[test]
+    fn constant_index_argument() {
+        // Substituting `5` for `i` would make `b[i]` a constant out of bounds
+        // access, which the validator rejects.
+        let module = inline_and_validate(
+            "
+            fn element(a: array<i32, 4>, i: u32) -> i32 {
+                var b = a;
+                return b[i];
+            }
+
+            @compute @workgroup_size(1)
+            fn main() {
+                let x = element(array(1, 2, 3, 4), 5);
+            }
+            ",
+            &InlineOptions::default(),
+        );
+
+        assert_eq!(calls(&module.entry_points[0].function.body), 0);
+    }
+}
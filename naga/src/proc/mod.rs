@@ -5,21 +5,30 @@
 mod constant_evaluator;
 mod emitter;
 pub mod index;
+mod inline;
 mod layouter;
 mod namer;
+mod optimize;
 mod overloads;
+mod pointer_arguments;
 mod terminator;
 mod type_methods;
 mod typifier;
+mod visit;
 
 pub use constant_evaluator::{
     ConstantEvaluator, ConstantEvaluatorError, ExpressionKind, ExpressionKindTracker,
 };
 pub use emitter::Emitter;
 pub use index::{BoundsCheckPolicies, BoundsCheckPolicy, IndexableLength, IndexableLengthError};
+pub use inline::{inline, InlineOptions};
 pub use layouter::{Alignment, LayoutError, LayoutErrorInner, Layouter, TypeLayout};
 pub use namer::{EntryPointIndex, ExternalTextureNameKey, NameKey, Namer};
+pub use optimize::optimize;
 pub use overloads::{Conclusion, MissingSpecialType, OverloadSet, Rule};
+#[cfg(any(hlsl_out, msl_out, spv_out, glsl_out))]
+pub(crate) use pointer_arguments::has_unrestricted_pointer_arguments;
+pub use pointer_arguments::specialize_pointer_arguments;
 pub use terminator::ensure_block_returns;
 use thiserror::Error;
 pub use type_methods::min_max_float_representable_by;
@@ -0,0 +1,626 @@
+/*!
+Constant folding and dead code elimination in function bodies.
+
+Frontends already evaluate const-expressions, but expressions that only become
+constant after other transformations (like [`inline`](super::inline)), or that
+come from languages without that requirement, are still computed at runtime.
+[`optimize`] folds those, prunes the control flow that can be decided at compile
+time and drops expressions whose values are never used.
+*/
+
+use alloc::vec::Vec;
+
+use super::visit::{
+    emit_from, for_each_child_block, map_expression_operands, map_statement_operands,
+};
+use super::{ConstantEvaluator, Emitter, ExpressionKind, ExpressionKindTracker, Layouter};
+use crate::arena::{Arena, Handle, HandleSet, HandleVec, Range};
+use crate::{
+    Block, Expression, Function, Literal, Module, NamedExpressions, Span, Statement, SwitchValue,
+};
+
+/// Fold constants and eliminate dead code in all functions of `module`, which
+/// must be valid.
+///
+/// This:
+///
+/// - evaluates expressions whose operands are all constant, following the
+///   rules for WGSL const-expressions,
+///
+/// - replaces [`If`] and [`Switch`] statements whose condition or selector is
+///   constant with the statements that would run,
+///
+/// - removes statements following a [`Return`], [`Kill`], [`Break`] or
+///   [`Continue`],
+///
+/// - removes named expressions that are never used, and all unused
+///   expressions from [`Emit`] statements.
+///
+/// Expressions that are no longer used are left in the arenas, use
+/// [`compact`] to remove them. Expression handles are renumbered, so any
+/// [`ModuleInfo`] for `module` must be recomputed by validating it again.
+///
+/// [`If`]: Statement::If
+/// [`Switch`]: Statement::Switch
+/// [`Return`]: Statement::Return
+/// [`Kill`]: Statement::Kill
+/// [`Break`]: Statement::Break
+/// [`Continue`]: Statement::Continue
+/// [`Emit`]: Statement::Emit
+/// [`compact`]: crate::compact::compact
+/// [`ModuleInfo`]: crate::valid::ModuleInfo
+pub fn optimize(module: &mut Module) {
+    let mut layouter = Layouter::default();
+
+    let handles: Vec<_> = module.functions.iter().map(|(handle, _)| handle).collect();
+    for handle in handles {
+        let mut function = core::mem::take(module.functions.get_mut(handle));
+        optimize_function(module, &mut layouter, &mut function);
+        *module.functions.get_mut(handle) = function;
+    }
+
+    for index in 0..module.entry_points.len() {
+        let mut function = core::mem::take(&mut module.entry_points[index].function);
+        optimize_function(module, &mut layouter, &mut function);
+        module.entry_points[index].function = function;
+    }
+}
+
+fn optimize_function(module: &mut Module, layouter: &mut Layouter, function: &mut Function) {
+    let expressions = core::mem::take(&mut function.expressions);
+    let named_expressions = core::mem::take(&mut function.named_expressions);
+    let body = core::mem::take(&mut function.body);
+
+    let mut map = HandleVec::new();
+    map.resize(expressions.len(), None);
+    let mut folder = Folder {
+        module,
+        layouter,
+        old_expressions: &expressions,
+        old_named_expressions: &named_expressions,
+        map,
+        expressions: Arena::new(),
+        expression_kind_tracker: ExpressionKindTracker::new(),
+        named_expressions: NamedExpressions::default(),
+    };
+    let body = folder.block(&body);
+    for (_, local) in function.local_variables.iter_mut() {
+        if let Some(ref mut init) = local.init {
+            *init = folder.expr(*init);
+        }
+    }
+
+    function.expressions = folder.expressions;
+    function.named_expressions = folder.named_expressions;
+    function.body = body;
+
+    eliminate_dead_expressions(function);
+}
+
+/// Rebuilds a function's expression arena, evaluating constant expressions
+/// along the way.
+struct Folder<'a> {
+    module: &'a mut Module,
+    layouter: &'a mut Layouter,
+    old_expressions: &'a Arena<Expression>,
+    old_named_expressions: &'a NamedExpressions,
+    /// The copies of `old_expressions` in `expressions` currently in scope.
+    map: HandleVec<Expression, Option<Handle<Expression>>>,
+    expressions: Arena<Expression>,
+    expression_kind_tracker: ExpressionKindTracker,
+    named_expressions: NamedExpressions,
+}
+
+impl Folder<'_> {
+    /// Returns the copy of `handle` in scope, copying it if there's none.
+    fn expr(&mut self, handle: Handle<Expression>) -> Handle<Expression> {
+        match self.map[handle] {
+            Some(copy) => copy,
+            None => self.copy(handle),
+        }
+    }
+
+    /// Appends the folded value of `handle`.
+    fn copy(&mut self, handle: Handle<Expression>) -> Handle<Expression> {
+        let old_expressions = self.old_expressions;
+        let span = old_expressions.get_span(handle);
+        let mut expression = old_expressions[handle].clone();
+        map_expression_operands(&mut expression, |operand| *operand = self.expr(*operand));
+
+        // The validator rejects constant indices that are out of bounds, so
+        // don't let folding turn a dynamic index into a constant one. It's
+        // still evaluated as usual at runtime.
+        if let (
+            &Expression::Access { index, .. },
+            &mut Expression::Access {
+                index: ref mut new_index,
+                ..
+            },
+        ) = (&old_expressions[handle], &mut expression)
+        {
+            if self.is_literal(*new_index, &self.expressions)
+                && !self.is_literal(index, old_expressions)
+            {
+                let mut unfolded = old_expressions[index].clone();
+                map_expression_operands(&mut unfolded, |operand| *operand = self.expr(*operand));
+                *new_index = self.append(unfolded, span, ExpressionKind::Runtime);
+                return self.finish_copy(handle, expression, span);
+            }
+        }
+
+        let copy = if self.layouter.update(self.module.to_ctx()).is_ok() {
+            let mut emitter = Emitter::default();
+            let mut block = Block::new();
+            ConstantEvaluator::for_wgsl_function(
+                self.module,
+                &mut self.expressions,
+                &mut self.expression_kind_tracker,
+                self.layouter,
+                &mut emitter,
+                &mut block,
+                false,
+            )
+            .try_eval_and_append(expression.clone(), span)
+            .ok()
+        } else {
+            None
+        };
+        match copy {
+            Some(copy) => self.finish(handle, copy),
+            None => self.finish_copy(handle, expression, span),
+        }
+    }
+
+    /// Appends `expression` as the unevaluated copy of `handle`.
+    fn finish_copy(
+        &mut self,
+        handle: Handle<Expression>,
+        expression: Expression,
+        span: Span,
+    ) -> Handle<Expression> {
+        let copy = self.append(expression, span, ExpressionKind::Runtime);
+        self.finish(handle, copy)
+    }
+
+    fn finish(
+        &mut self,
+        handle: Handle<Expression>,
+        copy: Handle<Expression>,
+    ) -> Handle<Expression> {
+        self.map[handle] = Some(copy);
+        if let Some(name) = self.old_named_expressions.get(&handle) {
+            self.named_expressions
+                .entry(copy)
+                .or_insert_with(|| name.clone());
+        }
+        copy
+    }
+
+    fn append(
+        &mut self,
+        expression: Expression,
+        span: Span,
+        kind: ExpressionKind,
+    ) -> Handle<Expression> {
+        let handle = self.expressions.append(expression, span);
+        self.expression_kind_tracker.insert(handle, kind);
+        handle
+    }
+
+    fn is_literal(&self, handle: Handle<Expression>, expressions: &Arena<Expression>) -> bool {
+        self.module
+            .to_ctx()
+            .eval_expr_to_literal_from(handle, expressions)
+            .is_some()
+    }
+
+    fn literal(&self, handle: Handle<Expression>) -> Option<Literal> {
+        self.module
+            .to_ctx()
+            .eval_expr_to_literal_from(handle, &self.expressions)
+    }
+
+    fn block(&mut self, block: &Block) -> Block {
+        let mut new_block = Block::with_capacity(block.len());
+        for (statement, &span) in block.span_iter() {
+            self.statement(statement, span, &mut new_block);
+            if matches!(
+                new_block.last(),
+                Some(
+                    &Statement::Return { .. }
+                        | &Statement::Kill
+                        | &Statement::Break
+                        | &Statement::Continue
+                )
+            ) {
+                break;
+            }
+        }
+        new_block
+    }
+
+    fn statement(&mut self, statement: &Statement, span: Span, block: &mut Block) {
+        let statement = match *statement {
+            Statement::Emit(ref range) => {
+                let start = self.expressions.len();
+                for handle in range.clone() {
+                    // Always make a new copy, the same expression may be
+                    // emitted in more than one branch.
+                    if !self.old_expressions[handle].needs_pre_emit() {
+                        self.copy(handle);
+                    }
+                }
+                emit_from(&self.expressions, start, span, block);
+                return;
+            }
+            Statement::Block(ref inner) => Statement::Block(self.block(inner)),
+            Statement::If {
+                condition,
+                ref accept,
+                ref reject,
+            } => {
+                let condition = self.expr(condition);
+                match self.literal(condition) {
+                    Some(Literal::Bool(value)) => {
+                        let taken = if value { accept } else { reject };
+                        return self.splice(taken, block);
+                    }
+                    _ => {
+                        let accept = self.block(accept);
+                        let reject = self.block(reject);
+                        if accept.is_empty() && reject.is_empty() {
+                            return;
+                        }
+                        Statement::If {
+                            condition,
+                            accept,
+                            reject,
+                        }
+                    }
+                }
+            }
+            Statement::Switch {
+                selector,
+                ref cases,
+            } => {
+                let selector = self.expr(selector);
+                let value = match self.literal(selector) {
+                    Some(Literal::I32(value)) => Some(SwitchValue::I32(value)),
+                    Some(Literal::U32(value)) => Some(SwitchValue::U32(value)),
+                    _ => None,
+                };
+                let taken = value.and_then(|value| {
+                    let index =
+                        cases
+                            .iter()
+                            .position(|case| case.value == value)
+                            .or_else(|| {
+                                cases
+                                    .iter()
+                                    .position(|case| case.value == SwitchValue::Default)
+                            })?;
+                    Some(&cases[index..])
+                });
+                match taken {
+                    // A `Break` in the body would exit the switch, so it can
+                    // only be replaced by the body if there's none.
+                    Some(taken) if !taken.iter().any(|case| breaks_out(&case.body)) => {
+                        for case in taken {
+                            self.splice(&case.body, block);
+                            if !case.fall_through {
+                                break;
+                            }
+                        }
+                        return;
+                    }
+                    _ => Statement::Switch {
+                        selector,
+                        cases: cases
+                            .iter()
+                            .map(|case| crate::SwitchCase {
+                                value: case.value,
+                                body: self.block(&case.body),
+                                fall_through: case.fall_through,
+                            })
+                            .collect(),
+                    },
+                }
+            }
+            Statement::Loop {
+                ref body,
+                ref continuing,
+                break_if,
+            } => {
+                let body = self.block(body);
+                let continuing = self.block(continuing);
+                Statement::Loop {
+                    body,
+                    continuing,
+                    break_if: break_if.map(|break_if| self.expr(break_if)),
+                }
+            }
+            _ => {
+                let mut statement = statement.clone();
+                map_statement_operands(&mut statement, |operand| *operand = self.expr(*operand));
+                statement
+            }
+        };
+        block.push(statement, span);
+    }
+
+    /// Appends the statements of `taken` to `block`.
+    ///
+    /// The statements are not wrapped in a [`Statement::Block`], so that
+    /// simple branches don't leave a trace in the output.
+    fn splice(&mut self, taken: &Block, block: &mut Block) {
+        let mut taken = self.block(taken);
+        block.append(&mut taken);
+    }
+}
+
+/// Returns `true` if `block` contains a [`Statement::Break`] that isn't nested
+/// in a loop or switch of its own.
+fn breaks_out(block: &Block) -> bool {
+    block.iter().any(|statement| match *statement {
+        Statement::Break => true,
+        Statement::Loop { .. } | Statement::Switch { .. } => false,
+        _ => {
+            let mut found = false;
+            for_each_child_block(statement, |block| found |= breaks_out(block));
+            found
+        }
+    })
+}
+
+/// Removes unused named expressions and unused expressions from `Emit`
+/// statements.
+fn eliminate_dead_expressions(function: &mut Function) {
+    let mut used = HandleSet::for_arena(&function.expressions);
+
+    fn mark_block(block: &mut Block, used: &mut HandleSet<Expression>) {
+        for statement in block.iter_mut() {
+            map_statement_operands(statement, |operand| {
+                used.insert(*operand);
+            });
+            match *statement {
+                Statement::Block(ref mut block) => mark_block(block, used),
+                Statement::If {
+                    ref mut accept,
+                    ref mut reject,
+                    ..
+                } => {
+                    mark_block(accept, used);
+                    mark_block(reject, used);
+                }
+                Statement::Switch { ref mut cases, .. } => {
+                    for case in cases {
+                        mark_block(&mut case.body, used);
+                    }
+                }
+                Statement::Loop {
+                    ref mut body,
+                    ref mut continuing,
+                    ..
+                } => {
+                    mark_block(body, used);
+                    mark_block(continuing, used);
+                }
+                _ => {}
+            }
+        }
+    }
+    mark_block(&mut function.body, &mut used);
+    for (_, local) in function.local_variables.iter() {
+        used.insert_iter(local.init);
+    }
+
+    // Operands always precede the expressions using them.
+    for (handle, expression) in function.expressions.iter_mut().rev() {
+        if used.contains(handle) {
+            map_expression_operands(expression, |operand| {
+                used.insert(*operand);
+            });
+        }
+    }
+
+    function
+        .named_expressions
+        .retain(|&handle, _| used.contains(handle));
+
+    fn trim_block(block: &mut Block, used: &HandleSet<Expression>) {
+        let mut trimmed = Block::with_capacity(block.len());
+        for (mut statement, span) in core::mem::take(block).span_into_iter() {
+            match statement {
+                Statement::Emit(ref range) => {
+                    let mut run = None;
+                    for handle in range.clone() {
+                        if used.contains(handle) {
+                            run = Some(run.map_or((handle, handle), |(first, _)| (first, handle)));
+                        } else if let Some((first, last)) = run.take() {
+                            trimmed
+                                .push(Statement::Emit(Range::new_from_bounds(first, last)), span);
+                        }
+                    }
+                    if let Some((first, last)) = run {
+                        trimmed.push(Statement::Emit(Range::new_from_bounds(first, last)), span);
+                    }
+                    continue;
+                }
+                Statement::Block(ref mut block) => trim_block(block, used),
+                Statement::If {
+                    ref mut accept,
+                    ref mut reject,
+                    ..
+                } => {
+                    trim_block(accept, used);
+                    trim_block(reject, used);
+                }
+                Statement::Switch { ref mut cases, .. } => {
+                    for case in cases {
+                        trim_block(&mut case.body, used);
+                    }
+                }
+                Statement::Loop {
+                    ref mut body,
+                    ref mut continuing,
+                    ..
+                } => {
+                    trim_block(body, used);
+                    trim_block(continuing, used);
+                }
+                _ => {}
+            }
+            trimmed.push(statement, span);
+        }
+        *block = trimmed;
+    }
+    trim_block(&mut function.body, &used);
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::*;
+
+    /// Parses `source`, inlines all calls, optimizes it, and checks that the
+    /// module is still valid.
+    fn optimize_and_validate(source: &str) -> Module {
+        let validate = |module: &Module| {
+            crate::valid::Validator::new(
+                crate::valid::ValidationFlags::all(),
+                crate::valid::Capabilities::default(),
+            )
+            .validate(module)
+            .unwrap_or_else(|err| panic!("{err:?}"));
+        };
+
+        let mut module = crate::front::wgsl::parse_str(source).unwrap();
+        validate(&module);
+        super::super::inline(&mut module, &Default::default());
+        optimize(&mut module);
+        validate(&module);
+        crate::compact::compact(&mut module, crate::compact::KeepUnused::Yes);
+        validate(&module);
+        module
+    }
+
+    fn count(block: &Block, f: &impl Fn(&Statement) -> bool) -> usize {
+        block
+            .iter()
+            .map(|statement| {
+                let mut n = usize::from(f(statement));
+                for_each_child_block(statement, |block| n += count(block, f));
+                n
+            })
+            .sum()
+    }
+
+    #[test]
+    fn constant_branches() {
+        let module = optimize_and_validate(
+            "
+            fn pick(mode: i32, x: f32) -> f32 {
+                var r = x;
+                if mode == 1 {
+                    r *= 2.0;
+                } else {
+                    r += 1.0;
+                }
+                switch mode {
+                    case 0: { r = 0.0; }
+                    case 1, 2: { r += 1.0; }
+                    default: {}
+                }
+                return r;
+            }
+
+            @fragment
+            fn main(@location(0) x: f32) -> @location(0) f32 {
+                return pick(1, x) + pick(5, x);
+            }
+            ",
+        );
+
+        let body = &module.entry_points[0].function.body;
+        let branches = count(body, &|statement| {
+            matches!(*statement, Statement::If { .. } | Statement::Switch { .. })
+        });
+        assert_eq!(branches, 0);
+    }
+
+    #[test]
+    fn unreachable_statements() {
+        let module = optimize_and_validate(
+            "
+            @fragment
+            fn main(@location(0) x: f32) -> @location(0) f32 {
+                var r = x;
+                loop {
+                    if r > 1.0 {
+                        break;
+                        r = 0.0;
+                    }
+                    r *= 2.0;
+                }
+                return r;
+            }
+            ",
+        );
+
+        let body = &module.entry_points[0].function.body;
+        let stores = count(body, &|statement| {
+            matches!(*statement, Statement::Store { .. })
+        });
+        assert_eq!(stores, 2);
+    }
+
+    #[test]
+    fn unused_expressions() {
+        let module = optimize_and_validate(
+            "
+            @fragment
+            fn main(@location(0) x: f32) -> @location(0) f32 {
+                let unused = x * 2.0;
+                let used = x + 1.0;
+                return used;
+            }
+            ",
+        );
+
+        let function = &module.entry_points[0].function;
+        let names: Vec<_> = function.named_expressions.values().collect();
+        assert!(names.contains(&&"used".into()));
+        assert!(!names.contains(&&"unused".into()));
+        assert!(!function.expressions.iter().any(|(_, expression)| matches!(
+            *expression,
+            Expression::Binary {
+                op: crate::BinaryOperator::Multiply,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn folded_index() {
+        // Folding `i + 3` would make the index a constant out of bounds
+        // index, which the validator rejects.
+        let module = optimize_and_validate(
+            "
+            fn element(i: i32) -> f32 {
+                var a = array(1.0, 2.0, 3.0, 4.0);
+                return a[i + 3];
+            }
+
+            @fragment
+            fn main() -> @location(0) f32 {
+                return element(2);
+            }
+            ",
+        );
+
+        let function = &module.entry_points[0].function;
+        assert!(function
+            .expressions
+            .iter()
+            .any(|(_, expression)| matches!(*expression, Expression::Access { .. })));
+    }
+}
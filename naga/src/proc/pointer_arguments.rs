@@ -0,0 +1,552 @@
+/*!
+Specialization of functions taking pointers to global variables.
+
+With the WGSL `unrestricted_pointer_parameters` language extension, functions
+can take pointers in the [`Uniform`], [`Storage`] and [`WorkGroup`] address
+spaces. Most backends can't express such pointers as function parameters, or
+not without extra capabilities: there are no pointers to buffers in GLSL, and
+HLSL storage buffers are accessed through the global variable itself.
+
+[`specialize_pointer_arguments`] removes them by making a copy of the function
+for every distinct way it's called, in which the pointer is rebuilt from the
+global variable it was derived from. For example:
+
+```wgsl
+@group(0) @binding(0) var<storage, read_write> buffers: array<array<f32, 4>, 2>;
+
+fn clear(p: ptr<storage, array<f32, 4>, read_write>, i: u32) { p[i] = 0.0; }
+
+@compute @workgroup_size(1)
+fn main(@builtin(local_invocation_index) j: u32) { clear(&buffers[j], 3u); }
+```
+
+becomes:
+
+```wgsl
+fn clear(p_index: u32, i: u32) { buffers[p_index][i] = 0.0; }
+
+@compute @workgroup_size(1)
+fn main(@builtin(local_invocation_index) j: u32) { clear(j, 3u); }
+```
+
+Constant indices are part of the specialization, while dynamic ones are passed
+as `u32` arguments.
+
+[`Uniform`]: crate::AddressSpace::Uniform
+[`Storage`]: crate::AddressSpace::Storage
+[`WorkGroup`]: crate::AddressSpace::WorkGroup
+*/
+
+use alloc::{format, vec::Vec};
+
+use super::{
+    inline::{Inliner, Instance},
+    visit::{emit_from, for_each_child_block_mut},
+    ResolveContext, TypeResolution,
+};
+use crate::arena::{Arena, Handle, HandleVec};
+use crate::{
+    AddressSpace, Block, Expression, FastHashMap, Function, FunctionArgument, GlobalVariable,
+    LocalVariable, Module, NamedExpressions, Scalar, ScalarKind, Span, Statement, Type, TypeInner,
+};
+
+/// A pointer derived from a global variable: the variable and, for every
+/// access from it, either a constant index or `None` for a dynamic one.
+type Root = (Handle<GlobalVariable>, Vec<Option<u32>>);
+
+/// A function and the roots of its pointer arguments, `None` for other
+/// arguments.
+type Variant = (Handle<Function>, Vec<Option<Root>>);
+
+/// Returns `true` if pointers in `space` need to be specialized away.
+const fn is_unrestricted(space: AddressSpace) -> bool {
+    matches!(
+        space,
+        AddressSpace::Uniform | AddressSpace::Storage { .. } | AddressSpace::WorkGroup
+    )
+}
+
+/// Returns, for every argument of `function`, whether it's a pointer that
+/// needs to be specialized away.
+fn unrestricted_arguments<'a>(
+    module: &'a Module,
+    function: &'a Function,
+) -> impl Iterator<Item = bool> + 'a {
+    function.arguments.iter().map(|argument| {
+        module.types[argument.ty]
+            .inner
+            .pointer_space()
+            .is_some_and(is_unrestricted)
+    })
+}
+
+/// Returns `true` if any function in `module` takes a pointer that
+/// [`specialize_pointer_arguments`] would remove.
+pub(crate) fn has_unrestricted_pointer_arguments(module: &Module) -> bool {
+    module
+        .functions
+        .iter()
+        .any(|(_, function)| unrestricted_arguments(module, function).any(|is| is))
+}
+
+/// Replace arguments of `module`'s functions that are pointers in the
+/// [`Uniform`], [`Storage`] or [`WorkGroup`] address spaces by direct accesses
+/// to the global variables they point to, which `module` must be valid.
+///
+/// Every call to such a function whose pointer arguments are all access chains
+/// rooted at a global variable is redirected to a copy specialized for those
+/// chains, see the [module documentation](self). The original functions are
+/// left in place, use [`compact`] to remove those that are no longer called.
+/// Function handles are renumbered, so any [`ModuleInfo`] for `module` must be
+/// recomputed by validating it again.
+///
+/// [`Uniform`]: AddressSpace::Uniform
+/// [`Storage`]: AddressSpace::Storage
+/// [`WorkGroup`]: AddressSpace::WorkGroup
+/// [`compact`]: crate::compact::compact
+/// [`ModuleInfo`]: crate::valid::ModuleInfo
+pub fn specialize_pointer_arguments(module: &mut Module) {
+    if !has_unrestricted_pointer_arguments(module) {
+        return;
+    }
+
+    let index_ty = module.types.insert(
+        Type {
+            name: None,
+            inner: TypeInner::Scalar(Scalar::U32),
+        },
+        Span::UNDEFINED,
+    );
+
+    let module_ref: &Module = module;
+    let mut specializer = Specializer {
+        module: module_ref,
+        global: module_ref.to_ctx(),
+        index_ty,
+        functions: Arena::new(),
+        map: HandleVec::with_capacity(module_ref.functions.len()),
+        variants: FastHashMap::default(),
+    };
+    for (handle, function) in module_ref.functions.iter() {
+        let mut function = function.clone();
+        specializer.rewrite_calls(&mut function);
+        let new_handle = specializer
+            .functions
+            .append(function, module_ref.functions.get_span(handle));
+        specializer.map.insert(handle, new_handle);
+    }
+    let entry_points: Vec<_> = module_ref
+        .entry_points
+        .iter()
+        .map(|ep| {
+            let mut function = ep.function.clone();
+            specializer.rewrite_calls(&mut function);
+            function
+        })
+        .collect();
+
+    let Specializer { functions, map, .. } = specializer;
+    module.functions = functions;
+    for (ep, function) in module.entry_points.iter_mut().zip(entry_points) {
+        ep.function = function;
+    }
+    if let Some(ref mut doc_comments) = module.doc_comments {
+        doc_comments.functions = core::mem::take(&mut doc_comments.functions)
+            .into_iter()
+            .map(|(handle, comment)| (map[handle], comment))
+            .collect();
+    }
+}
+
+struct Specializer<'a> {
+    module: &'a Module,
+    global: super::GlobalCtx<'a>,
+    /// The `u32` type of dynamic indices.
+    index_ty: Handle<Type>,
+    /// The new function arena, in which specialized copies are appended
+    /// right before their first caller.
+    functions: Arena<Function>,
+    /// The new handles of the original functions.
+    map: HandleVec<Function, Handle<Function>>,
+    /// The specialized copies of functions, by the roots of their pointer
+    /// arguments.
+    variants: FastHashMap<Variant, Handle<Function>>,
+}
+
+/// The function whose calls are being rewritten.
+struct Caller<'f> {
+    expressions: &'f mut Arena<Expression>,
+    local_variables: &'f Arena<LocalVariable>,
+    arguments: &'f [FunctionArgument],
+    /// The types of the original `expressions`, resolved on first use.
+    types: Option<Vec<TypeResolution>>,
+    /// The [`Expression::CallResult`]s to redirect to the new callees, once
+    /// `types` isn't needed anymore.
+    results: Vec<(Handle<Expression>, Handle<Function>)>,
+}
+
+impl Specializer<'_> {
+    /// Redirects calls in `function` to specialized copies of the callees,
+    /// and all other calls to the new handles of the callees.
+    fn rewrite_calls(&mut self, function: &mut Function) {
+        let mut caller = Caller {
+            expressions: &mut function.expressions,
+            local_variables: &function.local_variables,
+            arguments: &function.arguments,
+            types: None,
+            results: Vec::new(),
+        };
+        self.rewrite_block(&mut caller, &mut function.body);
+        for (result, callee) in caller.results {
+            caller.expressions[result] = Expression::CallResult(callee);
+        }
+    }
+
+    fn rewrite_block(&mut self, caller: &mut Caller, block: &mut Block) {
+        let old_block = core::mem::take(block);
+        for (mut statement, span) in old_block.span_into_iter() {
+            for_each_child_block_mut(&mut statement, |child| self.rewrite_block(caller, child));
+            if let Statement::Call {
+                ref mut function,
+                ref mut arguments,
+                result,
+            } = statement
+            {
+                *function = match self.specialize_call(caller, *function, arguments, span, block) {
+                    Some(variant) => variant,
+                    None => self.map[*function],
+                };
+                if let Some(result) = result {
+                    caller.results.push((result, *function));
+                }
+            }
+            block.push(statement, span);
+        }
+    }
+
+    /// Returns the copy of `function` specialized for `arguments`, replacing
+    /// the pointers in `arguments` by their dynamic indices.
+    ///
+    /// Returns `None` if `function` doesn't need to be specialized, or can't be
+    /// for these arguments.
+    fn specialize_call(
+        &mut self,
+        caller: &mut Caller,
+        function: Handle<Function>,
+        arguments: &mut Vec<Handle<Expression>>,
+        span: Span,
+        block: &mut Block,
+    ) -> Option<Handle<Function>> {
+        let module = self.module;
+        let unrestricted: Vec<_> =
+            unrestricted_arguments(module, &module.functions[function]).collect();
+        if !unrestricted.contains(&true) {
+            return None;
+        }
+
+        let mut roots = Vec::with_capacity(arguments.len());
+        let mut indices = Vec::new();
+        for (&argument, is_unrestricted) in arguments.iter().zip(unrestricted) {
+            if is_unrestricted {
+                let start = indices.len();
+                roots.push(Some(self.root(
+                    caller.expressions,
+                    argument,
+                    &mut indices,
+                )?));
+                indices[start..].reverse();
+            } else {
+                roots.push(None);
+            }
+        }
+
+        // Dynamic indices may be signed, but negative ones are out of bounds
+        // all the same once converted.
+        let start = caller.expressions.len();
+        let mut indices = indices.into_iter();
+        let mut new_arguments = Vec::with_capacity(arguments.len());
+        for (&argument, root) in arguments.iter().zip(roots.iter()) {
+            let Some((_, ref steps)) = *root else {
+                new_arguments.push(argument);
+                continue;
+            };
+            for _ in steps.iter().filter(|step| step.is_none()) {
+                let index = indices.next().unwrap();
+                if self.is_index_type(caller, index) {
+                    new_arguments.push(index);
+                } else {
+                    new_arguments.push(caller.expressions.append(
+                        Expression::As {
+                            expr: index,
+                            kind: ScalarKind::Uint,
+                            convert: Some(4),
+                        },
+                        span,
+                    ));
+                }
+            }
+        }
+        emit_from(caller.expressions, start, span, block);
+        *arguments = new_arguments;
+
+        let key = (function, roots);
+        if let Some(&variant) = self.variants.get(&key) {
+            return Some(variant);
+        }
+        let variant = self.specialize(key.0, &key.1);
+        self.variants.insert(key, variant);
+        Some(variant)
+    }
+
+    /// Returns the root of `pointer`, adding its dynamic indices to `indices`
+    /// from the last access to the first.
+    fn root(
+        &self,
+        expressions: &Arena<Expression>,
+        mut pointer: Handle<Expression>,
+        indices: &mut Vec<Handle<Expression>>,
+    ) -> Option<Root> {
+        let mut steps = Vec::new();
+        loop {
+            match expressions[pointer] {
+                Expression::GlobalVariable(global) => {
+                    steps.reverse();
+                    return Some((global, steps));
+                }
+                Expression::AccessIndex { base, index } => {
+                    steps.push(Some(index));
+                    pointer = base;
+                }
+                Expression::Access { base, index } => {
+                    match self.global.eval_expr_to_u32_from(index, expressions) {
+                        Ok(value) => steps.push(Some(value)),
+                        Err(_) => {
+                            steps.push(None);
+                            indices.push(index);
+                        }
+                    }
+                    pointer = base;
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Returns `true` if `index` is already a `u32`.
+    fn is_index_type(&self, caller: &mut Caller, index: Handle<Expression>) -> bool {
+        let module = self.module;
+        let types = caller.types.get_or_insert_with(|| {
+            let context =
+                ResolveContext::with_locals(module, caller.local_variables, caller.arguments);
+            let mut types = Vec::with_capacity(caller.expressions.len());
+            for (_, expression) in caller.expressions.iter() {
+                let Ok(resolution) =
+                    context.resolve(expression, |handle| Ok(&types[handle.index()]))
+                else {
+                    break;
+                };
+                types.push(resolution);
+            }
+            types
+        });
+        types.get(index.index()).is_some_and(|resolution| {
+            *resolution.inner_with(&module.types) == TypeInner::Scalar(Scalar::U32)
+        })
+    }
+
+    /// Appends a copy of `function` with the pointer arguments that have a
+    /// root in `roots` replaced by their dynamic indices.
+    fn specialize(
+        &mut self,
+        function: Handle<Function>,
+        roots: &[Option<Root>],
+    ) -> Handle<Function> {
+        let module = self.module;
+        let original = &module.functions[function];
+        let span = module.functions.get_span(function);
+
+        let mut specialized = Function {
+            name: original.name.clone(),
+            arguments: Vec::with_capacity(original.arguments.len()),
+            result: original.result.clone(),
+            local_variables: original.local_variables.clone(),
+            expressions: Arena::new(),
+            named_expressions: NamedExpressions::default(),
+            body: Block::new(),
+            diagnostic_filter_leaf: original.diagnostic_filter_leaf,
+        };
+
+        // Rebuild the pointers at the start of the body.
+        let mut values = Vec::with_capacity(original.arguments.len());
+        for (argument, root) in original.arguments.iter().zip(roots) {
+            let Some((global, ref steps)) = *root else {
+                let index = specialized.arguments.len() as u32;
+                specialized.arguments.push(argument.clone());
+                values.push(
+                    specialized
+                        .expressions
+                        .append(Expression::FunctionArgument(index), span),
+                );
+                continue;
+            };
+
+            let start = specialized.expressions.len();
+            let mut pointer = specialized
+                .expressions
+                .append(Expression::GlobalVariable(global), span);
+            for &step in steps {
+                let access = match step {
+                    Some(index) => Expression::AccessIndex {
+                        base: pointer,
+                        index,
+                    },
+                    None => {
+                        let index = specialized.arguments.len() as u32;
+                        specialized.arguments.push(FunctionArgument {
+                            name: argument.name.as_ref().map(|name| format!("{name}_index")),
+                            ty: self.index_ty,
+                            binding: None,
+                        });
+                        let index = specialized
+                            .expressions
+                            .append(Expression::FunctionArgument(index), span);
+                        Expression::Access {
+                            base: pointer,
+                            index,
+                        }
+                    }
+                };
+                pointer = specialized.expressions.append(access, span);
+            }
+            emit_from(&specialized.expressions, start, span, &mut specialized.body);
+            values.push(pointer);
+        }
+
+        let initializers: Vec<_> = original
+            .local_variables
+            .iter()
+            .filter_map(|(handle, local)| Some((handle, local.init?)))
+            .collect();
+
+        let mut inliner = Inliner {
+            global: self.global,
+            functions: &module.functions,
+            candidate: &|_| false,
+            expressions: core::mem::take(&mut specialized.expressions),
+            local_variables: &mut specialized.local_variables,
+            named_expressions: NamedExpressions::default(),
+        };
+        let mut instance = Instance::new(
+            &original.expressions,
+            &original.named_expressions,
+            None,
+            Some(values),
+        );
+        let mut body = inliner.block(&mut instance, &original.body);
+        for (handle, init) in initializers {
+            let init = inliner.expr(&mut instance, init);
+            inliner.local_variables.get_mut(handle).init = Some(init);
+        }
+
+        specialized.expressions = inliner.expressions;
+        specialized.named_expressions = inliner.named_expressions;
+        specialized.body.append(&mut body);
+
+        self.rewrite_calls(&mut specialized);
+        self.functions.append(specialized, span)
+    }
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::*;
+    use crate::valid::{Capabilities, ValidationFlags, Validator};
+
+    fn specialize_and_validate(source: &str) -> Module {
+        let mut module = crate::front::wgsl::parse_str(source).unwrap();
+        Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .unwrap();
+        specialize_pointer_arguments(&mut module);
+        Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .unwrap();
+        crate::compact::compact(&mut module, crate::compact::KeepUnused::No);
+        Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .unwrap();
+        assert!(!has_unrestricted_pointer_arguments(&module));
+        module
+    }
+
+    fn argument_names(function: &Function) -> Vec<&str> {
+        function
+            .arguments
+            .iter()
+            .map(|argument| argument.name.as_deref().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn dynamic_indices() {
+        let module = specialize_and_validate(
+            "
+            @group(0) @binding(0) var<storage, read_write> buffers: array<array<f32, 4>, 2>;
+
+            fn clear(p: ptr<storage, array<f32, 4>, read_write>, i: u32) {
+                p[i] = 0.0;
+            }
+
+            @compute @workgroup_size(1)
+            fn main(@builtin(local_invocation_index) j: u32) {
+                clear(&buffers[j], 3u);
+                clear(&buffers[i32(j) - 1], 2u);
+                clear(&buffers[1], 1u);
+            }
+            ",
+        );
+        let names: Vec<_> = module
+            .functions
+            .iter()
+            .map(|(_, function)| argument_names(function))
+            .collect();
+        // The calls with dynamic indices share a copy.
+        assert_eq!(names, [&["p_index", "i"][..], &["i"]]);
+    }
+
+    #[test]
+    fn nested_calls() {
+        let module = specialize_and_validate(
+            "
+            struct Counters { hits: atomic<u32>, misses: atomic<u32> }
+
+            @group(0) @binding(0) var<storage, read_write> counters: Counters;
+            var<workgroup> local_counters: Counters;
+
+            fn bump(p: ptr<storage, atomic<u32>, read_write>) -> u32 {
+                return atomicAdd(p, 1u);
+            }
+
+            fn bump_both(c: ptr<storage, Counters, read_write>) {
+                bump(&(*c).hits);
+                bump(&(*c).misses);
+            }
+
+            fn bump_local(p: ptr<workgroup, atomic<u32>>) {
+                atomicAdd(p, 1u);
+            }
+
+            @compute @workgroup_size(1)
+            fn main() {
+                bump_both(&counters);
+                bump_local(&local_counters.hits);
+            }
+            ",
+        );
+        // `bump` is specialized twice, `bump_both` and `bump_local` once.
+        assert_eq!(module.functions.len(), 4);
+        for (_, function) in module.functions.iter() {
+            assert!(function.arguments.is_empty());
+        }
+    }
+}
@@ -0,0 +1,374 @@
+/*!
+Helpers for walking and rewriting function bodies.
+*/
+
+use crate::arena::{Arena, Handle, Range};
+use crate::{Block, Expression, Span, Statement};
+
+/// Calls `f` on each block directly nested in `statement`.
+pub(super) fn for_each_child_block(statement: &Statement, mut f: impl FnMut(&Block)) {
+    match *statement {
+        Statement::Block(ref block) => f(block),
+        Statement::If {
+            ref accept,
+            ref reject,
+            ..
+        } => {
+            f(accept);
+            f(reject);
+        }
+        Statement::Switch { ref cases, .. } => {
+            for case in cases {
+                f(&case.body);
+            }
+        }
+        Statement::Loop {
+            ref body,
+            ref continuing,
+            ..
+        } => {
+            f(body);
+            f(continuing);
+        }
+        _ => {}
+    }
+}
+
+/// Like [`for_each_child_block`], but the blocks can be modified.
+pub(super) fn for_each_child_block_mut(statement: &mut Statement, mut f: impl FnMut(&mut Block)) {
+    match *statement {
+        Statement::Block(ref mut block) => f(block),
+        Statement::If {
+            ref mut accept,
+            ref mut reject,
+            ..
+        } => {
+            f(accept);
+            f(reject);
+        }
+        Statement::Switch { ref mut cases, .. } => {
+            for case in cases {
+                f(&mut case.body);
+            }
+        }
+        Statement::Loop {
+            ref mut body,
+            ref mut continuing,
+            ..
+        } => {
+            f(body);
+            f(continuing);
+        }
+        _ => {}
+    }
+}
+
+/// Returns `true` if `expression` must be covered by a [`Statement::Emit`].
+pub(super) const fn needs_emit(expression: &Expression) -> bool {
+    !expression.needs_pre_emit()
+        && !matches!(
+            *expression,
+            Expression::CallResult(_)
+                | Expression::AtomicResult { .. }
+                | Expression::WorkGroupUniformLoadResult { .. }
+                | Expression::SubgroupBallotResult
+                | Expression::SubgroupOperationResult { .. }
+                | Expression::RayQueryProceedResult
+        )
+}
+
+/// Calls `f` on every expression handle directly used by `expression`.
+pub(super) fn map_expression_operands(
+    expression: &mut Expression,
+    mut f: impl FnMut(&mut Handle<Expression>),
+) {
+    use crate::Expression as Ex;
+    match *expression {
+        Ex::Literal(_)
+        | Ex::Constant(_)
+        | Ex::Override(_)
+        | Ex::ZeroValue(_)
+        | Ex::FunctionArgument(_)
+        | Ex::GlobalVariable(_)
+        | Ex::LocalVariable(_)
+        | Ex::CallResult(_)
+        | Ex::AtomicResult { .. }
+        | Ex::WorkGroupUniformLoadResult { .. }
+        | Ex::SubgroupBallotResult
+        | Ex::SubgroupOperationResult { .. }
+        | Ex::RayQueryProceedResult => {}
+        Ex::Compose {
+            ref mut components, ..
+        } => components.iter_mut().for_each(f),
+        Ex::Access {
+            ref mut base,
+            ref mut index,
+        } => {
+            f(base);
+            f(index);
+        }
+        Ex::AccessIndex { ref mut base, .. } => f(base),
+        Ex::Splat { ref mut value, .. } => f(value),
+        Ex::Swizzle { ref mut vector, .. } => f(vector),
+        Ex::Load { ref mut pointer } => f(pointer),
+        Ex::ImageSample {
+            ref mut image,
+            ref mut sampler,
+            ref mut coordinate,
+            ref mut array_index,
+            ref mut offset,
+            ref mut level,
+            ref mut depth_ref,
+            ..
+        } => {
+            f(image);
+            f(sampler);
+            f(coordinate);
+            array_index.iter_mut().for_each(&mut f);
+            offset.iter_mut().for_each(&mut f);
+            match *level {
+                crate::SampleLevel::Auto | crate::SampleLevel::Zero => {}
+                crate::SampleLevel::Exact(ref mut expr)
+                | crate::SampleLevel::Bias(ref mut expr) => f(expr),
+                crate::SampleLevel::Gradient {
+                    ref mut x,
+                    ref mut y,
+                } => {
+                    f(x);
+                    f(y);
+                }
+            }
+            depth_ref.iter_mut().for_each(f);
+        }
+        Ex::ImageLoad {
+            ref mut image,
+            ref mut coordinate,
+            ref mut array_index,
+            ref mut sample,
+            ref mut level,
+        } => {
+            f(image);
+            f(coordinate);
+            array_index.iter_mut().for_each(&mut f);
+            sample.iter_mut().for_each(&mut f);
+            level.iter_mut().for_each(f);
+        }
+        Ex::ImageQuery {
+            ref mut image,
+            ref mut query,
+        } => {
+            f(image);
+            if let crate::ImageQuery::Size {
+                level: Some(ref mut level),
+            } = *query
+            {
+                f(level);
+            }
+        }
+        Ex::Unary { ref mut expr, .. } => f(expr),
+        Ex::Binary {
+            ref mut left,
+            ref mut right,
+            ..
+        } => {
+            f(left);
+            f(right);
+        }
+        Ex::Select {
+            ref mut condition,
+            ref mut accept,
+            ref mut reject,
+        } => {
+            f(condition);
+            f(accept);
+            f(reject);
+        }
+        Ex::Derivative { ref mut expr, .. } => f(expr),
+        Ex::Relational {
+            ref mut argument, ..
+        } => f(argument),
+        Ex::Math {
+            ref mut arg,
+            ref mut arg1,
+            ref mut arg2,
+            ref mut arg3,
+            ..
+        } => {
+            f(arg);
+            arg1.iter_mut().for_each(&mut f);
+            arg2.iter_mut().for_each(&mut f);
+            arg3.iter_mut().for_each(f);
+        }
+        Ex::As { ref mut expr, .. } => f(expr),
+        Ex::ArrayLength(ref mut expr) => f(expr),
+        Ex::RayQueryGetIntersection { ref mut query, .. } => f(query),
+        Ex::RayQueryVertexPositions { ref mut query, .. } => f(query),
+    }
+}
+
+/// Calls `f` on every expression handle directly used by `statement`, not
+/// including the statements in its child blocks.
+pub(super) fn map_statement_operands(
+    statement: &mut Statement,
+    mut f: impl FnMut(&mut Handle<Expression>),
+) {
+    use crate::Statement as St;
+    match *statement {
+        St::Emit(_)
+        | St::Block(_)
+        | St::Break
+        | St::Continue
+        | St::Kill
+        | St::ControlBarrier(_)
+        | St::MemoryBarrier(_) => {}
+        St::If {
+            ref mut condition, ..
+        } => f(condition),
+        St::Switch {
+            ref mut selector, ..
+        } => f(selector),
+        St::Loop {
+            ref mut break_if, ..
+        } => break_if.iter_mut().for_each(f),
+        St::Return { ref mut value } => value.iter_mut().for_each(f),
+        St::Store {
+            ref mut pointer,
+            ref mut value,
+        } => {
+            f(pointer);
+            f(value);
+        }
+        St::ImageStore {
+            ref mut image,
+            ref mut coordinate,
+            ref mut array_index,
+            ref mut value,
+        } => {
+            f(image);
+            f(coordinate);
+            array_index.iter_mut().for_each(&mut f);
+            f(value);
+        }
+        St::Atomic {
+            ref mut pointer,
+            ref mut fun,
+            ref mut value,
+            ref mut result,
+        } => {
+            f(pointer);
+            if let crate::AtomicFunction::Exchange {
+                compare: Some(ref mut compare),
+            } = *fun
+            {
+                f(compare);
+            }
+            f(value);
+            result.iter_mut().for_each(f);
+        }
+        St::ImageAtomic {
+            ref mut image,
+            ref mut coordinate,
+            ref mut array_index,
+            ref mut fun,
+            ref mut value,
+        } => {
+            f(image);
+            f(coordinate);
+            array_index.iter_mut().for_each(&mut f);
+            if let crate::AtomicFunction::Exchange {
+                compare: Some(ref mut compare),
+            } = *fun
+            {
+                f(compare);
+            }
+            f(value);
+        }
+        St::WorkGroupUniformLoad {
+            ref mut pointer,
+            ref mut result,
+        } => {
+            f(pointer);
+            f(result);
+        }
+        St::Call {
+            ref mut arguments,
+            ref mut result,
+            ..
+        } => {
+            arguments.iter_mut().for_each(&mut f);
+            result.iter_mut().for_each(f);
+        }
+        St::RayQuery {
+            ref mut query,
+            ref mut fun,
+        } => {
+            f(query);
+            match *fun {
+                crate::RayQueryFunction::Initialize {
+                    ref mut acceleration_structure,
+                    ref mut descriptor,
+                } => {
+                    f(acceleration_structure);
+                    f(descriptor);
+                }
+                crate::RayQueryFunction::Proceed { ref mut result } => f(result),
+                crate::RayQueryFunction::GenerateIntersection { ref mut hit_t } => f(hit_t),
+                crate::RayQueryFunction::ConfirmIntersection
+                | crate::RayQueryFunction::Terminate => {}
+            }
+        }
+        St::SubgroupBallot {
+            ref mut result,
+            ref mut predicate,
+        } => {
+            predicate.iter_mut().for_each(&mut f);
+            f(result);
+        }
+        St::SubgroupCollectiveOperation {
+            ref mut argument,
+            ref mut result,
+            ..
+        } => {
+            f(argument);
+            f(result);
+        }
+        St::SubgroupGather {
+            ref mut mode,
+            ref mut argument,
+            ref mut result,
+        } => {
+            match *mode {
+                crate::GatherMode::BroadcastFirst | crate::GatherMode::QuadSwap(_) => {}
+                crate::GatherMode::Broadcast(ref mut index)
+                | crate::GatherMode::Shuffle(ref mut index)
+                | crate::GatherMode::ShuffleDown(ref mut index)
+                | crate::GatherMode::ShuffleUp(ref mut index)
+                | crate::GatherMode::ShuffleXor(ref mut index)
+                | crate::GatherMode::QuadBroadcast(ref mut index) => f(index),
+            }
+            f(argument);
+            f(result);
+        }
+    }
+}
+
+/// Emits all expressions appended to `expressions` since it had `start`
+/// elements, skipping those that don't need to be emitted.
+pub(super) fn emit_from(
+    expressions: &Arena<Expression>,
+    start: usize,
+    span: Span,
+    block: &mut Block,
+) {
+    let mut run = None;
+    for (handle, expression) in expressions.iter().skip(start) {
+        if needs_emit(expression) {
+            run = Some(run.map_or((handle, handle), |(first, _)| (first, handle)));
+        } else if let Some((first, last)) = run.take() {
+            block.push(Statement::Emit(Range::new_from_bounds(first, last)), span);
+        }
+    }
+    if let Some((first, last)) = run {
+        block.push(Statement::Emit(Range::new_from_bounds(first, last)), span);
+    }
+}
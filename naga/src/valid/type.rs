@@ -214,15 +214,19 @@ fn check_member_layout(
 /// user-defined function, return `TypeFlags::ARGUMENT`. Otherwise, return
 /// `TypeFlags::empty()`.
 ///
-/// Pointers passed as arguments to user-defined functions must be in the
-/// `Function` or `Private` address space.
+/// Pointers passed as arguments to user-defined functions must not be in the
+/// `Handle` or `PushConstant` address space. Backends that can't pass pointers
+/// in the `Uniform`, `Storage` or `WorkGroup` address spaces rely on
+/// [`specialize_pointer_arguments`] to remove them.
+///
+/// [`specialize_pointer_arguments`]: crate::proc::specialize_pointer_arguments
 const fn ptr_space_argument_flag(space: crate::AddressSpace) -> TypeFlags {
     use crate::AddressSpace as As;
     match space {
-        As::Function | As::Private => TypeFlags::ARGUMENT,
-        As::Uniform | As::Storage { .. } | As::Handle | As::PushConstant | As::WorkGroup => {
-            TypeFlags::empty()
+        As::Function | As::Private | As::Uniform | As::Storage { .. } | As::WorkGroup => {
+            TypeFlags::ARGUMENT
         }
+        As::Handle | As::PushConstant => TypeFlags::empty(),
     }
 }
 
@@ -0,0 +1,66 @@
+// Binding arrays and globals accessed through the SM 6.6 descriptor heaps.
+
+struct UniformIndex {
+    index: u32
+};
+
+@group(0) @binding(0)
+var texture_array_unbounded: binding_array<texture_2d<f32>>;
+@group(0) @binding(1)
+var texture_array_bounded: binding_array<texture_2d<f32>, 5>;
+@group(0) @binding(2)
+var texture_array_storage: binding_array<texture_storage_2d<rgba32float, write>, 5>;
+@group(0) @binding(3)
+var samp: binding_array<sampler, 5>;
+@group(0) @binding(4)
+var samp_comp: binding_array<sampler_comparison, 5>;
+@group(0) @binding(5)
+var texture_depth: texture_depth_2d;
+@group(0) @binding(6)
+var<uniform> uni: UniformIndex;
+
+@group(1) @binding(0)
+var texture_single: texture_2d<f32>;
+@group(1) @binding(1)
+var samp_single: sampler;
+@group(1) @binding(2)
+var texture_not_lowered: texture_2d<f32>;
+@group(1) @binding(3)
+var texture_array_rest: binding_array<texture_2d<f32>>;
+
+struct FragmentIn {
+    @location(0) index: u32,
+};
+
+@fragment
+fn main(fragment_in: FragmentIn) -> @location(0) vec4<f32> {
+    let uniform_index = uni.index;
+    let non_uniform_index = fragment_in.index;
+
+    let uv = vec2<f32>(0.0);
+    let pix = vec2<i32>(0);
+
+    var u2 = vec2<u32>(0u);
+    var v4 = vec4<f32>(0.0);
+
+    u2 += textureDimensions(texture_array_unbounded[0]);
+    u2 += textureDimensions(texture_array_unbounded[uniform_index]);
+    u2 += textureDimensions(texture_array_unbounded[non_uniform_index]);
+
+    v4 += textureSample(texture_array_bounded[0], samp[0], uv);
+    v4 += textureSample(texture_array_bounded[uniform_index], samp[uniform_index], uv);
+    v4 += textureSample(texture_array_bounded[non_uniform_index], samp[non_uniform_index], uv);
+
+    v4 += textureGatherCompare(texture_depth, samp_comp[0], uv, 0.0);
+    v4 += textureGatherCompare(texture_depth, samp_comp[non_uniform_index], uv, 0.0);
+
+    textureStore(texture_array_storage[0], pix, v4);
+    textureStore(texture_array_storage[non_uniform_index], pix, v4);
+
+    v4 += textureSample(texture_single, samp_single, uv);
+    v4 += textureLoad(texture_not_lowered, pix, 0);
+    v4 += textureLoad(texture_array_rest[non_uniform_index], pix, 0);
+
+    let v2 = vec2<f32>(u2);
+    return v4 + vec4<f32>(v2.x, v2.y, v2.x, v2.y);
+}
@@ -0,0 +1,52 @@
+requires unrestricted_pointer_parameters;
+
+struct Particles {
+    count: u32,
+    positions: array<vec4<f32>>,
+}
+
+struct Params {
+    scale: f32,
+    offsets: array<vec4<f32>, 4>,
+}
+
+@group(0) @binding(0) var<storage, read_write> particles: Particles;
+@group(0) @binding(1) var<uniform> params: Params;
+@group(0) @binding(2) var<storage, read_write> histogram: array<array<atomic<u32>, 8>, 2>;
+
+var<workgroup> shared_positions: array<vec4<f32>, 64>;
+
+fn len(positions: ptr<storage, array<vec4<f32>>, read_write>) -> u32 {
+    return arrayLength(positions);
+}
+
+fn scaled(offset: ptr<uniform, vec4<f32>>, scale: ptr<uniform, f32>) -> vec4<f32> {
+    return *offset * *scale;
+}
+
+fn advance(position: ptr<storage, vec4<f32>, read_write>, offset: vec4<f32>) {
+    *position += offset;
+}
+
+fn count(bins: ptr<storage, array<atomic<u32>, 8>, read_write>, value: f32) {
+    atomicAdd(&bins[u32(clamp(value, 0.0, 7.0))], 1u);
+}
+
+fn stash(slot: ptr<workgroup, vec4<f32>>, position: ptr<storage, vec4<f32>, read_write>) {
+    *slot = *position;
+}
+
+@compute @workgroup_size(64)
+fn main(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(local_invocation_index) index: u32,
+) {
+    if id.x >= len(&particles.positions) {
+        return;
+    }
+    let offset = scaled(&params.offsets[id.x % 4u], &params.scale);
+    advance(&particles.positions[id.x], offset);
+    count(&histogram[0], particles.positions[id.x].x);
+    count(&histogram[1], particles.positions[id.x].y);
+    stash(&shared_positions[index], &particles.positions[id.x]);
+}
@@ -0,0 +1,529 @@
+/*!
+Tests for the CPU interpreter.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "interp"))]
+
+use naga::back::interp::{Buffer, Error, Interpreter, Options};
+use naga::proc::{BoundsCheckPolicies, BoundsCheckPolicy};
+use naga::ResourceBinding;
+
+fn parse(source: &str) -> (naga::Module, naga::valid::ModuleInfo) {
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .unwrap();
+    (module, info)
+}
+
+const fn binding(binding: u32) -> ResourceBinding {
+    ResourceBinding { group: 0, binding }
+}
+
+fn to_bytes<T: Copy>(values: &[T], to_le_bytes: impl Fn(T) -> [u8; 4]) -> Vec<u8> {
+    values.iter().flat_map(|&v| to_le_bytes(v)).collect()
+}
+
+fn from_bytes<T>(bytes: &[u8], from_le_bytes: impl Fn([u8; 4]) -> T) -> Vec<T> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| from_le_bytes(chunk.try_into().unwrap()))
+        .collect()
+}
+
+/// Run `entry_point` of `source` with a single `array<u32>` buffer at binding
+/// 0, returning the buffer's final contents.
+fn run_u32(
+    source: &str,
+    options: Options,
+    workgroups: [u32; 3],
+    data: &[u32],
+) -> Result<Vec<u32>, Error> {
+    let (module, info) = parse(source);
+    let interpreter = Interpreter::new(&module, &info, options);
+    let mut bytes = to_bytes(data, u32::to_le_bytes);
+    interpreter.dispatch(
+        "main",
+        workgroups,
+        &mut [Buffer {
+            binding: binding(0),
+            data: &mut bytes,
+        }],
+    )?;
+    Ok(from_bytes(&bytes, u32::from_le_bytes))
+}
+
+#[test]
+fn global_invocation_id() {
+    let source = "
+        @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+        @compute @workgroup_size(4)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            data[id.x] = data[id.x] * 2u + id.x;
+        }
+    ";
+    let output = run_u32(
+        source,
+        Options::default(),
+        [2, 1, 1],
+        &[1, 2, 3, 4, 5, 6, 7, 8],
+    )
+    .unwrap();
+    assert_eq!(output, [2, 5, 8, 11, 14, 17, 20, 23]);
+}
+
+#[test]
+fn float_buffers_and_structs() {
+    let source = "
+        struct Params {
+            scale: vec2<f32>,
+            offset: f32,
+        }
+
+        @group(0) @binding(0) var<uniform> params: Params;
+        @group(0) @binding(1) var<storage, read_write> points: array<vec2<f32>>;
+
+        @compute @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            let m = mat2x2<f32>(params.scale.x, 0.0, 0.0, params.scale.y);
+            points[id.x] = m * points[id.x] + vec2(params.offset);
+        }
+    ";
+    let (module, info) = parse(source);
+    let interpreter = Interpreter::new(&module, &info, Options::default());
+    // `Params` is 16 bytes: `scale`, `offset`, and padding.
+    let mut params = to_bytes(&[2.0f32, 3.0, 0.5, 0.0], f32::to_le_bytes);
+    let mut points = to_bytes(&[1.0f32, 1.0, -1.0, 2.0], f32::to_le_bytes);
+    interpreter
+        .dispatch(
+            "main",
+            [2, 1, 1],
+            &mut [
+                Buffer {
+                    binding: binding(0),
+                    data: &mut params,
+                },
+                Buffer {
+                    binding: binding(1),
+                    data: &mut points,
+                },
+            ],
+        )
+        .unwrap();
+    assert_eq!(
+        from_bytes(&points, f32::from_le_bytes),
+        [2.5, 3.5, -1.5, 6.5]
+    );
+}
+
+#[test]
+fn control_flow_and_calls() {
+    let source = "
+        @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+        fn collatz_step(n: ptr<function, u32>) {
+            switch *n % 2u {
+                case 0u: {
+                    *n /= 2u;
+                }
+                default: {
+                    *n = *n * 3u + 1u;
+                }
+            }
+        }
+
+        fn collatz(start: u32) -> u32 {
+            var n = start;
+            var steps = 0u;
+            loop {
+                if n == 1u {
+                    break;
+                }
+                steps++;
+                if n == 0u {
+                    continue;
+                }
+                collatz_step(&n);
+            }
+            return steps;
+        }
+
+        @compute @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            data[id.x] = collatz(data[id.x]);
+        }
+    ";
+    let output = run_u32(source, Options::default(), [4, 1, 1], &[1, 2, 6, 27]).unwrap();
+    assert_eq!(output, [0, 1, 8, 111]);
+}
+
+#[test]
+fn workgroup_barriers() {
+    // A tree reduction, with barriers inside a loop.
+    let source = "
+        @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+        var<workgroup> partial: array<u32, 8>;
+
+        @compute @workgroup_size(8)
+        fn main(
+            @builtin(local_invocation_index) index: u32,
+            @builtin(workgroup_id) group: vec3<u32>,
+        ) {
+            partial[index] = data[group.x * 8u + index];
+            workgroupBarrier();
+            for (var stride = 4u; stride > 0u; stride /= 2u) {
+                if index < stride {
+                    partial[index] += partial[index + stride];
+                }
+                workgroupBarrier();
+            }
+            let total = workgroupUniformLoad(&partial[0]);
+            data[group.x * 8u + index] = total;
+        }
+    ";
+    let input: Vec<u32> = (1..=16).collect();
+    let output = run_u32(source, Options::default(), [2, 1, 1], &input).unwrap();
+    assert_eq!(output[..8], [36; 8]);
+    assert_eq!(output[8..], [100; 8]);
+}
+
+#[test]
+fn atomics() {
+    let source = "
+        @group(0) @binding(0) var<storage, read_write> counters: array<atomic<u32>>;
+
+        @compute @workgroup_size(16)
+        fn main(@builtin(local_invocation_index) index: u32) {
+            atomicAdd(&counters[index % 2u], 1u);
+            atomicMax(&counters[2], index);
+            let old = atomicCompareExchangeWeak(&counters[3], index, index + 1u);
+            if old.exchanged {
+                atomicOr(&counters[4], 1u << index);
+            }
+        }
+    ";
+    let output = run_u32(source, Options::default(), [3, 1, 1], &[0; 5]).unwrap();
+    // Each invocation `i` exchanges the value `i` set by the one before it.
+    assert_eq!(output, [24, 24, 15, 16, 0xffff]);
+}
+
+#[test]
+fn bounds_check_policies() {
+    let source = "
+        @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            var local = array<u32, 4>(10u, 20u, 30u, 40u);
+            let index = data[0];
+            data[1] = local[index];
+            local[index] = 50u;
+            data[2] = local[3];
+            data[index] = 60u;
+        }
+    ";
+    let with_policy = |policy| Options {
+        bounds_check_policies: BoundsCheckPolicies {
+            index: policy,
+            buffer: policy,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let output = run_u32(
+        source,
+        with_policy(BoundsCheckPolicy::Restrict),
+        [1, 1, 1],
+        &[7, 0, 0, 0],
+    )
+    .unwrap();
+    assert_eq!(output, [7, 40, 50, 60]);
+
+    let output = run_u32(
+        source,
+        with_policy(BoundsCheckPolicy::ReadZeroSkipWrite),
+        [1, 1, 1],
+        &[7, 0, 0, 0],
+    )
+    .unwrap();
+    assert_eq!(output, [7, 0, 40, 0]);
+
+    let error = run_u32(
+        source,
+        with_policy(BoundsCheckPolicy::Unchecked),
+        [1, 1, 1],
+        &[7, 0, 0, 0],
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        Error::OutOfBounds {
+            index: 7,
+            length: 4
+        }
+    );
+
+    // In-bounds accesses behave the same under every policy.
+    let output = run_u32(
+        source,
+        with_policy(BoundsCheckPolicy::Unchecked),
+        [1, 1, 1],
+        &[2, 0, 0, 0],
+    )
+    .unwrap();
+    assert_eq!(output, [2, 30, 60, 0]);
+}
+
+#[test]
+fn array_length() {
+    let source = "
+        @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            data[0] = arrayLength(&data);
+        }
+    ";
+    let output = run_u32(source, Options::default(), [1, 1, 1], &[0; 6]).unwrap();
+    assert_eq!(output[0], 6);
+}
+
+#[test]
+fn builtin_functions() {
+    // Take the operands from the buffer, so the front end can't fold the
+    // calls to constants.
+    let source = "
+        @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            let a = data[0];
+            let f = bitcast<f32>(data[1]);
+            let n = i32(data[2]);
+            data[0] = countOneBits(a);
+            data[1] = extractBits(a, 4u, 8u);
+            data[2] = pack4x8unorm(vec4(0.0, 1.0, 0.5, f));
+            data[3] = u32(dot(vec3(1, 2, n), vec3(4, 5, 6)));
+            data[4] = a / 0u;
+            data[5] = u32(round(f) + floor(-f) + clamp(f * 3.0, 0.0, 3.0));
+            data[6] = u32(select(1, 2, all(vec2(f > 2.0, n > 3))));
+            data[7] = u32(length(vec2(f + 0.5, f + 1.5)));
+        }
+    ";
+    let input = [0xf0f0, 2.5f32.to_bits(), 3, 0, 0, 0, 0, 0];
+    let output = run_u32(source, Options::default(), [1, 1, 1], &input).unwrap();
+    assert_eq!(output, [8, 0x0f, 0xff80ff00, 32, 0xf0f0, 2, 1, 5]);
+}
+
+#[test]
+fn errors() {
+    let source = "
+        @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            loop {
+                data[0] += 1u;
+            }
+        }
+    ";
+    let (module, info) = parse(source);
+    let interpreter = Interpreter::new(
+        &module,
+        &info,
+        Options {
+            step_limit: Some(100),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        interpreter.dispatch("main", [1, 1, 1], &mut []),
+        Err(Error::MissingBinding(binding(0)))
+    );
+    assert_eq!(
+        interpreter.dispatch("other", [1, 1, 1], &mut []),
+        Err(Error::EntryPointNotFound("other".into()))
+    );
+
+    let mut bytes = [0; 4];
+    let result = interpreter.dispatch(
+        "main",
+        [1, 1, 1],
+        &mut [Buffer {
+            binding: binding(0),
+            data: &mut bytes,
+        }],
+    );
+    assert_eq!(result, Err(Error::StepLimitExceeded(100)));
+}
+
+/// Run the GLSL backend's polyfills for the data packing functions, parsed
+/// back by the GLSL frontend, and check them against WGSL's definitions.
+#[cfg(all(feature = "glsl-in", feature = "glsl-out"))]
+#[test]
+fn glsl_pack_polyfills() {
+    use naga::back::glsl;
+
+    // GLSL 330 lacks all of the builtins.
+    let (module, info) = parse(
+        "
+        @group(0) @binding(0) var<uniform> v: vec4<f32>;
+
+        @fragment
+        fn main() -> @location(0) vec4<u32> {
+            let h = unpack2x16float(pack2x16float(v.xy));
+            return vec4(pack4x8snorm(v), pack4x8unorm(v), pack2x16snorm(h), pack2x16unorm(v.zw));
+        }
+        ",
+    );
+    let options = glsl::Options {
+        version: glsl::Version::Desktop(330),
+        ..Default::default()
+    };
+    let pipeline_options = glsl::PipelineOptions {
+        shader_stage: naga::ShaderStage::Fragment,
+        entry_point: "main".into(),
+        multiview: None,
+    };
+    let mut output = String::new();
+    glsl::Writer::new(
+        &mut output,
+        &module,
+        &info,
+        &options,
+        &pipeline_options,
+        BoundsCheckPolicies::default(),
+    )
+    .unwrap()
+    .write()
+    .unwrap();
+    let function = |name: &str| {
+        let name = output.find(&format!(" {name}(")).unwrap();
+        let start = output[..name].rfind('\n').unwrap() + 1;
+        let end = name + output[name..].find("\n}\n").unwrap() + 3;
+        &output[start..end]
+    };
+    let polyfills = [
+        "naga_packSnorm4x8",
+        "naga_packUnorm4x8",
+        "naga_packSnorm2x16",
+        "naga_packUnorm2x16",
+        "naga_packHalf2x16",
+        "naga_unpackHalf2x16",
+    ]
+    .map(function)
+    .concat();
+
+    let source = format!(
+        "#version 450
+        layout(local_size_x = 1) in;
+        layout(std430, set = 0, binding = 0) buffer Inputs {{ vec4 inputs[]; }};
+        layout(std430, set = 0, binding = 1) buffer HalfInputs {{ uint half_inputs[]; }};
+        layout(std430, set = 0, binding = 2) buffer Outputs {{ uint outputs[]; }};
+        {polyfills}
+        void main() {{
+            uint i = gl_GlobalInvocationID.x;
+            vec4 v = inputs[i];
+            outputs[i * 8] = naga_packSnorm4x8(v);
+            outputs[i * 8 + 1] = naga_packUnorm4x8(v);
+            outputs[i * 8 + 2] = naga_packSnorm2x16(v.xy);
+            outputs[i * 8 + 3] = naga_packUnorm2x16(v.zw);
+            outputs[i * 8 + 4] = naga_packHalf2x16(v.xy);
+            outputs[i * 8 + 5] = naga_packHalf2x16(v.zw);
+            vec2 h = naga_unpackHalf2x16(half_inputs[i]);
+            outputs[i * 8 + 6] = floatBitsToUint(h.x);
+            outputs[i * 8 + 7] = floatBitsToUint(h.y);
+        }}
+        "
+    );
+    let module = naga::front::glsl::Frontend::default()
+        .parse(
+            &naga::front::glsl::Options::from(naga::ShaderStage::Compute),
+            &source,
+        )
+        .unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .unwrap();
+
+    // `1.0 + 2^-11` and `1.0 + 3 * 2^-11` are halfway between two `binary16`
+    // values, and round to the even one.
+    let inputs = [
+        [1.0f32, -2.0, 0.5, -0.5],
+        [65504.0, 1e6, 2f32.powi(-24), 0.0],
+        [1.000_488_3, 1.001_464_8, -0.75, 2.0],
+    ];
+    let half_inputs = [0xc000_3c00u32, 0x7c00_0001, 0x4000_ba00];
+    let mut input_bytes = to_bytes(inputs.as_flattened(), f32::to_le_bytes);
+    let mut half_input_bytes = to_bytes(&half_inputs, u32::to_le_bytes);
+    let mut output_bytes = vec![0; inputs.len() * 8 * 4];
+    Interpreter::new(&module, &info, Options::default())
+        .dispatch(
+            "main",
+            [inputs.len() as u32, 1, 1],
+            &mut [
+                Buffer {
+                    binding: binding(0),
+                    data: &mut input_bytes,
+                },
+                Buffer {
+                    binding: binding(1),
+                    data: &mut half_input_bytes,
+                },
+                Buffer {
+                    binding: binding(2),
+                    data: &mut output_bytes,
+                },
+            ],
+        )
+        .unwrap();
+    let outputs = from_bytes(&output_bytes, u32::from_le_bytes);
+
+    // WGSL rounds with `floor(0.5 + x)`, rather than to even.
+    let pack = |values: &[f32], min: f32, max: u32| {
+        values.iter().enumerate().fold(0, |packed, (i, &value)| {
+            let value = (0.5 + value.clamp(min, 1.0) * max as f32).floor() as i32 as u32;
+            let bits = 32 / values.len();
+            packed | (value & ((1 << bits) - 1)) << (bits * i)
+        })
+    };
+    let halves = [
+        [0xc000_3c00, 0xb800_3800],
+        [0x7c00_7bff, 0x0000_0001],
+        [0x3c02_3c00, 0x4000_ba00],
+    ];
+    let unpacked = [
+        [1.0f32, -2.0],
+        [2f32.powi(-24), f32::INFINITY],
+        [-0.75, 2.0],
+    ];
+    for (i, v) in inputs.iter().enumerate() {
+        assert_eq!(
+            outputs[i * 8..i * 8 + 8],
+            [
+                pack(v, -1.0, 127),
+                pack(v, 0.0, 255),
+                pack(&v[..2], -1.0, 32767),
+                pack(&v[2..], 0.0, 65535),
+                halves[i][0],
+                halves[i][1],
+                unpacked[i][0].to_bits(),
+                unpacked[i][1].to_bits(),
+            ],
+            "inputs {v:?} and {:#x}",
+            half_inputs[i]
+        );
+    }
+    // Rounding to even would pack `-0.5 * 127.0` as -64.
+    assert_eq!(outputs[0] >> 24, 0xc1);
+}
@@ -1,5 +1,8 @@
 mod example_wgsl;
+mod interp;
 mod snapshots;
+mod source_map;
 mod spirv_capabilities;
+mod spirv_control_flow;
 mod validation;
 mod wgsl_errors;
@@ -834,7 +834,8 @@ fn convert_snapshots_wgsl() {
         let params = input.read_parameters();
         let WgslInParameters { parse_doc_comments } = params.wgsl_in;
 
-        let options = naga::front::wgsl::Options { parse_doc_comments };
+        let mut options = naga::front::wgsl::Options::new();
+        options.parse_doc_comments = parse_doc_comments;
         let mut frontend = naga::front::wgsl::Frontend::new_with_options(options);
         match frontend.parse(&source) {
             Ok(mut module) => check_targets(&input, &mut module, Some(&source)),
@@ -926,6 +927,7 @@ fn convert_snapshots_glsl() {
                 &naga::front::glsl::Options {
                     stage,
                     defines: Default::default(),
+                    include_resolver: None,
                 },
                 &input.read_source(),
             )
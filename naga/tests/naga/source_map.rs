@@ -0,0 +1,156 @@
+/*!
+Tests for source maps and `#line` directives in the text backends.
+*/
+
+#![cfg(all(
+    feature = "wgsl-in",
+    feature = "hlsl-out",
+    feature = "msl-out",
+    feature = "glsl-out"
+))]
+
+use naga::back::{LineDirectives, SourceMap};
+
+const SOURCE: &str = "\
+fn double(x: f32) -> f32 {
+    let y = x * 2.0;
+    return y;
+}
+
+@fragment
+fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+    var z = double(x);
+    z += 1.0;
+    return vec4(z);
+}
+";
+
+fn parse() -> (naga::Module, naga::valid::ModuleInfo) {
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .unwrap();
+    (module, info)
+}
+
+fn line_directives() -> Option<LineDirectives> {
+    Some(LineDirectives {
+        source_code: SOURCE.into(),
+        file_name: Some("shader.wgsl".into()),
+    })
+}
+
+/// Checks that the output lines containing each of `snippets` map back to
+/// a span starting on the source line containing the same snippet.
+#[track_caller]
+fn check_source_map(output: &str, source_map: &SourceMap, snippets: &[(&str, &str)]) {
+    for &(output_snippet, source_snippet) in snippets {
+        let line = output
+            .lines()
+            .position(|line| line.contains(output_snippet))
+            .unwrap_or_else(|| panic!("{output_snippet:?} not found in:\n{output}"));
+        let span = source_map.span(line as u32 + 1).unwrap();
+        let location = span.location(SOURCE);
+        let source_line = SOURCE
+            .lines()
+            .nth(location.line_number as usize - 1)
+            .unwrap();
+        assert!(
+            source_line.contains(source_snippet),
+            "{output_snippet:?} mapped to {source_line:?}"
+        );
+    }
+}
+
+#[test]
+fn hlsl() {
+    let (module, info) = parse();
+    let pipeline_options = naga::back::hlsl::PipelineOptions::default();
+    let options = naga::back::hlsl::Options {
+        source_map: true,
+        ..Default::default()
+    };
+    let mut output = String::new();
+    let mut writer = naga::back::hlsl::Writer::new(&mut output, &options, &pipeline_options);
+    let reflection = writer.write(&module, &info, None).unwrap();
+    check_source_map(
+        &output,
+        &reflection.source_map.unwrap(),
+        &[
+            ("float double_(float x_1)", "fn double"),
+            ("return y;", "return y;"),
+            ("z = (_e4 + 1.0);", "z += 1.0;"),
+        ],
+    );
+
+    let options = naga::back::hlsl::Options {
+        line_directives: line_directives(),
+        ..Default::default()
+    };
+    let mut output = String::new();
+    let mut writer = naga::back::hlsl::Writer::new(&mut output, &options, &pipeline_options);
+    let reflection = writer.write(&module, &info, None).unwrap();
+    assert!(reflection.source_map.is_none());
+    assert!(output.contains("#line 1 \"shader.wgsl\"\nfloat double_(float x_1)"));
+    assert!(output.contains("#line 9 \"shader.wgsl\"\n"));
+}
+
+#[test]
+fn msl() {
+    let (module, info) = parse();
+    let options = naga::back::msl::Options {
+        source_map: true,
+        line_directives: line_directives(),
+        ..Default::default()
+    };
+    let mut output = String::new();
+    let mut writer = naga::back::msl::Writer::new(&mut output);
+    let translation = writer
+        .write(&module, &info, &options, &Default::default())
+        .unwrap();
+    check_source_map(
+        &output,
+        &translation.source_map.unwrap(),
+        &[("return y;", "return y;"), ("z = _e4 + 1.0;", "z += 1.0;")],
+    );
+    assert!(output.contains("#line 3 \"shader.wgsl\"\n    return y;"));
+}
+
+#[test]
+fn glsl() {
+    let (module, info) = parse();
+    let options = naga::back::glsl::Options {
+        source_map: true,
+        line_directives: line_directives(),
+        ..Default::default()
+    };
+    let pipeline_options = naga::back::glsl::PipelineOptions {
+        shader_stage: naga::ShaderStage::Fragment,
+        entry_point: "main".into(),
+        multiview: None,
+    };
+    let mut output = String::new();
+    let mut writer = naga::back::glsl::Writer::new(
+        &mut output,
+        &module,
+        &info,
+        &options,
+        &pipeline_options,
+        naga::proc::BoundsCheckPolicies::default(),
+    )
+    .unwrap();
+    let reflection = writer.write().unwrap();
+    check_source_map(
+        &output,
+        &reflection.source_map.unwrap(),
+        &[
+            ("return y;", "return y;"),
+            ("z = (_e4 + 1.0);", "z += 1.0;"),
+        ],
+    );
+    // GLSL can't name files.
+    assert!(output.contains("#line 3\n    return y;"));
+}
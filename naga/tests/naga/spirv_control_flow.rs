@@ -0,0 +1,517 @@
+/*!
+Test the SPIR-V frontend's handling of structured control flow, as produced by
+optimizers like `spirv-opt`.
+*/
+
+#![cfg(all(feature = "spv-in", feature = "wgsl-out"))]
+
+use rspirv::dr::{Builder, Operand};
+use spirv::{FunctionControl, LoopControl, SelectionControl, Word};
+
+struct Ids {
+    bool: Word,
+    int: Word,
+    zero: Word,
+    one: Word,
+    ten: Word,
+}
+
+fn labels<const N: usize>(b: &mut Builder) -> [Word; N] {
+    core::array::from_fn(|_| b.id())
+}
+
+/// Builds a function taking and returning an `i32`, using `build` to add its
+/// blocks, and returns the WGSL for it.
+fn function_to_wgsl(build: impl FnOnce(&mut Builder, &Ids, Word)) -> String {
+    let mut b = Builder::new();
+    b.set_version(1, 0);
+    b.capability(spirv::Capability::Shader);
+    b.extension("SPV_KHR_terminate_invocation");
+    b.memory_model(spirv::AddressingModel::Logical, spirv::MemoryModel::GLSL450);
+    let bool = b.type_bool();
+    let int = b.type_int(32, 1);
+    let function_type = b.type_function(int, [int]);
+    let ids = Ids {
+        bool,
+        int,
+        zero: b.constant_bit32(int, 0),
+        one: b.constant_bit32(int, 1),
+        ten: b.constant_bit32(int, 10),
+    };
+
+    b.begin_function(int, None, FunctionControl::NONE, function_type)
+        .unwrap();
+    let param = b.function_parameter(int).unwrap();
+    build(&mut b, &ids, param);
+    b.end_function().unwrap();
+
+    let words = rspirv::binary::Assemble::assemble(&b.module());
+    let module = naga::front::spv::Frontend::new(words.into_iter(), &Default::default())
+        .parse()
+        .unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .unwrap_or_else(|err| panic!("{err:?}\n{module:#?}"));
+    naga::back::wgsl::write_string(&module, &info, naga::back::wgsl::WriterFlags::empty()).unwrap()
+}
+
+#[track_caller]
+fn check(wgsl: String, expected: &str) {
+    if wgsl.trim() != expected.trim() {
+        panic!("unexpected WGSL:\n{wgsl}\nexpected:\n{expected}");
+    }
+}
+
+/// A loop whose header is its own continue target, with phis for the
+/// back-edge that refer to each other.
+#[test]
+fn single_block_loop_phis() {
+    let wgsl = function_to_wgsl(|b, ids, param| {
+        let [entry, header, merge] = labels(b);
+        let [x, y, i, sum, i_next] = labels(b);
+
+        b.begin_block(Some(entry)).unwrap();
+        b.branch(header).unwrap();
+
+        b.begin_block(Some(header)).unwrap();
+        b.phi(ids.int, Some(x), [(ids.zero, entry), (y, header)])
+            .unwrap();
+        b.phi(ids.int, Some(y), [(ids.one, entry), (sum, header)])
+            .unwrap();
+        b.phi(ids.int, Some(i), [(ids.zero, entry), (i_next, header)])
+            .unwrap();
+        b.i_add(ids.int, Some(sum), x, y).unwrap();
+        b.i_add(ids.int, Some(i_next), i, ids.one).unwrap();
+        let condition = b.s_less_than(ids.bool, None, i_next, param).unwrap();
+        b.loop_merge(merge, header, LoopControl::NONE, []).unwrap();
+        b.branch_conditional(condition, header, merge, []).unwrap();
+
+        b.begin_block(Some(merge)).unwrap();
+        b.ret_value(x).unwrap();
+    });
+    check(
+        wgsl,
+        "
+fn function(param: i32) -> i32 {
+    var phi_12_: i32;
+    var phi_13_: i32;
+    var phi_14_: i32;
+    var local: i32;
+
+    phi_12_ = 0i;
+    phi_13_ = 1i;
+    phi_14_ = 0i;
+    loop {
+        let _e5 = phi_12_;
+        let _e7 = phi_13_;
+        let _e9 = phi_14_;
+        let _e11 = (_e9 + 1i);
+        phi_12_ = _e7;
+        phi_13_ = (_e5 + _e7);
+        phi_14_ = _e11;
+        local = _e5;
+        if (_e11 < param) {
+            continue;
+        } else {
+            break;
+        }
+    }
+    let _e14 = local;
+    return _e14;
+}
+",
+    );
+}
+
+/// A selection at the end of a loop body, using the loop's continue target as
+/// its merge block.
+#[test]
+fn selection_merge_is_continue_target() {
+    let wgsl = function_to_wgsl(|b, ids, param| {
+        let [entry, header, body, accept, continuing, merge] = labels(b);
+        let [i, i_next, sum, sum_next] = labels(b);
+
+        b.begin_block(Some(entry)).unwrap();
+        b.branch(header).unwrap();
+
+        b.begin_block(Some(header)).unwrap();
+        b.phi(ids.int, Some(i), [(ids.zero, entry), (i_next, continuing)])
+            .unwrap();
+        b.phi(
+            ids.int,
+            Some(sum),
+            [(ids.zero, entry), (sum_next, continuing)],
+        )
+        .unwrap();
+        b.loop_merge(merge, continuing, LoopControl::NONE, [])
+            .unwrap();
+        b.branch(body).unwrap();
+
+        b.begin_block(Some(body)).unwrap();
+        let condition = b.s_less_than(ids.bool, None, i, ids.one).unwrap();
+        b.selection_merge(continuing, SelectionControl::NONE)
+            .unwrap();
+        b.branch_conditional(condition, accept, continuing, [])
+            .unwrap();
+
+        b.begin_block(Some(accept)).unwrap();
+        let value = b.i_add(ids.int, None, sum, ids.ten).unwrap();
+        b.branch(continuing).unwrap();
+
+        b.begin_block(Some(continuing)).unwrap();
+        b.phi(ids.int, Some(sum_next), [(sum, body), (value, accept)])
+            .unwrap();
+        b.i_add(ids.int, Some(i_next), i, ids.one).unwrap();
+        let condition = b.s_less_than(ids.bool, None, i_next, param).unwrap();
+        b.branch_conditional(condition, header, merge, []).unwrap();
+
+        b.begin_block(Some(merge)).unwrap();
+        b.ret_value(sum_next).unwrap();
+    });
+    check(
+        wgsl,
+        "
+fn function(param: i32) -> i32 {
+    var phi_15_: i32;
+    var phi_17_: i32;
+    var phi_18_: i32;
+    var local: i32;
+
+    phi_15_ = 0i;
+    phi_17_ = 0i;
+    loop {
+        let _e5 = phi_15_;
+        let _e7 = phi_17_;
+        phi_18_ = _e7;
+        if (_e5 < 1i) {
+            phi_18_ = (_e7 + 10i);
+            continue;
+        } else {
+            continue;
+        }
+        continuing {
+            let _e11 = phi_18_;
+            let _e12 = (_e5 + 1i);
+            phi_15_ = _e12;
+            phi_17_ = _e11;
+            local = _e11;
+            break if !((_e12 < param));
+        }
+    }
+    let _e16 = local;
+    return _e16;
+}
+",
+    );
+}
+
+/// A nested selection branching straight to the merge block of the selection
+/// enclosing it.
+#[test]
+fn exit_enclosing_selection() {
+    let wgsl = function_to_wgsl(|b, ids, param| {
+        let [entry, outer, inner, inner_merge, merge] = labels(b);
+
+        b.begin_block(Some(entry)).unwrap();
+        let condition = b.s_less_than(ids.bool, None, param, ids.ten).unwrap();
+        b.selection_merge(merge, SelectionControl::NONE).unwrap();
+        b.branch_conditional(condition, outer, merge, []).unwrap();
+
+        b.begin_block(Some(outer)).unwrap();
+        let condition = b.s_less_than(ids.bool, None, param, ids.one).unwrap();
+        b.selection_merge(inner_merge, SelectionControl::NONE)
+            .unwrap();
+        b.branch_conditional(condition, inner, inner_merge, [])
+            .unwrap();
+
+        b.begin_block(Some(inner)).unwrap();
+        let early = b.i_add(ids.int, None, param, ids.ten).unwrap();
+        b.branch(merge).unwrap();
+
+        b.begin_block(Some(inner_merge)).unwrap();
+        let late = b.i_add(ids.int, None, param, ids.one).unwrap();
+        b.branch(merge).unwrap();
+
+        b.begin_block(Some(merge)).unwrap();
+        let result = b
+            .phi(
+                ids.int,
+                None,
+                [(early, inner), (late, inner_merge), (ids.zero, entry)],
+            )
+            .unwrap();
+        b.ret_value(result).unwrap();
+    });
+    check(
+        wgsl,
+        "
+fn function(param: i32) -> i32 {
+    var phi_18_: i32;
+
+    phi_18_ = 0i;
+    switch 0i {
+        default: {
+            if (param < 10i) {
+                if (param < 1i) {
+                    phi_18_ = (param + 10i);
+                    break;
+                }
+                phi_18_ = (param + 1i);
+            }
+        }
+    }
+    let _e9 = phi_18_;
+    return _e9;
+}
+",
+    );
+}
+
+/// Breaking out of a loop from within a switch, which naga's `Break` can't do
+/// directly.
+#[test]
+fn break_loop_from_switch() {
+    let wgsl = function_to_wgsl(|b, ids, param| {
+        let [entry, header, body, case, switch_merge, continuing, merge] = labels(b);
+        let [i, i_next] = labels(b);
+
+        b.begin_block(Some(entry)).unwrap();
+        b.branch(header).unwrap();
+
+        b.begin_block(Some(header)).unwrap();
+        b.phi(ids.int, Some(i), [(ids.zero, entry), (i_next, continuing)])
+            .unwrap();
+        b.loop_merge(merge, continuing, LoopControl::NONE, [])
+            .unwrap();
+        b.branch(body).unwrap();
+
+        b.begin_block(Some(body)).unwrap();
+        b.selection_merge(switch_merge, SelectionControl::NONE)
+            .unwrap();
+        b.switch(i, switch_merge, [(Operand::LiteralBit32(5), case)])
+            .unwrap();
+
+        b.begin_block(Some(case)).unwrap();
+        b.branch(merge).unwrap();
+
+        b.begin_block(Some(switch_merge)).unwrap();
+        b.branch(continuing).unwrap();
+
+        b.begin_block(Some(continuing)).unwrap();
+        b.i_add(ids.int, Some(i_next), i, ids.one).unwrap();
+        let condition = b.s_less_than(ids.bool, None, i_next, param).unwrap();
+        b.branch_conditional(condition, header, merge, []).unwrap();
+
+        b.begin_block(Some(merge)).unwrap();
+        let result = b
+            .phi(ids.int, None, [(i, case), (i_next, continuing)])
+            .unwrap();
+        b.ret_value(result).unwrap();
+    });
+    check(
+        wgsl,
+        "
+fn function(param: i32) -> i32 {
+    var phi_16_: i32;
+    var phi_19_: i32;
+    var exit_15_: bool;
+
+    phi_16_ = 0i;
+    loop {
+        let _e5 = phi_16_;
+        exit_15_ = false;
+        switch _e5 {
+            case 5: {
+                phi_19_ = _e5;
+                exit_15_ = true;
+                break;
+            }
+            default: {
+            }
+        }
+        let _e17 = exit_15_;
+        if _e17 {
+            break;
+        }
+        continue;
+        continuing {
+            let _e6 = (_e5 + 1i);
+            phi_16_ = _e6;
+            phi_19_ = _e6;
+            break if !((_e6 < param));
+        }
+    }
+    let _e10 = phi_19_;
+    return _e10;
+}
+",
+    );
+}
+
+/// Exits to a loop's merge block and to a selection's merge block from within
+/// two nested switches.
+#[test]
+fn exits_across_nested_switches() {
+    let wgsl = function_to_wgsl(|b, ids, param| {
+        let [entry, header, body, selection, selection_merge] = labels(b);
+        let [outer_case, outer_merge, inner_case, inner_merge] = labels(b);
+        let [continuing, merge, i, i_next] = labels(b);
+
+        b.begin_block(Some(entry)).unwrap();
+        b.branch(header).unwrap();
+
+        b.begin_block(Some(header)).unwrap();
+        b.phi(ids.int, Some(i), [(ids.zero, entry), (i_next, continuing)])
+            .unwrap();
+        b.loop_merge(merge, continuing, LoopControl::NONE, [])
+            .unwrap();
+        b.branch(body).unwrap();
+
+        b.begin_block(Some(body)).unwrap();
+        let condition = b.s_less_than(ids.bool, None, i, param).unwrap();
+        b.selection_merge(selection_merge, SelectionControl::NONE)
+            .unwrap();
+        b.branch_conditional(condition, selection, selection_merge, [])
+            .unwrap();
+
+        b.begin_block(Some(selection)).unwrap();
+        b.selection_merge(outer_merge, SelectionControl::NONE)
+            .unwrap();
+        b.switch(i, outer_merge, [(Operand::LiteralBit32(5), outer_case)])
+            .unwrap();
+
+        b.begin_block(Some(outer_case)).unwrap();
+        b.selection_merge(inner_merge, SelectionControl::NONE)
+            .unwrap();
+        b.switch(
+            param,
+            inner_merge,
+            [
+                (Operand::LiteralBit32(1), inner_case),
+                (Operand::LiteralBit32(2), selection_merge),
+            ],
+        )
+        .unwrap();
+
+        b.begin_block(Some(inner_case)).unwrap();
+        b.branch(merge).unwrap();
+
+        b.begin_block(Some(inner_merge)).unwrap();
+        b.branch(outer_merge).unwrap();
+
+        b.begin_block(Some(outer_merge)).unwrap();
+        b.branch(selection_merge).unwrap();
+
+        b.begin_block(Some(selection_merge)).unwrap();
+        b.branch(continuing).unwrap();
+
+        b.begin_block(Some(continuing)).unwrap();
+        b.i_add(ids.int, Some(i_next), i, ids.one).unwrap();
+        let condition = b.s_less_than(ids.bool, None, i_next, ids.ten).unwrap();
+        b.branch_conditional(condition, header, merge, []).unwrap();
+
+        b.begin_block(Some(merge)).unwrap();
+        b.ret_value(i).unwrap();
+    });
+    check(
+        wgsl,
+        "
+fn function(param: i32) -> i32 {
+    var phi_20_: i32;
+    var local: i32;
+    var exit_19_: bool;
+    var exit_13_: bool;
+
+    phi_20_ = 0i;
+    loop {
+        let _e5 = phi_20_;
+        local = _e5;
+        exit_19_ = false;
+        switch 0i {
+            default: {
+                if (_e5 < param) {
+                    exit_13_ = false;
+                    switch _e5 {
+                        case 5: {
+                            switch param {
+                                case 1: {
+                                    exit_19_ = true;
+                                    break;
+                                }
+                                case 2: {
+                                    exit_13_ = true;
+                                    break;
+                                }
+                                default: {
+                                }
+                            }
+                            let _e20 = exit_19_;
+                            if _e20 {
+                                break;
+                            }
+                            let _e21 = exit_13_;
+                            if _e21 {
+                                break;
+                            }
+                            break;
+                        }
+                        default: {
+                        }
+                    }
+                    let _e25 = exit_19_;
+                    if _e25 {
+                        break;
+                    }
+                }
+            }
+        }
+        let _e29 = exit_19_;
+        if _e29 {
+            break;
+        }
+        continue;
+        continuing {
+            let _e7 = (_e5 + 1i);
+            phi_20_ = _e7;
+            break if !((_e7 < 10i));
+        }
+    }
+    let _e11 = local;
+    return _e11;
+}
+",
+    );
+}
+
+#[test]
+fn terminate_invocation() {
+    let wgsl = function_to_wgsl(|b, ids, param| {
+        let [entry, terminate, merge] = labels(b);
+
+        b.begin_block(Some(entry)).unwrap();
+        let condition = b.s_less_than(ids.bool, None, param, ids.zero).unwrap();
+        b.selection_merge(merge, SelectionControl::NONE).unwrap();
+        b.branch_conditional(condition, terminate, merge, [])
+            .unwrap();
+
+        b.begin_block(Some(terminate)).unwrap();
+        b.terminate_invocation().unwrap();
+
+        b.begin_block(Some(merge)).unwrap();
+        b.ret_value(param).unwrap();
+    });
+    check(
+        wgsl,
+        "
+fn function(param: i32) -> i32 {
+    if (param < 0i) {
+        discard;
+    }
+    return param;
+}
+",
+    );
+}
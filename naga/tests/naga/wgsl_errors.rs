@@ -1452,45 +1452,30 @@ fn invalid_functions() {
         })
     }
 
-    // Pointers of these address spaces cannot be passed as arguments.
+    // Pointers in these address spaces can be passed as arguments, with the
+    // `unrestricted_pointer_parameters` language extension.
     check_validation! {
-        "fn unacceptable_ptr_space(arg: ptr<storage, array<f32>>) { }":
-        Err(naga::valid::ValidationError::Function {
-            name: function_name,
-            source: naga::valid::FunctionError::InvalidArgumentPointerSpace {
-                index: 0,
-                name: argument_name,
-                space: naga::AddressSpace::Storage { .. },
-            },
-            ..
-        })
-        if function_name == "unacceptable_ptr_space" && argument_name == "arg"
-    }
-    check_validation! {
-        "fn unacceptable_ptr_space(arg: ptr<uniform, f32>) { }":
-        Err(naga::valid::ValidationError::Function {
-            name: function_name,
-            source: naga::valid::FunctionError::InvalidArgumentPointerSpace {
-                index: 0,
-                name: argument_name,
-                space: naga::AddressSpace::Uniform,
-            },
-            ..
-        })
-        if function_name == "unacceptable_ptr_space" && argument_name == "arg"
+        "fn acceptable_ptr_space(arg: ptr<storage, array<f32>>) { }",
+        "fn acceptable_ptr_space(arg: ptr<storage, f32, read_write>) { }",
+        "fn acceptable_ptr_space(arg: ptr<uniform, f32>) { }",
+        "fn acceptable_ptr_space(arg: ptr<workgroup, f32>) { }":
+        Ok(_)
     }
+
+    // Pointers in this address space cannot be passed as arguments.
     check_validation! {
-        "fn unacceptable_ptr_space(arg: ptr<workgroup, f32>) { }":
+        "fn unacceptable_ptr_space(arg: ptr<push_constant, f32>) { }":
         Err(naga::valid::ValidationError::Function {
             name: function_name,
             source: naga::valid::FunctionError::InvalidArgumentPointerSpace {
                 index: 0,
                 name: argument_name,
-                space: naga::AddressSpace::WorkGroup,
+                space: naga::AddressSpace::PushConstant,
             },
             ..
         })
-        if function_name == "unacceptable_ptr_space" && argument_name == "arg"
+        if function_name == "unacceptable_ptr_space" && argument_name == "arg",
+        naga::valid::Capabilities::PUSH_CONSTANT
     }
 
     check_validation! {
@@ -1,5 +1,79 @@
 #version 330 core
 
+uint naga_packSnorm4x8(vec4 value) {
+    uvec4 bytes = uvec4(ivec4(floor(0.5 + clamp(value, -1.0, 1.0) * 127.0)) & 0xFF);
+    return bytes.x | bytes.y << 8 | bytes.z << 16 | bytes.w << 24;
+}
+
+uint naga_packUnorm4x8(vec4 value) {
+    uvec4 bytes = uvec4(floor(0.5 + clamp(value, 0.0, 1.0) * 255.0));
+    return bytes.x | bytes.y << 8 | bytes.z << 16 | bytes.w << 24;
+}
+
+uint naga_packSnorm2x16(vec2 value) {
+    uvec2 halves = uvec2(ivec2(floor(0.5 + clamp(value, -1.0, 1.0) * 32767.0)) & 0xFFFF);
+    return halves.x | halves.y << 16;
+}
+
+uint naga_packUnorm2x16(vec2 value) {
+    uvec2 halves = uvec2(floor(0.5 + clamp(value, 0.0, 1.0) * 65535.0));
+    return halves.x | halves.y << 16;
+}
+
+uint naga_packHalf2x16(vec2 value) {
+    uint result = 0u;
+    for (int i = 0; i < 2; i++) {
+        uint bits = floatBitsToUint(value[i]);
+        uint sign_bit = (bits >> 16) & 0x8000u;
+        int exponent = int((bits >> 23) & 0xFFu) - 112;
+        uint mantissa = bits & 0x7FFFFFu;
+        uint half_bits;
+        if (exponent >= 31) {
+            half_bits = (exponent == 143 && mantissa != 0u) ? 0x7E00u : 0x7C00u;
+        } else if (exponent <= 0) {
+            half_bits = 0u;
+            if (exponent >= -10) {
+                mantissa |= 0x800000u;
+                uint shift = uint(14 - exponent);
+                uint halfway = 1u << (shift - 1u);
+                uint remainder = mantissa & ((1u << shift) - 1u);
+                half_bits = mantissa >> shift;
+                if (remainder > halfway || (remainder == halfway && (half_bits & 1u) != 0u)) {
+                    half_bits += 1u;
+                }
+            }
+        } else {
+            uint remainder = mantissa & 0x1FFFu;
+            half_bits = uint(exponent) << 10 | mantissa >> 13;
+            if (remainder > 0x1000u || (remainder == 0x1000u && (half_bits & 1u) != 0u)) {
+                half_bits += 1u;
+            }
+        }
+        result |= (sign_bit | half_bits) << (16 * i);
+    }
+    return result;
+}
+
+vec2 naga_unpackHalf2x16(uint value) {
+    vec2 result;
+    for (int i = 0; i < 2; i++) {
+        uint half_bits = (value >> (16 * i)) & 0xFFFFu;
+        uint sign_bit = (half_bits & 0x8000u) << 16;
+        uint exponent = (half_bits >> 10) & 0x1Fu;
+        uint mantissa = half_bits & 0x3FFu;
+        uint bits;
+        if (exponent == 0u) {
+            bits = sign_bit | floatBitsToUint(float(mantissa) * exp2(-24.0));
+        } else if (exponent == 31u) {
+            bits = sign_bit | 0x7F800000u | mantissa << 13;
+        } else {
+            bits = sign_bit | (exponent + 112u) << 23 | mantissa << 13;
+        }
+        result[i] = uintBitsToFloat(bits);
+    }
+    return result;
+}
+
 void main() {
     int i = 0;
     ivec2 i2_ = ivec2(0);
@@ -11,18 +85,30 @@ void main() {
     uvec4 u4_ = uvec4(0u);
     vec2 f2_ = vec2(0.0);
     vec4 f4_ = vec4(0.0);
-    ivec4 _e23 = i4_;
-    u = uint((_e23[0] & 0xFF) | ((_e23[1] & 0xFF) << 8) | ((_e23[2] & 0xFF) << 16) | ((_e23[3] & 0xFF) << 24));
-    uvec4 _e25 = u4_;
-    u = (_e25[0] & 0xFFu) | ((_e25[1] & 0xFFu) << 8) | ((_e25[2] & 0xFFu) << 16) | ((_e25[3] & 0xFFu) << 24);
-    uint _e27 = u;
-    f4_ = (vec4(ivec4(_e27 << 24, _e27 << 16, _e27 << 8, _e27) >> 24) / 127.0);
-    uint _e29 = u;
-    f4_ = (vec4(_e29 & 0xFFu, _e29 >> 8 & 0xFFu, _e29 >> 16 & 0xFFu, _e29 >> 24) / 255.0);
-    uint _e31 = u;
-    f2_ = (vec2(ivec2(_e31 << 16, _e31) >> 16) / 32767.0);
-    uint _e33 = u;
-    f2_ = (vec2(_e33 & 0xFFFFu, _e33 >> 16) / 65535.0);
+    vec4 _e23 = f4_;
+    u = naga_packSnorm4x8(_e23);
+    vec4 _e25 = f4_;
+    u = naga_packUnorm4x8(_e25);
+    vec2 _e27 = f2_;
+    u = naga_packSnorm2x16(_e27);
+    vec2 _e29 = f2_;
+    u = naga_packUnorm2x16(_e29);
+    vec2 _e31 = f2_;
+    u = naga_packHalf2x16(_e31);
+    ivec4 _e33 = i4_;
+    u = uint((_e33[0] & 0xFF) | ((_e33[1] & 0xFF) << 8) | ((_e33[2] & 0xFF) << 16) | ((_e33[3] & 0xFF) << 24));
+    uvec4 _e35 = u4_;
+    u = (_e35[0] & 0xFFu) | ((_e35[1] & 0xFFu) << 8) | ((_e35[2] & 0xFFu) << 16) | ((_e35[3] & 0xFFu) << 24);
+    uint _e37 = u;
+    f4_ = (vec4(ivec4(_e37 << 24, _e37 << 16, _e37 << 8, _e37) >> 24) / 127.0);
+    uint _e39 = u;
+    f4_ = (vec4(_e39 & 0xFFu, _e39 >> 8 & 0xFFu, _e39 >> 16 & 0xFFu, _e39 >> 24) / 255.0);
+    uint _e41 = u;
+    f2_ = (vec2(ivec2(_e41 << 16, _e41) >> 16) / 32767.0);
+    uint _e43 = u;
+    f2_ = (vec2(_e43 & 0xFFFFu, _e43 >> 16) / 65535.0);
+    uint _e45 = u;
+    f2_ = naga_unpackHalf2x16(_e45);
     return;
 }
 
@@ -4,6 +4,16 @@ precision highp float;
 precision highp int;
 
 
+uint naga_packSnorm4x8(vec4 value) {
+    uvec4 bytes = uvec4(ivec4(floor(0.5 + clamp(value, -1.0, 1.0) * 127.0)) & 0xFF);
+    return bytes.x | bytes.y << 8 | bytes.z << 16 | bytes.w << 24;
+}
+
+uint naga_packUnorm4x8(vec4 value) {
+    uvec4 bytes = uvec4(floor(0.5 + clamp(value, 0.0, 1.0) * 255.0));
+    return bytes.x | bytes.y << 8 | bytes.z << 16 | bytes.w << 24;
+}
+
 void main() {
     int i = 0;
     ivec2 i2_ = ivec2(0);
@@ -15,18 +25,30 @@ void main() {
     uvec4 u4_ = uvec4(0u);
     vec2 f2_ = vec2(0.0);
     vec4 f4_ = vec4(0.0);
-    ivec4 _e23 = i4_;
-    u = uint((_e23[0] & 0xFF) | ((_e23[1] & 0xFF) << 8) | ((_e23[2] & 0xFF) << 16) | ((_e23[3] & 0xFF) << 24));
-    uvec4 _e25 = u4_;
-    u = (_e25[0] & 0xFFu) | ((_e25[1] & 0xFFu) << 8) | ((_e25[2] & 0xFFu) << 16) | ((_e25[3] & 0xFFu) << 24);
-    uint _e27 = u;
-    f4_ = (vec4(ivec4(_e27 << 24, _e27 << 16, _e27 << 8, _e27) >> 24) / 127.0);
-    uint _e29 = u;
-    f4_ = (vec4(_e29 & 0xFFu, _e29 >> 8 & 0xFFu, _e29 >> 16 & 0xFFu, _e29 >> 24) / 255.0);
-    uint _e31 = u;
-    f2_ = unpackSnorm2x16(_e31);
-    uint _e33 = u;
-    f2_ = unpackUnorm2x16(_e33);
+    vec4 _e23 = f4_;
+    u = naga_packSnorm4x8(_e23);
+    vec4 _e25 = f4_;
+    u = naga_packUnorm4x8(_e25);
+    vec2 _e27 = f2_;
+    u = packSnorm2x16(_e27);
+    vec2 _e29 = f2_;
+    u = packUnorm2x16(_e29);
+    vec2 _e31 = f2_;
+    u = packHalf2x16(_e31);
+    ivec4 _e33 = i4_;
+    u = uint((_e33[0] & 0xFF) | ((_e33[1] & 0xFF) << 8) | ((_e33[2] & 0xFF) << 16) | ((_e33[3] & 0xFF) << 24));
+    uvec4 _e35 = u4_;
+    u = (_e35[0] & 0xFFu) | ((_e35[1] & 0xFFu) << 8) | ((_e35[2] & 0xFFu) << 16) | ((_e35[3] & 0xFFu) << 24);
+    uint _e37 = u;
+    f4_ = (vec4(ivec4(_e37 << 24, _e37 << 16, _e37 << 8, _e37) >> 24) / 127.0);
+    uint _e39 = u;
+    f4_ = (vec4(_e39 & 0xFFu, _e39 >> 8 & 0xFFu, _e39 >> 16 & 0xFFu, _e39 >> 24) / 255.0);
+    uint _e41 = u;
+    f2_ = unpackSnorm2x16(_e41);
+    uint _e43 = u;
+    f2_ = unpackUnorm2x16(_e43);
+    uint _e45 = u;
+    f2_ = unpackHalf2x16(_e45);
     return;
 }
 
@@ -0,0 +1,159 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+struct NagaExternalTextureTransferFn {
+    float a;
+    float b;
+    float g;
+    float k;
+};
+struct NagaExternalTextureParams {
+    mat4x4 yuv_conversion_matrix;
+    mat3x3 gamut_conversion_matrix;
+    NagaExternalTextureTransferFn src_tf;
+    NagaExternalTextureTransferFn dst_tf;
+    vec2 sample_transform_0;
+    vec2 sample_transform_1;
+    vec2 sample_transform_2;
+    vec2 load_transform_0;
+    vec2 load_transform_1;
+    vec2 load_transform_2;
+    uvec2 size;
+    uint num_planes;
+};
+layout(binding = 0) uniform highp sampler2D _group_0_binding_0_plane0_cs;
+layout(binding = 1) uniform highp sampler2D _group_0_binding_0_plane1_cs;
+layout(binding = 2) uniform highp sampler2D _group_0_binding_0_plane2_cs;
+layout(std140, binding = 0) uniform NagaExternalTextureParams_block_0Compute { NagaExternalTextureParams _group_0_binding_0_params_cs; };
+
+
+vec4 naga_textureSampleBaseClampToEdge(
+    highp sampler2D plane0,
+    highp sampler2D plane1,
+    highp sampler2D plane2,
+    NagaExternalTextureParams params,
+    vec2 coords)
+{
+    vec2 plane0_size = vec2(textureSize(plane0, 0));
+    mat3x2 sample_transform = mat3x2(
+        params.sample_transform_0,
+        params.sample_transform_1,
+        params.sample_transform_2
+    );
+    coords = sample_transform * vec3(coords, 1.0);
+    vec2 bounds_min = sample_transform * vec3(0.0, 0.0, 1.0);
+    vec2 bounds_max = sample_transform * vec3(1.0, 1.0, 1.0);
+    vec4 bounds = vec4(min(bounds_min, bounds_max), max(bounds_min, bounds_max));
+    vec2 plane0_half_texel = vec2(0.5, 0.5) / plane0_size;
+    vec2 plane0_coords = clamp(coords, bounds.xy + plane0_half_texel, bounds.zw - plane0_half_texel);
+    if (params.num_planes == 1u) {
+        return textureLod(plane0, plane0_coords, 0.0);
+    } else {
+        vec2 plane1_size = vec2(textureSize(plane1, 0));
+        vec2 plane1_half_texel = vec2(0.5, 0.5) / plane1_size;
+        vec2 plane1_coords = clamp(coords, bounds.xy + plane1_half_texel, bounds.zw - plane1_half_texel);
+        float y = textureLod(plane0, plane0_coords, 0.0).x;
+        vec2 uv;
+        if (params.num_planes == 2u) {
+            uv = textureLod(plane1, plane1_coords, 0.0).xy;
+        } else {
+            vec2 plane2_size = vec2(textureSize(plane2, 0));
+            vec2 plane2_half_texel = vec2(0.5, 0.5) / plane2_size;
+            vec2 plane2_coords = clamp(coords, bounds.xy + plane2_half_texel, bounds.zw - plane2_half_texel);
+            uv = vec2(textureLod(plane1, plane1_coords, 0.0).x, textureLod(plane2, plane2_coords, 0.0).x);
+        }
+        vec3 srcGammaRgb = (params.yuv_conversion_matrix * vec4(y, uv, 1.0)).rgb;
+        vec3 srcLinearRgb = mix(
+            pow((srcGammaRgb + params.src_tf.a - 1.0) / params.src_tf.a, vec3(params.src_tf.g)),
+            srcGammaRgb / params.src_tf.k,
+            lessThan(srcGammaRgb, vec3(params.src_tf.k * params.src_tf.b)));
+        vec3 dstLinearRgb = params.gamut_conversion_matrix * srcLinearRgb;
+        vec3 dstGammaRgb = mix(
+            params.dst_tf.a * pow(dstLinearRgb, vec3(1.0 / params.dst_tf.g)) - (params.dst_tf.a - 1.0),
+            params.dst_tf.k * dstLinearRgb,
+            lessThan(dstLinearRgb, vec3(params.dst_tf.b)));
+        return vec4(dstGammaRgb, 1.0);
+    }
+}
+
+vec4 naga_textureLoadExternal(
+    highp sampler2D plane0,
+    highp sampler2D plane1,
+    highp sampler2D plane2,
+    NagaExternalTextureParams params,
+    uvec2 coords)
+{
+    uvec2 plane0_size = uvec2(textureSize(plane0, 0));
+    uvec2 cropped_size = any(notEqual(params.size, uvec2(0u))) ? params.size : plane0_size;
+    coords = min(coords, cropped_size - 1u);
+    mat3x2 load_transform = mat3x2(
+        params.load_transform_0,
+        params.load_transform_1,
+        params.load_transform_2
+    );
+    uvec2 plane0_coords = uvec2(round(load_transform * vec3(coords, 1.0)));
+    if (params.num_planes == 1u) {
+        return texelFetch(plane0, ivec2(plane0_coords), 0);
+    } else {
+        uvec2 plane1_size = uvec2(textureSize(plane1, 0));
+        uvec2 plane1_coords = uvec2(floor(vec2(plane0_coords) * vec2(plane1_size) / vec2(plane0_size)));
+        float y = texelFetch(plane0, ivec2(plane0_coords), 0).x;
+        vec2 uv;
+        if (params.num_planes == 2u) {
+            uv = texelFetch(plane1, ivec2(plane1_coords), 0).xy;
+        } else {
+            uvec2 plane2_size = uvec2(textureSize(plane2, 0));
+            uvec2 plane2_coords = uvec2(floor(vec2(plane0_coords) * vec2(plane2_size) / vec2(plane0_size)));
+            uv = vec2(texelFetch(plane1, ivec2(plane1_coords), 0).x, texelFetch(plane2, ivec2(plane2_coords), 0).x);
+        }
+        vec3 srcGammaRgb = (params.yuv_conversion_matrix * vec4(y, uv, 1.0)).rgb;
+        vec3 srcLinearRgb = mix(
+            pow((srcGammaRgb + params.src_tf.a - 1.0) / params.src_tf.a, vec3(params.src_tf.g)),
+            srcGammaRgb / params.src_tf.k,
+            lessThan(srcGammaRgb, vec3(params.src_tf.k * params.src_tf.b)));
+        vec3 dstLinearRgb = params.gamut_conversion_matrix * srcLinearRgb;
+        vec3 dstGammaRgb = mix(
+            params.dst_tf.a * pow(dstLinearRgb, vec3(1.0 / params.dst_tf.g)) - (params.dst_tf.a - 1.0),
+            params.dst_tf.k * dstLinearRgb,
+            lessThan(dstLinearRgb, vec3(params.dst_tf.b)));
+        return vec4(dstGammaRgb, 1.0);
+    }
+}
+
+uvec2 naga_textureDimensionsExternal(
+    highp sampler2D plane0,
+    highp sampler2D plane1,
+    highp sampler2D plane2,
+    NagaExternalTextureParams params)
+{
+    if (any(notEqual(params.size, uvec2(0u)))) {
+        return params.size;
+    } else {
+        return uvec2(textureSize(plane0, 0));
+    }
+}
+
+vec4 test(highp sampler2D t_plane0_, highp sampler2D t_plane1_, highp sampler2D t_plane2_, NagaExternalTextureParams t_params) {
+    vec4 a = vec4(0.0);
+    vec4 b = vec4(0.0);
+    uvec2 c = uvec2(0u);
+    vec4 _e4 = naga_textureSampleBaseClampToEdge(t_plane0_, t_plane1_, t_plane2_, t_params, vec2(0.0));
+    a = _e4;
+    vec4 _e8 = naga_textureLoadExternal(t_plane0_, t_plane1_, t_plane2_, t_params, uvec2(uvec2(0u)));
+    b = _e8;
+    c = uvec2(naga_textureDimensionsExternal(t_plane0_, t_plane1_, t_plane2_, t_params).xy);
+    vec4 _e12 = a;
+    vec4 _e13 = b;
+    uvec2 _e15 = c;
+    return ((_e12 + _e13) + vec2(_e15).xyxy);
+}
+
+void main() {
+    vec4 _e1 = test(_group_0_binding_0_plane0_cs, _group_0_binding_0_plane1_cs, _group_0_binding_0_plane2_cs, _group_0_binding_0_params_cs);
+    return;
+}
+
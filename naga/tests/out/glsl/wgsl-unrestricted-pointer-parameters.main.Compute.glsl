@@ -0,0 +1,77 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 64, local_size_y = 1, local_size_z = 1) in;
+
+struct Params {
+    float scale;
+    vec4 offsets[4];
+};
+layout(std430) buffer Particles_block_0Compute {
+    uint count;
+    vec4 positions[];
+} _group_0_binding_0_cs;
+
+layout(std140) uniform Params_block_1Compute { Params _group_0_binding_1_cs; };
+
+layout(std430) buffer type_7_block_2Compute { uint _group_0_binding_2_cs[2][8]; };
+
+shared vec4 shared_positions[64];
+
+
+uint len() {
+    return uint(_group_0_binding_0_cs.positions.length());
+}
+
+vec4 scaled(uint offset_index) {
+    vec4 _e6 = _group_0_binding_1_cs.offsets[offset_index];
+    float _e7 = _group_0_binding_1_cs.scale;
+    return (_e6 * _e7);
+}
+
+void advance(uint position_index, vec4 offset) {
+    vec4 _e5 = _group_0_binding_0_cs.positions[position_index];
+    _group_0_binding_0_cs.positions[position_index] = (_e5 + offset);
+    return;
+}
+
+void count(float value) {
+    uint _e9 = atomicAdd(_group_0_binding_2_cs[0][uint(clamp(value, 0.0, 7.0))], 1u);
+    return;
+}
+
+void count_1(float value_1) {
+    uint _e9 = atomicAdd(_group_0_binding_2_cs[1][uint(clamp(value_1, 0.0, 7.0))], 1u);
+    return;
+}
+
+void stash(uint slot_index, uint position_index_1) {
+    vec4 _e7 = _group_0_binding_0_cs.positions[position_index_1];
+    shared_positions[slot_index] = _e7;
+    return;
+}
+
+void main() {
+    if (gl_LocalInvocationID == uvec3(0u)) {
+        shared_positions = vec4[64](vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0));
+    }
+    memoryBarrierShared();
+    barrier();
+    uvec3 id = gl_GlobalInvocationID;
+    uint index = gl_LocalInvocationIndex;
+    uint _e3 = len();
+    if ((id.x >= _e3)) {
+        return;
+    }
+    vec4 _e8 = scaled((id.x % 4u));
+    advance(id.x, _e8);
+    float _e15 = _group_0_binding_0_cs.positions[id.x].x;
+    count(_e15);
+    float _e21 = _group_0_binding_0_cs.positions[id.x].y;
+    count_1(_e21);
+    stash(index, id.x);
+    return;
+}
+
@@ -0,0 +1,97 @@
+struct UniformIndex {
+    uint index;
+};
+
+struct FragmentIn {
+    nointerpolation uint index : LOC0;
+};
+
+StructuredBuffer<uint2> nagaDescriptorHeapBases : register(t0, space254);
+Texture2D<float4> nagaDescriptorHeapLoad(uint index) {
+    Texture2D<float4> descriptor = ResourceDescriptorHeap[index];
+    return descriptor;
+}
+static const uint texture_array_unbounded = nagaDescriptorHeapBases[0].x + 0;
+static const uint texture_array_bounded = nagaDescriptorHeapBases[0].x + 10;
+RWTexture2D<float4> nagaDescriptorHeapLoad_1(uint index) {
+    RWTexture2D<float4> descriptor = ResourceDescriptorHeap[index];
+    return descriptor;
+}
+static const uint texture_array_storage = nagaDescriptorHeapBases[0].x + 15;
+SamplerState nagaDescriptorHeapLoad_2(uint index) {
+    SamplerState descriptor = SamplerDescriptorHeap[index];
+    return descriptor;
+}
+static const uint samp = nagaDescriptorHeapBases[0].y + 0;
+SamplerComparisonState nagaDescriptorHeapLoad_3(uint index) {
+    SamplerComparisonState descriptor = SamplerDescriptorHeap[index];
+    return descriptor;
+}
+static const uint samp_comp = nagaDescriptorHeapBases[0].y + 5;
+static const Texture2D<float> texture_depth = ResourceDescriptorHeap[nagaDescriptorHeapBases[0].x + 20];
+cbuffer uni : register(b6) { UniformIndex uni; }
+static const Texture2D<float4> texture_single = ResourceDescriptorHeap[nagaDescriptorHeapBases[1].x + 0];
+static const SamplerState samp_single = SamplerDescriptorHeap[nagaDescriptorHeapBases[1].y + 0];
+Texture2D<float4> texture_not_lowered : register(t2, space1);
+static const uint texture_array_rest = nagaDescriptorHeapBases[1].x + 1;
+
+struct FragmentInput_main {
+    nointerpolation uint index : LOC0;
+};
+
+uint2 NagaDimensions2D(Texture2D<float4> tex)
+{
+    uint4 ret;
+    tex.GetDimensions(0, ret.x, ret.y, ret.z);
+    return ret.xy;
+}
+
+float4 main(FragmentInput_main fragmentinput_main) : SV_Target0
+{
+    FragmentIn fragment_in = { fragmentinput_main.index };
+    uint2 u2_ = (0u).xx;
+    float4 v4_ = (0.0).xxxx;
+
+    uint uniform_index = uni.index;
+    uint non_uniform_index = fragment_in.index;
+    float2 uv = (0.0).xx;
+    int2 pix = (int(0)).xx;
+    uint2 _e18 = u2_;
+    u2_ = (_e18 + NagaDimensions2D(nagaDescriptorHeapLoad(texture_array_unbounded + 0)));
+    uint2 _e23 = u2_;
+    u2_ = (_e23 + NagaDimensions2D(nagaDescriptorHeapLoad(texture_array_unbounded + uniform_index)));
+    uint2 _e28 = u2_;
+    u2_ = (_e28 + NagaDimensions2D(nagaDescriptorHeapLoad(NonUniformResourceIndex(texture_array_unbounded + non_uniform_index))));
+    float4 _e34 = nagaDescriptorHeapLoad(texture_array_bounded + 0).Sample(nagaDescriptorHeapLoad_2(samp + 0), uv);
+    float4 _e35 = v4_;
+    v4_ = (_e35 + _e34);
+    float4 _e41 = nagaDescriptorHeapLoad(texture_array_bounded + uniform_index).Sample(nagaDescriptorHeapLoad_2(samp + uniform_index), uv);
+    float4 _e42 = v4_;
+    v4_ = (_e42 + _e41);
+    float4 _e48 = nagaDescriptorHeapLoad(NonUniformResourceIndex(texture_array_bounded + non_uniform_index)).Sample(nagaDescriptorHeapLoad_2(NonUniformResourceIndex(samp + non_uniform_index)), uv);
+    float4 _e49 = v4_;
+    v4_ = (_e49 + _e48);
+    float4 _e55 = texture_depth.GatherCmp(nagaDescriptorHeapLoad_3(samp_comp + 0), uv, 0.0);
+    float4 _e56 = v4_;
+    v4_ = (_e56 + _e55);
+    float4 _e62 = texture_depth.GatherCmp(nagaDescriptorHeapLoad_3(NonUniformResourceIndex(samp_comp + non_uniform_index)), uv, 0.0);
+    float4 _e63 = v4_;
+    v4_ = (_e63 + _e62);
+    float4 _e67 = v4_;
+    nagaDescriptorHeapLoad_1(texture_array_storage + 0)[pix] = _e67;
+    float4 _e70 = v4_;
+    nagaDescriptorHeapLoad_1(NonUniformResourceIndex(texture_array_storage + non_uniform_index))[pix] = _e70;
+    float4 _e73 = texture_single.Sample(samp_single, uv);
+    float4 _e74 = v4_;
+    v4_ = (_e74 + _e73);
+    float4 _e78 = texture_not_lowered.Load(int3(pix, int(0)));
+    float4 _e79 = v4_;
+    v4_ = (_e79 + _e78);
+    float4 _e84 = nagaDescriptorHeapLoad(NonUniformResourceIndex(texture_array_rest + non_uniform_index)).Load(int3(pix, int(0)));
+    float4 _e85 = v4_;
+    v4_ = (_e85 + _e84);
+    uint2 _e87 = u2_;
+    float2 v2_ = float2(_e87);
+    float4 _e89 = v4_;
+    return (_e89 + float4(v2_.x, v2_.y, v2_.x, v2_.y));
+}
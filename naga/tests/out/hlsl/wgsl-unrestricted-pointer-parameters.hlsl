@@ -0,0 +1,86 @@
+struct Params {
+    float scale;
+    int _pad1_0;
+    int _pad1_1;
+    int _pad1_2;
+    float4 offsets[4];
+};
+
+RWByteAddressBuffer particles : register(u0);
+cbuffer params : register(b1) { Params params; }
+RWByteAddressBuffer histogram : register(u2);
+groupshared float4 shared_positions[64];
+
+uint NagaBufferLengthRW(RWByteAddressBuffer buffer)
+{
+    uint ret;
+    buffer.GetDimensions(ret);
+    return ret;
+}
+
+uint len()
+{
+    return ((NagaBufferLengthRW(particles) - 16) / 16);
+}
+
+float4 scaled(uint offset_index)
+{
+    float4 _e6 = params.offsets[offset_index];
+    float _e7 = params.scale;
+    return (_e6 * _e7);
+}
+
+void advance(uint position_index, float4 offset)
+{
+    float4 _e5 = asfloat(particles.Load4(position_index*16+16));
+    particles.Store4(position_index*16+16, asuint((_e5 + offset)));
+    return;
+}
+
+uint naga_f2u32(float value) {
+    return uint(clamp(value, 0.0, 4294967000.0));
+}
+
+void count(float value)
+{
+    uint _e9; histogram.InterlockedAdd(naga_f2u32(clamp(value, 0.0, 7.0))*4+0, 1u, _e9);
+    return;
+}
+
+void count_1(float value_1)
+{
+    uint _e9; histogram.InterlockedAdd(naga_f2u32(clamp(value_1, 0.0, 7.0))*4+32, 1u, _e9);
+    return;
+}
+
+void stash(uint slot_index, uint position_index_1)
+{
+    float4 _e7 = asfloat(particles.Load4(position_index_1*16+16));
+    shared_positions[min(uint(slot_index), 63u)] = _e7;
+    return;
+}
+
+uint naga_mod(uint lhs, uint rhs) {
+    return lhs % (rhs == 0u ? 1u : rhs);
+}
+
+[numthreads(64, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID, uint index : SV_GroupIndex, uint3 __local_invocation_id : SV_GroupThreadID)
+{
+    if (all(__local_invocation_id == uint3(0u, 0u, 0u))) {
+        shared_positions = (float4[64])0;
+    }
+    GroupMemoryBarrierWithGroupSync();
+    const uint _e3 = len();
+    if ((id.x >= _e3)) {
+        return;
+    }
+    const float4 _e8 = scaled(naga_mod(id.x, 4u));
+    advance(id.x, _e8);
+    float _e15 = asfloat(particles.Load(0+id.x*16+16));
+    count(_e15);
+    float _e21 = asfloat(particles.Load(4+id.x*16+16));
+    count_1(_e21);
+    stash(index, id.x);
+    return;
+}
@@ -0,0 +1,60 @@
+struct Particles {
+    count: u32,
+    positions: array<vec4<f32>>,
+}
+
+struct Params {
+    scale: f32,
+    offsets: array<vec4<f32>, 4>,
+}
+
+@group(0) @binding(0) 
+var<storage, read_write> particles: Particles;
+@group(0) @binding(1) 
+var<uniform> params: Params;
+@group(0) @binding(2) 
+var<storage, read_write> histogram: array<array<atomic<u32>, 8>, 2>;
+var<workgroup> shared_positions: array<vec4<f32>, 64>;
+
+fn len(positions: ptr<storage, array<vec4<f32>>, read_write>) -> u32 {
+    return arrayLength(positions);
+}
+
+fn scaled(offset: ptr<uniform, vec4<f32>>, scale: ptr<uniform, f32>) -> vec4<f32> {
+    let _e2 = (*offset);
+    let _e3 = (*scale);
+    return (_e2 * _e3);
+}
+
+fn advance(position: ptr<storage, vec4<f32>, read_write>, offset_1: vec4<f32>) {
+    let _e2 = (*position);
+    (*position) = (_e2 + offset_1);
+    return;
+}
+
+fn count(bins: ptr<storage, array<atomic<u32>, 8>, read_write>, value: f32) {
+    let _e8 = atomicAdd((&(*bins)[u32(clamp(value, 0f, 7f))]), 1u);
+    return;
+}
+
+fn stash(slot: ptr<workgroup, vec4<f32>>, position_1: ptr<storage, vec4<f32>, read_write>) {
+    let _e2 = (*position_1);
+    (*slot) = _e2;
+    return;
+}
+
+@compute @workgroup_size(64, 1, 1) 
+fn main(@builtin(global_invocation_id) id: vec3<u32>, @builtin(local_invocation_index) index: u32) {
+    let _e5 = len((&particles.positions));
+    if (id.x >= _e5) {
+        return;
+    }
+    let _e15 = scaled((&params.offsets[(id.x % 4u)]), (&params.scale));
+    advance((&particles.positions[id.x]), _e15);
+    let _e27 = particles.positions[id.x].x;
+    count((&histogram[0]), _e27);
+    let _e35 = particles.positions[id.x].y;
+    count((&histogram[1]), _e35);
+    stash((&shared_positions[index]), (&particles.positions[id.x]));
+    return;
+}
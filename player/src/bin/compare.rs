@@ -0,0 +1,196 @@
+//! This is a tool to summarize and compare WebGPU traces.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+
+/// Print statistics about a wgpu trace, or compare two traces of the same
+/// scene.
+///
+/// The statistics are the number of objects created of each kind, and for
+/// each frame, the number of draws, dispatches, pipelines created and bytes
+/// written to buffers. When comparing, only the frames that differ are listed,
+/// followed by the actions that were removed from or added to each frame.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(argh::FromArgs)]
+struct Args {
+    /// don't list the actions that differ between the traces
+    #[argh(switch)]
+    no_diff: bool,
+
+    /// the trace directory or file
+    #[argh(positional)]
+    trace: PathBuf,
+
+    /// the trace directory or file to compare with
+    #[argh(positional)]
+    other: Option<PathBuf>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(path: &Path) -> player::stats::Stats {
+    let reader = match player::TraceReader::open(path) {
+        Ok(reader) => reader,
+        Err(e) => {
+            eprintln!("Unable to open trace '{}': {e}", path.display());
+            std::process::exit(1);
+        }
+    };
+    let mut stats = player::stats::Stats::new();
+    for action in reader {
+        match action {
+            Ok(action) => stats.add(&action),
+            Err(e) => {
+                eprintln!("Unable to read trace '{}': {e}", path.display());
+                std::process::exit(1);
+            }
+        }
+    }
+    stats
+}
+
+/// Prints a row of values, one per trace, followed by their difference if
+/// they differ.
+#[cfg(not(target_arch = "wasm32"))]
+fn print_row(name: &str, values: &[u64]) {
+    print!("  {name:<24}");
+    for value in values {
+        print!("{value:>12}");
+    }
+    if let [a, b] = *values {
+        if a != b {
+            print!("  ({:+})", i128::from(b) - i128::from(a));
+        }
+    }
+    println!();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn signatures(trace: &player::stats::Stats, frame: usize) -> &[String] {
+    trace
+        .frames
+        .get(frame)
+        .map_or(&[], |frame| &frame.signatures)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use player::stats::{self, Counts};
+
+    env_logger::init();
+
+    let args: Args = argh::from_env();
+
+    let paths = [Some(&args.trace), args.other.as_ref()];
+    let paths = paths.into_iter().flatten().collect::<Vec<_>>();
+    let traces = paths.iter().map(|path| read(path)).collect::<Vec<_>>();
+    for (name, path) in ["A", "B"].iter().zip(&paths) {
+        println!("Trace {name}: {}", path.display());
+    }
+
+    println!();
+    println!("Objects created");
+    let kinds = traces
+        .iter()
+        .flat_map(|trace| trace.created.keys().copied())
+        .collect::<std::collections::BTreeSet<_>>();
+    for kind in kinds {
+        let values = traces
+            .iter()
+            .map(|trace| trace.created.get(kind).copied().unwrap_or(0) as u64)
+            .collect::<Vec<_>>();
+        print_row(kind, &values);
+    }
+
+    /// A total to print, and how to get it from the counts of a trace.
+    type Row = (&'static str, fn(&Counts) -> u64);
+    let rows: [Row; 8] = [
+        ("actions", |c| c.actions as u64),
+        ("submissions", |c| c.submissions as u64),
+        ("render passes", |c| c.render_passes as u64),
+        ("compute passes", |c| c.compute_passes as u64),
+        ("draws", |c| c.draws as u64),
+        ("dispatches", |c| c.dispatches as u64),
+        ("pipelines created", |c| c.pipelines as u64),
+        ("buffer bytes written", |c| c.buffer_bytes_written),
+    ];
+    println!();
+    println!("Totals");
+    let frames = traces
+        .iter()
+        .map(|trace| trace.frames.len() as u64)
+        .collect::<Vec<_>>();
+    print_row("frames", &frames);
+    let totals = traces.iter().map(|trace| trace.total()).collect::<Vec<_>>();
+    for (name, value) in rows {
+        print_row(name, &totals.iter().map(value).collect::<Vec<_>>());
+    }
+
+    let frame_count = traces.iter().map(|trace| trace.frames.len()).max();
+    let frame_count = frame_count.unwrap_or(0);
+
+    println!();
+    println!(
+        "{:<8}{:>16}{:>16}{:>16}{:>16}{:>24}",
+        "Frame", "actions", "draws", "dispatches", "pipelines", "buffer bytes written"
+    );
+    for index in 0..frame_count {
+        let counts = traces
+            .iter()
+            .map(|trace| {
+                trace
+                    .frames
+                    .get(index)
+                    .map_or(Counts::default(), |f| f.counts)
+            })
+            .collect::<Vec<_>>();
+        if counts.windows(2).any(|pair| pair[0] == pair[1]) {
+            continue;
+        }
+        let cell = |value: fn(&Counts) -> u64| match *counts {
+            [ref a, ref b] if value(a) != value(b) => format!("{} -> {}", value(a), value(b)),
+            _ => value(&counts[0]).to_string(),
+        };
+        println!(
+            "{index:<8}{:>16}{:>16}{:>16}{:>16}{:>24}",
+            cell(|c| c.actions as u64),
+            cell(|c| c.draws as u64),
+            cell(|c| c.dispatches as u64),
+            cell(|c| c.pipelines as u64),
+            cell(|c| c.buffer_bytes_written),
+        );
+    }
+
+    let [ref a, ref b] = *traces else {
+        return;
+    };
+    if args.no_diff {
+        return;
+    }
+    println!();
+    println!("Differences");
+    let mut identical = true;
+    for index in 0..frame_count {
+        let changes = stats::diff(signatures(a, index), signatures(b, index));
+        if changes.is_empty() {
+            continue;
+        }
+        identical = false;
+        println!("Frame {index}:");
+        for change in changes {
+            match change {
+                stats::Change::Removed { index, signature } => {
+                    println!("  - A {index:>6}: {signature}")
+                }
+                stats::Change::Added { index, signature } => {
+                    println!("  + B {index:>6}: {signature}")
+                }
+            }
+        }
+    }
+    if identical {
+        println!("  The traces have the same structure");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
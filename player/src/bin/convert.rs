@@ -0,0 +1,99 @@
+//! This is a converter between the formats of WebGPU traces.
+
+#[cfg(not(target_arch = "wasm32"))]
+extern crate wgpu_core as wgc;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{path::PathBuf, str::FromStr};
+
+/// Convert a wgpu trace between the RON and binary formats.
+///
+/// The converted trace is written to the output directory, along with the
+/// buffers, textures, and shaders it needs.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(argh::FromArgs)]
+struct Args {
+    /// the format to convert to, `ron` or `binary`, by default the one the
+    /// trace is not in
+    #[argh(option)]
+    format: Option<FormatArg>,
+
+    /// the trace directory or file
+    #[argh(positional)]
+    trace: PathBuf,
+
+    /// directory to write the converted trace into
+    #[argh(positional)]
+    output: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct FormatArg(wgc::device::trace::Format);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FromStr for FormatArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use wgc::device::trace::Format;
+        Ok(Self(match s.to_lowercase().as_str() {
+            "ron" => Format::Ron,
+            "binary" | "bin" => Format::Binary,
+            _ => return Err(format!("Invalid value for --format: {s}")),
+        }))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use wgc::device::trace;
+
+    use std::{fs, process::exit};
+
+    env_logger::init();
+
+    let args: Args = argh::from_env();
+
+    let reader = match player::TraceReader::open(&args.trace) {
+        Ok(reader) => reader,
+        Err(e) => {
+            eprintln!("Unable to open trace '{}': {e}", args.trace.display());
+            exit(1);
+        }
+    };
+    let format = match args.format {
+        Some(FormatArg(format)) => format,
+        None if reader.format == trace::Format::Ron => trace::Format::Binary,
+        None => trace::Format::Ron,
+    };
+    log::info!(
+        "Converting trace '{:?}' from {:?} to {format:?}",
+        args.trace,
+        reader.format
+    );
+
+    fs::create_dir_all(&args.output).unwrap();
+    let dir = reader.dir.clone();
+    let mut trace = trace::Trace::new(args.output.clone(), format).unwrap();
+    let mut count = 0;
+    for action in reader {
+        let action = match action {
+            Ok(action) => action,
+            Err(e) => {
+                eprintln!("Stopping after {count} actions, unable to read the trace: {e}");
+                exit(1);
+            }
+        };
+        if let Some(data) = player::data_file(&action) {
+            if dir != args.output {
+                fs::copy(dir.join(data), args.output.join(data)).unwrap();
+            }
+        }
+        trace.add(action);
+        count += 1;
+    }
+    println!("Converted {count} actions");
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -0,0 +1,80 @@
+//! This is a minimizer for WebGPU traces.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+/// Reduce a wgpu trace to the smallest set of actions that still fails with
+/// the same error, replaying it on the noop backend.
+///
+/// The reduced trace is written to the output directory in the format of the
+/// original trace, along with the buffers, textures, and shaders it needs.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(argh::FromArgs)]
+struct Args {
+    /// only keep subsets of the trace that fail with an error containing this
+    /// text, instead of the whole error of the original trace
+    #[argh(option)]
+    error: Option<String>,
+
+    /// directory to write the reduced trace into
+    #[argh(option, short = 'o')]
+    output: PathBuf,
+
+    /// the trace directory or file
+    #[argh(positional)]
+    trace: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use std::process::exit;
+
+    env_logger::init();
+
+    let args: Args = argh::from_env();
+
+    log::info!("Loading trace '{:?}'", args.trace);
+    let reader = match player::TraceReader::open(&args.trace) {
+        Ok(reader) => reader,
+        Err(e) => {
+            eprintln!("Unable to open trace '{}': {e}", args.trace.display());
+            exit(1);
+        }
+    };
+    let dir = reader.dir.clone();
+    let format = reader.format;
+    let actions = match reader.collect::<Result<Vec<_>, _>>() {
+        Ok(actions) => actions,
+        Err(e) => {
+            eprintln!("Unable to read trace '{}': {e}", args.trace.display());
+            exit(1);
+        }
+    };
+    log::info!("Found {} actions", actions.len());
+
+    let count = actions.len();
+    let mut minimizer = player::minimize::Minimizer::new(actions, dir);
+    let error = match minimizer.replay(&(0..count).collect::<Vec<_>>()) {
+        Ok(()) => {
+            eprintln!("The trace replays without errors on the noop backend");
+            exit(1);
+        }
+        Err(error) => error,
+    };
+    println!("The trace fails with: {error}");
+    let error = match args.error {
+        Some(expected) if !error.contains(&expected) => {
+            eprintln!("The error of the trace does not contain '{expected}'");
+            exit(1);
+        }
+        Some(expected) => expected,
+        None => error,
+    };
+
+    let kept = minimizer.minimize(&error);
+    println!("Reduced the trace from {count} to {} actions", kept.len());
+    minimizer.write(&kept, &args.output, format).unwrap();
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -1,19 +1,20 @@
 //! This is a player for WebGPU traces.
 
 #[cfg(not(target_arch = "wasm32"))]
-fn main() {
-    extern crate wgpu_core as wgc;
-    extern crate wgpu_types as wgt;
+extern crate wgpu_core as wgc;
+#[cfg(not(target_arch = "wasm32"))]
+extern crate wgpu_types as wgt;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
 
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
     use player::GlobalPlay as _;
     use wgc::device::trace;
     use wgpu_core::identity::IdentityManager;
 
-    use std::{
-        fs,
-        path::{Path, PathBuf},
-        process::exit,
-    };
+    use std::process::exit;
 
     #[cfg(feature = "winit")]
     use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
@@ -27,34 +28,67 @@ fn main() {
 
     env_logger::init();
 
-    //TODO: setting for the backend bits
-    //TODO: setting for the target frame, or controls
-
-    const HELP: &str = "\
-    Usage: play <trace directory> | <trace file>\n\
-    \n\
-    Play a wgpu trace from the specified file or directory. If the trace contains\n\
-    buffers, textures, or shaders, the directory form must be used.\n";
-
-    let (dir, trace) = match std::env::args().nth(1) {
-        Some(arg) if Path::new(&arg).is_dir() => (
-            PathBuf::from(arg.clone()),
-            PathBuf::from(arg).join(trace::FILE_NAME),
-        ),
-        Some(arg) if Path::new(&arg).is_file() => {
-            (PathBuf::from("/nonexistent"), PathBuf::from(arg))
+    let args: Args = argh::from_env();
+
+    log::info!("Loading trace '{:?}'", args.trace);
+    let reader = match player::TraceReader::open(&args.trace) {
+        Ok(reader) => reader,
+        Err(e) => {
+            eprintln!("Unable to open trace '{}': {e}", args.trace.display());
+            exit(1);
         }
-        _ => {
-            eprintln!("{HELP}");
+    };
+    let dir = reader.dir.clone();
+    let mut actions = match reader.collect::<Result<Vec<_>, _>>() {
+        Ok(actions) => actions,
+        Err(e) => {
+            eprintln!("Unable to read trace '{}': {e}", args.trace.display());
             exit(1);
         }
     };
+    log::info!("Found {} actions", actions.len());
+
+    if args.list {
+        for (index, action) in actions.iter().enumerate() {
+            println!("{index}: {}", describe(action));
+        }
+        return;
+    }
+
+    if let Some(frame) = args.stop_after_frame {
+        let presents = actions
+            .iter()
+            .enumerate()
+            .filter(|(_, action)| matches!(action, trace::Action::Present(_)))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        match presents.get(frame.saturating_sub(1)) {
+            Some(&index) => actions.truncate(index + 1),
+            None => log::warn!("The trace only has {} frames", presents.len()),
+        }
+    }
+    if let Some(index) = args.stop_after_action {
+        actions.truncate(index + 1);
+    }
+
+    if args.check {
+        let errors = check(actions, &dir);
+        if errors > 0 {
+            eprintln!("Found {errors} validation errors");
+            exit(1);
+        }
+        println!("No validation errors");
+        return;
+    }
 
-    log::info!("Loading trace '{trace:?}'");
-    let file = fs::File::open(trace).unwrap();
-    let mut actions: Vec<trace::Action> = ron::de::from_reader(file).unwrap();
+    let mut replay = Replay {
+        dumper: player::dump::Dumper::new(args.dump.clone(), args.dump_dir.clone(), &actions),
+        dump_after: (!args.dump.is_empty()).then_some(args.dump_after_submit),
+        step: args.step,
+        next_action: 0,
+    };
+    let action_count = actions.len();
     actions.reverse(); // allows us to pop from the top
-    log::info!("Found {} actions", actions.len());
 
     #[cfg(feature = "winit")]
     let event_loop = {
@@ -68,8 +102,12 @@ fn main() {
         .build(&event_loop)
         .unwrap();
 
-    let global =
-        wgc::global::Global::new("player", &wgt::InstanceDescriptor::from_env_or_default());
+    let mut instance_desc = wgt::InstanceDescriptor::from_env_or_default();
+    if let Some(backends) = args.backends {
+        instance_desc.backends = backends;
+        instance_desc.backend_options.noop.enable |= backends.contains(wgt::Backends::NOOP);
+    }
+    let global = wgc::global::Global::new("player", &instance_desc);
     let mut command_encoder_id_manager = IdentityManager::new();
     let mut command_buffer_id_manager = IdentityManager::new();
 
@@ -92,6 +130,8 @@ fn main() {
             Some(_) => unreachable!(),
             None => (wgt::Backends::all(), wgt::DeviceDescriptor::default()),
         };
+    let backends = args.backends.unwrap_or(backends);
+    replay.next_action = action_count - actions.len();
 
     let adapter = global
         .request_adapter(
@@ -122,7 +162,8 @@ fn main() {
     {
         unsafe { global.device_start_graphics_debugger_capture(device) };
 
-        while let Some(action) = actions.pop() {
+        while let Some(mut action) = actions.pop() {
+            let dump = replay.begin(&mut action);
             global.process(
                 device,
                 queue,
@@ -131,6 +172,26 @@ fn main() {
                 &mut command_encoder_id_manager,
                 &mut command_buffer_id_manager,
             );
+            if dump {
+                replay.dumper.dump(
+                    &global,
+                    device,
+                    queue,
+                    &dir,
+                    &mut command_encoder_id_manager,
+                    &mut command_buffer_id_manager,
+                );
+            }
+        }
+        if replay.finish() {
+            replay.dumper.dump(
+                &global,
+                device,
+                queue,
+                &dir,
+                &mut command_encoder_id_manager,
+                &mut command_buffer_id_manager,
+            );
         }
 
         unsafe { global.device_stop_graphics_debugger_capture(device) };
@@ -153,7 +214,9 @@ fn main() {
                 match event {
                     Event::WindowEvent { event, .. } => match event {
                         WindowEvent::RedrawRequested if resize_config.is_none() => {
-                            match actions.pop() {
+                            let mut action = actions.pop();
+                            let dump = action.as_mut().is_some_and(|action| replay.begin(action));
+                            match action {
                                 Some(trace::Action::ConfigureSurface(_device_id, config)) => {
                                     log::info!("Configuring the surface");
                                     let current_size: (u32, u32) = window.inner_size().into();
@@ -198,12 +261,32 @@ fn main() {
                                 }
                                 None => {
                                     if !done {
+                                        if replay.finish() {
+                                            replay.dumper.dump(
+                                                &global,
+                                                device,
+                                                queue,
+                                                &dir,
+                                                &mut command_encoder_id_manager,
+                                                &mut command_buffer_id_manager,
+                                            );
+                                        }
                                         println!("Finished the end at frame {frame_count}");
                                         done = true;
                                     }
                                     target.exit();
                                 }
                             }
+                            if dump {
+                                replay.dumper.dump(
+                                    &global,
+                                    device,
+                                    queue,
+                                    &dir,
+                                    &mut command_encoder_id_manager,
+                                    &mut command_buffer_id_manager,
+                                );
+                            }
                         }
                         WindowEvent::Resized(_) => {
                             if let Some(config) = resize_config.take() {
@@ -236,5 +319,164 @@ fn main() {
     }
 }
 
+/// Play a wgpu trace from the specified file or directory. If the trace
+/// contains buffers, textures, or shaders, the directory form must be used.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(argh::FromArgs)]
+struct Args {
+    /// comma-separated list of backends to replay the trace on, such as
+    /// `vulkan` or `noop`, instead of the backend it was recorded on
+    #[argh(option, from_str_fn(parse_backends))]
+    backends: Option<wgt::Backends>,
+
+    /// stop after presenting the given frame, counting from 1
+    #[argh(option)]
+    stop_after_frame: Option<usize>,
+
+    /// stop after the action with the given index, as shown by `--list`
+    #[argh(option)]
+    stop_after_action: Option<usize>,
+
+    /// list the actions of the trace with their indices, without replaying it
+    #[argh(switch)]
+    list: bool,
+
+    /// print each action and wait for enter to be pressed before executing it
+    #[argh(switch)]
+    step: bool,
+
+    /// a resource to dump, as `buffer:<id>` or `texture:<id>`, optionally
+    /// followed by `,<epoch>`. Can be given multiple times.
+    ///
+    /// Buffers are written as raw bytes, textures as PNG images when they have
+    /// an 8-bit RGBA or BGRA format, and as raw texels of their first mip level
+    /// otherwise.
+    #[argh(option)]
+    dump: Vec<player::dump::Resource>,
+
+    /// dump the resources right after the submission with the given index,
+    /// instead of at the end of the replay
+    #[argh(option)]
+    dump_after_submit: Option<wgc::SubmissionIndex>,
+
+    /// directory to write dumped resources into, the current directory by default
+    #[argh(option, default = "PathBuf::from(\".\")")]
+    dump_dir: PathBuf,
+
+    /// replay the trace on the noop backend, without a window, and print every
+    /// validation error with the index of the action that raised it, instead
+    /// of stopping at the first one. Exits with a non-zero status if there are
+    /// any.
+    #[argh(switch)]
+    check: bool,
+
+    /// the trace directory or file
+    #[argh(positional)]
+    trace: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_backends(value: &str) -> Result<wgt::Backends, String> {
+    let backends = wgt::Backends::from_comma_list(value);
+    if backends.is_empty() {
+        return Err(format!("No valid backends in '{value}'"));
+    }
+    Ok(backends)
+}
+
+/// Replays `actions` on the noop backend, printing the validation errors, and
+/// returns how many there are.
+#[cfg(not(target_arch = "wasm32"))]
+fn check(actions: Vec<wgc::device::trace::Action>, dir: &Path) -> usize {
+    use wgc::device::trace;
+
+    let mut actions = actions.into_iter().enumerate().peekable();
+    let device_desc =
+        match actions.next_if(|(_, action)| matches!(action, trace::Action::Init { .. })) {
+            Some((_, trace::Action::Init { desc, .. })) => desc,
+            _ => wgt::DeviceDescriptor::default(),
+        };
+    let mut replay = match player::headless::Replay::new(&device_desc, dir) {
+        Ok(replay) => replay,
+        Err(e) => {
+            eprintln!("Unable to create a noop device: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut errors = 0;
+    for (index, action) in actions {
+        if let Err(e) = replay.process(action) {
+            println!("Action {index}: {}", player::format_error(&e));
+            errors += 1;
+        }
+    }
+    if let Err(e) = replay.wait() {
+        println!("After the last action: {e}");
+        errors += 1;
+    }
+    errors
+}
+
+/// The state of the replay that isn't specific to the windowing.
+#[cfg(not(target_arch = "wasm32"))]
+struct Replay {
+    dumper: player::dump::Dumper,
+    /// When to dump the resources, if there are any.
+    ///
+    /// `Some(None)` dumps them at the end of the replay.
+    dump_after: Option<Option<wgc::SubmissionIndex>>,
+    step: bool,
+    next_action: usize,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Replay {
+    /// Prepare to execute `action`, returning whether the resources have to be
+    /// dumped after it.
+    fn begin(&mut self, action: &mut wgc::device::trace::Action) -> bool {
+        self.dumper.observe(action);
+        if self.step {
+            println!("{}: {}", self.next_action, describe(action));
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line).unwrap();
+        }
+        self.next_action += 1;
+
+        match *action {
+            wgc::device::trace::Action::Submit(index, _)
+                if self.dump_after == Some(Some(index)) =>
+            {
+                self.dump_after = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Finish the replay, returning whether the resources still have to be dumped.
+    fn finish(&mut self) -> bool {
+        match self.dump_after.take() {
+            Some(None) => true,
+            Some(Some(index)) => {
+                log::warn!("Submission {index} was not replayed, nothing was dumped");
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+/// Describe `action` on a single line.
+#[cfg(not(target_arch = "wasm32"))]
+fn describe(action: &wgc::device::trace::Action) -> String {
+    match *action {
+        wgc::device::trace::Action::Submit(index, ref commands) => {
+            format!("Submit({index}, {} commands)", commands.len())
+        }
+        ref action => format!("{action:?}"),
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 fn main() {}
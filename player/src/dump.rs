@@ -0,0 +1,360 @@
+//! Reading buffers and textures back to the host while a trace is replayed.
+
+use wgc::{device::trace, identity::IdentityManager};
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::GlobalPlay as _;
+
+/// A buffer or texture of the trace to be dumped to a file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Resource {
+    Buffer(wgc::id::BufferId),
+    Texture(wgc::id::TextureId),
+}
+
+impl FromStr for Resource {
+    type Err = String;
+
+    /// Parses `buffer:<index>` or `texture:<index>`, optionally followed by
+    /// `,<epoch>` as in the `Id(<index>, <epoch>)` of the trace. The epoch
+    /// defaults to 1.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || format!("Invalid resource '{s}', expected 'buffer:<id>' or 'texture:<id>'");
+        let (kind, id) = s.split_once(':').ok_or_else(invalid)?;
+        let (index, epoch) = match id.split_once(',') {
+            Some((index, epoch)) => (index, epoch.trim()),
+            None => (id, "1"),
+        };
+        let index = index.trim().parse().map_err(|_| invalid())?;
+        let epoch = epoch.parse().map_err(|_| invalid())?;
+        if index == 0 && epoch == 0 {
+            return Err(invalid());
+        }
+        match kind.trim().to_lowercase().as_str() {
+            "buffer" => Ok(Self::Buffer(wgc::id::Id::zip(index, epoch))),
+            "texture" => Ok(Self::Texture(wgc::id::Id::zip(index, epoch))),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, (index, epoch)) = match *self {
+            Self::Buffer(id) => ("buffer", id.unzip()),
+            Self::Texture(id) => ("texture", id.unzip()),
+        };
+        write!(f, "{kind}-{index}-{epoch}")
+    }
+}
+
+/// Dumps the contents of buffers and textures to files in a directory.
+///
+/// Buffers are written as raw bytes. Textures are written as PNG images when
+/// they are 2D, single-sampled and have an 8-bit RGBA or BGRA format, and as
+/// the raw, tightly packed texels of their first mip level otherwise.
+///
+/// The dumper has to [`observe`] every action before it's processed, so that
+/// it knows the size of the resources to dump, and can make them copyable.
+///
+/// [`observe`]: Self::observe
+#[derive(Debug)]
+pub struct Dumper {
+    resources: Vec<Resource>,
+    dir: PathBuf,
+    /// The ID of the buffer the resources are copied into for reading, which
+    /// must not be used by the trace.
+    staging: wgc::id::BufferId,
+    buffer_sizes: BTreeMap<wgc::id::BufferId, wgt::BufferAddress>,
+    textures: BTreeMap<wgc::id::TextureId, wgt::TextureDescriptor<(), ()>>,
+}
+
+impl Dumper {
+    pub fn new(resources: Vec<Resource>, dir: PathBuf, actions: &[trace::Action]) -> Self {
+        let staging_index = actions
+            .iter()
+            .filter_map(|action| match *action {
+                trace::Action::CreateBuffer(id, _) => Some(id.unzip().0 + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        Self {
+            resources,
+            dir,
+            staging: wgc::id::Id::zip(staging_index, 1),
+            buffer_sizes: BTreeMap::new(),
+            textures: BTreeMap::new(),
+        }
+    }
+
+    /// Record the descriptors of the resources to dump, and add `COPY_SRC` to
+    /// their usages so that they can be read back.
+    pub fn observe(&mut self, action: &mut trace::Action) {
+        match *action {
+            trace::Action::CreateBuffer(id, ref mut desc)
+                if self.resources.contains(&Resource::Buffer(id)) =>
+            {
+                // Buffers that can be mapped for reading can't be copied from.
+                if !desc.usage.contains(wgt::BufferUsages::MAP_READ) {
+                    desc.usage |= wgt::BufferUsages::COPY_SRC;
+                }
+                self.buffer_sizes.insert(id, desc.size);
+            }
+            trace::Action::CreateTexture(id, ref mut desc)
+                if self.resources.contains(&Resource::Texture(id)) =>
+            {
+                desc.usage |= wgt::TextureUsages::COPY_SRC;
+                self.textures
+                    .insert(id, desc.map_label_and_view_formats(|_| (), |_| ()));
+            }
+            _ => {}
+        }
+    }
+
+    /// Read back every resource and write it to its file.
+    ///
+    /// Resources that can't be read back are reported and skipped.
+    pub fn dump(
+        &self,
+        global: &wgc::global::Global,
+        device: wgc::id::DeviceId,
+        queue: wgc::id::QueueId,
+        dir: &Path,
+        command_encoder_id_manager: &mut IdentityManager<wgc::id::markers::CommandEncoder>,
+        command_buffer_id_manager: &mut IdentityManager<wgc::id::markers::CommandBuffer>,
+    ) {
+        for &resource in &self.resources {
+            let readback = match resource {
+                Resource::Buffer(id) => self.buffer_readback(id),
+                Resource::Texture(id) => self.texture_readback(id),
+            };
+            let readback = match readback {
+                Ok(readback) => readback,
+                Err(e) => {
+                    log::error!("Unable to dump {resource}: {e}");
+                    continue;
+                }
+            };
+
+            global.process(
+                device,
+                queue,
+                trace::Action::CreateBuffer(
+                    self.staging,
+                    wgt::BufferDescriptor {
+                        label: Some("dump".into()),
+                        size: readback.size,
+                        usage: wgt::BufferUsages::MAP_READ | wgt::BufferUsages::COPY_DST,
+                        mapped_at_creation: false,
+                    },
+                ),
+                dir,
+                command_encoder_id_manager,
+                command_buffer_id_manager,
+            );
+            global.process(
+                device,
+                queue,
+                trace::Action::Submit(0, vec![readback.command]),
+                dir,
+                command_encoder_id_manager,
+                command_buffer_id_manager,
+            );
+            global
+                .buffer_map_async(
+                    self.staging,
+                    0,
+                    None,
+                    wgc::resource::BufferMapOperation {
+                        host: wgc::device::HostMap::Read,
+                        callback: None,
+                    },
+                )
+                .unwrap();
+            global.device_poll(device, wgt::PollType::wait()).unwrap();
+            let (ptr, size) = global
+                .buffer_get_mapped_range(self.staging, 0, None)
+                .unwrap();
+            // SAFETY: The range stays mapped until `buffer_unmap` below.
+            let data = unsafe { std::slice::from_raw_parts(ptr.as_ptr(), size as usize) };
+
+            let path = self
+                .dir
+                .join(format!("{resource}.{}", readback.format.extension()));
+            log::info!("Dumping {resource} to {path:?}");
+            let result = readback.format.write(&path, data);
+
+            global.buffer_unmap(self.staging).unwrap();
+            global.buffer_drop(self.staging);
+            if let Err(e) = result {
+                log::error!("Unable to write {path:?}: {e}");
+            }
+        }
+    }
+
+    fn buffer_readback(&self, id: wgc::id::BufferId) -> Result<Readback, String> {
+        let &size = self
+            .buffer_sizes
+            .get(&id)
+            .ok_or("the buffer wasn't created by the trace")?;
+        // Copies must be a multiple of 4 bytes.
+        let size = size & !(wgt::COPY_BUFFER_ALIGNMENT - 1);
+        Ok(Readback {
+            size,
+            command: trace::Command::CopyBufferToBuffer {
+                src: id,
+                src_offset: 0,
+                dst: self.staging,
+                dst_offset: 0,
+                size: Some(size),
+            },
+            format: Format::Raw {
+                row_size: size as usize,
+                padded_row_size: size as usize,
+            },
+        })
+    }
+
+    fn texture_readback(&self, id: wgc::id::TextureId) -> Result<Readback, String> {
+        let desc = self
+            .textures
+            .get(&id)
+            .ok_or("the texture wasn't created by the trace")?;
+        if desc.sample_count > 1 {
+            return Err("multisampled textures can't be copied".into());
+        }
+        let block_size = desc
+            .format
+            .block_copy_size(None)
+            .ok_or_else(|| format!("textures of format {:?} can't be copied", desc.format))?;
+        let (block_width, block_height) = desc.format.block_dimensions();
+
+        let size = wgt::Extent3d {
+            depth_or_array_layers: desc.array_layer_count(),
+            ..desc.size
+        };
+        let row_size = size.width.div_ceil(block_width) * block_size;
+        let padded_row_size = row_size.next_multiple_of(wgt::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let rows = size.height.div_ceil(block_height) * size.depth_or_array_layers;
+
+        let is_image = desc.dimension == wgt::TextureDimension::D2
+            && size.depth_or_array_layers == 1
+            && matches!(
+                desc.format.remove_srgb_suffix(),
+                wgt::TextureFormat::Rgba8Unorm | wgt::TextureFormat::Bgra8Unorm
+            );
+        let format = if is_image {
+            Format::Png {
+                width: size.width,
+                height: size.height,
+                padded_row_size: padded_row_size as usize,
+                bgra: desc.format.remove_srgb_suffix() == wgt::TextureFormat::Bgra8Unorm,
+            }
+        } else {
+            Format::Raw {
+                row_size: row_size as usize,
+                padded_row_size: padded_row_size as usize,
+            }
+        };
+
+        Ok(Readback {
+            size: u64::from(padded_row_size) * u64::from(rows),
+            command: trace::Command::CopyTextureToBuffer {
+                src: wgt::TexelCopyTextureInfo {
+                    texture: id,
+                    mip_level: 0,
+                    origin: wgt::Origin3d::ZERO,
+                    aspect: wgt::TextureAspect::All,
+                },
+                dst: wgt::TexelCopyBufferInfo {
+                    buffer: self.staging,
+                    layout: wgt::TexelCopyBufferLayout {
+                        offset: 0,
+                        bytes_per_row: Some(padded_row_size),
+                        rows_per_image: Some(size.height.div_ceil(block_height)),
+                    },
+                },
+                size,
+            },
+            format,
+        })
+    }
+}
+
+/// How to copy a resource into the staging buffer, and write it out.
+struct Readback {
+    size: wgt::BufferAddress,
+    command: trace::Command,
+    format: Format,
+}
+
+enum Format {
+    /// Rows of bytes, with the padding at the end of each row removed.
+    Raw {
+        row_size: usize,
+        padded_row_size: usize,
+    },
+    Png {
+        width: u32,
+        height: u32,
+        padded_row_size: usize,
+        bgra: bool,
+    },
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match *self {
+            Self::Raw { .. } => "bin",
+            Self::Png { .. } => "png",
+        }
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        match *self {
+            Self::Raw {
+                row_size,
+                padded_row_size,
+            } => {
+                let bytes = data
+                    .chunks(padded_row_size)
+                    .flat_map(|row| &row[..row_size])
+                    .copied()
+                    .collect::<Vec<u8>>();
+                fs::write(path, bytes)?;
+            }
+            Self::Png {
+                width,
+                height,
+                padded_row_size,
+                bgra,
+            } => {
+                let row_size = width as usize * 4;
+                let mut pixels = data
+                    .chunks(padded_row_size)
+                    .flat_map(|row| &row[..row_size])
+                    .copied()
+                    .collect::<Vec<u8>>();
+                if bgra {
+                    for pixel in pixels.chunks_exact_mut(4) {
+                        pixel.swap(0, 2);
+                    }
+                }
+                let file = fs::File::create(path)?;
+                let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), width, height);
+                encoder.set_color(png::ColorType::Rgba);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.write_header()?.write_image_data(&pixels)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+//! Replaying traces on the noop backend, without a GPU or a window.
+
+use wgc::{device::trace, error::ContextError, id, identity::IdentityManager};
+
+use std::path::{Path, PathBuf};
+
+use crate::GlobalPlay as _;
+
+/// Replays the actions of a trace on a device of the noop backend.
+///
+/// Surfaces are emulated: the textures returned by
+/// [`trace::Action::GetSurfaceTexture`] are created with the size and format of
+/// the last surface configuration, and presenting does nothing.
+pub struct Replay {
+    global: wgc::global::Global,
+    device: id::DeviceId,
+    queue: id::QueueId,
+    /// The directory of the files that the actions refer to.
+    dir: PathBuf,
+    command_encoder_id_manager: IdentityManager<id::markers::CommandEncoder>,
+    command_buffer_id_manager: IdentityManager<id::markers::CommandBuffer>,
+    surface_config: Option<wgt::SurfaceConfiguration<Vec<wgt::TextureFormat>>>,
+}
+
+impl Replay {
+    /// Creates the device described by `desc`, to replay the actions of the
+    /// trace in `dir`.
+    pub fn new(desc: &wgc::device::DeviceDescriptor, dir: &Path) -> Result<Self, String> {
+        let global = wgc::global::Global::new(
+            "player",
+            &wgt::InstanceDescriptor {
+                backends: wgt::Backends::NOOP,
+                backend_options: wgt::BackendOptions {
+                    noop: wgt::NoopBackendOptions {
+                        enable: true,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        let adapter = global
+            .request_adapter(
+                &wgc::instance::RequestAdapterOptions::default(),
+                wgt::Backends::NOOP,
+                Some(id::AdapterId::zip(0, 1)),
+            )
+            .map_err(|e| e.to_string())?;
+        let device = id::Id::zip(0, 1);
+        let queue = id::Id::zip(0, 1);
+        global
+            .adapter_request_device(adapter, desc, Some(device), Some(queue))
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            global,
+            device,
+            queue,
+            dir: dir.to_path_buf(),
+            command_encoder_id_manager: IdentityManager::new(),
+            command_buffer_id_manager: IdentityManager::new(),
+            surface_config: None,
+        })
+    }
+
+    /// Processes `action`, returning the error `wgpu_core` raised for it, if
+    /// any.
+    pub fn process(&mut self, action: trace::Action) -> Result<(), ContextError> {
+        let action = match action {
+            trace::Action::ConfigureSurface(_, config) => {
+                self.surface_config = Some(config);
+                return Ok(());
+            }
+            trace::Action::Present(_) | trace::Action::DiscardSurfaceTexture(_) => return Ok(()),
+            trace::Action::GetSurfaceTexture { id, .. } => {
+                let config = self.surface_config.as_ref().ok_or(ContextError {
+                    fn_ident: "GetSurfaceTexture",
+                    source: "The surface is not configured".into(),
+                    label: String::new(),
+                })?;
+                trace::Action::CreateTexture(
+                    id,
+                    wgt::TextureDescriptor {
+                        label: Some("surface".into()),
+                        size: wgt::Extent3d {
+                            width: config.width,
+                            height: config.height,
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgt::TextureDimension::D2,
+                        format: config.format,
+                        usage: config.usage,
+                        view_formats: config.view_formats.clone(),
+                    },
+                )
+            }
+            action => action,
+        };
+        self.global.try_process(
+            self.device,
+            self.queue,
+            action,
+            &self.dir,
+            &mut self.command_encoder_id_manager,
+            &mut self.command_buffer_id_manager,
+        )
+    }
+
+    /// Waits for the work submitted by the actions to be done.
+    pub fn wait(&self) -> Result<(), String> {
+        self.global
+            .device_poll(self.device, wgt::PollType::wait())
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A trace whose passes use a buffer that failed to be created, in a
+    /// submission of their own, and followed by another pass.
+    const ACTIONS: &str = r#"[
+        CreateBuffer(Id(0, 1), (label: Some("invalid"), size: 16, usage: "MAP_READ | STORAGE", mapped_at_creation: false)),
+        CreateBuffer(Id(1, 1), (label: Some("valid"), size: 16, usage: "VERTEX | INDIRECT", mapped_at_creation: false)),
+        CreateTexture(Id(0, 1), (
+            label: Some("target"),
+            size: (width: 4, height: 4),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: r#2d,
+            format: "rgba8unorm",
+            usage: "RENDER_ATTACHMENT",
+            view_formats: [],
+        )),
+        CreateTextureView(id: Id(0, 1), parent_id: Id(0, 1), desc: ()),
+        Submit(1, [RunComputePass(base: (
+            commands: [DispatchIndirect(buffer_id: Id(0, 1), offset: 0)],
+            dynamic_offsets: [], string_data: [], push_constant_data: [],
+        ))]),
+        Submit(2, [RunRenderPass(
+            base: (
+                commands: [SetVertexBuffer(slot: 0, buffer_id: Id(0, 1), offset: 0, size: None)],
+                dynamic_offsets: [], string_data: [], push_constant_data: [],
+            ),
+            target_colors: [Some((view: Id(0, 1), resolve_target: None, load_op: load, store_op: store))],
+            target_depth_stencil: None,
+        )]),
+        Submit(3, [
+            RunComputePass(base: (
+                commands: [DispatchIndirect(buffer_id: Id(0, 1), offset: 0)],
+                dynamic_offsets: [], string_data: [], push_constant_data: [],
+            )),
+            RunRenderPass(
+                base: (
+                    commands: [SetVertexBuffer(slot: 0, buffer_id: Id(1, 1), offset: 0, size: None)],
+                    dynamic_offsets: [], string_data: [], push_constant_data: [],
+                ),
+                target_colors: [Some((view: Id(0, 1), resolve_target: None, load_op: load, store_op: store))],
+                target_depth_stencil: None,
+            ),
+        ]),
+        Submit(4, [RunRenderPass(
+            base: (
+                commands: [SetVertexBuffer(slot: 0, buffer_id: Id(1, 1), offset: 0, size: None)],
+                dynamic_offsets: [], string_data: [], push_constant_data: [],
+            ),
+            target_colors: [Some((view: Id(0, 1), resolve_target: None, load_op: load, store_op: store))],
+            target_depth_stencil: None,
+        )]),
+    ]"#;
+
+    #[test]
+    fn invalid_ids_in_passes() {
+        let actions: Vec<trace::Action> = ron::from_str(ACTIONS).unwrap();
+        let mut replay = Replay::new(&Default::default(), Path::new("")).unwrap();
+        let errors = actions
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, action)| {
+                let error = replay.process(action).err()?;
+                Some((index, crate::format_error(&error)))
+            })
+            .collect::<Vec<_>>();
+        replay.wait().unwrap();
+
+        let indices = errors.iter().map(|&(index, _)| index).collect::<Vec<_>>();
+        assert_eq!(indices, [0, 4, 5, 6], "{errors:#?}");
+        for (_, error) in &errors[1..] {
+            assert!(error.starts_with("In Submit"), "{error}");
+        }
+        assert!(errors[1].1.contains("In a dispatch command"), "{errors:#?}");
+        assert!(
+            errors[2].1.contains("In a set_vertex_buffer command"),
+            "{errors:#?}"
+        );
+        for (_, error) in &errors[1..3] {
+            assert!(
+                error.contains("Buffer with 'invalid' label is invalid"),
+                "{error}"
+            );
+        }
+    }
+}
@@ -6,9 +6,209 @@
 extern crate wgpu_core as wgc;
 extern crate wgpu_types as wgt;
 
-use wgc::{device::trace, identity::IdentityManager};
+use wgc::{
+    device::trace,
+    error::{ContextError, ContextErrorSource},
+    identity::IdentityManager,
+};
 
-use std::{borrow::Cow, fs, path::Path};
+use std::{
+    borrow::Cow,
+    fs, io,
+    io::{BufRead as _, Read as _, Seek as _},
+    path::{Path, PathBuf},
+};
+
+pub mod dump;
+pub mod headless;
+pub mod minimize;
+pub mod stats;
+
+/// Reads the actions of a trace, in either [`trace::Format`].
+///
+/// Binary traces are read one action at a time, while RON traces are read
+/// at once when opened.
+#[derive(Debug)]
+pub struct TraceReader {
+    /// The directory of the files that the actions refer to.
+    pub dir: PathBuf,
+    pub format: trace::Format,
+    actions: Actions,
+}
+
+#[derive(Debug)]
+enum Actions {
+    Ron(std::vec::IntoIter<trace::Action<'static>>),
+    Binary(trace::BinaryReader<io::BufReader<fs::File>>),
+}
+
+impl TraceReader {
+    /// Opens the trace at `path`.
+    ///
+    /// `path` is either a trace directory, holding a `trace.bin` or `trace.ron`
+    /// file, or the trace file itself, whose format is detected from its
+    /// contents. If the trace contains buffers, textures, or shaders, the
+    /// directory form must be used.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let (dir, file) = if path.is_dir() {
+            let binary = path.join(trace::BINARY_FILE_NAME);
+            let file = if binary.is_file() {
+                binary
+            } else {
+                path.join(trace::FILE_NAME)
+            };
+            (path.to_path_buf(), file)
+        } else {
+            (PathBuf::from("/nonexistent"), path.to_path_buf())
+        };
+
+        let mut reader = io::BufReader::new(fs::File::open(&file)?);
+        // The version of the binary format is left out, so that `BinaryReader`
+        // reports traces of other versions as such, rather than as invalid RON.
+        let magic = &trace::BINARY_MAGIC[..trace::BINARY_MAGIC.len() - 1];
+        let is_binary = reader.fill_buf()?.starts_with(magic);
+        let (format, actions) = if is_binary {
+            let reader = trace::BinaryReader::new(reader)?;
+            (trace::Format::Binary, Actions::Binary(reader))
+        } else {
+            reader.rewind()?;
+            let mut string = String::new();
+            reader.read_to_string(&mut string)?;
+            let actions: Vec<trace::Action> = ron::de::from_str(&string)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            (trace::Format::Ron, Actions::Ron(actions.into_iter()))
+        };
+        Ok(Self {
+            dir,
+            format,
+            actions,
+        })
+    }
+}
+
+impl Iterator for TraceReader {
+    type Item = io::Result<trace::Action<'static>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.actions {
+            Actions::Ron(ref mut actions) => actions.next().map(Ok),
+            Actions::Binary(ref mut reader) => reader.next(),
+        }
+    }
+}
+
+/// Returns the name of the file in the trace directory that `action` reads, if any.
+pub fn data_file<'a>(action: &'a trace::Action) -> Option<&'a str> {
+    match *action {
+        trace::Action::WriteBuffer { ref data, .. }
+        | trace::Action::WriteTexture { ref data, .. }
+        | trace::Action::CreateShaderModule { ref data, .. } => Some(data),
+        _ => None,
+    }
+}
+
+/// Returns the name of the variant of `action`.
+pub fn action_name(action: &trace::Action) -> &'static str {
+    use trace::Action as A;
+    match *action {
+        A::Init { .. } => "Init",
+        A::ConfigureSurface(..) => "ConfigureSurface",
+        A::CreateBuffer(..) => "CreateBuffer",
+        A::FreeBuffer(_) => "FreeBuffer",
+        A::DestroyBuffer(_) => "DestroyBuffer",
+        A::CreateTexture(..) => "CreateTexture",
+        A::FreeTexture(_) => "FreeTexture",
+        A::DestroyTexture(_) => "DestroyTexture",
+        A::CreateTextureView { .. } => "CreateTextureView",
+        A::DestroyTextureView(_) => "DestroyTextureView",
+        A::CreateExternalTexture { .. } => "CreateExternalTexture",
+        A::FreeExternalTexture(_) => "FreeExternalTexture",
+        A::DestroyExternalTexture(_) => "DestroyExternalTexture",
+        A::CreateSampler(..) => "CreateSampler",
+        A::DestroySampler(_) => "DestroySampler",
+        A::GetSurfaceTexture { .. } => "GetSurfaceTexture",
+        A::Present(_) => "Present",
+        A::DiscardSurfaceTexture(_) => "DiscardSurfaceTexture",
+        A::CreateBindGroupLayout(..) => "CreateBindGroupLayout",
+        A::DestroyBindGroupLayout(_) => "DestroyBindGroupLayout",
+        A::CreatePipelineLayout(..) => "CreatePipelineLayout",
+        A::DestroyPipelineLayout(_) => "DestroyPipelineLayout",
+        A::CreateBindGroup(..) => "CreateBindGroup",
+        A::DestroyBindGroup(_) => "DestroyBindGroup",
+        A::CreateShaderModule { .. } => "CreateShaderModule",
+        A::DestroyShaderModule(_) => "DestroyShaderModule",
+        A::CreateComputePipeline { .. } => "CreateComputePipeline",
+        A::DestroyComputePipeline(_) => "DestroyComputePipeline",
+        A::CreateRenderPipeline { .. } => "CreateRenderPipeline",
+        A::CreateMeshPipeline { .. } => "CreateMeshPipeline",
+        A::DestroyRenderPipeline(_) => "DestroyRenderPipeline",
+        A::CreatePipelineCache { .. } => "CreatePipelineCache",
+        A::DestroyPipelineCache(_) => "DestroyPipelineCache",
+        A::CreateRenderBundle { .. } => "CreateRenderBundle",
+        A::DestroyRenderBundle(_) => "DestroyRenderBundle",
+        A::CreateQuerySet { .. } => "CreateQuerySet",
+        A::DestroyQuerySet(_) => "DestroyQuerySet",
+        A::CreateHeap { .. } => "CreateHeap",
+        A::DestroyHeap(_) => "DestroyHeap",
+        A::CreatePlacedBuffer { .. } => "CreatePlacedBuffer",
+        A::CreatePlacedTexture { .. } => "CreatePlacedTexture",
+        A::WriteBuffer { .. } => "WriteBuffer",
+        A::WriteTexture { .. } => "WriteTexture",
+        A::UpdateSparseResidency { .. } => "UpdateSparseResidency",
+        A::Submit(..) => "Submit",
+        A::CreateBlas { .. } => "CreateBlas",
+        A::DestroyBlas(_) => "DestroyBlas",
+        A::CreateTlas { .. } => "CreateTlas",
+        A::DestroyTlas(_) => "DestroyTlas",
+    }
+}
+
+/// Returns the label of the object `action` creates, if any.
+pub fn action_label<'a>(action: &'a trace::Action) -> Option<&'a str> {
+    use trace::Action as A;
+    match *action {
+        A::CreateBuffer(_, ref desc) => desc.label.as_deref(),
+        A::CreateTexture(_, ref desc) => desc.label.as_deref(),
+        A::CreateTextureView { ref desc, .. } => desc.label.as_deref(),
+        A::CreateExternalTexture { ref desc, .. } => desc.label.as_deref(),
+        A::CreateSampler(_, ref desc) => desc.label.as_deref(),
+        A::CreateBindGroupLayout(_, ref desc) => desc.label.as_deref(),
+        A::CreatePipelineLayout(_, ref desc) => desc.label.as_deref(),
+        A::CreateBindGroup(_, ref desc) => desc.label.as_deref(),
+        A::CreateShaderModule { ref desc, .. } => desc.label.as_deref(),
+        A::CreateComputePipeline { ref desc, .. } => desc.label.as_deref(),
+        A::CreateRenderPipeline { ref desc, .. } => desc.label.as_deref(),
+        A::CreateMeshPipeline { ref desc, .. } => desc.label.as_deref(),
+        A::CreatePipelineCache { ref desc, .. } => desc.label.as_deref(),
+        A::CreateRenderBundle { ref desc, .. } => desc.label.as_deref(),
+        A::CreateQuerySet { ref desc, .. } => desc.label.as_deref(),
+        A::CreateHeap { ref desc, .. } => desc.label.as_deref(),
+        A::CreatePlacedBuffer { ref desc, .. } => desc.label.as_deref(),
+        A::CreatePlacedTexture { ref desc, .. } => desc.label.as_deref(),
+        A::CreateBlas { ref desc, .. } => desc.label.as_deref(),
+        A::CreateTlas { ref desc, .. } => desc.label.as_deref(),
+        _ => None,
+    }
+}
+
+/// Formats `error` followed by the errors that caused it, each on its own line
+/// and indented one level deeper than the error it caused.
+///
+/// Errors whose message is already part of the ones they caused are skipped.
+pub fn format_error(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    let mut indent = 2;
+    while let Some(error) = source {
+        let text = error.to_string();
+        if !message.contains(&text) {
+            message += &format!("\n{:indent$}{text}", "");
+            indent += 2;
+        }
+        source = error.source();
+    }
+    message
+}
 
 pub trait GlobalPlay {
     fn encode_commands(
@@ -16,7 +216,21 @@ pub trait GlobalPlay {
         encoder: wgc::id::CommandEncoderId,
         commands: Vec<trace::Command>,
         command_buffer_id_manager: &mut IdentityManager<wgc::id::markers::CommandBuffer>,
-    ) -> wgc::id::CommandBufferId;
+    ) -> Result<wgc::id::CommandBufferId, ContextErrorSource>;
+
+    /// Processes `action`, returning the error `wgpu_core` raised for it, if
+    /// any, in the context of the action and the label of what it creates.
+    fn try_process(
+        &self,
+        device: wgc::id::DeviceId,
+        queue: wgc::id::QueueId,
+        action: trace::Action,
+        dir: &Path,
+        command_encoder_id_manager: &mut IdentityManager<wgc::id::markers::CommandEncoder>,
+        command_buffer_id_manager: &mut IdentityManager<wgc::id::markers::CommandBuffer>,
+    ) -> Result<(), ContextError>;
+
+    /// Processes `action`, panicking if `wgpu_core` raises an error for it.
     fn process(
         &self,
         device: wgc::id::DeviceId,
@@ -25,7 +239,19 @@ pub trait GlobalPlay {
         dir: &Path,
         command_encoder_id_manager: &mut IdentityManager<wgc::id::markers::CommandEncoder>,
         command_buffer_id_manager: &mut IdentityManager<wgc::id::markers::CommandBuffer>,
-    );
+    ) {
+        let result = self.try_process(
+            device,
+            queue,
+            action,
+            dir,
+            command_encoder_id_manager,
+            command_buffer_id_manager,
+        );
+        if let Err(e) = result {
+            panic!("{}", format_error(&e));
+        }
+    }
 }
 
 impl GlobalPlay for wgc::global::Global {
@@ -34,7 +260,7 @@ impl GlobalPlay for wgc::global::Global {
         encoder: wgc::id::CommandEncoderId,
         commands: Vec<trace::Command>,
         command_buffer_id_manager: &mut IdentityManager<wgc::id::markers::CommandBuffer>,
-    ) -> wgc::id::CommandBufferId {
+    ) -> Result<wgc::id::CommandBufferId, ContextErrorSource> {
         for command in commands {
             match command {
                 trace::Command::CopyBufferToBuffer {
@@ -43,60 +269,50 @@ impl GlobalPlay for wgc::global::Global {
                     dst,
                     dst_offset,
                     size,
-                } => self
-                    .command_encoder_copy_buffer_to_buffer(
-                        encoder, src, src_offset, dst, dst_offset, size,
-                    )
-                    .unwrap(),
-                trace::Command::CopyBufferToTexture { src, dst, size } => self
-                    .command_encoder_copy_buffer_to_texture(encoder, &src, &dst, &size)
-                    .unwrap(),
-                trace::Command::CopyTextureToBuffer { src, dst, size } => self
-                    .command_encoder_copy_texture_to_buffer(encoder, &src, &dst, &size)
-                    .unwrap(),
-                trace::Command::CopyTextureToTexture { src, dst, size } => self
-                    .command_encoder_copy_texture_to_texture(encoder, &src, &dst, &size)
-                    .unwrap(),
-                trace::Command::ClearBuffer { dst, offset, size } => self
-                    .command_encoder_clear_buffer(encoder, dst, offset, size)
-                    .unwrap(),
+                } => self.command_encoder_copy_buffer_to_buffer(
+                    encoder, src, src_offset, dst, dst_offset, size,
+                )?,
+                trace::Command::CopyBufferToTexture { src, dst, size } => {
+                    self.command_encoder_copy_buffer_to_texture(encoder, &src, &dst, &size)?
+                }
+                trace::Command::CopyTextureToBuffer { src, dst, size } => {
+                    self.command_encoder_copy_texture_to_buffer(encoder, &src, &dst, &size)?
+                }
+                trace::Command::CopyTextureToTexture { src, dst, size } => {
+                    self.command_encoder_copy_texture_to_texture(encoder, &src, &dst, &size)?
+                }
+                trace::Command::ClearBuffer { dst, offset, size } => {
+                    self.command_encoder_clear_buffer(encoder, dst, offset, size)?
+                }
                 trace::Command::ClearTexture {
                     dst,
                     subresource_range,
-                } => self
-                    .command_encoder_clear_texture(encoder, dst, &subresource_range)
-                    .unwrap(),
+                } => self.command_encoder_clear_texture(encoder, dst, &subresource_range)?,
                 trace::Command::WriteTimestamp {
                     query_set_id,
                     query_index,
-                } => self
-                    .command_encoder_write_timestamp(encoder, query_set_id, query_index)
-                    .unwrap(),
+                } => self.command_encoder_write_timestamp(encoder, query_set_id, query_index)?,
                 trace::Command::ResolveQuerySet {
                     query_set_id,
                     start_query,
                     query_count,
                     destination,
                     destination_offset,
-                } => self
-                    .command_encoder_resolve_query_set(
-                        encoder,
-                        query_set_id,
-                        start_query,
-                        query_count,
-                        destination,
-                        destination_offset,
-                    )
-                    .unwrap(),
-                trace::Command::PushDebugGroup(marker) => self
-                    .command_encoder_push_debug_group(encoder, &marker)
-                    .unwrap(),
-                trace::Command::PopDebugGroup => {
-                    self.command_encoder_pop_debug_group(encoder).unwrap()
+                } => self.command_encoder_resolve_query_set(
+                    encoder,
+                    query_set_id,
+                    start_query,
+                    query_count,
+                    destination,
+                    destination_offset,
+                )?,
+                trace::Command::PushDebugGroup(marker) => {
+                    self.command_encoder_push_debug_group(encoder, &marker)?
+                }
+                trace::Command::PopDebugGroup => self.command_encoder_pop_debug_group(encoder)?,
+                trace::Command::InsertDebugMarker(marker) => {
+                    self.command_encoder_insert_debug_marker(encoder, &marker)?
                 }
-                trace::Command::InsertDebugMarker(marker) => self
-                    .command_encoder_insert_debug_marker(encoder, &marker)
-                    .unwrap(),
                 trace::Command::RunComputePass {
                     base,
                     timestamp_writes,
@@ -170,8 +386,10 @@ impl GlobalPlay for wgc::global::Global {
 
                     self.command_encoder_build_acceleration_structures(
                         encoder, blas_iter, tlas_iter,
-                    )
-                    .unwrap();
+                    )?;
+                }
+                trace::Command::AliasingBarrier { buffers, textures } => {
+                    self.command_encoder_aliasing_barrier(encoder, &buffers, &textures)?
                 }
             }
         }
@@ -181,12 +399,12 @@ impl GlobalPlay for wgc::global::Global {
             Some(command_buffer_id_manager.process()),
         );
         if let Some(e) = error {
-            panic!("{e}");
+            return Err(e.into());
         }
-        cmd_buf
+        Ok(cmd_buf)
     }
 
-    fn process(
+    fn try_process(
         &self,
         device: wgc::id::DeviceId,
         queue: wgc::id::QueueId,
@@ -194,237 +412,300 @@ impl GlobalPlay for wgc::global::Global {
         dir: &Path,
         command_encoder_id_manager: &mut IdentityManager<wgc::id::markers::CommandEncoder>,
         command_buffer_id_manager: &mut IdentityManager<wgc::id::markers::CommandBuffer>,
-    ) {
-        use wgc::device::trace::Action;
-        log::debug!("action {action:?}");
-        //TODO: find a way to force ID perishing without excessive `maintain()` calls.
-        match action {
-            Action::Init { .. } => {
-                panic!("Unexpected Action::Init: has to be the first action only")
-            }
-            Action::ConfigureSurface { .. }
-            | Action::Present(_)
-            | Action::DiscardSurfaceTexture(_) => {
-                panic!("Unexpected Surface action: winit feature is not enabled")
-            }
-            Action::CreateBuffer(id, desc) => {
-                let (_, error) = self.device_create_buffer(device, &desc, Some(id));
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
-            }
-            Action::FreeBuffer(id) => {
-                self.buffer_destroy(id);
-            }
-            Action::DestroyBuffer(id) => {
-                self.buffer_drop(id);
-            }
-            Action::CreateTexture(id, desc) => {
-                let (_, error) = self.device_create_texture(device, &desc, Some(id));
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
-            }
-            Action::FreeTexture(id) => {
-                self.texture_destroy(id);
-            }
-            Action::DestroyTexture(id) => {
-                self.texture_drop(id);
-            }
-            Action::CreateTextureView {
-                id,
-                parent_id,
-                desc,
-            } => {
-                let (_, error) = self.texture_create_view(parent_id, &desc, Some(id));
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
-            }
-            Action::DestroyTextureView(id) => {
-                self.texture_view_drop(id).unwrap();
-            }
-            Action::CreateExternalTexture { id, desc, planes } => {
-                let (_, error) =
-                    self.device_create_external_texture(device, &desc, &planes, Some(id));
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
-            }
-            Action::FreeExternalTexture(id) => {
-                self.external_texture_destroy(id);
-            }
-            Action::DestroyExternalTexture(id) => {
-                self.external_texture_drop(id);
-            }
-            Action::CreateSampler(id, desc) => {
-                let (_, error) = self.device_create_sampler(device, &desc, Some(id));
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
-            }
-            Action::DestroySampler(id) => {
-                self.sampler_drop(id);
-            }
-            Action::GetSurfaceTexture { id, parent_id } => {
-                self.surface_get_current_texture(parent_id, Some(id))
-                    .unwrap()
-                    .texture
-                    .unwrap();
-            }
-            Action::CreateBindGroupLayout(id, desc) => {
-                let (_, error) = self.device_create_bind_group_layout(device, &desc, Some(id));
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
-            }
-            Action::DestroyBindGroupLayout(id) => {
-                self.bind_group_layout_drop(id);
-            }
-            Action::CreatePipelineLayout(id, desc) => {
-                let (_, error) = self.device_create_pipeline_layout(device, &desc, Some(id));
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
-            }
-            Action::DestroyPipelineLayout(id) => {
-                self.pipeline_layout_drop(id);
-            }
-            Action::CreateBindGroup(id, desc) => {
-                let (_, error) = self.device_create_bind_group(device, &desc, Some(id));
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
-            }
-            Action::DestroyBindGroup(id) => {
-                self.bind_group_drop(id);
-            }
-            Action::CreateShaderModule { id, desc, data } => {
-                log::debug!("Creating shader from {data}");
-                let code = fs::read_to_string(dir.join(&data)).unwrap();
-                let source = if data.ends_with(".wgsl") {
-                    wgc::pipeline::ShaderModuleSource::Wgsl(Cow::Owned(code.clone()))
-                } else if data.ends_with(".ron") {
-                    let module = ron::de::from_str(&code).unwrap();
-                    wgc::pipeline::ShaderModuleSource::Naga(module)
-                } else {
-                    panic!("Unknown shader {data}");
-                };
-                let (_, error) = self.device_create_shader_module(device, &desc, source, Some(id));
-                if let Some(e) = error {
-                    println!("shader compilation error:\n---{code}\n---\n{e}");
-                }
-            }
-            Action::DestroyShaderModule(id) => {
-                self.shader_module_drop(id);
+    ) -> Result<(), ContextError> {
+        let fn_ident = action_name(&action);
+        let label = action_label(&action).unwrap_or_default().to_string();
+        process_action(
+            self,
+            device,
+            queue,
+            action,
+            dir,
+            command_encoder_id_manager,
+            command_buffer_id_manager,
+        )
+        .map_err(|source| ContextError {
+            fn_ident,
+            source,
+            label,
+        })
+    }
+}
+
+fn process_action(
+    global: &wgc::global::Global,
+    device: wgc::id::DeviceId,
+    queue: wgc::id::QueueId,
+    action: trace::Action,
+    dir: &Path,
+    command_encoder_id_manager: &mut IdentityManager<wgc::id::markers::CommandEncoder>,
+    command_buffer_id_manager: &mut IdentityManager<wgc::id::markers::CommandBuffer>,
+) -> Result<(), ContextErrorSource> {
+    use wgc::device::trace::Action;
+    log::debug!("action {action:?}");
+    //TODO: find a way to force ID perishing without excessive `maintain()` calls.
+    match action {
+        Action::Init { .. } => {
+            panic!("Unexpected Action::Init: has to be the first action only")
+        }
+        Action::ConfigureSurface { .. } | Action::Present(_) | Action::DiscardSurfaceTexture(_) => {
+            panic!("Unexpected Surface action: winit feature is not enabled")
+        }
+        Action::CreateBuffer(id, desc) => {
+            let (_, error) = global.device_create_buffer(device, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::CreateComputePipeline { id, desc } => {
-                let (_, error) = self.device_create_compute_pipeline(device, &desc, Some(id));
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
+        }
+        Action::FreeBuffer(id) => {
+            global.buffer_destroy(id);
+        }
+        Action::DestroyBuffer(id) => {
+            global.buffer_drop(id);
+        }
+        Action::CreateTexture(id, desc) => {
+            let (_, error) = global.device_create_texture(device, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::DestroyComputePipeline(id) => {
-                self.compute_pipeline_drop(id);
+        }
+        Action::FreeTexture(id) => {
+            global.texture_destroy(id);
+        }
+        Action::DestroyTexture(id) => {
+            global.texture_drop(id);
+        }
+        Action::CreateTextureView {
+            id,
+            parent_id,
+            desc,
+        } => {
+            let (_, error) = global.texture_create_view(parent_id, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::CreateRenderPipeline { id, desc } => {
-                let (_, error) = self.device_create_render_pipeline(device, &desc, Some(id));
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
+        }
+        Action::DestroyTextureView(id) => {
+            global.texture_view_drop(id)?;
+        }
+        Action::CreateExternalTexture { id, desc, planes } => {
+            let (_, error) =
+                global.device_create_external_texture(device, &desc, &planes, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::CreateMeshPipeline { id, desc } => {
-                let (_, error) = self.device_create_mesh_pipeline(device, &desc, Some(id));
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
+        }
+        Action::FreeExternalTexture(id) => {
+            global.external_texture_destroy(id);
+        }
+        Action::DestroyExternalTexture(id) => {
+            global.external_texture_drop(id);
+        }
+        Action::CreateSampler(id, desc) => {
+            let (_, error) = global.device_create_sampler(device, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::DestroyRenderPipeline(id) => {
-                self.render_pipeline_drop(id);
+        }
+        Action::DestroySampler(id) => {
+            global.sampler_drop(id);
+        }
+        Action::GetSurfaceTexture { id, parent_id } => {
+            global
+                .surface_get_current_texture(parent_id, Some(id))
+                .unwrap()
+                .texture
+                .unwrap();
+        }
+        Action::CreateBindGroupLayout(id, desc) => {
+            let (_, error) = global.device_create_bind_group_layout(device, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::CreatePipelineCache { id, desc } => {
-                let _ = unsafe { self.device_create_pipeline_cache(device, &desc, Some(id)) };
+        }
+        Action::DestroyBindGroupLayout(id) => {
+            global.bind_group_layout_drop(id);
+        }
+        Action::CreatePipelineLayout(id, desc) => {
+            let (_, error) = global.device_create_pipeline_layout(device, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::DestroyPipelineCache(id) => {
-                self.pipeline_cache_drop(id);
+        }
+        Action::DestroyPipelineLayout(id) => {
+            global.pipeline_layout_drop(id);
+        }
+        Action::CreateBindGroup(id, desc) => {
+            let (_, error) = global.device_create_bind_group(device, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::CreateRenderBundle { id, desc, base } => {
-                let bundle =
-                    wgc::command::RenderBundleEncoder::new(&desc, device, Some(base)).unwrap();
-                let (_, error) = self.render_bundle_encoder_finish(
-                    bundle,
-                    &wgt::RenderBundleDescriptor { label: desc.label },
-                    Some(id),
-                );
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
+        }
+        Action::DestroyBindGroup(id) => {
+            global.bind_group_drop(id);
+        }
+        Action::CreateShaderModule { id, desc, data } => {
+            log::debug!("Creating shader from {data}");
+            let code = fs::read_to_string(dir.join(&data)).unwrap();
+            let source = if data.ends_with(".wgsl") {
+                wgc::pipeline::ShaderModuleSource::Wgsl(Cow::Owned(code))
+            } else if data.ends_with(".ron") {
+                let module = ron::de::from_str(&code).unwrap();
+                wgc::pipeline::ShaderModuleSource::Naga(module)
+            } else {
+                panic!("Unknown shader {data}");
+            };
+            let (_, error) = global.device_create_shader_module(device, &desc, source, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::DestroyRenderBundle(id) => {
-                self.render_bundle_drop(id);
+        }
+        Action::DestroyShaderModule(id) => {
+            global.shader_module_drop(id);
+        }
+        Action::CreateComputePipeline { id, desc } => {
+            let (_, error) = global.device_create_compute_pipeline(device, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::CreateQuerySet { id, desc } => {
-                let (_, error) = self.device_create_query_set(device, &desc, Some(id));
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
+        }
+        Action::DestroyComputePipeline(id) => {
+            global.compute_pipeline_drop(id);
+        }
+        Action::CreateRenderPipeline { id, desc } => {
+            let (_, error) = global.device_create_render_pipeline(device, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::DestroyQuerySet(id) => {
-                self.query_set_drop(id);
+        }
+        Action::CreateMeshPipeline { id, desc } => {
+            let (_, error) = global.device_create_mesh_pipeline(device, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::WriteBuffer {
-                id,
-                data,
-                range,
-                queued,
-            } => {
-                let bin = std::fs::read(dir.join(data)).unwrap();
-                let size = (range.end - range.start) as usize;
-                if queued {
-                    self.queue_write_buffer(queue, id, range.start, &bin)
-                        .unwrap();
-                } else {
-                    self.device_set_buffer_data(id, range.start, &bin[..size])
-                        .unwrap();
-                }
+        }
+        Action::DestroyRenderPipeline(id) => {
+            global.render_pipeline_drop(id);
+        }
+        Action::CreatePipelineCache { id, desc } => {
+            let _ = unsafe { global.device_create_pipeline_cache(device, &desc, Some(id)) };
+        }
+        Action::DestroyPipelineCache(id) => {
+            global.pipeline_cache_drop(id);
+        }
+        Action::CreateRenderBundle { id, desc, base } => {
+            let bundle = wgc::command::RenderBundleEncoder::new(&desc, device, Some(base))?;
+            let (_, error) = global.render_bundle_encoder_finish(
+                bundle,
+                &wgt::RenderBundleDescriptor { label: desc.label },
+                Some(id),
+            );
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::WriteTexture {
-                to,
-                data,
-                layout,
-                size,
-            } => {
-                let bin = std::fs::read(dir.join(data)).unwrap();
-                self.queue_write_texture(queue, &to, &bin, &layout, &size)
-                    .unwrap();
+        }
+        Action::DestroyRenderBundle(id) => {
+            global.render_bundle_drop(id);
+        }
+        Action::CreateQuerySet { id, desc } => {
+            let (_, error) = global.device_create_query_set(device, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::Submit(_index, ref commands) if commands.is_empty() => {
-                self.queue_submit(queue, &[]).unwrap();
+        }
+        Action::DestroyQuerySet(id) => {
+            global.query_set_drop(id);
+        }
+        Action::CreateHeap { id, desc } => {
+            let (_, error) = global.device_create_heap(device, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::Submit(_index, commands) => {
-                let (encoder, error) = self.device_create_command_encoder(
-                    device,
-                    &wgt::CommandEncoderDescriptor { label: None },
-                    Some(command_encoder_id_manager.process()),
-                );
-                if let Some(e) = error {
-                    panic!("{e}");
-                }
-                let cmdbuf = self.encode_commands(encoder, commands, command_buffer_id_manager);
-                self.queue_submit(queue, &[cmdbuf]).unwrap();
+        }
+        Action::DestroyHeap(id) => {
+            global.heap_drop(id);
+        }
+        Action::CreatePlacedBuffer {
+            id,
+            heap,
+            offset,
+            desc,
+        } => {
+            let (_, error) = global.heap_create_buffer(heap, offset, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::CreateBlas { id, desc, sizes } => {
-                self.device_create_blas(device, &desc, sizes, Some(id));
+        }
+        Action::CreatePlacedTexture {
+            id,
+            heap,
+            offset,
+            desc,
+        } => {
+            let (_, error) = global.heap_create_texture(heap, offset, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::DestroyBlas(id) => {
-                self.blas_drop(id);
+        }
+        Action::WriteBuffer {
+            id,
+            data,
+            range,
+            queued,
+        } => {
+            let bin = std::fs::read(dir.join(data)).unwrap();
+            let size = (range.end - range.start) as usize;
+            if queued {
+                global.queue_write_buffer(queue, id, range.start, &bin)?;
+            } else {
+                global.device_set_buffer_data(id, range.start, &bin[..size])?;
             }
-            Action::CreateTlas { id, desc } => {
-                self.device_create_tlas(device, &desc, Some(id));
+        }
+        Action::WriteTexture {
+            to,
+            data,
+            layout,
+            size,
+        } => {
+            let bin = std::fs::read(dir.join(data)).unwrap();
+            global.queue_write_texture(queue, &to, &bin, &layout, &size)?;
+        }
+        Action::UpdateSparseResidency { buffers, textures } => {
+            global.queue_update_sparse_residency(queue, &buffers, &textures)?;
+        }
+        Action::Submit(_index, ref commands) if commands.is_empty() => {
+            global.queue_submit(queue, &[]).map_err(|(_, e)| e)?;
+        }
+        Action::Submit(_index, commands) => {
+            let (encoder, error) = global.device_create_command_encoder(
+                device,
+                &wgt::CommandEncoderDescriptor { label: None },
+                Some(command_encoder_id_manager.process()),
+            );
+            if let Some(e) = error {
+                return Err(e.into());
+            }
+            let cmdbuf = global.encode_commands(encoder, commands, command_buffer_id_manager)?;
+            global.queue_submit(queue, &[cmdbuf]).map_err(|(_, e)| e)?;
+        }
+        Action::CreateBlas { id, desc, sizes } => {
+            let (_, _, error) = global.device_create_blas(device, &desc, sizes, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
-            Action::DestroyTlas(id) => {
-                self.tlas_drop(id);
+        }
+        Action::DestroyBlas(id) => {
+            global.blas_drop(id);
+        }
+        Action::CreateTlas { id, desc } => {
+            let (_, error) = global.device_create_tlas(device, &desc, Some(id));
+            if let Some(e) = error {
+                return Err(e.into());
             }
         }
+        Action::DestroyTlas(id) => {
+            global.tlas_drop(id);
+        }
     }
+    Ok(())
 }
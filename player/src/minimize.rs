@@ -0,0 +1,572 @@
+//! Reducing a trace to a smaller one that fails in the same way.
+//!
+//! The reduction is a delta debugging search over the actions of the trace:
+//! chunks of actions are removed as long as replaying the rest on the noop
+//! backend still fails with the same error. When an action that creates an
+//! object is removed, every later action that uses the object is removed with
+//! it, so that the reduced trace never refers to objects it doesn't create.
+
+use wgc::{device::trace, id};
+
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+};
+
+use crate::headless;
+
+/// An object created by the actions of a trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Object {
+    Buffer(id::BufferId),
+    Texture(id::TextureId),
+    TextureView(id::TextureViewId),
+    ExternalTexture(id::ExternalTextureId),
+    Sampler(id::SamplerId),
+    BindGroupLayout(id::BindGroupLayoutId),
+    PipelineLayout(id::PipelineLayoutId),
+    BindGroup(id::BindGroupId),
+    ShaderModule(id::ShaderModuleId),
+    ComputePipeline(id::ComputePipelineId),
+    RenderPipeline(id::RenderPipelineId),
+    PipelineCache(id::PipelineCacheId),
+    RenderBundle(id::RenderBundleId),
+    QuerySet(id::QuerySetId),
+    Heap(id::HeapId),
+    Blas(id::BlasId),
+    Tlas(id::TlasId),
+}
+
+/// Returns the object `action` creates, if any.
+pub fn created(action: &trace::Action) -> Option<Object> {
+    use trace::Action as A;
+    Some(match *action {
+        A::CreateBuffer(id, _) | A::CreatePlacedBuffer { id, .. } => Object::Buffer(id),
+        A::CreateTexture(id, _)
+        | A::CreatePlacedTexture { id, .. }
+        | A::GetSurfaceTexture { id, .. } => Object::Texture(id),
+        A::CreateTextureView { id, .. } => Object::TextureView(id),
+        A::CreateExternalTexture { id, .. } => Object::ExternalTexture(id),
+        A::CreateSampler(id, _) => Object::Sampler(id),
+        A::CreateBindGroupLayout(id, _) => Object::BindGroupLayout(id),
+        A::CreatePipelineLayout(id, _) => Object::PipelineLayout(id),
+        A::CreateBindGroup(id, _) => Object::BindGroup(id),
+        A::CreateShaderModule { id, .. } => Object::ShaderModule(id),
+        A::CreateComputePipeline { id, .. } => Object::ComputePipeline(id),
+        A::CreateRenderPipeline { id, .. } | A::CreateMeshPipeline { id, .. } => {
+            Object::RenderPipeline(id)
+        }
+        A::CreatePipelineCache { id, .. } => Object::PipelineCache(id),
+        A::CreateRenderBundle { id, .. } => Object::RenderBundle(id),
+        A::CreateQuerySet { id, .. } => Object::QuerySet(id),
+        A::CreateHeap { id, .. } => Object::Heap(id),
+        A::CreateBlas { id, .. } => Object::Blas(id),
+        A::CreateTlas { id, .. } => Object::Tlas(id),
+        _ => return None,
+    })
+}
+
+/// Calls `f` with every object `action` uses, apart from the one it creates.
+pub fn for_each_use(action: &trace::Action, mut f: impl FnMut(Object)) {
+    use trace::Action as A;
+    match *action {
+        A::FreeBuffer(id) | A::DestroyBuffer(id) | A::WriteBuffer { id, .. } => {
+            f(Object::Buffer(id))
+        }
+        A::FreeTexture(id) | A::DestroyTexture(id) => f(Object::Texture(id)),
+        A::WriteTexture { ref to, .. } => f(Object::Texture(to.texture)),
+        A::UpdateSparseResidency {
+            ref buffers,
+            ref textures,
+        } => {
+            buffers
+                .iter()
+                .for_each(|binding| f(Object::Buffer(binding.buffer)));
+            textures
+                .iter()
+                .for_each(|binding| f(Object::Texture(binding.texture)));
+        }
+        A::CreateTextureView { parent_id, .. } => f(Object::Texture(parent_id)),
+        A::DestroyTextureView(id) => f(Object::TextureView(id)),
+        A::CreateExternalTexture { ref planes, .. } => {
+            planes.iter().for_each(|&id| f(Object::TextureView(id)))
+        }
+        A::FreeExternalTexture(id) | A::DestroyExternalTexture(id) => {
+            f(Object::ExternalTexture(id))
+        }
+        A::DestroySampler(id) => f(Object::Sampler(id)),
+        A::DestroyBindGroupLayout(id) => f(Object::BindGroupLayout(id)),
+        A::CreatePipelineLayout(_, ref desc) => desc
+            .bind_group_layouts
+            .iter()
+            .for_each(|&id| f(Object::BindGroupLayout(id))),
+        A::DestroyPipelineLayout(id) => f(Object::PipelineLayout(id)),
+        A::CreateBindGroup(_, ref desc) => {
+            use wgc::binding_model::BindingResource as R;
+            f(Object::BindGroupLayout(desc.layout));
+            for entry in desc.entries.iter() {
+                match entry.resource {
+                    R::Buffer(ref binding) => f(Object::Buffer(binding.buffer)),
+                    R::BufferArray(ref bindings) => bindings
+                        .iter()
+                        .for_each(|binding| f(Object::Buffer(binding.buffer))),
+                    R::Sampler(id) => f(Object::Sampler(id)),
+                    R::SamplerArray(ref ids) => ids.iter().for_each(|&id| f(Object::Sampler(id))),
+                    R::TextureView(id) => f(Object::TextureView(id)),
+                    R::TextureViewArray(ref ids) => {
+                        ids.iter().for_each(|&id| f(Object::TextureView(id)))
+                    }
+                    R::AccelerationStructure(id) => f(Object::Tlas(id)),
+                    R::ExternalTexture(id) => f(Object::ExternalTexture(id)),
+                }
+            }
+        }
+        A::DestroyBindGroup(id) => f(Object::BindGroup(id)),
+        A::DestroyShaderModule(id) => f(Object::ShaderModule(id)),
+        A::CreateComputePipeline { ref desc, .. } => {
+            if let Some(id) = desc.layout {
+                f(Object::PipelineLayout(id));
+            }
+            f(Object::ShaderModule(desc.stage.module));
+            if let Some(id) = desc.cache {
+                f(Object::PipelineCache(id));
+            }
+        }
+        A::DestroyComputePipeline(id) => f(Object::ComputePipeline(id)),
+        A::CreateRenderPipeline { ref desc, .. } => {
+            if let Some(id) = desc.layout {
+                f(Object::PipelineLayout(id));
+            }
+            f(Object::ShaderModule(desc.vertex.stage.module));
+            if let Some(ref fragment) = desc.fragment {
+                f(Object::ShaderModule(fragment.stage.module));
+            }
+            if let Some(id) = desc.cache {
+                f(Object::PipelineCache(id));
+            }
+        }
+        A::CreateMeshPipeline { ref desc, .. } => {
+            if let Some(id) = desc.layout {
+                f(Object::PipelineLayout(id));
+            }
+            if let Some(ref task) = desc.task {
+                f(Object::ShaderModule(task.stage.module));
+            }
+            f(Object::ShaderModule(desc.mesh.stage.module));
+            if let Some(ref fragment) = desc.fragment {
+                f(Object::ShaderModule(fragment.stage.module));
+            }
+            if let Some(id) = desc.cache {
+                f(Object::PipelineCache(id));
+            }
+        }
+        A::DestroyRenderPipeline(id) => f(Object::RenderPipeline(id)),
+        A::DestroyPipelineCache(id) => f(Object::PipelineCache(id)),
+        A::CreateRenderBundle { ref base, .. } => {
+            base.commands
+                .iter()
+                .for_each(|command| render_command_uses(command, &mut f));
+        }
+        A::DestroyRenderBundle(id) => f(Object::RenderBundle(id)),
+        A::DestroyQuerySet(id) => f(Object::QuerySet(id)),
+        A::DestroyHeap(id)
+        | A::CreatePlacedBuffer { heap: id, .. }
+        | A::CreatePlacedTexture { heap: id, .. } => f(Object::Heap(id)),
+        A::Submit(_, ref commands) => commands
+            .iter()
+            .for_each(|command| command_uses(command, &mut f)),
+        A::DestroyBlas(id) => f(Object::Blas(id)),
+        A::DestroyTlas(id) => f(Object::Tlas(id)),
+        A::Init { .. }
+        | A::ConfigureSurface(..)
+        | A::CreateBuffer(..)
+        | A::CreateTexture(..)
+        | A::CreateSampler(..)
+        | A::GetSurfaceTexture { .. }
+        | A::Present(_)
+        | A::DiscardSurfaceTexture(_)
+        | A::CreateBindGroupLayout(..)
+        | A::CreateShaderModule { .. }
+        | A::CreatePipelineCache { .. }
+        | A::CreateQuerySet { .. }
+        | A::CreateHeap { .. }
+        | A::CreateBlas { .. }
+        | A::CreateTlas { .. } => {}
+    }
+}
+
+fn command_uses(command: &trace::Command, f: &mut impl FnMut(Object)) {
+    use trace::Command as C;
+    match *command {
+        C::CopyBufferToBuffer { src, dst, .. } => {
+            f(Object::Buffer(src));
+            f(Object::Buffer(dst));
+        }
+        C::CopyBufferToTexture {
+            ref src, ref dst, ..
+        } => {
+            f(Object::Buffer(src.buffer));
+            f(Object::Texture(dst.texture));
+        }
+        C::CopyTextureToBuffer {
+            ref src, ref dst, ..
+        } => {
+            f(Object::Texture(src.texture));
+            f(Object::Buffer(dst.buffer));
+        }
+        C::CopyTextureToTexture {
+            ref src, ref dst, ..
+        } => {
+            f(Object::Texture(src.texture));
+            f(Object::Texture(dst.texture));
+        }
+        C::ClearBuffer { dst, .. } => f(Object::Buffer(dst)),
+        C::ClearTexture { dst, .. } => f(Object::Texture(dst)),
+        C::WriteTimestamp { query_set_id, .. } => f(Object::QuerySet(query_set_id)),
+        C::ResolveQuerySet {
+            query_set_id,
+            destination,
+            ..
+        } => {
+            f(Object::QuerySet(query_set_id));
+            f(Object::Buffer(destination));
+        }
+        C::PushDebugGroup(_) | C::PopDebugGroup | C::InsertDebugMarker(_) => {}
+        C::RunComputePass {
+            ref base,
+            ref timestamp_writes,
+        } => {
+            use wgc::command::ComputeCommand as Cc;
+            for command in base.commands.iter() {
+                match *command {
+                    Cc::SetBindGroup {
+                        bind_group_id: Some(id),
+                        ..
+                    } => f(Object::BindGroup(id)),
+                    Cc::SetPipeline(id) => f(Object::ComputePipeline(id)),
+                    Cc::DispatchIndirect { buffer_id, .. } => f(Object::Buffer(buffer_id)),
+                    Cc::WriteTimestamp { query_set_id, .. }
+                    | Cc::BeginPipelineStatisticsQuery { query_set_id, .. } => {
+                        f(Object::QuerySet(query_set_id))
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(ref writes) = *timestamp_writes {
+                f(Object::QuerySet(writes.query_set));
+            }
+        }
+        C::RunRenderPass {
+            ref base,
+            ref target_colors,
+            ref target_depth_stencil,
+            ref timestamp_writes,
+            occlusion_query_set_id,
+        } => {
+            base.commands
+                .iter()
+                .for_each(|command| render_command_uses(command, f));
+            for attachment in target_colors.iter().flatten() {
+                f(Object::TextureView(attachment.view));
+                if let Some(id) = attachment.resolve_target {
+                    f(Object::TextureView(id));
+                }
+            }
+            if let Some(ref attachment) = *target_depth_stencil {
+                f(Object::TextureView(attachment.view));
+            }
+            if let Some(ref writes) = *timestamp_writes {
+                f(Object::QuerySet(writes.query_set));
+            }
+            if let Some(id) = occlusion_query_set_id {
+                f(Object::QuerySet(id));
+            }
+        }
+        C::BuildAccelerationStructures { ref blas, ref tlas } => {
+            for entry in blas {
+                f(Object::Blas(entry.blas_id));
+                let wgc::ray_tracing::TraceBlasGeometries::TriangleGeometries(ref geometries) =
+                    entry.geometries;
+                for geometry in geometries {
+                    f(Object::Buffer(geometry.vertex_buffer));
+                    if let Some(id) = geometry.index_buffer {
+                        f(Object::Buffer(id));
+                    }
+                    if let Some(id) = geometry.transform_buffer {
+                        f(Object::Buffer(id));
+                    }
+                }
+            }
+            for package in tlas {
+                f(Object::Tlas(package.tlas_id));
+                for instance in package.instances.iter().flatten() {
+                    f(Object::Blas(instance.blas_id));
+                }
+            }
+        }
+        C::AliasingBarrier {
+            ref buffers,
+            ref textures,
+        } => {
+            buffers.iter().for_each(|&id| f(Object::Buffer(id)));
+            textures.iter().for_each(|&id| f(Object::Texture(id)));
+        }
+    }
+}
+
+fn render_command_uses(command: &wgc::command::RenderCommand, f: &mut impl FnMut(Object)) {
+    use wgc::command::RenderCommand as Rc;
+    match *command {
+        Rc::SetBindGroup {
+            bind_group_id: Some(id),
+            ..
+        } => f(Object::BindGroup(id)),
+        Rc::SetPipeline(id) => f(Object::RenderPipeline(id)),
+        Rc::SetIndexBuffer { buffer_id, .. }
+        | Rc::SetVertexBuffer { buffer_id, .. }
+        | Rc::DrawIndirect { buffer_id, .. } => f(Object::Buffer(buffer_id)),
+        Rc::MultiDrawIndirectCount {
+            buffer_id,
+            count_buffer_id,
+            ..
+        } => {
+            f(Object::Buffer(buffer_id));
+            f(Object::Buffer(count_buffer_id));
+        }
+        Rc::WriteTimestamp { query_set_id, .. }
+        | Rc::BeginPipelineStatisticsQuery { query_set_id, .. } => {
+            f(Object::QuerySet(query_set_id))
+        }
+        Rc::ExecuteBundle(id) => f(Object::RenderBundle(id)),
+        _ => {}
+    }
+}
+
+/// Replays `actions` on the noop backend, returning the error it fails with.
+///
+/// Errors are those raised by `wgpu_core`, formatted with [`crate::format_error`],
+/// and the messages of the panics raised while replaying. Surfaces are emulated
+/// as by [`headless::Replay`].
+///
+/// Panics are caught, but still reported by the panic hook.
+pub fn replay<'a>(
+    actions: impl IntoIterator<Item = trace::Action<'a>>,
+    dir: &Path,
+) -> Result<(), String> {
+    let mut actions = actions.into_iter().peekable();
+    let device_desc = match actions.next_if(|action| matches!(action, trace::Action::Init { .. })) {
+        Some(trace::Action::Init { desc, .. }) => desc,
+        _ => wgt::DeviceDescriptor::default(),
+    };
+
+    let mut replay = headless::Replay::new(&device_desc, dir)?;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        for action in actions {
+            replay
+                .process(action)
+                .map_err(|e| crate::format_error(&e))?;
+        }
+        replay.wait()
+    }));
+    // The objects may be left in an inconsistent state by a panic.
+    let dropped = panic::catch_unwind(AssertUnwindSafe(|| drop(replay)));
+    match result {
+        Ok(result) => result.and(dropped.map_err(panic_message)),
+        Err(payload) => Err(panic_message(payload)),
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
+/// Searches for the smallest subset of the actions of a trace that fails with
+/// a given error.
+pub struct Minimizer {
+    actions: Vec<trace::Action<'static>>,
+    /// The actions in RON, to make a copy of them for every replay.
+    sources: Vec<String>,
+    dir: PathBuf,
+    replays: usize,
+}
+
+impl Minimizer {
+    pub fn new(actions: Vec<trace::Action<'static>>, dir: PathBuf) -> Self {
+        let sources = actions
+            .iter()
+            .map(|action| ron::to_string(action).unwrap())
+            .collect();
+        Self {
+            actions,
+            sources,
+            dir,
+            replays: 0,
+        }
+    }
+
+    /// Replays the actions at `indices`, returning the error it fails with.
+    pub fn replay(&mut self, indices: &[usize]) -> Result<(), String> {
+        self.replays += 1;
+        let actions = indices
+            .iter()
+            .map(|&index| ron::from_str(&self.sources[index]).unwrap());
+        replay(actions, &self.dir)
+    }
+
+    fn reproduces(&mut self, indices: &[usize], error: &str) -> bool {
+        self.replay(indices)
+            .is_err_and(|message| message.contains(error))
+    }
+
+    /// Removes the actions at `removed` from `kept`, along with the actions
+    /// that use the objects they create. Both must be sorted.
+    ///
+    /// [`trace::Action::Init`] is never removed.
+    fn remove(&self, kept: &[usize], removed: &[usize]) -> Vec<usize> {
+        let mut dead = BTreeSet::new();
+        kept.iter()
+            .copied()
+            .filter(|&index| {
+                let action = &self.actions[index];
+                let mut remove = removed.binary_search(&index).is_ok()
+                    && !matches!(action, trace::Action::Init { .. });
+                for_each_use(action, |object| remove |= dead.contains(&object));
+                if remove {
+                    dead.extend(created(action));
+                }
+                !remove
+            })
+            .collect()
+    }
+
+    /// Returns the indices of the smallest subset of actions found that fails
+    /// with an error containing `error`.
+    ///
+    /// The actions are split into chunks, and the first chunk whose removal
+    /// still reproduces the error is removed. When no chunk can be removed,
+    /// the chunks are made smaller, down to single actions.
+    pub fn minimize(&mut self, error: &str) -> Vec<usize> {
+        // Silence the panics of the replays that fail.
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+
+        let mut kept = (0..self.actions.len()).collect::<Vec<_>>();
+        let mut chunk_count = 2;
+        while kept.len() > 1 {
+            let chunk_len = kept.len().div_ceil(chunk_count);
+            let reduced = kept.chunks(chunk_len).find_map(|chunk| {
+                let candidate = self.remove(&kept, chunk);
+                (candidate.len() < kept.len() && self.reproduces(&candidate, error))
+                    .then_some(candidate)
+            });
+            match reduced {
+                Some(candidate) => {
+                    kept = candidate;
+                    log::info!(
+                        "Reduced to {} actions after {} replays",
+                        kept.len(),
+                        self.replays
+                    );
+                    chunk_count = (chunk_count - 1).max(2);
+                }
+                None if chunk_len == 1 => break,
+                None => chunk_count = (chunk_count * 2).min(kept.len()),
+            }
+        }
+
+        panic::set_hook(hook);
+        kept
+    }
+
+    /// Writes the actions at `indices` as a trace in `output`, along with the
+    /// files they read.
+    pub fn write(&self, indices: &[usize], output: &Path, format: trace::Format) -> io::Result<()> {
+        fs::create_dir_all(output)?;
+        let mut trace = trace::Trace::new(output.to_path_buf(), format)?;
+        for &index in indices {
+            if let Some(data) = crate::data_file(&self.actions[index]) {
+                fs::copy(self.dir.join(data), output.join(data))?;
+            }
+            trace.add(ron::from_str(&self.sources[index]).unwrap());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A trace in which only the actions at [`FAILING`] are needed to fail:
+    /// the bind group is created with a buffer that can't be bound as a
+    /// uniform buffer.
+    const ACTIONS: &str = r#"[
+        CreateBuffer(Id(0, 1), (label: Some("unused"), size: 16, usage: "COPY_DST", mapped_at_creation: false)),
+        CreateBindGroupLayout(Id(0, 1), (label: None, entries: [
+            (binding: 0, visibility: "COMPUTE", ty: Buffer(ty: Uniform)),
+        ])),
+        CreateBuffer(Id(1, 1), (label: Some("storage"), size: 16, usage: "STORAGE", mapped_at_creation: false)),
+        CreateBuffer(Id(2, 1), (label: Some("uniform"), size: 16, usage: "UNIFORM", mapped_at_creation: false)),
+        DestroyBuffer(Id(0, 1)),
+        CreateBindGroup(Id(0, 1), (label: None, layout: Id(0, 1), entries: [
+            (binding: 0, resource: Buffer((buffer: Id(2, 1), offset: 0, size: None))),
+        ])),
+        CreateBindGroup(Id(1, 1), (label: Some("invalid"), layout: Id(0, 1), entries: [
+            (binding: 0, resource: Buffer((buffer: Id(1, 1), offset: 0, size: None))),
+        ])),
+        DestroyBindGroup(Id(0, 1)),
+        Submit(1, []),
+    ]"#;
+
+    const FAILING: [usize; 3] = [1, 2, 6];
+
+    fn minimizer() -> Minimizer {
+        let actions = ron::from_str(ACTIONS).unwrap();
+        Minimizer::new(actions, PathBuf::new())
+    }
+
+    #[test]
+    fn replay_fails_with_the_error() {
+        let mut minimizer = minimizer();
+        let error = minimizer.replay(&(0..9).collect::<Vec<_>>()).unwrap_err();
+        assert!(error.contains("'storage' label"), "{error}");
+        assert!(error.contains("UNIFORM"), "{error}");
+        assert_eq!(minimizer.replay(&FAILING), Err(error));
+        assert_eq!(minimizer.replay(&[0, 1, 2, 3, 5, 7, 8]), Ok(()));
+    }
+
+    #[test]
+    fn remove_also_removes_users() {
+        let minimizer = minimizer();
+        let all = (0..9).collect::<Vec<_>>();
+
+        // The destruction of the unused buffer goes with its creation.
+        assert_eq!(minimizer.remove(&all, &[0]), [1, 2, 3, 5, 6, 7, 8]);
+        // Without the layout, neither bind group can be created.
+        assert_eq!(minimizer.remove(&all, &[1]), [0, 2, 3, 4, 8]);
+        // Removing a user leaves what it uses.
+        assert_eq!(minimizer.remove(&all, &[6, 7]), [0, 1, 2, 3, 4, 5, 8]);
+    }
+
+    #[test]
+    fn minimize_keeps_the_failing_actions() {
+        let mut minimizer = minimizer();
+        let error = minimizer.replay(&(0..9).collect::<Vec<_>>()).unwrap_err();
+
+        let kept = minimizer.minimize(&error);
+        assert_eq!(kept, FAILING);
+        // The result is minimal: no action can be removed from it.
+        for &index in &kept {
+            let reduced = minimizer.remove(&kept, &[index]);
+            assert!(!minimizer.reproduces(&reduced, &error), "{reduced:?}");
+        }
+    }
+}
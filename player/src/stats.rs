@@ -0,0 +1,477 @@
+//! Statistics about the actions of a trace, and differences between traces.
+//!
+//! Traces are split into frames after each [`trace::Action::Present`]. A trace
+//! that never presents is a single frame.
+//!
+//! To compare the structure of two traces, each action is reduced to a
+//! signature that leaves out the IDs and data files, which differ between
+//! captures of the same scene, but keeps the labels and the work it does.
+
+use wgc::device::trace;
+
+use std::{collections::BTreeMap, ops::AddAssign};
+
+/// Counts of the work done by some actions of a trace.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+    pub actions: usize,
+    pub submissions: usize,
+    pub render_passes: usize,
+    pub compute_passes: usize,
+    /// Draw commands, including those of the render bundles executed.
+    pub draws: usize,
+    pub dispatches: usize,
+    /// Render, mesh and compute pipelines created.
+    pub pipelines: usize,
+    /// Bytes written to buffers by [`trace::Action::WriteBuffer`].
+    pub buffer_bytes_written: u64,
+}
+
+impl AddAssign<&Counts> for Counts {
+    fn add_assign(&mut self, other: &Counts) {
+        self.actions += other.actions;
+        self.submissions += other.submissions;
+        self.render_passes += other.render_passes;
+        self.compute_passes += other.compute_passes;
+        self.draws += other.draws;
+        self.dispatches += other.dispatches;
+        self.pipelines += other.pipelines;
+        self.buffer_bytes_written += other.buffer_bytes_written;
+    }
+}
+
+/// A frame of a trace.
+#[derive(Debug, Default)]
+pub struct Frame {
+    pub counts: Counts,
+    /// The signature of each action, as compared by [`diff`].
+    pub signatures: Vec<String>,
+}
+
+/// Statistics about a trace, gathered one action at a time.
+#[derive(Debug, Default)]
+pub struct Stats {
+    /// The number of objects created, by the kind of action creating them.
+    pub created: BTreeMap<&'static str, usize>,
+    pub frames: Vec<Frame>,
+    /// Whether the last frame has been presented, so that the next action
+    /// starts a new one.
+    presented: bool,
+    /// The number of draws recorded in each render bundle.
+    bundle_draws: BTreeMap<wgc::id::RenderBundleId, usize>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Account for `action`, the next action of the trace.
+    pub fn add(&mut self, action: &trace::Action) {
+        use trace::Action as A;
+
+        if self.frames.is_empty() || self.presented {
+            self.frames.push(Frame::default());
+            self.presented = false;
+        }
+        if crate::minimize::created(action).is_some() {
+            *self.created.entry(crate::action_name(action)).or_default() += 1;
+        }
+
+        let mut counts = Counts {
+            actions: 1,
+            ..Counts::default()
+        };
+        match *action {
+            A::CreateComputePipeline { .. }
+            | A::CreateRenderPipeline { .. }
+            | A::CreateMeshPipeline { .. } => counts.pipelines += 1,
+            A::CreateRenderBundle { id, ref base, .. } => {
+                let draws = base.commands.iter().filter(|c| is_draw(c)).count();
+                self.bundle_draws.insert(id, draws);
+            }
+            A::WriteBuffer { ref range, .. } => {
+                counts.buffer_bytes_written += range.end - range.start;
+            }
+            A::Submit(_, ref commands) => {
+                counts.submissions += 1;
+                for command in commands {
+                    match *command {
+                        trace::Command::RunComputePass { ref base, .. } => {
+                            counts.compute_passes += 1;
+                            counts.dispatches += dispatches(&base.commands);
+                        }
+                        trace::Command::RunRenderPass { ref base, .. } => {
+                            counts.render_passes += 1;
+                            counts.draws += self.draws(&base.commands);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            A::Present(_) => self.presented = true,
+            _ => {}
+        }
+
+        let signature = self.signature(action);
+        let frame = self.frames.last_mut().unwrap();
+        frame.counts += &counts;
+        frame.signatures.push(signature);
+    }
+
+    /// The counts of the whole trace.
+    pub fn total(&self) -> Counts {
+        let mut total = Counts::default();
+        for frame in &self.frames {
+            total += &frame.counts;
+        }
+        total
+    }
+
+    fn draws(&self, commands: &[wgc::command::RenderCommand]) -> usize {
+        commands
+            .iter()
+            .map(|command| match *command {
+                wgc::command::RenderCommand::ExecuteBundle(id) => {
+                    self.bundle_draws.get(&id).copied().unwrap_or(0)
+                }
+                ref command => usize::from(is_draw(command)),
+            })
+            .sum()
+    }
+
+    /// Returns the signature of `action`, which is what is compared when
+    /// diffing traces.
+    fn signature(&self, action: &trace::Action) -> String {
+        use trace::Action as A;
+
+        let mut signature = crate::action_name(action).to_string();
+        if let Some(label) = crate::action_label(action) {
+            signature += &format!(" {label:?}");
+        }
+        match *action {
+            A::CreateBuffer(_, ref desc) => {
+                signature += &format!(" size {} {:?}", desc.size, desc.usage);
+            }
+            A::CreateTexture(_, ref desc) => {
+                let size = desc.size;
+                signature += &format!(
+                    " {}x{}x{} {:?} {:?}",
+                    size.width, size.height, size.depth_or_array_layers, desc.format, desc.usage
+                );
+            }
+            A::WriteBuffer { ref range, .. } => {
+                signature += &format!(" {} bytes", range.end - range.start);
+            }
+            A::WriteTexture { size, .. } => {
+                signature += &format!(
+                    " {}x{}x{}",
+                    size.width, size.height, size.depth_or_array_layers
+                );
+            }
+            A::Submit(_, ref commands) => {
+                let commands = commands
+                    .iter()
+                    .map(|command| self.command_signature(command))
+                    .collect::<Vec<_>>();
+                signature += &format!(" [{}]", commands.join(", "));
+            }
+            _ => {}
+        }
+        signature
+    }
+
+    fn command_signature(&self, command: &trace::Command) -> String {
+        use trace::Command as C;
+        let (name, label) = match *command {
+            C::CopyBufferToBuffer { .. } => ("CopyBufferToBuffer", None),
+            C::CopyBufferToTexture { .. } => ("CopyBufferToTexture", None),
+            C::CopyTextureToBuffer { .. } => ("CopyTextureToBuffer", None),
+            C::CopyTextureToTexture { .. } => ("CopyTextureToTexture", None),
+            C::ClearBuffer { .. } => ("ClearBuffer", None),
+            C::ClearTexture { .. } => ("ClearTexture", None),
+            C::WriteTimestamp { .. } => ("WriteTimestamp", None),
+            C::ResolveQuerySet { .. } => ("ResolveQuerySet", None),
+            C::PushDebugGroup(ref label) => ("PushDebugGroup", Some(label)),
+            C::PopDebugGroup => ("PopDebugGroup", None),
+            C::InsertDebugMarker(ref label) => ("InsertDebugMarker", Some(label)),
+            C::RunComputePass { ref base, .. } => {
+                let label = base.label.as_ref();
+                let label = label.map_or(String::new(), |label| format!(" {label:?}"));
+                let dispatches = dispatches(&base.commands);
+                return format!("RunComputePass{label} ({dispatches} dispatches)");
+            }
+            C::RunRenderPass { ref base, .. } => {
+                let label = base.label.as_ref();
+                let label = label.map_or(String::new(), |label| format!(" {label:?}"));
+                let draws = self.draws(&base.commands);
+                return format!("RunRenderPass{label} ({draws} draws)");
+            }
+            C::BuildAccelerationStructures { .. } => ("BuildAccelerationStructures", None),
+            C::AliasingBarrier { .. } => ("AliasingBarrier", None),
+        };
+        match label {
+            Some(label) => format!("{name} {label:?}"),
+            None => name.to_string(),
+        }
+    }
+}
+
+fn is_draw(command: &wgc::command::RenderCommand) -> bool {
+    use wgc::command::RenderCommand as Rc;
+    matches!(
+        *command,
+        Rc::Draw { .. }
+            | Rc::DrawIndexed { .. }
+            | Rc::DrawMeshTasks { .. }
+            | Rc::DrawIndirect { .. }
+            | Rc::MultiDrawIndirectCount { .. }
+    )
+}
+
+fn dispatches(commands: &[wgc::command::ComputeCommand]) -> usize {
+    use wgc::command::ComputeCommand as Cc;
+    commands
+        .iter()
+        .filter(|command| matches!(**command, Cc::Dispatch(_) | Cc::DispatchIndirect { .. }))
+        .count()
+}
+
+/// A difference between two sequences of signatures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change<'a> {
+    /// The signature at `index` of the first sequence isn't in the second.
+    Removed { index: usize, signature: &'a str },
+    /// The signature at `index` of the second sequence isn't in the first.
+    Added { index: usize, signature: &'a str },
+}
+
+/// The largest number of pairs of signatures [`diff`] compares, beyond which
+/// the differing parts of the sequences are reported as a whole.
+const MAX_DIFF_PAIRS: usize = 1 << 22;
+
+/// Returns the changes turning `old` into `new`, based on their longest
+/// common subsequence.
+pub fn diff<'a>(old: &'a [String], new: &'a [String]) -> Vec<Change<'a>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old = &old[prefix..old.len() - suffix];
+    let new = &new[prefix..new.len() - suffix];
+    let removed = |i: usize| Change::Removed {
+        index: prefix + i,
+        signature: &old[i],
+    };
+    let added = |j: usize| Change::Added {
+        index: prefix + j,
+        signature: &new[j],
+    };
+
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_PAIRS {
+        return (0..old.len())
+            .map(removed)
+            .chain((0..new.len()).map(added))
+            .collect();
+    }
+
+    // `common[i * width + j]` is the length of the longest common subsequence
+    // of `old[i..]` and `new[j..]`.
+    let width = new.len() + 1;
+    let mut common = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i * width + j] = if old[i] == new[j] {
+                common[(i + 1) * width + j + 1] + 1
+            } else {
+                common[(i + 1) * width + j].max(common[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if j == new.len()
+            || (i < old.len() && common[(i + 1) * width + j] >= common[i * width + j + 1])
+        {
+            changes.push(removed(i));
+            i += 1;
+        } else {
+            changes.push(added(j));
+            j += 1;
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pass(commands: &str) -> String {
+        format!(
+            "(commands: [{commands}], dynamic_offsets: [], string_data: [], push_constant_data: [])"
+        )
+    }
+
+    /// Returns the statistics of a trace of two frames, in which `draws` are
+    /// recorded in the render pass of the second frame.
+    fn stats(draws: &str) -> Stats {
+        let draw = "Draw(vertex_count: 3, instance_count: 1, first_vertex: 0, first_instance: 0)";
+        let actions = format!(
+            r#"[
+            CreateBuffer(Id(0, 1), (label: Some("vertices"), size: 64, usage: "VERTEX", mapped_at_creation: false)),
+            WriteBuffer(id: Id(0, 1), data: "data1.bin", range: (start: 0, end: 64), queued: true),
+            CreateComputePipeline(id: Id(0, 1), desc: (label: None, layout: None, stage: (
+                module: Id(0, 1), entry_point: None, constants: {{}},
+                zero_initialize_workgroup_memory: true, vertex_pulling_transform: false,
+            ))),
+            CreateRenderBundle(
+                id: Id(0, 1),
+                desc: (label: None, color_formats: [], depth_stencil: None, sample_count: 1, multiview: None),
+                base: {bundle},
+            ),
+            Submit(1, [
+                RunComputePass(base: {compute}),
+                RunRenderPass(base: {render}, target_colors: [], target_depth_stencil: None),
+            ]),
+            Present(Id(0, 1)),
+            WriteBuffer(id: Id(0, 1), data: "data2.bin", range: (start: 0, end: 16), queued: true),
+            Submit(2, [RunRenderPass(base: {second}, target_colors: [], target_depth_stencil: None)]),
+        ]"#,
+            bundle = pass(&[draw, draw].join(", ")),
+            compute = pass("Dispatch((4, 1, 1)), Dispatch((8, 1, 1))"),
+            render = pass(&format!("{draw}, ExecuteBundle(Id(0, 1))")),
+            second = pass(draws),
+        );
+        let actions: Vec<trace::Action> = ron::from_str(&actions).unwrap();
+        let mut stats = Stats::new();
+        for action in &actions {
+            stats.add(action);
+        }
+        stats
+    }
+
+    #[test]
+    fn counts() {
+        let stats = stats("ExecuteBundle(Id(0, 1))");
+        let created = stats.created.clone().into_iter().collect::<Vec<_>>();
+        assert_eq!(
+            created,
+            [
+                ("CreateBuffer", 1),
+                ("CreateComputePipeline", 1),
+                ("CreateRenderBundle", 1),
+            ]
+        );
+
+        assert_eq!(stats.frames.len(), 2);
+        assert_eq!(
+            stats.frames[0].counts,
+            Counts {
+                actions: 6,
+                submissions: 1,
+                render_passes: 1,
+                compute_passes: 1,
+                draws: 3,
+                dispatches: 2,
+                pipelines: 1,
+                buffer_bytes_written: 64,
+            }
+        );
+        assert_eq!(
+            stats.frames[1].counts,
+            Counts {
+                actions: 2,
+                submissions: 1,
+                render_passes: 1,
+                draws: 2,
+                buffer_bytes_written: 16,
+                ..Counts::default()
+            }
+        );
+        let total = stats.total();
+        assert_eq!((total.actions, total.draws), (8, 5));
+    }
+
+    #[test]
+    fn signatures() {
+        let stats = stats("ExecuteBundle(Id(0, 1))");
+        assert_eq!(
+            stats.frames[0].signatures[4],
+            "Submit [RunComputePass (2 dispatches), RunRenderPass (3 draws)]"
+        );
+        assert_eq!(
+            stats.frames[1].signatures,
+            ["WriteBuffer 16 bytes", "Submit [RunRenderPass (2 draws)]"]
+        );
+    }
+
+    #[test]
+    fn diff_traces() {
+        let a = stats("ExecuteBundle(Id(0, 1))");
+        let b = stats("SetPipeline(Id(0, 1))");
+        assert!(diff(&a.frames[0].signatures, &b.frames[0].signatures).is_empty());
+        assert_eq!(
+            diff(&a.frames[1].signatures, &b.frames[1].signatures),
+            [
+                Change::Removed {
+                    index: 1,
+                    signature: "Submit [RunRenderPass (2 draws)]",
+                },
+                Change::Added {
+                    index: 1,
+                    signature: "Submit [RunRenderPass (0 draws)]",
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_sequences() {
+        let strings = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let old = strings(&["a", "b", "c"]);
+        let new = strings(&["a", "x", "c", "d"]);
+        assert_eq!(
+            diff(&old, &new),
+            [
+                Change::Removed {
+                    index: 1,
+                    signature: "b",
+                },
+                Change::Added {
+                    index: 1,
+                    signature: "x",
+                },
+                Change::Added {
+                    index: 3,
+                    signature: "d",
+                },
+            ]
+        );
+        assert_eq!(
+            diff(&new, &old),
+            [
+                Change::Removed {
+                    index: 1,
+                    signature: "x",
+                },
+                Change::Added {
+                    index: 1,
+                    signature: "b",
+                },
+                Change::Removed {
+                    index: 3,
+                    signature: "d",
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Tests of `play --check`.
+
+use std::fs;
+
+use crate::util::{run, stdout, temp_dir};
+
+const PLAY: &str = env!("CARGO_BIN_EXE_play");
+
+/// Returns a trace with a compute pass of `compute_commands`, and a render
+/// pass binding the buffer `Id(0, 1)`, created with `usage`.
+fn trace(usage: &str, compute_commands: &str) -> String {
+    let pass = |commands| {
+        format!(
+            "(commands: [{commands}], dynamic_offsets: [], string_data: [], push_constant_data: [])"
+        )
+    };
+    let compute = pass(compute_commands);
+    let render = pass("SetVertexBuffer(slot: 0, buffer_id: Id(0, 1), offset: 0, size: None)");
+    format!(
+        r#"[
+        CreateBuffer(Id(0, 1), (label: Some("buffer"), size: 16, usage: "{usage}", mapped_at_creation: false)),
+        CreateTexture(Id(0, 1), (
+            label: None,
+            size: (width: 4, height: 4),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: r#2d,
+            format: "rgba8unorm",
+            usage: "RENDER_ATTACHMENT",
+            view_formats: [],
+        )),
+        CreateTextureView(id: Id(0, 1), parent_id: Id(0, 1), desc: ()),
+        Submit(1, [RunComputePass(base: {compute})]),
+        Submit(2, [RunRenderPass(
+            base: {render},
+            target_colors: [Some((view: Id(0, 1), resolve_target: None, load_op: load, store_op: store))],
+            target_depth_stencil: None,
+        )]),
+    ]"#
+    )
+}
+
+#[test]
+fn check_valid_trace() {
+    let dir = temp_dir("check-valid");
+    let path = dir.join("trace.ron");
+    fs::write(&path, trace("VERTEX", "")).unwrap();
+
+    let output = stdout(run(PLAY, &[&"--check", &path]));
+    assert_eq!(output, "No validation errors\n");
+}
+
+#[test]
+fn check_invalid_ids() {
+    let dir = temp_dir("check-invalid");
+    let path = dir.join("trace.ron");
+    // The buffer fails to be created, and the passes refer to an invalid ID.
+    let dispatch = "DispatchIndirect(buffer_id: Id(0, 1), offset: 0)";
+    fs::write(&path, trace("MAP_READ | STORAGE", dispatch)).unwrap();
+
+    let output = run(PLAY, &[&"--check", &path]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{stdout}\n{stderr}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+    assert!(stderr.contains("Found 3 validation errors"), "{stderr}");
+
+    let errors = stdout
+        .lines()
+        .filter(|line| line.starts_with("Action"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        [
+            "Action 0: In CreateBuffer, label = 'buffer'",
+            "Action 3: In Submit",
+            "Action 4: In Submit",
+        ]
+    );
+    assert!(stdout.contains("In a dispatch command"), "{stdout}");
+    assert!(
+        stdout.contains("In a set_vertex_buffer command"),
+        "{stdout}"
+    );
+    assert_eq!(
+        stdout
+            .matches("Buffer with 'buffer' label is invalid")
+            .count(),
+        2,
+        "{stdout}"
+    );
+}
@@ -0,0 +1,118 @@
+//! Tests of the `compare` tool.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::util::{run, stdout, temp_dir};
+
+const COMPARE: &str = env!("CARGO_BIN_EXE_compare");
+
+/// Writes a trace of two frames, with `draws` draws in the first one, and
+/// `extra` actions at the end of the second one.
+fn write_trace(dir: &Path, name: &str, draws: usize, extra: &str) -> PathBuf {
+    let draw = "Draw(vertex_count: 3, instance_count: 1, first_vertex: 0, first_instance: 0)";
+    let draws = vec![draw; draws].join(", ");
+    let trace = format!(
+        r#"[
+        CreateBuffer(Id(0, 1), (label: Some("a"), size: 16, usage: "COPY_DST", mapped_at_creation: false)),
+        Submit(1, [RunRenderPass(
+            base: (commands: [{draws}], dynamic_offsets: [], string_data: [], push_constant_data: []),
+            target_colors: [],
+            target_depth_stencil: None,
+        )]),
+        Present(Id(0, 1)),
+        Submit(2, []),
+        {extra}
+    ]"#
+    );
+    let path = dir.join(name);
+    fs::write(&path, trace).unwrap();
+    path
+}
+
+/// Returns the values of the row of `output` named `name`.
+fn row<'a>(output: &'a str, name: &str) -> Vec<&'a str> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(name)?.strip_prefix(' '))
+        .unwrap_or_else(|| panic!("No row {name:?} in:\n{output}"))
+        .split_whitespace()
+        .collect()
+}
+
+#[test]
+fn statistics() {
+    let dir = temp_dir("compare-statistics");
+    let a = write_trace(&dir, "a.ron", 2, "");
+
+    let output = stdout(run(COMPARE, &[&a]));
+    assert_eq!(row(&output, "CreateBuffer"), ["1"]);
+    assert_eq!(row(&output, "frames"), ["2"]);
+    assert_eq!(row(&output, "actions"), ["4"]);
+    assert_eq!(row(&output, "submissions"), ["2"]);
+    assert_eq!(row(&output, "render passes"), ["1"]);
+    assert_eq!(row(&output, "draws"), ["2"]);
+    // The frames, with their actions, draws, dispatches, pipelines and bytes.
+    assert_eq!(row(&output, "0"), ["3", "2", "0", "0", "0"]);
+    assert_eq!(row(&output, "1"), ["1", "0", "0", "0", "0"]);
+    assert!(!output.contains("Differences"), "{output}");
+}
+
+#[test]
+fn differences() {
+    let dir = temp_dir("compare-differences");
+    let extra = r#"CreateBuffer(Id(1, 1), (label: Some("b"), size: 16, usage: "COPY_DST", mapped_at_creation: false)),"#;
+    let a = write_trace(&dir, "a.ron", 1, "");
+    let b = write_trace(&dir, "b.ron", 2, extra);
+
+    let output = stdout(run(COMPARE, &[&a, &b]));
+    assert_eq!(row(&output, "CreateBuffer"), ["1", "2", "(+1)"]);
+    assert_eq!(row(&output, "frames"), ["2", "2"]);
+    assert_eq!(row(&output, "actions"), ["4", "5", "(+1)"]);
+    assert_eq!(row(&output, "submissions"), ["2", "2"]);
+    assert_eq!(row(&output, "draws"), ["1", "2", "(+1)"]);
+    assert_eq!(row(&output, "0"), ["3", "1", "->", "2", "0", "0", "0"]);
+    assert_eq!(row(&output, "1"), ["1", "->", "2", "0", "0", "0", "0"]);
+
+    let differences = output.split_once("Differences\n").unwrap().1;
+    let differences = differences.lines().collect::<Vec<_>>();
+    assert_eq!(differences.len(), 5, "{output}");
+    assert_eq!(differences[0], "Frame 0:");
+    assert_eq!(
+        differences[1],
+        "  - A      1: Submit [RunRenderPass (1 draws)]"
+    );
+    assert_eq!(
+        differences[2],
+        "  + B      1: Submit [RunRenderPass (2 draws)]"
+    );
+    assert_eq!(differences[3], "Frame 1:");
+    assert!(
+        differences[4].starts_with(r#"  + B      1: CreateBuffer "b" size 16"#),
+        "{output}"
+    );
+
+    let output = stdout(run(COMPARE, &[&"--no-diff", &a, &b]));
+    assert!(!output.contains("Differences"), "{output}");
+}
+
+#[test]
+fn same_structure() {
+    let dir = temp_dir("compare-same-structure");
+    let a = write_trace(&dir, "a.ron", 1, "");
+    let b = write_trace(&dir, "b.ron", 1, "");
+    // IDs and data files are not compared.
+    let trace = fs::read_to_string(&b)
+        .unwrap()
+        .replace("Id(0, 1)", "Id(3, 2)");
+    fs::write(&b, trace).unwrap();
+
+    let output = stdout(run(COMPARE, &[&a, &b]));
+    assert_eq!(row(&output, "draws"), ["1", "1"]);
+    assert!(
+        output.ends_with("Differences\n  The traces have the same structure\n"),
+        "{output}"
+    );
+}
@@ -0,0 +1,127 @@
+//! Tests of the RON and binary trace formats, and the `convert` tool.
+
+use std::{fs, io, path::Path};
+
+use wgc::device::trace;
+
+use crate::util::{record, run, stdout, temp_dir};
+
+const CONVERT: &str = env!("CARGO_BIN_EXE_convert");
+const PLAY: &str = env!("CARGO_BIN_EXE_play");
+
+const DATA: [u8; 16] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+
+/// Writes [`DATA`] to a buffer and copies it to another, returning the ID of
+/// the latter.
+fn copy_buffer(
+    global: &wgc::global::Global,
+    device: wgc::id::DeviceId,
+    queue: wgc::id::QueueId,
+) -> wgc::id::BufferId {
+    let buffer = |label: &'static str, usage| {
+        let desc = wgc::resource::BufferDescriptor {
+            label: Some(label.into()),
+            size: DATA.len() as wgt::BufferAddress,
+            usage,
+            mapped_at_creation: false,
+        };
+        let (id, error) = global.device_create_buffer(device, &desc, None);
+        assert!(error.is_none(), "{error:?}");
+        id
+    };
+    let src = buffer(
+        "src",
+        wgt::BufferUsages::COPY_SRC | wgt::BufferUsages::COPY_DST,
+    );
+    let dst = buffer("dst", wgt::BufferUsages::COPY_DST);
+    global.queue_write_buffer(queue, src, 0, &DATA).unwrap();
+
+    let (encoder, error) = global.device_create_command_encoder(
+        device,
+        &wgt::CommandEncoderDescriptor { label: None },
+        None,
+    );
+    assert!(error.is_none(), "{error:?}");
+    global
+        .command_encoder_copy_buffer_to_buffer(encoder, src, 0, dst, 0, None)
+        .unwrap();
+    let (command_buffer, error) =
+        global.command_encoder_finish(encoder, &wgt::CommandBufferDescriptor { label: None }, None);
+    assert!(error.is_none(), "{error:?}");
+    global.queue_submit(queue, &[command_buffer]).unwrap();
+    dst
+}
+
+/// Returns the actions of the trace at `path`, in RON.
+fn actions(path: &Path) -> Vec<String> {
+    player::TraceReader::open(path)
+        .unwrap()
+        .map(|action| ron::to_string(&action.unwrap()).unwrap())
+        .collect()
+}
+
+/// Replays the trace at `path` on the noop backend, returning the contents of
+/// `buffer` at the end.
+fn replay(path: &Path, buffer: wgc::id::BufferId) -> Vec<u8> {
+    let dump_dir = path.with_extension("dump");
+    fs::create_dir_all(&dump_dir).unwrap();
+    let (index, epoch) = buffer.unzip();
+    stdout(run(
+        PLAY,
+        &[
+            &"--backends",
+            &"noop",
+            &"--dump",
+            &format!("buffer:{index},{epoch}"),
+            &"--dump-dir",
+            &dump_dir,
+            &path,
+        ],
+    ));
+    fs::read(dump_dir.join(format!("buffer-{index}-{epoch}.bin"))).unwrap()
+}
+
+#[test]
+fn convert_between_formats() {
+    let dir = temp_dir("convert");
+    let ron = dir.join("ron");
+    let binary = dir.join("binary");
+    fs::create_dir_all(&ron).unwrap();
+    fs::create_dir_all(&binary).unwrap();
+
+    let buffer = record(&ron, trace::Format::Ron, copy_buffer);
+    assert_eq!(record(&binary, trace::Format::Binary, copy_buffer), buffer);
+    assert!(ron.join(trace::FILE_NAME).is_file());
+    assert!(binary.join(trace::BINARY_FILE_NAME).is_file());
+
+    let ron_to_binary = dir.join("ron-to-binary");
+    let binary_to_ron = dir.join("binary-to-ron");
+    let converted = stdout(run(CONVERT, &[&ron, &ron_to_binary]));
+    assert!(converted.starts_with("Converted"), "{converted}");
+    stdout(run(CONVERT, &[&binary, &binary_to_ron]));
+    assert!(ron_to_binary.join(trace::BINARY_FILE_NAME).is_file());
+    assert!(binary_to_ron.join(trace::FILE_NAME).is_file());
+
+    let expected = actions(&ron);
+    for trace in [&ron, &binary, &ron_to_binary, &binary_to_ron] {
+        assert_eq!(actions(trace), expected, "{trace:?}");
+        assert_eq!(replay(trace, buffer), DATA, "{trace:?}");
+    }
+}
+
+#[test]
+fn binary_trace_magic_is_checked() {
+    let mut other_version = trace::BINARY_MAGIC;
+    *other_version.last_mut().unwrap() += 1;
+    for bytes in [&other_version[..], b"not a trace"] {
+        let error = trace::BinaryReader::new(bytes).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    let dir = temp_dir("other-version");
+    fs::write(dir.join(trace::BINARY_FILE_NAME), other_version).unwrap();
+    let output = run(CONVERT, &[&dir, &dir.join("converted")]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("incompatible version"), "{stderr}");
+}
@@ -0,0 +1,166 @@
+//! Tests of dumping buffers and textures with `play --dump`.
+
+use std::{fs, path::Path};
+
+use wgc::device::trace;
+
+use crate::util::{record, run, stdout, temp_dir};
+
+const PLAY: &str = env!("CARGO_BIN_EXE_play");
+
+/// Creates a texture of `format`, with 4 bytes per texel, and writes `texels`
+/// to it.
+fn texture(
+    global: &wgc::global::Global,
+    device: wgc::id::DeviceId,
+    queue: wgc::id::QueueId,
+    format: wgt::TextureFormat,
+    size: (u32, u32),
+    texels: &[u8],
+) -> wgc::id::TextureId {
+    let size = wgt::Extent3d {
+        width: size.0,
+        height: size.1,
+        depth_or_array_layers: 1,
+    };
+    let desc = wgc::resource::TextureDescriptor {
+        label: None,
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgt::TextureDimension::D2,
+        format,
+        usage: wgt::TextureUsages::COPY_DST,
+        view_formats: Vec::new(),
+    };
+    let (id, error) = global.device_create_texture(device, &desc, None);
+    assert!(error.is_none(), "{error:?}");
+    global
+        .queue_write_texture(
+            queue,
+            &wgt::TexelCopyTextureInfo {
+                texture: id,
+                mip_level: 0,
+                origin: wgt::Origin3d::ZERO,
+                aspect: wgt::TextureAspect::All,
+            },
+            texels,
+            &wgt::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(size.width * 4),
+                rows_per_image: None,
+            },
+            &size,
+        )
+        .unwrap();
+    id
+}
+
+/// Decodes the PNG image at `path`, returning its size and RGBA pixels.
+fn read_png(path: &Path) -> ((u32, u32), Vec<u8>) {
+    let decoder = png::Decoder::new(fs::File::open(path).unwrap());
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    assert_eq!(info.bit_depth, png::BitDepth::Eight);
+    pixels.truncate(info.buffer_size());
+    ((info.width, info.height), pixels)
+}
+
+#[test]
+fn dump_resources() {
+    let dir = temp_dir("dump");
+    let trace_dir = dir.join("trace");
+    let dump_dir = dir.join("dump");
+    fs::create_dir_all(&trace_dir).unwrap();
+    fs::create_dir_all(&dump_dir).unwrap();
+
+    let texels = (0..24).map(|i| i * 10).collect::<Vec<u8>>();
+    let resources = record(&trace_dir, trace::Format::Ron, |global, device, queue| {
+        let desc = wgc::resource::BufferDescriptor {
+            label: None,
+            size: 24,
+            usage: wgt::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        };
+        let (buffer, error) = global.device_create_buffer(device, &desc, None);
+        assert!(error.is_none(), "{error:?}");
+        global
+            .queue_write_buffer(queue, buffer, 0, &texels)
+            .unwrap();
+
+        let texture = |format| texture(global, device, queue, format, (3, 2), &texels);
+        let rgba = texture(wgt::TextureFormat::Rgba8Unorm);
+        let bgra = texture(wgt::TextureFormat::Bgra8UnormSrgb);
+        let raw = texture(wgt::TextureFormat::R32Uint);
+        global.queue_submit(queue, &[]).unwrap();
+        let resource = |kind, (index, epoch): (u32, u32)| format!("{kind}:{index},{epoch}");
+        [
+            resource("buffer", buffer.unzip()),
+            resource("texture", rgba.unzip()),
+            resource("texture", bgra.unzip()),
+            resource("texture", raw.unzip()),
+        ]
+    });
+
+    let mut args: Vec<&dyn AsRef<std::ffi::OsStr>> = vec![&"--backends", &"noop"];
+    for resource in &resources {
+        args.push(&"--dump");
+        args.push(resource);
+    }
+    args.extend([&"--dump-dir", &dump_dir, &trace_dir] as [&dyn AsRef<_>; 3]);
+    stdout(run(PLAY, &args));
+
+    let file = |resource: &str, extension| {
+        let name = resource.replace([':', ','], "-");
+        dump_dir.join(format!("{name}.{extension}"))
+    };
+
+    // Buffers and textures that aren't 8-bit RGBA or BGRA are dumped as is,
+    // without the padding of the rows of the copy.
+    assert_eq!(fs::read(file(&resources[0], "bin")).unwrap(), texels);
+    assert_eq!(fs::read(file(&resources[3], "bin")).unwrap(), texels);
+
+    assert_eq!(
+        read_png(&file(&resources[1], "png")),
+        ((3, 2), texels.clone())
+    );
+    let rgba = texels
+        .chunks(4)
+        .flat_map(|texel| [texel[2], texel[1], texel[0], texel[3]])
+        .collect();
+    assert_eq!(read_png(&file(&resources[2], "png")), ((3, 2), rgba));
+}
+
+#[test]
+fn dump_unknown_resource() {
+    let dir = temp_dir("dump-unknown");
+    let trace_dir = dir.join("trace");
+    fs::create_dir_all(&trace_dir).unwrap();
+    record(&trace_dir, trace::Format::Ron, |global, _device, queue| {
+        global.queue_submit(queue, &[]).unwrap();
+    });
+
+    // Resources that the trace doesn't create are skipped.
+    let output = run(
+        PLAY,
+        &[
+            &"--backends",
+            &"noop",
+            &"--dump",
+            &"texture:5",
+            &"--dump-dir",
+            &dir,
+            &trace_dir,
+        ],
+    );
+    assert!(output.status.success());
+    assert!(!dir.join("texture-5-1.png").exists());
+    assert!(!dir.join("texture-5-1.bin").exists());
+
+    let output = run(PLAY, &[&"--dump", &"sampler:1", &trace_dir]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("Invalid resource 'sampler:1'"), "{stderr}");
+}
@@ -22,6 +22,12 @@ use std::{
 };
 use wgc::identity::IdentityManager;
 
+mod check;
+mod compare;
+mod convert;
+mod dump;
+mod util;
+
 #[derive(serde::Deserialize)]
 struct RawId {
     index: u32,
@@ -0,0 +1,87 @@
+//! Helpers to record traces and run the player's tools on them.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use wgc::device::trace;
+
+/// Returns an empty directory named `name`, for the files of a test.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("player")
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Records a trace of `f` in `dir`, with a device of the noop backend.
+pub fn record<R>(
+    dir: &Path,
+    format: trace::Format,
+    f: impl FnOnce(&wgc::global::Global, wgc::id::DeviceId, wgc::id::QueueId) -> R,
+) -> R {
+    let global = wgc::global::Global::new(
+        "player-test",
+        &wgt::InstanceDescriptor {
+            backends: wgt::Backends::NOOP,
+            backend_options: wgt::BackendOptions {
+                noop: wgt::NoopBackendOptions {
+                    enable: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+    let adapter = global
+        .request_adapter(
+            &wgc::instance::RequestAdapterOptions::default(),
+            wgt::Backends::NOOP,
+            None,
+        )
+        .unwrap();
+    let trace = match format {
+        trace::Format::Ron => wgt::Trace::Directory(dir.to_path_buf()),
+        trace::Format::Binary => wgt::Trace::BinaryDirectory(dir.to_path_buf()),
+    };
+    let (device, queue) = global
+        .adapter_request_device(
+            adapter,
+            &wgt::DeviceDescriptor {
+                trace,
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .unwrap();
+
+    let result = f(&global, device, queue);
+
+    global.device_poll(device, wgt::PollType::wait()).unwrap();
+    global.queue_drop(queue);
+    global.device_drop(device);
+    // The trace is finished when the device is dropped, along with the global.
+    result
+}
+
+/// Runs the player tool `bin` with `args`.
+pub fn run(bin: &str, args: &[&dyn AsRef<std::ffi::OsStr>]) -> Output {
+    Command::new(bin)
+        .args(args.iter().map(|arg| arg.as_ref()))
+        .output()
+        .unwrap()
+}
+
+/// Returns the standard output of `output`, after checking that the tool
+/// succeeded.
+pub fn stdout(output: Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}\n{stderr}", output.status);
+    String::from_utf8(output.stdout).unwrap()
+}
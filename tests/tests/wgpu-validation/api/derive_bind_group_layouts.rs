@@ -0,0 +1,192 @@
+use wgpu::*;
+use wgpu_test::valid;
+
+fn shader(device: &Device, source: &str) -> ShaderModule {
+    device.create_shader_module(ShaderModuleDescriptor {
+        label: None,
+        source: ShaderSource::Wgsl(source.into()),
+    })
+}
+
+#[test]
+fn merges_entry_points() {
+    let (device, _queue) = Device::noop(&DeviceDescriptor::default());
+
+    let render = shader(
+        &device,
+        "
+        struct Globals {
+            transform: mat4x4<f32>,
+        }
+
+        @group(0) @binding(0) var<uniform> globals: Globals;
+        @group(1) @binding(0) var color: texture_2d<f32>;
+        @group(1) @binding(1) var color_sampler: sampler;
+
+        @vertex
+        fn vs_main(@location(0) position: vec4<f32>) -> @builtin(position) vec4<f32> {
+            return globals.transform * position;
+        }
+
+        @fragment
+        fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+            return textureSample(color, color_sampler, position.xy);
+        }
+        ",
+    );
+    let compute = shader(
+        &device,
+        "
+        struct Globals {
+            transform: mat4x4<f32>,
+            time: vec4<f32>,
+        }
+
+        @group(0) @binding(0) var<uniform> globals: Globals;
+        @group(1) @binding(0) var color: texture_2d<f32>;
+        @group(2) @binding(3) var<storage, read_write> output: array<vec4<f32>>;
+
+        @compute @workgroup_size(1)
+        fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+            output[id.x] = textureLoad(color, id.xy, 0) * globals.time;
+        }
+        ",
+    );
+
+    let layouts = valid(&device, || {
+        device.derive_bind_group_layouts(&[
+            (&render, "vs_main"),
+            (&render, "fs_main"),
+            (&compute, "cs_main"),
+        ])
+    })
+    .unwrap();
+
+    assert_eq!(
+        layouts,
+        [
+            vec![BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX | ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    // The larger of the two `Globals` structs.
+                    min_binding_size: BufferSize::new(80),
+                },
+                count: None,
+            }],
+            vec![
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT | ShaderStages::COMPUTE,
+                    ty: BindingType::Texture {
+                        // Sampled by the fragment shader.
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            vec![BindGroupLayoutEntry {
+                binding: 3,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: BufferSize::new(16),
+                },
+                count: None,
+            }],
+        ]
+    );
+
+    // The derived entries can be used to create layouts for the pipelines.
+    valid(&device, || {
+        for entries in &layouts {
+            let _ = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: None,
+                entries,
+            });
+        }
+    });
+}
+
+#[test]
+fn skips_unused_groups() {
+    let (device, _queue) = Device::noop(&DeviceDescriptor::default());
+
+    let module = shader(
+        &device,
+        "
+        @group(2) @binding(0) var<storage, read_write> data: array<u32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            data[0] = 1u;
+        }
+        ",
+    );
+
+    let layouts = valid(&device, || {
+        device.derive_bind_group_layouts(&[(&module, "main")])
+    })
+    .unwrap();
+    assert_eq!(layouts.len(), 3);
+    assert!(layouts[0].is_empty());
+    assert!(layouts[1].is_empty());
+    assert_eq!(layouts[2].len(), 1);
+}
+
+#[test]
+fn incompatible_uses() {
+    let (device, _queue) = Device::noop(&DeviceDescriptor::default());
+
+    let module = shader(
+        &device,
+        "
+        @group(0) @binding(0) var<storage, read> input: array<u32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            _ = input[0];
+        }
+        ",
+    );
+    let other = shader(
+        &device,
+        "
+        @group(0) @binding(0) var<uniform> input: vec4<u32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            _ = input.x;
+        }
+        ",
+    );
+
+    // Errors are returned instead of being raised.
+    let error = valid(&device, || {
+        device
+            .derive_bind_group_layouts(&[(&module, "main"), (&other, "main")])
+            .unwrap_err()
+    });
+    assert!(matches!(error, Error::Validation { .. }));
+    assert!(error
+        .to_string()
+        .contains("Derived bind group layout type is not consistent between stages"));
+
+    let error = device
+        .derive_bind_group_layouts(&[(&module, "missing")])
+        .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("Unable to find entry point 'missing'"));
+}
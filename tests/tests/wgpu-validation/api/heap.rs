@@ -0,0 +1,272 @@
+//! Tests of resource heaps and placed resources, see [`wgpu::Features::RESOURCE_HEAPS`].
+
+use wgpu::*;
+use wgpu_test::{fail, valid};
+
+fn device_with_resource_heaps() -> (Device, Queue) {
+    Device::noop(&DeviceDescriptor {
+        required_features: Features::RESOURCE_HEAPS,
+        ..Default::default()
+    })
+}
+
+fn heap_descriptor(size: BufferAddress) -> HeapDescriptor<'static> {
+    HeapDescriptor {
+        label: Some("heap"),
+        size,
+    }
+}
+
+fn buffer_descriptor(label: &'static str, size: BufferAddress) -> BufferDescriptor<'static> {
+    BufferDescriptor {
+        label: Some(label),
+        size,
+        usage: BufferUsages::COPY_DST | BufferUsages::STORAGE,
+        mapped_at_creation: false,
+    }
+}
+
+fn texture_descriptor() -> TextureDescriptor<'static> {
+    TextureDescriptor {
+        label: Some("texture"),
+        size: Extent3d {
+            width: 64,
+            height: 64,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: TextureFormat::Rgba8Unorm,
+        usage: TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    }
+}
+
+#[test]
+fn resource_heaps_require_feature() {
+    let (device, _queue) = Device::noop(&DeviceDescriptor::default());
+
+    fail(
+        &device,
+        || device.create_heap(&heap_descriptor(4096)),
+        Some("RESOURCE_HEAPS"),
+    );
+    fail(
+        &device,
+        || device.get_buffer_memory_requirements(&buffer_descriptor("buffer", 256)),
+        Some("RESOURCE_HEAPS"),
+    );
+    fail(
+        &device,
+        || device.get_texture_memory_requirements(&texture_descriptor()),
+        Some("RESOURCE_HEAPS"),
+    );
+
+    let buffer = device.create_buffer(&buffer_descriptor("buffer", 256));
+    fail(
+        &device,
+        || {
+            let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+            encoder.aliasing_barrier(&[&buffer], &[]);
+            encoder.finish()
+        },
+        Some("RESOURCE_HEAPS"),
+    );
+}
+
+#[test]
+fn heap_creation() {
+    let (device, _queue) = device_with_resource_heaps();
+
+    valid(&device, || device.create_heap(&heap_descriptor(4096)));
+    fail(
+        &device,
+        || device.create_heap(&heap_descriptor(0)),
+        Some("size of zero"),
+    );
+}
+
+#[test]
+fn placed_buffer_creation() {
+    let (device, _queue) = device_with_resource_heaps();
+    let heap = device.create_heap(&heap_descriptor(4096));
+
+    let requirements = device
+        .get_buffer_memory_requirements(&buffer_descriptor("buffer", 1024))
+        .unwrap();
+    assert!(requirements.size >= 1024);
+
+    valid(&device, || {
+        let _a = heap.create_buffer(0, &buffer_descriptor("a", 1024));
+        let _b = heap.create_buffer(requirements.alignment, &buffer_descriptor("b", 1024));
+    });
+
+    fail(
+        &device,
+        || heap.create_buffer(requirements.alignment + 4, &buffer_descriptor("c", 1024)),
+        Some("alignment"),
+    );
+    fail(
+        &device,
+        || heap.create_buffer(0, &buffer_descriptor("c", 8192)),
+        Some("overruns"),
+    );
+    fail(
+        &device,
+        || {
+            heap.create_buffer(
+                0,
+                &BufferDescriptor {
+                    label: None,
+                    size: 256,
+                    usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                },
+            )
+        },
+        Some("can't be mapped"),
+    );
+}
+
+#[test]
+fn placed_texture_creation() {
+    let (device, _queue) = device_with_resource_heaps();
+
+    let requirements = device
+        .get_texture_memory_requirements(&texture_descriptor())
+        .unwrap();
+    let heap = device.create_heap(&heap_descriptor(requirements.size));
+
+    valid(&device, || heap.create_texture(0, &texture_descriptor()));
+    fail(
+        &device,
+        || heap.create_texture(requirements.alignment, &texture_descriptor()),
+        Some("overruns"),
+    );
+}
+
+#[test]
+fn placed_resources_must_be_activated() {
+    let (device, queue) = device_with_resource_heaps();
+    let heap = device.create_heap(&heap_descriptor(4096));
+
+    let a = heap.create_buffer(0, &buffer_descriptor("a", 1024));
+    let b = heap.create_buffer(512, &buffer_descriptor("b", 1024));
+
+    // Placed resources start out inactive.
+    fail(
+        &device,
+        || queue.write_buffer(&a, 0, &[0; 4]),
+        Some("must be activated again"),
+    );
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.aliasing_barrier(&[&a], &[]);
+    encoder.clear_buffer(&a, 0, None);
+    valid(&device, || queue.submit([encoder.finish()]));
+    valid(&device, || queue.write_buffer(&a, 0, &[0; 4]));
+
+    // Activating `b` deactivates the overlapping `a`.
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.aliasing_barrier(&[&b], &[]);
+    encoder.clear_buffer(&b, 0, None);
+    valid(&device, || queue.submit([encoder.finish()]));
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.clear_buffer(&a, 0, None);
+    fail(
+        &device,
+        || queue.submit([encoder.finish()]),
+        Some("must be activated again"),
+    );
+}
+
+#[test]
+fn failed_submissions_dont_activate() {
+    let (device, queue) = device_with_resource_heaps();
+    let heap = device.create_heap(&heap_descriptor(4096));
+
+    let a = heap.create_buffer(0, &buffer_descriptor("a", 1024));
+    let b = heap.create_buffer(512, &buffer_descriptor("b", 1024));
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.aliasing_barrier(&[&a], &[]);
+    valid(&device, || queue.submit([encoder.finish()]));
+
+    // The barrier is checked against the command buffer it was recorded in.
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.aliasing_barrier(&[&b], &[]);
+    encoder.clear_buffer(&a, 0, None);
+    fail(
+        &device,
+        || queue.submit([encoder.finish()]),
+        Some("must be activated again"),
+    );
+
+    // And later command buffers of the same submission.
+    let mut activate = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    activate.aliasing_barrier(&[&b], &[]);
+    let mut use_a = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    use_a.clear_buffer(&a, 0, None);
+    fail(
+        &device,
+        || queue.submit([activate.finish(), use_a.finish()]),
+        Some("must be activated again"),
+    );
+
+    // Neither submission went ahead, so `a` is still active.
+    valid(&device, || queue.write_buffer(&a, 0, &[0; 4]));
+
+    let mut activate = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    activate.aliasing_barrier(&[&b], &[]);
+    let mut use_b = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    use_b.clear_buffer(&b, 0, None);
+    valid(&device, || {
+        queue.submit([activate.finish(), use_b.finish()])
+    });
+    fail(
+        &device,
+        || queue.write_buffer(&a, 0, &[0; 4]),
+        Some("must be activated again"),
+    );
+}
+
+#[test]
+fn aliasing_barrier_requires_placed_resources() {
+    let (device, _queue) = device_with_resource_heaps();
+    let buffer = device.create_buffer(&buffer_descriptor("buffer", 256));
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.aliasing_barrier(&[&buffer], &[]);
+    fail(&device, || encoder.finish(), Some("not placed in a heap"));
+}
+
+#[test]
+fn allocation_report() {
+    let (device, _queue) = device_with_resource_heaps();
+    let heap = device.create_heap(&heap_descriptor(4096));
+
+    let _a = heap.create_buffer(0, &buffer_descriptor("a", 1024));
+    let _b = heap.create_buffer(512, &buffer_descriptor("b", 1024));
+    let c = heap.create_buffer(2048, &buffer_descriptor("c", 1024));
+
+    let report = heap.generate_allocation_report().unwrap();
+    let allocations = report
+        .allocations
+        .iter()
+        .map(|allocation| (allocation.name.as_str(), allocation.offset, allocation.size))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        allocations,
+        [("a", 0, 1024), ("b", 512, 1024), ("c", 2048, 1024)]
+    );
+    assert_eq!(report.total_allocated_bytes, 2560);
+    assert_eq!(report.total_reserved_bytes, 4096);
+
+    // Dropped resources no longer appear in the report.
+    drop(c);
+    let report = heap.generate_allocation_report().unwrap();
+    assert_eq!(report.allocations.len(), 2);
+    assert_eq!(report.total_allocated_bytes, 1536);
+}
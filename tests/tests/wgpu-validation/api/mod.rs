@@ -1,6 +1,11 @@
 mod binding_arrays;
 mod buffer;
 mod buffer_slice;
+mod derive_bind_group_layouts;
 mod external_texture;
+mod heap;
 mod instance;
+mod pipeline_async;
+mod queues;
+mod sparse;
 mod texture;
@@ -0,0 +1,118 @@
+//! Tests of [`wgpu::Device::create_render_pipeline_async`] and
+//! [`wgpu::Device::create_compute_pipeline_async`].
+
+use wgpu::*;
+
+const SHADER: &str = "
+@group(0) @binding(0) var<storage, read_write> data: u32;
+@compute @workgroup_size(1) fn main_cs() { data = 1u; }
+@vertex fn main_vs() -> @builtin(position) vec4<f32> { return vec4<f32>(0); }
+@fragment fn main_fs() -> @location(0) vec4<f32> { return vec4<f32>(0); }
+";
+
+fn shader_module(device: &Device) -> ShaderModule {
+    device.create_shader_module(ShaderModuleDescriptor {
+        label: None,
+        source: ShaderSource::Wgsl(std::borrow::Cow::Borrowed(SHADER)),
+    })
+}
+
+fn compute_pipeline_descriptor<'a>(
+    module: &'a ShaderModule,
+    entry_point: &'a str,
+) -> ComputePipelineDescriptor<'a> {
+    ComputePipelineDescriptor {
+        label: Some("compute pipeline"),
+        layout: None,
+        module,
+        entry_point: Some(entry_point),
+        compilation_options: PipelineCompilationOptions::default(),
+        cache: None,
+    }
+}
+
+fn color_targets(format: TextureFormat) -> [Option<ColorTargetState>; 1] {
+    [Some(ColorTargetState {
+        format,
+        blend: None,
+        write_mask: ColorWrites::ALL,
+    })]
+}
+
+fn render_pipeline_descriptor<'a>(
+    module: &'a ShaderModule,
+    targets: &'a [Option<ColorTargetState>],
+) -> RenderPipelineDescriptor<'a> {
+    RenderPipelineDescriptor {
+        label: Some("render pipeline"),
+        layout: None,
+        vertex: VertexState {
+            module,
+            entry_point: Some("main_vs"),
+            compilation_options: PipelineCompilationOptions::default(),
+            buffers: &[],
+        },
+        primitive: PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: MultisampleState::default(),
+        fragment: Some(FragmentState {
+            module,
+            entry_point: Some("main_fs"),
+            compilation_options: PipelineCompilationOptions::default(),
+            targets,
+        }),
+        multiview: None,
+        cache: None,
+    }
+}
+
+#[test]
+fn compute_pipeline_async() {
+    let (device, _queue) = Device::noop(&DeviceDescriptor::default());
+    let module = shader_module(&device);
+
+    let pipeline = pollster::block_on(
+        device.create_compute_pipeline_async(&compute_pipeline_descriptor(&module, "main_cs")),
+    );
+    let pipeline = pipeline.unwrap();
+    let _layout = pipeline.get_bind_group_layout(0);
+}
+
+#[test]
+fn render_pipeline_async() {
+    let (device, _queue) = Device::noop(&DeviceDescriptor::default());
+    let module = shader_module(&device);
+
+    let targets = color_targets(TextureFormat::Rgba8Unorm);
+
+    let pipeline = pollster::block_on(
+        device.create_render_pipeline_async(&render_pipeline_descriptor(&module, &targets)),
+    );
+    assert!(pipeline.is_ok());
+}
+
+#[test]
+fn pipeline_async_errors_are_returned() {
+    let (device, _queue) = Device::noop(&DeviceDescriptor::default());
+    let module = shader_module(&device);
+
+    // Errors are returned by the future rather than reported to error scopes.
+    device.push_error_scope(ErrorFilter::Validation);
+
+    let compute = pollster::block_on(
+        device.create_compute_pipeline_async(&compute_pipeline_descriptor(&module, "missing")),
+    );
+    let Err(Error::Validation { description, .. }) = compute else {
+        panic!("expected a validation error");
+    };
+    assert!(description.contains("missing"), "{description}");
+
+    // Depth formats can't be color targets.
+    let targets = color_targets(TextureFormat::Depth32Float);
+    let render = pollster::block_on(
+        device.create_render_pipeline_async(&render_pipeline_descriptor(&module, &targets)),
+    );
+    assert!(matches!(render, Err(Error::Validation { .. })));
+
+    assert!(pollster::block_on(device.pop_error_scope()).is_none());
+}
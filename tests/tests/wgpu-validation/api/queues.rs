@@ -0,0 +1,184 @@
+use wgpu::*;
+use wgpu_test::{fail, valid};
+
+fn device_with_queues() -> (Device, Queue) {
+    Device::noop(&DeviceDescriptor {
+        required_features: Features::MULTIPLE_QUEUES,
+        ..Default::default()
+    })
+}
+
+fn create_queue(device: &Device, ty: QueueType) -> Queue {
+    valid(device, || {
+        device.create_queue(&QueueDescriptor { label: None, ty })
+    })
+    .expect("the noop backend has compute and transfer queues")
+}
+
+fn create_buffer(device: &Device) -> Buffer {
+    device.create_buffer(&BufferDescriptor {
+        label: None,
+        size: 16,
+        usage: BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+#[test]
+fn create_queue_requires_feature() {
+    let (device, _queue) = Device::noop(&DeviceDescriptor::default());
+
+    fail(
+        &device,
+        || {
+            device.create_queue(&QueueDescriptor {
+                label: None,
+                ty: QueueType::Compute,
+            })
+        },
+        Some("MULTIPLE_QUEUES"),
+    );
+}
+
+#[test]
+fn create_queues() {
+    let (device, _queue) = device_with_queues();
+
+    let _compute = create_queue(&device, QueueType::Compute);
+    let transfer = create_queue(&device, QueueType::Transfer);
+
+    // The main queue is the only graphics queue.
+    fail(
+        &device,
+        || {
+            device.create_queue(&QueueDescriptor {
+                label: None,
+                ty: QueueType::Graphics,
+            })
+        },
+        Some("only graphics queue"),
+    );
+
+    // Each queue can only be created once at a time.
+    fail(
+        &device,
+        || {
+            device.create_queue(&QueueDescriptor {
+                label: None,
+                ty: QueueType::Transfer,
+            })
+        },
+        Some("already in use"),
+    );
+
+    drop(transfer);
+    let _transfer = create_queue(&device, QueueType::Transfer);
+}
+
+#[test]
+fn unsupported_passes() {
+    let (device, _queue) = device_with_queues();
+    let compute = create_queue(&device, QueueType::Compute);
+    let transfer = create_queue(&device, QueueType::Transfer);
+
+    let texture = device.create_texture(&TextureDescriptor {
+        label: None,
+        size: Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: TextureFormat::Rgba8Unorm,
+        usage: TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&TextureViewDescriptor::default());
+
+    let mut encoder = compute.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.begin_render_pass(&RenderPassDescriptor {
+        color_attachments: &[Some(RenderPassColorAttachment {
+            view: &view,
+            depth_slice: None,
+            resolve_target: None,
+            ops: Operations::default(),
+        })],
+        ..Default::default()
+    });
+    fail(&device, || encoder.finish(), Some("Compute queue"));
+
+    let mut encoder = transfer.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.begin_compute_pass(&ComputePassDescriptor::default());
+    fail(&device, || encoder.finish(), Some("Transfer queue"));
+
+    // Copies are fine on every queue.
+    let source = create_buffer(&device);
+    let destination = create_buffer(&device);
+    let mut encoder = transfer.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(&source, 0, &destination, 0, 16);
+    let command_buffer = valid(&device, || encoder.finish());
+    valid(&device, || transfer.submit([command_buffer]));
+}
+
+#[test]
+fn submit_to_wrong_queue() {
+    let (device, queue) = device_with_queues();
+    let compute = create_queue(&device, QueueType::Compute);
+
+    let command_buffer = compute
+        .create_command_encoder(&CommandEncoderDescriptor::default())
+        .finish();
+    fail(
+        &device,
+        || queue.submit([command_buffer]),
+        Some("recorded for a Compute queue"),
+    );
+
+    let command_buffer = device
+        .create_command_encoder(&CommandEncoderDescriptor::default())
+        .finish();
+    fail(
+        &device,
+        || compute.submit([command_buffer]),
+        Some("recorded for a Graphics queue"),
+    );
+}
+
+#[test]
+fn cross_queue_use() {
+    let (device, queue) = device_with_queues();
+    let transfer = create_queue(&device, QueueType::Transfer);
+
+    let source = create_buffer(&device);
+    let destination = create_buffer(&device);
+    transfer.write_buffer(&source, 0, &[1; 16]);
+
+    let copy = || {
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+        encoder.copy_buffer_to_buffer(&source, 0, &destination, 0, 16);
+        encoder.finish()
+    };
+
+    // The write hasn't been submitted on the transfer queue yet.
+    fail(&device, || queue.submit([copy()]), Some("pending"));
+
+    let submission = transfer.submit([]);
+    valid(&device, || {
+        queue.wait_for_submission(&transfer, &submission)
+    });
+    valid(&device, || queue.submit([copy()]));
+}
+
+#[test]
+fn wait_for_own_submission() {
+    let (device, queue) = device_with_queues();
+
+    let submission = queue.submit([]);
+    fail(
+        &device,
+        || queue.wait_for_submission(&queue, &submission),
+        Some("own submissions"),
+    );
+}
@@ -0,0 +1,446 @@
+//! Tests of sparse buffers and textures, see [`wgpu::Features::SPARSE_RESOURCES`].
+
+use wgpu::*;
+use wgpu_test::{fail, valid};
+
+fn device_with_sparse_resources() -> (Device, Queue) {
+    Device::noop(&DeviceDescriptor {
+        required_features: Features::SPARSE_RESOURCES,
+        ..Default::default()
+    })
+}
+
+fn buffer_descriptor(size: BufferAddress, usage: BufferUsages) -> BufferDescriptor<'static> {
+    BufferDescriptor {
+        label: None,
+        size,
+        usage,
+        mapped_at_creation: false,
+    }
+}
+
+fn texture_descriptor(
+    format: TextureFormat,
+    size: u32,
+    mip_level_count: u32,
+) -> TextureDescriptor<'static> {
+    TextureDescriptor {
+        label: None,
+        size: Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 2,
+        },
+        mip_level_count,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format,
+        usage: TextureUsages::TEXTURE_BINDING | TextureUsages::SPARSE,
+        view_formats: &[],
+    }
+}
+
+/// Submits `encoder`, then reads back the contents of `buffer`.
+fn read_buffer(
+    device: &Device,
+    queue: &Queue,
+    encoder: CommandEncoder,
+    buffer: &Buffer,
+) -> Vec<u8> {
+    queue.submit([encoder.finish()]);
+    buffer.map_async(MapMode::Read, .., |result| result.unwrap());
+    device.poll(PollType::Wait).unwrap();
+    let data = buffer.get_mapped_range(..).to_vec();
+    buffer.unmap();
+    data
+}
+
+/// Submits the pending writes, then reads back the contents of the sparse `buffer`.
+fn read_sparse_buffer(device: &Device, queue: &Queue, buffer: &Buffer) -> Vec<u8> {
+    let readback = device.create_buffer(&buffer_descriptor(
+        buffer.size(),
+        BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+    ));
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(buffer, 0, &readback, 0, None);
+    read_buffer(device, queue, encoder, &readback)
+}
+
+#[test]
+fn sparse_resources_require_feature() {
+    let (device, queue) = Device::noop(&DeviceDescriptor::default());
+
+    fail(
+        &device,
+        || {
+            device.create_buffer(&buffer_descriptor(
+                SPARSE_PAGE_SIZE,
+                BufferUsages::STORAGE | BufferUsages::SPARSE,
+            ))
+        },
+        Some("SPARSE_RESOURCES"),
+    );
+    fail(
+        &device,
+        || device.create_texture(&texture_descriptor(TextureFormat::Rgba8Unorm, 256, 1)),
+        Some("SPARSE_RESOURCES"),
+    );
+    fail(
+        &device,
+        || queue.update_sparse_residency(&[], &[]),
+        Some("SPARSE_RESOURCES"),
+    );
+}
+
+#[test]
+fn sparse_buffer_creation() {
+    let (device, _queue) = device_with_sparse_resources();
+
+    valid(&device, || {
+        device.create_buffer(&buffer_descriptor(
+            4 * SPARSE_PAGE_SIZE,
+            BufferUsages::STORAGE | BufferUsages::VERTEX | BufferUsages::SPARSE,
+        ))
+    });
+
+    fail(
+        &device,
+        || {
+            device.create_buffer(&buffer_descriptor(
+                SPARSE_PAGE_SIZE + 4,
+                BufferUsages::STORAGE | BufferUsages::SPARSE,
+            ))
+        },
+        Some("not a multiple of `SPARSE_PAGE_SIZE`"),
+    );
+    fail(
+        &device,
+        || {
+            device.create_buffer(&buffer_descriptor(
+                SPARSE_PAGE_SIZE,
+                BufferUsages::MAP_READ | BufferUsages::COPY_DST | BufferUsages::SPARSE,
+            ))
+        },
+        Some("Sparse buffers can't be mapped"),
+    );
+    fail(
+        &device,
+        || {
+            device.create_buffer(&BufferDescriptor {
+                mapped_at_creation: true,
+                ..buffer_descriptor(
+                    SPARSE_PAGE_SIZE,
+                    BufferUsages::STORAGE | BufferUsages::SPARSE,
+                )
+            })
+        },
+        Some("Sparse buffers can't be mapped"),
+    );
+}
+
+#[test]
+fn sparse_texture_creation() {
+    let (device, _queue) = device_with_sparse_resources();
+
+    // Tiles of `Rgba8Unorm` are 128x128 texels, so two mip levels fit.
+    valid(&device, || {
+        device.create_texture(&texture_descriptor(TextureFormat::Rgba8Unorm, 256, 2))
+    });
+    // Tiles of `R8Unorm` are 256x256 texels.
+    valid(&device, || {
+        device.create_texture(&texture_descriptor(TextureFormat::R8Unorm, 256, 1))
+    });
+
+    fail(
+        &device,
+        || device.create_texture(&texture_descriptor(TextureFormat::Rgba8Unorm, 256, 3)),
+        Some("mip level 2 of a sparse texture is not a multiple of the tile size"),
+    );
+    fail(
+        &device,
+        || device.create_texture(&texture_descriptor(TextureFormat::R8Unorm, 128, 1)),
+        Some("not a multiple of the tile size"),
+    );
+    fail(
+        &device,
+        || {
+            device.create_texture(&TextureDescriptor {
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::SPARSE,
+                ..texture_descriptor(TextureFormat::Depth32Float, 256, 1)
+            })
+        },
+        Some("can't be used by sparse textures"),
+    );
+    fail(
+        &device,
+        || {
+            device.create_texture(&TextureDescriptor {
+                dimension: TextureDimension::D3,
+                ..texture_descriptor(TextureFormat::Rgba8Unorm, 256, 1)
+            })
+        },
+        Some("Sparse textures must be single-sampled 2D textures"),
+    );
+}
+
+#[test]
+fn update_sparse_buffer_residency() {
+    let (device, queue) = device_with_sparse_resources();
+
+    let buffer = device.create_buffer(&buffer_descriptor(
+        4 * SPARSE_PAGE_SIZE,
+        BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::SPARSE,
+    ));
+    let binding = |offset, size, resident| SparseBufferBinding {
+        buffer: &buffer,
+        offset,
+        size,
+        resident,
+    };
+
+    valid(&device, || {
+        queue.update_sparse_residency(
+            &[binding(SPARSE_PAGE_SIZE, 2 * SPARSE_PAGE_SIZE, true)],
+            &[],
+        )
+    });
+    // Writes to resident pages go through as usual.
+    queue.write_buffer(&buffer, SPARSE_PAGE_SIZE, &[1; 16]);
+    queue.submit([]);
+    valid(&device, || {
+        queue.update_sparse_residency(
+            &[
+                binding(0, 4 * SPARSE_PAGE_SIZE, true),
+                binding(SPARSE_PAGE_SIZE, SPARSE_PAGE_SIZE, false),
+            ],
+            &[],
+        )
+    });
+    queue.submit([]);
+
+    fail(
+        &device,
+        || queue.update_sparse_residency(&[binding(4, SPARSE_PAGE_SIZE, true)], &[]),
+        Some("is not aligned to `SPARSE_PAGE_SIZE`"),
+    );
+    fail(
+        &device,
+        || {
+            queue.update_sparse_residency(
+                &[binding(2 * SPARSE_PAGE_SIZE, 4 * SPARSE_PAGE_SIZE, true)],
+                &[],
+            )
+        },
+        Some("is out of bounds"),
+    );
+
+    let regular_buffer =
+        device.create_buffer(&buffer_descriptor(SPARSE_PAGE_SIZE, BufferUsages::STORAGE));
+    fail(
+        &device,
+        || {
+            queue.update_sparse_residency(
+                &[SparseBufferBinding {
+                    buffer: &regular_buffer,
+                    offset: 0,
+                    size: SPARSE_PAGE_SIZE,
+                    resident: true,
+                }],
+                &[],
+            )
+        },
+        Some("was not created with the `SPARSE` usage"),
+    );
+
+    buffer.destroy();
+    fail(
+        &device,
+        || queue.update_sparse_residency(&[binding(0, SPARSE_PAGE_SIZE, false)], &[]),
+        Some("has been destroyed"),
+    );
+}
+
+#[test]
+fn sparse_buffer_readback() {
+    let (device, queue) = device_with_sparse_resources();
+
+    let buffer = device.create_buffer(&buffer_descriptor(
+        2 * SPARSE_PAGE_SIZE,
+        BufferUsages::STORAGE
+            | BufferUsages::COPY_SRC
+            | BufferUsages::COPY_DST
+            | BufferUsages::SPARSE,
+    ));
+    let binding = |offset, size, resident| SparseBufferBinding {
+        buffer: &buffer,
+        offset,
+        size,
+        resident,
+    };
+    let page = SPARSE_PAGE_SIZE as usize;
+
+    queue.update_sparse_residency(&[binding(0, 2 * SPARSE_PAGE_SIZE, true)], &[]);
+    queue.write_buffer(&buffer, 0, &[1; 4]);
+    queue.write_buffer(&buffer, SPARSE_PAGE_SIZE, &[2; 4]);
+    let data = read_sparse_buffer(&device, &queue, &buffer);
+    assert_eq!(data[..4], [1; 4]);
+    assert_eq!(data[page..page + 4], [2; 4]);
+
+    // Reads from pages that aren't resident return zero, and writes to them
+    // are discarded.
+    queue.update_sparse_residency(&[binding(SPARSE_PAGE_SIZE, SPARSE_PAGE_SIZE, false)], &[]);
+    queue.submit([]);
+    queue.write_buffer(&buffer, SPARSE_PAGE_SIZE + 4, &[3; 4]);
+    let data = read_sparse_buffer(&device, &queue, &buffer);
+    assert_eq!(data[..4], [1; 4]);
+    assert!(data[page..].iter().all(|&byte| byte == 0));
+
+    // Pages made resident again are zeroed, rather than holding their old contents.
+    queue.update_sparse_residency(&[binding(SPARSE_PAGE_SIZE, SPARSE_PAGE_SIZE, true)], &[]);
+    let data = read_sparse_buffer(&device, &queue, &buffer);
+    assert_eq!(data[..4], [1; 4]);
+    assert!(data[page..].iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn sparse_texture_readback() {
+    let (device, queue) = device_with_sparse_resources();
+
+    let texture = device.create_texture(&TextureDescriptor {
+        usage: TextureUsages::COPY_SRC | TextureUsages::COPY_DST | TextureUsages::SPARSE,
+        ..texture_descriptor(TextureFormat::Rgba8Unorm, 256, 1)
+    });
+    let size = Extent3d {
+        width: 256,
+        height: 256,
+        depth_or_array_layers: 1,
+    };
+    let layout = TexelCopyBufferLayout {
+        offset: 0,
+        bytes_per_row: Some(256 * 4),
+        rows_per_image: None,
+    };
+
+    // Only the top left tile of the first layer is resident.
+    queue.update_sparse_residency(
+        &[],
+        &[SparseTextureBinding {
+            texture: &texture,
+            mip_level: 0,
+            array_layer: 0,
+            origin: Origin3d::ZERO,
+            size: Extent3d {
+                width: 128,
+                height: 128,
+                depth_or_array_layers: 1,
+            },
+            resident: true,
+        }],
+    );
+    queue.write_texture(
+        texture.as_image_copy(),
+        &[0xff; 256 * 256 * 4],
+        layout,
+        size,
+    );
+
+    let readback = device.create_buffer(&buffer_descriptor(
+        256 * 256 * 4,
+        BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+    ));
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        TexelCopyBufferInfo {
+            buffer: &readback,
+            layout,
+        },
+        size,
+    );
+    let data = read_buffer(&device, &queue, encoder, &readback);
+
+    for (y, row) in data.chunks(256 * 4).enumerate() {
+        let (left, right) = row.split_at(128 * 4);
+        let expected = if y < 128 { 0xff } else { 0 };
+        assert!(left.iter().all(|&byte| byte == expected), "row {y}");
+        assert!(right.iter().all(|&byte| byte == 0), "row {y}");
+    }
+}
+
+#[test]
+fn update_sparse_texture_residency() {
+    let (device, queue) = device_with_sparse_resources();
+
+    let texture = device.create_texture(&texture_descriptor(TextureFormat::Rgba8Unorm, 256, 2));
+    let binding = |mip_level, array_layer, x, y, size, resident| SparseTextureBinding {
+        texture: &texture,
+        mip_level,
+        array_layer,
+        origin: Origin3d { x, y, z: 0 },
+        size: Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        resident,
+    };
+
+    valid(&device, || {
+        queue.update_sparse_residency(
+            &[],
+            &[
+                binding(0, 0, 0, 0, 256, true),
+                binding(0, 1, 128, 128, 128, true),
+                binding(1, 1, 0, 0, 128, true),
+            ],
+        )
+    });
+    queue.submit([]);
+    valid(&device, || {
+        queue.update_sparse_residency(&[], &[binding(0, 0, 128, 0, 128, false)])
+    });
+    queue.submit([]);
+
+    fail(
+        &device,
+        || queue.update_sparse_residency(&[], &[binding(0, 0, 64, 0, 128, true)]),
+        Some("is not aligned to the tile size"),
+    );
+    fail(
+        &device,
+        || queue.update_sparse_residency(&[], &[binding(1, 0, 128, 0, 128, true)]),
+        Some("is out of bounds of mip level 1"),
+    );
+    fail(
+        &device,
+        || queue.update_sparse_residency(&[], &[binding(2, 0, 0, 0, 128, true)]),
+        Some("Mip level 2 or array layer 0 is out of bounds"),
+    );
+    fail(
+        &device,
+        || queue.update_sparse_residency(&[], &[binding(0, 2, 0, 0, 128, true)]),
+        Some("Mip level 0 or array layer 2 is out of bounds"),
+    );
+}
+
+/// Residency can't be updated on additional queues, whose queue families may
+/// not support binding memory.
+#[test]
+fn update_sparse_residency_main_queue_only() {
+    let (device, _queue) = Device::noop(&DeviceDescriptor {
+        required_features: Features::SPARSE_RESOURCES | Features::MULTIPLE_QUEUES,
+        ..Default::default()
+    });
+    let compute = device
+        .create_queue(&QueueDescriptor {
+            label: None,
+            ty: QueueType::Compute,
+        })
+        .expect("the noop backend has a compute queue");
+
+    fail(
+        &device,
+        || compute.update_sparse_residency(&[], &[]),
+        Some("main queue"),
+    );
+}
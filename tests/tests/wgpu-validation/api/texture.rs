@@ -1,5 +1,7 @@
 //! Tests of [`wgpu::Texture`] and related.
 
+use wgpu_test::{fail, valid};
+
 /// Ensures that submitting a command buffer referencing an already destroyed texture
 /// results in an error.
 #[test]
@@ -54,3 +56,252 @@ fn destroyed_texture() {
 
     queue.submit([encoder.finish()]);
 }
+
+fn transient_texture(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("transient"),
+        size: wgpu::Extent3d {
+            width: 64,
+            height: 64,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TRANSIENT,
+        view_formats: &[],
+    })
+}
+
+/// Transient textures can't have any usage other than `RENDER_ATTACHMENT`.
+#[test]
+fn transient_texture_usages() {
+    let (device, _queue) = wgpu::Device::noop(&wgpu::DeviceDescriptor::default());
+
+    valid(&device, || {
+        transient_texture(&device, wgpu::TextureFormat::Rgba8Unorm, 4)
+    });
+    valid(&device, || {
+        transient_texture(&device, wgpu::TextureFormat::Depth24PlusStencil8, 1)
+    });
+
+    for usage in [
+        wgpu::TextureUsages::TRANSIENT,
+        wgpu::TextureUsages::TRANSIENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        wgpu::TextureUsages::TRANSIENT
+            | wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_SRC,
+    ] {
+        fail(
+            &device,
+            || {
+                device.create_texture(&wgpu::TextureDescriptor {
+                    label: None,
+                    size: wgpu::Extent3d {
+                        width: 64,
+                        height: 64,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    usage,
+                    view_formats: &[],
+                })
+            },
+            Some("Transient textures must have exactly"),
+        );
+    }
+}
+
+/// Render passes must clear transient color attachments and discard them, and
+/// can't resolve into them.
+#[test]
+fn transient_color_attachment() {
+    let (device, _queue) = wgpu::Device::noop(&wgpu::DeviceDescriptor::default());
+
+    let msaa = transient_texture(&device, wgpu::TextureFormat::Rgba8Unorm, 4)
+        .create_view(&wgpu::TextureViewDescriptor::default());
+    let resolve = device
+        .create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 64,
+                height: 64,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        })
+        .create_view(&wgpu::TextureViewDescriptor::default());
+
+    let pass = |view: &wgpu::TextureView,
+                resolve_target: Option<&wgpu::TextureView>,
+                ops: wgpu::Operations<wgpu::Color>| {
+        let mut encoder = device.create_command_encoder(&Default::default());
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                depth_slice: None,
+                resolve_target,
+                ops,
+            })],
+            ..Default::default()
+        });
+        encoder.finish()
+    };
+
+    valid(&device, || {
+        pass(
+            &msaa,
+            Some(&resolve),
+            wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: wgpu::StoreOp::Discard,
+            },
+        )
+    });
+    fail(
+        &device,
+        || {
+            pass(
+                &msaa,
+                Some(&resolve),
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Discard,
+                },
+            )
+        },
+        Some("must be cleared at the start of the pass and discarded at the end"),
+    );
+    fail(
+        &device,
+        || {
+            pass(
+                &msaa,
+                Some(&resolve),
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            )
+        },
+        Some("must be cleared at the start of the pass and discarded at the end"),
+    );
+
+    let single_sampled = transient_texture(&device, wgpu::TextureFormat::Rgba8Unorm, 1)
+        .create_view(&wgpu::TextureViewDescriptor::default());
+    let msaa = device
+        .create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 64,
+                height: 64,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 4,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        })
+        .create_view(&wgpu::TextureViewDescriptor::default());
+    fail(
+        &device,
+        || {
+            pass(
+                &msaa,
+                Some(&single_sampled),
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Discard,
+                },
+            )
+        },
+        Some("is transient and can't be resolved into"),
+    );
+}
+
+/// Both aspects of a transient depth-stencil attachment must be cleared and
+/// discarded.
+#[test]
+fn transient_depth_stencil_attachment() {
+    let (device, _queue) = wgpu::Device::noop(&wgpu::DeviceDescriptor::default());
+
+    let view = transient_texture(&device, wgpu::TextureFormat::Depth24PlusStencil8, 1)
+        .create_view(&wgpu::TextureViewDescriptor::default());
+
+    let pass = |depth_ops: Option<wgpu::Operations<f32>>,
+                stencil_ops: Option<wgpu::Operations<u32>>| {
+        let mut encoder = device.create_command_encoder(&Default::default());
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &view,
+                depth_ops,
+                stencil_ops,
+            }),
+            ..Default::default()
+        });
+        encoder.finish()
+    };
+
+    valid(&device, || {
+        pass(
+            Some(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(1.0),
+                store: wgpu::StoreOp::Discard,
+            }),
+            Some(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(0),
+                store: wgpu::StoreOp::Discard,
+            }),
+        )
+    });
+    // A read-only stencil aspect is loaded and stored.
+    fail(
+        &device,
+        || {
+            pass(
+                Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                None,
+            )
+        },
+        Some("must be cleared at the start of the pass and discarded at the end"),
+    );
+}
+
+/// Transient textures are only ever cleared by the render passes using them.
+#[test]
+fn clear_transient_texture() {
+    let (device, _queue) = wgpu::Device::noop(&wgpu::DeviceDescriptor {
+        required_features: wgpu::Features::CLEAR_TEXTURE,
+        ..Default::default()
+    });
+
+    let texture = transient_texture(&device, wgpu::TextureFormat::Rgba8Unorm, 1);
+
+    fail(
+        &device,
+        || {
+            let mut encoder = device.create_command_encoder(&Default::default());
+            encoder.clear_texture(&texture, &wgpu::ImageSubresourceRange::default());
+            encoder.finish()
+        },
+        Some("can not be cleared"),
+    );
+}
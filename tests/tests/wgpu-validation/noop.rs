@@ -19,7 +19,10 @@ fn device_is_available_when_requested() {
     let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
         backends: wgpu::Backends::NOOP,
         backend_options: wgpu::BackendOptions {
-            noop: wgpu::NoopBackendOptions { enable: true },
+            noop: wgpu::NoopBackendOptions {
+                enable: true,
+                ..Default::default()
+            },
             ..Default::default()
         },
         ..Default::default()
@@ -56,3 +59,335 @@ fn device_and_buffers() {
     device.poll(wgpu::PollType::Wait).unwrap();
     assert!(done2.load(Relaxed));
 }
+
+/// Submit `encoder`, then read back the contents of `buffer`.
+fn read_buffer(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    encoder: wgpu::CommandEncoder,
+    buffer: &wgpu::Buffer,
+) -> Vec<u8> {
+    queue.submit([encoder.finish()]);
+    buffer.map_async(wgpu::MapMode::Read, .., |result| result.unwrap());
+    device.poll(wgpu::PollType::Wait).unwrap();
+    let data = buffer.get_mapped_range(..).to_vec();
+    buffer.unmap();
+    data
+}
+
+#[test]
+fn texture_copies() {
+    let (device, queue) = wgpu::Device::noop(&wgpu::DeviceDescriptor::default());
+
+    let size = wgpu::Extent3d {
+        width: 3,
+        height: 2,
+        depth_or_array_layers: 2,
+    };
+    let descriptor = wgpu::TextureDescriptor {
+        label: None,
+        size,
+        mip_level_count: 2,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::R8Uint,
+        usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    };
+    let src = device.create_texture(&descriptor);
+    let dst = device.create_texture(&descriptor);
+
+    // Upload both layers of the first mip level, with padding between rows,
+    // and the second layer of the second.
+    #[rustfmt::skip]
+    let data = [
+        1, 2, 3, 0,
+        4, 5, 6, 0,
+        7, 8, 9, 0,
+        10, 11, 12, 0,
+    ];
+    queue.write_texture(
+        src.as_image_copy(),
+        &data,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(4),
+            rows_per_image: Some(2),
+        },
+        size,
+    );
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            mip_level: 1,
+            origin: wgpu::Origin3d { x: 0, y: 0, z: 1 },
+            ..src.as_image_copy()
+        },
+        &[13],
+        wgpu::TexelCopyBufferLayout::default(),
+        wgpu::Extent3d::default(),
+    );
+
+    let mut encoder = device.create_command_encoder(&Default::default());
+    // Copy the bottom right corner of the first mip level of each layer, and
+    // the second mip level of the second layer.
+    encoder.copy_texture_to_texture(
+        wgpu::TexelCopyTextureInfo {
+            origin: wgpu::Origin3d { x: 1, y: 1, z: 0 },
+            ..src.as_image_copy()
+        },
+        wgpu::TexelCopyTextureInfo {
+            origin: wgpu::Origin3d { x: 0, y: 0, z: 0 },
+            ..dst.as_image_copy()
+        },
+        wgpu::Extent3d {
+            width: 2,
+            height: 1,
+            depth_or_array_layers: 2,
+        },
+    );
+    encoder.copy_texture_to_texture(
+        wgpu::TexelCopyTextureInfo {
+            mip_level: 1,
+            origin: wgpu::Origin3d { x: 0, y: 0, z: 1 },
+            ..src.as_image_copy()
+        },
+        wgpu::TexelCopyTextureInfo {
+            mip_level: 1,
+            origin: wgpu::Origin3d { x: 0, y: 0, z: 1 },
+            ..dst.as_image_copy()
+        },
+        wgpu::Extent3d::default(),
+    );
+
+    // Read back the first row of the first mip level of each layer, and the
+    // second mip level of the second layer.
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 2 * 256 + 4,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    encoder.copy_texture_to_buffer(
+        dst.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &readback,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(256),
+                rows_per_image: Some(1),
+            },
+        },
+        wgpu::Extent3d {
+            width: 3,
+            height: 1,
+            depth_or_array_layers: 2,
+        },
+    );
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            mip_level: 1,
+            origin: wgpu::Origin3d { x: 0, y: 0, z: 1 },
+            ..dst.as_image_copy()
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &readback,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 2 * 256,
+                ..Default::default()
+            },
+        },
+        wgpu::Extent3d::default(),
+    );
+
+    let data = read_buffer(&device, &queue, encoder, &readback);
+    assert_eq!(data[..3], [5, 6, 0]);
+    assert_eq!(data[256..259], [11, 12, 0]);
+    assert_eq!(data[512], 13);
+}
+
+#[test]
+fn texture_clears() {
+    let (device, queue) = wgpu::Device::noop(&wgpu::DeviceDescriptor {
+        required_features: wgpu::Features::CLEAR_TEXTURE,
+        ..Default::default()
+    });
+
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width: 2,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::COPY_DST
+            | wgpu::TextureUsages::COPY_SRC
+            | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 8,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let copy_to_readback = |encoder: &mut wgpu::CommandEncoder| {
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: wgpu::TexelCopyBufferLayout::default(),
+            },
+            wgpu::Extent3d {
+                width: 2,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+    };
+
+    // Clearing a render pass attachment writes the clear color.
+    let view = texture.create_view(&Default::default());
+    let mut encoder = device.create_command_encoder(&Default::default());
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: &view,
+            depth_slice: None,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color {
+                    r: 1.0,
+                    g: 0.0,
+                    b: 0.5,
+                    a: 1.0,
+                }),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        ..Default::default()
+    });
+    copy_to_readback(&mut encoder);
+    let data = read_buffer(&device, &queue, encoder, &readback);
+    assert_eq!(data, [255, 0, 128, 255, 255, 0, 128, 255]);
+
+    // `clear_texture` zeroes it again.
+    let mut encoder = device.create_command_encoder(&Default::default());
+    encoder.clear_texture(&texture, &Default::default());
+    copy_to_readback(&mut encoder);
+    let data = read_buffer(&device, &queue, encoder, &readback);
+    assert_eq!(data, [0; 8]);
+}
+
+#[test]
+fn queries() {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::NOOP,
+        backend_options: wgpu::BackendOptions {
+            noop: wgpu::NoopBackendOptions {
+                enable: true,
+                occlusion_query_result: 42,
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    let adapter =
+        pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+            .unwrap();
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        required_features: wgpu::Features::TIMESTAMP_QUERY,
+        ..Default::default()
+    }))
+    .unwrap();
+
+    let occlusion = device.create_query_set(&wgpu::QuerySetDescriptor {
+        label: None,
+        ty: wgpu::QueryType::Occlusion,
+        count: 1,
+    });
+    let timestamps = device.create_query_set(&wgpu::QuerySetDescriptor {
+        label: None,
+        ty: wgpu::QueryType::Timestamp,
+        count: 4,
+    });
+    let resolve = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: wgpu::QUERY_RESOLVE_BUFFER_ALIGNMENT + 4 * 8,
+        usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 5 * 8,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d::default(),
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let view = target.create_view(&Default::default());
+
+    let mut encoder = device.create_command_encoder(&Default::default());
+    encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+        label: None,
+        timestamp_writes: Some(wgpu::ComputePassTimestampWrites {
+            query_set: &timestamps,
+            beginning_of_pass_write_index: Some(0),
+            end_of_pass_write_index: Some(1),
+        }),
+    });
+    let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: &view,
+            depth_slice: None,
+            resolve_target: None,
+            ops: wgpu::Operations::default(),
+        })],
+        timestamp_writes: Some(wgpu::RenderPassTimestampWrites {
+            query_set: &timestamps,
+            beginning_of_pass_write_index: Some(2),
+            end_of_pass_write_index: Some(3),
+        }),
+        occlusion_query_set: Some(&occlusion),
+        ..Default::default()
+    });
+    pass.begin_occlusion_query(0);
+    pass.end_occlusion_query();
+    drop(pass);
+    encoder.resolve_query_set(&occlusion, 0..1, &resolve, 0);
+    encoder.resolve_query_set(
+        &timestamps,
+        0..4,
+        &resolve,
+        wgpu::QUERY_RESOLVE_BUFFER_ALIGNMENT,
+    );
+    encoder.copy_buffer_to_buffer(&resolve, 0, &readback, 0, 8);
+    encoder.copy_buffer_to_buffer(
+        &resolve,
+        wgpu::QUERY_RESOLVE_BUFFER_ALIGNMENT,
+        &readback,
+        8,
+        4 * 8,
+    );
+
+    let data = read_buffer(&device, &queue, encoder, &readback);
+    let results: Vec<u64> = data
+        .chunks_exact(8)
+        .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+        .collect();
+    assert_eq!(results[0], 42);
+    assert!(
+        results[1..].is_sorted(),
+        "timestamps are not monotonic: {results:?}"
+    );
+}
@@ -8,6 +8,8 @@ fn main() {
                 all(feature = "fragile-send-sync-non-atomic-wasm", not(target_feature = "atomics"))
             )
         ) },
+        // Pipelines can be created asynchronously on worker threads.
+        threads: { all(feature = "std", not(target_arch = "wasm32")) },
         dx12: { all(target_os = "windows", feature = "dx12") },
         webgl: { all(target_arch = "wasm32", not(target_os = "emscripten"), feature = "webgl") },
         gles: { any(
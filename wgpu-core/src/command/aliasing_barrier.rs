@@ -0,0 +1,143 @@
+use alloc::{sync::Arc, vec::Vec};
+
+use thiserror::Error;
+use wgt::error::{ErrorType, WebGpuError};
+
+use crate::{
+    api_log,
+    command::{CommandEncoderError, EncoderStateError},
+    device::MissingFeatures,
+    global::Global,
+    id::{BufferId, CommandEncoderId, TextureId},
+    init_tracker::{BufferInitTracker, TextureInitTracker},
+    resource::{Buffer, Labeled, ParentDevice, ResourceErrorIdent, Texture, Trackable},
+    track::{PendingActivations, PlacedResourceId},
+};
+
+#[cfg(feature = "trace")]
+use crate::device::trace::Command as TraceCommand;
+
+/// The placed resources activated by the aliasing barriers of a command buffer.
+///
+/// Aliasing barriers take effect at the start of the command buffer they were
+/// recorded in, when it is submitted.
+#[derive(Debug, Default)]
+pub(crate) struct AliasingBarriers {
+    buffers: Vec<Arc<Buffer>>,
+    textures: Vec<Arc<Texture>>,
+}
+
+impl AliasingBarriers {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.buffers.is_empty() && self.textures.is_empty()
+    }
+
+    /// Adds the activations of the resources to the `pending` activations of
+    /// a submission.
+    pub(crate) fn activate(&self, pending: &mut PendingActivations) {
+        for buffer in self.buffers.iter() {
+            let placement = buffer.placement.as_ref().unwrap();
+            pending.push(
+                &placement.heap,
+                PlacedResourceId::Buffer(buffer.tracker_index()),
+                placement.range.clone(),
+            );
+        }
+        for texture in self.textures.iter() {
+            let placement = texture.placement.as_ref().unwrap();
+            pending.push(
+                &placement.heap,
+                PlacedResourceId::Texture(texture.tracker_index()),
+                placement.range.clone(),
+            );
+        }
+    }
+
+    /// Marks the contents of the resources as uninitialized, so that they are
+    /// cleared before the command buffer uses them.
+    pub(crate) fn discard_contents(&self) {
+        for buffer in self.buffers.iter() {
+            *buffer.initialization_status.write() =
+                BufferInitTracker::new(buffer.size.next_multiple_of(wgt::COPY_BUFFER_ALIGNMENT));
+        }
+        for texture in self.textures.iter() {
+            *texture.initialization_status.write() = TextureInitTracker::new(
+                texture.desc.mip_level_count,
+                texture.desc.array_layer_count(),
+            );
+        }
+    }
+}
+
+impl Global {
+    pub fn command_encoder_aliasing_barrier(
+        &self,
+        command_encoder_id: CommandEncoderId,
+        buffers: &[BufferId],
+        textures: &[TextureId],
+    ) -> Result<(), EncoderStateError> {
+        profiling::scope!("CommandEncoder::aliasing_barrier");
+        api_log!("CommandEncoder::aliasing_barrier {buffers:?} {textures:?}");
+
+        let hub = &self.hub;
+
+        let cmd_enc = hub.command_encoders.get(command_encoder_id);
+        let mut cmd_buf_data = cmd_enc.data.lock();
+        cmd_buf_data.record_with(|cmd_buf_data| -> Result<(), CommandEncoderError> {
+            #[cfg(feature = "trace")]
+            if let Some(ref mut list) = cmd_buf_data.commands {
+                list.push(TraceCommand::AliasingBarrier {
+                    buffers: buffers.to_vec(),
+                    textures: textures.to_vec(),
+                });
+            }
+
+            let device = &cmd_enc.device;
+            device.check_is_valid()?;
+            device
+                .require_features(wgt::Features::RESOURCE_HEAPS)
+                .map_err(AliasingBarrierError::from)?;
+
+            for &buffer_id in buffers {
+                let buffer = hub.buffers.get(buffer_id).get()?;
+                buffer.same_device_as(cmd_enc.as_ref())?;
+                if buffer.placement.is_none() {
+                    return Err(AliasingBarrierError::NotPlaced(buffer.error_ident()).into());
+                }
+                cmd_buf_data.aliasing_barriers.buffers.push(buffer);
+            }
+
+            for &texture_id in textures {
+                let texture = hub.textures.get(texture_id).get()?;
+                texture.same_device_as(cmd_enc.as_ref())?;
+                if texture.placement.is_none() {
+                    return Err(AliasingBarrierError::NotPlaced(texture.error_ident()).into());
+                }
+                cmd_buf_data.aliasing_barriers.textures.push(texture);
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Error encountered while attempting to perform [`Global::command_encoder_aliasing_barrier`].
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum AliasingBarrierError {
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error("{0} was not placed in a heap")]
+    NotPlaced(ResourceErrorIdent),
+}
+
+impl WebGpuError for AliasingBarrierError {
+    fn webgpu_error_type(&self) -> ErrorType {
+        let e: &dyn WebGpuError = match self {
+            Self::MissingFeatures(e) => e,
+
+            Self::NotPlaced(_) => return ErrorType::Validation,
+        };
+        e.webgpu_error_type()
+    }
+}
@@ -1,10 +1,15 @@
 use alloc::{boxed::Box, vec::Vec};
 
-use crate::lock::{rank, Mutex};
+use crate::{
+    lock::{rank, Mutex},
+    FastHashMap,
+};
 
 /// A pool of free [`wgpu_hal::CommandEncoder`]s, owned by a `Device`.
 ///
-/// Each encoder in this list is in the "closed" state.
+/// Each encoder in this list is in the "closed" state. Encoders are kept
+/// separately for each type of queue, since they can only be submitted to
+/// a queue of the type they were created for.
 ///
 /// Since a raw [`CommandEncoder`][ce] is itself a pool for allocating
 /// raw [`CommandBuffer`][cb]s, this is a pool of pools.
@@ -13,29 +18,33 @@ use crate::lock::{rank, Mutex};
 /// [ce]: hal::CommandEncoder
 /// [cb]: hal::Api::CommandBuffer
 pub(crate) struct CommandAllocator {
-    free_encoders: Mutex<Vec<Box<dyn hal::DynCommandEncoder>>>,
+    free_encoders: Mutex<FastHashMap<wgt::QueueType, Vec<Box<dyn hal::DynCommandEncoder>>>>,
 }
 
 impl CommandAllocator {
     pub(crate) fn new() -> Self {
         Self {
-            free_encoders: Mutex::new(rank::COMMAND_ALLOCATOR_FREE_ENCODERS, Vec::new()),
+            free_encoders: Mutex::new(
+                rank::COMMAND_ALLOCATOR_FREE_ENCODERS,
+                FastHashMap::default(),
+            ),
         }
     }
 
     /// Return a fresh [`wgpu_hal::CommandEncoder`] in the "closed" state.
     ///
-    /// If we have free encoders in the pool, take one of those. Otherwise,
-    /// create a new one on `device`.
+    /// If we have free encoders for `queue_type` in the pool, take one of
+    /// those. Otherwise, create a new one on `device` for `queue`.
     ///
     /// [`wgpu_hal::CommandEncoder`]: hal::CommandEncoder
     pub(crate) fn acquire_encoder(
         &self,
         device: &dyn hal::DynDevice,
         queue: &dyn hal::DynQueue,
+        queue_type: wgt::QueueType,
     ) -> Result<Box<dyn hal::DynCommandEncoder>, hal::DeviceError> {
         let mut free_encoders = self.free_encoders.lock();
-        match free_encoders.pop() {
+        match free_encoders.get_mut(&queue_type).and_then(Vec::pop) {
             Some(encoder) => Ok(encoder),
             None => unsafe {
                 let hal_desc = hal::CommandEncoderDescriptor { label: None, queue };
@@ -44,9 +53,13 @@ impl CommandAllocator {
         }
     }
 
-    /// Add `encoder` back to the free pool.
-    pub(crate) fn release_encoder(&self, encoder: Box<dyn hal::DynCommandEncoder>) {
+    /// Add `encoder` back to the free pool for `queue_type`.
+    pub(crate) fn release_encoder(
+        &self,
+        encoder: Box<dyn hal::DynCommandEncoder>,
+        queue_type: wgt::QueueType,
+    ) {
         let mut free_encoders = self.free_encoders.lock();
-        free_encoders.push(encoder);
+        free_encoders.entry(queue_type).or_default().push(encoder);
     }
 }
@@ -5,7 +5,7 @@ use core::ops::Range;
 use crate::device::trace::Command as TraceCommand;
 use crate::{
     api_log,
-    command::EncoderStateError,
+    command::{EncoderStateError, UnsupportedQueueType},
     device::{DeviceError, MissingFeatures},
     get_lowest_common_denom,
     global::Global,
@@ -79,6 +79,8 @@ whereas subesource range specified start {subresource_base_array_layer} and coun
     EncoderState(#[from] EncoderStateError),
     #[error(transparent)]
     InvalidResource(#[from] InvalidResourceError),
+    #[error(transparent)]
+    UnsupportedQueueType(#[from] UnsupportedQueueType),
 }
 
 impl WebGpuError for ClearError {
@@ -90,6 +92,7 @@ impl WebGpuError for ClearError {
             Self::Device(e) => e,
             Self::EncoderState(e) => e,
             Self::InvalidResource(e) => e,
+            Self::UnsupportedQueueType(e) => e,
             Self::NoValidTextureClearMode(..)
             | Self::UnalignedFillSize(..)
             | Self::UnalignedBufferOffset(..)
@@ -259,6 +262,7 @@ impl Global {
 
             let device = &cmd_enc.device;
             device.check_is_valid()?;
+            let queue_type = cmd_buf_data.encoder.queue_type;
             let (encoder, tracker) = cmd_buf_data.open_encoder_and_tracker()?;
 
             let snatch_guard = device.snatchable_lock.read();
@@ -274,6 +278,7 @@ impl Global {
                 device.zero_buffer.as_ref(),
                 &snatch_guard,
                 device.instance_flags,
+                queue_type,
             )?;
 
             Ok(())
@@ -290,6 +295,7 @@ pub(crate) fn clear_texture<T: TextureTrackerSetSingle>(
     zero_buffer: &dyn hal::DynBuffer,
     snatch_guard: &SnatchGuard<'_>,
     instance_flags: wgt::InstanceFlags,
+    queue_type: wgt::QueueType,
 ) -> Result<(), ClearError> {
     let dst_raw = dst_texture.try_raw(snatch_guard)?;
 
@@ -309,6 +315,15 @@ pub(crate) fn clear_texture<T: TextureTrackerSetSingle>(
         }
     };
 
+    // Anything but a buffer copy is cleared with render passes.
+    if clear_usage != wgt::TextureUses::COPY_DST && !queue_type.supports(wgt::QueueType::Graphics) {
+        return Err(UnsupportedQueueType {
+            queue_type,
+            required: wgt::QueueType::Graphics,
+        }
+        .into());
+    }
+
     let selector = TextureSelector {
         mips: range.mip_range.clone(),
         layers: range.layer_range.clone(),
@@ -9,6 +9,7 @@ use core::{fmt, str};
 
 use crate::command::{
     pass, CommandEncoder, DebugGroupError, EncoderStateError, PassStateError, TimestampWritesError,
+    UnsupportedQueueType,
 };
 use crate::resource::DestroyedResourceError;
 use crate::{binding_model::BindError, resource::RawResourceAccess};
@@ -181,6 +182,8 @@ pub enum ComputePassErrorInner {
     MissingFeatures(#[from] MissingFeatures),
     #[error(transparent)]
     MissingDownlevelFlags(#[from] MissingDownlevelFlags),
+    #[error(transparent)]
+    UnsupportedQueueType(#[from] UnsupportedQueueType),
     #[error("The compute pass has already been ended and no further commands can be recorded")]
     PassEnded,
     #[error(transparent)]
@@ -236,6 +239,7 @@ impl WebGpuError for ComputePassError {
             ComputePassErrorInner::QueryUse(e) => e,
             ComputePassErrorInner::MissingFeatures(e) => e,
             ComputePassErrorInner::MissingDownlevelFlags(e) => e,
+            ComputePassErrorInner::UnsupportedQueueType(e) => e,
             ComputePassErrorInner::InvalidResource(e) => e,
             ComputePassErrorInner::TimestampWrites(e) => e,
             ComputePassErrorInner::InvalidValuesOffset(e) => e,
@@ -424,7 +428,7 @@ impl Global {
     /// create a new pass, replay the commands and end the pass.
     ///
     /// # Panics
-    /// On any error.
+    /// If the encoder can't begin a pass.
     #[doc(hidden)]
     #[cfg(any(feature = "serde", feature = "replay"))]
     pub fn compute_pass_end_with_unresolved_commands(
@@ -437,9 +441,11 @@ impl Global {
         {
             let cmd_enc = self.hub.command_encoders.get(encoder_id);
             let mut cmd_buf_data = cmd_enc.data.lock();
-            let cmd_buf_data = cmd_buf_data.get_inner();
+            let list = cmd_buf_data
+                .get_inner()
+                .and_then(|cmd_buf_data| cmd_buf_data.commands.as_mut());
 
-            if let Some(ref mut list) = cmd_buf_data.commands {
+            if let Some(list) = list {
                 list.push(crate::device::trace::Command::RunComputePass {
                     base: BasePass {
                         label: base.label.clone(),
@@ -474,11 +480,18 @@ impl Global {
             panic!("{:?}", err);
         };
 
+        // Commands referring to invalid resources invalidate the pass, whose
+        // error is reported when the encoder is finished. An error beginning
+        // the pass takes precedence.
+        let (commands, error) =
+            match super::ComputeCommand::resolve_compute_command_ids(&self.hub, &commands) {
+                Ok(commands) => (commands, None),
+                Err(error) => (Vec::new(), Some(error)),
+            };
         compute_pass.base = BasePass {
             label,
-            error: None,
-            commands: super::ComputeCommand::resolve_compute_command_ids(&self.hub, &commands)
-                .unwrap(),
+            error: compute_pass.base.error.take().or(error),
+            commands,
             dynamic_offsets,
             string_data,
             push_constant_data,
@@ -526,6 +539,9 @@ impl Global {
             let base = &mut pass.base;
 
             let encoder = &mut cmd_buf_data.encoder;
+            encoder
+                .require_queue_type(wgt::QueueType::Compute)
+                .map_pass_err(pass_scope)?;
 
             // We automatically keep extending command buffers over time, and because
             // we want to insert a command buffer _before_ what we're about to record,
@@ -150,6 +150,7 @@ pub(crate) fn fixup_discarded_surfaces<InitIter: Iterator<Item = TextureSurfaceD
             device.zero_buffer.as_ref(),
             snatch_guard,
             device.instance_flags,
+            wgt::QueueType::Graphics,
         )
         .unwrap();
     }
@@ -263,7 +264,7 @@ impl BakedCommands {
         device_tracker: &mut DeviceTracker,
         device: &Device,
         snatch_guard: &SnatchGuard<'_>,
-    ) -> Result<(), DestroyedResourceError> {
+    ) -> Result<(), ClearError> {
         profiling::scope!("initialize_texture_memory");
 
         let mut ranges: Vec<TextureInitRange> = Vec::new();
@@ -306,12 +307,18 @@ impl BakedCommands {
                     device.zero_buffer.as_ref(),
                     snatch_guard,
                     device.instance_flags,
+                    self.encoder.queue_type,
                 );
 
                 // A Texture can be destroyed between the command recording
                 // and now, this is out of our control so we have to handle
-                // it gracefully.
-                if let Err(ClearError::DestroyedResource(e)) = clear_result {
+                // it gracefully. Textures that can only be cleared with render
+                // passes may also have been used on a queue that can't record
+                // those.
+                if let Err(
+                    e @ (ClearError::DestroyedResource(_) | ClearError::UnsupportedQueueType(_)),
+                ) = clear_result
+                {
                     return Err(e);
                 }
 
@@ -1,3 +1,4 @@
+mod aliasing_barrier;
 mod allocator;
 mod bind;
 mod bundle;
@@ -19,10 +20,12 @@ use alloc::{borrow::ToOwned as _, boxed::Box, string::String, sync::Arc, vec::Ve
 use core::mem::{self, ManuallyDrop};
 use core::ops;
 
+pub(crate) use self::aliasing_barrier::AliasingBarriers;
 pub(crate) use self::clear::clear_texture;
 pub use self::{
-    bundle::*, clear::ClearError, compute::*, compute_command::ComputeCommand, draw::*, query::*,
-    render::*, render_command::RenderCommand, transfer::*,
+    aliasing_barrier::AliasingBarrierError, bundle::*, clear::ClearError, compute::*,
+    compute_command::ComputeCommand, draw::*, query::*, render::*, render_command::RenderCommand,
+    transfer::*,
 };
 pub(crate) use allocator::CommandAllocator;
 
@@ -182,16 +185,18 @@ impl CommandEncoderStatus {
         }
     }
 
+    /// Returns the state of the encoder, unless it is invalid.
+    ///
+    /// This is only used when playing back a recorded trace, to record the
+    /// passes of the trace.
     #[cfg(feature = "trace")]
-    fn get_inner(&mut self) -> &mut CommandBufferMutable {
+    fn get_inner(&mut self) -> Option<&mut CommandBufferMutable> {
         match self {
-            Self::Locked(inner) | Self::Finished(inner) | Self::Recording(inner) => inner,
-            // This is unreachable because this function is only used when
-            // playing back a recorded trace. If only to avoid having to
-            // implement serialization for all the error types, we don't support
-            // storing the errors in a trace.
+            Self::Locked(inner) | Self::Finished(inner) | Self::Recording(inner) => Some(inner),
+            // A previous command of the trace was invalid. Invalid encoders
+            // don't record anything, which includes not tracing their passes.
+            Self::Error(_) => None,
             Self::Consumed => unreachable!("command encoder is consumed"),
-            Self::Error(_) => unreachable!("passes in a trace do not store errors"),
             Self::Transitioning => unreachable!(),
         }
     }
@@ -460,10 +465,34 @@ pub(crate) struct InnerCommandEncoder {
     /// [`wgpu_hal::CommandEncoder`]: hal::CommandEncoder
     pub(crate) is_open: bool,
 
+    /// The type of queue `raw` was created for.
+    ///
+    /// The recorded commands can only be submitted to a queue of this type,
+    /// and `raw` goes back to the [`CommandAllocator`] pool for it.
+    ///
+    /// [`CommandAllocator`]: crate::command::CommandAllocator
+    pub(crate) queue_type: wgt::QueueType,
+
     pub(crate) label: String,
 }
 
 impl InnerCommandEncoder {
+    /// Check that the queue type this encoder records for can execute
+    /// commands that need a `required` queue.
+    pub(crate) fn require_queue_type(
+        &self,
+        required: wgt::QueueType,
+    ) -> Result<(), UnsupportedQueueType> {
+        if self.queue_type.supports(required) {
+            Ok(())
+        } else {
+            Err(UnsupportedQueueType {
+                queue_type: self.queue_type,
+                required,
+            })
+        }
+    }
+
     /// Finish the current command buffer and insert it just before
     /// the last element in [`self.list`][l].
     ///
@@ -610,7 +639,9 @@ impl Drop for InnerCommandEncoder {
         }
         // SAFETY: We are in the Drop impl and we don't use self.raw anymore after this point.
         let raw = unsafe { ManuallyDrop::take(&mut self.raw) };
-        self.device.command_allocator.release_encoder(raw);
+        self.device
+            .command_allocator
+            .release_encoder(raw, self.queue_type);
     }
 }
 
@@ -623,6 +654,7 @@ pub(crate) struct BakedCommands {
     pub(crate) indirect_draw_validation_resources: crate::indirect_validation::DrawResources,
     buffer_memory_init_actions: Vec<BufferInitTrackerAction>,
     texture_memory_actions: CommandBufferTextureMemoryActions,
+    pub(crate) aliasing_barriers: AliasingBarriers,
 }
 
 /// The mutable state of a [`CommandBuffer`].
@@ -645,6 +677,10 @@ pub struct CommandBufferMutable {
     buffer_memory_init_actions: Vec<BufferInitTrackerAction>,
     texture_memory_actions: CommandBufferTextureMemoryActions,
 
+    /// The placed resources activated by aliasing barriers, which take effect
+    /// at the start of this command buffer.
+    pub(crate) aliasing_barriers: AliasingBarriers,
+
     pub(crate) pending_query_resets: QueryResetMap,
 
     as_actions: Vec<AsAction>,
@@ -676,6 +712,7 @@ impl CommandBufferMutable {
             indirect_draw_validation_resources: self.indirect_draw_validation_resources,
             buffer_memory_init_actions: self.buffer_memory_init_actions,
             texture_memory_actions: self.texture_memory_actions,
+            aliasing_barriers: self.aliasing_barriers,
         }
     }
 }
@@ -703,6 +740,7 @@ impl Drop for CommandBuffer {
 impl CommandEncoder {
     pub(crate) fn new(
         encoder: Box<dyn hal::DynCommandEncoder>,
+        queue_type: wgt::QueueType,
         device: &Arc<Device>,
         label: &Label,
     ) -> Self {
@@ -717,11 +755,13 @@ impl CommandEncoder {
                         list: Vec::new(),
                         device: device.clone(),
                         is_open: false,
+                        queue_type,
                         label: label.to_string(),
                     },
                     trackers: Tracker::new(),
                     buffer_memory_init_actions: Default::default(),
                     texture_memory_actions: Default::default(),
+                    aliasing_barriers: Default::default(),
                     pending_query_resets: QueryResetMap::new(),
                     as_actions: Default::default(),
                     temp_resources: Default::default(),
@@ -1062,6 +1102,8 @@ pub enum CommandEncoderError {
     #[error(transparent)]
     TransitionResources(#[from] TransitionResourcesError),
     #[error(transparent)]
+    AliasingBarrier(#[from] AliasingBarrierError),
+    #[error(transparent)]
     ComputePass(#[from] ComputePassError),
     #[error(transparent)]
     RenderPass(#[from] RenderPassError),
@@ -1112,6 +1154,7 @@ impl WebGpuError for CommandEncoderError {
             Self::Query(e) => e,
             Self::BuildAccelerationStructure(e) => e,
             Self::TransitionResources(e) => e,
+            Self::AliasingBarrier(e) => e,
             Self::ResourceUsage(e) => e,
             Self::ComputePass(e) => e,
             Self::RenderPass(e) => e,
@@ -1120,6 +1163,21 @@ impl WebGpuError for CommandEncoderError {
     }
 }
 
+/// A command needs a queue with more capabilities than the one its encoder
+/// was created for.
+#[derive(Clone, Debug, Error)]
+#[error("Encoder was created for a {queue_type:?} queue, which can't execute commands that need a {required:?} queue")]
+pub struct UnsupportedQueueType {
+    pub queue_type: wgt::QueueType,
+    pub required: wgt::QueueType,
+}
+
+impl WebGpuError for UnsupportedQueueType {
+    fn webgpu_error_type(&self) -> ErrorType {
+        ErrorType::Validation
+    }
+}
+
 #[derive(Clone, Debug, Error)]
 #[non_exhaustive]
 pub enum DebugGroupError {
@@ -4,7 +4,7 @@ use core::{iter, mem};
 #[cfg(feature = "trace")]
 use crate::device::trace::Command as TraceCommand;
 use crate::{
-    command::{CommandEncoder, EncoderStateError},
+    command::{CommandEncoder, EncoderStateError, UnsupportedQueueType},
     device::{DeviceError, MissingFeatures},
     global::Global,
     id,
@@ -110,6 +110,8 @@ pub enum QueryError {
     DestroyedResource(#[from] DestroyedResourceError),
     #[error(transparent)]
     InvalidResource(#[from] InvalidResourceError),
+    #[error(transparent)]
+    UnsupportedQueueType(#[from] UnsupportedQueueType),
 }
 
 impl WebGpuError for QueryError {
@@ -122,6 +124,7 @@ impl WebGpuError for QueryError {
             Self::Device(e) => e,
             Self::MissingFeature(e) => e,
             Self::DestroyedResource(e) => e,
+            Self::UnsupportedQueueType(e) => e,
         };
         e.webgpu_error_type()
     }
@@ -379,6 +382,9 @@ impl Global {
             cmd_enc
                 .device
                 .require_features(wgt::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS)?;
+            cmd_buf_data
+                .encoder
+                .require_queue_type(wgt::QueueType::Compute)?;
 
             let raw_encoder = cmd_buf_data.encoder.open()?;
 
@@ -419,6 +425,9 @@ impl Global {
             }
 
             cmd_enc.device.check_is_valid()?;
+            cmd_buf_data
+                .encoder
+                .require_queue_type(wgt::QueueType::Compute)?;
 
             if destination_offset % wgt::QUERY_RESOLVE_BUFFER_ALIGNMENT != 0 {
                 return Err(QueryError::Resolve(ResolveError::BufferOffsetAlignment));
@@ -210,6 +210,9 @@ impl Global {
             let device = &cmd_enc.device;
             device.check_is_valid()?;
             device.require_features(Features::EXPERIMENTAL_RAY_QUERY)?;
+            cmd_buf_data
+                .encoder
+                .require_queue_type(wgt::QueueType::Compute)?;
 
             let mut buf_storage = Vec::new();
             iter_blas(
@@ -12,7 +12,7 @@ use wgt::{
 use crate::command::{
     pass, pass_base, pass_try, validate_and_begin_occlusion_query,
     validate_and_begin_pipeline_statistics_query, DebugGroupError, EncoderStateError,
-    InnerCommandEncoder, PassStateError, TimestampWritesError,
+    InnerCommandEncoder, PassStateError, TimestampWritesError, UnsupportedQueueType,
 };
 use crate::pipeline::{RenderPipeline, VertexStep};
 use crate::resource::RawResourceAccess;
@@ -73,6 +73,12 @@ fn store_hal_ops(store: StoreOp) -> hal::AttachmentOps {
     }
 }
 
+/// Transient attachments have no contents outside of a pass, so they must be
+/// cleared at its start and discarded at its end.
+fn is_transient_ops<V>(load: LoadOp<V>, store: StoreOp) -> bool {
+    matches!(load, LoadOp::Clear(_)) && store == StoreOp::Discard
+}
+
 /// Describes an individual channel within a render pass, such as color, depth, or stencil.
 #[repr(C)]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -155,6 +161,10 @@ impl<V: Copy + Default> ResolvedPassChannel<V> {
     fn hal_ops(&self) -> hal::AttachmentOps {
         load_hal_ops(self.load_op()) | store_hal_ops(self.store_op())
     }
+
+    fn is_transient(&self) -> bool {
+        is_transient_ops(self.load_op(), self.store_op())
+    }
 }
 
 /// Describes a color attachment to a render pass.
@@ -714,6 +724,10 @@ pub enum RenderPassErrorInner {
         src: wgt::TextureFormat,
         dst: wgt::TextureFormat,
     },
+    #[error("The {location} is transient, so it must be cleared at the start of the pass and discarded at the end")]
+    InvalidTransientAttachmentOps { location: AttachmentErrorLocation },
+    #[error("The {location} is transient and can't be resolved into")]
+    TransientResolveTarget { location: AttachmentErrorLocation },
     #[error("Unable to clear non-present/read-only depth")]
     InvalidDepthOps,
     #[error("Unable to clear non-present/read-only stencil")]
@@ -723,6 +737,8 @@ pub enum RenderPassErrorInner {
     #[error(transparent)]
     MissingFeatures(#[from] MissingFeatures),
     #[error(transparent)]
+    UnsupportedQueueType(#[from] UnsupportedQueueType),
+    #[error(transparent)]
     MissingDownlevelFlags(#[from] MissingDownlevelFlags),
     #[error("Indirect buffer offset {0:?} is not a multiple of 4")]
     UnalignedIndirectBufferOffset(BufferAddress),
@@ -844,6 +860,7 @@ impl WebGpuError for RenderPassError {
             RenderPassErrorInner::EncoderState(e) => e,
             RenderPassErrorInner::DebugGroupError(e) => e,
             RenderPassErrorInner::MissingFeatures(e) => e,
+            RenderPassErrorInner::UnsupportedQueueType(e) => e,
             RenderPassErrorInner::MissingDownlevelFlags(e) => e,
             RenderPassErrorInner::RenderCommand(e) => e,
             RenderPassErrorInner::Draw(e) => e,
@@ -864,6 +881,8 @@ impl WebGpuError for RenderPassError {
             | RenderPassErrorInner::AttachmentSampleCountMismatch { .. }
             | RenderPassErrorInner::InvalidResolveSampleCounts { .. }
             | RenderPassErrorInner::MismatchedResolveTextureFormat { .. }
+            | RenderPassErrorInner::InvalidTransientAttachmentOps { .. }
+            | RenderPassErrorInner::TransientResolveTarget { .. }
             | RenderPassErrorInner::InvalidDepthOps
             | RenderPassErrorInner::InvalidStencilOps
             | RenderPassErrorInner::UnalignedIndirectBufferOffset(..)
@@ -1058,6 +1077,16 @@ impl RenderPassInfo {
 
             let ds_aspects = view.desc.aspects();
 
+            if view.parent.desc.usage.contains(TextureUsages::TRANSIENT)
+                && (ds_aspects.contains(hal::FormatAspects::DEPTH) && !at.depth.is_transient()
+                    || ds_aspects.contains(hal::FormatAspects::STENCIL)
+                        && !at.stencil.is_transient())
+            {
+                return Err(RenderPassErrorInner::InvalidTransientAttachmentOps {
+                    location: AttachmentErrorLocation::Depth,
+                });
+            }
+
             if !ds_aspects.contains(hal::FormatAspects::STENCIL)
                 || (at.stencil.load_op().eq_variant(at.depth.load_op())
                     && at.stencil.store_op() == at.depth.store_op())
@@ -1203,6 +1232,21 @@ impl RenderPassInfo {
                 ));
             }
 
+            if color_view
+                .parent
+                .desc
+                .usage
+                .contains(TextureUsages::TRANSIENT)
+                && !is_transient_ops(at.load_op, at.store_op)
+            {
+                return Err(RenderPassErrorInner::InvalidTransientAttachmentOps {
+                    location: AttachmentErrorLocation::Color {
+                        index,
+                        resolve: false,
+                    },
+                });
+            }
+
             if color_view.desc.dimension == TextureViewDimension::D3 {
                 if let Some(depth_slice) = at.depth_slice {
                     let mip = color_view.desc.range.base_mip_level;
@@ -1290,6 +1334,17 @@ impl RenderPassInfo {
                     resolve: true,
                 };
 
+                if resolve_view
+                    .parent
+                    .desc
+                    .usage
+                    .contains(TextureUsages::TRANSIENT)
+                {
+                    return Err(RenderPassErrorInner::TransientResolveTarget {
+                        location: resolve_location,
+                    });
+                }
+
                 let render_extent = resolve_view.render_extent.map_err(|reason| {
                     RenderPassErrorInner::TextureViewIsNotRenderable {
                         location: resolve_location,
@@ -1750,9 +1805,11 @@ impl Global {
         {
             let cmd_enc = self.hub.command_encoders.get(encoder_id);
             let mut cmd_buf_data = cmd_enc.data.lock();
-            let cmd_buf_data = cmd_buf_data.get_inner();
+            let list = cmd_buf_data
+                .get_inner()
+                .and_then(|cmd_buf_data| cmd_buf_data.commands.as_mut());
 
-            if let Some(ref mut list) = cmd_buf_data.commands {
+            if let Some(list) = list {
                 list.push(crate::device::trace::Command::RunRenderPass {
                     base: BasePass {
                         label: base.label.clone(),
@@ -1793,11 +1850,18 @@ impl Global {
             panic!("{:?}", err);
         };
 
+        // Commands referring to invalid resources invalidate the pass, whose
+        // error is reported when the encoder is finished. An error beginning
+        // the pass takes precedence.
+        let (commands, error) =
+            match super::RenderCommand::resolve_render_command_ids(&self.hub, &commands) {
+                Ok(commands) => (commands, None),
+                Err(error) => (Vec::new(), Some(error)),
+            };
         render_pass.base = BasePass {
             label,
-            error: None,
-            commands: super::RenderCommand::resolve_render_command_ids(&self.hub, &commands)
-                .unwrap(),
+            error: render_pass.base.error.take().or(error),
+            commands,
             dynamic_offsets,
             string_data,
             push_constant_data,
@@ -1841,6 +1905,10 @@ impl Global {
         cmd_buf_data.unlock_and_record(|cmd_buf_data| -> Result<(), RenderPassError> {
             let device = &cmd_enc.device;
             device.check_is_valid().map_pass_err(pass_scope)?;
+            cmd_buf_data
+                .encoder
+                .require_queue_type(wgt::QueueType::Graphics)
+                .map_pass_err(pass_scope)?;
             let snatch_guard = &device.snatchable_lock.read();
 
             let base = &mut pass.base;
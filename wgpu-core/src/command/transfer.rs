@@ -601,6 +601,7 @@ fn handle_texture_init(
 
     // In rare cases we may need to insert an init operation immediately onto the command buffer.
     if !immediate_inits.is_empty() {
+        let queue_type = cmd_buf_data.encoder.queue_type;
         let cmd_buf_raw = cmd_buf_data.encoder.open()?;
         for init in immediate_inits {
             clear_texture(
@@ -615,6 +616,7 @@ fn handle_texture_init(
                 device.zero_buffer.as_ref(),
                 snatch_guard,
                 device.instance_flags,
+                queue_type,
             )?;
         }
     }
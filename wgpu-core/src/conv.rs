@@ -158,7 +158,13 @@ pub fn map_texture_usage_for_texture(
 ) -> wgt::TextureUses {
     // Enforce having COPY_DST/DEPTH_STENCIL_WRITE/COLOR_TARGET otherwise we
     // wouldn't be able to initialize the texture.
+    let transient = if desc.usage.contains(wgt::TextureUsages::TRANSIENT) {
+        wgt::TextureUses::TRANSIENT
+    } else {
+        wgt::TextureUses::empty()
+    };
     map_texture_usage(desc.usage, desc.format.into(), format_features.flags)
+        | transient
         | if desc.format.is_depth_stencil_format() {
             wgt::TextureUses::DEPTH_STENCIL_WRITE
         } else if desc.usage.contains(wgt::TextureUsages::COPY_DST) {
@@ -11,7 +11,7 @@ use crate::{
     },
     command::{self, CommandEncoder},
     conv,
-    device::{bgl, life::WaitIdleError, DeviceError, DeviceLostClosure},
+    device::{bgl, life::WaitIdleError, queue::CreateQueueError, DeviceError, DeviceLostClosure},
     global::Global,
     id::{self, AdapterId, DeviceId, QueueId, SurfaceId},
     instance::{self, Adapter, Surface},
@@ -1158,6 +1158,38 @@ impl Global {
         }
     }
 
+    /// Derive bind group layout entries for the resources used by each
+    /// `(shader module, entry point name)` pair in `entry_points`.
+    ///
+    /// Bindings used by several entry points get a single entry whose
+    /// visibility includes all of their stages. See
+    /// [`Interface::derive_bind_group_layouts`] for how differing
+    /// uses are merged.
+    ///
+    /// [`Interface::derive_bind_group_layouts`]: crate::validation::Interface::derive_bind_group_layouts
+    pub fn device_derive_bind_group_layouts(
+        &self,
+        device_id: DeviceId,
+        entry_points: &[(id::ShaderModuleId, &str)],
+    ) -> Result<Vec<Vec<wgt::BindGroupLayoutEntry>>, pipeline::DeriveBindGroupLayoutsError> {
+        profiling::scope!("Device::derive_bind_group_layouts");
+
+        let hub = &self.hub;
+        let device = hub.devices.get(device_id);
+
+        let entry_points = {
+            let shader_module_guard = hub.shader_modules.read();
+            entry_points
+                .iter()
+                .map(|&(module_id, entry_point)| {
+                    Ok((shader_module_guard.get(module_id).get()?, entry_point))
+                })
+                .collect::<Result<Vec<_>, resource::InvalidResourceError>>()?
+        };
+
+        device.derive_bind_group_layouts(&entry_points)
+    }
+
     pub fn device_create_command_encoder(
         &self,
         device_id: DeviceId,
@@ -1190,6 +1222,22 @@ impl Global {
         (id, Some(error))
     }
 
+    pub fn device_create_queue(
+        &self,
+        device_id: DeviceId,
+        desc: &wgt::QueueDescriptor<Label>,
+        id_in: Option<QueueId>,
+    ) -> Result<QueueId, CreateQueueError> {
+        profiling::scope!("Device::create_queue");
+
+        let device = self.hub.devices.get(device_id);
+        let queue = device.create_queue(desc)?;
+
+        let id = self.hub.queues.prepare(id_in).assign(queue);
+        api_log!("Device::create_queue -> {id:?}");
+        Ok(id)
+    }
+
     pub fn command_encoder_drop(&self, command_encoder_id: id::CommandEncoderId) {
         profiling::scope!("CommandEncoder::drop");
         api_log!("CommandEncoder::drop {command_encoder_id:?}");
@@ -1332,6 +1380,180 @@ impl Global {
         }
     }
 
+    pub fn device_create_heap(
+        &self,
+        device_id: DeviceId,
+        desc: &resource::HeapDescriptor,
+        id_in: Option<id::HeapId>,
+    ) -> (id::HeapId, Option<resource::CreateHeapError>) {
+        profiling::scope!("Device::create_heap");
+
+        let hub = &self.hub;
+        let fid = hub.heaps.prepare(id_in);
+
+        let error = 'error: {
+            let device = self.hub.devices.get(device_id);
+
+            #[cfg(feature = "trace")]
+            if let Some(ref mut trace) = *device.trace.lock() {
+                trace.add(trace::Action::CreateHeap {
+                    id: fid.id(),
+                    desc: desc.clone(),
+                });
+            }
+
+            let heap = match device.create_heap(desc) {
+                Ok(heap) => heap,
+                Err(err) => break 'error err,
+            };
+
+            let id = fid.assign(Fallible::Valid(heap));
+            api_log!("Device::create_heap -> {id:?}");
+
+            return (id, None);
+        };
+
+        let id = fid.assign(Fallible::Invalid(Arc::new(desc.label.to_string())));
+        (id, Some(error))
+    }
+
+    pub fn heap_drop(&self, heap_id: id::HeapId) {
+        profiling::scope!("Heap::drop");
+        api_log!("Heap::drop {heap_id:?}");
+
+        let hub = &self.hub;
+
+        let _heap = hub.heaps.remove(heap_id);
+
+        #[cfg(feature = "trace")]
+        if let Ok(heap) = _heap.get() {
+            if let Some(trace) = heap.device.trace.lock().as_mut() {
+                trace.add(trace::Action::DestroyHeap(heap_id));
+            }
+        }
+    }
+
+    pub fn heap_create_buffer(
+        &self,
+        heap_id: id::HeapId,
+        offset: BufferAddress,
+        desc: &resource::BufferDescriptor,
+        id_in: Option<id::BufferId>,
+    ) -> (id::BufferId, Option<CreateBufferError>) {
+        profiling::scope!("Heap::create_buffer");
+
+        let hub = &self.hub;
+        let fid = hub.buffers.prepare(id_in);
+
+        let error = 'error: {
+            let heap = match hub.heaps.get(heap_id).get() {
+                Ok(heap) => heap,
+                Err(e) => break 'error resource::PlacementError::from(e).into(),
+            };
+            let device = &heap.device;
+
+            #[cfg(feature = "trace")]
+            if let Some(ref mut trace) = *device.trace.lock() {
+                trace.add(trace::Action::CreatePlacedBuffer {
+                    id: fid.id(),
+                    heap: heap_id,
+                    offset,
+                    desc: desc.clone(),
+                });
+            }
+
+            let buffer = match device.create_placed_buffer(&heap, offset, desc) {
+                Ok(buffer) => buffer,
+                Err(e) => break 'error e,
+            };
+
+            let id = fid.assign(Fallible::Valid(buffer));
+            api_log!(
+                "Heap::create_buffer({:?}, {offset}) -> {id:?}",
+                desc.label.as_deref().unwrap_or("")
+            );
+
+            return (id, None);
+        };
+
+        let id = fid.assign(Fallible::Invalid(Arc::new(desc.label.to_string())));
+        (id, Some(error))
+    }
+
+    pub fn heap_create_texture(
+        &self,
+        heap_id: id::HeapId,
+        offset: BufferAddress,
+        desc: &resource::TextureDescriptor,
+        id_in: Option<id::TextureId>,
+    ) -> (id::TextureId, Option<resource::CreateTextureError>) {
+        profiling::scope!("Heap::create_texture");
+
+        let hub = &self.hub;
+        let fid = hub.textures.prepare(id_in);
+
+        let error = 'error: {
+            let heap = match hub.heaps.get(heap_id).get() {
+                Ok(heap) => heap,
+                Err(e) => break 'error resource::PlacementError::from(e).into(),
+            };
+            let device = &heap.device;
+
+            #[cfg(feature = "trace")]
+            if let Some(ref mut trace) = *device.trace.lock() {
+                trace.add(trace::Action::CreatePlacedTexture {
+                    id: fid.id(),
+                    heap: heap_id,
+                    offset,
+                    desc: desc.clone(),
+                });
+            }
+
+            let texture = match device.create_placed_texture(&heap, offset, desc) {
+                Ok(texture) => texture,
+                Err(error) => break 'error error,
+            };
+
+            let id = fid.assign(Fallible::Valid(texture));
+            api_log!("Heap::create_texture({desc:?}, {offset}) -> {id:?}");
+
+            return (id, None);
+        };
+
+        let id = fid.assign(Fallible::Invalid(Arc::new(desc.label.to_string())));
+        (id, Some(error))
+    }
+
+    /// Describes the resources placed in a heap.
+    ///
+    /// The heap is reported as a single memory block.
+    pub fn heap_generate_allocation_report(
+        &self,
+        heap_id: id::HeapId,
+    ) -> Result<wgt::AllocatorReport, resource::InvalidResourceError> {
+        let heap = self.hub.heaps.get(heap_id).get()?;
+        let report = heap.aliasing.lock().generate_report(heap.size);
+        Ok(report)
+    }
+
+    pub fn device_get_buffer_memory_requirements(
+        &self,
+        device_id: DeviceId,
+        desc: &resource::BufferDescriptor,
+    ) -> Result<wgt::MemoryRequirements, CreateBufferError> {
+        let device = self.hub.devices.get(device_id);
+        device.get_buffer_memory_requirements(desc)
+    }
+
+    pub fn device_get_texture_memory_requirements(
+        &self,
+        device_id: DeviceId,
+        desc: &resource::TextureDescriptor,
+    ) -> Result<wgt::MemoryRequirements, resource::CreateTextureError> {
+        let device = self.hub.devices.get(device_id);
+        device.get_texture_memory_requirements(desc)
+    }
+
     pub fn device_create_render_pipeline(
         &self,
         device_id: DeviceId,
@@ -1358,6 +1580,63 @@ impl Global {
         self.device_create_general_render_pipeline(desc.clone().into(), device, fid)
     }
 
+    /// Creates a render pipeline on one of the pipeline worker threads of the
+    /// device, so shader translation and compilation don't block the caller.
+    ///
+    /// Once the pipeline is created, `callback` is called on the worker thread
+    /// with its ID and the error, if any. As with
+    /// [`Global::device_create_render_pipeline`], the ID refers to an invalid
+    /// pipeline if there is an error. Errors found while looking up the
+    /// resources `desc` refers to are reported before this returns, as are all
+    /// outcomes on platforms without threads.
+    pub fn device_create_render_pipeline_async(
+        &self,
+        device_id: DeviceId,
+        desc: &pipeline::RenderPipelineDescriptor,
+        id_in: Option<id::RenderPipelineId>,
+        callback: pipeline::CreateRenderPipelineCallback,
+    ) {
+        profiling::scope!("Device::create_render_pipeline_async");
+
+        let hub = &self.hub;
+
+        let fid = hub.render_pipelines.prepare(id_in);
+
+        let device = self.hub.devices.get(device_id);
+        #[cfg(feature = "trace")]
+        if let Some(ref mut trace) = *device.trace.lock() {
+            trace.add(trace::Action::CreateRenderPipeline {
+                id: fid.id(),
+                desc: desc.clone(),
+            });
+        }
+
+        let desc = pipeline::GeneralRenderPipelineDescriptor::from(desc.clone());
+        let label = desc.label.to_string();
+        let resolved = device
+            .check_is_valid()
+            .map_err(Into::into)
+            .and_then(|()| self.resolve_render_pipeline_descriptor(&desc));
+        let resolved = match resolved {
+            Ok(resolved) => resolved.into_owned(),
+            Err(e) => {
+                let id = fid.assign(Fallible::Invalid(Arc::new(label)));
+                callback(id, Some(e));
+                return;
+            }
+        };
+
+        let worker_device = Arc::clone(&device);
+        device.pipeline_workers.run(Box::new(move || {
+            let (id, error) = match worker_device.create_render_pipeline(resolved) {
+                Ok(pipeline) => (fid.assign(Fallible::Valid(pipeline)), None),
+                Err(e) => (fid.assign(Fallible::Invalid(Arc::new(label))), Some(e)),
+            };
+            api_log!("Device::create_render_pipeline_async -> {id:?}");
+            callback(id, error);
+        }));
+    }
+
     pub fn device_create_mesh_pipeline(
         &self,
         device_id: DeviceId,
@@ -1393,164 +1672,148 @@ impl Global {
     ) {
         profiling::scope!("Device::create_general_render_pipeline");
 
-        let hub = &self.hub;
-
         let error = 'error: {
             if let Err(e) = device.check_is_valid() {
                 break 'error e.into();
             }
 
-            let layout = desc
-                .layout
-                .map(|layout| hub.pipeline_layouts.get(layout).get())
-                .transpose();
-            let layout = match layout {
-                Ok(layout) => layout,
-                Err(e) => break 'error e.into(),
+            let desc = match self.resolve_render_pipeline_descriptor(&desc) {
+                Ok(desc) => desc,
+                Err(e) => break 'error e,
             };
 
-            let cache = desc
-                .cache
-                .map(|cache| hub.pipeline_caches.get(cache).get())
-                .transpose();
-            let cache = match cache {
-                Ok(cache) => cache,
-                Err(e) => break 'error e.into(),
+            let pipeline = match device.create_render_pipeline(desc) {
+                Ok(pair) => pair,
+                Err(e) => break 'error e,
             };
 
-            let vertex = match desc.vertex {
-                RenderPipelineVertexProcessor::Vertex(ref vertex) => {
+            let id = fid.assign(Fallible::Valid(pipeline));
+            api_log!("Device::create_render_pipeline -> {id:?}");
+
+            return (id, None);
+        };
+
+        let id = fid.assign(Fallible::Invalid(Arc::new(desc.label.to_string())));
+
+        (id, Some(error))
+    }
+
+    /// Looks up the resources referenced by `desc`.
+    fn resolve_render_pipeline_descriptor<'a>(
+        &self,
+        desc: &pipeline::GeneralRenderPipelineDescriptor<'a>,
+    ) -> Result<ResolvedGeneralRenderPipelineDescriptor<'a>, pipeline::CreateRenderPipelineError>
+    {
+        let hub = &self.hub;
+
+        let layout = desc
+            .layout
+            .map(|layout| hub.pipeline_layouts.get(layout).get())
+            .transpose()?;
+
+        let cache = desc
+            .cache
+            .map(|cache| hub.pipeline_caches.get(cache).get())
+            .transpose()?;
+
+        let vertex = match desc.vertex {
+            RenderPipelineVertexProcessor::Vertex(ref vertex) => {
+                let module = hub
+                    .shader_modules
+                    .get(vertex.stage.module)
+                    .get()
+                    .map_err(|e| pipeline::CreateRenderPipelineError::Stage {
+                        stage: wgt::ShaderStages::VERTEX,
+                        error: e.into(),
+                    })?;
+                let stage = ResolvedProgrammableStageDescriptor {
+                    module,
+                    entry_point: vertex.stage.entry_point.clone(),
+                    constants: vertex.stage.constants.clone(),
+                    zero_initialize_workgroup_memory: vertex.stage.zero_initialize_workgroup_memory,
+                };
+                RenderPipelineVertexProcessor::Vertex(ResolvedVertexState {
+                    stage,
+                    buffers: vertex.buffers.clone(),
+                })
+            }
+            RenderPipelineVertexProcessor::Mesh(ref task, ref mesh) => {
+                let task_module = if let Some(task) = task {
                     let module = hub
                         .shader_modules
-                        .get(vertex.stage.module)
+                        .get(task.stage.module)
                         .get()
                         .map_err(|e| pipeline::CreateRenderPipelineError::Stage {
                             stage: wgt::ShaderStages::VERTEX,
                             error: e.into(),
-                        });
-                    let module = match module {
-                        Ok(module) => module,
-                        Err(e) => break 'error e,
-                    };
-                    let stage = ResolvedProgrammableStageDescriptor {
+                        })?;
+                    let state = ResolvedProgrammableStageDescriptor {
                         module,
-                        entry_point: vertex.stage.entry_point.clone(),
-                        constants: vertex.stage.constants.clone(),
-                        zero_initialize_workgroup_memory: vertex
+                        entry_point: task.stage.entry_point.clone(),
+                        constants: task.stage.constants.clone(),
+                        zero_initialize_workgroup_memory: task
                             .stage
                             .zero_initialize_workgroup_memory,
                     };
-                    RenderPipelineVertexProcessor::Vertex(ResolvedVertexState {
-                        stage,
-                        buffers: vertex.buffers.clone(),
-                    })
-                }
-                RenderPipelineVertexProcessor::Mesh(ref task, ref mesh) => {
-                    let task_module = if let Some(task) = task {
-                        let module = hub
-                            .shader_modules
-                            .get(task.stage.module)
-                            .get()
-                            .map_err(|e| pipeline::CreateRenderPipelineError::Stage {
-                                stage: wgt::ShaderStages::VERTEX,
-                                error: e.into(),
-                            });
-                        let module = match module {
-                            Ok(module) => module,
-                            Err(e) => break 'error e,
-                        };
-                        let state = ResolvedProgrammableStageDescriptor {
-                            module,
-                            entry_point: task.stage.entry_point.clone(),
-                            constants: task.stage.constants.clone(),
-                            zero_initialize_workgroup_memory: task
-                                .stage
-                                .zero_initialize_workgroup_memory,
-                        };
-                        Some(ResolvedTaskState { stage: state })
-                    } else {
-                        None
-                    };
-                    let mesh_module =
-                        hub.shader_modules
-                            .get(mesh.stage.module)
-                            .get()
-                            .map_err(|e| pipeline::CreateRenderPipelineError::Stage {
-                                stage: wgt::ShaderStages::MESH,
-                                error: e.into(),
-                            });
-                    let mesh_module = match mesh_module {
-                        Ok(module) => module,
-                        Err(e) => break 'error e,
-                    };
-                    let mesh_stage = ResolvedProgrammableStageDescriptor {
-                        module: mesh_module,
-                        entry_point: mesh.stage.entry_point.clone(),
-                        constants: mesh.stage.constants.clone(),
-                        zero_initialize_workgroup_memory: mesh
-                            .stage
-                            .zero_initialize_workgroup_memory,
-                    };
-                    RenderPipelineVertexProcessor::Mesh(
-                        task_module,
-                        ResolvedMeshState { stage: mesh_stage },
-                    )
-                }
-            };
-
-            let fragment = if let Some(ref state) = desc.fragment {
-                let module = hub
+                    Some(ResolvedTaskState { stage: state })
+                } else {
+                    None
+                };
+                let mesh_module = hub
                     .shader_modules
-                    .get(state.stage.module)
+                    .get(mesh.stage.module)
                     .get()
                     .map_err(|e| pipeline::CreateRenderPipelineError::Stage {
-                        stage: wgt::ShaderStages::FRAGMENT,
+                        stage: wgt::ShaderStages::MESH,
                         error: e.into(),
-                    });
-                let module = match module {
-                    Ok(module) => module,
-                    Err(e) => break 'error e,
+                    })?;
+                let mesh_stage = ResolvedProgrammableStageDescriptor {
+                    module: mesh_module,
+                    entry_point: mesh.stage.entry_point.clone(),
+                    constants: mesh.stage.constants.clone(),
+                    zero_initialize_workgroup_memory: mesh.stage.zero_initialize_workgroup_memory,
                 };
-                let stage = ResolvedProgrammableStageDescriptor {
-                    module,
-                    entry_point: state.stage.entry_point.clone(),
-                    constants: state.stage.constants.clone(),
-                    zero_initialize_workgroup_memory: state.stage.zero_initialize_workgroup_memory,
-                };
-                Some(ResolvedFragmentState {
-                    stage,
-                    targets: state.targets.clone(),
-                })
-            } else {
-                None
-            };
-
-            let desc = ResolvedGeneralRenderPipelineDescriptor {
-                label: desc.label.clone(),
-                layout,
-                vertex,
-                primitive: desc.primitive,
-                depth_stencil: desc.depth_stencil.clone(),
-                multisample: desc.multisample,
-                fragment,
-                multiview: desc.multiview,
-                cache,
-            };
+                RenderPipelineVertexProcessor::Mesh(
+                    task_module,
+                    ResolvedMeshState { stage: mesh_stage },
+                )
+            }
+        };
 
-            let pipeline = match device.create_render_pipeline(desc) {
-                Ok(pair) => pair,
-                Err(e) => break 'error e,
+        let fragment = if let Some(ref state) = desc.fragment {
+            let module = hub
+                .shader_modules
+                .get(state.stage.module)
+                .get()
+                .map_err(|e| pipeline::CreateRenderPipelineError::Stage {
+                    stage: wgt::ShaderStages::FRAGMENT,
+                    error: e.into(),
+                })?;
+            let stage = ResolvedProgrammableStageDescriptor {
+                module,
+                entry_point: state.stage.entry_point.clone(),
+                constants: state.stage.constants.clone(),
+                zero_initialize_workgroup_memory: state.stage.zero_initialize_workgroup_memory,
             };
-
-            let id = fid.assign(Fallible::Valid(pipeline));
-            api_log!("Device::create_render_pipeline -> {id:?}");
-
-            return (id, None);
+            Some(ResolvedFragmentState {
+                stage,
+                targets: state.targets.clone(),
+            })
+        } else {
+            None
         };
 
-        let id = fid.assign(Fallible::Invalid(Arc::new(desc.label.to_string())));
-
-        (id, Some(error))
+        Ok(ResolvedGeneralRenderPipelineDescriptor {
+            label: desc.label.clone(),
+            layout,
+            vertex,
+            primitive: desc.primitive,
+            depth_stencil: desc.depth_stencil.clone(),
+            multisample: desc.multisample,
+            fragment,
+            multiview: desc.multiview,
+            cache,
+        })
     }
 
     /// Get an ID of one of the bind group layouts. The ID adds a refcount,
@@ -1632,41 +1895,9 @@ impl Global {
                 break 'error e.into();
             }
 
-            let layout = desc
-                .layout
-                .map(|layout| hub.pipeline_layouts.get(layout).get())
-                .transpose();
-            let layout = match layout {
-                Ok(layout) => layout,
-                Err(e) => break 'error e.into(),
-            };
-
-            let cache = desc
-                .cache
-                .map(|cache| hub.pipeline_caches.get(cache).get())
-                .transpose();
-            let cache = match cache {
-                Ok(cache) => cache,
-                Err(e) => break 'error e.into(),
-            };
-
-            let module = hub.shader_modules.get(desc.stage.module).get();
-            let module = match module {
-                Ok(module) => module,
-                Err(e) => break 'error e.into(),
-            };
-            let stage = ResolvedProgrammableStageDescriptor {
-                module,
-                entry_point: desc.stage.entry_point.clone(),
-                constants: desc.stage.constants.clone(),
-                zero_initialize_workgroup_memory: desc.stage.zero_initialize_workgroup_memory,
-            };
-
-            let desc = ResolvedComputePipelineDescriptor {
-                label: desc.label.clone(),
-                layout,
-                stage,
-                cache,
+            let desc = match self.resolve_compute_pipeline_descriptor(desc) {
+                Ok(desc) => desc,
+                Err(e) => break 'error e,
             };
 
             let pipeline = match device.create_compute_pipeline(desc) {
@@ -1685,6 +1916,91 @@ impl Global {
         (id, Some(error))
     }
 
+    /// Creates a compute pipeline on one of the pipeline worker threads of the
+    /// device, so shader translation and compilation don't block the caller.
+    ///
+    /// See [`Global::device_create_render_pipeline_async`] for when `callback`
+    /// is called.
+    pub fn device_create_compute_pipeline_async(
+        &self,
+        device_id: DeviceId,
+        desc: &pipeline::ComputePipelineDescriptor,
+        id_in: Option<id::ComputePipelineId>,
+        callback: pipeline::CreateComputePipelineCallback,
+    ) {
+        profiling::scope!("Device::create_compute_pipeline_async");
+
+        let hub = &self.hub;
+
+        let fid = hub.compute_pipelines.prepare(id_in);
+
+        let device = self.hub.devices.get(device_id);
+        #[cfg(feature = "trace")]
+        if let Some(ref mut trace) = *device.trace.lock() {
+            trace.add(trace::Action::CreateComputePipeline {
+                id: fid.id(),
+                desc: desc.clone(),
+            });
+        }
+
+        let label = desc.label.to_string();
+        let resolved = device
+            .check_is_valid()
+            .map_err(Into::into)
+            .and_then(|()| self.resolve_compute_pipeline_descriptor(desc));
+        let resolved = match resolved {
+            Ok(resolved) => resolved.into_owned(),
+            Err(e) => {
+                let id = fid.assign(Fallible::Invalid(Arc::new(label)));
+                callback(id, Some(e));
+                return;
+            }
+        };
+
+        let worker_device = Arc::clone(&device);
+        device.pipeline_workers.run(Box::new(move || {
+            let (id, error) = match worker_device.create_compute_pipeline(resolved) {
+                Ok(pipeline) => (fid.assign(Fallible::Valid(pipeline)), None),
+                Err(e) => (fid.assign(Fallible::Invalid(Arc::new(label))), Some(e)),
+            };
+            api_log!("Device::create_compute_pipeline_async -> {id:?}");
+            callback(id, error);
+        }));
+    }
+
+    /// Looks up the resources referenced by `desc`.
+    fn resolve_compute_pipeline_descriptor<'a>(
+        &self,
+        desc: &pipeline::ComputePipelineDescriptor<'a>,
+    ) -> Result<ResolvedComputePipelineDescriptor<'a>, pipeline::CreateComputePipelineError> {
+        let hub = &self.hub;
+
+        let layout = desc
+            .layout
+            .map(|layout| hub.pipeline_layouts.get(layout).get())
+            .transpose()?;
+
+        let cache = desc
+            .cache
+            .map(|cache| hub.pipeline_caches.get(cache).get())
+            .transpose()?;
+
+        let module = hub.shader_modules.get(desc.stage.module).get()?;
+        let stage = ResolvedProgrammableStageDescriptor {
+            module,
+            entry_point: desc.stage.entry_point.clone(),
+            constants: desc.stage.constants.clone(),
+            zero_initialize_workgroup_memory: desc.stage.zero_initialize_workgroup_memory,
+        };
+
+        Ok(ResolvedComputePipelineDescriptor {
+            label: desc.label.clone(),
+            layout,
+            stage,
+            cache,
+        })
+    }
+
     /// Get an ID of one of the bind group layouts. The ID adds a refcount,
     /// which needs to be released by calling `bind_group_layout_drop`.
     pub fn compute_pipeline_get_bind_group_layout(
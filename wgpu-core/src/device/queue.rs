@@ -1,4 +1,10 @@
-use alloc::{boxed::Box, string::ToString, sync::Arc, vec, vec::Vec};
+use alloc::{
+    boxed::Box,
+    string::ToString,
+    sync::{Arc, Weak},
+    vec,
+    vec::Vec,
+};
 use core::{
     iter,
     mem::{self, ManuallyDrop},
@@ -24,7 +30,7 @@ use crate::{
         CommandEncoderError, CopySide, TexelCopyTextureInfo, TransferError,
     },
     conv,
-    device::{DeviceError, WaitIdleError},
+    device::{DeviceError, MissingFeatures, WaitIdleError},
     get_lowest_common_denom,
     global::Global,
     hal_label,
@@ -41,15 +47,101 @@ use crate::{
     resource_log,
     scratch::ScratchBuffer,
     snatch::{SnatchGuard, Snatchable},
-    track::{self, Tracker, TrackerIndex},
+    track::{self, PendingActivations, Tracker, TrackerIndex},
     FastHashMap, SubmissionIndex,
 };
 use crate::{device::resource::CommandIndices, resource::RawResourceAccess};
 
+/// A compute or transfer queue opened along with its [`Device`].
+///
+/// See [`wgt::Features::MULTIPLE_QUEUES`]. The device keeps one of these for
+/// each additional hal queue, and hands it out to at most one [`Queue`] at a
+/// time through [`Device::create_queue`]. The fence lives here rather than in
+/// the [`Queue`], so that it stays valid for other queues that waited on it.
+pub(crate) struct AdditionalQueue {
+    pub(crate) ty: wgt::QueueType,
+    state: Mutex<AdditionalQueueState>,
+    pub(crate) fence: RwLock<ManuallyDrop<Box<dyn hal::DynFence>>>,
+    /// The index of the last successful submission to this queue.
+    ///
+    /// See [`Device::last_successful_submission_index`].
+    pub(crate) last_successful_submission_index: hal::AtomicFenceValue,
+}
+
+enum AdditionalQueueState {
+    /// No [`Queue`] is using the hal queue.
+    Free(Box<dyn hal::DynQueue>),
+    /// The hal queue belongs to this [`Queue`].
+    InUse(Weak<Queue>),
+}
+
+impl AdditionalQueue {
+    pub(crate) fn new(
+        ty: wgt::QueueType,
+        raw: Box<dyn hal::DynQueue>,
+        fence: Box<dyn hal::DynFence>,
+    ) -> Self {
+        Self {
+            ty,
+            state: Mutex::new(
+                rank::ADDITIONAL_QUEUE_STATE,
+                AdditionalQueueState::Free(raw),
+            ),
+            fence: RwLock::new(rank::ADDITIONAL_QUEUE_FENCE, ManuallyDrop::new(fence)),
+            last_successful_submission_index: hal::AtomicFenceValue::new(0),
+        }
+    }
+
+    /// The [`Queue`] currently using this slot, if any.
+    pub(crate) fn queue(&self) -> Option<Arc<Queue>> {
+        match *self.state.lock() {
+            AdditionalQueueState::Free(_) => None,
+            AdditionalQueueState::InUse(ref queue) => queue.upgrade(),
+        }
+    }
+
+    /// Take the hal queue out of the slot, if no [`Queue`] is using it.
+    ///
+    /// The slot counts as in use until the queue is given back with
+    /// [`Self::release`].
+    pub(crate) fn acquire(&self) -> Option<Box<dyn hal::DynQueue>> {
+        let mut state = self.state.lock();
+        match mem::replace(&mut *state, AdditionalQueueState::InUse(Weak::new())) {
+            AdditionalQueueState::Free(raw) => Some(raw),
+            in_use => {
+                *state = in_use;
+                None
+            }
+        }
+    }
+
+    pub(crate) fn set_queue(&self, queue: &Arc<Queue>) {
+        *self.state.lock() = AdditionalQueueState::InUse(Arc::downgrade(queue));
+    }
+
+    pub(crate) fn release(&self, raw: Box<dyn hal::DynQueue>) {
+        *self.state.lock() = AdditionalQueueState::Free(raw);
+    }
+
+    /// # Safety
+    ///
+    /// The fence must not be used after this.
+    pub(crate) unsafe fn destroy(&mut self, device: &dyn hal::DynDevice) {
+        let fence = unsafe { ManuallyDrop::take(&mut self.fence.write()) };
+        unsafe { device.destroy_fence(fence) };
+    }
+}
+
 pub struct Queue {
-    raw: Box<dyn hal::DynQueue>,
+    raw: ManuallyDrop<Box<dyn hal::DynQueue>>,
+    ty: wgt::QueueType,
     pub(crate) pending_writes: Mutex<PendingWrites>,
     life_tracker: Mutex<LifetimeTracker>,
+    /// The highest submission index known to have completed on this queue.
+    pub(crate) completed_submission_index: hal::AtomicFenceValue,
+    /// For each other queue, the highest submission index this queue has
+    /// waited for with [`Queue::wait_for_submission`].
+    pub(crate) waits: Mutex<FastHashMap<wgt::QueueType, SubmissionIndex>>,
     // The device needs to be dropped last (`Device.zero_buffer` might be referenced by the encoder in pending writes).
     pub(crate) device: Arc<Device>,
 }
@@ -58,11 +150,12 @@ impl Queue {
     pub(crate) fn new(
         device: Arc<Device>,
         raw: Box<dyn hal::DynQueue>,
+        ty: wgt::QueueType,
         instance_flags: wgt::InstanceFlags,
     ) -> Result<Self, DeviceError> {
         let pending_encoder = device
             .command_allocator
-            .acquire_encoder(device.raw(), raw.as_ref())
+            .acquire_encoder(device.raw(), raw.as_ref(), ty)
             .map_err(DeviceError::from_hal);
 
         let pending_encoder = match pending_encoder {
@@ -74,37 +167,44 @@ impl Queue {
 
         let mut pending_writes = PendingWrites::new(pending_encoder, instance_flags);
 
-        let zero_buffer = device.zero_buffer.as_ref();
-        pending_writes.activate();
-        unsafe {
-            pending_writes
-                .command_encoder
-                .transition_buffers(&[hal::BufferBarrier {
-                    buffer: zero_buffer,
-                    usage: hal::StateTransition {
-                        from: wgt::BufferUses::empty(),
-                        to: wgt::BufferUses::COPY_DST,
-                    },
-                }]);
-            pending_writes
-                .command_encoder
-                .clear_buffer(zero_buffer, 0..super::ZERO_BUFFER_SIZE);
-            pending_writes
-                .command_encoder
-                .transition_buffers(&[hal::BufferBarrier {
-                    buffer: zero_buffer,
-                    usage: hal::StateTransition {
-                        from: wgt::BufferUses::COPY_DST,
-                        to: wgt::BufferUses::COPY_SRC,
-                    },
-                }]);
+        // Additional queues are only created once the main queue has
+        // submitted this, see `Device::create_queue`.
+        if ty == wgt::QueueType::Graphics {
+            let zero_buffer = device.zero_buffer.as_ref();
+            pending_writes.activate();
+            unsafe {
+                pending_writes
+                    .command_encoder
+                    .transition_buffers(&[hal::BufferBarrier {
+                        buffer: zero_buffer,
+                        usage: hal::StateTransition {
+                            from: wgt::BufferUses::empty(),
+                            to: wgt::BufferUses::COPY_DST,
+                        },
+                    }]);
+                pending_writes
+                    .command_encoder
+                    .clear_buffer(zero_buffer, 0..super::ZERO_BUFFER_SIZE);
+                pending_writes
+                    .command_encoder
+                    .transition_buffers(&[hal::BufferBarrier {
+                        buffer: zero_buffer,
+                        usage: hal::StateTransition {
+                            from: wgt::BufferUses::COPY_DST,
+                            to: wgt::BufferUses::COPY_SRC,
+                        },
+                    }]);
+            }
         }
 
         Ok(Queue {
-            raw,
+            raw: ManuallyDrop::new(raw),
+            ty,
             device,
             pending_writes: Mutex::new(rank::QUEUE_PENDING_WRITES, pending_writes),
             life_tracker: Mutex::new(rank::QUEUE_LIFE_TRACKER, LifetimeTracker::new()),
+            completed_submission_index: hal::AtomicFenceValue::new(0),
+            waits: Mutex::new(rank::QUEUE_WAITS, FastHashMap::default()),
         })
     }
 
@@ -112,6 +212,63 @@ impl Queue {
         self.raw.as_ref()
     }
 
+    /// The type of work this queue can execute.
+    pub fn ty(&self) -> wgt::QueueType {
+        self.ty
+    }
+
+    /// The device's slot for this queue, if it's not the main queue.
+    fn additional(&self) -> Option<&AdditionalQueue> {
+        self.device.additional_queue(self.ty)
+    }
+
+    /// The fence signaled by submissions to this queue.
+    pub(crate) fn fence(&self) -> &RwLock<ManuallyDrop<Box<dyn hal::DynFence>>> {
+        match self.additional() {
+            Some(additional) => &additional.fence,
+            None => &self.device.fence,
+        }
+    }
+
+    /// The index of the last successful submission to this queue.
+    pub(crate) fn last_successful_submission_index(&self) -> &hal::AtomicFenceValue {
+        match self.additional() {
+            Some(additional) => &additional.last_successful_submission_index,
+            None => &self.device.last_successful_submission_index,
+        }
+    }
+
+    pub(crate) fn completed_submission_index(&self) -> SubmissionIndex {
+        self.completed_submission_index.load(Ordering::Acquire)
+    }
+
+    /// Check that this queue may use a resource whose queue ownership is
+    /// tracked by `owner`.
+    ///
+    /// Without [`wgt::Features::MULTIPLE_QUEUES`], the main queue is the only
+    /// one, so there is nothing to check.
+    pub(crate) fn check_queue_owner(
+        &self,
+        owner: &track::QueueOwner,
+        resource: &impl Labeled,
+    ) -> Result<(), track::QueueOwnershipError> {
+        if self
+            .device
+            .features
+            .contains(wgt::Features::MULTIPLE_QUEUES)
+        {
+            owner.check(self, resource)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The highest submission index on the queue of type `ty` that this queue
+    /// has waited for.
+    pub(crate) fn waited_for(&self, ty: wgt::QueueType) -> SubmissionIndex {
+        self.waits.lock().get(&ty).copied().unwrap_or(0)
+    }
+
     #[track_caller]
     pub(crate) fn lock_life<'a>(&'a self) -> MutexGuard<'a, LifetimeTracker> {
         self.life_tracker.lock()
@@ -127,6 +284,9 @@ impl Queue {
         Vec<BlasCompactReadyPendingClosure>,
         bool,
     ) {
+        self.completed_submission_index
+            .fetch_max(submission_index, Ordering::AcqRel);
+
         let mut life_tracker = self.lock_life();
         let submission_closures = life_tracker.triage_submissions(submission_index);
 
@@ -159,11 +319,10 @@ impl Drop for Queue {
         resource_log!("Drop {}", self.error_ident());
 
         let last_successful_submission_index = self
-            .device
-            .last_successful_submission_index
+            .last_successful_submission_index()
             .load(Ordering::Acquire);
 
-        let fence = self.device.fence.read();
+        let fence = self.fence().read();
 
         // Try waiting on the last submission using the following sequence of timeouts
         let timeouts_in_ms = [100, 200, 400, 800, 1600, 3200];
@@ -248,6 +407,13 @@ impl Drop for Queue {
         };
 
         closures.fire();
+
+        // SAFETY: We are in the Drop impl and we don't use self.raw anymore after this point.
+        let raw = unsafe { ManuallyDrop::take(&mut self.raw) };
+        // Give additional queues back to the device, so they can be created again.
+        if let Some(additional) = self.additional() {
+            additional.release(raw);
+        }
     }
 }
 
@@ -330,6 +496,9 @@ pub(crate) struct PendingWrites {
     dst_buffers: FastHashMap<TrackerIndex, Arc<Buffer>>,
     dst_textures: FastHashMap<TrackerIndex, Arc<Texture>>,
     copied_blas_s: FastHashMap<TrackerIndex, Arc<Blas>>,
+    /// Residency changes to make before the next submission, in order.
+    sparse_buffers: Vec<wgt::SparseBufferBinding<Arc<Buffer>>>,
+    sparse_textures: Vec<wgt::SparseTextureBinding<Arc<Texture>>>,
     instance_flags: wgt::InstanceFlags,
 }
 
@@ -345,6 +514,8 @@ impl PendingWrites {
             dst_buffers: FastHashMap::default(),
             dst_textures: FastHashMap::default(),
             copied_blas_s: FastHashMap::default(),
+            sparse_buffers: Vec::new(),
+            sparse_textures: Vec::new(),
             instance_flags,
         }
     }
@@ -397,7 +568,7 @@ impl PendingWrites {
             self.is_recording = false;
 
             let new_encoder = command_allocator
-                .acquire_encoder(device.raw(), queue.raw())
+                .acquire_encoder(device.raw(), queue.raw(), queue.ty)
                 .map_err(|e| device.handle_hal_error(e))?;
 
             let encoder = EncoderInFlight {
@@ -406,6 +577,7 @@ impl PendingWrites {
                     list: vec![cmd_buf],
                     device: device.clone(),
                     is_open: false,
+                    queue_type: queue.ty,
                     label: "(wgpu internal) PendingWrites command encoder".into(),
                 },
                 trackers: Tracker::new(),
@@ -426,6 +598,55 @@ impl PendingWrites {
         }
     }
 
+    /// Apply the residency changes made by [`Queue::update_sparse_residency`]
+    /// since the last submission.
+    ///
+    /// This must be called before submitting the pending writes, which zero
+    /// the newly resident memory.
+    fn bind_sparse_memory(
+        &mut self,
+        queue: &Queue,
+        snatch_guard: &SnatchGuard,
+    ) -> Result<(), DeviceError> {
+        if self.sparse_buffers.is_empty() && self.sparse_textures.is_empty() {
+            return Ok(());
+        }
+        let sparse_buffers = mem::take(&mut self.sparse_buffers);
+        let sparse_textures = mem::take(&mut self.sparse_textures);
+
+        // Resources destroyed since then have had their memory freed already.
+        let buffer_binds = sparse_buffers
+            .iter()
+            .filter_map(|binding| {
+                Some(hal::SparseBufferBind {
+                    buffer: binding.buffer.raw(snatch_guard)?,
+                    range: binding.offset..binding.offset + binding.size,
+                    resident: binding.resident,
+                })
+            })
+            .collect::<Vec<_>>();
+        let texture_binds = sparse_textures
+            .iter()
+            .filter_map(|binding| {
+                Some(hal::SparseTextureBind {
+                    texture: binding.texture.raw(snatch_guard)?,
+                    mip_level: binding.mip_level,
+                    array_layer: binding.array_layer,
+                    origin: binding.origin,
+                    size: binding.size,
+                    resident: binding.resident,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        unsafe {
+            queue
+                .raw()
+                .bind_sparse_memory(&buffer_binds, &texture_binds)
+        }
+        .map_err(|e| queue.device.handle_hal_error(e))
+    }
+
     pub fn activate(&mut self) -> &mut dyn hal::DynCommandEncoder {
         if !self.is_recording {
             unsafe {
@@ -465,6 +686,10 @@ pub enum QueueWriteError {
     DestroyedResource(#[from] DestroyedResourceError),
     #[error(transparent)]
     InvalidResource(#[from] InvalidResourceError),
+    #[error(transparent)]
+    QueueOwnership(#[from] track::QueueOwnershipError),
+    #[error(transparent)]
+    InactiveResource(#[from] track::InactiveResourceError),
 }
 
 impl WebGpuError for QueueWriteError {
@@ -475,6 +700,8 @@ impl WebGpuError for QueueWriteError {
             Self::MemoryInitFailure(e) => e,
             Self::DestroyedResource(e) => e,
             Self::InvalidResource(e) => e,
+            Self::QueueOwnership(e) => e,
+            Self::InactiveResource(e) => e,
         };
         e.webgpu_error_type()
     }
@@ -497,6 +724,21 @@ pub enum QueueSubmitError {
     CommandEncoder(#[from] CommandEncoderError),
     #[error(transparent)]
     ValidateAsActionsError(#[from] crate::ray_tracing::ValidateAsActionsError),
+    #[error(transparent)]
+    MemoryInitFailure(#[from] ClearError),
+    #[error(transparent)]
+    QueueOwnership(#[from] track::QueueOwnershipError),
+    #[error(transparent)]
+    InactiveResource(#[from] track::InactiveResourceError),
+    #[error(
+        "Command buffer was recorded for a {encoder:?} queue, but submitted to a {queue:?} queue"
+    )]
+    WrongQueueType {
+        encoder: wgt::QueueType,
+        queue: wgt::QueueType,
+    },
+    #[error("{0} can only be used on the device's main queue")]
+    MainQueueOnly(ResourceErrorIdent),
 }
 
 impl WebGpuError for QueueSubmitError {
@@ -507,14 +749,139 @@ impl WebGpuError for QueueSubmitError {
             Self::CommandEncoder(e) => e,
             Self::ValidateAsActionsError(e) => e,
             Self::InvalidResource(e) => e,
-            Self::DestroyedResource(_) | Self::BufferStillMapped(_) => {
-                return ErrorType::Validation
-            }
+            Self::MemoryInitFailure(e) => e,
+            Self::QueueOwnership(e) => e,
+            Self::InactiveResource(e) => e,
+            Self::DestroyedResource(_)
+            | Self::BufferStillMapped(_)
+            | Self::WrongQueueType { .. }
+            | Self::MainQueueOnly(_) => return ErrorType::Validation,
+        };
+        e.webgpu_error_type()
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum CreateQueueError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error("The device's main queue is its only graphics queue")]
+    Graphics,
+    #[error("The device has no {0:?} queue")]
+    Unavailable(wgt::QueueType),
+    #[error("The device's {0:?} queue is already in use")]
+    InUse(wgt::QueueType),
+    #[error(transparent)]
+    Submit(#[from] QueueSubmitError),
+}
+
+impl WebGpuError for CreateQueueError {
+    fn webgpu_error_type(&self) -> ErrorType {
+        let e: &dyn WebGpuError = match self {
+            Self::Device(e) => e,
+            Self::MissingFeatures(e) => e,
+            Self::Submit(e) => e,
+            Self::Graphics | Self::Unavailable(_) | Self::InUse(_) => return ErrorType::Validation,
+        };
+        e.webgpu_error_type()
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum QueueSparseResidencyError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error(transparent)]
+    DestroyedResource(#[from] DestroyedResourceError),
+    #[error(transparent)]
+    InvalidResource(#[from] InvalidResourceError),
+    #[error(transparent)]
+    QueueOwnership(#[from] track::QueueOwnershipError),
+    #[error("Sparse residency can only be updated on the device's main queue")]
+    MainQueueOnly,
+    #[error("{0} was not created with the `SPARSE` usage")]
+    NotSparse(ResourceErrorIdent),
+    #[error("Range {offset}..{offset}+{size} of {buffer} is not aligned to `SPARSE_PAGE_SIZE`")]
+    UnalignedBufferRange {
+        buffer: ResourceErrorIdent,
+        offset: wgt::BufferAddress,
+        size: wgt::BufferAddress,
+    },
+    #[error("Range {offset}..{offset}+{size} is out of bounds of {buffer} of size {buffer_size}")]
+    BufferRangeOutOfBounds {
+        buffer: ResourceErrorIdent,
+        offset: wgt::BufferAddress,
+        size: wgt::BufferAddress,
+        buffer_size: wgt::BufferAddress,
+    },
+    #[error("Mip level {mip_level} or array layer {array_layer} is out of bounds of {texture}")]
+    InvalidSubresource {
+        texture: ResourceErrorIdent,
+        mip_level: u32,
+        array_layer: u32,
+    },
+    #[error("Region at {origin:?} of size {size:?} of {texture} is not aligned to the tile size {tile_size:?}")]
+    UnalignedTextureRegion {
+        texture: ResourceErrorIdent,
+        origin: wgt::Origin3d,
+        size: wgt::Extent3d,
+        tile_size: wgt::Extent3d,
+    },
+    #[error("Region at {origin:?} of size {size:?} is out of bounds of mip level {mip_level} of {texture}")]
+    TextureRegionOutOfBounds {
+        texture: ResourceErrorIdent,
+        mip_level: u32,
+        origin: wgt::Origin3d,
+        size: wgt::Extent3d,
+    },
+}
+
+impl WebGpuError for QueueSparseResidencyError {
+    fn webgpu_error_type(&self) -> ErrorType {
+        let e: &dyn WebGpuError = match self {
+            Self::Device(e) => e,
+            Self::MissingFeatures(e) => e,
+            Self::DestroyedResource(e) => e,
+            Self::InvalidResource(e) => e,
+            Self::QueueOwnership(e) => e,
+            Self::MainQueueOnly
+            | Self::NotSparse(_)
+            | Self::UnalignedBufferRange { .. }
+            | Self::BufferRangeOutOfBounds { .. }
+            | Self::InvalidSubresource { .. }
+            | Self::UnalignedTextureRegion { .. }
+            | Self::TextureRegionOutOfBounds { .. } => return ErrorType::Validation,
         };
         e.webgpu_error_type()
     }
 }
 
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum QueueWaitError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error("A queue can't wait for its own submissions")]
+    SameQueue,
+    #[error("Tried to wait for a submission index ({0}) that has not been returned by a successful submission to the other queue (last successful submission: {1})")]
+    WrongSubmissionIndex(SubmissionIndex, SubmissionIndex),
+}
+
+impl WebGpuError for QueueWaitError {
+    fn webgpu_error_type(&self) -> ErrorType {
+        match self {
+            Self::Device(e) => e.webgpu_error_type(),
+            Self::SameQueue | Self::WrongSubmissionIndex(..) => ErrorType::Validation,
+        }
+    }
+}
+
 //TODO: move out common parts of write_xxx.
 
 impl Queue {
@@ -667,6 +1034,8 @@ impl Queue {
         buffer_offset: u64,
     ) -> Result<(), QueueWriteError> {
         self.device.check_is_valid()?;
+        self.check_queue_owner(&buffer.queue_owner, buffer.as_ref())?;
+        buffer.check_active(None)?;
 
         let transition = {
             let mut trackers = self.device.trackers.lock();
@@ -702,6 +1071,7 @@ impl Queue {
         }
 
         pending_writes.insert_buffer(&buffer);
+        buffer.queue_owner.set(self.ty, None);
 
         // Ensure the overwritten bytes are marked as initialized so
         // they don't need to be nulled prior to mapping or binding.
@@ -736,6 +1106,8 @@ impl Queue {
         };
 
         self.same_device_as(dst.as_ref())?;
+        self.check_queue_owner(&dst.queue_owner, dst.as_ref())?;
+        dst.check_active(None)?;
 
         dst.check_usage(wgt::TextureUsages::COPY_DST)
             .map_err(TransferError::MissingTextureUsage)?;
@@ -827,6 +1199,7 @@ impl Queue {
                         self.device.zero_buffer.as_ref(),
                         &snatch_guard,
                         self.device.instance_flags,
+                        self.ty,
                     )
                     .map_err(QueueWriteError::from)?;
                 }
@@ -940,6 +1313,7 @@ impl Queue {
 
         pending_writes.consume(staging_buffer);
         pending_writes.insert_texture(&dst);
+        dst.queue_owner.set(self.ty, None);
 
         Ok(())
     }
@@ -1080,6 +1454,7 @@ impl Queue {
                         self.device.zero_buffer.as_ref(),
                         &self.device.snatchable_lock.read(),
                         self.device.instance_flags,
+                        self.ty,
                     )
                     .map_err(QueueWriteError::from)?;
                 }
@@ -1141,6 +1516,267 @@ impl Queue {
         Ok(())
     }
 
+    pub fn update_sparse_residency(
+        &self,
+        buffers: Vec<wgt::SparseBufferBinding<Fallible<Buffer>>>,
+        textures: Vec<wgt::SparseTextureBinding<Fallible<Texture>>>,
+    ) -> Result<(), QueueSparseResidencyError> {
+        profiling::scope!("Queue::update_sparse_residency");
+        api_log!("Queue::update_sparse_residency");
+
+        self.device.check_is_valid()?;
+        self.device
+            .require_features(wgt::Features::SPARSE_RESOURCES)?;
+        if self.ty != wgt::QueueType::Graphics {
+            return Err(QueueSparseResidencyError::MainQueueOnly);
+        }
+
+        let snatch_guard = self.device.snatchable_lock.read();
+
+        // Validate everything up front, so that an error leaves the residency
+        // of every resource unchanged.
+        let buffers = buffers
+            .into_iter()
+            .map(|binding| {
+                let binding = wgt::SparseBufferBinding {
+                    buffer: binding.buffer.get()?,
+                    offset: binding.offset,
+                    size: binding.size,
+                    resident: binding.resident,
+                };
+                self.validate_sparse_buffer_binding(&binding, &snatch_guard)?;
+                Ok(binding)
+            })
+            .collect::<Result<Vec<_>, QueueSparseResidencyError>>()?;
+        let textures = textures
+            .into_iter()
+            .map(|binding| {
+                let binding = wgt::SparseTextureBinding {
+                    texture: binding.texture.get()?,
+                    mip_level: binding.mip_level,
+                    array_layer: binding.array_layer,
+                    origin: binding.origin,
+                    size: binding.size,
+                    resident: binding.resident,
+                };
+                self.validate_sparse_texture_binding(&binding, &snatch_guard)?;
+                Ok(binding)
+            })
+            .collect::<Result<Vec<_>, QueueSparseResidencyError>>()?;
+
+        let mut pending_writes = self.pending_writes.lock();
+        let zero_buffer = self.device.zero_buffer.as_ref();
+
+        // The memory of newly resident pages and tiles is zeroed by the
+        // pending writes, which run after the memory is bound.
+        for binding in buffers {
+            let buffer = &binding.buffer;
+            let first_page = (binding.offset / wgt::SPARSE_PAGE_SIZE) as usize;
+            let page_count = (binding.size / wgt::SPARSE_PAGE_SIZE) as usize;
+            let newly_resident = buffer
+                .sparse_residency
+                .as_ref()
+                .unwrap()
+                .lock()
+                .update(first_page..first_page + page_count, binding.resident);
+
+            if !newly_resident.is_empty() {
+                let dst_raw = buffer.try_raw(&snatch_guard)?;
+                let transition = {
+                    let mut trackers = self.device.trackers.lock();
+                    trackers
+                        .buffers
+                        .set_single(buffer, wgt::BufferUses::COPY_DST)
+                };
+                let barriers = transition
+                    .map(|pending| pending.into_hal(buffer, &snatch_guard))
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                let encoder = pending_writes.activate();
+                unsafe {
+                    encoder.transition_buffers(&barriers);
+                    for pages in newly_resident {
+                        encoder.clear_buffer(
+                            dst_raw,
+                            pages.start as u64 * wgt::SPARSE_PAGE_SIZE
+                                ..pages.end as u64 * wgt::SPARSE_PAGE_SIZE,
+                        );
+                    }
+                }
+                pending_writes.insert_buffer(buffer);
+                buffer.queue_owner.set(self.ty, None);
+            }
+
+            pending_writes.sparse_buffers.push(binding);
+        }
+
+        for binding in textures {
+            let texture = &binding.texture;
+            let newly_resident = texture.sparse_residency.as_ref().unwrap().lock().update(
+                binding.mip_level,
+                binding.array_layer,
+                binding.origin,
+                binding.size,
+                binding.resident,
+            );
+
+            if !newly_resident.is_empty() {
+                let dst_raw = texture.try_raw(&snatch_guard)?;
+                let format = texture.desc.format;
+                let tile_size = format.sparse_tile_extent().unwrap();
+                let (block_width, _) = format.block_dimensions();
+                let block_size = format.block_copy_size(None).unwrap();
+                let bytes_per_row_alignment = get_lowest_common_denom(
+                    self.device.alignments.buffer_copy_pitch.get() as u32,
+                    block_size,
+                );
+                let bytes_per_row = wgt::math::align_to(
+                    tile_size.width / block_width * block_size,
+                    bytes_per_row_alignment,
+                );
+
+                let regions = newly_resident
+                    .into_iter()
+                    .map(|origin| hal::BufferTextureCopy {
+                        buffer_layout: wgt::TexelCopyBufferLayout {
+                            offset: 0,
+                            bytes_per_row: Some(bytes_per_row),
+                            rows_per_image: None,
+                        },
+                        texture_base: hal::TextureCopyBase {
+                            mip_level: binding.mip_level,
+                            array_layer: binding.array_layer,
+                            origin,
+                            aspect: hal::FormatAspects::COLOR,
+                        },
+                        size: hal::CopyExtent {
+                            width: tile_size.width,
+                            height: tile_size.height,
+                            depth: 1,
+                        },
+                    })
+                    .collect::<Vec<_>>();
+
+                let selector = wgt::TextureSelector {
+                    mips: binding.mip_level..binding.mip_level + 1,
+                    layers: binding.array_layer..binding.array_layer + 1,
+                };
+                let barriers = {
+                    let mut trackers = self.device.trackers.lock();
+                    trackers
+                        .textures
+                        .set_single(texture, selector, wgt::TextureUses::COPY_DST)
+                        .map(|pending| pending.into_hal(dst_raw))
+                        .collect::<Vec<_>>()
+                };
+                let encoder = pending_writes.activate();
+                unsafe {
+                    encoder.transition_textures(&barriers);
+                    encoder.copy_buffer_to_texture(zero_buffer, dst_raw, &regions);
+                }
+                pending_writes.insert_texture(texture);
+                texture.queue_owner.set(self.ty, None);
+            }
+
+            pending_writes.sparse_textures.push(binding);
+        }
+
+        Ok(())
+    }
+
+    fn validate_sparse_buffer_binding(
+        &self,
+        binding: &wgt::SparseBufferBinding<Arc<Buffer>>,
+        snatch_guard: &SnatchGuard,
+    ) -> Result<(), QueueSparseResidencyError> {
+        let buffer = &binding.buffer;
+        self.same_device_as(buffer.as_ref())?;
+        buffer.check_destroyed(snatch_guard)?;
+        self.check_queue_owner(&buffer.queue_owner, buffer.as_ref())?;
+
+        if buffer.sparse_residency.is_none() {
+            return Err(QueueSparseResidencyError::NotSparse(buffer.error_ident()));
+        }
+        if binding.offset % wgt::SPARSE_PAGE_SIZE != 0 || binding.size % wgt::SPARSE_PAGE_SIZE != 0
+        {
+            return Err(QueueSparseResidencyError::UnalignedBufferRange {
+                buffer: buffer.error_ident(),
+                offset: binding.offset,
+                size: binding.size,
+            });
+        }
+        if binding
+            .offset
+            .checked_add(binding.size)
+            .is_none_or(|end| end > buffer.size)
+        {
+            return Err(QueueSparseResidencyError::BufferRangeOutOfBounds {
+                buffer: buffer.error_ident(),
+                offset: binding.offset,
+                size: binding.size,
+                buffer_size: buffer.size,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn validate_sparse_texture_binding(
+        &self,
+        binding: &wgt::SparseTextureBinding<Arc<Texture>>,
+        snatch_guard: &SnatchGuard,
+    ) -> Result<(), QueueSparseResidencyError> {
+        let texture = &binding.texture;
+        self.same_device_as(texture.as_ref())?;
+        texture.check_destroyed(snatch_guard)?;
+        self.check_queue_owner(&texture.queue_owner, texture.as_ref())?;
+
+        if texture.sparse_residency.is_none() {
+            return Err(QueueSparseResidencyError::NotSparse(texture.error_ident()));
+        }
+        if binding.mip_level >= texture.desc.mip_level_count
+            || binding.array_layer >= texture.desc.array_layer_count()
+        {
+            return Err(QueueSparseResidencyError::InvalidSubresource {
+                texture: texture.error_ident(),
+                mip_level: binding.mip_level,
+                array_layer: binding.array_layer,
+            });
+        }
+
+        let (origin, size) = (binding.origin, binding.size);
+        let tile_size = texture.desc.format.sparse_tile_extent().unwrap();
+        if origin.x % tile_size.width != 0
+            || origin.y % tile_size.height != 0
+            || size.width % tile_size.width != 0
+            || size.height % tile_size.height != 0
+        {
+            return Err(QueueSparseResidencyError::UnalignedTextureRegion {
+                texture: texture.error_ident(),
+                origin,
+                size,
+                tile_size,
+            });
+        }
+
+        let mip_size = texture.desc.mip_level_size(binding.mip_level).unwrap();
+        let fits = |origin: u32, size: u32, max: u32| size <= max && origin <= max - size;
+        if origin.z != 0
+            || size.depth_or_array_layers != 1
+            || !fits(origin.x, size.width, mip_size.width)
+            || !fits(origin.y, size.height, mip_size.height)
+        {
+            return Err(QueueSparseResidencyError::TextureRegionOutOfBounds {
+                texture: texture.error_ident(),
+                mip_level: binding.mip_level,
+                origin,
+                size,
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn submit(
         &self,
         command_buffers: &[Arc<CommandBuffer>],
@@ -1154,7 +1790,7 @@ impl Queue {
             let snatch_guard = self.device.snatchable_lock.read();
 
             // Fence lock must be acquired after the snatch lock everywhere to avoid deadlocks.
-            let mut fence = self.device.fence.write();
+            let mut fence = self.fence().write();
 
             let mut command_index_guard = self.device.command_indices.write();
             command_index_guard.active_submission_index += 1;
@@ -1166,6 +1802,10 @@ impl Queue {
 
             let mut active_executions = Vec::new();
 
+            // The placed resources activated by the submitted command buffers,
+            // applied to their heaps once the submission succeeds.
+            let mut pending_activations = PendingActivations::default();
+
             let mut used_surface_textures = track::TextureUsageScope::default();
 
             // Use a hashmap here to deduplicate the surface textures that are used in the command buffers.
@@ -1219,11 +1859,13 @@ impl Queue {
                                     &mut submit_surface_textures_owned,
                                     &mut used_surface_textures,
                                     &mut command_index_guard,
+                                    &mut pending_activations,
                                 );
                                 if let Err(err) = res {
                                     first_error.get_or_insert(err);
                                     continue;
                                 }
+                                cmd_buf_data.aliasing_barriers.discard_contents();
                                 cmd_buf_data.into_baked_commands()
                             }
                             Err(err) => {
@@ -1240,6 +1882,13 @@ impl Queue {
                             break 'error Err(e.into());
                         }
 
+                        // Aliasing barriers come before anything else the
+                        // command buffer does, including clearing the
+                        // resources they activated.
+                        if !baked.aliasing_barriers.is_empty() {
+                            unsafe { baked.encoder.raw.aliasing_barrier() };
+                        }
+
                         //Note: locking the trackers has to be done after the storages
                         let mut trackers = self.device.trackers.lock();
                         if let Err(e) = baked.initialize_buffer_memory(&mut trackers, &snatch_guard)
@@ -1251,7 +1900,10 @@ impl Queue {
                             &self.device,
                             &snatch_guard,
                         ) {
-                            break 'error Err(e.into());
+                            break 'error Err(match e {
+                                ClearError::DestroyedResource(e) => e.into(),
+                                e => e.into(),
+                            });
                         }
 
                         //Note: stateless trackers are not merged:
@@ -1320,6 +1972,12 @@ impl Queue {
                     match texture.try_inner(&snatch_guard) {
                         Ok(TextureInner::Native { .. }) => {}
                         Ok(TextureInner::Surface { .. }) => {
+                            if self.ty != wgt::QueueType::Graphics {
+                                break 'error Err(QueueSubmitError::MainQueueOnly(
+                                    texture.error_ident(),
+                                ));
+                            }
+
                             // Compare the Arcs by pointer as Textures don't implement Eq
                             submit_surface_textures_owned
                                 .insert(Arc::as_ptr(texture), texture.clone());
@@ -1356,6 +2014,10 @@ impl Queue {
                 }
             }
 
+            if let Err(e) = pending_writes.bind_sparse_memory(self, &snatch_guard) {
+                break 'error Err(e.into());
+            }
+
             match pending_writes.pre_submit(&self.device.command_allocator, &self.device, self) {
                 Ok(Some(pending_execution)) => {
                     active_executions.insert(0, pending_execution);
@@ -1394,16 +2056,43 @@ impl Queue {
                     break 'error Err(e.into());
                 }
 
+                pending_activations.commit();
+
                 drop(command_index_guard);
 
                 // Advance the successful submission index.
-                self.device
-                    .last_successful_submission_index
+                self.last_successful_submission_index()
                     .fetch_max(submit_index, Ordering::SeqCst);
             }
 
             profiling::scope!("cleanup");
 
+            // Resources used by this submission now belong to this queue.
+            if self
+                .device
+                .features
+                .contains(wgt::Features::MULTIPLE_QUEUES)
+            {
+                for execution in active_executions.iter() {
+                    for buffer in execution
+                        .trackers
+                        .buffers
+                        .used_resources()
+                        .chain(execution.pending_buffers.values())
+                    {
+                        buffer.queue_owner.set(self.ty, Some(submit_index));
+                    }
+                    for texture in execution
+                        .trackers
+                        .textures
+                        .used_resources()
+                        .chain(execution.pending_textures.values())
+                    {
+                        texture.queue_owner.set(self.ty, Some(submit_index));
+                    }
+                }
+            }
+
             // this will register the new submission to the life time tracker
             self.lock_life()
                 .track_submission(submit_index, active_executions);
@@ -1411,7 +2100,14 @@ impl Queue {
 
             // This will schedule destruction of all resources that are no longer needed
             // by the user but used in the command stream, among other things.
-            let fence_guard = RwLockWriteGuard::downgrade(fence);
+            let fence_guard = if self.ty == wgt::QueueType::Graphics {
+                RwLockWriteGuard::downgrade(fence)
+            } else {
+                // `Device::maintain` takes the main queue's fence, and looks
+                // at the fences of additional queues itself.
+                drop(fence);
+                self.device.fence.read()
+            };
             let (closures, result) =
                 self.device
                     .maintain(fence_guard, wgt::PollType::Poll, snatch_guard);
@@ -1447,6 +2143,48 @@ impl Queue {
         Ok(submit_index)
     }
 
+    /// Make the next submission to this queue wait on the GPU until
+    /// `submission_index` has completed on `other`.
+    ///
+    /// Resources last used by `other` in that submission, or earlier, can
+    /// then be used on this queue.
+    pub fn wait_for_submission(
+        &self,
+        other: &Queue,
+        submission_index: SubmissionIndex,
+    ) -> Result<(), QueueWaitError> {
+        profiling::scope!("Queue::wait_for_submission");
+        api_log!("Queue::wait_for_submission");
+
+        self.device.check_is_valid()?;
+        self.same_device_as(other)?;
+
+        if self.ty == other.ty {
+            return Err(QueueWaitError::SameQueue);
+        }
+
+        let last_successful_submission_index = other
+            .last_successful_submission_index()
+            .load(Ordering::Acquire);
+        if submission_index > last_successful_submission_index {
+            return Err(QueueWaitError::WrongSubmissionIndex(
+                submission_index,
+                last_successful_submission_index,
+            ));
+        }
+
+        let fence = other.fence().read();
+        unsafe { self.raw().wait_for_fence(fence.as_ref(), submission_index) }
+            .map_err(|e| self.device.handle_hal_error(e))?;
+        drop(fence);
+
+        let mut waits = self.waits.lock();
+        let waited = waits.entry(other.ty).or_insert(0);
+        *waited = (*waited).max(submission_index);
+
+        Ok(())
+    }
+
     pub fn get_timestamp_period(&self) -> f32 {
         unsafe { self.raw().get_timestamp_period() }
     }
@@ -1656,6 +2394,45 @@ impl Global {
         queue.copy_external_image_to_texture(source, destination, size)
     }
 
+    pub fn queue_update_sparse_residency(
+        &self,
+        queue_id: QueueId,
+        buffers: &[wgt::SparseBufferBinding<id::BufferId>],
+        textures: &[wgt::SparseTextureBinding<id::TextureId>],
+    ) -> Result<(), QueueSparseResidencyError> {
+        let queue = self.hub.queues.get(queue_id);
+
+        #[cfg(feature = "trace")]
+        if let Some(ref mut trace) = *queue.device.trace.lock() {
+            trace.add(Action::UpdateSparseResidency {
+                buffers: buffers.to_vec(),
+                textures: textures.to_vec(),
+            });
+        }
+
+        let buffers = buffers
+            .iter()
+            .map(|binding| wgt::SparseBufferBinding {
+                buffer: self.hub.buffers.get(binding.buffer),
+                offset: binding.offset,
+                size: binding.size,
+                resident: binding.resident,
+            })
+            .collect::<Vec<_>>();
+        let textures = textures
+            .iter()
+            .map(|binding| wgt::SparseTextureBinding {
+                texture: self.hub.textures.get(binding.texture),
+                mip_level: binding.mip_level,
+                array_layer: binding.array_layer,
+                origin: binding.origin,
+                size: binding.size,
+                resident: binding.resident,
+            })
+            .collect::<Vec<_>>();
+        queue.update_sparse_residency(buffers, textures)
+    }
+
     pub fn queue_submit(
         &self,
         queue_id: QueueId,
@@ -1671,6 +2448,51 @@ impl Global {
         queue.submit(&command_buffers)
     }
 
+    pub fn queue_wait_for_submission(
+        &self,
+        queue_id: QueueId,
+        other_queue_id: QueueId,
+        submission_index: SubmissionIndex,
+    ) -> Result<(), QueueWaitError> {
+        let queue = self.hub.queues.get(queue_id);
+        let other = self.hub.queues.get(other_queue_id);
+        queue.wait_for_submission(&other, submission_index)
+    }
+
+    pub fn queue_create_command_encoder(
+        &self,
+        queue_id: QueueId,
+        desc: &wgt::CommandEncoderDescriptor<crate::Label>,
+        id_in: Option<id::CommandEncoderId>,
+    ) -> (id::CommandEncoderId, Option<DeviceError>) {
+        profiling::scope!("Queue::create_command_encoder");
+
+        let fid = self.hub.command_encoders.prepare(id_in);
+
+        let queue = self.hub.queues.get(queue_id);
+
+        let error = 'error: {
+            let cmd_enc = match queue
+                .device
+                .create_command_encoder_for_queue(&queue, &desc.label)
+            {
+                Ok(cmd_enc) => cmd_enc,
+                Err(e) => break 'error e,
+            };
+
+            let id = fid.assign(cmd_enc);
+            api_log!("Queue::create_command_encoder -> {id:?}");
+            return (id, None);
+        };
+
+        let id = fid.assign(Arc::new(CommandEncoder::new_invalid(
+            &queue.device,
+            &desc.label,
+            error.clone().into(),
+        )));
+        (id, Some(error))
+    }
+
     pub fn queue_get_timestamp_period(&self, queue_id: QueueId) -> f32 {
         let queue = self.hub.queues.get(queue_id);
 
@@ -1752,9 +2574,17 @@ fn validate_command_buffer(
     submit_surface_textures_owned: &mut FastHashMap<*const Texture, Arc<Texture>>,
     used_surface_textures: &mut track::TextureUsageScope,
     command_index_guard: &mut RwLockWriteGuard<CommandIndices>,
+    pending_activations: &mut PendingActivations,
 ) -> Result<(), QueueSubmitError> {
     command_buffer.same_device_as(queue)?;
 
+    if cmd_buf_data.encoder.queue_type != queue.ty {
+        return Err(QueueSubmitError::WrongQueueType {
+            encoder: cmd_buf_data.encoder.queue_type,
+            queue: queue.ty,
+        });
+    }
+
     {
         profiling::scope!("check resource state");
 
@@ -1762,6 +2592,7 @@ fn validate_command_buffer(
             profiling::scope!("buffers");
             for buffer in cmd_buf_data.trackers.buffers.used_resources() {
                 buffer.check_destroyed(snatch_guard)?;
+                queue.check_queue_owner(&buffer.queue_owner, buffer.as_ref())?;
 
                 match *buffer.map_state.lock() {
                     BufferMapState::Idle => (),
@@ -1772,9 +2603,14 @@ fn validate_command_buffer(
         {
             profiling::scope!("textures");
             for texture in cmd_buf_data.trackers.textures.used_resources() {
+                queue.check_queue_owner(&texture.queue_owner, texture.as_ref())?;
                 let should_extend = match texture.try_inner(snatch_guard)? {
                     TextureInner::Native { .. } => false,
                     TextureInner::Surface { .. } => {
+                        if queue.ty != wgt::QueueType::Graphics {
+                            return Err(QueueSubmitError::MainQueueOnly(texture.error_ident()));
+                        }
+
                         // Compare the Arcs by pointer as Textures don't implement Eq.
                         submit_surface_textures_owned.insert(Arc::as_ptr(texture), texture.clone());
 
@@ -1797,5 +2633,18 @@ fn validate_command_buffer(
             return Err(e.into());
         }
     }
+
+    {
+        profiling::scope!("check aliasing");
+
+        cmd_buf_data.aliasing_barriers.activate(pending_activations);
+
+        for buffer in cmd_buf_data.trackers.buffers.used_resources() {
+            buffer.check_active(Some(pending_activations))?;
+        }
+        for texture in cmd_buf_data.trackers.textures.used_resources() {
+            texture.check_active(Some(pending_activations))?;
+        }
+    }
     Ok(())
 }
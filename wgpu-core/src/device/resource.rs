@@ -41,22 +41,27 @@ use crate::{
     pipeline,
     pool::ResourcePool,
     resource::{
-        self, Buffer, ExternalTexture, Fallible, Labeled, ParentDevice, QuerySet,
+        self, Buffer, ExternalTexture, Fallible, Heap, Labeled, ParentDevice, QuerySet,
         RawResourceAccess, Sampler, StagingBuffer, Texture, TextureView,
-        TextureViewNotRenderableReason, Tlas, TrackingData,
+        TextureViewNotRenderableReason, Tlas, Trackable, TrackingData,
     },
     resource_log,
     snatch::{SnatchGuard, SnatchLock, Snatchable},
+    sparse::{SparseResidency, SparseTextureResidency},
     timestamp_normalization::TIMESTAMP_NORMALIZATION_BUFFER_USES,
-    track::{BindGroupStates, DeviceTracker, TrackerIndexAllocators, UsageScope, UsageScopePool},
+    track::{
+        AliasingTracker, BindGroupStates, DeviceTracker, PlacedResourceId, QueueOwner,
+        TrackerIndexAllocators, UsageScope, UsageScopePool,
+    },
     validation::{self, validate_color_attachment_bytes_per_sample},
     weak_vec::WeakVec,
     FastHashMap, LabelHelpers, OnceCellOrLock,
 };
 
 use super::{
-    queue::Queue, DeviceDescriptor, DeviceError, DeviceLostClosure, UserClosures,
-    ENTRYPOINT_FAILURE_ERROR, ZERO_BUFFER_SIZE,
+    queue::{AdditionalQueue, CreateQueueError, Queue},
+    DeviceDescriptor, DeviceError, DeviceLostClosure, UserClosures, ENTRYPOINT_FAILURE_ERROR,
+    ZERO_BUFFER_SIZE,
 };
 
 #[cfg(supports_64bit_atomics)]
@@ -201,6 +206,11 @@ pub struct Device {
     raw: Box<dyn hal::DynDevice>,
     pub(crate) adapter: Arc<Adapter>,
     pub(crate) queue: OnceCellOrLock<Weak<Queue>>,
+    /// Compute and transfer queues opened along with the device, which can
+    /// be handed out by [`Device::create_queue`].
+    ///
+    /// Empty unless [`wgt::Features::MULTIPLE_QUEUES`] is enabled.
+    pub(crate) additional_queues: Vec<AdditionalQueue>,
     pub(crate) zero_buffer: ManuallyDrop<Box<dyn hal::DynBuffer>>,
     /// The `label` from the descriptor used to create the resource.
     label: String,
@@ -254,6 +264,7 @@ pub struct Device {
     pub(crate) downlevel: wgt::DownlevelCapabilities,
     pub(crate) instance_flags: wgt::InstanceFlags,
     pub(crate) deferred_destroy: Mutex<Vec<DeferredDestroy>>,
+    pub(crate) pipeline_workers: PipelineWorkers,
     pub(crate) usage_scopes: UsageScopePool,
     pub(crate) indirect_validation: Option<crate::indirect_validation::IndirectValidation>,
     // Optional so that we can late-initialize this after the queue is created.
@@ -274,6 +285,87 @@ pub(crate) enum DeferredDestroy {
     BindGroups(WeakVec<BindGroup>),
 }
 
+#[cfg(send_sync)]
+pub(crate) type PipelineJob = Box<dyn FnOnce() + Send + 'static>;
+#[cfg(not(send_sync))]
+pub(crate) type PipelineJob = Box<dyn FnOnce() + 'static>;
+
+/// The maximum number of threads a device spawns to create pipelines.
+#[cfg(threads)]
+const MAX_PIPELINE_WORKERS: usize = 4;
+
+/// A pool of threads creating pipelines for
+/// [`Global::device_create_render_pipeline_async`] and
+/// [`Global::device_create_compute_pipeline_async`].
+///
+/// The threads are spawned when the first job is queued. Jobs own a strong
+/// reference to the device, so the device outlives the jobs queued on it;
+/// once it is dropped, the threads exit.
+///
+/// Where threads are unavailable, jobs run on the thread queueing them.
+///
+/// [`Global::device_create_render_pipeline_async`]: crate::global::Global::device_create_render_pipeline_async
+/// [`Global::device_create_compute_pipeline_async`]: crate::global::Global::device_create_compute_pipeline_async
+pub(crate) struct PipelineWorkers {
+    /// Sends jobs to the threads, or `None` if no thread could be spawned.
+    #[cfg(threads)]
+    sender: std::sync::OnceLock<Option<std::sync::mpsc::Sender<PipelineJob>>>,
+}
+
+impl PipelineWorkers {
+    fn new() -> Self {
+        Self {
+            #[cfg(threads)]
+            sender: std::sync::OnceLock::new(),
+        }
+    }
+
+    /// Runs `job` on one of the threads of the pool.
+    pub(crate) fn run(&self, job: PipelineJob) {
+        #[cfg(threads)]
+        let job = match self.sender.get_or_init(Self::spawn_threads) {
+            Some(sender) => match sender.send(job) {
+                Ok(()) => return,
+                Err(std::sync::mpsc::SendError(job)) => job,
+            },
+            None => job,
+        };
+        job();
+    }
+
+    #[cfg(threads)]
+    fn spawn_threads() -> Option<std::sync::mpsc::Sender<PipelineJob>> {
+        let (sender, receiver) = std::sync::mpsc::channel::<PipelineJob>();
+        let receiver = Arc::new(Mutex::new(rank::PIPELINE_WORKERS_RECEIVER, receiver));
+
+        let count = std::thread::available_parallelism()
+            .map_or(1, |count| count.get())
+            .min(MAX_PIPELINE_WORKERS);
+        let mut spawned = 0;
+        for index in 0..count {
+            let receiver = Arc::clone(&receiver);
+            let thread = std::thread::Builder::new()
+                .name(alloc::format!("wgpu pipeline worker {index}"))
+                .spawn(move || loop {
+                    // Release the lock before running the job, so other threads
+                    // can pick up jobs meanwhile.
+                    let job = receiver.lock().recv();
+                    // `recv` fails once the device, and with it the sender, is dropped.
+                    let Ok(job) = job else {
+                        break;
+                    };
+                    job();
+                });
+            match thread {
+                Ok(_) => spawned += 1,
+                Err(e) => log::warn!("Failed to spawn a pipeline worker thread: {e}"),
+            }
+        }
+
+        (spawned > 0).then_some(sender)
+    }
+}
+
 impl fmt::Debug for Device {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Device")
@@ -297,6 +389,10 @@ impl Drop for Device {
             unsafe { ManuallyDrop::take(&mut self.default_external_texture_params_buffer) };
         // SAFETY: We are in the Drop impl and we don't use self.fence anymore after this point.
         let fence = unsafe { ManuallyDrop::take(&mut self.fence.write()) };
+        for additional_queue in self.additional_queues.iter_mut() {
+            // SAFETY: All queues are gone, so nothing can wait on the fence anymore.
+            unsafe { additional_queue.destroy(self.raw.as_ref()) };
+        }
         if let Some(indirect_validation) = self.indirect_validation.take() {
             indirect_validation.dispose(self.raw.as_ref());
         }
@@ -339,6 +435,7 @@ impl Device {
 impl Device {
     pub(crate) fn new(
         raw_device: Box<dyn hal::DynDevice>,
+        additional_queues: Vec<(wgt::QueueType, Box<dyn hal::DynQueue>)>,
         adapter: &Arc<Adapter>,
         desc: &DeviceDescriptor,
         instance_flags: wgt::InstanceFlags,
@@ -351,9 +448,10 @@ impl Device {
             }
         };
         #[cfg(feature = "trace")]
-        let trace_dir_name: Option<&std::path::PathBuf> = match &desc.trace {
+        let trace_dir_name: Option<(&std::path::PathBuf, trace::Format)> = match &desc.trace {
             wgt::Trace::Off => None,
-            wgt::Trace::Directory(d) => Some(d),
+            wgt::Trace::Directory(d) => Some((d, trace::Format::Ron)),
+            wgt::Trace::BinaryDirectory(d) => Some((d, trace::Format::Binary)),
             // The enum is non_exhaustive, so we must have a fallback arm (that should be
             // unreachable in practice).
             t => {
@@ -364,6 +462,14 @@ impl Device {
 
         let fence = unsafe { raw_device.create_fence() }.map_err(DeviceError::from_hal)?;
 
+        let additional_queues = additional_queues
+            .into_iter()
+            .map(|(ty, raw)| {
+                let fence = unsafe { raw_device.create_fence() }.map_err(DeviceError::from_hal)?;
+                Ok(AdditionalQueue::new(ty, raw, fence))
+            })
+            .collect::<Result<Vec<_>, DeviceError>>()?;
+
         let command_allocator = command::CommandAllocator::new();
 
         let rt_uses = if desc
@@ -423,6 +529,7 @@ impl Device {
             raw: raw_device,
             adapter: adapter.clone(),
             queue: OnceCellOrLock::new(),
+            additional_queues,
             zero_buffer: ManuallyDrop::new(zero_buffer),
             default_external_texture_params_buffer: ManuallyDrop::new(
                 default_external_texture_params_buffer,
@@ -448,20 +555,22 @@ impl Device {
             #[cfg(feature = "trace")]
             trace: Mutex::new(
                 rank::DEVICE_TRACE,
-                trace_dir_name.and_then(|path| match trace::Trace::new(path.clone()) {
-                    Ok(mut trace) => {
-                        trace.add(trace::Action::Init {
-                            desc: wgt::DeviceDescriptor {
-                                trace: wgt::Trace::Off,
-                                ..desc.clone()
-                            },
-                            backend: adapter.backend(),
-                        });
-                        Some(trace)
-                    }
-                    Err(e) => {
-                        log::error!("Unable to start a trace in '{path:?}': {e}");
-                        None
+                trace_dir_name.and_then(|(path, format)| {
+                    match trace::Trace::new(path.clone(), format) {
+                        Ok(mut trace) => {
+                            trace.add(trace::Action::Init {
+                                desc: wgt::DeviceDescriptor {
+                                    trace: wgt::Trace::Off,
+                                    ..desc.clone()
+                                },
+                                backend: adapter.backend(),
+                            });
+                            Some(trace)
+                        }
+                        Err(e) => {
+                            log::error!("Unable to start a trace in '{path:?}': {e}");
+                            None
+                        }
                     }
                 }),
             ),
@@ -471,6 +580,7 @@ impl Device {
             downlevel,
             instance_flags,
             deferred_destroy: Mutex::new(rank::DEVICE_DEFERRED_DESTROY, Vec::new()),
+            pipeline_workers: PipelineWorkers::new(),
             usage_scopes: Mutex::new(rank::DEVICE_USAGE_SCOPES, Default::default()),
             timestamp_normalizer: OnceCellOrLock::new(),
             indirect_validation,
@@ -679,6 +789,78 @@ impl Device {
         assert!(self.queue.set(Arc::downgrade(queue)).is_ok());
     }
 
+    /// The device's slot for its additional queue of type `ty`, if it has one.
+    pub(crate) fn additional_queue(&self, ty: wgt::QueueType) -> Option<&AdditionalQueue> {
+        self.additional_queues.iter().find(|queue| queue.ty == ty)
+    }
+
+    /// The live queue of type `ty`, if any.
+    pub(crate) fn get_queue_of_type(&self, ty: wgt::QueueType) -> Option<Arc<Queue>> {
+        match ty {
+            wgt::QueueType::Graphics => self.get_queue(),
+            _ => self.additional_queue(ty)?.queue(),
+        }
+    }
+
+    /// The queue that last used a resource whose ownership is tracked by
+    /// `owner`, falling back to the main queue.
+    pub(crate) fn get_owning_queue(&self, owner: &QueueOwner) -> Option<Arc<Queue>> {
+        owner
+            .last_use()
+            .and_then(|last_use| self.get_queue_of_type(last_use.queue))
+            .or_else(|| self.get_queue())
+    }
+
+    /// Create a [`Queue`] for one of the device's additional queues.
+    ///
+    /// Each additional queue can only be used by one [`Queue`] at a time.
+    pub(crate) fn create_queue(
+        self: &Arc<Self>,
+        desc: &wgt::QueueDescriptor<crate::Label>,
+    ) -> Result<Arc<Queue>, CreateQueueError> {
+        self.check_is_valid()?;
+        self.require_features(wgt::Features::MULTIPLE_QUEUES)?;
+
+        if desc.ty == wgt::QueueType::Graphics {
+            return Err(CreateQueueError::Graphics);
+        }
+        let slot = self
+            .additional_queue(desc.ty)
+            .ok_or(CreateQueueError::Unavailable(desc.ty))?;
+
+        // Flush the main queue's pending writes, which initialize the zero
+        // buffer, so that the new queue can be ordered after them.
+        let main_queue = self.get_queue().unwrap();
+        let main_submission = main_queue.submit(&[]).map_err(|(_, e)| e)?;
+
+        let completed = unsafe { self.raw().get_fence_value(slot.fence.read().as_ref()) }
+            .map_err(|e| self.handle_hal_error(e))?;
+
+        let raw = slot.acquire().ok_or(CreateQueueError::InUse(desc.ty))?;
+        let wait_result = {
+            let fence = self.fence.read();
+            unsafe { raw.wait_for_fence(fence.as_ref(), main_submission) }
+        };
+        if let Err(e) = wait_result {
+            slot.release(raw);
+            return Err(self.handle_hal_error(e).into());
+        }
+
+        let queue = Queue::new(self.clone(), raw, desc.ty, self.instance_flags)?;
+        queue
+            .completed_submission_index
+            .store(completed, Ordering::Release);
+        queue
+            .waits
+            .lock()
+            .insert(wgt::QueueType::Graphics, main_submission);
+        let queue = Arc::new(queue);
+
+        slot.set_queue(&queue);
+
+        Ok(queue)
+    }
+
     /// Check the current status of the GPU and process any submissions that have
     /// finished.
     ///
@@ -706,13 +888,24 @@ impl Device {
 
         let mut user_closures = UserClosures::default();
 
+        // Submission indices are shared by all of the device's queues.
+        let last_successful_submission_index = self
+            .additional_queues
+            .iter()
+            .map(|queue| {
+                queue
+                    .last_successful_submission_index
+                    .load(Ordering::Acquire)
+            })
+            .fold(
+                self.last_successful_submission_index
+                    .load(Ordering::Acquire),
+                u64::max,
+            );
+
         // If a wait was requested, determine which submission index to wait for.
         let wait_submission_index = match poll_type {
             wgt::PollType::WaitForSubmissionIndex(submission_index) => {
-                let last_successful_submission_index = self
-                    .last_successful_submission_index
-                    .load(Ordering::Acquire);
-
                 if submission_index > last_successful_submission_index {
                     let result = Err(WaitIdleError::WrongSubmissionIndex(
                         submission_index,
@@ -724,15 +917,20 @@ impl Device {
 
                 Some(submission_index)
             }
-            wgt::PollType::Wait => Some(
-                self.last_successful_submission_index
-                    .load(Ordering::Acquire),
-            ),
+            wgt::PollType::Wait => Some(last_successful_submission_index),
             wgt::PollType::Poll => None,
         };
 
+        // Each queue only needs to wait for its own submissions.
+        let main_wait_submission_index = wait_submission_index.map(|index| {
+            index.min(
+                self.last_successful_submission_index
+                    .load(Ordering::Acquire),
+            )
+        });
+
         // Wait for the submission index if requested.
-        if let Some(target_submission_index) = wait_submission_index {
+        if let Some(target_submission_index) = main_wait_submission_index {
             log::trace!("Device::maintain: waiting for submission index {target_submission_index}");
 
             let wait_result = unsafe {
@@ -774,9 +972,8 @@ impl Device {
             ) = queue_result
         };
 
-        // Based on the queue empty status, and the current finished submission index, determine the result of the poll.
-        let result = if queue_empty {
-            if let Some(wait_submission_index) = wait_submission_index {
+        if let Some(wait_submission_index) = main_wait_submission_index {
+            if queue_empty {
                 // Assert to ensure that if we received a queue empty status, the fence shows the correct value.
                 // This is defensive, as this should never be hit.
                 assert!(
@@ -784,13 +981,63 @@ impl Device {
                     "If the queue is empty, the current submission index ({current_finished_submission}) should be at least the wait submission index ({wait_submission_index})"
                 );
             }
+        }
+
+        // This is theoretically possible to succeed more than checking on the poll result
+        // as submissions could have finished in the time between the timeout resolving,
+        // the thread getting scheduled again, and us checking the fence value.
+        let mut wait_succeeded =
+            main_wait_submission_index.is_none_or(|index| current_finished_submission >= index);
+
+        // Do the same for the additional queues.
+        for additional_queue in self.additional_queues.iter() {
+            let Some(queue) = additional_queue.queue() else {
+                continue;
+            };
+
+            let fence = additional_queue.fence.read();
+            let wait_submission_index = wait_submission_index.map(|index| {
+                index.min(
+                    additional_queue
+                        .last_successful_submission_index
+                        .load(Ordering::Acquire),
+                )
+            });
+            if let Some(target_submission_index) = wait_submission_index {
+                let wait_result = unsafe {
+                    self.raw()
+                        .wait(fence.as_ref(), target_submission_index, CLEANUP_WAIT_MS)
+                };
+                if let Err(e) = wait_result {
+                    let hal_error: WaitIdleError = self.handle_hal_error(e).into();
+                    return (user_closures, Err(hal_error));
+                }
+            }
+            let finished_submission = match unsafe { self.raw().get_fence_value(fence.as_ref()) } {
+                Ok(fence_value) => fence_value,
+                Err(e) => {
+                    let hal_error: WaitIdleError = self.handle_hal_error(e).into();
+                    return (user_closures, Err(hal_error));
+                }
+            };
+            drop(fence);
 
+            let (submissions, mappings, blas_compact_ready, additional_queue_empty) =
+                queue.maintain(finished_submission, &snatch_guard);
+            user_closures.submissions.extend(submissions);
+            user_closures.mappings.extend(mappings);
+            user_closures.blas_compact_ready.extend(blas_compact_ready);
+
+            queue_empty &= additional_queue_empty;
+            wait_succeeded &=
+                wait_submission_index.is_none_or(|index| finished_submission >= index);
+        }
+
+        // Based on the queue empty status, and the current finished submission index, determine the result of the poll.
+        let result = if queue_empty {
             Ok(wgt::PollStatus::QueueEmpty)
-        } else if let Some(wait_submission_index) = wait_submission_index {
-            // This is theoretically possible to succeed more than checking on the poll result
-            // as submissions could have finished in the time between the timeout resolving,
-            // the thread getting scheduled again, and us checking the fence value.
-            if current_finished_submission >= wait_submission_index {
+        } else if wait_submission_index.is_some() {
+            if wait_succeeded {
                 Ok(wgt::PollStatus::WaitSucceeded)
             } else {
                 Err(WaitIdleError::Timeout)
@@ -839,8 +1086,36 @@ impl Device {
         self: &Arc<Self>,
         desc: &resource::BufferDescriptor,
     ) -> Result<Arc<Buffer>, resource::CreateBufferError> {
+        self.create_buffer_impl(desc, None)
+    }
+
+    pub(crate) fn create_placed_buffer(
+        self: &Arc<Self>,
+        heap: &Arc<Heap>,
+        offset: wgt::BufferAddress,
+        desc: &resource::BufferDescriptor,
+    ) -> Result<Arc<Buffer>, resource::CreateBufferError> {
+        self.create_buffer_impl(desc, Some((heap, offset)))
+    }
+
+    pub(crate) fn get_buffer_memory_requirements(
+        &self,
+        desc: &resource::BufferDescriptor,
+    ) -> Result<wgt::MemoryRequirements, resource::CreateBufferError> {
         self.check_is_valid()?;
+        self.require_features(wgt::Features::RESOURCE_HEAPS)?;
 
+        let hal_desc = self.buffer_hal_descriptor(desc)?;
+        let requirements = unsafe { self.raw().get_buffer_memory_requirements(&hal_desc) }
+            .map_err(|e| self.handle_hal_error(e))?;
+        Ok(requirements)
+    }
+
+    /// Validates `desc`, and returns the descriptor of the raw buffer to create for it.
+    fn buffer_hal_descriptor<'a>(
+        &self,
+        desc: &'a resource::BufferDescriptor,
+    ) -> Result<hal::BufferDescriptor<'a>, resource::CreateBufferError> {
         if desc.size > self.limits.max_buffer_size {
             return Err(resource::CreateBufferError::MaxBufferSize {
                 requested: desc.size,
@@ -870,6 +1145,21 @@ impl Device {
             return Err(resource::CreateBufferError::InvalidUsage(desc.usage));
         }
 
+        let sparse = desc.usage.contains(wgt::BufferUsages::SPARSE);
+        if sparse {
+            self.require_features(wgt::Features::SPARSE_RESOURCES)?;
+            if desc.mapped_at_creation
+                || desc
+                    .usage
+                    .intersects(wgt::BufferUsages::MAP_READ | wgt::BufferUsages::MAP_WRITE)
+            {
+                return Err(resource::CreateBufferError::MappableSparseBuffer);
+            }
+            if desc.size % wgt::SPARSE_PAGE_SIZE != 0 {
+                return Err(resource::CreateBufferError::UnalignedSparseSize(desc.size));
+            }
+        }
+
         if !self
             .features
             .contains(wgt::Features::MAPPABLE_PRIMARY_BUFFERS)
@@ -911,7 +1201,15 @@ impl Device {
             usage |= wgt::BufferUses::COPY_DST;
         }
 
-        let actual_size = if desc.size == 0 {
+        let actual_size = if sparse {
+            // Sparse buffers are bound in whole pages. Reserve an extra page
+            // instead of the usual padding; it is never made resident.
+            if desc.size == 0 || desc.usage.contains(wgt::BufferUsages::VERTEX) {
+                desc.size + wgt::SPARSE_PAGE_SIZE
+            } else {
+                desc.size
+            }
+        } else if desc.size == 0 {
             wgt::COPY_BUFFER_ALIGNMENT
         } else if desc.usage.contains(wgt::BufferUsages::VERTEX) {
             // Bumping the size by 1 so that we can bind an empty range at the
@@ -931,10 +1229,58 @@ impl Device {
             label: desc.label.to_hal(self.instance_flags),
             size: aligned_size,
             usage,
-            memory_flags: hal::MemoryFlags::empty(),
+            memory_flags: if sparse {
+                hal::MemoryFlags::SPARSE
+            } else {
+                hal::MemoryFlags::empty()
+            },
+        };
+        Ok(hal_desc)
+    }
+
+    fn create_buffer_impl(
+        self: &Arc<Self>,
+        desc: &resource::BufferDescriptor,
+        placement: Option<(&Arc<Heap>, wgt::BufferAddress)>,
+    ) -> Result<Arc<Buffer>, resource::CreateBufferError> {
+        self.check_is_valid()?;
+
+        let hal_desc = self.buffer_hal_descriptor(desc)?;
+        let sparse = desc.usage.contains(wgt::BufferUsages::SPARSE);
+        let aligned_size = hal_desc.size;
+
+        let (buffer, placement) = match placement {
+            None => {
+                let buffer = unsafe { self.raw().create_buffer(&hal_desc) }
+                    .map_err(|e| self.handle_hal_error_with_nonfatal_oom(e))?;
+                (buffer, None)
+            }
+            Some((heap, offset)) => {
+                heap.same_device(self)?;
+                self.require_features(wgt::Features::RESOURCE_HEAPS)
+                    .map_err(resource::PlacementError::from)?;
+                if desc.mapped_at_creation
+                    || desc
+                        .usage
+                        .intersects(wgt::BufferUsages::MAP_READ | wgt::BufferUsages::MAP_WRITE)
+                {
+                    return Err(resource::PlacementError::Mappable.into());
+                }
+                if sparse {
+                    return Err(resource::PlacementError::Sparse.into());
+                }
+
+                let requirements = unsafe { self.raw().get_buffer_memory_requirements(&hal_desc) }
+                    .map_err(|e| self.handle_hal_error(e))?;
+                let placement = Self::place(heap, offset, requirements)?;
+                let buffer = unsafe {
+                    self.raw()
+                        .create_placed_buffer(&hal_desc, heap.raw(), offset)
+                }
+                .map_err(|e| self.handle_hal_error_with_nonfatal_oom(e))?;
+                (buffer, Some(placement))
+            }
         };
-        let buffer = unsafe { self.raw().create_buffer(&hal_desc) }
-            .map_err(|e| self.handle_hal_error_with_nonfatal_oom(e))?;
 
         let timestamp_normalization_bind_group = Snatchable::new(unsafe {
             // SAFETY: The size passed here must not overflow the buffer.
@@ -968,10 +1314,32 @@ impl Device {
             bind_groups: Mutex::new(rank::BUFFER_BIND_GROUPS, WeakVec::new()),
             timestamp_normalization_bind_group,
             indirect_validation_bind_groups,
+            queue_owner: QueueOwner::new(),
+            sparse_residency: sparse.then(|| {
+                Mutex::new(
+                    rank::RESOURCE_SPARSE_RESIDENCY,
+                    SparseResidency::new((desc.size / wgt::SPARSE_PAGE_SIZE) as usize),
+                )
+            }),
+            placement,
         };
 
         let buffer = Arc::new(buffer);
 
+        if let Some(ref placement) = buffer.placement {
+            placement.heap.aliasing.lock().insert(
+                PlacedResourceId::Buffer(buffer.tracker_index()),
+                placement.range.clone(),
+                buffer.label.clone(),
+            );
+        }
+
+        if sparse {
+            // Pages are zeroed when they become resident, and reads from
+            // non-resident pages return zero.
+            buffer.initialization_status.write().drain(0..aligned_size);
+        }
+
         let buffer_use = if !desc.mapped_at_creation {
             wgt::BufferUses::empty()
         } else if desc.usage.contains(wgt::BufferUsages::MAP_WRITE) {
@@ -1103,6 +1471,9 @@ impl Device {
             bind_groups: Mutex::new(rank::BUFFER_BIND_GROUPS, WeakVec::new()),
             timestamp_normalization_bind_group,
             indirect_validation_bind_groups,
+            queue_owner: QueueOwner::new(),
+            sparse_residency: None,
+            placement: None,
         };
 
         let buffer = Arc::new(buffer);
@@ -1149,14 +1520,53 @@ impl Device {
         self: &Arc<Self>,
         desc: &resource::TextureDescriptor,
     ) -> Result<Arc<Texture>, resource::CreateTextureError> {
-        use resource::{CreateTextureError, TextureDimensionError};
+        self.create_texture_impl(desc, None)
+    }
+
+    pub(crate) fn create_placed_texture(
+        self: &Arc<Self>,
+        heap: &Arc<Heap>,
+        offset: wgt::BufferAddress,
+        desc: &resource::TextureDescriptor,
+    ) -> Result<Arc<Texture>, resource::CreateTextureError> {
+        self.create_texture_impl(desc, Some((heap, offset)))
+    }
 
+    pub(crate) fn get_texture_memory_requirements(
+        &self,
+        desc: &resource::TextureDescriptor,
+    ) -> Result<wgt::MemoryRequirements, resource::CreateTextureError> {
         self.check_is_valid()?;
+        self.require_features(wgt::Features::RESOURCE_HEAPS)
+            .map_err(|error| resource::CreateTextureError::MissingFeatures(desc.format, error))?;
+
+        let (hal_desc, _) = self.texture_hal_descriptor(desc)?;
+        let requirements = unsafe { self.raw().get_texture_memory_requirements(&hal_desc) }
+            .map_err(|e| self.handle_hal_error(e))?;
+        Ok(requirements)
+    }
+
+    /// Validates `desc`, and returns the descriptor of the raw texture to
+    /// create for it along with the features of its format.
+    fn texture_hal_descriptor<'a>(
+        &self,
+        desc: &'a resource::TextureDescriptor,
+    ) -> Result<
+        (hal::TextureDescriptor<'a>, wgt::TextureFormatFeatures),
+        resource::CreateTextureError,
+    > {
+        use resource::{CreateTextureError, TextureDimensionError};
 
         if desc.usage.is_empty() || desc.usage.contains_unknown_bits() {
             return Err(CreateTextureError::InvalidUsage(desc.usage));
         }
 
+        if desc.usage.contains(wgt::TextureUsages::TRANSIENT)
+            && desc.usage != wgt::TextureUsages::RENDER_ATTACHMENT | wgt::TextureUsages::TRANSIENT
+        {
+            return Err(CreateTextureError::InvalidTransientUsage(desc.usage));
+        }
+
         conv::check_texture_dimension_size(
             desc.dimension,
             desc.size,
@@ -1323,7 +1733,37 @@ impl Device {
             });
         }
 
-        let missing_allowed_usages = desc.usage - format_features.allowed_usages;
+        let sparse = desc.usage.contains(wgt::TextureUsages::SPARSE);
+        if sparse {
+            self.require_features(wgt::Features::SPARSE_RESOURCES)
+                .map_err(|error| CreateTextureError::MissingFeatures(desc.format, error))?;
+            if desc.dimension != wgt::TextureDimension::D2 || desc.sample_count != 1 {
+                return Err(CreateTextureError::InvalidSparseDimension);
+            }
+            let tile_size = desc
+                .format
+                .sparse_tile_extent()
+                .ok_or(CreateTextureError::InvalidSparseFormat(desc.format))?;
+            // There is no support for packed mip tails, so every mip level
+            // has to be made of whole tiles.
+            for mip_level in 0..desc.mip_level_count {
+                let size = desc.mip_level_size(mip_level).unwrap();
+                if size.width % tile_size.width != 0 || size.height % tile_size.height != 0 {
+                    return Err(CreateTextureError::UnalignedSparseMipLevel {
+                        mip_level,
+                        size,
+                        tile_size,
+                    });
+                }
+            }
+        }
+
+        // Any format that can be rendered to can be transient, and any format
+        // with a tile extent can be sparse.
+        let missing_allowed_usages = desc.usage
+            - wgt::TextureUsages::TRANSIENT
+            - wgt::TextureUsages::SPARSE
+            - format_features.allowed_usages;
         if !missing_allowed_usages.is_empty() {
             // detect downlevel incompatibilities
             let wgpu_allowed_usages = desc
@@ -1352,7 +1792,11 @@ impl Device {
             self.require_downlevel_flags(wgt::DownlevelFlags::VIEW_FORMATS)?;
         }
 
-        let hal_usage = conv::map_texture_usage_for_texture(desc, &format_features);
+        let mut hal_usage = conv::map_texture_usage_for_texture(desc, &format_features);
+        if sparse {
+            // Tiles are zeroed by copies when they become resident.
+            hal_usage |= wgt::TextureUses::COPY_DST;
+        }
 
         let hal_desc = hal::TextureDescriptor {
             label: desc.label.to_hal(self.instance_flags),
@@ -1362,14 +1806,60 @@ impl Device {
             dimension: desc.dimension,
             format: desc.format,
             usage: hal_usage,
-            memory_flags: hal::MemoryFlags::empty(),
+            memory_flags: if sparse {
+                hal::MemoryFlags::SPARSE
+            } else {
+                hal::MemoryFlags::empty()
+            },
             view_formats: hal_view_formats,
         };
+        Ok((hal_desc, format_features))
+    }
 
-        let raw_texture = unsafe { self.raw().create_texture(&hal_desc) }
-            .map_err(|e| self.handle_hal_error_with_nonfatal_oom(e))?;
+    fn create_texture_impl(
+        self: &Arc<Self>,
+        desc: &resource::TextureDescriptor,
+        placement: Option<(&Arc<Heap>, wgt::BufferAddress)>,
+    ) -> Result<Arc<Texture>, resource::CreateTextureError> {
+        self.check_is_valid()?;
+
+        let (hal_desc, format_features) = self.texture_hal_descriptor(desc)?;
+        let hal_usage = hal_desc.usage;
+        let sparse = desc.usage.contains(wgt::TextureUsages::SPARSE);
+
+        let (raw_texture, placement) = match placement {
+            None => {
+                let raw_texture = unsafe { self.raw().create_texture(&hal_desc) }
+                    .map_err(|e| self.handle_hal_error_with_nonfatal_oom(e))?;
+                (raw_texture, None)
+            }
+            Some((heap, offset)) => {
+                heap.same_device(self)?;
+                self.require_features(wgt::Features::RESOURCE_HEAPS)
+                    .map_err(resource::PlacementError::from)?;
+                if sparse {
+                    return Err(resource::PlacementError::Sparse.into());
+                }
+                if desc.usage.contains(wgt::TextureUsages::TRANSIENT) {
+                    return Err(resource::PlacementError::Transient.into());
+                }
+
+                let requirements = unsafe { self.raw().get_texture_memory_requirements(&hal_desc) }
+                    .map_err(|e| self.handle_hal_error(e))?;
+                let placement = Self::place(heap, offset, requirements)?;
+                let raw_texture = unsafe {
+                    self.raw()
+                        .create_placed_texture(&hal_desc, heap.raw(), offset)
+                }
+                .map_err(|e| self.handle_hal_error_with_nonfatal_oom(e))?;
+                (raw_texture, Some(placement))
+            }
+        };
 
-        let clear_mode = if hal_usage
+        let clear_mode = if desc.usage.contains(wgt::TextureUsages::TRANSIENT) {
+            // Transient textures are always cleared by the render passes that use them.
+            resource::TextureClearMode::None
+        } else if hal_usage
             .intersects(wgt::TextureUses::DEPTH_STENCIL_WRITE | wgt::TextureUses::COLOR_TARGET)
             && desc.dimension == wgt::TextureDimension::D2
         {
@@ -1430,18 +1920,35 @@ impl Device {
             resource::TextureClearMode::BufferCopy
         };
 
-        let texture = Texture::new(
+        let mut texture = Texture::new(
             self,
             resource::TextureInner::Native { raw: raw_texture },
             hal_usage,
             desc,
             format_features,
             clear_mode,
-            true,
+            // Tiles of sparse textures are zeroed when they become resident,
+            // and reads from non-resident tiles return zero.
+            !sparse,
         );
+        if sparse {
+            texture.sparse_residency = Some(Mutex::new(
+                rank::RESOURCE_SPARSE_RESIDENCY,
+                SparseTextureResidency::new(desc),
+            ));
+        }
+        texture.placement = placement;
 
         let texture = Arc::new(texture);
 
+        if let Some(ref placement) = texture.placement {
+            placement.heap.aliasing.lock().insert(
+                PlacedResourceId::Texture(texture.tracker_index()),
+                placement.range.clone(),
+                texture.label.clone(),
+            );
+        }
+
         self.trackers
             .lock()
             .textures
@@ -2193,13 +2700,23 @@ impl Device {
         self.check_is_valid()?;
 
         let queue = self.get_queue().unwrap();
+        self.create_command_encoder_for_queue(&queue, label)
+    }
+
+    /// Create a command encoder whose commands can be submitted to `queue`.
+    pub(crate) fn create_command_encoder_for_queue(
+        self: &Arc<Self>,
+        queue: &Queue,
+        label: &crate::Label,
+    ) -> Result<Arc<command::CommandEncoder>, DeviceError> {
+        self.check_is_valid()?;
 
         let encoder = self
             .command_allocator
-            .acquire_encoder(self.raw(), queue.raw())
+            .acquire_encoder(self.raw(), queue.raw(), queue.ty())
             .map_err(|e| self.handle_hal_error(e))?;
 
-        let cmd_enc = command::CommandEncoder::new(encoder, self, label);
+        let cmd_enc = command::CommandEncoder::new(encoder, queue.ty(), self, label);
 
         let cmd_enc = Arc::new(cmd_enc);
 
@@ -3417,6 +3934,57 @@ impl Device {
         Ok(layout)
     }
 
+    /// Derive bind group layout entries for the resources used by each
+    /// `(module, entry point name)` pair in `entry_points`, merging the
+    /// entries of bindings that several of them use.
+    ///
+    /// Returns the entries of each bind group, sorted by binding index, up to
+    /// the last group that any of the entry points uses.
+    pub(crate) fn derive_bind_group_layouts(
+        self: &Arc<Self>,
+        entry_points: &[(Arc<pipeline::ShaderModule>, &str)],
+    ) -> Result<Vec<Vec<wgt::BindGroupLayoutEntry>>, pipeline::DeriveBindGroupLayoutsError> {
+        self.check_is_valid()?;
+
+        let mut derived_group_layouts = (0..self.limits.max_bind_groups)
+            .map(|_| bgl::EntryMap::default())
+            .collect::<ArrayVec<_, { hal::MAX_BIND_GROUPS }>>();
+
+        for (shader_module, entry_point) in entry_points {
+            shader_module.same_device(self)?;
+
+            let Some(ref interface) = shader_module.interface else {
+                return Err(
+                    pipeline::DeriveBindGroupLayoutsError::ReflectionUnavailable(
+                        shader_module.error_ident(),
+                    ),
+                );
+            };
+            interface
+                .derive_bind_group_layouts(&mut derived_group_layouts, entry_point)
+                .map_err(|error| pipeline::DeriveBindGroupLayoutsError::Stage {
+                    module: shader_module.error_ident(),
+                    entry_point: entry_point.to_string(),
+                    error,
+                })?;
+        }
+
+        while derived_group_layouts
+            .last()
+            .is_some_and(|map| map.is_empty())
+        {
+            derived_group_layouts.pop();
+        }
+
+        Ok(derived_group_layouts
+            .iter_mut()
+            .map(|map| {
+                map.sort();
+                map.values().copied().collect()
+            })
+            .collect())
+    }
+
     pub(crate) fn derive_pipeline_layout(
         self: &Arc<Self>,
         mut derived_group_layouts: Box<ArrayVec<bgl::EntryMap, { hal::MAX_BIND_GROUPS }>>,
@@ -4536,6 +5104,63 @@ impl Device {
         Ok(query_set)
     }
 
+    pub(crate) fn create_heap(
+        self: &Arc<Self>,
+        desc: &resource::HeapDescriptor,
+    ) -> Result<Arc<Heap>, resource::CreateHeapError> {
+        self.check_is_valid()?;
+        self.require_features(wgt::Features::RESOURCE_HEAPS)?;
+
+        if desc.size == 0 {
+            return Err(resource::CreateHeapError::ZeroSize);
+        }
+
+        let hal_desc = desc.map_label(|label| label.to_hal(self.instance_flags));
+
+        let raw = unsafe { self.raw().create_heap(&hal_desc) }
+            .map_err(|e| self.handle_hal_error_with_nonfatal_oom(e))?;
+
+        let heap = Heap {
+            raw: ManuallyDrop::new(raw),
+            device: self.clone(),
+            size: desc.size,
+            label: desc.label.to_string(),
+            aliasing: Mutex::new(rank::HEAP_ALIASING, AliasingTracker::new()),
+        };
+
+        Ok(Arc::new(heap))
+    }
+
+    /// Checks that a resource with the given memory requirements fits in
+    /// `heap` at `offset`.
+    fn place(
+        heap: &Arc<Heap>,
+        offset: wgt::BufferAddress,
+        requirements: wgt::MemoryRequirements,
+    ) -> Result<resource::Placement, resource::PlacementError> {
+        if offset % requirements.alignment != 0 {
+            return Err(resource::PlacementError::UnalignedOffset {
+                offset,
+                alignment: requirements.alignment,
+            });
+        }
+
+        let end = offset
+            .checked_add(requirements.size)
+            .filter(|&end| end <= heap.size)
+            .ok_or_else(|| resource::PlacementError::OutOfBounds {
+                offset,
+                size: requirements.size,
+                heap: heap.error_ident(),
+                heap_size: heap.size,
+            })?;
+
+        Ok(resource::Placement {
+            heap: heap.clone(),
+            range: offset..end,
+        })
+    }
+
     fn lose(&self, message: &str) {
         // Follow the steps at https://gpuweb.github.io/gpuweb/#lose-the-device.
 
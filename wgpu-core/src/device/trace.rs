@@ -12,6 +12,39 @@ type FileName = String;
 
 pub const FILE_NAME: &str = "trace.ron";
 
+/// The name of the file holding the actions of a trace in the binary [`Format`].
+pub const BINARY_FILE_NAME: &str = "trace.bin";
+
+/// The bytes a trace in the binary [`Format`] starts with.
+///
+/// The last byte is the version of the encoding.
+pub const BINARY_MAGIC: [u8; 8] = *b"WGPUTRC\x01";
+
+/// The encoding of the actions of a trace.
+///
+/// A RON trace is a list of actions, which is easy to read and edit by hand.
+/// A binary trace is [`BINARY_MAGIC`] followed by the actions, each encoded
+/// with `bincode`'s standard configuration, and can be written and read one
+/// action at a time.
+///
+/// In both cases, the data of buffers, textures and shaders is stored in
+/// separate files, next to the trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Ron,
+    Binary,
+}
+
+impl Format {
+    /// The name of the file the actions are stored in.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Ron => FILE_NAME,
+            Self::Binary => BINARY_FILE_NAME,
+        }
+    }
+}
+
 #[cfg(feature = "trace")]
 pub(crate) fn new_render_bundle_encoder_descriptor<'a>(
     label: crate::Label<'a>,
@@ -124,6 +157,23 @@ pub enum Action<'a> {
         desc: crate::resource::QuerySetDescriptor<'a>,
     },
     DestroyQuerySet(id::QuerySetId),
+    CreateHeap {
+        id: id::HeapId,
+        desc: crate::resource::HeapDescriptor<'a>,
+    },
+    DestroyHeap(id::HeapId),
+    CreatePlacedBuffer {
+        id: id::BufferId,
+        heap: id::HeapId,
+        offset: wgt::BufferAddress,
+        desc: crate::resource::BufferDescriptor<'a>,
+    },
+    CreatePlacedTexture {
+        id: id::TextureId,
+        heap: id::HeapId,
+        offset: wgt::BufferAddress,
+        desc: crate::resource::TextureDescriptor<'a>,
+    },
     WriteBuffer {
         id: id::BufferId,
         data: FileName,
@@ -136,6 +186,10 @@ pub enum Action<'a> {
         layout: wgt::TexelCopyBufferLayout,
         size: wgt::Extent3d,
     },
+    UpdateSparseResidency {
+        buffers: Vec<wgt::SparseBufferBinding<id::BufferId>>,
+        textures: Vec<wgt::SparseTextureBinding<id::TextureId>>,
+    },
     Submit(crate::SubmissionIndex, Vec<Command>),
     CreateBlas {
         id: id::BlasId,
@@ -213,6 +267,10 @@ pub enum Command {
         blas: Vec<crate::ray_tracing::TraceBlasBuildEntry>,
         tlas: Vec<crate::ray_tracing::TraceTlasPackage>,
     },
+    AliasingBarrier {
+        buffers: Vec<id::BufferId>,
+        textures: Vec<id::TextureId>,
+    },
 }
 
 #[cfg(feature = "trace")]
@@ -220,20 +278,38 @@ pub enum Command {
 pub struct Trace {
     path: std::path::PathBuf,
     file: std::fs::File,
-    config: ron::ser::PrettyConfig,
+    encoder: Encoder,
     binary_id: usize,
 }
 
+#[cfg(feature = "trace")]
+#[derive(Debug)]
+enum Encoder {
+    Ron(ron::ser::PrettyConfig),
+    /// The buffer each action is encoded into before being written, so that
+    /// it's written at once.
+    Binary(Vec<u8>),
+}
+
 #[cfg(feature = "trace")]
 impl Trace {
-    pub fn new(path: std::path::PathBuf) -> Result<Self, std::io::Error> {
+    pub fn new(path: std::path::PathBuf, format: Format) -> Result<Self, std::io::Error> {
         log::info!("Tracing into '{path:?}'");
-        let mut file = std::fs::File::create(path.join(FILE_NAME))?;
-        file.write_all(b"[\n")?;
+        let mut file = std::fs::File::create(path.join(format.file_name()))?;
+        let encoder = match format {
+            Format::Ron => {
+                file.write_all(b"[\n")?;
+                Encoder::Ron(ron::ser::PrettyConfig::default())
+            }
+            Format::Binary => {
+                file.write_all(&BINARY_MAGIC)?;
+                Encoder::Binary(Vec::new())
+            }
+        };
         Ok(Self {
             path,
             file,
-            config: ron::ser::PrettyConfig::default(),
+            encoder,
             binary_id: 0,
         })
     }
@@ -245,13 +321,30 @@ impl Trace {
         name
     }
 
-    pub(crate) fn add(&mut self, action: Action) {
-        match ron::ser::to_string_pretty(&action, self.config.clone()) {
-            Ok(string) => {
-                let _ = writeln!(self.file, "{string},");
-            }
-            Err(e) => {
-                log::warn!("RON serialization failure: {e:?}");
+    pub fn add(&mut self, action: Action) {
+        match self.encoder {
+            Encoder::Ron(ref config) => match ron::ser::to_string_pretty(&action, config.clone()) {
+                Ok(string) => {
+                    let _ = writeln!(self.file, "{string},");
+                }
+                Err(e) => {
+                    log::warn!("RON serialization failure: {e:?}");
+                }
+            },
+            Encoder::Binary(ref mut buffer) => {
+                buffer.clear();
+                match bincode::serde::encode_into_std_write(
+                    &action,
+                    buffer,
+                    bincode::config::standard(),
+                ) {
+                    Ok(_) => {
+                        let _ = self.file.write_all(buffer);
+                    }
+                    Err(e) => {
+                        log::warn!("Binary serialization failure: {e:?}");
+                    }
+                }
             }
         }
     }
@@ -260,6 +353,48 @@ impl Trace {
 #[cfg(feature = "trace")]
 impl Drop for Trace {
     fn drop(&mut self) {
-        let _ = self.file.write_all(b"]");
+        if let Encoder::Ron(_) = self.encoder {
+            let _ = self.file.write_all(b"]");
+        }
+    }
+}
+
+/// Reads the actions of a trace in the binary [`Format`] one at a time.
+#[cfg(feature = "replay")]
+#[derive(Debug)]
+pub struct BinaryReader<R> {
+    reader: R,
+}
+
+#[cfg(feature = "replay")]
+impl<R: std::io::BufRead> BinaryReader<R> {
+    /// Starts reading a binary trace, checking that it starts with [`BINARY_MAGIC`].
+    pub fn new(mut reader: R) -> Result<Self, std::io::Error> {
+        let mut magic = [0; BINARY_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != BINARY_MAGIC {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "not a binary trace, or written by an incompatible version",
+            ));
+        }
+        Ok(Self { reader })
+    }
+}
+
+#[cfg(feature = "replay")]
+impl<R: std::io::BufRead> Iterator for BinaryReader<R> {
+    type Item = Result<Action<'static>, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.reader.fill_buf() {
+            Ok([]) => return None,
+            Ok(_) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        Some(
+            bincode::serde::decode_from_std_read(&mut self.reader, bincode::config::standard())
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+        )
     }
 }
@@ -111,7 +111,7 @@ use crate::{
     pipeline::{ComputePipeline, PipelineCache, RenderPipeline, ShaderModule},
     registry::{Registry, RegistryReport},
     resource::{
-        Blas, Buffer, ExternalTexture, Fallible, QuerySet, Sampler, StagingBuffer, Texture,
+        Blas, Buffer, ExternalTexture, Fallible, Heap, QuerySet, Sampler, StagingBuffer, Texture,
         TextureView, Tlas,
     },
 };
@@ -132,6 +132,7 @@ pub struct HubReport {
     pub compute_pipelines: RegistryReport,
     pub pipeline_caches: RegistryReport,
     pub query_sets: RegistryReport,
+    pub heaps: RegistryReport,
     pub buffers: RegistryReport,
     pub textures: RegistryReport,
     pub texture_views: RegistryReport,
@@ -181,6 +182,7 @@ pub struct Hub {
     pub(crate) compute_pipelines: Registry<Fallible<ComputePipeline>>,
     pub(crate) pipeline_caches: Registry<Fallible<PipelineCache>>,
     pub(crate) query_sets: Registry<Fallible<QuerySet>>,
+    pub(crate) heaps: Registry<Fallible<Heap>>,
     pub(crate) buffers: Registry<Fallible<Buffer>>,
     pub(crate) staging_buffers: Registry<StagingBuffer>,
     pub(crate) textures: Registry<Fallible<Texture>>,
@@ -208,6 +210,7 @@ impl Hub {
             compute_pipelines: Registry::new(),
             pipeline_caches: Registry::new(),
             query_sets: Registry::new(),
+            heaps: Registry::new(),
             buffers: Registry::new(),
             staging_buffers: Registry::new(),
             textures: Registry::new(),
@@ -235,6 +238,7 @@ impl Hub {
             compute_pipelines: self.compute_pipelines.generate_report(),
             pipeline_caches: self.pipeline_caches.generate_report(),
             query_sets: self.query_sets.generate_report(),
+            heaps: self.heaps.generate_report(),
             buffers: self.buffers.generate_report(),
             textures: self.textures.generate_report(),
             texture_views: self.texture_views.generate_report(),
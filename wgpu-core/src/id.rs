@@ -277,6 +277,7 @@ ids! {
     pub type RenderBundleEncoderId RenderBundleEncoder;
     pub type RenderBundleId RenderBundle;
     pub type QuerySetId QuerySet;
+    pub type HeapId Heap;
     pub type BlasId Blas;
     pub type TlasId Tlas;
 }
@@ -24,12 +24,23 @@ impl BufferInitTracker {
 
     /// Creates an action if it would have any effect on the initialization
     /// status and shrinks the range if possible.
+    ///
+    /// Placed buffers are left uninitialized by the aliasing barriers that
+    /// activate them, which only happens at submission, so actions on them
+    /// are never dropped or shrunk.
     pub(crate) fn create_action(
         &self,
         buffer: &Arc<Buffer>,
         query_range: Range<wgt::BufferAddress>,
         kind: MemoryInitKind,
     ) -> Option<BufferInitTrackerAction> {
+        if buffer.placement.is_some() {
+            return (!query_range.is_empty()).then(|| BufferInitTrackerAction {
+                buffer: buffer.clone(),
+                range: query_range,
+                kind,
+            });
+        }
         self.check(query_range)
             .map(|range| BufferInitTrackerAction {
                 buffer: buffer.clone(),
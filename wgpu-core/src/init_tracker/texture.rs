@@ -65,6 +65,11 @@ impl TextureInitTracker {
         &self,
         action: &TextureInitTrackerAction,
     ) -> Option<TextureInitTrackerAction> {
+        // See `BufferInitTracker::create_action`.
+        if action.texture.placement.is_some() {
+            return Some(action.clone());
+        }
+
         let mut mip_range_start = usize::MAX;
         let mut mip_range_end = usize::MIN;
         let mut layer_range_start = u32::MAX;
@@ -773,10 +773,21 @@ impl Adapter {
     ) -> Result<(Arc<Device>, Arc<Queue>), RequestDeviceError> {
         api_log!("Adapter::create_device");
 
-        let device = Device::new(hal_device.device, self, desc, instance_flags)?;
+        let device = Device::new(
+            hal_device.device,
+            hal_device.additional_queues,
+            self,
+            desc,
+            instance_flags,
+        )?;
         let device = Arc::new(device);
 
-        let queue = Queue::new(device.clone(), hal_device.queue, instance_flags)?;
+        let queue = Queue::new(
+            device.clone(),
+            hal_device.queue,
+            wgt::QueueType::Graphics,
+            instance_flags,
+        )?;
         let queue = Arc::new(queue);
 
         device.set_queue(&queue);
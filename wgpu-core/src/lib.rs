@@ -90,6 +90,7 @@ pub mod ray_tracing;
 pub mod registry;
 pub mod resource;
 mod snatch;
+mod sparse;
 pub mod storage;
 mod timestamp_normalization;
 mod track;
@@ -101,10 +101,12 @@ define_lock_ranks! {
         DEVICE_SNATCHABLE_LOCK,
         DEVICE_USAGE_SCOPES,
         SHARED_TRACKER_INDEX_ALLOCATOR_INNER,
+        HEAP_ALIASING,
         BUFFER_MAP_STATE,
     }
     rank DEVICE_SNATCHABLE_LOCK "Device::snatchable_lock" followed by {
         SHARED_TRACKER_INDEX_ALLOCATOR_INNER,
+        HEAP_ALIASING,
         DEVICE_TRACE,
         BUFFER_MAP_STATE,
         // Uncomment this to see an interesting cycle.
@@ -113,32 +115,47 @@ define_lock_ranks! {
     rank BUFFER_MAP_STATE "Buffer::map_state" followed by {
         QUEUE_PENDING_WRITES,
         SHARED_TRACKER_INDEX_ALLOCATOR_INNER,
+        HEAP_ALIASING,
         DEVICE_TRACE,
     }
     rank QUEUE_PENDING_WRITES "Queue::pending_writes" followed by {
         COMMAND_ALLOCATOR_FREE_ENCODERS,
         SHARED_TRACKER_INDEX_ALLOCATOR_INNER,
+        HEAP_ALIASING,
         QUEUE_LIFE_TRACKER,
+        RESOURCE_QUEUE_OWNER,
+        RESOURCE_SPARSE_RESIDENCY,
+        QUEUE_WAITS,
     }
     rank QUEUE_LIFE_TRACKER "Queue::life_tracker" followed by {
         COMMAND_ALLOCATOR_FREE_ENCODERS,
         DEVICE_TRACE,
+        HEAP_ALIASING,
     }
     rank COMMAND_ALLOCATOR_FREE_ENCODERS "CommandAllocator::free_encoders" followed by {
         SHARED_TRACKER_INDEX_ALLOCATOR_INNER,
+        HEAP_ALIASING,
     }
 
     rank BUFFER_BIND_GROUPS "Buffer::bind_groups" followed by { }
     rank BUFFER_INITIALIZATION_STATUS "Buffer::initialization_status" followed by { }
-    rank DEVICE_COMMAND_INDICES "Device::command_indices" followed by {}
+    rank DEVICE_COMMAND_INDICES "Device::command_indices" followed by { HEAP_ALIASING }
     rank DEVICE_DEFERRED_DESTROY "Device::deferred_destroy" followed by {}
-    rank DEVICE_FENCE "Device::fence" followed by { }
+    rank DEVICE_FENCE "Device::fence" followed by { ADDITIONAL_QUEUE_FENCE }
+    rank ADDITIONAL_QUEUE_FENCE "AdditionalQueue::fence" followed by { }
+    rank ADDITIONAL_QUEUE_STATE "AdditionalQueue::state" followed by { }
     #[allow(dead_code)]
     rank DEVICE_TRACE "Device::trace" followed by { }
     rank DEVICE_TRACKERS "Device::trackers" followed by { }
     rank DEVICE_LOST_CLOSURE "Device::device_lost_closure" followed by { }
     rank DEVICE_USAGE_SCOPES "Device::usage_scopes" followed by { }
+    rank HEAP_ALIASING "Heap::aliasing" followed by { }
     rank IDENTITY_MANAGER_VALUES "IdentityManager::values" followed by { }
+    #[cfg_attr(not(threads), allow(dead_code))]
+    rank PIPELINE_WORKERS_RECEIVER "PipelineWorkers::receiver" followed by { }
+    rank QUEUE_WAITS "Queue::waits" followed by { }
+    rank RESOURCE_QUEUE_OWNER "QueueOwner::last_use" followed by { }
+    rank RESOURCE_SPARSE_RESIDENCY "Buffer::sparse_residency, Texture::sparse_residency" followed by { }
     rank REGISTRY_STORAGE "Registry::storage" followed by { }
     rank RESOURCE_POOL_INNER "ResourcePool::inner" followed by { }
     rank SHARED_TRACKER_INDEX_ALLOCATOR_INNER "SharedTrackerIndexAllocator::inner" followed by { }
@@ -17,8 +17,8 @@ use crate::{
     binding_model::{CreateBindGroupLayoutError, CreatePipelineLayoutError, PipelineLayout},
     command::ColorAttachmentError,
     device::{Device, DeviceError, MissingDownlevelFlags, MissingFeatures, RenderPassContext},
-    id::{PipelineCacheId, PipelineLayoutId, ShaderModuleId},
-    resource::{InvalidResourceError, Labeled, TrackingData},
+    id::{ComputePipelineId, PipelineCacheId, PipelineLayoutId, RenderPipelineId, ShaderModuleId},
+    resource::{InvalidResourceError, Labeled, ResourceErrorIdent, TrackingData},
     resource_log, validation, Label,
 };
 
@@ -180,6 +180,20 @@ pub struct ProgrammableStageDescriptor<'a, SM = ShaderModuleId> {
     pub zero_initialize_workgroup_memory: bool,
 }
 
+impl<SM> ProgrammableStageDescriptor<'_, SM> {
+    /// Copies any borrowed data, so the descriptor can outlive it.
+    pub(crate) fn into_owned(self) -> ProgrammableStageDescriptor<'static, SM> {
+        ProgrammableStageDescriptor {
+            module: self.module,
+            entry_point: self
+                .entry_point
+                .map(|entry_point| Cow::Owned(entry_point.into_owned())),
+            constants: self.constants,
+            zero_initialize_workgroup_memory: self.zero_initialize_workgroup_memory,
+        }
+    }
+}
+
 /// cbindgen:ignore
 pub type ResolvedProgrammableStageDescriptor<'a> =
     ProgrammableStageDescriptor<'a, Arc<ShaderModule>>;
@@ -209,6 +223,38 @@ impl WebGpuError for ImplicitLayoutError {
     }
 }
 
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum DeriveBindGroupLayoutsError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error(
+        "Unable to reflect the interface of {0}, which was created from passthrough shader code"
+    )]
+    ReflectionUnavailable(ResourceErrorIdent),
+    #[error("Error deriving bind group layouts for entry point {entry_point:?} of {module}")]
+    Stage {
+        module: ResourceErrorIdent,
+        entry_point: String,
+        #[source]
+        error: validation::StageError,
+    },
+    #[error(transparent)]
+    InvalidResource(#[from] InvalidResourceError),
+}
+
+impl WebGpuError for DeriveBindGroupLayoutsError {
+    fn webgpu_error_type(&self) -> ErrorType {
+        let e: &dyn WebGpuError = match self {
+            Self::Device(e) => e,
+            Self::InvalidResource(e) => e,
+            Self::Stage { error, .. } => error,
+            Self::ReflectionUnavailable(_) => return ErrorType::Validation,
+        };
+        e.webgpu_error_type()
+    }
+}
+
 /// Describes a compute pipeline.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -227,6 +273,18 @@ pub struct ComputePipelineDescriptor<
     pub cache: Option<PLC>,
 }
 
+impl<PLL, SM, PLC> ComputePipelineDescriptor<'_, PLL, SM, PLC> {
+    /// Copies any borrowed data, so the descriptor can outlive it.
+    pub(crate) fn into_owned(self) -> ComputePipelineDescriptor<'static, PLL, SM, PLC> {
+        ComputePipelineDescriptor {
+            label: self.label.map(|label| Cow::Owned(label.into_owned())),
+            layout: self.layout,
+            stage: self.stage.into_owned(),
+            cache: self.cache,
+        }
+    }
+}
+
 /// cbindgen:ignore
 pub type ResolvedComputePipelineDescriptor<'a> =
     ComputePipelineDescriptor<'a, Arc<PipelineLayout>, Arc<ShaderModule>, Arc<PipelineCache>>;
@@ -265,6 +323,13 @@ impl WebGpuError for CreateComputePipelineError {
     }
 }
 
+#[cfg(send_sync)]
+pub type CreateComputePipelineCallback =
+    Box<dyn FnOnce(ComputePipelineId, Option<CreateComputePipelineError>) + Send + 'static>;
+#[cfg(not(send_sync))]
+pub type CreateComputePipelineCallback =
+    Box<dyn FnOnce(ComputePipelineId, Option<CreateComputePipelineError>) + 'static>;
+
 #[derive(Debug)]
 pub struct ComputePipeline {
     pub(crate) raw: ManuallyDrop<Box<dyn hal::DynComputePipeline>>,
@@ -376,6 +441,16 @@ impl Default for VertexBufferLayout<'_> {
     }
 }
 
+impl VertexBufferLayout<'_> {
+    fn into_owned(self) -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: self.array_stride,
+            step_mode: self.step_mode,
+            attributes: Cow::Owned(self.attributes.into_owned()),
+        }
+    }
+}
+
 /// Describes the vertex process in a render pipeline.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -386,6 +461,20 @@ pub struct VertexState<'a, SM = ShaderModuleId> {
     pub buffers: Cow<'a, [VertexBufferLayout<'a>]>,
 }
 
+impl<SM> VertexState<'_, SM> {
+    fn into_owned(self) -> VertexState<'static, SM> {
+        VertexState {
+            stage: self.stage.into_owned(),
+            buffers: self
+                .buffers
+                .into_owned()
+                .into_iter()
+                .map(VertexBufferLayout::into_owned)
+                .collect(),
+        }
+    }
+}
+
 /// cbindgen:ignore
 pub type ResolvedVertexState<'a> = VertexState<'a, Arc<ShaderModule>>;
 
@@ -399,6 +488,15 @@ pub struct FragmentState<'a, SM = ShaderModuleId> {
     pub targets: Cow<'a, [Option<wgt::ColorTargetState>]>,
 }
 
+impl<SM> FragmentState<'_, SM> {
+    fn into_owned(self) -> FragmentState<'static, SM> {
+        FragmentState {
+            stage: self.stage.into_owned(),
+            targets: Cow::Owned(self.targets.into_owned()),
+        }
+    }
+}
+
 /// cbindgen:ignore
 pub type ResolvedFragmentState<'a> = FragmentState<'a, Arc<ShaderModule>>;
 
@@ -429,6 +527,22 @@ pub(crate) enum RenderPipelineVertexProcessor<'a, SM = ShaderModuleId> {
     Mesh(Option<TaskState<'a, SM>>, MeshState<'a, SM>),
 }
 
+impl<SM> RenderPipelineVertexProcessor<'_, SM> {
+    fn into_owned(self) -> RenderPipelineVertexProcessor<'static, SM> {
+        match self {
+            Self::Vertex(vertex) => RenderPipelineVertexProcessor::Vertex(vertex.into_owned()),
+            Self::Mesh(task, mesh) => RenderPipelineVertexProcessor::Mesh(
+                task.map(|task| TaskState {
+                    stage: task.stage.into_owned(),
+                }),
+                MeshState {
+                    stage: mesh.stage.into_owned(),
+                },
+            ),
+        }
+    }
+}
+
 /// Describes a render (graphics) pipeline.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -525,6 +639,23 @@ pub(crate) struct GeneralRenderPipelineDescriptor<
     /// The pipeline cache to use when creating this pipeline.
     pub cache: Option<PLC>,
 }
+impl<PLL, SM, PLC> GeneralRenderPipelineDescriptor<'_, PLL, SM, PLC> {
+    /// Copies any borrowed data, so the descriptor can outlive it.
+    pub(crate) fn into_owned(self) -> GeneralRenderPipelineDescriptor<'static, PLL, SM, PLC> {
+        GeneralRenderPipelineDescriptor {
+            label: self.label.map(|label| Cow::Owned(label.into_owned())),
+            layout: self.layout,
+            vertex: self.vertex.into_owned(),
+            primitive: self.primitive,
+            depth_stencil: self.depth_stencil,
+            multisample: self.multisample,
+            fragment: self.fragment.map(FragmentState::into_owned),
+            multiview: self.multiview,
+            cache: self.cache,
+        }
+    }
+}
+
 impl<'a, PLL, SM, PLC> From<RenderPipelineDescriptor<'a, PLL, SM, PLC>>
     for GeneralRenderPipelineDescriptor<'a, PLL, SM, PLC>
 {
@@ -730,6 +861,13 @@ impl WebGpuError for CreateRenderPipelineError {
     }
 }
 
+#[cfg(send_sync)]
+pub type CreateRenderPipelineCallback =
+    Box<dyn FnOnce(RenderPipelineId, Option<CreateRenderPipelineError>) + Send + 'static>;
+#[cfg(not(send_sync))]
+pub type CreateRenderPipelineCallback =
+    Box<dyn FnOnce(RenderPipelineId, Option<CreateRenderPipelineError>) + 'static>;
+
 bitflags::bitflags! {
     #[repr(transparent)]
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -17,7 +17,7 @@ use wgt::{
 };
 
 use crate::{
-    command::EncoderStateError,
+    command::{EncoderStateError, UnsupportedQueueType},
     device::{DeviceError, MissingFeatures},
     id::{BlasId, BufferId, TlasId},
     resource::{
@@ -104,6 +104,9 @@ pub enum BuildAccelerationStructureError {
     #[error(transparent)]
     MissingFeatures(#[from] MissingFeatures),
 
+    #[error(transparent)]
+    UnsupportedQueueType(#[from] UnsupportedQueueType),
+
     #[error(
         "Buffer {0:?} size is insufficient for provided size information (size: {1}, required: {2}"
     )]
@@ -190,6 +193,7 @@ impl WebGpuError for BuildAccelerationStructureError {
             Self::DestroyedResource(e) => e,
             Self::MissingBufferUsage(e) => e,
             Self::MissingFeatures(e) => e,
+            Self::UnsupportedQueueType(e) => e,
             Self::InsufficientBufferSize(..)
             | Self::UnalignedIndexBufferOffset(..)
             | Self::UnalignedTransformBufferOffset(..)
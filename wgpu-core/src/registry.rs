@@ -36,25 +36,27 @@ impl RegistryReport {
 pub(crate) struct Registry<T: StorageItem> {
     // Must only contain an id which has either never been used or has been released from `storage`
     identity: Arc<IdentityManager<T::Marker>>,
-    storage: RwLock<Storage<T>>,
+    // Shared with the `FutureId`s handed out by `prepare`, so resources can be
+    // assigned from other threads, such as the pipeline workers of a device.
+    storage: Arc<RwLock<Storage<T>>>,
 }
 
 impl<T: StorageItem> Registry<T> {
     pub(crate) fn new() -> Self {
         Self {
             identity: Arc::new(IdentityManager::new()),
-            storage: RwLock::new(rank::REGISTRY_STORAGE, Storage::new()),
+            storage: Arc::new(RwLock::new(rank::REGISTRY_STORAGE, Storage::new())),
         }
     }
 }
 
 #[must_use]
-pub(crate) struct FutureId<'a, T: StorageItem> {
+pub(crate) struct FutureId<T: StorageItem> {
     id: Id<T::Marker>,
-    data: &'a RwLock<Storage<T>>,
+    data: Arc<RwLock<Storage<T>>>,
 }
 
-impl<T: StorageItem> FutureId<'_, T> {
+impl<T: StorageItem> FutureId<T> {
     #[cfg(feature = "trace")]
     pub fn id(&self) -> Id<T::Marker> {
         self.id
@@ -71,7 +73,7 @@ impl<T: StorageItem> FutureId<'_, T> {
 }
 
 impl<T: StorageItem> Registry<T> {
-    pub(crate) fn prepare(&self, id_in: Option<Id<T::Marker>>) -> FutureId<'_, T> {
+    pub(crate) fn prepare(&self, id_in: Option<Id<T::Marker>>) -> FutureId<T> {
         FutureId {
             id: match id_in {
                 Some(id_in) => {
@@ -80,7 +82,7 @@ impl<T: StorageItem> Registry<T> {
                 }
                 None => self.identity.process(),
             },
-            data: &self.storage,
+            data: Arc::clone(&self.storage),
         }
     }
 
@@ -28,8 +28,12 @@ use crate::{
     ray_tracing::{BlasCompactReadyPendingClosure, BlasPrepareCompactError},
     resource_log,
     snatch::{SnatchGuard, Snatchable},
+    sparse::{SparseResidency, SparseTextureResidency},
     timestamp_normalization::TimestampNormalizationBindGroup,
-    track::{SharedTrackerIndexAllocator, TrackerIndex},
+    track::{
+        AliasingTracker, InactiveResourceError, PendingActivations, PlacedResourceId, QueueOwner,
+        SharedTrackerIndexAllocator, TrackerIndex,
+    },
     weak_vec::WeakVec,
     Label, LabelHelpers, SubmissionIndex,
 };
@@ -438,10 +442,25 @@ pub struct Buffer {
     pub(crate) bind_groups: Mutex<WeakVec<BindGroup>>,
     pub(crate) timestamp_normalization_bind_group: Snatchable<TimestampNormalizationBindGroup>,
     pub(crate) indirect_validation_bind_groups: Snatchable<crate::indirect_validation::BindGroups>,
+    /// The queue that last used this buffer.
+    pub(crate) queue_owner: QueueOwner,
+    /// Which pages are resident, if the buffer was created with
+    /// [`wgt::BufferUsages::SPARSE`].
+    pub(crate) sparse_residency: Option<Mutex<SparseResidency>>,
+    /// Where the buffer is, if it was placed in a [`Heap`].
+    pub(crate) placement: Option<Placement>,
 }
 
 impl Drop for Buffer {
     fn drop(&mut self) {
+        if let Some(ref placement) = self.placement {
+            placement
+                .heap
+                .aliasing
+                .lock()
+                .remove(PlacedResourceId::Buffer(self.tracker_index()));
+        }
+
         if let Some(raw) = self.timestamp_normalization_bind_group.take() {
             raw.dispose(self.device.raw());
         }
@@ -478,6 +497,25 @@ impl Buffer {
             .ok_or_else(|| DestroyedResourceError(self.error_ident()))
     }
 
+    /// Checks that the buffer holds meaningful contents, which is only
+    /// untrue of placed buffers that a later aliasing barrier overlapped.
+    ///
+    /// If given, the `pending` activations of a submission are taken into
+    /// account.
+    pub(crate) fn check_active(
+        &self,
+        pending: Option<&PendingActivations>,
+    ) -> Result<(), InactiveResourceError> {
+        match self.placement {
+            Some(ref placement) => placement.check_active(
+                PlacedResourceId::Buffer(self.tracker_index()),
+                pending,
+                || self.error_ident(),
+            ),
+            None => Ok(()),
+        }
+    }
+
     /// Checks that the given buffer usage contains the required buffer usage,
     /// returns an error otherwise.
     pub(crate) fn check_usage(
@@ -663,7 +701,7 @@ impl Buffer {
             .buffers
             .set_single(self, internal_use);
 
-        let submit_index = if let Some(queue) = device.get_queue() {
+        let submit_index = if let Some(queue) = device.get_owning_queue(&self.queue_owner) {
             queue.lock_life().map(self).unwrap_or(0) // '0' means no wait is necessary
         } else {
             // We can safely unwrap below since we just set the `map_state` to `BufferMapState::Waiting`.
@@ -803,6 +841,7 @@ impl Buffer {
                     }
                     pending_writes.consume(staging_buffer);
                     pending_writes.insert_buffer(self);
+                    self.queue_owner.set(wgt::QueueType::Graphics, None);
                 }
             }
             BufferMapState::Idle => {
@@ -877,10 +916,14 @@ impl Buffer {
                 bind_groups,
                 timestamp_normalization_bind_group,
                 indirect_validation_bind_groups,
+                _heap: self
+                    .placement
+                    .as_ref()
+                    .map(|placement| placement.heap.clone()),
             })
         };
 
-        if let Some(queue) = device.get_queue() {
+        if let Some(queue) = device.get_owning_queue(&self.queue_owner) {
             let mut pending_writes = queue.pending_writes.lock();
             if pending_writes.contains_buffer(self) {
                 pending_writes.consume_temp(temp);
@@ -910,12 +953,18 @@ pub enum CreateBufferError {
     UsageMismatch(wgt::BufferUsages),
     #[error("Buffer size {requested} is greater than the maximum buffer size ({maximum})")]
     MaxBufferSize { requested: u64, maximum: u64 },
+    #[error("Sparse buffers can't be mapped")]
+    MappableSparseBuffer,
+    #[error("Size {0} of a sparse buffer is not a multiple of `SPARSE_PAGE_SIZE`")]
+    UnalignedSparseSize(u64),
     #[error(transparent)]
     MissingDownlevelFlags(#[from] MissingDownlevelFlags),
     #[error(transparent)]
     MissingFeatures(#[from] MissingFeatures),
     #[error("Failed to create bind group for indirect buffer validation: {0}")]
     IndirectValidationBindGroup(DeviceError),
+    #[error(transparent)]
+    Placement(#[from] PlacementError),
 }
 
 crate::impl_resource_type!(Buffer);
@@ -932,11 +981,14 @@ impl WebGpuError for CreateBufferError {
             Self::MissingDownlevelFlags(e) => e,
             Self::IndirectValidationBindGroup(e) => e,
             Self::MissingFeatures(e) => e,
+            Self::Placement(e) => e,
 
             Self::UnalignedSize
             | Self::InvalidUsage(_)
             | Self::UsageMismatch(_)
-            | Self::MaxBufferSize { .. } => return ErrorType::Validation,
+            | Self::MaxBufferSize { .. }
+            | Self::MappableSparseBuffer
+            | Self::UnalignedSparseSize(_) => return ErrorType::Validation,
         };
         e.webgpu_error_type()
     }
@@ -951,6 +1003,8 @@ pub struct DestroyedBuffer {
     bind_groups: WeakVec<BindGroup>,
     timestamp_normalization_bind_group: Option<TimestampNormalizationBindGroup>,
     indirect_validation_bind_groups: Option<crate::indirect_validation::BindGroups>,
+    /// The heap the buffer was placed in, which must outlive it.
+    _heap: Option<Arc<Heap>>,
 }
 
 impl DestroyedBuffer {
@@ -1194,6 +1248,13 @@ pub struct Texture {
     pub(crate) clear_mode: RwLock<TextureClearMode>,
     pub(crate) views: Mutex<WeakVec<TextureView>>,
     pub(crate) bind_groups: Mutex<WeakVec<BindGroup>>,
+    /// The queue that last used this texture.
+    pub(crate) queue_owner: QueueOwner,
+    /// Which tiles are resident, if the texture was created with
+    /// [`wgt::TextureUsages::SPARSE`].
+    pub(crate) sparse_residency: Option<Mutex<SparseTextureResidency>>,
+    /// Where the texture is, if it was placed in a [`Heap`].
+    pub(crate) placement: Option<Placement>,
 }
 
 impl Texture {
@@ -1229,6 +1290,9 @@ impl Texture {
             clear_mode: RwLock::new(rank::TEXTURE_CLEAR_MODE, clear_mode),
             views: Mutex::new(rank::TEXTURE_VIEWS, WeakVec::new()),
             bind_groups: Mutex::new(rank::TEXTURE_BIND_GROUPS, WeakVec::new()),
+            queue_owner: QueueOwner::new(),
+            sparse_residency: None,
+            placement: None,
         }
     }
 
@@ -1252,6 +1316,14 @@ impl Texture {
 
 impl Drop for Texture {
     fn drop(&mut self) {
+        if let Some(ref placement) = self.placement {
+            placement
+                .heap
+                .aliasing
+                .lock()
+                .remove(PlacedResourceId::Texture(self.tracker_index()));
+        }
+
         match *self.clear_mode.write() {
             TextureClearMode::Surface {
                 ref mut clear_view, ..
@@ -1314,6 +1386,25 @@ impl Texture {
             .ok_or_else(|| DestroyedResourceError(self.error_ident()))
     }
 
+    /// Checks that the texture holds meaningful contents, which is only
+    /// untrue of placed textures that a later aliasing barrier overlapped.
+    ///
+    /// If given, the `pending` activations of a submission are taken into
+    /// account.
+    pub(crate) fn check_active(
+        &self,
+        pending: Option<&PendingActivations>,
+    ) -> Result<(), InactiveResourceError> {
+        match self.placement {
+            Some(ref placement) => placement.check_active(
+                PlacedResourceId::Texture(self.tracker_index()),
+                pending,
+                || self.error_ident(),
+            ),
+            None => Ok(()),
+        }
+    }
+
     pub(crate) fn get_clear_view<'a>(
         clear_mode: &'a TextureClearMode,
         desc: &'a wgt::TextureDescriptor<(), Vec<wgt::TextureFormat>>,
@@ -1375,10 +1466,14 @@ impl Texture {
                 bind_groups,
                 device: Arc::clone(&self.device),
                 label: self.label().to_owned(),
+                _heap: self
+                    .placement
+                    .as_ref()
+                    .map(|placement| placement.heap.clone()),
             })
         };
 
-        if let Some(queue) = device.get_queue() {
+        if let Some(queue) = device.get_owning_queue(&self.queue_owner) {
             let mut pending_writes = queue.pending_writes.lock();
             if pending_writes.contains_texture(self) {
                 pending_writes.consume_temp(temp);
@@ -1402,6 +1497,8 @@ pub struct DestroyedTexture {
     bind_groups: WeakVec<BindGroup>,
     device: Arc<Device>,
     label: String,
+    /// The heap the texture was placed in, which must outlive it.
+    _heap: Option<Arc<Heap>>,
 }
 
 impl DestroyedTexture {
@@ -1536,10 +1633,24 @@ pub enum CreateTextureError {
     InvalidSampleCount(u32, wgt::TextureFormat, Vec<u32>, Vec<u32>),
     #[error("Multisampled textures must have RENDER_ATTACHMENT usage")]
     MultisampledNotRenderAttachment,
+    #[error("Transient textures must have exactly the RENDER_ATTACHMENT and TRANSIENT usages, but {0:?} were requested")]
+    InvalidTransientUsage(wgt::TextureUsages),
+    #[error("Sparse textures must be single-sampled 2D textures")]
+    InvalidSparseDimension,
+    #[error("Format {0:?} can't be used by sparse textures")]
+    InvalidSparseFormat(wgt::TextureFormat),
+    #[error("Size {size:?} of mip level {mip_level} of a sparse texture is not a multiple of the tile size {tile_size:?}")]
+    UnalignedSparseMipLevel {
+        mip_level: u32,
+        size: wgt::Extent3d,
+        tile_size: wgt::Extent3d,
+    },
     #[error("Texture format {0:?} can't be used due to missing features")]
     MissingFeatures(wgt::TextureFormat, #[source] MissingFeatures),
     #[error(transparent)]
     MissingDownlevelFlags(#[from] MissingDownlevelFlags),
+    #[error(transparent)]
+    Placement(#[from] PlacementError),
 }
 
 crate::impl_resource_type!(Texture);
@@ -1562,6 +1673,7 @@ impl WebGpuError for CreateTextureError {
             Self::InvalidDimension(e) => e,
             Self::MissingFeatures(_, e) => e,
             Self::MissingDownlevelFlags(e) => e,
+            Self::Placement(e) => e,
 
             Self::InvalidUsage(_)
             | Self::InvalidDepthDimension(_, _)
@@ -1573,7 +1685,11 @@ impl WebGpuError for CreateTextureError {
             | Self::InvalidMultisampledStorageBinding
             | Self::InvalidMultisampledFormat(_)
             | Self::InvalidSampleCount(..)
-            | Self::MultisampledNotRenderAttachment => return ErrorType::Validation,
+            | Self::MultisampledNotRenderAttachment
+            | Self::InvalidTransientUsage(_)
+            | Self::InvalidSparseDimension
+            | Self::InvalidSparseFormat(_)
+            | Self::UnalignedSparseMipLevel { .. } => return ErrorType::Validation,
         };
         e.webgpu_error_type()
     }
@@ -2074,6 +2190,138 @@ impl QuerySet {
     }
 }
 
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum CreateHeapError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error("Heaps cannot be made with a size of zero")]
+    ZeroSize,
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+}
+
+impl WebGpuError for CreateHeapError {
+    fn webgpu_error_type(&self) -> ErrorType {
+        let e: &dyn WebGpuError = match self {
+            Self::Device(e) => e,
+            Self::MissingFeatures(e) => e,
+
+            Self::ZeroSize => return ErrorType::Validation,
+        };
+        e.webgpu_error_type()
+    }
+}
+
+/// Error encountered while placing a buffer or texture in a [`Heap`].
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum PlacementError {
+    #[error(transparent)]
+    InvalidResource(#[from] InvalidResourceError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error("Resources placed in a heap can't be mapped")]
+    Mappable,
+    #[error("Sparse resources can't be placed in a heap")]
+    Sparse,
+    #[error("Transient textures can't be placed in a heap")]
+    Transient,
+    #[error("Offset {offset} is not a multiple of the required alignment {alignment}")]
+    UnalignedOffset {
+        offset: wgt::BufferAddress,
+        alignment: wgt::BufferAddress,
+    },
+    #[error("Placing {size} bytes at offset {offset} overruns {heap}, which is {heap_size} bytes")]
+    OutOfBounds {
+        offset: wgt::BufferAddress,
+        size: wgt::BufferAddress,
+        heap: ResourceErrorIdent,
+        heap_size: wgt::BufferAddress,
+    },
+}
+
+impl WebGpuError for PlacementError {
+    fn webgpu_error_type(&self) -> ErrorType {
+        let e: &dyn WebGpuError = match self {
+            Self::InvalidResource(e) => e,
+            Self::MissingFeatures(e) => e,
+
+            Self::Mappable
+            | Self::Sparse
+            | Self::Transient
+            | Self::UnalignedOffset { .. }
+            | Self::OutOfBounds { .. } => return ErrorType::Validation,
+        };
+        e.webgpu_error_type()
+    }
+}
+
+pub type HeapDescriptor<'a> = wgt::HeapDescriptor<Label<'a>>;
+
+/// A block of memory that buffers and textures can be placed in.
+///
+/// Resources placed in the same heap may overlap. Of overlapping resources,
+/// only the one most recently activated by an aliasing barrier may be used.
+#[derive(Debug)]
+pub struct Heap {
+    pub(crate) raw: ManuallyDrop<Box<dyn hal::DynHeap>>,
+    pub(crate) device: Arc<Device>,
+    pub(crate) size: wgt::BufferAddress,
+    /// The `label` from the descriptor used to create the resource.
+    pub(crate) label: String,
+    /// The resources placed in this heap, and which of them may be used.
+    pub(crate) aliasing: Mutex<AliasingTracker>,
+}
+
+impl Drop for Heap {
+    fn drop(&mut self) {
+        resource_log!("Destroy raw {}", self.error_ident());
+        // SAFETY: We are in the Drop impl and we don't use self.raw anymore after this point.
+        let raw = unsafe { ManuallyDrop::take(&mut self.raw) };
+        unsafe {
+            self.device.raw().destroy_heap(raw);
+        }
+    }
+}
+
+crate::impl_resource_type!(Heap);
+crate::impl_labeled!(Heap);
+crate::impl_parent_device!(Heap);
+crate::impl_storage_item!(Heap);
+
+impl Heap {
+    pub(crate) fn raw(&self) -> &dyn hal::DynHeap {
+        self.raw.as_ref()
+    }
+}
+
+/// The part of a [`Heap`] a buffer or texture was placed in.
+#[derive(Debug)]
+pub(crate) struct Placement {
+    pub(crate) heap: Arc<Heap>,
+    pub(crate) range: Range<wgt::BufferAddress>,
+}
+
+impl Placement {
+    fn check_active(
+        &self,
+        id: PlacedResourceId,
+        pending: Option<&PendingActivations>,
+        ident: impl FnOnce() -> ResourceErrorIdent,
+    ) -> Result<(), InactiveResourceError> {
+        let active = match pending {
+            Some(pending) => pending.is_active(&self.heap, id, &self.range),
+            None => self.heap.aliasing.lock().is_active(id),
+        };
+        if active {
+            Ok(())
+        } else {
+            Err(InactiveResourceError(ident()))
+        }
+    }
+}
+
 pub type BlasDescriptor<'a> = wgt::CreateBlasDescriptor<Label<'a>>;
 pub type TlasDescriptor<'a> = wgt::CreateTlasDescriptor<Label<'a>>;
 
@@ -0,0 +1,188 @@
+//! Residency tracking for sparse buffers and textures.
+//!
+//! Sparse resources have no memory of their own when they are created. Ranges
+//! of pages (for buffers) or tiles (for textures) are made resident or
+//! non-resident with `Queue::update_sparse_residency`, and the backend binds
+//! and frees memory for them on the next submission.
+//!
+//! Memory newly bound to a resource has undefined contents, so we keep track
+//! of what is resident and zero everything that becomes resident. Every
+//! backend exposing [`wgt::Features::SPARSE_RESOURCES`] guarantees that reads
+//! from non-resident parts return zero, so the contents of a sparse resource
+//! are always well defined and the usual initialization tracking is not
+//! needed.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use bit_vec::BitVec;
+
+/// Residency of the pages (or the tiles of a subresource) of a sparse resource.
+#[derive(Debug)]
+pub(crate) struct SparseResidency {
+    resident: BitVec,
+}
+
+impl SparseResidency {
+    pub(crate) fn new(count: usize) -> Self {
+        Self {
+            resident: BitVec::from_elem(count, false),
+        }
+    }
+
+    /// Marks `range` as resident or non-resident.
+    ///
+    /// Returns the runs of entries that were made resident by this call.
+    pub(crate) fn update(&mut self, range: Range<usize>, resident: bool) -> Vec<Range<usize>> {
+        let mut newly_resident = Vec::new();
+        for index in range {
+            let was_resident = self.resident[index];
+            self.resident.set(index, resident);
+            if !resident || was_resident {
+                continue;
+            }
+            match newly_resident.last_mut() {
+                Some(Range { end, .. }) if *end == index => *end += 1,
+                _ => newly_resident.push(index..index + 1),
+            }
+        }
+        newly_resident
+    }
+}
+
+/// Residency of the tiles of a sparse texture.
+#[derive(Debug)]
+pub(crate) struct SparseTextureResidency {
+    /// Size of a tile in texels.
+    tile_size: wgt::Extent3d,
+    array_layer_count: u32,
+    /// Number of tile columns of each mip level.
+    columns: Vec<u32>,
+    /// Tiles of each subresource, row by row, indexed by
+    /// `mip_level * array_layer_count + array_layer`.
+    subresources: Vec<SparseResidency>,
+}
+
+impl SparseTextureResidency {
+    /// Creates a tracker with no resident tiles.
+    ///
+    /// `desc` must have been validated for use with
+    /// [`wgt::TextureUsages::SPARSE`]; in particular the format must have a
+    /// [sparse tile extent](wgt::TextureFormat::sparse_tile_extent).
+    pub(crate) fn new<L, V>(desc: &wgt::TextureDescriptor<L, V>) -> Self {
+        let tile_size = desc.format.sparse_tile_extent().unwrap();
+        let array_layer_count = desc.array_layer_count();
+        let mut columns = Vec::with_capacity(desc.mip_level_count as usize);
+        let mut subresources =
+            Vec::with_capacity((desc.mip_level_count * array_layer_count) as usize);
+        for mip_level in 0..desc.mip_level_count {
+            let size = desc.mip_level_size(mip_level).unwrap();
+            let mip_columns = size.width.div_ceil(tile_size.width);
+            let mip_rows = size.height.div_ceil(tile_size.height);
+            columns.push(mip_columns);
+            for _ in 0..array_layer_count {
+                subresources.push(SparseResidency::new((mip_columns * mip_rows) as usize));
+            }
+        }
+        Self {
+            tile_size,
+            array_layer_count,
+            columns,
+            subresources,
+        }
+    }
+
+    /// Marks the tiles of the given region as resident or non-resident.
+    ///
+    /// The region must be aligned to the tile size and lie within the
+    /// subresource. Returns the origin, in texels, of every tile that was made
+    /// resident by this call.
+    pub(crate) fn update(
+        &mut self,
+        mip_level: u32,
+        array_layer: u32,
+        origin: wgt::Origin3d,
+        size: wgt::Extent3d,
+        resident: bool,
+    ) -> Vec<wgt::Origin3d> {
+        let columns = self.columns[mip_level as usize];
+        let subresource =
+            &mut self.subresources[(mip_level * self.array_layer_count + array_layer) as usize];
+
+        let first_column = origin.x / self.tile_size.width;
+        let column_count = size.width / self.tile_size.width;
+        let first_row = origin.y / self.tile_size.height;
+        let row_count = size.height / self.tile_size.height;
+
+        let mut newly_resident = Vec::new();
+        for row in first_row..first_row + row_count {
+            let start = (row * columns + first_column) as usize;
+            for run in subresource.update(start..start + column_count as usize, resident) {
+                newly_resident.extend(run.map(|index| wgt::Origin3d {
+                    x: (index as u32 % columns) * self.tile_size.width,
+                    y: row * self.tile_size.height,
+                    z: 0,
+                }));
+            }
+        }
+        newly_resident
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[expect(clippy::single_range_in_vec_init)]
+    fn newly_resident_runs() {
+        let mut residency = SparseResidency::new(8);
+        assert_eq!(residency.update(2..4, true), [2..4]);
+        assert_eq!(residency.update(0..8, true), [0..2, 4..8]);
+        assert_eq!(residency.update(3..6, false), []);
+        assert_eq!(residency.update(0..8, true), [3..6]);
+    }
+
+    #[test]
+    fn newly_resident_tiles() {
+        let desc = wgt::TextureDescriptor {
+            label: (),
+            size: wgt::Extent3d {
+                width: 512,
+                height: 256,
+                depth_or_array_layers: 2,
+            },
+            mip_level_count: 2,
+            sample_count: 1,
+            dimension: wgt::TextureDimension::D2,
+            format: wgt::TextureFormat::Rgba8Unorm,
+            usage: wgt::TextureUsages::SPARSE,
+            view_formats: (),
+        };
+        let mut residency = SparseTextureResidency::new(&desc);
+        let size = |width, height| wgt::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let origin = |x, y| wgt::Origin3d { x, y, z: 0 };
+
+        assert_eq!(
+            residency.update(0, 1, origin(128, 0), size(128, 256), true),
+            [origin(128, 0), origin(128, 128)]
+        );
+        assert_eq!(
+            residency.update(0, 1, origin(0, 128), size(512, 128), true),
+            [origin(0, 128), origin(256, 128), origin(384, 128)]
+        );
+        // Other subresources are tracked separately.
+        assert_eq!(
+            residency.update(1, 1, origin(128, 0), size(128, 128), true),
+            [origin(128, 0)]
+        );
+        assert_eq!(
+            residency.update(0, 1, origin(0, 0), size(512, 256), false),
+            []
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! Tracking of which resources placed in a [`Heap`] may be used.
+//!
+//! Buffers and textures placed in a heap may overlap each other. Of a set of
+//! overlapping resources, only the one that was last *activated* by an
+//! aliasing barrier holds meaningful contents, so it is the only one that may
+//! be used. Activating a resource deactivates every resource it overlaps.
+//!
+//! Aliasing barriers are recorded by command encoders, but they take effect on
+//! the queue timeline, at the start of the command buffer they were recorded
+//! in. When a command buffer is submitted, its barriers are added to the
+//! [`PendingActivations`] of the submission, then every placed resource the
+//! command buffer uses must be active. The activations are only applied to the
+//! [`AliasingTracker`] of each heap once the submission succeeds.
+
+use alloc::{string::String, sync::Arc, vec::Vec};
+use core::ops::Range;
+
+use thiserror::Error;
+use wgt::error::{ErrorType, WebGpuError};
+
+use crate::resource::{Heap, ResourceErrorIdent};
+
+use super::TrackerIndex;
+
+/// Identifies a resource placed in a heap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PlacedResourceId {
+    Buffer(TrackerIndex),
+    Texture(TrackerIndex),
+}
+
+#[derive(Debug)]
+struct PlacedResource {
+    id: PlacedResourceId,
+    range: Range<wgt::BufferAddress>,
+    label: String,
+    active: bool,
+}
+
+/// The resources placed in a heap, and which of them are active.
+#[derive(Debug, Default)]
+pub(crate) struct AliasingTracker {
+    resources: Vec<PlacedResource>,
+}
+
+impl AliasingTracker {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an inactive resource occupying `range` of the heap.
+    pub(crate) fn insert(
+        &mut self,
+        id: PlacedResourceId,
+        range: Range<wgt::BufferAddress>,
+        label: String,
+    ) {
+        self.resources.push(PlacedResource {
+            id,
+            range,
+            label,
+            active: false,
+        });
+    }
+
+    pub(crate) fn remove(&mut self, id: PlacedResourceId) {
+        self.resources.retain(|resource| resource.id != id);
+    }
+
+    /// Makes `id` active, deactivating every resource it overlaps.
+    pub(crate) fn activate(&mut self, id: PlacedResourceId) {
+        let Some(range) = self
+            .resources
+            .iter()
+            .find(|resource| resource.id == id)
+            .map(|resource| resource.range.clone())
+        else {
+            return;
+        };
+        for resource in self.resources.iter_mut() {
+            if resource.id == id {
+                resource.active = true;
+            } else if resource.range.start < range.end && range.start < resource.range.end {
+                resource.active = false;
+            }
+        }
+    }
+
+    pub(crate) fn is_active(&self, id: PlacedResourceId) -> bool {
+        self.resources
+            .iter()
+            .any(|resource| resource.id == id && resource.active)
+    }
+
+    /// Describes the heap as a single block of `heap_size` bytes, with an
+    /// allocation for every placed resource.
+    ///
+    /// Overlapping parts of placed resources only count once towards
+    /// [`wgt::AllocatorReport::total_allocated_bytes`].
+    pub(crate) fn generate_report(&self, heap_size: wgt::BufferAddress) -> wgt::AllocatorReport {
+        let mut allocations = self
+            .resources
+            .iter()
+            .map(|resource| wgt::AllocationReport {
+                name: resource.label.clone(),
+                offset: resource.range.start,
+                size: resource.range.end - resource.range.start,
+            })
+            .collect::<Vec<_>>();
+        allocations.sort_by_key(|allocation| allocation.offset);
+
+        let mut total_allocated_bytes = 0;
+        let mut covered_end = 0;
+        for allocation in allocations.iter() {
+            let end = allocation.offset + allocation.size;
+            total_allocated_bytes += end.saturating_sub(allocation.offset.max(covered_end));
+            covered_end = covered_end.max(end);
+        }
+
+        wgt::AllocatorReport {
+            blocks: alloc::vec![wgt::MemoryBlockReport {
+                size: heap_size,
+                allocations: 0..allocations.len(),
+            }],
+            allocations,
+            total_allocated_bytes,
+            total_reserved_bytes: heap_size,
+        }
+    }
+}
+
+/// The resources activated by the aliasing barriers of a submission, in order.
+#[derive(Debug, Default)]
+pub(crate) struct PendingActivations {
+    activations: Vec<(Arc<Heap>, PlacedResourceId, Range<wgt::BufferAddress>)>,
+}
+
+impl PendingActivations {
+    /// Adds an activation of `id`, occupying `range` of `heap`.
+    pub(crate) fn push(
+        &mut self,
+        heap: &Arc<Heap>,
+        id: PlacedResourceId,
+        range: Range<wgt::BufferAddress>,
+    ) {
+        self.activations.push((heap.clone(), id, range));
+    }
+
+    /// Returns whether `id` would be active once the pending activations are
+    /// applied.
+    pub(crate) fn is_active(
+        &self,
+        heap: &Arc<Heap>,
+        id: PlacedResourceId,
+        range: &Range<wgt::BufferAddress>,
+    ) -> bool {
+        let last = self
+            .activations
+            .iter()
+            .rev()
+            .find(|(other_heap, other, other_range)| {
+                Arc::ptr_eq(other_heap, heap)
+                    && (*other == id
+                        || (other_range.start < range.end && range.start < other_range.end))
+            });
+        match last {
+            Some(&(_, other, _)) => other == id,
+            None => heap.aliasing.lock().is_active(id),
+        }
+    }
+
+    /// Applies the activations to the heaps.
+    pub(crate) fn commit(self) {
+        for (heap, id, _) in self.activations {
+            heap.aliasing.lock().activate(id);
+        }
+    }
+}
+
+/// Error raised when a placed resource is used without being active.
+#[derive(Clone, Debug, Error)]
+#[error("{0} overlaps a resource that was activated after it, and must be activated again with an aliasing barrier before it can be used")]
+pub struct InactiveResourceError(pub ResourceErrorIdent);
+
+impl WebGpuError for InactiveResourceError {
+    fn webgpu_error_type(&self) -> ErrorType {
+        ErrorType::Validation
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn buffer(index: u32) -> PlacedResourceId {
+        PlacedResourceId::Buffer(TrackerIndex(index))
+    }
+
+    #[test]
+    fn activation() {
+        let mut tracker = AliasingTracker::new();
+        tracker.insert(buffer(0), 0..256, String::from("a"));
+        tracker.insert(buffer(1), 128..512, String::from("b"));
+        tracker.insert(buffer(2), 256..512, String::from("c"));
+        assert!(!tracker.is_active(buffer(0)));
+
+        tracker.activate(buffer(0));
+        tracker.activate(buffer(2));
+        assert!(tracker.is_active(buffer(0)));
+        assert!(tracker.is_active(buffer(2)));
+
+        tracker.activate(buffer(1));
+        assert!(!tracker.is_active(buffer(0)));
+        assert!(tracker.is_active(buffer(1)));
+        assert!(!tracker.is_active(buffer(2)));
+
+        tracker.remove(buffer(1));
+        assert!(!tracker.is_active(buffer(1)));
+        // Textures have tracker indices of their own.
+        assert!(!tracker.is_active(PlacedResourceId::Texture(TrackerIndex(1))));
+    }
+
+    #[test]
+    fn report() {
+        let mut tracker = AliasingTracker::new();
+        tracker.insert(buffer(0), 256..512, String::from("a"));
+        tracker.insert(buffer(1), 0..384, String::from("b"));
+        tracker.insert(buffer(2), 768..1024, String::from("c"));
+
+        let report = tracker.generate_report(2048);
+        let offsets = report
+            .allocations
+            .iter()
+            .map(|allocation| (allocation.name.as_str(), allocation.offset))
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, [("b", 0), ("a", 256), ("c", 768)]);
+        assert_eq!(report.blocks.len(), 1);
+        assert_eq!(report.blocks[0].allocations, 0..3);
+        assert_eq!(report.total_allocated_bytes, 768);
+        assert_eq!(report.total_reserved_bytes, 2048);
+    }
+}
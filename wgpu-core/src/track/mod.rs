@@ -95,9 +95,11 @@ Device <- CommandBuffer = insert(device.start, device.end, buffer.start, buffer.
 [`UsageScope`]: https://gpuweb.github.io/gpuweb/#programming-model-synchronization
 */
 
+mod aliasing;
 mod blas;
 mod buffer;
 mod metadata;
+mod queue;
 mod range;
 mod stateless;
 mod texture;
@@ -116,10 +118,14 @@ use core::{fmt, mem, ops};
 
 use thiserror::Error;
 
+pub use aliasing::InactiveResourceError;
+pub(crate) use aliasing::{AliasingTracker, PendingActivations, PlacedResourceId};
 pub(crate) use buffer::{
     BufferBindGroupState, BufferTracker, BufferUsageScope, DeviceBufferTracker,
 };
 use metadata::{ResourceMetadata, ResourceMetadataProvider};
+pub(crate) use queue::QueueOwner;
+pub use queue::QueueOwnershipError;
 pub(crate) use stateless::StatelessTracker;
 pub(crate) use texture::{
     DeviceTextureTracker, TextureTracker, TextureTrackerSetSingle, TextureUsageScope,
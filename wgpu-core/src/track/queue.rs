@@ -0,0 +1,128 @@
+//! Tracking of which queue last used a buffer or texture.
+//!
+//! With [`wgt::Features::MULTIPLE_QUEUES`], a device can have several
+//! [`Queue`]s that execute independently of each other. The state trackers
+//! only know the state a resource was left in, not which queue left it there,
+//! so before a resource moves from one queue to another we have to check that
+//! the new queue is ordered after the last use on the old one.
+//!
+//! A queue is ordered after a submission on another queue if it called
+//! [`Queue::wait_for_submission`] with that submission (or a later one), or if
+//! the submission has already completed on the GPU.
+//!
+//! [`Queue::wait_for_submission`]: crate::device::queue::Queue::wait_for_submission
+
+use thiserror::Error;
+use wgt::error::{ErrorType, WebGpuError};
+
+use crate::{
+    device::queue::Queue,
+    lock::{rank, Mutex},
+    resource::{Labeled, ResourceErrorIdent},
+    SubmissionIndex,
+};
+
+/// The last queue operation that used a resource.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct QueueUse {
+    /// The type of the queue that used the resource.
+    pub queue: wgt::QueueType,
+    /// The submission that used the resource, or `None` if it is still
+    /// waiting in the queue's pending writes.
+    pub submission: Option<SubmissionIndex>,
+}
+
+/// The queue that currently owns a buffer or texture.
+#[derive(Debug)]
+pub(crate) struct QueueOwner {
+    last_use: Mutex<Option<QueueUse>>,
+}
+
+impl QueueOwner {
+    pub(crate) fn new() -> Self {
+        Self {
+            last_use: Mutex::new(rank::RESOURCE_QUEUE_OWNER, None),
+        }
+    }
+
+    /// The last use of the resource, if it has been used by any queue.
+    pub(crate) fn last_use(&self) -> Option<QueueUse> {
+        *self.last_use.lock()
+    }
+
+    /// Check that `queue` may use `resource`, whose ownership is tracked by
+    /// `self`.
+    pub(crate) fn check(
+        &self,
+        queue: &Queue,
+        resource: &impl Labeled,
+    ) -> Result<(), QueueOwnershipError> {
+        let Some(last_use) = self.last_use() else {
+            return Ok(());
+        };
+        if last_use.queue == queue.ty() {
+            return Ok(());
+        }
+
+        let Some(submission) = last_use.submission else {
+            return Err(QueueOwnershipError::PendingWrites {
+                resource: resource.error_ident(),
+                owner: last_use.queue,
+                queue: queue.ty(),
+            });
+        };
+
+        if queue.waited_for(last_use.queue) >= submission {
+            return Ok(());
+        }
+        // If the owning queue is gone, it waited for all of its work when it
+        // was dropped.
+        let completed = queue
+            .device
+            .get_queue_of_type(last_use.queue)
+            .is_none_or(|owner| owner.completed_submission_index() >= submission);
+        if completed {
+            return Ok(());
+        }
+
+        Err(QueueOwnershipError::NotWaited {
+            resource: resource.error_ident(),
+            owner: last_use.queue,
+            queue: queue.ty(),
+            submission,
+        })
+    }
+
+    /// Record that `queue` used the resource in `submission`.
+    pub(crate) fn set(&self, queue: wgt::QueueType, submission: Option<SubmissionIndex>) {
+        *self.last_use.lock() = Some(QueueUse { queue, submission });
+    }
+}
+
+/// A resource was used on a queue that is not ordered after its last use on
+/// another queue.
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum QueueOwnershipError {
+    #[error(
+        "{resource} was last used by submission {submission} on the {owner:?} queue, which the {queue:?} queue has not waited for"
+    )]
+    NotWaited {
+        resource: ResourceErrorIdent,
+        owner: wgt::QueueType,
+        queue: wgt::QueueType,
+        submission: SubmissionIndex,
+    },
+    #[error("{resource} has writes pending on the {owner:?} queue that need to be submitted before it can be used on the {queue:?} queue")]
+    PendingWrites {
+        resource: ResourceErrorIdent,
+        owner: wgt::QueueType,
+        queue: wgt::QueueType,
+    },
+}
+
+impl WebGpuError for QueueOwnershipError {
+    fn webgpu_error_type(&self) -> ErrorType {
+        ErrorType::Validation
+    }
+}
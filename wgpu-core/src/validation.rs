@@ -1369,6 +1369,132 @@ impl Interface {
             .ok_or(StageError::MissingEntryPoint(pair.1))
             .map(|ep| ep.dual_source_blending)
     }
+
+    /// Add the resources used by the entry points named `entry_point_name` to
+    /// the bind group layouts in `layouts`, indexed by group.
+    ///
+    /// This derives layouts the way [`Self::check_stage`] does for a pipeline
+    /// without a layout, except that a binding whose uses differ only in their
+    /// minimum buffer size or in whether a texture is filtered gets an entry
+    /// that suits all of them. This lets the same layouts be shared by
+    /// several pipelines.
+    pub fn derive_bind_group_layouts(
+        &self,
+        layouts: &mut [bgl::EntryMap],
+        entry_point_name: &str,
+    ) -> Result<(), StageError> {
+        let mut found = false;
+        for ((stage, name), entry_point) in self.entry_points.iter() {
+            if name != entry_point_name {
+                continue;
+            }
+            found = true;
+            let stage_bit = match *stage {
+                naga::ShaderStage::Vertex => wgt::ShaderStages::VERTEX,
+                naga::ShaderStage::Fragment => wgt::ShaderStages::FRAGMENT,
+                naga::ShaderStage::Compute => wgt::ShaderStages::COMPUTE,
+                naga::ShaderStage::Task => wgt::ShaderStages::TASK,
+                naga::ShaderStage::Mesh => wgt::ShaderStages::MESH,
+            };
+
+            for &handle in entry_point.resources.iter() {
+                let res = &self.resources[handle];
+                let result = 'err: {
+                    let Some(map) = layouts.get_mut(res.bind.group as usize) else {
+                        break 'err Err(BindingError::Missing);
+                    };
+
+                    let ty = match res.derive_binding_type(
+                        entry_point
+                            .sampling_pairs
+                            .iter()
+                            .any(|&(im, _samp)| im == handle),
+                    ) {
+                        Ok(ty) => ty,
+                        Err(error) => break 'err Err(error),
+                    };
+
+                    match map.entry(res.bind.binding) {
+                        indexmap::map::Entry::Occupied(e) => {
+                            let entry = e.into_mut();
+                            let Some(merged) = merge_derived_binding_types(entry.ty, ty) else {
+                                break 'err Err(BindingError::InconsistentlyDerivedType);
+                            };
+                            entry.ty = merged;
+                            entry.visibility |= stage_bit;
+                        }
+                        indexmap::map::Entry::Vacant(e) => {
+                            e.insert(BindGroupLayoutEntry {
+                                binding: res.bind.binding,
+                                ty,
+                                visibility: stage_bit,
+                                count: None,
+                            });
+                        }
+                    }
+                    Ok(())
+                };
+                if let Err(error) = result {
+                    return Err(StageError::Binding(res.bind, error));
+                }
+            }
+        }
+
+        if !found {
+            return Err(StageError::MissingEntryPoint(entry_point_name.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Combine the binding types derived from two uses of the same binding into
+/// one that is compatible with both, if there is one.
+fn merge_derived_binding_types(a: BindingType, b: BindingType) -> Option<BindingType> {
+    match (a, b) {
+        _ if a == b => Some(a),
+        (
+            BindingType::Buffer {
+                ty,
+                has_dynamic_offset,
+                min_binding_size,
+            },
+            BindingType::Buffer {
+                ty: other_ty,
+                has_dynamic_offset: other_has_dynamic_offset,
+                min_binding_size: other_min_binding_size,
+            },
+        ) if ty == other_ty && has_dynamic_offset == other_has_dynamic_offset => {
+            Some(BindingType::Buffer {
+                ty,
+                has_dynamic_offset,
+                min_binding_size: min_binding_size.max(other_min_binding_size),
+            })
+        }
+        (
+            BindingType::Texture {
+                sample_type: wgt::TextureSampleType::Float { filterable },
+                view_dimension,
+                multisampled,
+            },
+            BindingType::Texture {
+                sample_type:
+                    wgt::TextureSampleType::Float {
+                        filterable: other_filterable,
+                    },
+                view_dimension: other_view_dimension,
+                multisampled: other_multisampled,
+            },
+        ) if view_dimension == other_view_dimension && multisampled == other_multisampled => {
+            Some(BindingType::Texture {
+                sample_type: wgt::TextureSampleType::Float {
+                    filterable: filterable || other_filterable,
+                },
+                view_dimension,
+                multisampled,
+            })
+        }
+        _ => None,
+    }
 }
 
 // https://gpuweb.github.io/gpuweb/#abstract-opdef-calculating-color-attachment-bytes-per-sample
@@ -23,6 +23,8 @@ fn main() {
         ) },
         metal: { all(target_vendor = "apple", feature = "metal") },
         vulkan: { all(not(target_arch = "wasm32"), feature = "vulkan") },
+        // `std::time::Instant` panics on wasm32-unknown-unknown.
+        std_clock: { all(feature = "std", not(all(target_arch = "wasm32", target_os = "unknown"))) },
         // ⚠️ Keep in sync with target.cfg() definition in Cargo.toml and cfg_alias in `wgpu` crate ⚠️
         static_dxc: { all(target_os = "windows", feature = "static-dxc", not(target_arch = "aarch64")) },
         supports_64bit_atomics: { target_has_atomic = "64" },
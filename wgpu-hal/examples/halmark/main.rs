@@ -125,7 +125,7 @@ impl<A: hal::Api> Example<A> {
             .ok_or("failed to get surface capabilities")?;
         log::info!("Surface caps: {surface_caps:#?}");
 
-        let hal::OpenDevice { device, queue } = unsafe {
+        let hal::OpenDevice { device, queue, .. } = unsafe {
             adapter
                 .open(
                     wgpu_types::Features::empty(),
@@ -375,6 +375,19 @@ impl super::Adapter {
             features |= wgt::Features::VERTEX_WRITABLE_STORAGE;
         }
 
+        // From tier 2, reads from unmapped tiles return zero and writes to them
+        // are discarded.
+        features.set(
+            wgt::Features::SPARSE_RESOURCES,
+            options.TiledResourcesTier.0 >= Direct3D12::D3D12_TILED_RESOURCES_TIER_2.0,
+        );
+
+        // Heaps must be able to hold buffers and all kinds of textures at once.
+        features.set(
+            wgt::Features::RESOURCE_HEAPS,
+            options.ResourceHeapTier.0 >= Direct3D12::D3D12_RESOURCE_HEAP_TIER_2.0,
+        );
+
         features.set(
             wgt::Features::CONSERVATIVE_RASTERIZATION,
             options.ConservativeRasterizationTier
@@ -706,7 +719,10 @@ impl crate::Adapter for super::Adapter {
             queue: super::Queue {
                 raw: queue,
                 temp_lists: Mutex::new(Vec::new()),
+                device: self.device.clone(),
+                retired_sparse_heaps: Mutex::new(Default::default()),
             },
+            additional_queues: Vec::new(),
         })
     }
 
@@ -528,6 +528,21 @@ impl crate::CommandEncoder for super::CommandEncoder {
         }
     }
 
+    unsafe fn aliasing_barrier(&mut self) {
+        // Null resources make the barrier apply to every placed resource.
+        let raw = Direct3D12::D3D12_RESOURCE_BARRIER {
+            Type: Direct3D12::D3D12_RESOURCE_BARRIER_TYPE_ALIASING,
+            Flags: Direct3D12::D3D12_RESOURCE_BARRIER_FLAG_NONE,
+            Anonymous: Direct3D12::D3D12_RESOURCE_BARRIER_0 {
+                Aliasing: mem::ManuallyDrop::new(Direct3D12::D3D12_RESOURCE_ALIASING_BARRIER {
+                    pResourceBefore: mem::ManuallyDrop::new(None),
+                    pResourceAfter: mem::ManuallyDrop::new(None),
+                }),
+            },
+        };
+        unsafe { self.list.as_ref().unwrap().ResourceBarrier(&[raw]) };
+    }
+
     unsafe fn clear_buffer(&mut self, buffer: &super::Buffer, range: crate::MemoryRange) {
         let list = self.list.as_ref().unwrap();
         let mut offset = range.start;
@@ -1,6 +1,7 @@
 use alloc::borrow::ToOwned;
 use alloc::{
     borrow::Cow,
+    boxed::Box,
     string::{String, ToString as _},
     sync::Arc,
     vec::Vec,
@@ -438,6 +439,7 @@ impl super::Device {
                 suballocation::AllocationType::Texture,
                 format.theoretical_memory_footprint(size),
             ),
+            sparse: None,
         }
     }
 
@@ -452,8 +454,77 @@ impl super::Device {
                 suballocation::AllocationType::Buffer,
                 size,
             ),
+            sparse: None,
         }
     }
+
+    fn texture_resource_desc(
+        &self,
+        desc: &crate::TextureDescriptor,
+    ) -> Direct3D12::D3D12_RESOURCE_DESC {
+        let sparse = desc.memory_flags.contains(crate::MemoryFlags::SPARSE);
+        Direct3D12::D3D12_RESOURCE_DESC {
+            Dimension: conv::map_texture_dimension(desc.dimension),
+            Alignment: 0,
+            Width: desc.size.width as u64,
+            Height: desc.size.height,
+            DepthOrArraySize: desc.size.depth_or_array_layers as u16,
+            MipLevels: desc.mip_level_count as u16,
+            Format: auxil::dxgi::conv::map_texture_format_for_resource(
+                desc.format,
+                desc.usage,
+                !desc.view_formats.is_empty(),
+                self.shared
+                    .private_caps
+                    .casting_fully_typed_format_supported,
+            ),
+            SampleDesc: Dxgi::Common::DXGI_SAMPLE_DESC {
+                Count: desc.sample_count,
+                Quality: 0,
+            },
+            // Reserved resources use the standard tile shapes, which every mip
+            // level of a sparse texture is made of.
+            Layout: if sparse {
+                Direct3D12::D3D12_TEXTURE_LAYOUT_64KB_UNDEFINED_SWIZZLE
+            } else {
+                Direct3D12::D3D12_TEXTURE_LAYOUT_UNKNOWN
+            },
+            Flags: conv::map_texture_usage_to_resource_flags(desc.usage),
+        }
+    }
+
+    /// Returns `desc` with the size of uniform buffers rounded up to the
+    /// alignment of constant buffer views.
+    fn padded_buffer_descriptor<'a>(
+        &self,
+        desc: &crate::BufferDescriptor<'a>,
+    ) -> crate::BufferDescriptor<'a> {
+        let mut desc = desc.clone();
+        if desc.usage.contains(wgt::BufferUses::UNIFORM) {
+            desc.size = desc
+                .size
+                .next_multiple_of(Direct3D12::D3D12_CONSTANT_BUFFER_DATA_PLACEMENT_ALIGNMENT.into())
+        }
+        desc
+    }
+
+    fn resource_memory_requirements(
+        &self,
+        raw_desc: &Direct3D12::D3D12_RESOURCE_DESC,
+    ) -> Result<wgt::MemoryRequirements, crate::DeviceError> {
+        let info = unsafe {
+            self.raw
+                .GetResourceAllocationInfo(0, core::slice::from_ref(raw_desc))
+        };
+        // `UINT64_MAX` signals an invalid descriptor.
+        if info.SizeInBytes == u64::MAX {
+            return Err(crate::DeviceError::Unexpected);
+        }
+        Ok(wgt::MemoryRequirements {
+            size: info.SizeInBytes,
+            alignment: info.Alignment,
+        })
+    }
 }
 
 impl crate::Device for super::Device {
@@ -463,12 +534,30 @@ impl crate::Device for super::Device {
         &self,
         desc: &crate::BufferDescriptor,
     ) -> Result<super::Buffer, crate::DeviceError> {
-        let mut desc = desc.clone();
+        let desc = self.padded_buffer_descriptor(desc);
+
+        if desc.memory_flags.contains(crate::MemoryFlags::SPARSE) {
+            let raw_desc = conv::map_buffer_descriptor(&desc);
+            let (resource, allocation) = suballocation::DeviceAllocationContext::from(self)
+                .create_reserved_resource(
+                    desc.label,
+                    raw_desc,
+                    suballocation::AllocationType::Buffer,
+                )?;
+            let heap_flags = if self.shared.private_caps.heterogeneous_resource_heaps {
+                Direct3D12::D3D12_HEAP_FLAG_ALLOW_ALL_BUFFERS_AND_TEXTURES
+            } else {
+                Direct3D12::D3D12_HEAP_FLAG_ALLOW_ONLY_BUFFERS
+            };
 
-        if desc.usage.contains(wgt::BufferUses::UNIFORM) {
-            desc.size = desc
-                .size
-                .next_multiple_of(Direct3D12::D3D12_CONSTANT_BUFFER_DATA_PLACEMENT_ALIGNMENT.into())
+            self.counters.buffers.add(1);
+
+            return Ok(super::Buffer {
+                resource,
+                size: desc.size,
+                allocation,
+                sparse: Some(Box::new(super::SparseMemory::new(heap_flags))),
+            });
         }
 
         let (resource, allocation) =
@@ -480,6 +569,7 @@ impl crate::Device for super::Device {
             resource,
             size: desc.size,
             allocation,
+            sparse: None,
         })
     }
 
@@ -523,32 +613,34 @@ impl crate::Device for super::Device {
         &self,
         desc: &crate::TextureDescriptor,
     ) -> Result<super::Texture, crate::DeviceError> {
-        let raw_desc = Direct3D12::D3D12_RESOURCE_DESC {
-            Dimension: conv::map_texture_dimension(desc.dimension),
-            Alignment: 0,
-            Width: desc.size.width as u64,
-            Height: desc.size.height,
-            DepthOrArraySize: desc.size.depth_or_array_layers as u16,
-            MipLevels: desc.mip_level_count as u16,
-            Format: auxil::dxgi::conv::map_texture_format_for_resource(
-                desc.format,
-                desc.usage,
-                !desc.view_formats.is_empty(),
-                self.shared
-                    .private_caps
-                    .casting_fully_typed_format_supported,
-            ),
-            SampleDesc: Dxgi::Common::DXGI_SAMPLE_DESC {
-                Count: desc.sample_count,
-                Quality: 0,
-            },
-            Layout: Direct3D12::D3D12_TEXTURE_LAYOUT_UNKNOWN,
-            Flags: conv::map_texture_usage_to_resource_flags(desc.usage),
+        let sparse = desc.memory_flags.contains(crate::MemoryFlags::SPARSE);
+        let raw_desc = self.texture_resource_desc(desc);
+
+        let (resource, allocation, sparse) = if sparse {
+            let (resource, allocation) = suballocation::DeviceAllocationContext::from(self)
+                .create_reserved_resource(
+                    desc.label,
+                    raw_desc,
+                    suballocation::AllocationType::Texture,
+                )?;
+            let heap_flags = if self.shared.private_caps.heterogeneous_resource_heaps {
+                Direct3D12::D3D12_HEAP_FLAG_ALLOW_ALL_BUFFERS_AND_TEXTURES
+            } else if desc
+                .usage
+                .intersects(wgt::TextureUses::COLOR_TARGET | wgt::TextureUses::DEPTH_STENCIL_WRITE)
+            {
+                Direct3D12::D3D12_HEAP_FLAG_ALLOW_ONLY_RT_DS_TEXTURES
+            } else {
+                Direct3D12::D3D12_HEAP_FLAG_ALLOW_ONLY_NON_RT_DS_TEXTURES
+            };
+            let sparse = super::SparseMemory::new(heap_flags);
+            (resource, allocation, Some(Box::new(sparse)))
+        } else {
+            let (resource, allocation) = suballocation::DeviceAllocationContext::from(self)
+                .create_texture(desc, raw_desc)?;
+            (resource, allocation, None)
         };
 
-        let (resource, allocation) =
-            suballocation::DeviceAllocationContext::from(self).create_texture(desc, raw_desc)?;
-
         self.counters.textures.add(1);
 
         Ok(super::Texture {
@@ -559,6 +651,7 @@ impl crate::Device for super::Device {
             mip_level_count: desc.mip_level_count,
             sample_count: desc.sample_count,
             allocation,
+            sparse,
         })
     }
 
@@ -573,6 +666,100 @@ impl crate::Device for super::Device {
         self.counters.textures.add(1);
     }
 
+    unsafe fn create_heap(
+        &self,
+        desc: &wgt::HeapDescriptor<crate::Label>,
+    ) -> Result<super::Heap, crate::DeviceError> {
+        let raw_desc = Direct3D12::D3D12_HEAP_DESC {
+            SizeInBytes: desc.size,
+            Properties: Direct3D12::D3D12_HEAP_PROPERTIES {
+                Type: Direct3D12::D3D12_HEAP_TYPE_DEFAULT,
+                ..Default::default()
+            },
+            // Large enough for multisampled textures.
+            Alignment: Direct3D12::D3D12_DEFAULT_MSAA_RESOURCE_PLACEMENT_ALIGNMENT.into(),
+            Flags: Direct3D12::D3D12_HEAP_FLAG_ALLOW_ALL_BUFFERS_AND_TEXTURES,
+        };
+        let mut raw = None;
+        unsafe { self.raw.CreateHeap(&raw_desc, &mut raw) }.into_device_result("Heap creation")?;
+        let raw: Direct3D12::ID3D12Heap = raw.ok_or(crate::DeviceError::Unexpected)?;
+
+        if let Some(label) = desc.label {
+            raw.set_name(label)?;
+        }
+
+        Ok(super::Heap { raw })
+    }
+    unsafe fn destroy_heap(&self, _heap: super::Heap) {}
+
+    unsafe fn get_buffer_memory_requirements(
+        &self,
+        desc: &crate::BufferDescriptor,
+    ) -> Result<wgt::MemoryRequirements, crate::DeviceError> {
+        let raw_desc = conv::map_buffer_descriptor(&self.padded_buffer_descriptor(desc));
+        self.resource_memory_requirements(&raw_desc)
+    }
+    unsafe fn get_texture_memory_requirements(
+        &self,
+        desc: &crate::TextureDescriptor,
+    ) -> Result<wgt::MemoryRequirements, crate::DeviceError> {
+        self.resource_memory_requirements(&self.texture_resource_desc(desc))
+    }
+
+    unsafe fn create_placed_buffer(
+        &self,
+        desc: &crate::BufferDescriptor,
+        heap: &super::Heap,
+        offset: wgt::BufferAddress,
+    ) -> Result<super::Buffer, crate::DeviceError> {
+        let desc = self.padded_buffer_descriptor(desc);
+        let (resource, allocation) = suballocation::DeviceAllocationContext::from(self)
+            .create_resource_in_heap(
+                desc.label,
+                conv::map_buffer_descriptor(&desc),
+                &heap.raw,
+                offset,
+                suballocation::AllocationType::Buffer,
+            )?;
+
+        self.counters.buffers.add(1);
+
+        Ok(super::Buffer {
+            resource,
+            size: desc.size,
+            allocation,
+            sparse: None,
+        })
+    }
+    unsafe fn create_placed_texture(
+        &self,
+        desc: &crate::TextureDescriptor,
+        heap: &super::Heap,
+        offset: wgt::BufferAddress,
+    ) -> Result<super::Texture, crate::DeviceError> {
+        let (resource, allocation) = suballocation::DeviceAllocationContext::from(self)
+            .create_resource_in_heap(
+                desc.label,
+                self.texture_resource_desc(desc),
+                &heap.raw,
+                offset,
+                suballocation::AllocationType::Texture,
+            )?;
+
+        self.counters.textures.add(1);
+
+        Ok(super::Texture {
+            resource,
+            format: desc.format,
+            dimension: desc.dimension,
+            size: desc.size,
+            mip_level_count: desc.mip_level_count,
+            sample_count: desc.sample_count,
+            allocation,
+            sparse: None,
+        })
+    }
+
     unsafe fn create_texture_view(
         &self,
         texture: &super::Texture,
@@ -1444,7 +1631,10 @@ impl crate::Device for super::Device {
                 sampler_heap_target,
                 sampler_buffer_binding_map,
                 external_texture_binding_map,
+                dynamic_resources: None,
                 force_loop_bounding: true,
+                source_map: false,
+                line_directives: None,
             },
         })
     }
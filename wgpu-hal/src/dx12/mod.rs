@@ -84,7 +84,7 @@ mod suballocation;
 mod types;
 mod view;
 
-use alloc::{borrow::ToOwned as _, string::String, sync::Arc, vec::Vec};
+use alloc::{borrow::ToOwned as _, boxed::Box, string::String, sync::Arc, vec::Vec};
 use core::{ffi, fmt, mem, num::NonZeroU32, ops::Deref};
 
 use arrayvec::ArrayVec;
@@ -414,6 +414,7 @@ impl crate::Api for Api {
     type TextureView = TextureView;
     type Sampler = Sampler;
     type QuerySet = QuerySet;
+    type Heap = Heap;
     type Fence = Fence;
 
     type BindGroupLayout = BindGroupLayout;
@@ -442,6 +443,7 @@ crate::impl_dyn_resource!(
     PipelineCache,
     PipelineLayout,
     QuerySet,
+    Heap,
     Queue,
     RenderPipeline,
     Sampler,
@@ -707,12 +709,114 @@ unsafe impl Sync for Device {}
 pub struct Queue {
     raw: Direct3D12::ID3D12CommandQueue,
     temp_lists: Mutex<Vec<Option<Direct3D12::ID3D12CommandList>>>,
+    /// Creates the heaps mapped to tiles of sparse resources.
+    device: Direct3D12::ID3D12Device,
+    retired_sparse_heaps: Mutex<RetiredSparseHeaps>,
 }
 
 impl Queue {
     pub fn as_raw(&self) -> &Direct3D12::ID3D12CommandQueue {
         &self.raw
     }
+
+    /// Release the heaps unmapped before submissions that `fence` shows have
+    /// completed, and tie the heaps unmapped since the last submission to the
+    /// one that signals `signal_value`.
+    fn retire_sparse_heaps(&self, fence: &Fence, signal_value: crate::FenceValue) {
+        let mut retired = self.retired_sparse_heaps.lock();
+        if !retired.submitted.is_empty() {
+            let latest = unsafe { fence.raw.GetCompletedValue() };
+            retired.submitted.retain(|&(value, _)| value > latest);
+        }
+        let pending = mem::take(&mut retired.pending);
+        retired
+            .submitted
+            .extend(pending.into_iter().map(|heap| (signal_value, heap)));
+    }
+
+    fn create_sparse_heap(
+        &self,
+        flags: Direct3D12::D3D12_HEAP_FLAGS,
+    ) -> Result<Direct3D12::ID3D12Heap, crate::DeviceError> {
+        let desc = Direct3D12::D3D12_HEAP_DESC {
+            SizeInBytes: wgt::SPARSE_PAGE_SIZE,
+            Properties: Direct3D12::D3D12_HEAP_PROPERTIES {
+                Type: Direct3D12::D3D12_HEAP_TYPE_DEFAULT,
+                ..Default::default()
+            },
+            Alignment: 0,
+            Flags: flags,
+        };
+        let mut heap = None;
+        unsafe { self.device.CreateHeap(&desc, &mut heap) }
+            .into_device_result("Sparse heap creation")?;
+        heap.ok_or(crate::DeviceError::Unexpected)
+    }
+
+    /// Map `heap` to the tile of `resource` at `coordinate`, or unmap the tile
+    /// if `heap` is `None`.
+    fn update_tile_mapping(
+        &self,
+        resource: &Direct3D12::ID3D12Resource,
+        coordinate: Direct3D12::D3D12_TILED_RESOURCE_COORDINATE,
+        heap: Option<&Direct3D12::ID3D12Heap>,
+    ) {
+        let region_size = Direct3D12::D3D12_TILE_REGION_SIZE {
+            NumTiles: 1,
+            ..Default::default()
+        };
+        let range_flags = match heap {
+            Some(_) => Direct3D12::D3D12_TILE_RANGE_FLAG_NONE,
+            None => Direct3D12::D3D12_TILE_RANGE_FLAG_NULL,
+        };
+        let heap_offset = 0;
+        let tile_count = 1;
+        unsafe {
+            self.raw.UpdateTileMappings(
+                resource,
+                1,
+                Some(&coordinate),
+                Some(&region_size),
+                heap,
+                1,
+                Some(&range_flags),
+                Some(&heap_offset),
+                Some(&tile_count),
+                Direct3D12::D3D12_TILE_MAPPING_FLAG_NONE,
+            )
+        };
+    }
+}
+
+/// Heaps mapped to the tiles of a sparse buffer or texture by
+/// [`crate::Queue::bind_sparse_memory`].
+#[derive(Debug)]
+struct SparseMemory<T> {
+    /// The flags tiles of the resource need on heaps they're mapped to.
+    heap_flags: Direct3D12::D3D12_HEAP_FLAGS,
+    /// One heap of [`wgt::SPARSE_PAGE_SIZE`] bytes for each resident tile.
+    tiles: Mutex<HashMap<T, Direct3D12::ID3D12Heap>>,
+}
+
+impl<T> SparseMemory<T> {
+    fn new(heap_flags: Direct3D12::D3D12_HEAP_FLAGS) -> Self {
+        Self {
+            heap_flags,
+            tiles: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// A tile of a sparse texture: its subresource, and position in tiles.
+type SparseTile = (u32, u32, u32);
+
+/// Heaps unmapped from sparse resources, which the GPU may still be using.
+#[derive(Debug, Default)]
+struct RetiredSparseHeaps {
+    /// Unmapped since the last submission.
+    pending: Vec<Direct3D12::ID3D12Heap>,
+    /// Unmapped before submissions that signal their fence with the value.
+    submitted: Vec<(crate::FenceValue, Direct3D12::ID3D12Heap)>,
 }
 
 unsafe impl Send for Queue {}
@@ -872,6 +976,8 @@ pub struct Buffer {
     // as the allocation size varies for assorted reasons.
     size: wgt::BufferAddress,
     allocation: suballocation::Allocation,
+    /// Heaps mapped to the tiles of a sparse buffer, by tile index.
+    sparse: Option<Box<SparseMemory<u32>>>,
 }
 
 unsafe impl Send for Buffer {}
@@ -902,6 +1008,8 @@ pub struct Texture {
     mip_level_count: u32,
     sample_count: u32,
     allocation: suballocation::Allocation,
+    /// Heaps mapped to the tiles of a sparse texture.
+    sparse: Option<Box<SparseMemory<SparseTile>>>,
 }
 
 impl Texture {
@@ -987,6 +1095,16 @@ impl crate::DynQuerySet for QuerySet {}
 unsafe impl Send for QuerySet {}
 unsafe impl Sync for QuerySet {}
 
+#[derive(Debug)]
+pub struct Heap {
+    raw: Direct3D12::ID3D12Heap,
+}
+
+impl crate::DynHeap for Heap {}
+
+unsafe impl Send for Heap {}
+unsafe impl Sync for Heap {}
+
 #[derive(Debug)]
 pub struct Fence {
     raw: Direct3D12::ID3D12Fence,
@@ -1464,6 +1582,7 @@ impl crate::Surface for Surface {
                 suballocation::AllocationType::Texture,
                 sc.format.theoretical_memory_footprint(sc.size),
             ),
+            sparse: None,
         };
         Ok(Some(crate::AcquiredSurfaceTexture {
             texture,
@@ -1486,6 +1605,8 @@ impl crate::Queue for Queue {
         _surface_textures: &[&Texture],
         (signal_fence, signal_value): (&mut Fence, crate::FenceValue),
     ) -> Result<(), crate::DeviceError> {
+        self.retire_sparse_heaps(signal_fence, signal_value);
+
         let mut temp_lists = self.temp_lists.lock();
         temp_lists.clear();
         for cmd_buf in command_buffers {
@@ -1508,6 +1629,87 @@ impl crate::Queue for Queue {
 
         Ok(())
     }
+    unsafe fn wait_for_fence(
+        &self,
+        _fence: &Fence,
+        _value: crate::FenceValue,
+    ) -> Result<(), crate::DeviceError> {
+        crate::hal_usage_error("DX12 devices don't expose additional queues")
+    }
+    unsafe fn bind_sparse_memory(
+        &self,
+        buffer_binds: &[crate::SparseBufferBind<'_, Buffer>],
+        texture_binds: &[crate::SparseTextureBind<'_, Texture>],
+    ) -> Result<(), crate::DeviceError> {
+        // Tile mappings are updated in queue order, so they take effect between
+        // the surrounding submissions.
+        let page_size = wgt::SPARSE_PAGE_SIZE;
+        let mut retired = self.retired_sparse_heaps.lock();
+
+        for bind in buffer_binds {
+            let sparse = bind.buffer.sparse.as_deref().expect("Buffer is not sparse");
+            let mut tiles = sparse.tiles.lock();
+            for offset in bind.range.clone().step_by(page_size as usize) {
+                let index = (offset / page_size) as u32;
+                let coordinate = Direct3D12::D3D12_TILED_RESOURCE_COORDINATE {
+                    X: index,
+                    Y: 0,
+                    Z: 0,
+                    Subresource: 0,
+                };
+                if bind.resident {
+                    if tiles.contains_key(&index) {
+                        continue;
+                    }
+                    let heap = self.create_sparse_heap(sparse.heap_flags)?;
+                    self.update_tile_mapping(&bind.buffer.resource, coordinate, Some(&heap));
+                    tiles.insert(index, heap);
+                } else if let Some(heap) = tiles.remove(&index) {
+                    self.update_tile_mapping(&bind.buffer.resource, coordinate, None);
+                    retired.pending.push(heap);
+                }
+            }
+        }
+
+        for bind in texture_binds {
+            let texture = bind.texture;
+            let sparse = texture.sparse.as_deref().expect("Texture is not sparse");
+            let tile = texture
+                .format
+                .sparse_tile_extent()
+                .expect("Format has no sparse tile size");
+            let subresource = texture.calc_subresource(bind.mip_level, bind.array_layer, 0);
+            let mut tiles = sparse.tiles.lock();
+            let rows =
+                bind.origin.y / tile.height..(bind.origin.y + bind.size.height) / tile.height;
+            let columns =
+                bind.origin.x / tile.width..(bind.origin.x + bind.size.width) / tile.width;
+            for y in rows {
+                for x in columns.clone() {
+                    let key = (subresource, x, y);
+                    let coordinate = Direct3D12::D3D12_TILED_RESOURCE_COORDINATE {
+                        X: x,
+                        Y: y,
+                        Z: 0,
+                        Subresource: subresource,
+                    };
+                    if bind.resident {
+                        if tiles.contains_key(&key) {
+                            continue;
+                        }
+                        let heap = self.create_sparse_heap(sparse.heap_flags)?;
+                        self.update_tile_mapping(&texture.resource, coordinate, Some(&heap));
+                        tiles.insert(key, heap);
+                    } else if let Some(heap) = tiles.remove(&key) {
+                        self.update_tile_mapping(&texture.resource, coordinate, None);
+                        retired.pending.push(heap);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
     unsafe fn present(
         &self,
         surface: &Surface,
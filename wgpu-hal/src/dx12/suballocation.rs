@@ -268,6 +268,66 @@ impl<'a> DeviceAllocationContext<'a> {
         Ok((resource, allocation))
     }
 
+    /// Create a resource without memory, whose tiles are mapped to heaps by
+    /// [`crate::Queue::bind_sparse_memory`].
+    pub(crate) fn create_reserved_resource(
+        &self,
+        label: crate::Label,
+        raw_desc: Direct3D12::D3D12_RESOURCE_DESC,
+        ty: AllocationType,
+    ) -> Result<(Direct3D12::ID3D12Resource, Allocation), crate::DeviceError> {
+        let mut resource = None;
+        unsafe {
+            self.raw.CreateReservedResource(
+                &raw_desc,
+                Direct3D12::D3D12_RESOURCE_STATE_COMMON,
+                None,
+                &mut resource,
+            )
+        }
+        .into_device_result("Reserved resource creation")?;
+
+        let resource = resource.ok_or(crate::DeviceError::Unexpected)?;
+
+        if let Some(label) = label {
+            resource.set_name(label)?;
+        }
+
+        Ok((resource, Allocation::none(ty, 0)))
+    }
+
+    /// Create a resource using the memory of a heap owned by the caller.
+    pub(crate) fn create_resource_in_heap(
+        &self,
+        label: crate::Label,
+        raw_desc: Direct3D12::D3D12_RESOURCE_DESC,
+        heap: &Direct3D12::ID3D12Heap,
+        offset: u64,
+        ty: AllocationType,
+    ) -> Result<(Direct3D12::ID3D12Resource, Allocation), crate::DeviceError> {
+        let mut resource = None;
+        unsafe {
+            self.raw.CreatePlacedResource(
+                heap,
+                offset,
+                &raw_desc,
+                Direct3D12::D3D12_RESOURCE_STATE_COMMON,
+                None,
+                &mut resource,
+            )
+        }
+        .into_device_result("Placed resource creation")?;
+
+        let resource = resource.ok_or(crate::DeviceError::Unexpected)?;
+
+        if let Some(label) = label {
+            resource.set_name(label)?;
+        }
+
+        // The memory belongs to the heap, so the resource has nothing to free.
+        Ok((resource, Allocation::none(ty, 0)))
+    }
+
     //////////////////////////
     // Resource Destruction //
     //////////////////////////
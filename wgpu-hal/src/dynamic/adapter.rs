@@ -1,4 +1,4 @@
-use alloc::boxed::Box;
+use alloc::{boxed::Box, vec::Vec};
 
 use crate::{
     Adapter, Api, DeviceError, OpenDevice, SurfaceCapabilities, TextureFormatCapabilities,
@@ -9,6 +9,7 @@ use super::{DynDevice, DynQueue, DynResource, DynResourceExt, DynSurface};
 pub struct DynOpenDevice {
     pub device: Box<dyn DynDevice>,
     pub queue: Box<dyn DynQueue>,
+    pub additional_queues: Vec<(wgt::QueueType, Box<dyn DynQueue>)>,
 }
 
 impl<A: Api> From<OpenDevice<A>> for DynOpenDevice {
@@ -16,6 +17,11 @@ impl<A: Api> From<OpenDevice<A>> for DynOpenDevice {
         Self {
             device: Box::new(open_device.device),
             queue: Box::new(open_device.queue),
+            additional_queues: open_device
+                .additional_queues
+                .into_iter()
+                .map(|(ty, queue)| -> (_, Box<dyn DynQueue>) { (ty, Box::new(queue)) })
+                .collect(),
         }
     }
 }
@@ -45,10 +51,7 @@ impl<A: Adapter + DynResource> DynAdapter for A {
         limits: &wgt::Limits,
         memory_hints: &wgt::MemoryHints,
     ) -> Result<DynOpenDevice, DeviceError> {
-        unsafe { A::open(self, features, limits, memory_hints) }.map(|open_device| DynOpenDevice {
-            device: Box::new(open_device.device),
-            queue: Box::new(open_device.queue),
-        })
+        unsafe { A::open(self, features, limits, memory_hints) }.map(DynOpenDevice::from)
     }
 
     unsafe fn texture_format_capabilities(
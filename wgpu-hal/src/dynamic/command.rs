@@ -26,6 +26,8 @@ pub trait DynCommandEncoder: DynResource + core::fmt::Debug {
     unsafe fn transition_buffers(&mut self, barriers: &[BufferBarrier<'_, dyn DynBuffer>]);
     unsafe fn transition_textures(&mut self, barriers: &[TextureBarrier<'_, dyn DynTexture>]);
 
+    unsafe fn aliasing_barrier(&mut self);
+
     unsafe fn clear_buffer(&mut self, buffer: &dyn DynBuffer, range: MemoryRange);
 
     unsafe fn copy_buffer_to_buffer(
@@ -252,6 +254,10 @@ impl<C: CommandEncoder + DynResource> DynCommandEncoder for C {
         unsafe { self.transition_textures(barriers) };
     }
 
+    unsafe fn aliasing_barrier(&mut self) {
+        unsafe { C::aliasing_barrier(self) };
+    }
+
     unsafe fn clear_buffer(&mut self, buffer: &dyn DynBuffer, range: MemoryRange) {
         let buffer = buffer.expect_downcast_ref();
         unsafe { C::clear_buffer(self, buffer, range) };
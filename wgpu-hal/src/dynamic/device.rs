@@ -12,9 +12,9 @@ use crate::{
 
 use super::{
     DynAccelerationStructure, DynBindGroup, DynBindGroupLayout, DynBuffer, DynCommandEncoder,
-    DynComputePipeline, DynFence, DynPipelineCache, DynPipelineLayout, DynQuerySet, DynQueue,
-    DynRenderPipeline, DynResource, DynResourceExt as _, DynSampler, DynShaderModule, DynTexture,
-    DynTextureView,
+    DynComputePipeline, DynFence, DynHeap, DynPipelineCache, DynPipelineLayout, DynQuerySet,
+    DynQueue, DynRenderPipeline, DynResource, DynResourceExt as _, DynSampler, DynShaderModule,
+    DynTexture, DynTextureView,
 };
 
 pub trait DynDevice: DynResource {
@@ -44,6 +44,32 @@ pub trait DynDevice: DynResource {
     unsafe fn destroy_texture(&self, texture: Box<dyn DynTexture>);
     unsafe fn add_raw_texture(&self, texture: &dyn DynTexture);
 
+    unsafe fn create_heap(
+        &self,
+        desc: &wgt::HeapDescriptor<Label>,
+    ) -> Result<Box<dyn DynHeap>, DeviceError>;
+    unsafe fn destroy_heap(&self, heap: Box<dyn DynHeap>);
+    unsafe fn get_buffer_memory_requirements(
+        &self,
+        desc: &BufferDescriptor,
+    ) -> Result<wgt::MemoryRequirements, DeviceError>;
+    unsafe fn get_texture_memory_requirements(
+        &self,
+        desc: &TextureDescriptor,
+    ) -> Result<wgt::MemoryRequirements, DeviceError>;
+    unsafe fn create_placed_buffer(
+        &self,
+        desc: &BufferDescriptor,
+        heap: &dyn DynHeap,
+        offset: wgt::BufferAddress,
+    ) -> Result<Box<dyn DynBuffer>, DeviceError>;
+    unsafe fn create_placed_texture(
+        &self,
+        desc: &TextureDescriptor,
+        heap: &dyn DynHeap,
+        offset: wgt::BufferAddress,
+    ) -> Result<Box<dyn DynTexture>, DeviceError>;
+
     unsafe fn create_texture_view(
         &self,
         texture: &dyn DynTexture,
@@ -227,6 +253,53 @@ impl<D: Device + DynResource> DynDevice for D {
         unsafe { D::add_raw_texture(self, texture) };
     }
 
+    unsafe fn create_heap(
+        &self,
+        desc: &wgt::HeapDescriptor<Label>,
+    ) -> Result<Box<dyn DynHeap>, DeviceError> {
+        unsafe { D::create_heap(self, desc) }.map(|b| -> Box<dyn DynHeap> { Box::new(b) })
+    }
+
+    unsafe fn destroy_heap(&self, heap: Box<dyn DynHeap>) {
+        unsafe { D::destroy_heap(self, heap.unbox()) };
+    }
+
+    unsafe fn get_buffer_memory_requirements(
+        &self,
+        desc: &BufferDescriptor,
+    ) -> Result<wgt::MemoryRequirements, DeviceError> {
+        unsafe { D::get_buffer_memory_requirements(self, desc) }
+    }
+
+    unsafe fn get_texture_memory_requirements(
+        &self,
+        desc: &TextureDescriptor,
+    ) -> Result<wgt::MemoryRequirements, DeviceError> {
+        unsafe { D::get_texture_memory_requirements(self, desc) }
+    }
+
+    unsafe fn create_placed_buffer(
+        &self,
+        desc: &BufferDescriptor,
+        heap: &dyn DynHeap,
+        offset: wgt::BufferAddress,
+    ) -> Result<Box<dyn DynBuffer>, DeviceError> {
+        let heap = heap.expect_downcast_ref();
+        unsafe { D::create_placed_buffer(self, desc, heap, offset) }
+            .map(|b| -> Box<dyn DynBuffer> { Box::new(b) })
+    }
+
+    unsafe fn create_placed_texture(
+        &self,
+        desc: &TextureDescriptor,
+        heap: &dyn DynHeap,
+        offset: wgt::BufferAddress,
+    ) -> Result<Box<dyn DynTexture>, DeviceError> {
+        let heap = heap.expect_downcast_ref();
+        unsafe { D::create_placed_texture(self, desc, heap, offset) }
+            .map(|b| -> Box<dyn DynTexture> { Box::new(b) })
+    }
+
     unsafe fn create_texture_view(
         &self,
         texture: &dyn DynTexture,
@@ -1,8 +1,8 @@
 use alloc::{boxed::Box, vec::Vec};
 
 use crate::{
-    DeviceError, DynCommandBuffer, DynFence, DynResource, DynSurface, DynSurfaceTexture,
-    FenceValue, Queue, SurfaceError,
+    DeviceError, DynBuffer, DynCommandBuffer, DynFence, DynResource, DynSurface, DynSurfaceTexture,
+    DynTexture, FenceValue, Queue, SparseBufferBind, SparseTextureBind, SurfaceError,
 };
 
 use super::DynResourceExt as _;
@@ -14,6 +14,16 @@ pub trait DynQueue: DynResource {
         surface_textures: &[&dyn DynSurfaceTexture],
         signal_fence: (&mut dyn DynFence, FenceValue),
     ) -> Result<(), DeviceError>;
+    unsafe fn wait_for_fence(
+        &self,
+        fence: &dyn DynFence,
+        value: FenceValue,
+    ) -> Result<(), DeviceError>;
+    unsafe fn bind_sparse_memory(
+        &self,
+        buffer_binds: &[SparseBufferBind<'_, dyn DynBuffer>],
+        texture_binds: &[SparseTextureBind<'_, dyn DynTexture>],
+    ) -> Result<(), DeviceError>;
     unsafe fn present(
         &self,
         surface: &dyn DynSurface,
@@ -41,6 +51,42 @@ impl<Q: Queue + DynResource> DynQueue for Q {
         unsafe { Q::submit(self, &command_buffers, &surface_textures, signal_fence) }
     }
 
+    unsafe fn wait_for_fence(
+        &self,
+        fence: &dyn DynFence,
+        value: FenceValue,
+    ) -> Result<(), DeviceError> {
+        let fence = fence.expect_downcast_ref();
+        unsafe { Q::wait_for_fence(self, fence, value) }
+    }
+
+    unsafe fn bind_sparse_memory(
+        &self,
+        buffer_binds: &[SparseBufferBind<'_, dyn DynBuffer>],
+        texture_binds: &[SparseTextureBind<'_, dyn DynTexture>],
+    ) -> Result<(), DeviceError> {
+        let buffer_binds = buffer_binds
+            .iter()
+            .map(|bind| SparseBufferBind {
+                buffer: bind.buffer.expect_downcast_ref(),
+                range: bind.range.clone(),
+                resident: bind.resident,
+            })
+            .collect::<Vec<_>>();
+        let texture_binds = texture_binds
+            .iter()
+            .map(|bind| SparseTextureBind {
+                texture: bind.texture.expect_downcast_ref(),
+                mip_level: bind.mip_level,
+                array_layer: bind.array_layer,
+                origin: bind.origin,
+                size: bind.size,
+                resident: bind.resident,
+            })
+            .collect::<Vec<_>>();
+        unsafe { Q::bind_sparse_memory(self, &buffer_binds, &texture_binds) }
+    }
+
     unsafe fn present(
         &self,
         surface: &dyn DynSurface,
@@ -441,7 +441,8 @@ impl super::Adapter {
             | wgt::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
             | wgt::Features::CLEAR_TEXTURE
             | wgt::Features::PUSH_CONSTANTS
-            | wgt::Features::DEPTH32FLOAT_STENCIL8;
+            | wgt::Features::DEPTH32FLOAT_STENCIL8
+            | wgt::Features::EXTERNAL_TEXTURE;
         features.set(
             wgt::Features::ADDRESS_MODE_CLAMP_TO_BORDER | wgt::Features::ADDRESS_MODE_CLAMP_TO_ZERO,
             extensions.contains("GL_EXT_texture_border_clamp")
@@ -1030,6 +1031,7 @@ impl crate::Adapter for super::Adapter {
                 draw_buffer_count: AtomicU8::new(1),
                 current_index_buffer: Mutex::new(None),
             },
+            additional_queues: Vec::new(),
         })
     }
 
@@ -330,6 +330,8 @@ impl crate::CommandEncoder for super::CommandEncoder {
         }
     }
 
+    unsafe fn aliasing_barrier(&mut self) {}
+
     unsafe fn clear_buffer(&mut self, buffer: &super::Buffer, range: crate::MemoryRange) {
         self.cmd_buffer.commands.push(C::ClearBuffer {
             dst: buffer.clone(),
@@ -782,6 +784,39 @@ impl crate::CommandEncoder for super::CommandEncoder {
                         binding: binding.clone(),
                     });
                 }
+                super::RawBinding::ExternalTexture {
+                    ref planes,
+                    params,
+                    params_offset,
+                    params_size,
+                } => {
+                    let br = naga::ResourceBinding {
+                        group: index,
+                        binding: binding_layout.binding,
+                    };
+                    let bind_target = &layout.naga_options.external_texture_binding_map[&br];
+                    for (&plane_slot, &(raw, target, ref mip_levels)) in
+                        bind_target.planes.iter().zip(planes.iter())
+                    {
+                        let plane_slot = plane_slot as u32;
+                        dirty_textures |= 1 << plane_slot;
+                        self.state.texture_slots[plane_slot as usize].tex_target = target;
+                        self.cmd_buffer.commands.push(C::BindTexture {
+                            slot: plane_slot,
+                            texture: raw,
+                            target,
+                            aspects: crate::FormatAspects::COLOR,
+                            mip_levels: mip_levels.clone(),
+                        });
+                    }
+                    self.cmd_buffer.commands.push(C::BindBuffer {
+                        target: glow::UNIFORM_BUFFER,
+                        slot: bind_target.params as u32,
+                        buffer: params,
+                        offset: params_offset,
+                        size: params_size,
+                    });
+                }
             }
         }
 
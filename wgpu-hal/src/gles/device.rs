@@ -43,11 +43,27 @@ impl CompilationContext<'_> {
             let register = match var.space {
                 naga::AddressSpace::Uniform => super::BindingRegister::UniformBuffers,
                 naga::AddressSpace::Storage { .. } => super::BindingRegister::StorageBuffers,
+                // The parameters of external textures are in a uniform block
+                naga::AddressSpace::Handle => match module.types[var.ty].inner {
+                    naga::TypeInner::Image {
+                        class: naga::ImageClass::External,
+                        ..
+                    } => super::BindingRegister::UniformBuffers,
+                    _ => continue,
+                },
                 _ => continue,
             };
 
             let br = var.binding.as_ref().unwrap();
-            let slot = self.layout.get_slot(br);
+            let slot = match self
+                .layout
+                .naga_options
+                .external_texture_binding_map
+                .get(br)
+            {
+                Some(bind_target) => bind_target.params,
+                None => self.layout.get_slot(br),
+            };
 
             let name = match reflection_info.uniforms.get(&handle) {
                 Some(name) => name.clone(),
@@ -74,7 +90,12 @@ impl CompilationContext<'_> {
             };
 
             let tex_br = var.binding.as_ref().unwrap();
-            let texture_linear_index = self.layout.get_slot(tex_br);
+            let texture_linear_index = match mapping.external_plane {
+                Some(plane) => {
+                    self.layout.naga_options.external_texture_binding_map[tex_br].planes[plane]
+                }
+                None => self.layout.get_slot(tex_br),
+            };
 
             self.name_binding_map
                 .insert(name, (register, texture_linear_index));
@@ -734,7 +755,8 @@ impl crate::Device for super::Device {
 
         let render_usage = wgt::TextureUses::COLOR_TARGET
             | wgt::TextureUses::DEPTH_STENCIL_WRITE
-            | wgt::TextureUses::DEPTH_STENCIL_READ;
+            | wgt::TextureUses::DEPTH_STENCIL_READ
+            | wgt::TextureUses::TRANSIENT;
         let format_desc = self.shared.describe_texture_format(desc.format);
 
         let inner = if render_usage.contains(desc.usage)
@@ -990,6 +1012,42 @@ impl crate::Device for super::Device {
         self.counters.textures.add(1);
     }
 
+    unsafe fn create_heap(
+        &self,
+        _desc: &wgt::HeapDescriptor<crate::Label>,
+    ) -> Result<super::Heap, crate::DeviceError> {
+        crate::hal_usage_error("GLES devices don't support resource heaps")
+    }
+    unsafe fn destroy_heap(&self, _heap: super::Heap) {}
+    unsafe fn get_buffer_memory_requirements(
+        &self,
+        _desc: &crate::BufferDescriptor,
+    ) -> Result<wgt::MemoryRequirements, crate::DeviceError> {
+        crate::hal_usage_error("GLES devices don't support resource heaps")
+    }
+    unsafe fn get_texture_memory_requirements(
+        &self,
+        _desc: &crate::TextureDescriptor,
+    ) -> Result<wgt::MemoryRequirements, crate::DeviceError> {
+        crate::hal_usage_error("GLES devices don't support resource heaps")
+    }
+    unsafe fn create_placed_buffer(
+        &self,
+        _desc: &crate::BufferDescriptor,
+        _heap: &super::Heap,
+        _offset: wgt::BufferAddress,
+    ) -> Result<super::Buffer, crate::DeviceError> {
+        crate::hal_usage_error("GLES devices don't support resource heaps")
+    }
+    unsafe fn create_placed_texture(
+        &self,
+        _desc: &crate::TextureDescriptor,
+        _heap: &super::Heap,
+        _offset: wgt::BufferAddress,
+    ) -> Result<super::Texture, crate::DeviceError> {
+        crate::hal_usage_error("GLES devices don't support resource heaps")
+    }
+
     unsafe fn create_texture_view(
         &self,
         texture: &super::Texture,
@@ -1171,6 +1229,7 @@ impl crate::Device for super::Device {
         // https://github.com/gfx-rs/wgpu/pull/3440/files#r1095726950
         writer_flags.set(glsl::WriterFlags::FORCE_POINT_SIZE, true);
         let mut binding_map = glsl::BindingMap::default();
+        let mut external_texture_binding_map = glsl::ExternalTextureBindingMap::default();
 
         for (group_index, bg_layout) in desc.bind_group_layouts.iter().enumerate() {
             // create a vector with the size enough to hold all the bindings, filled with `!0`
@@ -1186,6 +1245,28 @@ impl crate::Device for super::Device {
             .into_boxed_slice();
 
             for entry in bg_layout.entries.iter() {
+                let br = naga::ResourceBinding {
+                    group: group_index as u32,
+                    binding: entry.binding,
+                };
+
+                // External textures need 3 texture slots (one for each plane)
+                // and a uniform buffer slot for the parameters.
+                if let wgt::BindingType::ExternalTexture = entry.ty {
+                    let bind_target = glsl::ExternalTextureBindTarget {
+                        planes: core::array::from_fn(|_| {
+                            let slot = num_textures;
+                            num_textures += 1;
+                            slot
+                        }),
+                        params: num_uniform_buffers,
+                    };
+                    num_uniform_buffers += 1;
+                    binding_to_slot[entry.binding as usize] = bind_target.planes[0];
+                    external_texture_binding_map.insert(br, bind_target);
+                    continue;
+                }
+
                 let counter = match entry.ty {
                     wgt::BindingType::Sampler { .. } => &mut num_samplers,
                     wgt::BindingType::Texture { .. } => &mut num_textures,
@@ -1199,14 +1280,10 @@ impl crate::Device for super::Device {
                         ..
                     } => &mut num_storage_buffers,
                     wgt::BindingType::AccelerationStructure { .. } => unimplemented!(),
-                    wgt::BindingType::ExternalTexture => unimplemented!(),
+                    wgt::BindingType::ExternalTexture => unreachable!(),
                 };
 
                 binding_to_slot[entry.binding as usize] = *counter;
-                let br = naga::ResourceBinding {
-                    group: group_index as u32,
-                    binding: entry.binding,
-                };
                 binding_map.insert(br, *counter);
                 *counter += entry.count.map_or(1, |c| c.get() as u8);
             }
@@ -1225,7 +1302,10 @@ impl crate::Device for super::Device {
                 version: self.shared.shading_language_version,
                 writer_flags,
                 binding_map,
+                external_texture_binding_map,
                 zero_initialize_workgroup_memory: true,
+                source_map: false,
+                line_directives: None,
             },
         })
     }
@@ -1310,7 +1390,24 @@ impl crate::Device for super::Device {
                     })
                 }
                 wgt::BindingType::AccelerationStructure { .. } => unimplemented!(),
-                wgt::BindingType::ExternalTexture => unimplemented!(),
+                wgt::BindingType::ExternalTexture => {
+                    let external_texture = &desc.external_textures[entry.resource_index as usize];
+                    let planes = core::array::from_fn(|i| {
+                        let view = external_texture.planes[i].view;
+                        let (raw, target) = view.inner.as_native();
+                        (raw, target, view.mip_levels.clone())
+                    });
+                    let params = &external_texture.params;
+                    super::RawBinding::ExternalTexture {
+                        planes,
+                        params: params.buffer.raw.unwrap(),
+                        params_offset: params.offset as i32,
+                        params_size: match params.size {
+                            Some(s) => s.get() as i32,
+                            None => (params.buffer.size - params.offset) as i32,
+                        },
+                    }
+                }
             };
             contents.push(binding);
         }
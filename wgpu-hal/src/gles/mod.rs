@@ -160,6 +160,7 @@ impl crate::Api for Api {
     type QuerySet = QuerySet;
     type Fence = Fence;
     type AccelerationStructure = AccelerationStructure;
+    type Heap = Heap;
     type PipelineCache = PipelineCache;
 
     type BindGroupLayout = BindGroupLayout;
@@ -173,6 +174,7 @@ impl crate::Api for Api {
 crate::impl_dyn_resource!(
     Adapter,
     AccelerationStructure,
+    Heap,
     BindGroup,
     BindGroupLayout,
     Buffer,
@@ -547,6 +549,7 @@ struct BindGroupLayoutInfo {
     entries: Arc<[wgt::BindGroupLayoutEntry]>,
     /// Mapping of resources, indexed by `binding`, into the whole layout space.
     /// For texture resources, the value is the texture slot index.
+    /// For external textures, the value is the texture slot index of the first plane.
     /// For sampler resources, the value is the index of the sampler in the whole layout.
     /// For buffers, the value is the uniform or storage slot index.
     /// For unused bindings, the value is `!0`
@@ -592,6 +595,18 @@ enum RawBinding {
     },
     Image(ImageBinding),
     Sampler(glow::Sampler),
+    /// The planes and parameters buffer of an external texture. The planes
+    /// occupy consecutive texture slots, and the parameters buffer the
+    /// uniform buffer slot in the pipeline layout's
+    /// [`external_texture_binding_map`].
+    ///
+    /// [`external_texture_binding_map`]: naga::back::glsl::Options::external_texture_binding_map
+    ExternalTexture {
+        planes: [(glow::Texture, BindTarget, Range<u32>); 3],
+        params: glow::Buffer,
+        params_offset: i32,
+        params_size: i32,
+    },
 }
 
 #[derive(Debug)]
@@ -760,6 +775,11 @@ pub struct AccelerationStructure;
 
 impl crate::DynAccelerationStructure for AccelerationStructure {}
 
+#[derive(Debug)]
+pub struct Heap;
+
+impl crate::DynHeap for Heap {}
+
 #[derive(Debug)]
 pub struct PipelineCache;
 
@@ -1916,6 +1916,22 @@ impl crate::Queue for super::Queue {
         Ok(())
     }
 
+    unsafe fn wait_for_fence(
+        &self,
+        _fence: &super::Fence,
+        _value: crate::FenceValue,
+    ) -> Result<(), crate::DeviceError> {
+        crate::hal_usage_error("GLES devices don't expose additional queues")
+    }
+
+    unsafe fn bind_sparse_memory(
+        &self,
+        _buffer_binds: &[crate::SparseBufferBind<'_, super::Buffer>],
+        _texture_binds: &[crate::SparseTextureBind<'_, super::Texture>],
+    ) -> Result<(), crate::DeviceError> {
+        crate::hal_usage_error("GLES devices don't support sparse resources")
+    }
+
     unsafe fn present(
         &self,
         surface: &super::Surface,
@@ -243,7 +243,7 @@
 extern crate alloc;
 extern crate wgpu_types as wgt;
 // Each of these backends needs `std` in some fashion; usually `std::thread` functions.
-#[cfg(any(dx12, gles_with_std, metal, vulkan))]
+#[cfg(any(dx12, gles_with_std, metal, vulkan, feature = "std"))]
 #[macro_use]
 extern crate std;
 
@@ -287,7 +287,7 @@ pub(crate) use dynamic::impl_dyn_resource;
 pub use dynamic::{
     DynAccelerationStructure, DynAcquiredSurfaceTexture, DynAdapter, DynBindGroup,
     DynBindGroupLayout, DynBuffer, DynCommandBuffer, DynCommandEncoder, DynComputePipeline,
-    DynDevice, DynExposedAdapter, DynFence, DynInstance, DynOpenDevice, DynPipelineCache,
+    DynDevice, DynExposedAdapter, DynFence, DynHeap, DynInstance, DynOpenDevice, DynPipelineCache,
     DynPipelineLayout, DynQuerySet, DynQueue, DynRenderPipeline, DynResource, DynSampler,
     DynShaderModule, DynSurface, DynSurfaceTexture, DynTexture, DynTextureView,
 };
@@ -509,6 +509,11 @@ pub trait Api: Clone + fmt::Debug + Sized + WasmNotSendSync + 'static {
     type Sampler: DynSampler;
     type QuerySet: DynQuerySet;
 
+    /// A block of device memory that buffers and textures can be placed in.
+    ///
+    /// See [`Device::create_placed_buffer`] and [`Device::create_placed_texture`].
+    type Heap: DynHeap;
+
     /// A value you can block on to wait for something to finish.
     ///
     /// A `Fence` holds a monotonically increasing [`FenceValue`]. You can call
@@ -870,6 +875,77 @@ pub trait Device: WasmNotSendSync {
     /// A hook for when a wgpu-core texture is created from a raw wgpu-hal texture.
     unsafe fn add_raw_texture(&self, texture: &<Self::A as Api>::Texture);
 
+    /// Allocates a heap of `desc.size` bytes of device local memory.
+    ///
+    /// Only backends that expose [`wgt::Features::RESOURCE_HEAPS`] have this
+    /// method called.
+    unsafe fn create_heap(
+        &self,
+        desc: &wgt::HeapDescriptor<Label>,
+    ) -> Result<<Self::A as Api>::Heap, DeviceError>;
+
+    /// Frees `heap`.
+    ///
+    /// # Safety
+    ///
+    /// - All resources placed in `heap` must have been destroyed.
+    unsafe fn destroy_heap(&self, heap: <Self::A as Api>::Heap);
+
+    /// Returns the size and alignment of the memory a buffer created with
+    /// `desc` needs when placed in a heap.
+    unsafe fn get_buffer_memory_requirements(
+        &self,
+        desc: &BufferDescriptor,
+    ) -> Result<wgt::MemoryRequirements, DeviceError>;
+
+    /// Returns the size and alignment of the memory a texture created with
+    /// `desc` needs when placed in a heap.
+    unsafe fn get_texture_memory_requirements(
+        &self,
+        desc: &TextureDescriptor,
+    ) -> Result<wgt::MemoryRequirements, DeviceError>;
+
+    /// Creates a buffer using the memory of `heap` starting at `offset`.
+    ///
+    /// Placed resources may overlap each other. Destroying a placed buffer
+    /// doesn't free any memory.
+    ///
+    /// # Safety
+    ///
+    /// - `desc.memory_flags` must not contain [`MemoryFlags::PREFER_COHERENT`]
+    ///   and `desc.usage` must not contain mapping usages.
+    ///
+    /// - `offset` must be a multiple of the alignment, and `offset` plus the
+    ///   size returned by [`get_buffer_memory_requirements`] for `desc` must
+    ///   not exceed the size of `heap`.
+    ///
+    /// [`get_buffer_memory_requirements`]: Device::get_buffer_memory_requirements
+    unsafe fn create_placed_buffer(
+        &self,
+        desc: &BufferDescriptor,
+        heap: &<Self::A as Api>::Heap,
+        offset: wgt::BufferAddress,
+    ) -> Result<<Self::A as Api>::Buffer, DeviceError>;
+
+    /// Creates a texture using the memory of `heap` starting at `offset`.
+    ///
+    /// Placed resources may overlap each other. Destroying a placed texture
+    /// doesn't free any memory.
+    ///
+    /// # Safety
+    ///
+    /// - `offset` must be a multiple of the alignment, and `offset` plus the
+    ///   size returned by [`get_texture_memory_requirements`] for `desc` must
+    ///   not exceed the size of `heap`.
+    ///
+    /// [`get_texture_memory_requirements`]: Device::get_texture_memory_requirements
+    unsafe fn create_placed_texture(
+        &self,
+        desc: &TextureDescriptor,
+        heap: &<Self::A as Api>::Heap,
+        offset: wgt::BufferAddress,
+    ) -> Result<<Self::A as Api>::Texture, DeviceError>;
+
     unsafe fn create_texture_view(
         &self,
         texture: &<Self::A as Api>::Texture,
@@ -1122,6 +1198,71 @@ pub trait Queue: WasmNotSendSync {
         surface_textures: &[&<Self::A as Api>::SurfaceTexture],
         signal_fence: (&mut <Self::A as Api>::Fence, FenceValue),
     ) -> Result<(), DeviceError>;
+    /// Make the next call to [`submit`] on this queue wait on the GPU until
+    /// `fence` reaches `value`.
+    ///
+    /// This is how work on one queue is ordered after work on another: the
+    /// fence is one that [`submit`] on the other queue signals. All results
+    /// produced by the other queue's work up to `value` are visible to the
+    /// commands of the next submission.
+    ///
+    /// # Safety
+    ///
+    /// - `fence` must have been created by the [`Device`][d] associated with
+    ///   this [`Queue`].
+    ///
+    /// - `fence` must remain alive until the next submission to this queue
+    ///   has completed.
+    ///
+    /// - A submission that signals `fence` with a value greater than or equal
+    ///   to `value` must have been made (or be made) on another queue, or the
+    ///   next submission on this queue will never complete.
+    ///
+    /// Backends that don't expose additional queues through
+    /// [`OpenDevice::additional_queues`] never have this method called.
+    ///
+    /// [`submit`]: Queue::submit
+    /// [d]: Api::Device
+    unsafe fn wait_for_fence(
+        &self,
+        fence: &<Self::A as Api>::Fence,
+        value: FenceValue,
+    ) -> Result<(), DeviceError>;
+    /// Change which pages of sparse buffers and tiles of sparse textures are
+    /// backed by memory.
+    ///
+    /// Pages and tiles made resident are backed by newly allocated memory,
+    /// whose contents are undefined. Ones made non-resident have their memory
+    /// released once the next submission on this queue has completed. Making
+    /// resident a page that already is, or the reverse, has no effect.
+    ///
+    /// The change takes effect after all previous submissions on this queue,
+    /// and before the next call to [`submit`].
+    ///
+    /// # Safety
+    ///
+    /// - Every buffer and texture must have been created with
+    ///   [`MemoryFlags::SPARSE`] by the [`Device`][d] associated with this
+    ///   [`Queue`].
+    ///
+    /// - Buffer ranges must be aligned to [`wgt::SPARSE_PAGE_SIZE`], and
+    ///   texture regions to the [tile size] of the texture's format, and lie
+    ///   within the resource.
+    ///
+    /// - [`submit`] must be called on this queue before any of the resources
+    ///   are destroyed.
+    ///
+    /// Only backends that expose [`wgt::Features::SPARSE_RESOURCES`] have this
+    /// method called.
+    ///
+    /// [`submit`]: Queue::submit
+    /// [d]: Api::Device
+    /// [tile size]: wgt::TextureFormat::sparse_tile_extent
+    unsafe fn bind_sparse_memory(
+        &self,
+        buffer_binds: &[SparseBufferBind<'_, <Self::A as Api>::Buffer>],
+        texture_binds: &[SparseTextureBind<'_, <Self::A as Api>::Texture>],
+    ) -> Result<(), DeviceError>;
     unsafe fn present(
         &self,
         surface: &<Self::A as Api>::Surface,
@@ -1250,6 +1391,14 @@ pub trait CommandEncoder: WasmNotSendSync + fmt::Debug {
     where
         T: Iterator<Item = TextureBarrier<'a, <Self::A as Api>::Texture>>;
 
+    /// Makes all previous accesses to memory shared by placed resources
+    /// complete before any following access.
+    ///
+    /// This must be recorded whenever a placed resource starts being used in
+    /// place of another resource it overlaps. The contents of the newly used
+    /// resource are undefined after the barrier, but its usage is unchanged.
+    unsafe fn aliasing_barrier(&mut self);
+
     // copy operations
 
     unsafe fn clear_buffer(&mut self, buffer: &<Self::A as Api>::Buffer, range: MemoryRange);
@@ -1745,6 +1894,9 @@ bitflags!(
     pub struct MemoryFlags: u32 {
         const TRANSIENT = 1 << 0;
         const PREFER_COHERENT = 1 << 1;
+        /// Create the resource without memory, for it to be bound page by page
+        /// with [`Queue::bind_sparse_memory`].
+        const SPARSE = 1 << 2;
     }
 );
 
@@ -1872,6 +2024,12 @@ pub struct AcquiredSurfaceTexture<A: Api> {
 pub struct OpenDevice<A: Api> {
     pub device: A::Device,
     pub queue: A::Queue,
+    /// Queues besides `queue`, one at most of each type, when
+    /// [`wgt::Features::MULTIPLE_QUEUES`] was requested.
+    ///
+    /// `queue` is always a [`wgt::QueueType::Graphics`] queue, and is never
+    /// duplicated here.
+    pub additional_queues: Vec<(wgt::QueueType, A::Queue)>,
 }
 
 #[derive(Clone, Debug)]
@@ -2399,6 +2557,30 @@ pub struct BufferBarrier<'a, B: DynBuffer + ?Sized> {
     pub usage: StateTransition<wgt::BufferUses>,
 }
 
+/// A change to the residency of a range of a sparse buffer, made by
+/// [`Queue::bind_sparse_memory`].
+#[derive(Debug, Clone)]
+pub struct SparseBufferBind<'a, B: DynBuffer + ?Sized> {
+    pub buffer: &'a B,
+    /// Aligned to [`wgt::SPARSE_PAGE_SIZE`].
+    pub range: MemoryRange,
+    pub resident: bool,
+}
+
+/// A change to the residency of a region of a sparse texture, made by
+/// [`Queue::bind_sparse_memory`].
+#[derive(Debug, Clone)]
+pub struct SparseTextureBind<'a, T: DynTexture + ?Sized> {
+    pub texture: &'a T,
+    pub mip_level: u32,
+    pub array_layer: u32,
+    /// Origin of the region in texels, aligned to the tile size.
+    pub origin: wgt::Origin3d,
+    /// Size of the region in texels, aligned to the tile size.
+    pub size: wgt::Extent3d,
+    pub resident: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct TextureBarrier<'a, T: DynTexture + ?Sized> {
     pub texture: &'a T,
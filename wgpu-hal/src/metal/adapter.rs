@@ -89,6 +89,7 @@ impl crate::Adapter for super::Adapter {
                 raw: Arc::new(Mutex::new(queue)),
                 timestamp_period,
             },
+            additional_queues: Vec::new(),
         })
     }
 
@@ -642,6 +643,7 @@ impl super::PrivateCapabilities {
             resource_heaps: Self::supports_any(device, RESOURCE_HEAP_SUPPORT),
             argument_buffers,
             shared_textures: !os_is_mac,
+            memoryless_storage: family_check && device.supports_family(MTLGPUFamily::Apple2),
             mutable_comparison_samplers: Self::supports_any(
                 device,
                 MUTABLE_COMPARISON_SAMPLER_SUPPORT,
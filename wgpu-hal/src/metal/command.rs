@@ -259,6 +259,8 @@ impl crate::CommandEncoder for super::CommandEncoder {
     {
     }
 
+    unsafe fn aliasing_barrier(&mut self) {}
+
     unsafe fn clear_buffer(&mut self, buffer: &super::Buffer, range: crate::MemoryRange) {
         let encoder = self.enter_blit();
         encoder.fill_buffer(&buffer.raw, conv::map_range(&range), 0);
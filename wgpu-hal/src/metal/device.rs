@@ -13,7 +13,7 @@ use metal::{
     foreign_types::ForeignType, MTLCommandBufferStatus, MTLDepthClipMode, MTLLanguageVersion,
     MTLMutability, MTLPixelFormat, MTLPrimitiveTopologyClass, MTLResourceID, MTLResourceOptions,
     MTLSamplerAddressMode, MTLSamplerMipFilter, MTLSize, MTLStorageMode, MTLTextureType,
-    MTLTriangleFillMode, MTLVertexStepFunction, NSRange,
+    MTLTextureUsage, MTLTriangleFillMode, MTLVertexStepFunction, NSRange,
 };
 
 type DeviceResult<T> = Result<T, crate::DeviceError>;
@@ -179,6 +179,8 @@ impl super::Device {
             },
             zero_initialize_workgroup_memory: stage.zero_initialize_workgroup_memory,
             force_loop_bounding: stage.module.bounds_checks.force_loop_bounding,
+            source_map: false,
+            line_directives: None,
         };
 
         let pipeline_options = naga::back::msl::PipelineOptions {
@@ -439,8 +441,17 @@ impl crate::Device for super::Device {
             descriptor.set_height(desc.size.height as u64);
             descriptor.set_mipmap_level_count(desc.mip_level_count as u64);
             descriptor.set_pixel_format(mtl_format);
-            descriptor.set_usage(conv::map_texture_usage(desc.format, desc.usage));
-            descriptor.set_storage_mode(MTLStorageMode::Private);
+            if desc.usage.contains(wgt::TextureUses::TRANSIENT)
+                && self.shared.private_caps.memoryless_storage
+            {
+                // Memoryless textures only exist in tile memory, so they can
+                // only be render targets.
+                descriptor.set_usage(MTLTextureUsage::RenderTarget);
+                descriptor.set_storage_mode(MTLStorageMode::Memoryless);
+            } else {
+                descriptor.set_usage(conv::map_texture_usage(desc.format, desc.usage));
+                descriptor.set_storage_mode(MTLStorageMode::Private);
+            }
 
             let raw = self.shared.device.lock().new_texture(&descriptor);
             if raw.as_ptr().is_null() {
@@ -471,6 +482,42 @@ impl crate::Device for super::Device {
         self.counters.textures.add(1);
     }
 
+    unsafe fn create_heap(
+        &self,
+        _desc: &wgt::HeapDescriptor<crate::Label>,
+    ) -> Result<super::Heap, crate::DeviceError> {
+        crate::hal_usage_error("Metal devices don't support resource heaps")
+    }
+    unsafe fn destroy_heap(&self, _heap: super::Heap) {}
+    unsafe fn get_buffer_memory_requirements(
+        &self,
+        _desc: &crate::BufferDescriptor,
+    ) -> Result<wgt::MemoryRequirements, crate::DeviceError> {
+        crate::hal_usage_error("Metal devices don't support resource heaps")
+    }
+    unsafe fn get_texture_memory_requirements(
+        &self,
+        _desc: &crate::TextureDescriptor,
+    ) -> Result<wgt::MemoryRequirements, crate::DeviceError> {
+        crate::hal_usage_error("Metal devices don't support resource heaps")
+    }
+    unsafe fn create_placed_buffer(
+        &self,
+        _desc: &crate::BufferDescriptor,
+        _heap: &super::Heap,
+        _offset: wgt::BufferAddress,
+    ) -> Result<super::Buffer, crate::DeviceError> {
+        crate::hal_usage_error("Metal devices don't support resource heaps")
+    }
+    unsafe fn create_placed_texture(
+        &self,
+        _desc: &crate::TextureDescriptor,
+        _heap: &super::Heap,
+        _offset: wgt::BufferAddress,
+    ) -> Result<super::Texture, crate::DeviceError> {
+        crate::hal_usage_error("Metal devices don't support resource heaps")
+    }
+
     unsafe fn create_texture_view(
         &self,
         texture: &super::Texture,
@@ -75,11 +75,13 @@ impl crate::Api for Api {
     type PipelineCache = PipelineCache;
 
     type AccelerationStructure = AccelerationStructure;
+    type Heap = Heap;
 }
 
 crate::impl_dyn_resource!(
     Adapter,
     AccelerationStructure,
+    Heap,
     BindGroup,
     BindGroupLayout,
     Buffer,
@@ -207,6 +209,7 @@ struct PrivateCapabilities {
     resource_heaps: bool,
     argument_buffers: MTLArgumentBuffersTier,
     shared_textures: bool,
+    memoryless_storage: bool,
     mutable_comparison_samplers: bool,
     sampler_clamp_to_border: bool,
     indirect_draw_dispatch: bool,
@@ -471,6 +474,20 @@ impl crate::Queue for Queue {
         });
         Ok(())
     }
+    unsafe fn wait_for_fence(
+        &self,
+        _fence: &Fence,
+        _value: crate::FenceValue,
+    ) -> Result<(), crate::DeviceError> {
+        crate::hal_usage_error("Metal devices don't expose additional queues")
+    }
+    unsafe fn bind_sparse_memory(
+        &self,
+        _buffer_binds: &[crate::SparseBufferBind<'_, Buffer>],
+        _texture_binds: &[crate::SparseTextureBind<'_, Texture>],
+    ) -> Result<(), crate::DeviceError> {
+        crate::hal_usage_error("Metal devices don't support sparse resources")
+    }
     unsafe fn present(
         &self,
         _surface: &Surface,
@@ -1022,3 +1039,8 @@ impl crate::DynPipelineCache for PipelineCache {}
 pub struct AccelerationStructure;
 
 impl crate::DynAccelerationStructure for AccelerationStructure {}
+
+#[derive(Debug)]
+pub struct Heap;
+
+impl crate::DynHeap for Heap {}
@@ -1,5 +1,5 @@
-use alloc::vec::Vec;
-use core::{cell::UnsafeCell, ops::Range, ptr};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::{cell::UnsafeCell, ops::Range, ptr, sync::atomic::Ordering};
 
 cfg_if::cfg_if! {
     if #[cfg(supports_ptr_atomics)] {
@@ -9,6 +9,11 @@ cfg_if::cfg_if! {
     }
 }
 
+#[cfg(supports_64bit_atomics)]
+use core::sync::atomic::AtomicU64;
+#[cfg(not(supports_64bit_atomics))]
+use portable_atomic::AtomicU64;
+
 #[derive(Clone, Debug)]
 pub struct Buffer {
     /// This data is potentially accessed mutably in arbitrary non-overlapping slices,
@@ -20,6 +25,44 @@ pub struct Buffer {
     /// This is redundant with `storage.get().len()`, but that method is not
     /// available until our MSRV is 1.79 or greater.
     size: usize,
+
+    /// Which bytes of the memory are resident, if it was created with
+    /// [`MemoryFlags::SPARSE`](crate::MemoryFlags::SPARSE).
+    residency: Option<Arc<Residency>>,
+}
+
+/// Tracks which bytes of sparse memory are resident, one bit per byte.
+///
+/// Memory that isn't resident reads as zero, and writes to it are discarded.
+/// Bytes are tracked individually, as a tile of a sparse texture covers many
+/// short rows of its memory.
+#[derive(Debug)]
+struct Residency {
+    bits: Box<[AtomicU64]>,
+}
+
+impl Residency {
+    /// Tracks `size` bytes, none of which are resident.
+    fn new(size: usize) -> Self {
+        Residency {
+            bits: (0..size.div_ceil(64)).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    fn set(&self, range: Range<usize>, resident: bool) {
+        for index in range {
+            let bit = 1 << (index % 64);
+            if resident {
+                self.bits[index / 64].fetch_or(bit, Ordering::Relaxed);
+            } else {
+                self.bits[index / 64].fetch_and(!bit, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn is_resident(&self, index: usize) -> bool {
+        self.bits[index / 64].load(Ordering::Relaxed) & (1 << (index % 64)) != 0
+    }
 }
 
 /// SAFETY:
@@ -35,9 +78,19 @@ impl Buffer {
             label: _,
             size,
             usage: _,
-            memory_flags: _,
+            memory_flags,
         } = desc;
+        Self::zeroed(size, memory_flags)
+    }
 
+    /// Allocates `size` bytes of zeroed memory.
+    ///
+    /// This also backs [`Texture`](super::Texture)s. Sparse memory starts out
+    /// with none of it resident.
+    pub(super) fn zeroed(
+        size: wgt::BufferAddress,
+        memory_flags: crate::MemoryFlags,
+    ) -> Result<Self, crate::DeviceError> {
         let size = usize::try_from(size).map_err(|_| crate::DeviceError::OutOfMemory)?;
 
         let mut vector: Vec<u8> = Vec::new();
@@ -54,7 +107,15 @@ impl Buffer {
         let storage: Arc<UnsafeCell<[u8]>> =
             unsafe { Arc::from_raw(Arc::into_raw(storage) as *mut UnsafeCell<[u8]>) };
 
-        Ok(Buffer { storage, size })
+        let residency = memory_flags
+            .contains(crate::MemoryFlags::SPARSE)
+            .then(|| Arc::new(Residency::new(size)));
+
+        Ok(Buffer {
+            storage,
+            size,
+            residency,
+        })
     }
 
     /// Returns a pointer to the memory owned by this buffer within the given `range`.
@@ -74,6 +135,119 @@ impl Buffer {
             range.len(),
         )
     }
+
+    /// Changes the residency of `range` of sparse memory.
+    pub(super) fn set_resident(&self, range: crate::MemoryRange, resident: bool) {
+        let range = range_to_usize(range, self.size);
+        self.residency
+            .as_ref()
+            .expect("memory is not sparse")
+            .set(range, resident);
+    }
+
+    /// Splits `range` into runs of bytes that are all resident or all not
+    /// resident, returned relative to the start of `range`.
+    fn residency_runs(&self, range: crate::MemoryRange) -> Vec<(Range<usize>, bool)> {
+        let range = range_to_usize(range, self.size);
+        let Some(ref residency) = self.residency else {
+            return vec![(0..range.len(), true)];
+        };
+
+        let mut runs: Vec<(Range<usize>, bool)> = Vec::new();
+        for index in range.clone() {
+            let resident = residency.is_resident(index);
+            match runs.last_mut() {
+                Some((run, run_resident)) if *run_resident == resident => run.end += 1,
+                _ => {
+                    let offset = index - range.start;
+                    runs.push((offset..offset + 1, resident));
+                }
+            }
+        }
+        runs
+    }
+
+    /// Copies `range` of the memory into `dst`, reading zeros from memory that
+    /// isn't resident.
+    ///
+    /// # Safety
+    ///
+    /// Must be called with appropriate synchronization, as for [`Self::get_slice_ptr`].
+    pub(super) unsafe fn read(&self, range: crate::MemoryRange, dst: &mut [u8]) {
+        // SAFETY: Caller is responsible for ensuring this does not alias.
+        let src: &[u8] = unsafe { &*self.get_slice_ptr(range.clone()) };
+        for (run, resident) in self.residency_runs(range) {
+            if resident {
+                dst[run.clone()].copy_from_slice(&src[run]);
+            } else {
+                dst[run].fill(0);
+            }
+        }
+    }
+
+    /// Copies `data` into the memory at `offset`, discarding the writes to
+    /// memory that isn't resident.
+    ///
+    /// # Safety
+    ///
+    /// Must be called with appropriate synchronization, as for [`Self::get_slice_ptr`].
+    pub(super) unsafe fn write(&self, offset: wgt::BufferAddress, data: &[u8]) {
+        let range = offset..offset + data.len() as u64;
+        // SAFETY: Caller is responsible for ensuring this does not alias.
+        let dst: &mut [u8] = unsafe { &mut *self.get_slice_ptr(range.clone()) };
+        for (run, resident) in self.residency_runs(range) {
+            if resident {
+                dst[run.clone()].copy_from_slice(&data[run]);
+            }
+        }
+    }
+
+    /// Fills `range` of the memory with repetitions of `pattern`, discarding
+    /// the writes to memory that isn't resident.
+    ///
+    /// # Safety
+    ///
+    /// Must be called with appropriate synchronization, as for [`Self::get_slice_ptr`].
+    pub(super) unsafe fn fill(&self, range: crate::MemoryRange, pattern: &[u8]) {
+        // SAFETY: Caller is responsible for ensuring this does not alias.
+        let dst: &mut [u8] = unsafe { &mut *self.get_slice_ptr(range.clone()) };
+        for (run, resident) in self.residency_runs(range) {
+            if resident {
+                for (index, byte) in dst[run.clone()].iter_mut().enumerate() {
+                    *byte = pattern[(run.start + index) % pattern.len()];
+                }
+            }
+        }
+    }
+
+    /// Copies `src_range` of the memory of `src` into the memory of `dst` at
+    /// `dst_offset`, as [`Self::read`] and [`Self::write`] do.
+    ///
+    /// # Safety
+    ///
+    /// Must be called with appropriate synchronization, as for [`Self::get_slice_ptr`].
+    pub(super) unsafe fn copy(
+        src: &Buffer,
+        src_range: crate::MemoryRange,
+        dst: &Buffer,
+        dst_offset: wgt::BufferAddress,
+    ) {
+        let size = src_range.end - src_range.start;
+        if src.residency.is_none() && dst.residency.is_none() {
+            // SAFETY: Caller is responsible for ensuring this does not alias.
+            let src_region: &[u8] = unsafe { &*src.get_slice_ptr(src_range) };
+            let dst_region: &mut [u8] =
+                unsafe { &mut *dst.get_slice_ptr(dst_offset..dst_offset + size) };
+            dst_region.copy_from_slice(src_region);
+        } else {
+            let mut data = vec![0; size as usize];
+            // SAFETY: Caller is responsible for ensuring this does not alias.
+            unsafe {
+                src.read(src_range, &mut data);
+                dst.write(dst_offset, &data);
+            }
+        }
+    }
 }
 
 /// Convert a [`crate::MemoryRange`] to `Range<usize>` and bounds check it.
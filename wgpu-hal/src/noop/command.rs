@@ -2,12 +2,14 @@ use alloc::vec::Vec;
 use core::mem;
 use core::ops::Range;
 
-use super::{Api, Buffer, DeviceResult, Resource};
+use super::{texture, Api, Buffer, DeviceResult, QuerySet, Resource, Texture, TextureView};
 
 /// Command buffer type, which performs double duty as the command encoder type too.
 #[derive(Debug)]
 pub struct CommandBuffer {
     commands: Vec<Command>,
+    /// The timestamp to write at the end of the current pass.
+    end_of_pass_timestamp: Option<(QuerySet, u32)>,
 }
 
 #[derive(Debug)]
@@ -21,6 +23,29 @@ enum Command {
         dst: Buffer,
         regions: Vec<crate::BufferCopy>,
     },
+    /// Fill `ranges` of the memory of a render pass attachment's texture with
+    /// repetitions of `pattern`, an encoded texel.
+    ClearAttachment {
+        memory: Buffer,
+        ranges: Vec<crate::MemoryRange>,
+        pattern: Vec<u8>,
+    },
+    /// Write the result of a query at its end, or write a timestamp.
+    EndQuery {
+        set: QuerySet,
+        index: u32,
+    },
+    ResetQueries {
+        set: QuerySet,
+        range: Range<u32>,
+    },
+    CopyQueryResults {
+        set: QuerySet,
+        range: Range<u32>,
+        buffer: Buffer,
+        offset: wgt::BufferAddress,
+        stride: wgt::BufferSize,
+    },
 }
 
 impl CommandBuffer {
@@ -37,8 +62,60 @@ impl CommandBuffer {
     pub(crate) fn new() -> Self {
         Self {
             commands: Vec::new(),
+            end_of_pass_timestamp: None,
+        }
+    }
+}
+
+impl CommandBuffer {
+    fn begin_pass_timestamps(&mut self, writes: Option<&crate::PassTimestampWrites<QuerySet>>) {
+        let Some(writes) = writes else {
+            return;
+        };
+        if let Some(index) = writes.beginning_of_pass_write_index {
+            self.commands.push(Command::EndQuery {
+                set: writes.query_set.clone(),
+                index,
+            });
+        }
+        self.end_of_pass_timestamp = writes
+            .end_of_pass_write_index
+            .map(|index| (writes.query_set.clone(), index));
+    }
+
+    fn end_pass_timestamps(&mut self) {
+        if let Some((set, index)) = self.end_of_pass_timestamp.take() {
+            self.commands.push(Command::EndQuery { set, index });
         }
     }
+
+    fn fill_attachment(
+        &mut self,
+        view: &TextureView,
+        aspect: crate::FormatAspects,
+        depth_slice: Option<u32>,
+        pattern: Vec<u8>,
+    ) {
+        let (memory, ranges) = view.attachment_ranges(aspect, depth_slice);
+        if ranges.is_empty() {
+            return;
+        }
+        self.commands.push(Command::ClearAttachment {
+            memory: memory.clone(),
+            ranges,
+            pattern,
+        });
+    }
+}
+
+/// Returns a copy between two ranges of the same length, unless they are empty.
+fn row_copy(src: crate::MemoryRange, dst: crate::MemoryRange) -> Option<crate::BufferCopy> {
+    debug_assert_eq!(src.end - src.start, dst.end - dst.start);
+    Some(crate::BufferCopy {
+        src_offset: src.start,
+        dst_offset: dst.start,
+        size: wgt::BufferSize::new(src.end - src.start)?,
+    })
 }
 
 impl crate::CommandEncoder for CommandBuffer {
@@ -54,6 +131,7 @@ impl crate::CommandEncoder for CommandBuffer {
     unsafe fn end_encoding(&mut self) -> DeviceResult<CommandBuffer> {
         Ok(CommandBuffer {
             commands: mem::take(&mut self.commands),
+            end_of_pass_timestamp: None,
         })
     }
     unsafe fn reset_all<I>(&mut self, command_buffers: I) {}
@@ -66,10 +144,12 @@ impl crate::CommandEncoder for CommandBuffer {
 
     unsafe fn transition_textures<'a, T>(&mut self, barriers: T)
     where
-        T: Iterator<Item = crate::TextureBarrier<'a, Resource>>,
+        T: Iterator<Item = crate::TextureBarrier<'a, Texture>>,
     {
     }
 
+    unsafe fn aliasing_barrier(&mut self) {}
+
     unsafe fn clear_buffer(&mut self, buffer: &Buffer, range: crate::MemoryRange) {
         self.commands.push(Command::ClearBuffer {
             buffer: buffer.clone(),
@@ -92,7 +172,7 @@ impl crate::CommandEncoder for CommandBuffer {
     unsafe fn copy_external_image_to_texture<T>(
         &mut self,
         src: &wgt::CopyExternalImageSourceInfo,
-        dst: &Resource,
+        dst: &Texture,
         dst_premultiplication: bool,
         regions: T,
     ) where
@@ -102,57 +182,147 @@ impl crate::CommandEncoder for CommandBuffer {
 
     unsafe fn copy_texture_to_texture<T>(
         &mut self,
-        src: &Resource,
+        src: &Texture,
         src_usage: wgt::TextureUses,
-        dst: &Resource,
+        dst: &Texture,
         regions: T,
-    ) {
-        // TODO: consider implementing this and other texture manipulation
+    ) where
+        T: Iterator<Item = crate::TextureCopy>,
+    {
+        let regions = regions
+            .flat_map(|copy| {
+                let src_rows = src.copy_rows(&copy.src_base, &copy.size);
+                let dst_rows = dst.copy_rows(&copy.dst_base, &copy.size);
+                src_rows.zip(dst_rows).collect::<Vec<_>>()
+            })
+            .filter_map(|(src_range, dst_range)| row_copy(src_range, dst_range))
+            .collect();
+        self.commands.push(Command::CopyBufferToBuffer {
+            src: src.memory().clone(),
+            dst: dst.memory().clone(),
+            regions,
+        });
     }
 
-    unsafe fn copy_buffer_to_texture<T>(&mut self, src: &Buffer, dst: &Resource, regions: T) {
-        // TODO: consider implementing this and other texture manipulation
+    unsafe fn copy_buffer_to_texture<T>(&mut self, src: &Buffer, dst: &Texture, regions: T)
+    where
+        T: Iterator<Item = crate::BufferTextureCopy>,
+    {
+        let regions = regions
+            .flat_map(|copy| dst.buffer_copy_rows(&copy).collect::<Vec<_>>())
+            .filter_map(|(buffer_range, texture_range)| row_copy(buffer_range, texture_range))
+            .collect();
+        self.commands.push(Command::CopyBufferToBuffer {
+            src: src.clone(),
+            dst: dst.memory().clone(),
+            regions,
+        });
     }
 
     unsafe fn copy_texture_to_buffer<T>(
         &mut self,
-        src: &Resource,
+        src: &Texture,
         src_usage: wgt::TextureUses,
         dst: &Buffer,
         regions: T,
-    ) {
-        // TODO: consider implementing this and other texture manipulation
+    ) where
+        T: Iterator<Item = crate::BufferTextureCopy>,
+    {
+        let regions = regions
+            .flat_map(|copy| src.buffer_copy_rows(&copy).collect::<Vec<_>>())
+            .filter_map(|(buffer_range, texture_range)| row_copy(texture_range, buffer_range))
+            .collect();
+        self.commands.push(Command::CopyBufferToBuffer {
+            src: src.memory().clone(),
+            dst: dst.clone(),
+            regions,
+        });
     }
 
-    unsafe fn begin_query(&mut self, set: &Resource, index: u32) {}
-    unsafe fn end_query(&mut self, set: &Resource, index: u32) {}
-    unsafe fn write_timestamp(&mut self, set: &Resource, index: u32) {}
+    unsafe fn begin_query(&mut self, set: &QuerySet, index: u32) {}
+    unsafe fn end_query(&mut self, set: &QuerySet, index: u32) {
+        self.commands.push(Command::EndQuery {
+            set: set.clone(),
+            index,
+        });
+    }
+    unsafe fn write_timestamp(&mut self, set: &QuerySet, index: u32) {
+        self.commands.push(Command::EndQuery {
+            set: set.clone(),
+            index,
+        });
+    }
     unsafe fn read_acceleration_structure_compact_size(
         &mut self,
         acceleration_structure: &Resource,
         buf: &Buffer,
     ) {
     }
-    unsafe fn reset_queries(&mut self, set: &Resource, range: Range<u32>) {}
+    unsafe fn reset_queries(&mut self, set: &QuerySet, range: Range<u32>) {
+        self.commands.push(Command::ResetQueries {
+            set: set.clone(),
+            range,
+        });
+    }
     unsafe fn copy_query_results(
         &mut self,
-        set: &Resource,
+        set: &QuerySet,
         range: Range<u32>,
         buffer: &Buffer,
         offset: wgt::BufferAddress,
         stride: wgt::BufferSize,
     ) {
+        self.commands.push(Command::CopyQueryResults {
+            set: set.clone(),
+            range,
+            buffer: buffer.clone(),
+            offset,
+            stride,
+        });
     }
 
     // render
 
     unsafe fn begin_render_pass(
         &mut self,
-        desc: &crate::RenderPassDescriptor<Resource, Resource>,
+        desc: &crate::RenderPassDescriptor<QuerySet, TextureView>,
     ) -> DeviceResult<()> {
+        self.begin_pass_timestamps(desc.timestamp_writes.as_ref());
+        // Nothing is drawn, but attachments that aren't loaded are cleared.
+        for attachment in desc.color_attachments.iter().flatten() {
+            if attachment.ops.contains(crate::AttachmentOps::LOAD) {
+                continue;
+            }
+            let view = attachment.target.view;
+            let Some(pattern) = texture::encode_color(view.format(), attachment.clear_value) else {
+                continue;
+            };
+            self.fill_attachment(
+                view,
+                crate::FormatAspects::COLOR,
+                attachment.depth_slice,
+                pattern,
+            );
+        }
+        if let Some(ref attachment) = desc.depth_stencil_attachment {
+            let view = attachment.target.view;
+            for (aspect, ops) in [
+                (crate::FormatAspects::DEPTH, attachment.depth_ops),
+                (crate::FormatAspects::STENCIL, attachment.stencil_ops),
+            ] {
+                if ops.contains(crate::AttachmentOps::LOAD) {
+                    continue;
+                }
+                let pattern =
+                    texture::encode_depth_stencil(view.format(), aspect, attachment.clear_value);
+                self.fill_attachment(view, aspect, None, pattern);
+            }
+        }
         Ok(())
     }
-    unsafe fn end_render_pass(&mut self) {}
+    unsafe fn end_render_pass(&mut self) {
+        self.end_pass_timestamps();
+    }
 
     unsafe fn set_bind_group(
         &mut self,
@@ -269,8 +439,12 @@ impl crate::CommandEncoder for CommandBuffer {
 
     // compute
 
-    unsafe fn begin_compute_pass(&mut self, desc: &crate::ComputePassDescriptor<Resource>) {}
-    unsafe fn end_compute_pass(&mut self) {}
+    unsafe fn begin_compute_pass(&mut self, desc: &crate::ComputePassDescriptor<QuerySet>) {
+        self.begin_pass_timestamps(desc.timestamp_writes.as_ref());
+    }
+    unsafe fn end_compute_pass(&mut self) {
+        self.end_pass_timestamps();
+    }
 
     unsafe fn set_compute_pipeline(&mut self, pipeline: &Resource) {}
 
@@ -312,8 +486,7 @@ impl Command {
             Command::ClearBuffer { ref buffer, range } => {
                 // SAFETY:
                 // Caller is responsible for ensuring this does not alias.
-                let buffer_slice: &mut [u8] = unsafe { &mut *buffer.get_slice_ptr(range.clone()) };
-                buffer_slice.fill(0);
+                unsafe { buffer.fill(range.clone(), &[0]) };
             }
 
             Command::CopyBufferToBuffer { src, dst, regions } => {
@@ -325,13 +498,35 @@ impl Command {
                 {
                     // SAFETY:
                     // Caller is responsible for ensuring this does not alias.
-                    let src_region: &[u8] =
-                        unsafe { &*src.get_slice_ptr(src_offset..src_offset + size.get()) };
-                    let dst_region: &mut [u8] =
-                        unsafe { &mut *dst.get_slice_ptr(dst_offset..dst_offset + size.get()) };
-                    dst_region.copy_from_slice(src_region);
+                    unsafe {
+                        Buffer::copy(src, src_offset..src_offset + size.get(), dst, dst_offset)
+                    };
                 }
             }
+
+            Command::ClearAttachment {
+                memory,
+                ranges,
+                pattern,
+            } => {
+                for range in ranges {
+                    // SAFETY:
+                    // Caller is responsible for ensuring this does not alias.
+                    unsafe { memory.fill(range.clone(), pattern) };
+                }
+            }
+
+            // SAFETY for the query commands:
+            // Caller is responsible for ensuring these do not alias.
+            Command::EndQuery { set, index } => unsafe { set.end(*index) },
+            Command::ResetQueries { set, range } => unsafe { set.reset(range.clone()) },
+            Command::CopyQueryResults {
+                set,
+                range,
+                buffer,
+                offset,
+                stride,
+            } => unsafe { set.copy_results(range.clone(), buffer, *offset, *stride) },
         }
     }
 }
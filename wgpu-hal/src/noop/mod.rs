@@ -14,10 +14,17 @@ mod buffer;
 pub use buffer::Buffer;
 mod command;
 pub use command::CommandBuffer;
+mod query;
+pub use query::QuerySet;
+mod texture;
+pub use texture::{Texture, TextureView};
 
 #[derive(Clone, Debug)]
 pub struct Api;
-pub struct Context;
+#[derive(Clone)]
+pub struct Context {
+    options: wgt::NoopBackendOptions,
+}
 #[derive(Debug)]
 pub struct Encoder;
 #[derive(Debug)]
@@ -43,11 +50,12 @@ impl crate::Api for Api {
     type CommandBuffer = CommandBuffer;
 
     type Buffer = Buffer;
-    type Texture = Resource;
-    type SurfaceTexture = Resource;
-    type TextureView = Resource;
+    type Texture = Texture;
+    type SurfaceTexture = Texture;
+    type TextureView = TextureView;
     type Sampler = Resource;
-    type QuerySet = Resource;
+    type QuerySet = QuerySet;
+    type Heap = Resource;
     type Fence = Fence;
     type AccelerationStructure = Resource;
     type PipelineCache = Resource;
@@ -60,7 +68,16 @@ impl crate::Api for Api {
     type ComputePipeline = Resource;
 }
 
-crate::impl_dyn_resource!(Buffer, CommandBuffer, Context, Fence, Resource);
+crate::impl_dyn_resource!(
+    Buffer,
+    CommandBuffer,
+    Context,
+    Fence,
+    QuerySet,
+    Resource,
+    Texture,
+    TextureView
+);
 
 impl crate::DynAccelerationStructure for Resource {}
 impl crate::DynBindGroup for Resource {}
@@ -69,17 +86,18 @@ impl crate::DynBuffer for Buffer {}
 impl crate::DynCommandBuffer for CommandBuffer {}
 impl crate::DynComputePipeline for Resource {}
 impl crate::DynFence for Fence {}
+impl crate::DynHeap for Resource {}
 impl crate::DynPipelineCache for Resource {}
 impl crate::DynPipelineLayout for Resource {}
-impl crate::DynQuerySet for Resource {}
+impl crate::DynQuerySet for QuerySet {}
 impl crate::DynRenderPipeline for Resource {}
 impl crate::DynSampler for Resource {}
 impl crate::DynShaderModule for Resource {}
-impl crate::DynSurfaceTexture for Resource {}
-impl crate::DynTexture for Resource {}
-impl crate::DynTextureView for Resource {}
+impl crate::DynSurfaceTexture for Texture {}
+impl crate::DynTexture for Texture {}
+impl crate::DynTextureView for TextureView {}
 
-impl core::borrow::Borrow<dyn crate::DynTexture> for Resource {
+impl core::borrow::Borrow<dyn crate::DynTexture> for Texture {
     fn borrow(&self) -> &dyn crate::DynTexture {
         self
     }
@@ -90,17 +108,15 @@ impl crate::Instance for Context {
 
     unsafe fn init(desc: &crate::InstanceDescriptor) -> Result<Self, crate::InstanceError> {
         let crate::InstanceDescriptor {
-            backend_options:
-                wgt::BackendOptions {
-                    noop: wgt::NoopBackendOptions { enable },
-                    ..
-                },
+            backend_options: wgt::BackendOptions { ref noop, .. },
             name: _,
             flags: _,
             memory_budget_thresholds: _,
         } = *desc;
-        if enable {
-            Ok(Context)
+        if noop.enable {
+            Ok(Context {
+                options: noop.clone(),
+            })
         } else {
             Err(crate::InstanceError::new(String::from(
                 "noop backend disabled because NoopBackendOptions::enable is false",
@@ -112,14 +128,14 @@ impl crate::Instance for Context {
         _display_handle: raw_window_handle::RawDisplayHandle,
         _window_handle: raw_window_handle::RawWindowHandle,
     ) -> Result<Context, crate::InstanceError> {
-        Ok(Context)
+        Ok(self.clone())
     }
     unsafe fn enumerate_adapters(
         &self,
         _surface_hint: Option<&Context>,
     ) -> Vec<crate::ExposedAdapter<Api>> {
         vec![crate::ExposedAdapter {
-            adapter: Context,
+            adapter: self.clone(),
             info: adapter_info(),
             features: wgt::Features::all(),
             capabilities: CAPABILITIES,
@@ -238,7 +254,7 @@ impl crate::Surface for Context {
     ) -> Result<Option<crate::AcquiredSurfaceTexture<Api>>, crate::SurfaceError> {
         Ok(None)
     }
-    unsafe fn discard_texture(&self, texture: Resource) {}
+    unsafe fn discard_texture(&self, texture: Texture) {}
 }
 
 impl crate::Adapter for Context {
@@ -250,9 +266,18 @@ impl crate::Adapter for Context {
         _limits: &wgt::Limits,
         _memory_hints: &wgt::MemoryHints,
     ) -> DeviceResult<crate::OpenDevice<Api>> {
+        let additional_queues = if features.contains(wgt::Features::MULTIPLE_QUEUES) {
+            vec![
+                (wgt::QueueType::Compute, self.clone()),
+                (wgt::QueueType::Transfer, self.clone()),
+            ]
+        } else {
+            Vec::new()
+        };
         Ok(crate::OpenDevice {
-            device: Context,
-            queue: Context,
+            device: self.clone(),
+            queue: self.clone(),
+            additional_queues,
         })
     }
     unsafe fn texture_format_capabilities(
@@ -277,7 +302,7 @@ impl crate::Queue for Context {
     unsafe fn submit(
         &self,
         command_buffers: &[&CommandBuffer],
-        surface_textures: &[&Resource],
+        surface_textures: &[&Texture],
         (fence, fence_value): (&mut Fence, crate::FenceValue),
     ) -> DeviceResult<()> {
         // All commands are executed synchronously.
@@ -291,10 +316,35 @@ impl crate::Queue for Context {
         fence.value.store(fence_value, Ordering::Release);
         Ok(())
     }
+    unsafe fn wait_for_fence(&self, fence: &Fence, value: crate::FenceValue) -> DeviceResult<()> {
+        // Submissions complete synchronously, so the fence has either already
+        // reached `value`, or the caller broke the contract.
+        debug_assert!(fence.value.load(Ordering::Acquire) >= value);
+        Ok(())
+    }
+    unsafe fn bind_sparse_memory(
+        &self,
+        buffer_binds: &[crate::SparseBufferBind<'_, Buffer>],
+        texture_binds: &[crate::SparseTextureBind<'_, Texture>],
+    ) -> DeviceResult<()> {
+        for bind in buffer_binds {
+            bind.buffer.set_resident(bind.range.clone(), bind.resident);
+        }
+        for bind in texture_binds {
+            bind.texture.set_resident(
+                bind.mip_level,
+                bind.array_layer,
+                bind.origin,
+                bind.size,
+                bind.resident,
+            );
+        }
+        Ok(())
+    }
     unsafe fn present(
         &self,
         surface: &Context,
-        texture: Resource,
+        texture: Texture,
     ) -> Result<(), crate::SurfaceError> {
         Ok(())
     }
@@ -331,20 +381,64 @@ impl crate::Device for Context {
     unsafe fn flush_mapped_ranges<I>(&self, buffer: &Buffer, ranges: I) {}
     unsafe fn invalidate_mapped_ranges<I>(&self, buffer: &Buffer, ranges: I) {}
 
-    unsafe fn create_texture(&self, desc: &crate::TextureDescriptor) -> DeviceResult<Resource> {
+    unsafe fn create_texture(&self, desc: &crate::TextureDescriptor) -> DeviceResult<Texture> {
+        Texture::new(desc)
+    }
+    unsafe fn destroy_texture(&self, texture: Texture) {}
+    unsafe fn add_raw_texture(&self, _texture: &Texture) {}
+
+    // Placed resources get memory of their own rather than sharing the
+    // heap's. Their contents are undefined after an aliasing barrier anyway.
+    unsafe fn create_heap(
+        &self,
+        desc: &wgt::HeapDescriptor<crate::Label>,
+    ) -> DeviceResult<Resource> {
         Ok(Resource)
     }
-    unsafe fn destroy_texture(&self, texture: Resource) {}
-    unsafe fn add_raw_texture(&self, _texture: &Resource) {}
+    unsafe fn destroy_heap(&self, heap: Resource) {}
+    unsafe fn get_buffer_memory_requirements(
+        &self,
+        desc: &crate::BufferDescriptor,
+    ) -> DeviceResult<wgt::MemoryRequirements> {
+        Ok(wgt::MemoryRequirements {
+            size: desc.size,
+            alignment: 256,
+        })
+    }
+    unsafe fn get_texture_memory_requirements(
+        &self,
+        desc: &crate::TextureDescriptor,
+    ) -> DeviceResult<wgt::MemoryRequirements> {
+        Ok(wgt::MemoryRequirements {
+            size: Texture::memory_size(desc),
+            alignment: 256,
+        })
+    }
+    unsafe fn create_placed_buffer(
+        &self,
+        desc: &crate::BufferDescriptor,
+        heap: &Resource,
+        offset: wgt::BufferAddress,
+    ) -> DeviceResult<Buffer> {
+        Buffer::new(desc)
+    }
+    unsafe fn create_placed_texture(
+        &self,
+        desc: &crate::TextureDescriptor,
+        heap: &Resource,
+        offset: wgt::BufferAddress,
+    ) -> DeviceResult<Texture> {
+        Texture::new(desc)
+    }
 
     unsafe fn create_texture_view(
         &self,
-        texture: &Resource,
+        texture: &Texture,
         desc: &crate::TextureViewDescriptor,
-    ) -> DeviceResult<Resource> {
-        Ok(Resource)
+    ) -> DeviceResult<TextureView> {
+        Ok(TextureView::new(texture, desc))
     }
-    unsafe fn destroy_texture_view(&self, view: Resource) {}
+    unsafe fn destroy_texture_view(&self, view: TextureView) {}
     unsafe fn create_sampler(&self, desc: &crate::SamplerDescriptor) -> DeviceResult<Resource> {
         Ok(Resource)
     }
@@ -373,7 +467,7 @@ impl crate::Device for Context {
     unsafe fn destroy_pipeline_layout(&self, pipeline_layout: Resource) {}
     unsafe fn create_bind_group(
         &self,
-        desc: &crate::BindGroupDescriptor<Resource, Buffer, Resource, Resource, Resource>,
+        desc: &crate::BindGroupDescriptor<Resource, Buffer, Resource, TextureView, Resource>,
     ) -> DeviceResult<Resource> {
         Ok(Resource)
     }
@@ -412,10 +506,10 @@ impl crate::Device for Context {
     unsafe fn create_query_set(
         &self,
         desc: &wgt::QuerySetDescriptor<crate::Label>,
-    ) -> DeviceResult<Resource> {
-        Ok(Resource)
+    ) -> DeviceResult<QuerySet> {
+        QuerySet::new(desc, self.options.occlusion_query_result)
     }
-    unsafe fn destroy_query_set(&self, set: Resource) {}
+    unsafe fn destroy_query_set(&self, set: QuerySet) {}
     unsafe fn create_fence(&self) -> DeviceResult<Fence> {
         Ok(Fence {
             value: AtomicU64::new(0),
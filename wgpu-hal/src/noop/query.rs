@@ -0,0 +1,110 @@
+use core::ops::Range;
+
+use super::Buffer;
+
+/// A query set, whose results are stored in host memory.
+///
+/// Since the noop backend doesn't draw anything, every occlusion query reports
+/// the same configured number of samples, and every pipeline statistic is zero.
+/// Timestamps are taken from the host's clock when the command that writes
+/// them is executed, in nanoseconds.
+#[derive(Clone, Debug)]
+pub struct QuerySet {
+    memory: Buffer,
+    /// The size of the result of a single query.
+    stride: u64,
+    ty: wgt::QueryType,
+    /// The result of every occlusion query.
+    occlusion_query_result: u64,
+}
+
+impl QuerySet {
+    pub(super) fn new(
+        desc: &wgt::QuerySetDescriptor<crate::Label>,
+        occlusion_query_result: u64,
+    ) -> Result<Self, crate::DeviceError> {
+        let values = match desc.ty {
+            wgt::QueryType::Occlusion | wgt::QueryType::Timestamp => 1,
+            wgt::QueryType::PipelineStatistics(types) => types.bits().count_ones(),
+        };
+        let stride = u64::from(values) * wgt::QUERY_SIZE as u64;
+        Ok(QuerySet {
+            memory: Buffer::zeroed(stride * u64::from(desc.count), crate::MemoryFlags::empty())?,
+            stride,
+            ty: desc.ty,
+            occlusion_query_result,
+        })
+    }
+
+    fn result_range(&self, index: u32) -> crate::MemoryRange {
+        let start = u64::from(index) * self.stride;
+        start..start + self.stride
+    }
+
+    /// Write the result of the query at `index`, at the end of the query.
+    ///
+    /// # Safety
+    ///
+    /// Must be called with appropriate synchronization, as for [`Buffer::get_slice_ptr`].
+    pub(super) unsafe fn end(&self, index: u32) {
+        // SAFETY: Caller is responsible for ensuring this does not alias.
+        let result: &mut [u8] =
+            unsafe { &mut *self.memory.get_slice_ptr(self.result_range(index)) };
+        match self.ty {
+            wgt::QueryType::Occlusion => {
+                result.copy_from_slice(&self.occlusion_query_result.to_le_bytes());
+            }
+            wgt::QueryType::PipelineStatistics(_) => result.fill(0),
+            wgt::QueryType::Timestamp => result.copy_from_slice(&timestamp().to_le_bytes()),
+        }
+    }
+
+    /// Reset the queries in `range` to zero.
+    ///
+    /// # Safety
+    ///
+    /// Must be called with appropriate synchronization, as for [`Buffer::get_slice_ptr`].
+    pub(super) unsafe fn reset(&self, range: Range<u32>) {
+        let range = self.result_range(range.start).start..self.result_range(range.end).start;
+        // SAFETY: Caller is responsible for ensuring this does not alias.
+        unsafe { &mut *self.memory.get_slice_ptr(range) }.fill(0);
+    }
+
+    /// Copy the results of the queries in `range` to `buffer`, starting at `offset` and
+    /// `stride` bytes apart.
+    ///
+    /// # Safety
+    ///
+    /// Must be called with appropriate synchronization, as for [`Buffer::get_slice_ptr`].
+    pub(super) unsafe fn copy_results(
+        &self,
+        range: Range<u32>,
+        buffer: &Buffer,
+        offset: wgt::BufferAddress,
+        stride: wgt::BufferSize,
+    ) {
+        for (i, index) in range.enumerate() {
+            let dst_offset = offset + i as u64 * stride.get();
+            // SAFETY: Caller is responsible for ensuring this does not alias.
+            unsafe { Buffer::copy(&self.memory, self.result_range(index), buffer, dst_offset) };
+        }
+    }
+}
+
+/// Returns the current time in nanoseconds, for timestamp queries.
+///
+/// Without a host clock, each timestamp is one more than the last one, so that
+/// they are still monotonic.
+fn timestamp() -> u64 {
+    #[cfg(std_clock)]
+    {
+        static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        let elapsed = EPOCH.get_or_init(std::time::Instant::now).elapsed();
+        u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX)
+    }
+    #[cfg(not(std_clock))]
+    {
+        static NEXT: super::AtomicU64 = super::AtomicU64::new(0);
+        NEXT.fetch_add(1, core::sync::atomic::Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,393 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::Buffer;
+use crate::FormatAspects;
+
+/// A texture, backed by host memory.
+///
+/// Each aspect of the texture is stored in turn, as a sequence of mip levels,
+/// each of which is a sequence of array layers. A layer is laid out like a
+/// buffer filled by a copy of the whole layer with no padding: each image is a
+/// sequence of tightly packed rows of blocks, and a 3D texture stores one image
+/// per depth slice.
+///
+/// Multisampled textures only store a single sample per texel.
+#[derive(Clone, Debug)]
+pub struct Texture {
+    memory: Buffer,
+    layout: Layout,
+}
+
+/// The parts of a [`Texture`]'s descriptor that determine how it is laid out.
+#[derive(Clone, Copy, Debug)]
+struct Layout {
+    format: wgt::TextureFormat,
+    dimension: wgt::TextureDimension,
+    size: wgt::Extent3d,
+    mip_level_count: u32,
+}
+
+/// How a single mip level of one aspect of a [`Texture`] is stored.
+#[derive(Clone, Copy, Debug)]
+struct Subresource {
+    block_size: u32,
+    block_width: u32,
+    block_height: u32,
+    /// The size in bytes of a row of blocks.
+    bytes_per_row: u64,
+    /// The number of rows of blocks in an image.
+    rows_per_image: u64,
+    /// The number of images in an array layer.
+    depth: u32,
+}
+
+impl Subresource {
+    const fn image_size(&self) -> u64 {
+        self.bytes_per_row * self.rows_per_image
+    }
+
+    const fn layer_size(&self) -> u64 {
+        self.image_size() * self.depth as u64
+    }
+}
+
+impl Layout {
+    fn new(desc: &crate::TextureDescriptor) -> Self {
+        Layout {
+            format: desc.format,
+            dimension: desc.dimension,
+            size: desc.size,
+            mip_level_count: desc.mip_level_count,
+        }
+    }
+
+    const fn array_layer_count(&self) -> u32 {
+        match self.dimension {
+            wgt::TextureDimension::D3 => 1,
+            _ => self.size.depth_or_array_layers,
+        }
+    }
+
+    fn subresource(&self, aspect: FormatAspects, mip_level: u32) -> Subresource {
+        // `Depth24Plus` has no defined copy size. It can't be copied, so any
+        // size will do.
+        let block_size = self.format.block_copy_size(Some(aspect.map())).unwrap_or(4);
+        let (block_width, block_height) = self.format.block_dimensions();
+        // The chroma plane of `NV12` has half the resolution of the texture.
+        let subsampling = if aspect == FormatAspects::PLANE_1 {
+            2
+        } else {
+            1
+        };
+
+        let size = self.size.mip_level_size(mip_level, self.dimension);
+        let width = (size.width / subsampling).max(1);
+        let height = (size.height / subsampling).max(1);
+        Subresource {
+            block_size,
+            block_width,
+            block_height,
+            bytes_per_row: u64::from(width.div_ceil(block_width)) * u64::from(block_size),
+            rows_per_image: height.div_ceil(block_height).into(),
+            depth: match self.dimension {
+                wgt::TextureDimension::D3 => size.depth_or_array_layers,
+                _ => 1,
+            },
+        }
+    }
+
+    fn aspect_size(&self, aspect: FormatAspects) -> u64 {
+        (0..self.mip_level_count)
+            .map(|mip_level| {
+                self.subresource(aspect, mip_level).layer_size()
+                    * u64::from(self.array_layer_count())
+            })
+            .sum()
+    }
+
+    fn size(&self) -> u64 {
+        FormatAspects::from(self.format)
+            .iter()
+            .map(|aspect| self.aspect_size(aspect))
+            .sum()
+    }
+
+    /// Returns the offset of an array layer of a subresource.
+    fn offset(&self, aspect: FormatAspects, mip_level: u32, array_layer: u32) -> u64 {
+        let aspects_before: u64 = FormatAspects::from(self.format)
+            .iter()
+            .take_while(|&other| other != aspect)
+            .map(|other| self.aspect_size(other))
+            .sum();
+        let mips_before: u64 = (0..mip_level)
+            .map(|mip_level| {
+                self.subresource(aspect, mip_level).layer_size()
+                    * u64::from(self.array_layer_count())
+            })
+            .sum();
+        let layers_before =
+            self.subresource(aspect, mip_level).layer_size() * u64::from(array_layer);
+        aspects_before + mips_before + layers_before
+    }
+}
+
+impl Texture {
+    pub(super) fn new(desc: &crate::TextureDescriptor) -> Result<Self, crate::DeviceError> {
+        let layout = Layout::new(desc);
+        Ok(Texture {
+            memory: Buffer::zeroed(layout.size(), desc.memory_flags)?,
+            layout,
+        })
+    }
+
+    /// Returns the number of bytes of memory a texture created with `desc` uses.
+    pub(super) fn memory_size(desc: &crate::TextureDescriptor) -> u64 {
+        Layout::new(desc).size()
+    }
+
+    pub(super) fn memory(&self) -> &Buffer {
+        &self.memory
+    }
+
+    /// Returns the ranges of memory holding the block rows that a copy of
+    /// `size` texels at `base` covers.
+    ///
+    /// The rows are returned in the order a buffer stores them: row by row,
+    /// then image by image. For a texture that isn't 3D, the images of the
+    /// copy are consecutive array layers.
+    pub(super) fn copy_rows<'a>(
+        &'a self,
+        base: &'a crate::TextureCopyBase,
+        size: &crate::CopyExtent,
+    ) -> impl Iterator<Item = crate::MemoryRange> + 'a {
+        let layout = &self.layout;
+        let sub = layout.subresource(base.aspect, base.mip_level);
+        let row_size = u64::from(size.width.div_ceil(sub.block_width)) * u64::from(sub.block_size);
+        let x = u64::from(base.origin.x / sub.block_width) * u64::from(sub.block_size);
+        let y = u64::from(base.origin.y / sub.block_height);
+        let rows = u64::from(size.height.div_ceil(sub.block_height));
+
+        let depth = size.depth;
+        (0..depth).flat_map(move |z| {
+            let image = match layout.dimension {
+                wgt::TextureDimension::D3 => {
+                    layout.offset(base.aspect, base.mip_level, base.array_layer)
+                        + u64::from(base.origin.z + z) * sub.image_size()
+                }
+                _ => layout.offset(base.aspect, base.mip_level, base.array_layer + z),
+            };
+            (0..rows).map(move |row| {
+                let start = image + (y + row) * sub.bytes_per_row + x;
+                start..start + row_size
+            })
+        })
+    }
+
+    /// Changes the residency of a region of one mip level and array layer of
+    /// a sparse texture.
+    pub(super) fn set_resident(
+        &self,
+        mip_level: u32,
+        array_layer: u32,
+        origin: wgt::Origin3d,
+        size: wgt::Extent3d,
+        resident: bool,
+    ) {
+        let base = crate::TextureCopyBase {
+            mip_level,
+            array_layer,
+            origin,
+            aspect: FormatAspects::COLOR,
+        };
+        let extent = crate::CopyExtent {
+            width: size.width,
+            height: size.height,
+            depth: 1,
+        };
+        for range in self.copy_rows(&base, &extent) {
+            self.memory.set_resident(range, resident);
+        }
+    }
+
+    /// Returns pairs of the ranges of buffer and texture memory that `copy`
+    /// moves data between, one pair per row of blocks.
+    pub(super) fn buffer_copy_rows<'a>(
+        &'a self,
+        copy: &'a crate::BufferTextureCopy,
+    ) -> impl Iterator<Item = (crate::MemoryRange, crate::MemoryRange)> + 'a {
+        let sub = self
+            .layout
+            .subresource(copy.texture_base.aspect, copy.texture_base.mip_level);
+        let row_size =
+            u64::from(copy.size.width.div_ceil(sub.block_width)) * u64::from(sub.block_size);
+        let rows = u64::from(copy.size.height.div_ceil(sub.block_height));
+        let bytes_per_row = copy.buffer_layout.bytes_per_row.map_or(row_size, u64::from);
+        let rows_per_image = copy.buffer_layout.rows_per_image.map_or(rows, u64::from);
+
+        let buffer_rows = (0..u64::from(copy.size.depth)).flat_map(move |z| {
+            (0..rows).map(move |row| {
+                let start = copy.buffer_layout.offset + (z * rows_per_image + row) * bytes_per_row;
+                start..start + row_size
+            })
+        });
+        buffer_rows.zip(self.copy_rows(&copy.texture_base, &copy.size))
+    }
+
+    /// Returns the ranges of memory holding the given array layers of a
+    /// subresource, or only one of their depth slices, for a 3D texture.
+    fn layer_ranges(
+        &self,
+        aspect: FormatAspects,
+        mip_level: u32,
+        array_layers: Range<u32>,
+        depth_slice: Option<u32>,
+    ) -> Vec<crate::MemoryRange> {
+        let sub = self.layout.subresource(aspect, mip_level);
+        array_layers
+            .map(|array_layer| {
+                let start = self.layout.offset(aspect, mip_level, array_layer);
+                match depth_slice {
+                    Some(slice) => {
+                        let start = start + u64::from(slice) * sub.image_size();
+                        start..start + sub.image_size()
+                    }
+                    None => start..start + sub.layer_size(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// A view of some subresources of a [`Texture`].
+#[derive(Debug)]
+pub struct TextureView {
+    texture: Texture,
+    format: wgt::TextureFormat,
+    aspects: FormatAspects,
+    mip_level: u32,
+    array_layers: Range<u32>,
+}
+
+impl TextureView {
+    pub(super) fn new(texture: &Texture, desc: &crate::TextureViewDescriptor) -> Self {
+        TextureView {
+            texture: texture.clone(),
+            format: desc.format,
+            aspects: FormatAspects::new(texture.layout.format, desc.range.aspect),
+            mip_level: desc.range.base_mip_level,
+            array_layers: desc.range.layer_range(texture.layout.array_layer_count()),
+        }
+    }
+
+    /// Returns the memory and the ranges of it that clearing this view's
+    /// `aspect` as a render pass attachment overwrites.
+    ///
+    /// Only the first mip level of the view is cleared, as that is the only
+    /// one an attachment may have.
+    pub(super) fn attachment_ranges(
+        &self,
+        aspect: FormatAspects,
+        depth_slice: Option<u32>,
+    ) -> (&Buffer, Vec<crate::MemoryRange>) {
+        let ranges = if self.aspects.contains(aspect) {
+            self.texture.layer_ranges(
+                aspect,
+                self.mip_level,
+                self.array_layers.clone(),
+                depth_slice,
+            )
+        } else {
+            Vec::new()
+        };
+        (&self.texture.memory, ranges)
+    }
+
+    pub(super) const fn format(&self) -> wgt::TextureFormat {
+        self.format
+    }
+}
+
+/// Encodes `color` as a texel of `format`, for clearing a color attachment.
+///
+/// Returns `None` for formats we don't know how to encode `color` in, unless
+/// it is all zeros, which every format encodes as zero bytes.
+pub(super) fn encode_color(format: wgt::TextureFormat, color: wgt::Color) -> Option<Vec<u8>> {
+    use wgt::TextureFormat as Tf;
+
+    let wgt::Color { r, g, b, a } = color;
+    let rgba = [r, g, b, a];
+    let unorm = |v: f64, max: f64| (v.clamp(0.0, 1.0) * max + 0.5) as u64;
+    let snorm = |v: f64, max: f64| {
+        // Round half away from zero; `f64::round` needs `std`.
+        let v = v.clamp(-1.0, 1.0) * max;
+        (v + 0.5f64.copysign(v)) as i64 as u64
+    };
+    // Integer formats use the clear value without conversion, truncated to
+    // the width of the component.
+    let int = |v: f64| v as i64 as u64;
+
+    let (components, width, encode): (usize, usize, &dyn Fn(f64) -> u64) = match format {
+        Tf::R8Unorm => (1, 1, &|v| unorm(v, 255.0)),
+        Tf::Rg8Unorm => (2, 1, &|v| unorm(v, 255.0)),
+        Tf::Rgba8Unorm => (4, 1, &|v| unorm(v, 255.0)),
+        Tf::R8Snorm => (1, 1, &|v| snorm(v, 127.0)),
+        Tf::Rg8Snorm => (2, 1, &|v| snorm(v, 127.0)),
+        Tf::Rgba8Snorm => (4, 1, &|v| snorm(v, 127.0)),
+        Tf::R16Unorm => (1, 2, &|v| unorm(v, 65535.0)),
+        Tf::Rg16Unorm => (2, 2, &|v| unorm(v, 65535.0)),
+        Tf::Rgba16Unorm => (4, 2, &|v| unorm(v, 65535.0)),
+        Tf::R16Snorm => (1, 2, &|v| snorm(v, 32767.0)),
+        Tf::Rg16Snorm => (2, 2, &|v| snorm(v, 32767.0)),
+        Tf::Rgba16Snorm => (4, 2, &|v| snorm(v, 32767.0)),
+        Tf::R8Uint | Tf::R8Sint => (1, 1, &int),
+        Tf::Rg8Uint | Tf::Rg8Sint => (2, 1, &int),
+        Tf::Rgba8Uint | Tf::Rgba8Sint => (4, 1, &int),
+        Tf::R16Uint | Tf::R16Sint => (1, 2, &int),
+        Tf::Rg16Uint | Tf::Rg16Sint => (2, 2, &int),
+        Tf::Rgba16Uint | Tf::Rgba16Sint => (4, 2, &int),
+        Tf::R32Uint | Tf::R32Sint => (1, 4, &int),
+        Tf::Rg32Uint | Tf::Rg32Sint => (2, 4, &int),
+        Tf::Rgba32Uint | Tf::Rgba32Sint => (4, 4, &int),
+        Tf::R32Float => (1, 4, &|v| u64::from((v as f32).to_bits())),
+        Tf::Rg32Float => (2, 4, &|v| u64::from((v as f32).to_bits())),
+        Tf::Rgba32Float => (4, 4, &|v| u64::from((v as f32).to_bits())),
+        Tf::Bgra8Unorm => {
+            let bgra = [b, g, r, a];
+            return Some(bgra.map(|v| unorm(v, 255.0) as u8).to_vec());
+        }
+        _ => {
+            if color != wgt::Color::TRANSPARENT {
+                return None;
+            }
+            let size = format.block_copy_size(None)?;
+            return Some(alloc::vec![0; size as usize]);
+        }
+    };
+
+    Some(
+        rgba[..components]
+            .iter()
+            .flat_map(|&v| encode(v).to_le_bytes().into_iter().take(width))
+            .collect(),
+    )
+}
+
+/// Encodes the clear value of `aspect` of a depth-stencil attachment as a
+/// texel of `format`.
+pub(super) fn encode_depth_stencil(
+    format: wgt::TextureFormat,
+    aspect: FormatAspects,
+    (depth, stencil): (f32, u32),
+) -> Vec<u8> {
+    if aspect == FormatAspects::STENCIL {
+        return alloc::vec![stencil as u8];
+    }
+    match format.aspect_specific_format(wgt::TextureAspect::DepthOnly) {
+        Some(wgt::TextureFormat::Depth16Unorm) => ((depth.clamp(0.0, 1.0) * 65535.0 + 0.5) as u16)
+            .to_le_bytes()
+            .to_vec(),
+        // `Depth24Plus` is stored like `Depth32Float`, as it can't be copied.
+        _ => depth.to_bits().to_le_bytes().to_vec(),
+    }
+}
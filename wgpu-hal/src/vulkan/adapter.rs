@@ -316,7 +316,14 @@ impl PhysicalDeviceFeatures {
                 //.shader_resource_residency(requested_features.contains(wgt::Features::SHADER_RESOURCE_RESIDENCY))
                 .geometry_shader(requested_features.contains(wgt::Features::SHADER_PRIMITIVE_INDEX))
                 .depth_clamp(requested_features.contains(wgt::Features::DEPTH_CLIP_CONTROL))
-                .dual_src_blend(requested_features.contains(wgt::Features::DUAL_SOURCE_BLENDING)),
+                .dual_src_blend(requested_features.contains(wgt::Features::DUAL_SOURCE_BLENDING))
+                .sparse_binding(requested_features.contains(wgt::Features::SPARSE_RESOURCES))
+                .sparse_residency_buffer(
+                    requested_features.contains(wgt::Features::SPARSE_RESOURCES),
+                )
+                .sparse_residency_image2_d(
+                    requested_features.contains(wgt::Features::SPARSE_RESOURCES),
+                ),
             descriptor_indexing: if requested_features.intersects(INDEXING_FEATURES) {
                 Some(
                     vk::PhysicalDeviceDescriptorIndexingFeaturesEXT::default()
@@ -1654,7 +1661,7 @@ impl super::Instance {
             },
             backend: wgt::Backend::Vulkan,
         };
-        let (available_features, downlevel_flags) =
+        let (mut available_features, downlevel_flags) =
             phd_features.to_wgpu(&self.shared.raw, phd, &phd_capabilities);
         let mut workarounds = super::Workarounds::empty();
         {
@@ -1785,6 +1792,30 @@ impl super::Instance {
                 .shader_float16_int8
                 .is_some_and(|features| features.shader_int8 != 0),
         };
+        // Ordering work across queues relies on waiting on timeline semaphores.
+        available_features.set(
+            wgt::Features::MULTIPLE_QUEUES,
+            private_caps.timeline_semaphores
+                && !select_additional_queues(&queue_families).is_empty(),
+        );
+        // Non-resident parts must read as zero, and tiles must have the standard
+        // shapes reported by `TextureFormat::sparse_tile_extent`, as on DX12. With
+        // aligned mip sizes, mip levels made of whole tiles could still be packed
+        // into a mip tail.
+        let sparse_properties = phd_capabilities.properties.sparse_properties;
+        available_features.set(
+            wgt::Features::SPARSE_RESOURCES,
+            phd_features.core.sparse_binding != 0
+                && phd_features.core.sparse_residency_buffer != 0
+                && phd_features.core.sparse_residency_image2_d != 0
+                && sparse_properties.residency_standard2_d_block_shape != 0
+                && sparse_properties.residency_non_resident_strict != 0
+                && sparse_properties.residency_aligned_mip_size == 0
+                && queue_flags.contains(vk::QueueFlags::SPARSE_BINDING),
+        );
+        // Heaps are plain device local allocations. Placing a resource whose
+        // memory type bits exclude the heap's memory type fails at creation.
+        available_features |= wgt::Features::RESOURCE_HEAPS;
         let capabilities = crate::Capabilities {
             limits: phd_capabilities.to_wgpu_limits(),
             alignments: phd_capabilities.to_hal_alignments(private_caps.robust_buffer_access2),
@@ -1798,7 +1829,7 @@ impl super::Instance {
         let adapter = super::Adapter {
             raw: phd,
             instance: Arc::clone(&self.shared),
-            //queue_families,
+            queue_families,
             known_memory_flags: vk::MemoryPropertyFlags::DEVICE_LOCAL
                 | vk::MemoryPropertyFlags::HOST_VISIBLE
                 | vk::MemoryPropertyFlags::HOST_COHERENT
@@ -1896,6 +1927,39 @@ impl super::Adapter {
         memory_hints: &wgt::MemoryHints,
         family_index: u32,
         queue_index: u32,
+    ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
+        unsafe {
+            self.device_from_raw_with_queues(
+                raw_device,
+                drop_callback,
+                enabled_extensions,
+                features,
+                memory_hints,
+                family_index,
+                queue_index,
+                &[],
+            )
+        }
+    }
+
+    /// Like [`Self::device_from_raw`], but also wraps the queues in
+    /// `additional_queues`, given as `(type, family index, queue index)`.
+    ///
+    /// # Safety
+    ///
+    /// - Same as `device_from_raw` plus
+    /// - `raw_device` must be created with all the queues in `additional_queues`.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn device_from_raw_with_queues(
+        &self,
+        raw_device: ash::Device,
+        drop_callback: Option<crate::DropCallback>,
+        enabled_extensions: &[&'static CStr],
+        features: wgt::Features,
+        memory_hints: &wgt::MemoryHints,
+        family_index: u32,
+        queue_index: u32,
+        additional_queues: &[(wgt::QueueType, u32, u32)],
     ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
         let mem_properties = {
             profiling::scope!("vkGetPhysicalDeviceMemoryProperties");
@@ -2156,11 +2220,19 @@ impl super::Adapter {
 
         let drop_guard = crate::DropGuard::from_option(drop_callback);
 
+        let mut queue_family_indices = vec![family_index];
+        for &(_, family, _) in additional_queues {
+            if !queue_family_indices.contains(&family) {
+                queue_family_indices.push(family);
+            }
+        }
+
         let shared = Arc::new(super::DeviceShared {
             raw: raw_device,
             family_index,
             queue_index,
             raw_queue,
+            queue_family_indices,
             drop_guard,
             instance: Arc::clone(&self.instance),
             physical_device: self.raw,
@@ -2188,17 +2260,6 @@ impl super::Adapter {
             texture_view_identity_factory: super::ResourceIdentityFactory::new(),
         });
 
-        let relay_semaphores = super::RelaySemaphores::new(&shared)?;
-
-        let queue = super::Queue {
-            raw: raw_queue,
-            swapchain_fn,
-            device: Arc::clone(&shared),
-            family_index,
-            relay_semaphores: Mutex::new(relay_semaphores),
-            signal_semaphores: Default::default(),
-        };
-
         let mem_allocator = {
             let limits = self.phd_capabilities.properties.limits;
 
@@ -2278,8 +2339,47 @@ impl super::Adapter {
                 buffer_device_address: enabled_extensions
                     .contains(&khr::buffer_device_address::NAME),
             };
-            gpu_alloc::GpuAllocator::new(config, properties)
+            Arc::new(Mutex::new(gpu_alloc::GpuAllocator::new(config, properties)))
+        };
+
+        let additional_queues = additional_queues
+            .iter()
+            .map(|&(ty, family_index, queue_index)| {
+                let raw = {
+                    profiling::scope!("vkGetDeviceQueue");
+                    unsafe { shared.raw.get_device_queue(family_index, queue_index) }
+                };
+                let queue = super::Queue {
+                    raw,
+                    swapchain_fn: swapchain_fn.clone(),
+                    device: Arc::clone(&shared),
+                    family_index,
+                    family_flags: self.queue_families[family_index as usize].queue_flags,
+                    relay_semaphores: Mutex::new(super::RelaySemaphores::new(&shared)?),
+                    signal_semaphores: Default::default(),
+                    wait_semaphores: Default::default(),
+                    mem_allocator: Arc::clone(&mem_allocator),
+                    retired_sparse_memory: Default::default(),
+                };
+                Ok((ty, queue))
+            })
+            .collect::<Result<Vec<_>, crate::DeviceError>>()?;
+
+        let relay_semaphores = super::RelaySemaphores::new(&shared)?;
+
+        let queue = super::Queue {
+            raw: raw_queue,
+            swapchain_fn,
+            device: Arc::clone(&shared),
+            family_index,
+            family_flags: self.queue_families[family_index as usize].queue_flags,
+            relay_semaphores: Mutex::new(relay_semaphores),
+            signal_semaphores: Default::default(),
+            wait_semaphores: Default::default(),
+            mem_allocator: Arc::clone(&mem_allocator),
+            retired_sparse_memory: Default::default(),
         };
+
         let desc_allocator = gpu_descriptor::DescriptorAllocator::new(
             if let Some(di) = self.phd_capabilities.descriptor_indexing {
                 di.max_update_after_bind_descriptors_in_all_pools
@@ -2290,7 +2390,7 @@ impl super::Adapter {
 
         let device = super::Device {
             shared,
-            mem_allocator: Mutex::new(mem_allocator),
+            mem_allocator,
             desc_allocator: Mutex::new(desc_allocator),
             valid_ash_memory_types,
             naga_options,
@@ -2299,7 +2399,11 @@ impl super::Adapter {
             counters: Default::default(),
         };
 
-        Ok(crate::OpenDevice { device, queue })
+        Ok(crate::OpenDevice {
+            device,
+            queue,
+            additional_queues,
+        })
     }
 
     pub fn texture_format_as_raw(&self, texture_format: wgt::TextureFormat) -> vk::Format {
@@ -2320,10 +2424,33 @@ impl super::Adapter {
         let mut enabled_phd_features = self.physical_device_features(&enabled_extensions, features);
 
         let family_index = 0; //TODO
-        let family_info = vk::DeviceQueueCreateInfo::default()
-            .queue_family_index(family_index)
-            .queue_priorities(&[1.0]);
-        let mut family_infos = Vec::from([family_info]);
+        let additional_queues = if features.contains(wgt::Features::MULTIPLE_QUEUES) {
+            select_additional_queues(&self.queue_families)
+        } else {
+            Vec::new()
+        };
+
+        // The number of queues to open in each family. A family never holds
+        // more than the main queue and the two additional ones.
+        let mut family_queue_counts = vec![(family_index, 1)];
+        for &(_, family, index) in &additional_queues {
+            match family_queue_counts
+                .iter_mut()
+                .find(|&&mut (f, _)| f == family)
+            {
+                Some((_, count)) => *count = (*count).max(index + 1),
+                None => family_queue_counts.push((family, index + 1)),
+            }
+        }
+        let priorities = [1.0; 3];
+        let mut family_infos = family_queue_counts
+            .iter()
+            .map(|&(family, count)| {
+                vk::DeviceQueueCreateInfo::default()
+                    .queue_family_index(family)
+                    .queue_priorities(&priorities[..count as usize])
+            })
+            .collect::<Vec<_>>();
 
         let mut pre_info = vk::DeviceCreateInfo::default();
 
@@ -2370,14 +2497,15 @@ impl super::Adapter {
         }
 
         unsafe {
-            self.device_from_raw(
+            self.device_from_raw_with_queues(
                 raw_device,
                 None,
                 &enabled_extensions,
                 features,
                 memory_hints,
-                family_info.queue_family_index,
+                family_index,
                 0,
+                &additional_queues,
             )
         }
     }
@@ -2653,6 +2781,45 @@ impl crate::Adapter for super::Adapter {
     }
 }
 
+/// Picks the queues to open for [`wgt::Features::MULTIPLE_QUEUES`], besides
+/// queue 0 of family 0, as `(type, family index, queue index)`.
+///
+/// Dedicated compute and transfer families are preferred, as they usually map
+/// to separate hardware engines. Otherwise, spare queues of family 0 are used.
+fn select_additional_queues(
+    queue_families: &[vk::QueueFamilyProperties],
+) -> Vec<(wgt::QueueType, u32, u32)> {
+    let find_family = |required: vk::QueueFlags, excluded: vk::QueueFlags| {
+        queue_families
+            .iter()
+            .position(|family| {
+                family.queue_count > 0
+                    && family.queue_flags.contains(required)
+                    && !family.queue_flags.intersects(excluded)
+            })
+            .map(|family| (family as u32, 0))
+    };
+    let mut spare_queues = 1..queue_families
+        .first()
+        .map_or(0, |family| family.queue_count);
+
+    let mut queues = Vec::new();
+    let compute = find_family(vk::QueueFlags::COMPUTE, vk::QueueFlags::GRAPHICS)
+        .or_else(|| spare_queues.next().map(|index| (0, index)));
+    if let Some((family, index)) = compute {
+        queues.push((wgt::QueueType::Compute, family, index));
+    }
+    let transfer = find_family(
+        vk::QueueFlags::TRANSFER,
+        vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE,
+    )
+    .or_else(|| spare_queues.next().map(|index| (0, index)));
+    if let Some((family, index)) = transfer {
+        queues.push((wgt::QueueType::Transfer, family, index));
+    }
+    queues
+}
+
 fn is_format_16bit_norm_supported(instance: &ash::Instance, phd: vk::PhysicalDevice) -> bool {
     let tiling = vk::ImageTiling::OPTIMAL;
     let features = vk::FormatFeatureFlags::SAMPLED_IMAGE
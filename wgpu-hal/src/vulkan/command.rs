@@ -198,13 +198,20 @@ impl crate::CommandEncoder for super::CommandEncoder {
         //Note: this is done so that we never end up with empty stage flags
         let mut src_stages = vk::PipelineStageFlags::TOP_OF_PIPE;
         let mut dst_stages = vk::PipelineStageFlags::BOTTOM_OF_PIPE;
+        let barrier_mask = self.barrier_mask;
         let vk_barriers = &mut self.temp.buffer_barriers;
         vk_barriers.clear();
 
         for bar in barriers {
-            let (src_stage, src_access) = conv::map_buffer_usage_to_barrier(bar.usage.from);
+            let (src_stage, src_access) = conv::apply_barrier_mask(
+                barrier_mask,
+                conv::map_buffer_usage_to_barrier(bar.usage.from),
+            );
             src_stages |= src_stage;
-            let (dst_stage, dst_access) = conv::map_buffer_usage_to_barrier(bar.usage.to);
+            let (dst_stage, dst_access) = conv::apply_barrier_mask(
+                barrier_mask,
+                conv::map_buffer_usage_to_barrier(bar.usage.to),
+            );
             dst_stages |= dst_stage;
 
             vk_barriers.push(
@@ -237,6 +244,7 @@ impl crate::CommandEncoder for super::CommandEncoder {
     {
         let mut src_stages = vk::PipelineStageFlags::empty();
         let mut dst_stages = vk::PipelineStageFlags::empty();
+        let barrier_mask = self.barrier_mask;
         let vk_barriers = &mut self.temp.image_barriers;
         vk_barriers.clear();
 
@@ -246,10 +254,16 @@ impl crate::CommandEncoder for super::CommandEncoder {
                 bar.texture.format,
                 &self.device.private_caps,
             );
-            let (src_stage, src_access) = conv::map_texture_usage_to_barrier(bar.usage.from);
+            let (src_stage, src_access) = conv::apply_barrier_mask(
+                barrier_mask,
+                conv::map_texture_usage_to_barrier(bar.usage.from),
+            );
             let src_layout = conv::derive_image_layout(bar.usage.from, bar.texture.format);
             src_stages |= src_stage;
-            let (dst_stage, dst_access) = conv::map_texture_usage_to_barrier(bar.usage.to);
+            let (dst_stage, dst_access) = conv::apply_barrier_mask(
+                barrier_mask,
+                conv::map_texture_usage_to_barrier(bar.usage.to),
+            );
             let dst_layout = conv::derive_image_layout(bar.usage.to, bar.texture.format);
             dst_stages |= dst_stage;
 
@@ -265,6 +279,13 @@ impl crate::CommandEncoder for super::CommandEncoder {
         }
 
         if !vk_barriers.is_empty() {
+            // Masking may have removed every stage a usage maps to.
+            if src_stages.is_empty() {
+                src_stages = vk::PipelineStageFlags::TOP_OF_PIPE;
+            }
+            if dst_stages.is_empty() {
+                dst_stages = vk::PipelineStageFlags::BOTTOM_OF_PIPE;
+            }
             unsafe {
                 self.device.raw.cmd_pipeline_barrier(
                     self.active,
@@ -279,6 +300,23 @@ impl crate::CommandEncoder for super::CommandEncoder {
         }
     }
 
+    unsafe fn aliasing_barrier(&mut self) {
+        let vk_barrier = vk::MemoryBarrier::default()
+            .src_access_mask(vk::AccessFlags::MEMORY_WRITE)
+            .dst_access_mask(vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE);
+        unsafe {
+            self.device.raw.cmd_pipeline_barrier(
+                self.active,
+                vk::PipelineStageFlags::ALL_COMMANDS,
+                vk::PipelineStageFlags::ALL_COMMANDS,
+                vk::DependencyFlags::empty(),
+                &[vk_barrier],
+                &[],
+                &[],
+            )
+        };
+    }
+
     unsafe fn clear_buffer(&mut self, buffer: &super::Buffer, range: crate::MemoryRange) {
         let range_size = range.end - range.start;
         if self.device.workarounds.contains(
@@ -749,13 +787,19 @@ impl crate::CommandEncoder for super::CommandEncoder {
         &mut self,
         barrier: crate::AccelerationStructureBarrier,
     ) {
-        let (src_stage, src_access) = conv::map_acceleration_structure_usage_to_barrier(
-            barrier.usage.from,
-            self.device.features,
+        let (src_stage, src_access) = conv::apply_barrier_mask(
+            self.barrier_mask,
+            conv::map_acceleration_structure_usage_to_barrier(
+                barrier.usage.from,
+                self.device.features,
+            ),
         );
-        let (dst_stage, dst_access) = conv::map_acceleration_structure_usage_to_barrier(
-            barrier.usage.to,
-            self.device.features,
+        let (dst_stage, dst_access) = conv::apply_barrier_mask(
+            self.barrier_mask,
+            conv::map_acceleration_structure_usage_to_barrier(
+                barrier.usage.to,
+                self.device.features,
+            ),
         );
 
         unsafe {
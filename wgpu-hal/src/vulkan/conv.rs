@@ -258,6 +258,9 @@ pub fn map_texture_usage(usage: wgt::TextureUses) -> vk::ImageUsageFlags {
     ) {
         flags |= vk::ImageUsageFlags::STORAGE;
     }
+    if usage.contains(wgt::TextureUses::TRANSIENT) {
+        flags |= vk::ImageUsageFlags::TRANSIENT_ATTACHMENT;
+    }
     flags
 }
 
@@ -1021,3 +1024,52 @@ pub fn map_acceleration_structure_usage_to_barrier(
 
     (stages, access)
 }
+
+/// Returns the pipeline stages and access types that barriers recorded for a
+/// queue of a family with the given `flags` may use, or `None` if the family
+/// supports graphics, and with it every stage.
+pub fn map_queue_flags_to_barrier_mask(
+    flags: vk::QueueFlags,
+) -> Option<(vk::PipelineStageFlags, vk::AccessFlags)> {
+    if flags.contains(vk::QueueFlags::GRAPHICS) {
+        return None;
+    }
+
+    let mut stages = vk::PipelineStageFlags::TOP_OF_PIPE
+        | vk::PipelineStageFlags::BOTTOM_OF_PIPE
+        | vk::PipelineStageFlags::TRANSFER
+        | vk::PipelineStageFlags::HOST
+        | vk::PipelineStageFlags::ALL_COMMANDS;
+    let mut access = vk::AccessFlags::TRANSFER_READ
+        | vk::AccessFlags::TRANSFER_WRITE
+        | vk::AccessFlags::HOST_READ
+        | vk::AccessFlags::HOST_WRITE
+        | vk::AccessFlags::MEMORY_READ
+        | vk::AccessFlags::MEMORY_WRITE;
+
+    if flags.contains(vk::QueueFlags::COMPUTE) {
+        stages |= vk::PipelineStageFlags::DRAW_INDIRECT
+            | vk::PipelineStageFlags::COMPUTE_SHADER
+            | vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR;
+        access |= vk::AccessFlags::INDIRECT_COMMAND_READ
+            | vk::AccessFlags::UNIFORM_READ
+            | vk::AccessFlags::SHADER_READ
+            | vk::AccessFlags::SHADER_WRITE
+            | vk::AccessFlags::ACCELERATION_STRUCTURE_READ_KHR
+            | vk::AccessFlags::ACCELERATION_STRUCTURE_WRITE_KHR;
+    }
+
+    Some((stages, access))
+}
+
+/// Drop the stages and access types from a barrier that aren't in `mask`, as
+/// returned by [`map_queue_flags_to_barrier_mask`].
+pub fn apply_barrier_mask(
+    mask: Option<(vk::PipelineStageFlags, vk::AccessFlags)>,
+    (stages, access): (vk::PipelineStageFlags, vk::AccessFlags),
+) -> (vk::PipelineStageFlags, vk::AccessFlags) {
+    match mask {
+        Some((stage_mask, access_mask)) => (stages & stage_mask, access & access_mask),
+        None => (stages, access),
+    }
+}
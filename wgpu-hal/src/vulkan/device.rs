@@ -1,5 +1,6 @@
 use alloc::{
     borrow::{Cow, ToOwned as _},
+    boxed::Box,
     collections::BTreeMap,
     ffi::CString,
     sync::Arc,
@@ -638,13 +639,13 @@ impl super::Device {
             drop_guard,
             external_memory: None,
             block: None,
+            sparse: None,
             format: desc.format,
             copy_size: desc.copy_extent(),
             identity,
         }
     }
 
-    #[cfg(windows)]
     fn find_memory_type_index(
         &self,
         type_bits_req: u32,
@@ -686,6 +687,10 @@ impl super::Device {
         if desc.is_cube_compatible() {
             raw_flags |= vk::ImageCreateFlags::CUBE_COMPATIBLE;
         }
+        if desc.memory_flags.contains(crate::MemoryFlags::SPARSE) {
+            raw_flags |=
+                vk::ImageCreateFlags::SPARSE_BINDING | vk::ImageCreateFlags::SPARSE_RESIDENCY;
+        }
 
         let original_format = self.shared.private_caps.map_texture_format(desc.format);
         let mut vk_view_formats = vec![];
@@ -717,6 +722,13 @@ impl super::Device {
             .usage(conv::map_texture_usage(desc.usage))
             .sharing_mode(vk::SharingMode::EXCLUSIVE)
             .initial_layout(vk::ImageLayout::UNDEFINED);
+        // With queues from several families, let them all use the image
+        // without explicit ownership transfers.
+        if self.shared.queue_family_indices.len() > 1 {
+            vk_info = vk_info
+                .sharing_mode(vk::SharingMode::CONCURRENT)
+                .queue_family_indices(&self.shared.queue_family_indices);
+        }
 
         let mut format_list_info = vk::ImageFormatListCreateInfo::default();
         if !vk_view_formats.is_empty() {
@@ -743,6 +755,62 @@ impl super::Device {
         })
     }
 
+    fn create_buffer_without_memory(
+        &self,
+        desc: &crate::BufferDescriptor,
+    ) -> Result<(vk::Buffer, vk::MemoryRequirements), crate::DeviceError> {
+        let mut vk_info = vk::BufferCreateInfo::default()
+            .size(desc.size)
+            .usage(conv::map_buffer_usage(desc.usage))
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+        if self.shared.queue_family_indices.len() > 1 {
+            vk_info = vk_info
+                .sharing_mode(vk::SharingMode::CONCURRENT)
+                .queue_family_indices(&self.shared.queue_family_indices);
+        }
+
+        if desc.memory_flags.contains(crate::MemoryFlags::SPARSE) {
+            vk_info = vk_info.flags(
+                vk::BufferCreateFlags::SPARSE_BINDING | vk::BufferCreateFlags::SPARSE_RESIDENCY,
+            );
+        }
+
+        let raw = unsafe {
+            self.shared
+                .raw
+                .create_buffer(&vk_info, None)
+                .map_err(super::map_host_device_oom_and_ioca_err)?
+        };
+        let req = unsafe { self.shared.raw.get_buffer_memory_requirements(raw) };
+
+        Ok((raw, req))
+    }
+
+    /// Checks that a resource with requirements `req` can be bound to `heap`
+    /// at `offset`, returning the offset in the heap's device memory.
+    fn placed_memory_offset(
+        heap: &super::Heap,
+        offset: wgt::BufferAddress,
+        req: &vk::MemoryRequirements,
+    ) -> Result<u64, crate::DeviceError> {
+        if req.memory_type_bits & (1 << heap.block.memory_type()) == 0 {
+            log::error!(
+                "Memory type {} of the heap can't back a resource with memory type bits {:#b}",
+                heap.block.memory_type(),
+                req.memory_type_bits
+            );
+            return Err(crate::DeviceError::Unexpected);
+        }
+        if req.alignment > super::HEAP_ALIGNMENT {
+            log::error!(
+                "Resource alignment {} is too large for heaps",
+                req.alignment
+            );
+            return Err(crate::DeviceError::Unexpected);
+        }
+        Ok(heap.block.offset() + offset)
+    }
+
     /// # Safety
     ///
     /// - Vulkan (with VK_KHR_external_memory_win32)
@@ -813,6 +881,140 @@ impl super::Device {
             drop_guard: None,
             external_memory: Some(memory),
             block: None,
+            sparse: None,
+            format: desc.format,
+            copy_size: image.copy_size,
+            identity,
+        })
+    }
+
+    /// Bind a dedicated allocation of lazily allocated memory to a transient
+    /// attachment, which tilers only commit if the image leaves tile memory.
+    ///
+    /// gpu-alloc doesn't allocate from lazily allocated memory types.
+    ///
+    /// # Safety
+    ///
+    /// - `mem_type_index` must be a lazily allocated memory type allowed by
+    ///   `image.requirements`.
+    unsafe fn create_lazily_allocated_texture(
+        &self,
+        desc: &crate::TextureDescriptor,
+        image: ImageWithoutMemory,
+        mem_type_index: usize,
+    ) -> Result<super::Texture, crate::DeviceError> {
+        let memory_allocate_info = vk::MemoryAllocateInfo::default()
+            .allocation_size(image.requirements.size)
+            .memory_type_index(mem_type_index as _);
+        let memory = unsafe { self.shared.raw.allocate_memory(&memory_allocate_info, None) }
+            .map_err(super::map_host_device_oom_err)
+            .inspect_err(|_| {
+                unsafe { self.shared.raw.destroy_image(image.raw, None) };
+            })?;
+
+        unsafe { self.shared.raw.bind_image_memory(image.raw, memory, 0) }
+            .map_err(super::map_host_device_oom_err)
+            .inspect_err(|_| {
+                unsafe { self.shared.raw.destroy_image(image.raw, None) };
+                unsafe { self.shared.raw.free_memory(memory, None) };
+            })?;
+
+        if let Some(label) = desc.label {
+            unsafe { self.shared.set_object_name(image.raw, label) };
+        }
+
+        let identity = self.shared.texture_identity_factory.next();
+
+        self.counters.textures.add(1);
+
+        Ok(super::Texture {
+            raw: image.raw,
+            drop_guard: None,
+            external_memory: Some(memory),
+            block: None,
+            sparse: None,
+            format: desc.format,
+            copy_size: image.copy_size,
+            identity,
+        })
+    }
+
+    /// Finish creating a sparse texture, whose tiles are bound by
+    /// [`crate::Queue::bind_sparse_memory`].
+    ///
+    /// # Safety
+    ///
+    /// - `image` must have been created with the sparse binding and residency
+    ///   flags.
+    unsafe fn create_sparse_texture(
+        &self,
+        desc: &crate::TextureDescriptor,
+        image: ImageWithoutMemory,
+    ) -> Result<super::Texture, crate::DeviceError> {
+        let tile = desc.format.sparse_tile_extent();
+        let sparse_requirements = unsafe {
+            self.shared
+                .raw
+                .get_image_sparse_memory_requirements(image.raw)
+        };
+
+        let mut supported = wgt::SPARSE_PAGE_SIZE % image.requirements.alignment == 0;
+        let mut metadata_ranges = Vec::new();
+        for req in sparse_requirements.iter() {
+            let properties = req.format_properties;
+            if properties
+                .aspect_mask
+                .contains(vk::ImageAspectFlags::METADATA)
+            {
+                let tail_count = if properties
+                    .flags
+                    .contains(vk::SparseImageFormatFlags::SINGLE_MIPTAIL)
+                {
+                    1
+                } else {
+                    desc.array_layer_count() as u64
+                };
+                metadata_ranges.extend((0..tail_count).map(|layer| {
+                    let start = req.image_mip_tail_offset + layer * req.image_mip_tail_stride;
+                    start..start + req.image_mip_tail_size
+                }));
+            } else {
+                // Every mip level is made of whole tiles of the standard shape,
+                // so nothing may be packed into a mip tail.
+                supported &= req.image_mip_tail_first_lod >= desc.mip_level_count
+                    && !properties
+                        .flags
+                        .contains(vk::SparseImageFormatFlags::NONSTANDARD_BLOCK_SIZE)
+                    && tile.is_some_and(|tile| {
+                        properties.image_granularity.width == tile.width
+                            && properties.image_granularity.height == tile.height
+                    });
+            }
+        }
+        if !supported {
+            log::error!("Unsupported sparse texture layout: {sparse_requirements:?}");
+            unsafe { self.shared.raw.destroy_image(image.raw, None) };
+            return Err(crate::DeviceError::Unexpected);
+        }
+
+        if let Some(label) = desc.label {
+            unsafe { self.shared.set_object_name(image.raw, label) };
+        }
+
+        let identity = self.shared.texture_identity_factory.next();
+
+        self.counters.textures.add(1);
+
+        Ok(super::Texture {
+            raw: image.raw,
+            drop_guard: None,
+            external_memory: None,
+            block: None,
+            sparse: Some(Box::new(super::SparseTexture {
+                memory: super::SparseMemory::new(image.requirements, self.valid_ash_memory_types),
+                metadata_ranges,
+                metadata: Mutex::new(Vec::new()),
+            })),
             format: desc.format,
             copy_size: image.copy_size,
             identity,
@@ -1074,18 +1276,31 @@ impl crate::Device for super::Device {
         &self,
         desc: &crate::BufferDescriptor,
     ) -> Result<super::Buffer, crate::DeviceError> {
-        let vk_info = vk::BufferCreateInfo::default()
-            .size(desc.size)
-            .usage(conv::map_buffer_usage(desc.usage))
-            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+        let (raw, req) = self.create_buffer_without_memory(desc)?;
 
-        let raw = unsafe {
-            self.shared
-                .raw
-                .create_buffer(&vk_info, None)
-                .map_err(super::map_host_device_oom_and_ioca_err)?
-        };
-        let req = unsafe { self.shared.raw.get_buffer_memory_requirements(raw) };
+        if desc.memory_flags.contains(crate::MemoryFlags::SPARSE) {
+            // Pages are bound in units of `SPARSE_PAGE_SIZE`.
+            if wgt::SPARSE_PAGE_SIZE % req.alignment != 0 {
+                log::error!("Sparse buffer pages are {} bytes", req.alignment);
+                unsafe { self.shared.raw.destroy_buffer(raw, None) };
+                return Err(crate::DeviceError::Unexpected);
+            }
+
+            if let Some(label) = desc.label {
+                unsafe { self.shared.set_object_name(raw, label) };
+            }
+
+            self.counters.buffers.add(1);
+
+            return Ok(super::Buffer {
+                raw,
+                block: None,
+                sparse: Some(Box::new(super::SparseMemory::new(
+                    req,
+                    self.valid_ash_memory_types,
+                ))),
+            });
+        }
 
         let mut alloc_usage = if desc
             .usage
@@ -1154,6 +1369,7 @@ impl crate::Device for super::Device {
         Ok(super::Buffer {
             raw,
             block: Some(Mutex::new(super::BufferMemoryBacking::Managed(block))),
+            sparse: None,
         })
     }
     unsafe fn destroy_buffer(&self, buffer: super::Buffer) {
@@ -1170,6 +1386,9 @@ impl crate::Device for super::Device {
                 },
             }
         }
+        if let Some(sparse) = buffer.sparse {
+            unsafe { sparse.free(&mut self.mem_allocator.lock(), &self.shared) };
+        }
 
         self.counters.buffers.sub(1);
     }
@@ -1249,6 +1468,21 @@ impl crate::Device for super::Device {
     ) -> Result<super::Texture, crate::DeviceError> {
         let image = self.create_image_without_memory(desc, None)?;
 
+        if desc.memory_flags.contains(crate::MemoryFlags::SPARSE) {
+            return unsafe { self.create_sparse_texture(desc, image) };
+        }
+
+        if desc.usage.contains(wgt::TextureUses::TRANSIENT) {
+            if let Some(mem_type_index) = self.find_memory_type_index(
+                image.requirements.memory_type_bits,
+                vk::MemoryPropertyFlags::LAZILY_ALLOCATED,
+            ) {
+                return unsafe {
+                    self.create_lazily_allocated_texture(desc, image, mem_type_index)
+                };
+            }
+        }
+
         self.error_if_would_oom_on_resource_allocation(false, image.requirements.size)
             .inspect_err(|_| {
                 unsafe { self.shared.raw.destroy_image(image.raw, None) };
@@ -1294,6 +1528,7 @@ impl crate::Device for super::Device {
             drop_guard: None,
             external_memory: None,
             block: Some(block),
+            sparse: None,
             format: desc.format,
             copy_size: image.copy_size,
             identity,
@@ -1311,6 +1546,13 @@ impl crate::Device for super::Device {
 
             unsafe { self.mem_allocator.lock().dealloc(&*self.shared, block) };
         }
+        if let Some(sparse) = texture.sparse {
+            let mut allocator = self.mem_allocator.lock();
+            for block in sparse.metadata.into_inner() {
+                unsafe { allocator.dealloc(&*self.shared, block) };
+            }
+            unsafe { sparse.memory.free(&mut allocator, &self.shared) };
+        }
 
         self.counters.textures.sub(1);
     }
@@ -1319,6 +1561,129 @@ impl crate::Device for super::Device {
         self.counters.textures.add(1);
     }
 
+    unsafe fn create_heap(
+        &self,
+        desc: &wgt::HeapDescriptor<crate::Label>,
+    ) -> Result<super::Heap, crate::DeviceError> {
+        self.error_if_would_oom_on_resource_allocation(false, desc.size)?;
+
+        let block = unsafe {
+            self.mem_allocator.lock().alloc(
+                &*self.shared,
+                gpu_alloc::Request {
+                    size: desc.size,
+                    align_mask: super::HEAP_ALIGNMENT - 1,
+                    usage: gpu_alloc::UsageFlags::FAST_DEVICE_ACCESS,
+                    memory_types: self.valid_ash_memory_types,
+                },
+            )
+        }?;
+
+        Ok(super::Heap { block })
+    }
+    unsafe fn destroy_heap(&self, heap: super::Heap) {
+        unsafe { self.mem_allocator.lock().dealloc(&*self.shared, heap.block) };
+    }
+
+    unsafe fn get_buffer_memory_requirements(
+        &self,
+        desc: &crate::BufferDescriptor,
+    ) -> Result<wgt::MemoryRequirements, crate::DeviceError> {
+        let (raw, req) = self.create_buffer_without_memory(desc)?;
+        unsafe { self.shared.raw.destroy_buffer(raw, None) };
+        Ok(wgt::MemoryRequirements {
+            size: req.size,
+            alignment: req.alignment,
+        })
+    }
+    unsafe fn get_texture_memory_requirements(
+        &self,
+        desc: &crate::TextureDescriptor,
+    ) -> Result<wgt::MemoryRequirements, crate::DeviceError> {
+        let image = self.create_image_without_memory(desc, None)?;
+        unsafe { self.shared.raw.destroy_image(image.raw, None) };
+        Ok(wgt::MemoryRequirements {
+            size: image.requirements.size,
+            alignment: image.requirements.alignment,
+        })
+    }
+
+    unsafe fn create_placed_buffer(
+        &self,
+        desc: &crate::BufferDescriptor,
+        heap: &super::Heap,
+        offset: wgt::BufferAddress,
+    ) -> Result<super::Buffer, crate::DeviceError> {
+        let (raw, req) = self.create_buffer_without_memory(desc)?;
+
+        Self::placed_memory_offset(heap, offset, &req)
+            .and_then(|memory_offset| {
+                unsafe {
+                    self.shared
+                        .raw
+                        .bind_buffer_memory(raw, *heap.block.memory(), memory_offset)
+                }
+                .map_err(super::map_host_device_oom_and_ioca_err)
+            })
+            .inspect_err(|_| {
+                unsafe { self.shared.raw.destroy_buffer(raw, None) };
+            })?;
+
+        if let Some(label) = desc.label {
+            unsafe { self.shared.set_object_name(raw, label) };
+        }
+
+        self.counters.buffers.add(1);
+
+        Ok(super::Buffer {
+            raw,
+            block: None,
+            sparse: None,
+        })
+    }
+    unsafe fn create_placed_texture(
+        &self,
+        desc: &crate::TextureDescriptor,
+        heap: &super::Heap,
+        offset: wgt::BufferAddress,
+    ) -> Result<super::Texture, crate::DeviceError> {
+        let image = self.create_image_without_memory(desc, None)?;
+
+        Self::placed_memory_offset(heap, offset, &image.requirements)
+            .and_then(|memory_offset| {
+                unsafe {
+                    self.shared.raw.bind_image_memory(
+                        image.raw,
+                        *heap.block.memory(),
+                        memory_offset,
+                    )
+                }
+                .map_err(super::map_host_device_oom_err)
+            })
+            .inspect_err(|_| {
+                unsafe { self.shared.raw.destroy_image(image.raw, None) };
+            })?;
+
+        if let Some(label) = desc.label {
+            unsafe { self.shared.set_object_name(image.raw, label) };
+        }
+
+        let identity = self.shared.texture_identity_factory.next();
+
+        self.counters.textures.add(1);
+
+        Ok(super::Texture {
+            raw: image.raw,
+            drop_guard: None,
+            external_memory: None,
+            block: None,
+            sparse: None,
+            format: desc.format,
+            copy_size: image.copy_size,
+            identity,
+        })
+    }
+
     unsafe fn create_texture_view(
         &self,
         texture: &super::Texture,
@@ -1455,6 +1820,7 @@ impl crate::Device for super::Device {
             device: Arc::clone(&self.shared),
             active: vk::CommandBuffer::null(),
             bind_point: vk::PipelineBindPoint::default(),
+            barrier_mask: conv::map_queue_flags_to_barrier_mask(desc.queue.family_flags),
             temp: super::Temp::default(),
             free: Vec::new(),
             discarded: Vec::new(),
@@ -1159,6 +1159,7 @@ impl crate::Surface for super::Surface {
                 drop_guard: None,
                 block: None,
                 external_memory: None,
+                sparse: None,
                 format: swapchain.config.format,
                 copy_size: crate::CopyExtent {
                     width: swapchain.config.extent.width,
@@ -73,6 +73,7 @@ impl crate::Api for Api {
     type TextureView = TextureView;
     type Sampler = Sampler;
     type QuerySet = QuerySet;
+    type Heap = Heap;
     type Fence = Fence;
     type AccelerationStructure = AccelerationStructure;
     type PipelineCache = PipelineCache;
@@ -100,6 +101,7 @@ crate::impl_dyn_resource!(
     PipelineCache,
     PipelineLayout,
     QuerySet,
+    Heap,
     Queue,
     RenderPipeline,
     Sampler,
@@ -533,7 +535,7 @@ impl Borrow<dyn crate::DynTexture> for SurfaceTexture {
 pub struct Adapter {
     raw: vk::PhysicalDevice,
     instance: Arc<InstanceShared>,
-    //queue_families: Vec<vk::QueueFamilyProperties>,
+    queue_families: Vec<vk::QueueFamilyProperties>,
     known_memory_flags: vk::MemoryPropertyFlags,
     phd_capabilities: adapter::PhysicalDeviceProperties,
     phd_features: PhysicalDeviceFeatures,
@@ -711,6 +713,11 @@ struct DeviceShared {
     family_index: u32,
     queue_index: u32,
     raw_queue: vk::Queue,
+    /// The distinct queue families of all queues opened on this device.
+    ///
+    /// When this has more than one entry, buffers and textures are created
+    /// with [`vk::SharingMode::CONCURRENT`] across these families.
+    queue_family_indices: Vec<u32>,
     drop_guard: Option<crate::DropGuard>,
     instance: Arc<InstanceShared>,
     physical_device: vk::PhysicalDevice,
@@ -748,7 +755,7 @@ impl Drop for DeviceShared {
 
 pub struct Device {
     shared: Arc<DeviceShared>,
-    mem_allocator: Mutex<gpu_alloc::GpuAllocator<vk::DeviceMemory>>,
+    mem_allocator: Arc<Mutex<gpu_alloc::GpuAllocator<vk::DeviceMemory>>>,
     desc_allocator:
         Mutex<gpu_descriptor::DescriptorAllocator<vk::DescriptorPool, vk::DescriptorSet>>,
     valid_ash_memory_types: u32,
@@ -845,19 +852,205 @@ pub struct Queue {
     swapchain_fn: khr::swapchain::Device,
     device: Arc<DeviceShared>,
     family_index: u32,
+    /// The capabilities of the queue family this queue belongs to.
+    family_flags: vk::QueueFlags,
     relay_semaphores: Mutex<RelaySemaphores>,
     signal_semaphores: Mutex<SemaphoreList>,
+    /// Timeline semaphores the next submission waits on, added by
+    /// [`crate::Queue::wait_for_fence`].
+    wait_semaphores: Mutex<SemaphoreList>,
+    /// Shared with the [`Device`], to allocate memory for sparse resources.
+    mem_allocator: Arc<Mutex<gpu_alloc::GpuAllocator<vk::DeviceMemory>>>,
+    retired_sparse_memory: Mutex<RetiredSparseMemory>,
 }
 
 impl Queue {
     pub fn as_raw(&self) -> vk::Queue {
         self.raw
     }
+
+    /// Free the sparse memory unbound before submissions that `fence` shows
+    /// have completed, and tie the memory unbound since the last submission to
+    /// the one that signals `signal_value`.
+    fn retire_sparse_memory(
+        &self,
+        fence: &Fence,
+        signal_value: crate::FenceValue,
+    ) -> Result<(), crate::DeviceError> {
+        let mut retired = self.retired_sparse_memory.lock();
+        if !retired.submitted.is_empty() {
+            let latest = fence.get_latest(
+                &self.device.raw,
+                self.device.extension_fns.timeline_semaphore.as_ref(),
+            )?;
+            let (completed, in_use) = mem::take(&mut retired.submitted)
+                .into_iter()
+                .partition::<Vec<_>, _>(|&(value, _)| value <= latest);
+            retired.submitted = in_use;
+            let mut allocator = self.mem_allocator.lock();
+            for (_, block) in completed {
+                unsafe { allocator.dealloc(&*self.device, block) };
+            }
+        }
+        let pending = mem::take(&mut retired.pending);
+        retired
+            .submitted
+            .extend(pending.into_iter().map(|block| (signal_value, block)));
+        Ok(())
+    }
+
+    /// Allocate memory for the pages and tiles `buffer_binds` and
+    /// `texture_binds` make resident, and retire the memory of those they make
+    /// non-resident, returning the binds to pass to `vkQueueBindSparse`.
+    ///
+    /// Every change made to the resources is recorded in `changes`, so that
+    /// they can be undone if an allocation fails.
+    unsafe fn update_sparse_memory<'a>(
+        &self,
+        buffer_binds: &[crate::SparseBufferBind<'a, Buffer>],
+        texture_binds: &[crate::SparseTextureBind<'a, Texture>],
+        allocator: &mut gpu_alloc::GpuAllocator<vk::DeviceMemory>,
+        retired: &mut RetiredSparseMemory,
+        changes: &mut Vec<SparseChange<'a>>,
+    ) -> Result<SparseMemoryBinds, crate::DeviceError> {
+        let page_size = wgt::SPARSE_PAGE_SIZE;
+
+        let mut buffer_memory_binds = Vec::with_capacity(buffer_binds.len());
+        for bind in buffer_binds {
+            let sparse = bind.buffer.sparse.as_deref().expect("Buffer is not sparse");
+            let mut pages = sparse.pages.lock();
+            let mut memory_binds = Vec::new();
+            for offset in bind.range.clone().step_by(page_size as usize) {
+                let page = offset / page_size;
+                let memory_bind = vk::SparseMemoryBind::default()
+                    .resource_offset(offset)
+                    .size(page_size);
+                if bind.resident {
+                    if pages.contains_key(&page) {
+                        continue;
+                    }
+                    let block = unsafe { sparse.allocate(allocator, &self.device, page_size) }?;
+                    memory_binds.push(
+                        memory_bind
+                            .memory(*block.memory())
+                            .memory_offset(block.offset()),
+                    );
+                    pages.insert(page, block);
+                    changes.push(SparseChange::BoundPage(sparse, page));
+                } else if let Some(block) = pages.remove(&page) {
+                    memory_binds.push(memory_bind);
+                    retired.pending.push(block);
+                    changes.push(SparseChange::UnboundPage(sparse, page));
+                }
+            }
+            if !memory_binds.is_empty() {
+                buffer_memory_binds.push((bind.buffer.raw, memory_binds));
+            }
+        }
+
+        let mut opaque_memory_binds = Vec::new();
+        let mut image_memory_binds = Vec::with_capacity(texture_binds.len());
+        for bind in texture_binds {
+            let texture = bind.texture;
+            let sparse = texture.sparse.as_deref().expect("Texture is not sparse");
+            let tile = texture
+                .format
+                .sparse_tile_extent()
+                .expect("Format has no sparse tile size");
+
+            let mut metadata = sparse.metadata.lock();
+            if bind.resident && metadata.is_empty() && !sparse.metadata_ranges.is_empty() {
+                let mut memory_binds = Vec::with_capacity(sparse.metadata_ranges.len());
+                for range in sparse.metadata_ranges.iter() {
+                    let size = range.end - range.start;
+                    let block = unsafe { sparse.memory.allocate(allocator, &self.device, size) }?;
+                    memory_binds.push(
+                        vk::SparseMemoryBind::default()
+                            .resource_offset(range.start)
+                            .size(size)
+                            .memory(*block.memory())
+                            .memory_offset(block.offset())
+                            .flags(vk::SparseMemoryBindFlags::METADATA),
+                    );
+                    metadata.push(block);
+                    changes.push(SparseChange::BoundMetadata(sparse));
+                }
+                opaque_memory_binds.push((texture.raw, memory_binds));
+            }
+
+            let mut tiles = sparse.memory.pages.lock();
+            let mut memory_binds = Vec::new();
+            let rows = bind.origin.y..bind.origin.y + bind.size.height;
+            for y in rows.step_by(tile.height as usize) {
+                let columns = bind.origin.x..bind.origin.x + bind.size.width;
+                for x in columns.step_by(tile.width as usize) {
+                    let key = (
+                        bind.mip_level,
+                        bind.array_layer,
+                        x / tile.width,
+                        y / tile.height,
+                    );
+                    let memory_bind = vk::SparseImageMemoryBind::default()
+                        .subresource(vk::ImageSubresource {
+                            aspect_mask: vk::ImageAspectFlags::COLOR,
+                            mip_level: bind.mip_level,
+                            array_layer: bind.array_layer,
+                        })
+                        .offset(vk::Offset3D {
+                            x: x as i32,
+                            y: y as i32,
+                            z: 0,
+                        })
+                        .extent(vk::Extent3D {
+                            width: tile.width,
+                            height: tile.height,
+                            depth: 1,
+                        });
+                    if bind.resident {
+                        if tiles.contains_key(&key) {
+                            continue;
+                        }
+                        let block =
+                            unsafe { sparse.memory.allocate(allocator, &self.device, page_size) }?;
+                        memory_binds.push(
+                            memory_bind
+                                .memory(*block.memory())
+                                .memory_offset(block.offset()),
+                        );
+                        tiles.insert(key, block);
+                        changes.push(SparseChange::BoundTile(&sparse.memory, key));
+                    } else if let Some(block) = tiles.remove(&key) {
+                        memory_binds.push(memory_bind);
+                        retired.pending.push(block);
+                        changes.push(SparseChange::UnboundTile(&sparse.memory, key));
+                    }
+                }
+            }
+            if !memory_binds.is_empty() {
+                image_memory_binds.push((texture.raw, memory_binds));
+            }
+        }
+
+        Ok(SparseMemoryBinds {
+            buffers: buffer_memory_binds,
+            opaque: opaque_memory_binds,
+            images: image_memory_binds,
+        })
+    }
 }
 
 impl Drop for Queue {
     fn drop(&mut self) {
         unsafe { self.relay_semaphores.lock().destroy(&self.device.raw) };
+        let retired = mem::take(self.retired_sparse_memory.get_mut());
+        let mut allocator = self.mem_allocator.lock();
+        let blocks = retired
+            .pending
+            .into_iter()
+            .chain(retired.submitted.into_iter().map(|(_, block)| block));
+        for block in blocks {
+            unsafe { allocator.dealloc(&*self.device, block) };
+        }
     }
 }
 #[derive(Debug)]
@@ -893,6 +1086,8 @@ impl BufferMemoryBacking {
 pub struct Buffer {
     raw: vk::Buffer,
     block: Option<Mutex<BufferMemoryBacking>>,
+    /// Memory bound to the pages of a sparse buffer, by page index.
+    sparse: Option<Box<SparseMemory<u64>>>,
 }
 impl Buffer {
     /// # Safety
@@ -903,6 +1098,7 @@ impl Buffer {
         Self {
             raw: vk_buffer,
             block: None,
+            sparse: None,
         }
     }
     /// # Safety
@@ -922,12 +1118,155 @@ impl Buffer {
                 offset,
                 size,
             })),
+            sparse: None,
         }
     }
 }
 
 impl crate::DynBuffer for Buffer {}
 
+/// Memory bound to the pages of a sparse buffer or the tiles of a sparse
+/// texture by [`crate::Queue::bind_sparse_memory`].
+#[derive(Debug)]
+struct SparseMemory<P> {
+    /// The memory types the resource can be bound to.
+    memory_types: u32,
+    alignment: u64,
+    /// One block of [`wgt::SPARSE_PAGE_SIZE`] bytes for each resident page.
+    pages: Mutex<FastHashMap<P, gpu_alloc::MemoryBlock<vk::DeviceMemory>>>,
+}
+
+impl<P> SparseMemory<P> {
+    fn new(requirements: vk::MemoryRequirements, valid_memory_types: u32) -> Self {
+        Self {
+            memory_types: requirements.memory_type_bits & valid_memory_types,
+            alignment: requirements.alignment,
+            pages: Mutex::new(FastHashMap::default()),
+        }
+    }
+
+    unsafe fn allocate(
+        &self,
+        allocator: &mut gpu_alloc::GpuAllocator<vk::DeviceMemory>,
+        device: &DeviceShared,
+        size: u64,
+    ) -> Result<gpu_alloc::MemoryBlock<vk::DeviceMemory>, crate::DeviceError> {
+        let block = unsafe {
+            allocator.alloc(
+                device,
+                gpu_alloc::Request {
+                    size,
+                    align_mask: self.alignment - 1,
+                    usage: gpu_alloc::UsageFlags::FAST_DEVICE_ACCESS,
+                    memory_types: self.memory_types,
+                },
+            )
+        }?;
+        Ok(block)
+    }
+
+    unsafe fn free(
+        self,
+        allocator: &mut gpu_alloc::GpuAllocator<vk::DeviceMemory>,
+        device: &DeviceShared,
+    ) {
+        for (_, block) in self.pages.into_inner() {
+            unsafe { allocator.dealloc(device, block) };
+        }
+    }
+}
+
+impl<P: Eq + core::hash::Hash> SparseMemory<P> {
+    /// Free the block allocated for `page`, which was never bound.
+    unsafe fn undo_bind(
+        &self,
+        page: P,
+        allocator: &mut gpu_alloc::GpuAllocator<vk::DeviceMemory>,
+        device: &DeviceShared,
+    ) {
+        if let Some(block) = self.pages.lock().remove(&page) {
+            unsafe { allocator.dealloc(device, block) };
+        }
+    }
+
+    /// Give `page` back its block, which is still bound and was the last one
+    /// moved to `retired`.
+    fn undo_unbind(&self, page: P, retired: &mut RetiredSparseMemory) {
+        if let Some(block) = retired.pending.pop() {
+            self.pages.lock().insert(page, block);
+        }
+    }
+}
+
+/// A tile of a sparse texture: its mip level, array layer, and position in
+/// tiles.
+type SparseTile = (u32, u32, u32, u32);
+
+#[derive(Debug)]
+struct SparseTexture {
+    memory: SparseMemory<SparseTile>,
+    /// Ranges of the metadata aspect, which some implementations need bound
+    /// before any tile is accessed.
+    metadata_ranges: Vec<crate::MemoryRange>,
+    /// Memory bound to `metadata_ranges`, allocated with the first resident
+    /// tile.
+    metadata: Mutex<Vec<gpu_alloc::MemoryBlock<vk::DeviceMemory>>>,
+}
+
+/// Memory unbound from sparse resources, which the GPU may still be using.
+#[derive(Debug, Default)]
+struct RetiredSparseMemory {
+    /// Unbound since the last submission.
+    pending: Vec<gpu_alloc::MemoryBlock<vk::DeviceMemory>>,
+    /// Unbound before submissions that signal their fence with the value.
+    submitted: Vec<(crate::FenceValue, gpu_alloc::MemoryBlock<vk::DeviceMemory>)>,
+}
+
+/// The binds [`Queue::bind_sparse_memory`] passes to `vkQueueBindSparse`.
+struct SparseMemoryBinds {
+    buffers: Vec<(vk::Buffer, Vec<vk::SparseMemoryBind>)>,
+    opaque: Vec<(vk::Image, Vec<vk::SparseMemoryBind>)>,
+    images: Vec<(vk::Image, Vec<vk::SparseImageMemoryBind>)>,
+}
+
+/// A change [`Queue::bind_sparse_memory`] made to the memory of a sparse
+/// resource before binding it.
+enum SparseChange<'a> {
+    /// A block was allocated for the page.
+    BoundPage(&'a SparseMemory<u64>, u64),
+    /// The block of the page was moved to [`RetiredSparseMemory::pending`].
+    UnboundPage(&'a SparseMemory<u64>, u64),
+    /// A block was allocated for the tile.
+    BoundTile(&'a SparseMemory<SparseTile>, SparseTile),
+    /// The block of the tile was moved to [`RetiredSparseMemory::pending`].
+    UnboundTile(&'a SparseMemory<SparseTile>, SparseTile),
+    /// A block was allocated for a range of the metadata aspect.
+    BoundMetadata(&'a SparseTexture),
+}
+
+impl SparseChange<'_> {
+    /// Undo the change. Changes must be undone in the reverse of the order
+    /// they were made in.
+    unsafe fn undo(
+        self,
+        allocator: &mut gpu_alloc::GpuAllocator<vk::DeviceMemory>,
+        device: &DeviceShared,
+        retired: &mut RetiredSparseMemory,
+    ) {
+        match self {
+            Self::BoundPage(memory, page) => unsafe { memory.undo_bind(page, allocator, device) },
+            Self::UnboundPage(memory, page) => memory.undo_unbind(page, retired),
+            Self::BoundTile(memory, tile) => unsafe { memory.undo_bind(tile, allocator, device) },
+            Self::UnboundTile(memory, tile) => memory.undo_unbind(tile, retired),
+            Self::BoundMetadata(texture) => {
+                if let Some(block) = texture.metadata.lock().pop() {
+                    unsafe { allocator.dealloc(device, block) };
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct AccelerationStructure {
     raw: vk::AccelerationStructureKHR,
@@ -942,8 +1281,10 @@ impl crate::DynAccelerationStructure for AccelerationStructure {}
 pub struct Texture {
     raw: vk::Image,
     drop_guard: Option<crate::DropGuard>,
+    /// Memory allocated for this texture alone, outside of `gpu-alloc`.
     external_memory: Option<vk::DeviceMemory>,
     block: Option<gpu_alloc::MemoryBlock<vk::DeviceMemory>>,
+    sparse: Option<Box<SparseTexture>>,
     format: wgt::TextureFormat,
     copy_size: crate::CopyExtent,
     identity: ResourceIdentity<vk::Image>,
@@ -1160,6 +1501,15 @@ pub struct CommandEncoder {
     /// What kind of pass we are currently within: compute or render.
     bind_point: vk::PipelineBindPoint,
 
+    /// The pipeline stages and access types barriers may use, if the queue
+    /// family this encoder records for doesn't support all of them.
+    ///
+    /// Compute and transfer queue families reject barriers that mention
+    /// graphics stages, even when the previous use of a resource was on a
+    /// graphics queue. The semaphore wait that orders the queues already
+    /// covers those stages.
+    barrier_mask: Option<(vk::PipelineStageFlags, vk::AccessFlags)>,
+
     /// Allocation recycling pool for this encoder.
     temp: Temp,
 
@@ -1282,6 +1632,18 @@ pub struct QuerySet {
 
 impl crate::DynQuerySet for QuerySet {}
 
+/// A block of device memory that placed buffers and textures are bound to.
+#[derive(Debug)]
+pub struct Heap {
+    block: gpu_alloc::MemoryBlock<vk::DeviceMemory>,
+}
+
+impl crate::DynHeap for Heap {}
+
+/// Offsets of placed resources in a [`Heap`] are aligned to this, which
+/// exceeds the alignment requirement of any buffer or image in practice.
+const HEAP_ALIGNMENT: u64 = 1 << 16;
+
 /// The [`Api::Fence`] type for [`vulkan::Api`].
 ///
 /// This is an `enum` because there are two possible implementations of
@@ -1439,7 +1801,7 @@ impl crate::Queue for Queue {
         let mut fence_raw = vk::Fence::null();
 
         let mut wait_stage_masks = Vec::new();
-        let mut wait_semaphores = Vec::new();
+        let mut wait_semaphores = SemaphoreList::default();
         let mut signal_semaphores = SemaphoreList::default();
 
         // Double check that the same swapchain image isn't being given to us multiple times,
@@ -1481,7 +1843,7 @@ impl crate::Queue for Queue {
             // done with it.
             if let Some(sem) = acquire_semaphore.get_acquire_wait_semaphore() {
                 wait_stage_masks.push(vk::PipelineStageFlags::TOP_OF_PIPE);
-                wait_semaphores.push(sem);
+                wait_semaphores.push_binary(sem);
             }
 
             // Get a semaphore to signal when we're done writing to this surface
@@ -1501,11 +1863,23 @@ impl crate::Queue for Queue {
 
         if let Some(sem) = semaphore_state.wait {
             wait_stage_masks.push(vk::PipelineStageFlags::TOP_OF_PIPE);
-            wait_semaphores.push(sem);
+            wait_semaphores.push_binary(sem);
         }
 
+        // Waits on other queues' work must cover every stage, as we don't
+        // know which commands of this submission depend on that work.
+        let mut queue_waits = self.wait_semaphores.lock();
+        wait_stage_masks.resize(
+            wait_stage_masks.len() + queue_waits.len(),
+            vk::PipelineStageFlags::ALL_COMMANDS,
+        );
+        wait_semaphores.append(&mut queue_waits);
+        drop(queue_waits);
+
         signal_semaphores.push_binary(semaphore_state.signal);
 
+        self.retire_sparse_memory(signal_fence, signal_value)?;
+
         // We need to signal our wgpu::Fence if we have one, this adds it to the signal list.
         signal_fence.maintain(&self.device.raw)?;
         match *signal_fence {
@@ -1535,14 +1909,16 @@ impl crate::Queue for Queue {
             .map(|cmd| cmd.raw)
             .collect::<Vec<_>>();
 
-        let mut vk_info = vk::SubmitInfo::default().command_buffers(&vk_cmd_buffers);
-
-        vk_info = vk_info
-            .wait_semaphores(&wait_semaphores)
-            .wait_dst_stage_mask(&wait_stage_masks);
+        let vk_info = vk::SubmitInfo::default().command_buffers(&vk_cmd_buffers);
 
         let mut vk_timeline_info = mem::MaybeUninit::uninit();
-        vk_info = signal_semaphores.add_to_submit(vk_info, &mut vk_timeline_info);
+        let vk_info = SemaphoreList::add_to_submit(
+            &wait_semaphores,
+            &wait_stage_masks,
+            &signal_semaphores,
+            vk_info,
+            &mut vk_timeline_info,
+        );
 
         profiling::scope!("vkQueueSubmit");
         unsafe {
@@ -1554,6 +1930,110 @@ impl crate::Queue for Queue {
         Ok(())
     }
 
+    unsafe fn wait_for_fence(
+        &self,
+        fence: &Fence,
+        value: crate::FenceValue,
+    ) -> Result<(), crate::DeviceError> {
+        match *fence {
+            Fence::TimelineSemaphore(raw) => {
+                self.wait_semaphores.lock().push_timeline(raw, value);
+                Ok(())
+            }
+            Fence::FencePool { .. } => {
+                crate::hal_usage_error("waiting on another queue requires timeline semaphores")
+            }
+        }
+    }
+
+    unsafe fn bind_sparse_memory(
+        &self,
+        buffer_binds: &[crate::SparseBufferBind<'_, Buffer>],
+        texture_binds: &[crate::SparseTextureBind<'_, Texture>],
+    ) -> Result<(), crate::DeviceError> {
+        let mut allocator = self.mem_allocator.lock();
+        let mut retired = self.retired_sparse_memory.lock();
+
+        let mut changes = Vec::new();
+        let binds = unsafe {
+            self.update_sparse_memory(
+                buffer_binds,
+                texture_binds,
+                &mut allocator,
+                &mut retired,
+                &mut changes,
+            )
+        };
+        let SparseMemoryBinds {
+            buffers: buffer_memory_binds,
+            opaque: opaque_memory_binds,
+            images: image_memory_binds,
+        } = match binds {
+            Ok(binds) => binds,
+            Err(error) => {
+                // Nothing was bound, so leave the resources as they were.
+                for change in changes.into_iter().rev() {
+                    unsafe { change.undo(&mut allocator, &self.device, &mut retired) };
+                }
+                return Err(error);
+            }
+        };
+        drop((allocator, retired));
+
+        if buffer_memory_binds.is_empty()
+            && opaque_memory_binds.is_empty()
+            && image_memory_binds.is_empty()
+        {
+            return Ok(());
+        }
+
+        let buffer_infos = buffer_memory_binds
+            .iter()
+            .map(|(raw, binds)| {
+                vk::SparseBufferMemoryBindInfo::default()
+                    .buffer(*raw)
+                    .binds(binds)
+            })
+            .collect::<Vec<_>>();
+        let opaque_infos = opaque_memory_binds
+            .iter()
+            .map(|(raw, binds)| {
+                vk::SparseImageOpaqueMemoryBindInfo::default()
+                    .image(*raw)
+                    .binds(binds)
+            })
+            .collect::<Vec<_>>();
+        let image_infos = image_memory_binds
+            .iter()
+            .map(|(raw, binds)| {
+                vk::SparseImageMemoryBindInfo::default()
+                    .image(*raw)
+                    .binds(binds)
+            })
+            .collect::<Vec<_>>();
+
+        // Binding isn't ordered with submissions on its own, so it takes part
+        // in the chain of relay semaphores `submit` uses.
+        let semaphore_state = self.relay_semaphores.lock().advance(&self.device)?;
+        let wait_semaphores = semaphore_state.wait.as_slice();
+        let signal_semaphores = [semaphore_state.signal];
+
+        let vk_info = vk::BindSparseInfo::default()
+            .wait_semaphores(wait_semaphores)
+            .buffer_binds(&buffer_infos)
+            .image_opaque_binds(&opaque_infos)
+            .image_binds(&image_infos)
+            .signal_semaphores(&signal_semaphores);
+
+        profiling::scope!("vkQueueBindSparse");
+        unsafe {
+            self.device
+                .raw
+                .queue_bind_sparse(self.raw, &[vk_info], vk::Fence::null())
+                .map_err(map_host_device_oom_and_lost_err)
+        }
+    }
+
     unsafe fn present(
         &self,
         surface: &Surface,
@@ -4,21 +4,21 @@ use alloc::vec::Vec;
 use ash::vk;
 use core::mem::MaybeUninit;
 
-/// A list of Vulkan semaphores to signal.
+/// A list of Vulkan semaphores to wait on or signal.
 ///
 /// This represents a list of binary or timeline semaphores, together
 /// with values for the timeline semaphores.
 ///
-/// This type ensures that the array of semaphores to be signaled
-/// stays aligned with the array of values for timeline semaphores
-/// appearing in that list. The [`add_to_submit`] method prepares the
-/// `vkQueueSubmit` arguments appropriately for whatever semaphores we
-/// actually have.
+/// This type ensures that the array of semaphores to be waited on or
+/// signaled stays aligned with the array of values for timeline
+/// semaphores appearing in that list. The [`add_to_submit`] function
+/// prepares the `vkQueueSubmit` arguments appropriately for whatever
+/// semaphores we actually have.
 ///
 /// [`add_to_submit`]: SemaphoreList::add_to_submit
 #[derive(Debug, Default)]
 pub struct SemaphoreList {
-    /// Semaphores to signal.
+    /// Semaphores to wait on or signal.
     ///
     /// This can be a mix of binary and timeline semaphores.
     semaphores: Vec<vk::Semaphore>,
@@ -40,26 +40,44 @@ impl SemaphoreList {
         self.semaphores.is_empty()
     }
 
-    /// Add this list to the semaphores to be signalled by a `vkQueueSubmit` call.
+    pub fn len(&self) -> usize {
+        self.semaphores.len()
+    }
+
+    /// Add `waits` and `signals` to the semaphores waited on and signalled
+    /// by a `vkQueueSubmit` call.
+    ///
+    /// - Set `submit_info`'s `pWaitSemaphores` list to `waits`' semaphores,
+    ///   with `wait_stage_masks` as their `pWaitDstStageMask`.
     ///
-    /// - Set `submit_info`'s `pSignalSemaphores` list to this list's
+    /// - Set `submit_info`'s `pSignalSemaphores` list to `signals`'
     ///   semaphores.
     ///
-    /// - If this list contains any timeline semaphores, then initialize
-    ///   `timeline_info`, set its `pSignalSemaphoreValues` to this
-    ///   list's values, and add it to `submit_info`s extension chain.
+    /// - If either list contains any timeline semaphores, then initialize
+    ///   `timeline_info`, set its `pWaitSemaphoreValues` and
+    ///   `pSignalSemaphoreValues` to the lists' values, and add it to
+    ///   `submit_info`s extension chain.
     ///
     /// Return the revised `submit_info` value.
     pub fn add_to_submit<'i, 's: 'i>(
-        &'s self,
+        waits: &'s Self,
+        wait_stage_masks: &'s [vk::PipelineStageFlags],
+        signals: &'s Self,
         submit_info: vk::SubmitInfo<'i>,
         timeline_info: &'i mut MaybeUninit<vk::TimelineSemaphoreSubmitInfo<'i>>,
     ) -> vk::SubmitInfo<'i> {
-        self.check();
-        let mut submit_info = submit_info.signal_semaphores(&self.semaphores);
-        if !self.values.is_empty() {
+        waits.check();
+        signals.check();
+        debug_assert_eq!(waits.semaphores.len(), wait_stage_masks.len());
+        let mut submit_info = submit_info
+            .wait_semaphores(&waits.semaphores)
+            .wait_dst_stage_mask(wait_stage_masks)
+            .signal_semaphores(&signals.semaphores);
+        if !waits.values.is_empty() || !signals.values.is_empty() {
             let timeline_info = timeline_info.write(
-                vk::TimelineSemaphoreSubmitInfo::default().signal_semaphore_values(&self.values),
+                vk::TimelineSemaphoreSubmitInfo::default()
+                    .wait_semaphore_values(&waits.values)
+                    .signal_semaphore_values(&signals.values),
             );
             submit_info = submit_info.push_next(timeline_info);
         }
@@ -76,8 +94,8 @@ impl SemaphoreList {
         self.check();
     }
 
-    /// Add a timeline semaphore to this list, to be signalled with
-    /// `value`.
+    /// Add a timeline semaphore to this list, to be waited on until it
+    /// reaches `value`, or to be signalled with `value`.
     pub fn push_timeline(&mut self, semaphore: vk::Semaphore, value: u64) {
         self.pad_values();
         self.semaphores.push(semaphore);
@@ -998,6 +998,7 @@ bitflags_array! {
         ///
         /// Supported platforms:
         /// - DX12
+        /// - OpenGL
         const EXTERNAL_TEXTURE = 1 << 30;
 
         // Shader:
@@ -1242,6 +1243,66 @@ bitflags_array! {
         ///
         /// This is a native only feature.
         const HLSL_DXIL_SHADER_PASSTHROUGH = 1 << 51;
+
+        /// Allows creating additional compute-only and transfer-only queues with
+        /// [`Device::create_queue`], next to the queue returned by `request_device`.
+        ///
+        /// Work submitted to different queues may execute concurrently. Ordering between
+        /// queues must be established explicitly with [`Queue::wait_for_submission`], and a
+        /// buffer or texture used by one queue may only be used by another queue once that
+        /// queue has waited on the submission that last used it.
+        ///
+        /// Supported platforms:
+        /// - Vulkan (with timeline semaphores)
+        ///
+        /// Not Supported:
+        /// - DX12
+        /// - Metal
+        /// - OpenGL
+        ///
+        /// This is a native only feature.
+        ///
+        /// [`Device::create_queue`]: ../wgpu/struct.Device.html#method.create_queue
+        /// [`Queue::wait_for_submission`]: ../wgpu/struct.Queue.html#method.wait_for_submission
+        const MULTIPLE_QUEUES = 1 << 52;
+
+        /// Allows creating sparse buffers and textures with [`BufferUsages::SPARSE`] and
+        /// [`TextureUsages::SPARSE`], which are created without memory and have parts of them
+        /// made resident with [`Queue::update_sparse_residency`].
+        ///
+        /// Reads from parts that aren't resident return zero, and writes to them are
+        /// discarded. Newly resident parts are zero-initialized.
+        ///
+        /// Supported platforms:
+        /// - Vulkan (with strict non-resident behavior and standard 2D tile shapes)
+        /// - DX12 (with tiled resources tier 2)
+        ///
+        /// This is a native only feature.
+        ///
+        /// [`BufferUsages::SPARSE`]: super::BufferUsages::SPARSE
+        /// [`TextureUsages::SPARSE`]: super::TextureUsages::SPARSE
+        /// [`Queue::update_sparse_residency`]: ../wgpu/struct.Queue.html#method.update_sparse_residency
+        const SPARSE_RESOURCES = 1 << 53;
+
+        /// Allows creating [`Heap`]s, blocks of device memory that buffers and textures
+        /// are placed in with [`Heap::create_buffer`] and [`Heap::create_texture`].
+        ///
+        /// Placed resources may overlap, so that resources that are never used at the same
+        /// time can share memory. Of a set of overlapping resources, only the one last
+        /// activated by [`CommandEncoder::aliasing_barrier`] can be used. Activating a
+        /// resource discards its contents. Placed resources can't be mapped.
+        ///
+        /// Supported platforms:
+        /// - Vulkan
+        /// - DX12 (with resource heap tier 2)
+        ///
+        /// This is a native only feature.
+        ///
+        /// [`Heap`]: ../wgpu/struct.Heap.html
+        /// [`Heap::create_buffer`]: ../wgpu/struct.Heap.html#method.create_buffer
+        /// [`Heap::create_texture`]: ../wgpu/struct.Heap.html#method.create_texture
+        /// [`CommandEncoder::aliasing_barrier`]: ../wgpu/struct.CommandEncoder.html#method.aliasing_barrier
+        const RESOURCE_HEAPS = 1 << 54;
     }
 
     /// Features that are not guaranteed to be supported.
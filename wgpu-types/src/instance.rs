@@ -400,10 +400,15 @@ impl Dx12BackendOptions {
 pub struct NoopBackendOptions {
     /// Whether to allow the noop backend to be used.
     ///
-    /// The noop backend stubs out all operations except for buffer creation and mapping, so
+    /// The noop backend stubs out most operations, including all drawing and dispatching, so
     /// it must not be used when not expected. Therefore, it will not be used unless explicitly
     /// enabled.
     pub enable: bool,
+
+    /// The number of samples that every occlusion query reports as having passed.
+    ///
+    /// The noop backend does not draw anything, so this value stands in for the result.
+    pub occlusion_query_result: u64,
 }
 
 impl NoopBackendOptions {
@@ -415,6 +420,7 @@ impl NoopBackendOptions {
     pub fn from_env_or_default() -> Self {
         Self {
             enable: Self::enable_from_env().unwrap_or(false),
+            ..Self::default()
         }
     }
 
@@ -426,6 +432,7 @@ impl NoopBackendOptions {
     pub fn with_env(self) -> Self {
         Self {
             enable: Self::enable_from_env().unwrap_or(self.enable),
+            ..self
         }
     }
 
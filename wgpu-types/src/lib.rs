@@ -116,6 +116,14 @@ pub const VERTEX_STRIDE_ALIGNMENT: BufferAddress = 4;
 /// [writes to push constant storage]: ../wgpu/struct.RenderPass.html#method.set_push_constants
 pub const PUSH_CONSTANT_ALIGNMENT: u32 = 4;
 
+/// Size in bytes of a page of a [sparse buffer], and of a tile of a [sparse texture].
+///
+/// Parts of sparse resources are made resident in units of this size.
+///
+/// [sparse buffer]: BufferUsages::SPARSE
+/// [sparse texture]: TextureUsages::SPARSE
+pub const SPARSE_PAGE_SIZE: BufferAddress = 1 << 16;
+
 /// Maximum queries in a [`QuerySetDescriptor`].
 pub const QUERY_SET_MAX_QUERIES: u32 = 4096;
 
@@ -140,12 +148,17 @@ pub enum Backend {
     /// * Enumerating adapters will always return one noop adapter, which can be used to create
     ///   devices.
     /// * Buffers may be created, written, mapped, and copied to other buffers.
-    /// * Command encoders may be created, but only buffer operations are useful.
+    /// * Textures may be created, written, cleared, and copied to and from buffers and other
+    ///   textures. Multisampled textures store a single sample per texel.
+    /// * Query sets may be created and resolved. Timestamps are taken from the host's clock
+    ///   when the `std` feature is enabled, occlusion queries report
+    ///   [`NoopBackendOptions::occlusion_query_result`], and pipeline statistics are zero.
+    /// * Command encoders may be created, but only copies, clears and queries are useful.
     ///
     /// Other resources can be created but are nonfunctional; notably,
     ///
-    /// * Render passes and compute passes are not executed.
-    /// * Textures may be created, but do not store any texels.
+    /// * Render passes and compute passes are not executed, except for clearing the attachments
+    ///   of render passes.
     /// * There are no compatible surfaces.
     ///
     /// An adapter using the noop backend can only be obtained if [`NoopBackendOptions`]
@@ -1437,11 +1450,19 @@ pub enum Trace {
     #[default]
     Off,
 
-    /// Tracing enabled.
+    /// Tracing enabled, writing the actions to a `trace.ron` file in the directory.
     #[cfg(feature = "trace")]
     // This must be owned rather than `&'a Path`, because if it were that, then the lifetime
     // parameter would be unused when the "trace" feature is disabled, which is prohibited.
     Directory(std::path::PathBuf),
+
+    /// Tracing enabled, writing the actions to a `trace.bin` file in the directory.
+    ///
+    /// The binary format is more compact and faster to write than RON, which makes it
+    /// better suited to long sessions. The player reads both formats, and can convert
+    /// between them.
+    #[cfg(feature = "trace")]
+    BinaryDirectory(std::path::PathBuf),
 }
 
 bitflags::bitflags! {
@@ -3392,6 +3413,35 @@ impl TextureFormat {
         }
     }
 
+    /// The size in texels of one tile of a [sparse] 2D texture of this format.
+    ///
+    /// Each tile occupies [`SPARSE_PAGE_SIZE`] bytes, and is the unit in which parts of the
+    /// texture are made resident. The shapes match the standard tile shapes of Vulkan and D3D12.
+    ///
+    /// Returns `None` for depth, stencil and multi-planar formats, which can't be sparse.
+    ///
+    /// [sparse]: TextureUsages::SPARSE
+    #[must_use]
+    pub fn sparse_tile_extent(&self) -> Option<Extent3d> {
+        if self.is_depth_stencil_format() || self.is_multi_planar_format() {
+            return None;
+        }
+        let (blocks_wide, blocks_high) = match self.block_copy_size(None)? {
+            1 => (256, 256),
+            2 => (256, 128),
+            4 => (128, 128),
+            8 => (128, 64),
+            16 => (64, 64),
+            _ => return None,
+        };
+        let (block_width, block_height) = self.block_dimensions();
+        Some(Extent3d {
+            width: blocks_wide * block_width,
+            height: blocks_high * block_height,
+            depth_or_array_layers: 1,
+        })
+    }
+
     /// The largest number that can be returned by [`Self::target_pixel_byte_cost`].
     pub const MAX_TARGET_PIXEL_BYTE_COST: u32 = 16;
 
@@ -5192,6 +5242,15 @@ bitflags::bitflags! {
         const BLAS_INPUT = 1 << 10;
         /// Allows a buffer to be used as input for a top level acceleration structure build
         const TLAS_INPUT = 1 << 11;
+        /// Creates the buffer without memory. Its pages of [`SPARSE_PAGE_SIZE`] bytes are made
+        /// resident individually with [`Queue::update_sparse_residency`]. Requires
+        /// [`Features::SPARSE_RESOURCES`].
+        ///
+        /// Reads from pages that aren't resident return zero and writes to them are discarded.
+        /// The buffer can't be mapped, and its size must be a multiple of [`SPARSE_PAGE_SIZE`].
+        ///
+        /// [`Queue::update_sparse_residency`]: ../wgpu/struct.Queue.html#method.update_sparse_residency
+        const SPARSE = 1 << 12;
     }
 }
 
@@ -5291,6 +5350,107 @@ impl<L> BufferDescriptor<L> {
     }
 }
 
+/// The kind of work a [`Queue`](../wgpu/struct.Queue.html) can execute.
+///
+/// Each type can execute everything the types after it can: graphics queues
+/// can also run compute and transfer work, and compute queues can also run
+/// transfer work.
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum QueueType {
+    /// Render passes, compute passes, queries and copies.
+    ///
+    /// This is the type of the queue returned by `request_device`.
+    Graphics,
+    /// Compute passes, queries and copies.
+    Compute,
+    /// Copies only.
+    Transfer,
+}
+
+impl QueueType {
+    /// Returns true if queues of this type can execute work that requires `other`.
+    #[must_use]
+    pub fn supports(self, other: Self) -> bool {
+        match self {
+            Self::Graphics => true,
+            Self::Compute => other != Self::Graphics,
+            Self::Transfer => other == Self::Transfer,
+        }
+    }
+}
+
+/// Describes an additional [`Queue`](../wgpu/struct.Queue.html).
+///
+/// Requires [`Features::MULTIPLE_QUEUES`].
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct QueueDescriptor<L> {
+    /// Debug label for the queue. This will show up in graphics debuggers for easy identification.
+    pub label: L,
+    /// The type of the queue. Must not be [`QueueType::Graphics`].
+    pub ty: QueueType,
+}
+
+impl<L> QueueDescriptor<L> {
+    /// Takes a closure and maps the label of the queue descriptor into another.
+    #[must_use]
+    pub fn map_label<K>(&self, fun: impl FnOnce(&L) -> K) -> QueueDescriptor<K> {
+        QueueDescriptor {
+            label: fun(&self.label),
+            ty: self.ty,
+        }
+    }
+}
+
+/// A change to the residency of a range of a [sparse] buffer.
+///
+/// Used by [`Queue::update_sparse_residency`].
+///
+/// [sparse]: BufferUsages::SPARSE
+/// [`Queue::update_sparse_residency`]: ../wgpu/struct.Queue.html#method.update_sparse_residency
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SparseBufferBinding<B> {
+    /// The buffer to change.
+    pub buffer: B,
+    /// Offset in bytes of the range. Must be a multiple of [`SPARSE_PAGE_SIZE`].
+    pub offset: BufferAddress,
+    /// Size in bytes of the range. Must be a multiple of [`SPARSE_PAGE_SIZE`].
+    pub size: BufferAddress,
+    /// Whether the pages of the range are backed by memory after the change.
+    pub resident: bool,
+}
+
+/// A change to the residency of a region of a [sparse] texture.
+///
+/// Used by [`Queue::update_sparse_residency`].
+///
+/// [sparse]: TextureUsages::SPARSE
+/// [`Queue::update_sparse_residency`]: ../wgpu/struct.Queue.html#method.update_sparse_residency
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SparseTextureBinding<T> {
+    /// The texture to change.
+    pub texture: T,
+    /// The mip level of the region.
+    pub mip_level: u32,
+    /// The array layer of the region.
+    pub array_layer: u32,
+    /// Origin of the region in texels. Must be a multiple of the
+    /// [tile size](TextureFormat::sparse_tile_extent).
+    pub origin: Origin3d,
+    /// Size of the region in texels. Must be a multiple of the
+    /// [tile size](TextureFormat::sparse_tile_extent), and `depth_or_array_layers` must be 1.
+    pub size: Extent3d,
+    /// Whether the tiles of the region are backed by memory after the change.
+    pub resident: bool,
+}
+
 /// Describes a [`CommandEncoder`](../wgpu/struct.CommandEncoder.html).
 ///
 /// Corresponds to [WebGPU `GPUCommandEncoderDescriptor`](
@@ -5488,6 +5648,27 @@ bitflags::bitflags! {
         //
         /// Allows a texture to be used with image atomics. Requires [`Features::TEXTURE_ATOMIC`].
         const STORAGE_ATOMIC = 1 << 16;
+        /// Marks a render attachment whose contents never need to leave tile memory, such as a
+        /// multisampled color target that is resolved at the end of the pass, or a depth buffer
+        /// that is only used within a pass.
+        ///
+        /// The texture is backed by memoryless storage on Metal and lazily allocated memory on
+        /// Vulkan, where the device supports them, and is a regular texture elsewhere.
+        ///
+        /// The only other usage allowed with it is [`TextureUsages::RENDER_ATTACHMENT`], and
+        /// every render pass using the texture must clear it at the start of the pass and
+        /// discard it at the end with [`StoreOp::Discard`]. It can't be a resolve target.
+        const TRANSIENT = 1 << 17;
+        /// Creates the texture without memory. Its tiles, of the size given by
+        /// [`TextureFormat::sparse_tile_extent`], are made resident individually with
+        /// [`Queue::update_sparse_residency`]. Requires [`Features::SPARSE_RESOURCES`].
+        ///
+        /// Reads from tiles that aren't resident return zero and writes to them are discarded.
+        /// The texture must be a single-sampled 2D texture with a color format, and the size
+        /// of each of its mip levels must be a multiple of the tile size.
+        ///
+        /// [`Queue::update_sparse_residency`]: ../wgpu/struct.Queue.html#method.update_sparse_residency
+        const SPARSE = 1 << 18;
     }
 }
 
@@ -5525,6 +5706,10 @@ bitflags::bitflags! {
         /// Image atomic enabled storage.
         /// cbindgen:ignore
         const STORAGE_ATOMIC = 1 << 11;
+        /// A transient attachment that may have no backing memory. This is not a state the
+        /// texture can be in, and is only passed down when creating the texture.
+        /// cbindgen:ignore
+        const TRANSIENT = 1 << 12;
         /// The combination of states that a texture may be in _at the same time_.
         /// cbindgen:ignore
         const INCLUSIVE = Self::COPY_SRC.bits() | Self::RESOURCE.bits() | Self::DEPTH_STENCIL_READ.bits();
@@ -5538,10 +5723,10 @@ bitflags::bitflags! {
         const ORDERED = Self::INCLUSIVE.bits() | Self::COLOR_TARGET.bits() | Self::DEPTH_STENCIL_WRITE.bits() | Self::STORAGE_READ_ONLY.bits();
 
         /// Flag used by the wgpu-core texture tracker to say a texture is in different states for every sub-resource
-        const COMPLEX = 1 << 12;
+        const COMPLEX = 1 << 13;
         /// Flag used by the wgpu-core texture tracker to say that the tracker does not know the state of the sub-resource.
         /// This is different from UNINITIALIZED as that says the tracker does know, but the texture has not been initialized.
-        const UNKNOWN = 1 << 13;
+        const UNKNOWN = 1 << 14;
     }
 }
 
@@ -7499,6 +7684,43 @@ pub enum SamplerBorderColor {
     Zero,
 }
 
+/// Describes a [`Heap`](../wgpu/struct.Heap.html).
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeapDescriptor<L> {
+    /// Debug label for the heap.
+    pub label: L,
+    /// Size of the heap in bytes. Must not be zero.
+    pub size: BufferAddress,
+}
+
+impl<L> HeapDescriptor<L> {
+    /// Takes a closure and maps the label of the heap descriptor into another.
+    #[must_use]
+    pub fn map_label<'a, K>(&'a self, fun: impl FnOnce(&'a L) -> K) -> HeapDescriptor<K> {
+        HeapDescriptor {
+            label: fun(&self.label),
+            size: self.size,
+        }
+    }
+}
+
+/// Memory needed by a buffer or texture placed in a [`Heap`].
+///
+/// Returned by [`Device::get_buffer_memory_requirements`] and
+/// [`Device::get_texture_memory_requirements`].
+///
+/// [`Heap`]: ../wgpu/struct.Heap.html
+/// [`Device::get_buffer_memory_requirements`]: ../wgpu/struct.Device.html#method.get_buffer_memory_requirements
+/// [`Device::get_texture_memory_requirements`]: ../wgpu/struct.Device.html#method.get_texture_memory_requirements
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MemoryRequirements {
+    /// Size of the memory, in bytes.
+    pub size: BufferAddress,
+    /// Required alignment of the offset of the resource in the heap, in bytes.
+    pub alignment: BufferAddress,
+}
+
 /// Describes how to create a `QuerySet`.
 ///
 /// Corresponds to [WebGPU `GPUQuerySetDescriptor`](
@@ -180,6 +180,21 @@ impl CommandEncoder {
         self.inner.clear_texture(&texture.inner, subresource_range);
     }
 
+    /// Activates buffers and textures placed in a [`Heap`].
+    ///
+    /// Each listed resource becomes the one that may be used out of the resources
+    /// it overlaps in its heap. The contents of the resources it overlaps are
+    /// discarded, and it is zeroed on first use.
+    ///
+    /// The barrier takes effect at the start of the command buffer it is recorded
+    /// in, when that command buffer is submitted. Requires [`Features::RESOURCE_HEAPS`].
+    pub fn aliasing_barrier(&mut self, buffers: &[&Buffer], textures: &[&Texture]) {
+        self.inner.aliasing_barrier(
+            &mut buffers.iter().map(|buffer| &buffer.inner),
+            &mut textures.iter().map(|texture| &texture.inner),
+        );
+    }
+
     /// Clears buffer to zero.
     ///
     /// # Panics
@@ -1,4 +1,4 @@
-use alloc::{boxed::Box, string::String, sync::Arc, vec};
+use alloc::{boxed::Box, string::String, sync::Arc, vec, vec::Vec};
 #[cfg(wgpu_core)]
 use core::ops::Deref;
 use core::{error, fmt, future::Future};
@@ -63,7 +63,10 @@ impl Device {
         let instance = Instance::new(&InstanceDescriptor {
             backends: Backends::NOOP,
             backend_options: BackendOptions {
-                noop: NoopBackendOptions { enable: true },
+                noop: NoopBackendOptions {
+                    enable: true,
+                    ..Default::default()
+                },
                 ..Default::default()
             },
             ..Default::default()
@@ -201,6 +204,20 @@ impl Device {
         CommandEncoder { inner: encoder }
     }
 
+    /// Creates an additional compute or transfer [`Queue`].
+    ///
+    /// Returns `None` if the device has no queue of the requested type, or if creating
+    /// the queue fails validation, in which case the error is reported like any other.
+    /// Each additional queue can only be used by one [`Queue`] at a time; dropping the
+    /// `Queue` waits for its work to finish and makes the queue available again.
+    ///
+    /// Requires [`Features::MULTIPLE_QUEUES`].
+    #[must_use]
+    pub fn create_queue(&self, desc: &QueueDescriptor<'_>) -> Option<Queue> {
+        let queue = self.inner.create_queue(desc)?;
+        Some(Queue { inner: queue })
+    }
+
     /// Creates an empty [`RenderBundleEncoder`].
     #[must_use]
     pub fn create_render_bundle_encoder<'a>(
@@ -231,6 +248,31 @@ impl Device {
         BindGroupLayout { inner: layout }
     }
 
+    /// Derives the entries of bind group layouts that cover the resources used
+    /// by a set of shader entry points.
+    ///
+    /// Each element of `entry_points` is a shader module and the name of an
+    /// entry point in it. The entries are derived the same way as for a
+    /// pipeline created without a layout, but the result can be shared by
+    /// several pipelines: a binding used by more than one entry point gets a
+    /// single entry, visible to all of their stages, whose minimum binding
+    /// size is large enough for each of them. A texture is marked filterable
+    /// if any entry point samples it.
+    ///
+    /// Returns the entries of each bind group, indexed by group and sorted by
+    /// binding index, up to the last group that any entry point uses.
+    ///
+    /// Errors are returned rather than reported to error scopes: a validation
+    /// error if the entry points use a binding in incompatible ways or a module
+    /// was created without reflection information, and an internal error on
+    /// the WebGPU backend, which has no way to reflect shader modules.
+    pub fn derive_bind_group_layouts(
+        &self,
+        entry_points: &[(&ShaderModule, &str)],
+    ) -> Result<Vec<Vec<BindGroupLayoutEntry>>, Error> {
+        self.inner.derive_bind_group_layouts(entry_points)
+    }
+
     /// Creates a [`PipelineLayout`].
     #[must_use]
     pub fn create_pipeline_layout(&self, desc: &PipelineLayoutDescriptor<'_>) -> PipelineLayout {
@@ -245,6 +287,29 @@ impl Device {
         RenderPipeline { inner: pipeline }
     }
 
+    /// Creates a [`RenderPipeline`] without blocking the calling thread while
+    /// its shaders are translated and compiled.
+    ///
+    /// Instead of being reported to the error scopes or the uncaptured error
+    /// handler, errors are returned by the future. Any borrowed data in `desc`
+    /// only needs to live until this returns.
+    ///
+    /// On native platforms with `std`, the pipeline is created on a background
+    /// thread and the future can be awaited with any executor. On WebGPU this
+    /// maps to [`GPUDevice.createRenderPipelineAsync`][mdn].
+    ///
+    /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/API/GPUDevice/createRenderPipelineAsync
+    pub fn create_render_pipeline_async(
+        &self,
+        desc: &RenderPipelineDescriptor<'_>,
+    ) -> impl Future<Output = Result<RenderPipeline, Error>> + WasmNotSend {
+        let future = self.inner.create_render_pipeline_async(desc);
+        async move {
+            let pipeline = future.await?;
+            Ok(RenderPipeline { inner: pipeline })
+        }
+    }
+
     /// Creates a mesh shader based [`RenderPipeline`].
     #[must_use]
     pub fn create_mesh_pipeline(&self, desc: &MeshPipelineDescriptor<'_>) -> RenderPipeline {
@@ -259,6 +324,26 @@ impl Device {
         ComputePipeline { inner: pipeline }
     }
 
+    /// Creates a [`ComputePipeline`] without blocking the calling thread while
+    /// its shader is translated and compiled.
+    ///
+    /// Errors are returned by the future, as with
+    /// [`Device::create_render_pipeline_async`].
+    ///
+    /// On WebGPU this maps to [`GPUDevice.createComputePipelineAsync`][mdn].
+    ///
+    /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/API/GPUDevice/createComputePipelineAsync
+    pub fn create_compute_pipeline_async(
+        &self,
+        desc: &ComputePipelineDescriptor<'_>,
+    ) -> impl Future<Output = Result<ComputePipeline, Error>> + WasmNotSend {
+        let future = self.inner.create_compute_pipeline_async(desc);
+        async move {
+            let pipeline = future.await?;
+            Ok(ComputePipeline { inner: pipeline })
+        }
+    }
+
     /// Creates a [`Buffer`].
     #[must_use]
     pub fn create_buffer(&self, desc: &BufferDescriptor<'_>) -> Buffer {
@@ -407,6 +492,39 @@ impl Device {
         QuerySet { inner: query_set }
     }
 
+    /// Creates a new [`Heap`] that buffers and textures can be placed in.
+    ///
+    /// Requires [`Features::RESOURCE_HEAPS`].
+    #[must_use]
+    pub fn create_heap(&self, desc: &HeapDescriptor<'_>) -> Heap {
+        let heap = self.inner.create_heap(desc);
+        Heap { inner: heap }
+    }
+
+    /// Returns the size and alignment of the memory a buffer created with `desc`
+    /// needs when placed in a [`Heap`].
+    ///
+    /// Requires [`Features::RESOURCE_HEAPS`]. Returns `None` if `desc` is invalid.
+    #[must_use]
+    pub fn get_buffer_memory_requirements(
+        &self,
+        desc: &BufferDescriptor<'_>,
+    ) -> Option<MemoryRequirements> {
+        self.inner.get_buffer_memory_requirements(desc)
+    }
+
+    /// Returns the size and alignment of the memory a texture created with `desc`
+    /// needs when placed in a [`Heap`].
+    ///
+    /// Requires [`Features::RESOURCE_HEAPS`]. Returns `None` if `desc` is invalid.
+    #[must_use]
+    pub fn get_texture_memory_requirements(
+        &self,
+        desc: &TextureDescriptor<'_>,
+    ) -> Option<MemoryRequirements> {
+        self.inner.get_texture_memory_requirements(desc)
+    }
+
     /// Set a callback for errors that are not handled in error scopes.
     pub fn on_uncaptured_error(&self, handler: Box<dyn UncapturedErrorHandler>) {
         self.inner.on_uncaptured_error(handler)
@@ -0,0 +1,95 @@
+use alloc::sync::Arc;
+
+use crate::api::buffer::MapContext;
+use crate::util::Mutex;
+use crate::*;
+
+/// Handle to a block of GPU memory that buffers and textures can be placed in.
+///
+/// It can be created with [`Device::create_heap`]. Requires [`Features::RESOURCE_HEAPS`].
+///
+/// Resources placed in a heap may overlap, which lets resources whose lifetimes
+/// don't overlap share memory. Of a set of overlapping resources, only the one
+/// most recently named in a [`CommandEncoder::aliasing_barrier`] may be used.
+/// Placed resources start out inactive, so every placed resource must appear in
+/// an aliasing barrier before it is first used. Activating a resource discards
+/// the contents of every resource it overlaps, and its own contents are zeroed
+/// on first use.
+///
+/// The memory of a heap is freed when the heap and every resource placed in it
+/// have been dropped.
+///
+/// This type is unique to the Rust API of `wgpu`.
+#[derive(Debug, Clone)]
+pub struct Heap {
+    pub(crate) inner: dispatch::DispatchHeap,
+}
+#[cfg(send_sync)]
+static_assertions::assert_impl_all!(Heap: Send, Sync);
+
+crate::cmp::impl_eq_ord_hash_proxy!(Heap => .inner);
+
+impl Heap {
+    /// Creates a new [`Buffer`] occupying the heap memory starting at `offset`.
+    ///
+    /// `offset` must be a multiple of the alignment returned by
+    /// [`Device::get_buffer_memory_requirements`] for `desc`, and the buffer must
+    /// fit within the heap. Placed buffers cannot be mappable.
+    #[must_use]
+    pub fn create_buffer(&self, offset: BufferAddress, desc: &BufferDescriptor<'_>) -> Buffer {
+        let buffer = self.inner.create_buffer(offset, desc);
+
+        Buffer {
+            inner: buffer,
+            map_context: Arc::new(Mutex::new(MapContext::new())),
+            size: desc.size,
+            usage: desc.usage,
+        }
+    }
+
+    /// Creates a new [`Texture`] occupying the heap memory starting at `offset`.
+    ///
+    /// `offset` must be a multiple of the alignment returned by
+    /// [`Device::get_texture_memory_requirements`] for `desc`, and the texture
+    /// must fit within the heap.
+    #[must_use]
+    pub fn create_texture(&self, offset: BufferAddress, desc: &TextureDescriptor<'_>) -> Texture {
+        let texture = self.inner.create_texture(offset, desc);
+
+        Texture {
+            inner: texture,
+            descriptor: TextureDescriptor {
+                label: None,
+                view_formats: &[],
+                ..desc.clone()
+            },
+        }
+    }
+
+    /// Generates a report of the resources placed in this heap.
+    ///
+    /// The report has a single memory block covering the whole heap, and an
+    /// allocation for every placed resource that is still alive. Overlapping
+    /// resources only count once towards
+    /// [`AllocatorReport::total_allocated_bytes`].
+    ///
+    /// Returns `None` if the heap is invalid.
+    #[must_use]
+    pub fn generate_allocation_report(&self) -> Option<AllocatorReport> {
+        self.inner.generate_allocation_report()
+    }
+
+    #[cfg(custom)]
+    /// Returns custom implementation of Heap (if custom backend and is internally T)
+    pub fn as_custom<T: custom::HeapInterface>(&self) -> Option<&T> {
+        self.inner.as_custom()
+    }
+}
+
+/// Describes a [`Heap`].
+///
+/// For use with [`Device::create_heap`].
+///
+/// This type is unique to the Rust API of `wgpu`.
+pub type HeapDescriptor<'a> = wgt::HeapDescriptor<Label<'a>>;
+static_assertions::assert_impl_all!(HeapDescriptor<'_>: Send, Sync);
@@ -33,6 +33,7 @@ mod compute_pass;
 mod compute_pipeline;
 mod device;
 mod external_texture;
+mod heap;
 mod instance;
 mod pipeline_cache;
 mod pipeline_layout;
@@ -62,6 +63,7 @@ pub use compute_pass::*;
 pub use compute_pipeline::*;
 pub use device::*;
 pub use external_texture::*;
+pub use heap::*;
 pub use instance::*;
 pub use pipeline_cache::*;
 pub use pipeline_layout::*;
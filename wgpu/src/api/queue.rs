@@ -35,6 +35,12 @@ impl Queue {
     }
 }
 
+/// Describes an additional [`Queue`].
+///
+/// For use with [`Device::create_queue`].
+pub type QueueDescriptor<'a> = wgt::QueueDescriptor<Label<'a>>;
+static_assertions::assert_impl_all!(QueueDescriptor<'_>: Send, Sync);
+
 /// Identifier for a particular call to [`Queue::submit`]. Can be used
 /// as part of an argument to [`Device::poll`] to block for a particular
 /// submission to finish.
@@ -53,6 +59,22 @@ pub type PollType = wgt::PollType<SubmissionIndex>;
 #[cfg(send_sync)]
 static_assertions::assert_impl_all!(PollType: Send, Sync);
 
+pub use wgt::SparseBufferBinding as SparseBufferBindingBase;
+/// A change to the residency of a range of pages of a sparse buffer.
+///
+/// For use with [`Queue::update_sparse_residency`].
+pub type SparseBufferBinding<'a> = SparseBufferBindingBase<&'a Buffer>;
+#[cfg(send_sync)]
+static_assertions::assert_impl_all!(SparseBufferBinding<'_>: Send, Sync);
+
+pub use wgt::SparseTextureBinding as SparseTextureBindingBase;
+/// A change to the residency of a region of tiles of a sparse texture.
+///
+/// For use with [`Queue::update_sparse_residency`].
+pub type SparseTextureBinding<'a> = SparseTextureBindingBase<&'a Texture>;
+#[cfg(send_sync)]
+static_assertions::assert_impl_all!(SparseTextureBinding<'_>: Send, Sync);
+
 /// A write-only view into a staging buffer.
 ///
 /// Reading into this buffer won't yield the contents of the buffer from the
@@ -243,6 +265,30 @@ impl Queue {
             .copy_external_image_to_texture(source, dest, size);
     }
 
+    /// Changes which pages of sparse buffers and tiles of sparse textures are
+    /// backed by memory.
+    ///
+    /// Buffer ranges must be aligned to [`SPARSE_PAGE_SIZE`], and texture
+    /// regions to the [tile size](TextureFormat::sparse_tile_extent) of the
+    /// texture's format. Memory made resident is zeroed. Reads from
+    /// non-resident memory return zero, and writes to it are discarded.
+    ///
+    /// The changes take effect at the next [`Queue::submit`], before any of its
+    /// command buffers, and before writes queued with [`Queue::write_buffer`] or
+    /// [`Queue::write_texture`] since the last submission. In particular, such
+    /// writes to memory that wasn't resident when they were queued are
+    /// discarded.
+    ///
+    /// This can only be called on the queue returned by
+    /// [`Adapter::request_device`], and requires [`Features::SPARSE_RESOURCES`].
+    pub fn update_sparse_residency(
+        &self,
+        buffers: &[SparseBufferBinding<'_>],
+        textures: &[SparseTextureBinding<'_>],
+    ) {
+        self.inner.update_sparse_residency(buffers, textures);
+    }
+
     /// Submits a series of finished command buffers for execution.
     pub fn submit<I: IntoIterator<Item = CommandBuffer>>(
         &self,
@@ -255,6 +301,31 @@ impl Queue {
         SubmissionIndex { index }
     }
 
+    /// Creates an empty [`CommandEncoder`] whose command buffers are submitted to this queue.
+    ///
+    /// The encoder can only record work this queue supports: compute queues can't
+    /// record render passes, and transfer queues can only record copies and clears
+    /// of buffers. Command buffers from [`Device::create_command_encoder`] can only
+    /// be submitted to the queue returned by [`Adapter::request_device`].
+    #[must_use]
+    pub fn create_command_encoder(&self, desc: &CommandEncoderDescriptor<'_>) -> CommandEncoder {
+        let encoder = self.inner.create_command_encoder(desc);
+        CommandEncoder { inner: encoder }
+    }
+
+    /// Makes work submitted to this queue after this call wait until `submission_index`
+    /// has finished executing on `other`.
+    ///
+    /// This is how work on different queues is ordered. A buffer or texture used by a
+    /// submission on one queue can only be used on another queue once that queue has
+    /// waited for the submission.
+    ///
+    /// Requires [`Features::MULTIPLE_QUEUES`].
+    pub fn wait_for_submission(&self, other: &Queue, submission_index: &SubmissionIndex) {
+        self.inner
+            .wait_for_submission(&other.inner, submission_index.index);
+    }
+
     /// Gets the amount of nanoseconds each tick of a timestamp query represents.
     ///
     /// Returns zero if timestamp queries are unsupported.
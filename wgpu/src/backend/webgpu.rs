@@ -891,6 +891,135 @@ fn map_js_sys_limits(limits: &wgt::Limits) -> js_sys::Object {
     object
 }
 
+fn map_render_pipeline_descriptor(
+    desc: &crate::RenderPipelineDescriptor<'_>,
+) -> webgpu_sys::GpuRenderPipelineDescriptor {
+    let module = desc.vertex.module.inner.as_webgpu();
+    let mapped_vertex_state = webgpu_sys::GpuVertexState::new(&module.module);
+    insert_constants_map(
+        &mapped_vertex_state,
+        desc.vertex.compilation_options.constants,
+    );
+    if let Some(ep) = desc.vertex.entry_point {
+        mapped_vertex_state.set_entry_point(ep);
+    }
+
+    let buffers = desc
+        .vertex
+        .buffers
+        .iter()
+        .map(|vbuf| {
+            let mapped_attributes = vbuf
+                .attributes
+                .iter()
+                .map(|attr| {
+                    webgpu_sys::GpuVertexAttribute::new(
+                        map_vertex_format(attr.format),
+                        attr.offset as f64,
+                        attr.shader_location,
+                    )
+                })
+                .collect::<js_sys::Array>();
+
+            let mapped_vbuf = webgpu_sys::GpuVertexBufferLayout::new(
+                vbuf.array_stride as f64,
+                &mapped_attributes,
+            );
+            mapped_vbuf.set_step_mode(map_vertex_step_mode(vbuf.step_mode));
+            mapped_vbuf
+        })
+        .collect::<js_sys::Array>();
+
+    mapped_vertex_state.set_buffers(&buffers);
+
+    let auto_layout = wasm_bindgen::JsValue::from(webgpu_sys::GpuAutoLayoutMode::Auto);
+    let mapped_desc = webgpu_sys::GpuRenderPipelineDescriptor::new(
+        &match desc.layout {
+            Some(layout) => {
+                let layout = &layout.inner.as_webgpu().inner;
+                JsValue::from(layout)
+            }
+            None => auto_layout,
+        },
+        &mapped_vertex_state,
+    );
+
+    if let Some(label) = desc.label {
+        mapped_desc.set_label(label);
+    }
+
+    if let Some(ref depth_stencil) = desc.depth_stencil {
+        mapped_desc.set_depth_stencil(&map_depth_stencil_state(depth_stencil));
+    }
+
+    if let Some(ref frag) = desc.fragment {
+        let targets = frag
+            .targets
+            .iter()
+            .map(|target| match target {
+                Some(target) => {
+                    let mapped_format = map_texture_format(target.format);
+                    let mapped_color_state = webgpu_sys::GpuColorTargetState::new(mapped_format);
+                    if let Some(ref bs) = target.blend {
+                        let alpha = map_blend_component(&bs.alpha);
+                        let color = map_blend_component(&bs.color);
+                        let mapped_blend_state = webgpu_sys::GpuBlendState::new(&alpha, &color);
+                        mapped_color_state.set_blend(&mapped_blend_state);
+                    }
+                    mapped_color_state.set_write_mask(target.write_mask.bits());
+                    wasm_bindgen::JsValue::from(mapped_color_state)
+                }
+                None => wasm_bindgen::JsValue::null(),
+            })
+            .collect::<js_sys::Array>();
+        let module = frag.module.inner.as_webgpu();
+        let mapped_fragment_desc = webgpu_sys::GpuFragmentState::new(&module.module, &targets);
+        insert_constants_map(&mapped_fragment_desc, frag.compilation_options.constants);
+        if let Some(ep) = frag.entry_point {
+            mapped_fragment_desc.set_entry_point(ep);
+        }
+        mapped_desc.set_fragment(&mapped_fragment_desc);
+    }
+
+    let mapped_multisample = webgpu_sys::GpuMultisampleState::new();
+    mapped_multisample.set_count(desc.multisample.count);
+    mapped_multisample.set_mask(desc.multisample.mask as u32);
+    mapped_multisample.set_alpha_to_coverage_enabled(desc.multisample.alpha_to_coverage_enabled);
+    mapped_desc.set_multisample(&mapped_multisample);
+
+    let mapped_primitive = map_primitive_state(&desc.primitive);
+    mapped_desc.set_primitive(&mapped_primitive);
+
+    mapped_desc
+}
+
+fn map_compute_pipeline_descriptor(
+    desc: &crate::ComputePipelineDescriptor<'_>,
+) -> webgpu_sys::GpuComputePipelineDescriptor {
+    let shader_module = desc.module.inner.as_webgpu();
+    let mapped_compute_stage = webgpu_sys::GpuProgrammableStage::new(&shader_module.module);
+    insert_constants_map(&mapped_compute_stage, desc.compilation_options.constants);
+    if let Some(ep) = desc.entry_point {
+        mapped_compute_stage.set_entry_point(ep);
+    }
+    let auto_layout = wasm_bindgen::JsValue::from(webgpu_sys::GpuAutoLayoutMode::Auto);
+    let mapped_desc = webgpu_sys::GpuComputePipelineDescriptor::new(
+        &match desc.layout {
+            Some(layout) => {
+                let layout = &layout.inner.as_webgpu().inner;
+                JsValue::from(layout)
+            }
+            None => auto_layout,
+        },
+        &mapped_compute_stage,
+    );
+    if let Some(label) = desc.label {
+        mapped_desc.set_label(label);
+    }
+
+    mapped_desc
+}
+
 type JsFutureResult = Result<wasm_bindgen::JsValue, wasm_bindgen::JsValue>;
 
 fn future_request_adapter(
@@ -951,6 +1080,54 @@ fn future_request_device(
         })
 }
 
+fn future_create_render_pipeline(
+    result: JsFutureResult,
+) -> Result<dispatch::DispatchRenderPipeline, crate::Error> {
+    match result {
+        Ok(js_value) => Ok(WebRenderPipeline {
+            inner: webgpu_sys::GpuRenderPipeline::from(js_value),
+            ident: crate::cmp::Identifier::create(),
+        }
+        .into()),
+        Err(js_error) => Err(map_pipeline_error(js_error)),
+    }
+}
+
+fn future_create_compute_pipeline(
+    result: JsFutureResult,
+) -> Result<dispatch::DispatchComputePipeline, crate::Error> {
+    match result {
+        Ok(js_value) => Ok(WebComputePipeline {
+            inner: webgpu_sys::GpuComputePipeline::from(js_value),
+            ident: crate::cmp::Identifier::create(),
+        }
+        .into()),
+        Err(js_error) => Err(map_pipeline_error(js_error)),
+    }
+}
+
+/// Maps the `GPUPipelineError` a pipeline creation promise was rejected with.
+fn map_pipeline_error(js_error: wasm_bindgen::JsValue) -> crate::Error {
+    let source = Box::<dyn core::error::Error + Send + Sync>::from("<WebGPU Error>");
+    let get_string = |key: &str| {
+        js_sys::Reflect::get(&js_error, &key.into())
+            .ok()
+            .and_then(|value| value.as_string())
+    };
+    let description = get_string("message").unwrap_or_default();
+    // `webgpu_sys` has no binding for `GPUPipelineError`, so read its `reason` directly.
+    match get_string("reason").as_deref() {
+        Some("internal") => crate::Error::Internal {
+            source,
+            description,
+        },
+        _ => crate::Error::Validation {
+            source,
+            description,
+        },
+    }
+}
+
 fn future_pop_error_scope(result: JsFutureResult) -> Option<crate::Error> {
     match result {
         Ok(js_value) if js_value.is_object() => {
@@ -1290,6 +1467,12 @@ pub struct WebQuerySet {
     ident: crate::cmp::Identifier,
 }
 
+#[derive(Debug)]
+pub struct WebHeap {
+    /// Unique identifier for this Heap.
+    ident: crate::cmp::Identifier,
+}
+
 #[derive(Debug)]
 pub struct WebPipelineLayout {
     pub(crate) inner: webgpu_sys::GpuPipelineLayout,
@@ -1408,6 +1591,7 @@ impl_send_sync!(WebExternalTexture);
 impl_send_sync!(WebBlas);
 impl_send_sync!(WebTlas);
 impl_send_sync!(WebQuerySet);
+impl_send_sync!(WebHeap);
 impl_send_sync!(WebPipelineLayout);
 impl_send_sync!(WebRenderPipeline);
 impl_send_sync!(WebComputePipeline);
@@ -1438,6 +1622,7 @@ crate::cmp::impl_eq_ord_hash_proxy!(WebExternalTexture => .ident);
 crate::cmp::impl_eq_ord_hash_proxy!(WebBlas => .ident);
 crate::cmp::impl_eq_ord_hash_proxy!(WebTlas => .ident);
 crate::cmp::impl_eq_ord_hash_proxy!(WebQuerySet => .ident);
+crate::cmp::impl_eq_ord_hash_proxy!(WebHeap => .ident);
 crate::cmp::impl_eq_ord_hash_proxy!(WebPipelineLayout => .ident);
 crate::cmp::impl_eq_ord_hash_proxy!(WebRenderPipeline => .ident);
 crate::cmp::impl_eq_ord_hash_proxy!(WebComputePipeline => .ident);
@@ -1770,6 +1955,7 @@ impl dispatch::DeviceInterface for WebDevice {
                         .iter()
                         .map(|&(key, value)| (String::from(key), String::from(value)))
                         .collect(),
+                    include_resolver: None,
                 };
                 let mut parser = front::glsl::Frontend::default();
                 parser
@@ -2073,104 +2259,7 @@ impl dispatch::DeviceInterface for WebDevice {
         &self,
         desc: &crate::RenderPipelineDescriptor<'_>,
     ) -> dispatch::DispatchRenderPipeline {
-        let module = desc.vertex.module.inner.as_webgpu();
-        let mapped_vertex_state = webgpu_sys::GpuVertexState::new(&module.module);
-        insert_constants_map(
-            &mapped_vertex_state,
-            desc.vertex.compilation_options.constants,
-        );
-        if let Some(ep) = desc.vertex.entry_point {
-            mapped_vertex_state.set_entry_point(ep);
-        }
-
-        let buffers = desc
-            .vertex
-            .buffers
-            .iter()
-            .map(|vbuf| {
-                let mapped_attributes = vbuf
-                    .attributes
-                    .iter()
-                    .map(|attr| {
-                        webgpu_sys::GpuVertexAttribute::new(
-                            map_vertex_format(attr.format),
-                            attr.offset as f64,
-                            attr.shader_location,
-                        )
-                    })
-                    .collect::<js_sys::Array>();
-
-                let mapped_vbuf = webgpu_sys::GpuVertexBufferLayout::new(
-                    vbuf.array_stride as f64,
-                    &mapped_attributes,
-                );
-                mapped_vbuf.set_step_mode(map_vertex_step_mode(vbuf.step_mode));
-                mapped_vbuf
-            })
-            .collect::<js_sys::Array>();
-
-        mapped_vertex_state.set_buffers(&buffers);
-
-        let auto_layout = wasm_bindgen::JsValue::from(webgpu_sys::GpuAutoLayoutMode::Auto);
-        let mapped_desc = webgpu_sys::GpuRenderPipelineDescriptor::new(
-            &match desc.layout {
-                Some(layout) => {
-                    let layout = &layout.inner.as_webgpu().inner;
-                    JsValue::from(layout)
-                }
-                None => auto_layout,
-            },
-            &mapped_vertex_state,
-        );
-
-        if let Some(label) = desc.label {
-            mapped_desc.set_label(label);
-        }
-
-        if let Some(ref depth_stencil) = desc.depth_stencil {
-            mapped_desc.set_depth_stencil(&map_depth_stencil_state(depth_stencil));
-        }
-
-        if let Some(ref frag) = desc.fragment {
-            let targets = frag
-                .targets
-                .iter()
-                .map(|target| match target {
-                    Some(target) => {
-                        let mapped_format = map_texture_format(target.format);
-                        let mapped_color_state =
-                            webgpu_sys::GpuColorTargetState::new(mapped_format);
-                        if let Some(ref bs) = target.blend {
-                            let alpha = map_blend_component(&bs.alpha);
-                            let color = map_blend_component(&bs.color);
-                            let mapped_blend_state = webgpu_sys::GpuBlendState::new(&alpha, &color);
-                            mapped_color_state.set_blend(&mapped_blend_state);
-                        }
-                        mapped_color_state.set_write_mask(target.write_mask.bits());
-                        wasm_bindgen::JsValue::from(mapped_color_state)
-                    }
-                    None => wasm_bindgen::JsValue::null(),
-                })
-                .collect::<js_sys::Array>();
-            let module = frag.module.inner.as_webgpu();
-            let mapped_fragment_desc = webgpu_sys::GpuFragmentState::new(&module.module, &targets);
-            insert_constants_map(&mapped_fragment_desc, frag.compilation_options.constants);
-            if let Some(ep) = frag.entry_point {
-                mapped_fragment_desc.set_entry_point(ep);
-            }
-            mapped_desc.set_fragment(&mapped_fragment_desc);
-        }
-
-        let mapped_multisample = webgpu_sys::GpuMultisampleState::new();
-        mapped_multisample.set_count(desc.multisample.count);
-        mapped_multisample.set_mask(desc.multisample.mask as u32);
-        mapped_multisample
-            .set_alpha_to_coverage_enabled(desc.multisample.alpha_to_coverage_enabled);
-        mapped_desc.set_multisample(&mapped_multisample);
-
-        let mapped_primitive = map_primitive_state(&desc.primitive);
-        mapped_desc.set_primitive(&mapped_primitive);
-
+        let mapped_desc = map_render_pipeline_descriptor(desc);
         let render_pipeline = self.inner.create_render_pipeline(&mapped_desc).unwrap();
 
         WebRenderPipeline {
@@ -2180,6 +2269,18 @@ impl dispatch::DeviceInterface for WebDevice {
         .into()
     }
 
+    fn create_render_pipeline_async(
+        &self,
+        desc: &crate::RenderPipelineDescriptor<'_>,
+    ) -> Pin<Box<dyn dispatch::CreateRenderPipelineFuture>> {
+        let mapped_desc = map_render_pipeline_descriptor(desc);
+        let pipeline_promise = self.inner.create_render_pipeline_async(&mapped_desc);
+        Box::pin(MakeSendFuture::new(
+            wasm_bindgen_futures::JsFuture::from(pipeline_promise),
+            future_create_render_pipeline,
+        ))
+    }
+
     fn create_mesh_pipeline(
         &self,
         _desc: &crate::MeshPipelineDescriptor<'_>,
@@ -2187,31 +2288,22 @@ impl dispatch::DeviceInterface for WebDevice {
         panic!("MESH_SHADER feature must be enabled to call create_mesh_pipeline")
     }
 
+    fn derive_bind_group_layouts(
+        &self,
+        _entry_points: &[(&crate::ShaderModule, &str)],
+    ) -> Result<Vec<Vec<crate::BindGroupLayoutEntry>>, crate::Error> {
+        let description = String::from("Deriving bind group layouts is not supported on the web");
+        Err(crate::Error::Internal {
+            source: Box::<dyn core::error::Error + Send + Sync>::from(description.clone()),
+            description,
+        })
+    }
+
     fn create_compute_pipeline(
         &self,
         desc: &crate::ComputePipelineDescriptor<'_>,
     ) -> dispatch::DispatchComputePipeline {
-        let shader_module = desc.module.inner.as_webgpu();
-        let mapped_compute_stage = webgpu_sys::GpuProgrammableStage::new(&shader_module.module);
-        insert_constants_map(&mapped_compute_stage, desc.compilation_options.constants);
-        if let Some(ep) = desc.entry_point {
-            mapped_compute_stage.set_entry_point(ep);
-        }
-        let auto_layout = wasm_bindgen::JsValue::from(webgpu_sys::GpuAutoLayoutMode::Auto);
-        let mapped_desc = webgpu_sys::GpuComputePipelineDescriptor::new(
-            &match desc.layout {
-                Some(layout) => {
-                    let layout = &layout.inner.as_webgpu().inner;
-                    JsValue::from(layout)
-                }
-                None => auto_layout,
-            },
-            &mapped_compute_stage,
-        );
-        if let Some(label) = desc.label {
-            mapped_desc.set_label(label);
-        }
-
+        let mapped_desc = map_compute_pipeline_descriptor(desc);
         let compute_pipeline = self.inner.create_compute_pipeline(&mapped_desc);
 
         WebComputePipeline {
@@ -2221,6 +2313,18 @@ impl dispatch::DeviceInterface for WebDevice {
         .into()
     }
 
+    fn create_compute_pipeline_async(
+        &self,
+        desc: &crate::ComputePipelineDescriptor<'_>,
+    ) -> Pin<Box<dyn dispatch::CreateComputePipelineFuture>> {
+        let mapped_desc = map_compute_pipeline_descriptor(desc);
+        let pipeline_promise = self.inner.create_compute_pipeline_async(&mapped_desc);
+        Box::pin(MakeSendFuture::new(
+            wasm_bindgen_futures::JsFuture::from(pipeline_promise),
+            future_create_compute_pipeline,
+        ))
+    }
+
     unsafe fn create_pipeline_cache(
         &self,
         _desc: &crate::PipelineCacheDescriptor<'_>,
@@ -2241,10 +2345,11 @@ impl dispatch::DeviceInterface for WebDevice {
     }
 
     fn create_texture(&self, desc: &crate::TextureDescriptor<'_>) -> dispatch::DispatchTexture {
+        // Transient textures are regular render attachments on the web.
         let mapped_desc = webgpu_sys::GpuTextureDescriptor::new(
             map_texture_format(desc.format),
             &map_extent_3d(desc.size),
-            desc.usage.bits(),
+            (desc.usage - crate::TextureUsages::TRANSIENT).bits(),
         );
         if let Some(label) = desc.label {
             mapped_desc.set_label(label);
@@ -2334,6 +2439,24 @@ impl dispatch::DeviceInterface for WebDevice {
         .into()
     }
 
+    fn create_heap(&self, _desc: &crate::HeapDescriptor<'_>) -> dispatch::DispatchHeap {
+        unimplemented!("Resource heaps are not supported on the web")
+    }
+
+    fn get_buffer_memory_requirements(
+        &self,
+        _desc: &crate::BufferDescriptor<'_>,
+    ) -> Option<crate::MemoryRequirements> {
+        unimplemented!("Resource heaps are not supported on the web")
+    }
+
+    fn get_texture_memory_requirements(
+        &self,
+        _desc: &crate::TextureDescriptor<'_>,
+    ) -> Option<crate::MemoryRequirements> {
+        unimplemented!("Resource heaps are not supported on the web")
+    }
+
     fn create_command_encoder(
         &self,
         desc: &crate::CommandEncoderDescriptor<'_>,
@@ -2354,6 +2477,11 @@ impl dispatch::DeviceInterface for WebDevice {
         .into()
     }
 
+    fn create_queue(&self, _desc: &crate::QueueDescriptor<'_>) -> Option<dispatch::DispatchQueue> {
+        // WebGPU devices only have a single queue.
+        None
+    }
+
     fn create_render_bundle_encoder(
         &self,
         desc: &crate::RenderBundleEncoderDescriptor<'_>,
@@ -2601,6 +2729,14 @@ impl dispatch::QueueInterface for WebQueue {
             .unwrap();
     }
 
+    fn update_sparse_residency(
+        &self,
+        _buffers: &[crate::SparseBufferBinding<'_>],
+        _textures: &[crate::SparseTextureBinding<'_>],
+    ) {
+        unimplemented!("Sparse resources are not supported on the web")
+    }
+
     fn submit(
         &self,
         command_buffers: &mut dyn Iterator<Item = dispatch::DispatchCommandBuffer>,
@@ -2617,6 +2753,17 @@ impl dispatch::QueueInterface for WebQueue {
         0
     }
 
+    fn create_command_encoder(
+        &self,
+        _desc: &crate::CommandEncoderDescriptor<'_>,
+    ) -> dispatch::DispatchCommandEncoder {
+        unimplemented!("Creating command encoders from a queue is not supported on the web")
+    }
+
+    fn wait_for_submission(&self, _other: &dispatch::DispatchQueue, _submission_index: u64) {
+        unimplemented!("Multiple queues are not supported on the web")
+    }
+
     fn get_timestamp_period(&self) -> f32 {
         // Timestamp values are always in nanoseconds, see https://gpuweb.github.io/gpuweb/#timestamp
         1.0
@@ -2826,6 +2973,33 @@ impl Drop for WebQuerySet {
     }
 }
 
+impl dispatch::HeapInterface for WebHeap {
+    fn create_buffer(
+        &self,
+        _offset: crate::BufferAddress,
+        _desc: &crate::BufferDescriptor<'_>,
+    ) -> dispatch::DispatchBuffer {
+        unimplemented!("Resource heaps are not supported on the web")
+    }
+
+    fn create_texture(
+        &self,
+        _offset: crate::BufferAddress,
+        _desc: &crate::TextureDescriptor<'_>,
+    ) -> dispatch::DispatchTexture {
+        unimplemented!("Resource heaps are not supported on the web")
+    }
+
+    fn generate_allocation_report(&self) -> Option<crate::AllocatorReport> {
+        unimplemented!("Resource heaps are not supported on the web")
+    }
+}
+impl Drop for WebHeap {
+    fn drop(&mut self) {
+        // no-op
+    }
+}
+
 impl dispatch::PipelineLayoutInterface for WebPipelineLayout {}
 impl Drop for WebPipelineLayout {
     fn drop(&mut self) {
@@ -3119,6 +3293,14 @@ impl dispatch::CommandEncoderInterface for WebCommandEncoder {
         }
     }
 
+    fn aliasing_barrier<'a>(
+        &self,
+        _buffers: &mut dyn Iterator<Item = &'a dispatch::DispatchBuffer>,
+        _textures: &mut dyn Iterator<Item = &'a dispatch::DispatchTexture>,
+    ) {
+        unimplemented!("Resource heaps are not supported on the web")
+    }
+
     fn insert_debug_marker(&self, _label: &str) {
         // Not available in gecko yet
         // self.insert_debug_marker(label);
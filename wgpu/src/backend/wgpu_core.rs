@@ -10,11 +10,12 @@ use alloc::{
 use core::{
     error::Error,
     fmt,
-    future::ready,
+    future::{ready, Future},
     ops::{Deref, Range},
     pin::Pin,
     ptr::NonNull,
     slice,
+    task::{Context, Poll, Waker},
 };
 
 use arrayvec::ArrayVec;
@@ -276,12 +277,26 @@ impl ContextWgpuCore {
         label: Label<'_>,
         fn_ident: &'static str,
     ) {
+        let mut sink = sink_mutex.lock();
+        let Some(error) = self.make_error(error_type, source, label, fn_ident) else {
+            return; // will be surfaced via callback
+        };
+        sink.handle_error(error);
+    }
+
+    /// Builds the [`crate::Error`] for `source`, or returns `None` if the device was lost.
+    fn make_error(
+        &self,
+        error_type: ErrorType,
+        source: ContextErrorSource,
+        label: Label<'_>,
+        fn_ident: &'static str,
+    ) -> Option<crate::Error> {
         let source: ErrorSource = Box::new(wgc::error::ContextError {
             fn_ident,
             source,
             label: label.unwrap_or_default().to_string(),
         });
-        let mut sink = sink_mutex.lock();
         let description = || self.format_error(&*source);
         let error = match error_type {
             ErrorType::Internal => {
@@ -299,9 +314,9 @@ impl ContextWgpuCore {
                     description,
                 }
             }
-            ErrorType::DeviceLost => return, // will be surfaced via callback
+            ErrorType::DeviceLost => return None,
         };
-        sink.handle_error(error);
+        Some(error)
     }
 
     #[inline]
@@ -377,6 +392,120 @@ impl ContextWgpuCore {
     }
 }
 
+fn map_vertex_buffer_layouts<'a>(
+    buffers: &[crate::VertexBufferLayout<'a>],
+) -> ArrayVec<wgc::pipeline::VertexBufferLayout<'a>, { wgc::MAX_VERTEX_BUFFERS }> {
+    buffers
+        .iter()
+        .map(|vbuf| wgc::pipeline::VertexBufferLayout {
+            array_stride: vbuf.array_stride,
+            step_mode: vbuf.step_mode,
+            attributes: Borrowed(vbuf.attributes),
+        })
+        .collect()
+}
+
+fn map_render_pipeline_descriptor<'a>(
+    desc: &crate::RenderPipelineDescriptor<'a>,
+    vertex_buffers: &'a [wgc::pipeline::VertexBufferLayout<'a>],
+) -> wgc::pipeline::RenderPipelineDescriptor<'a> {
+    use wgc::pipeline as pipe;
+
+    let vert_constants = desc
+        .vertex
+        .compilation_options
+        .constants
+        .iter()
+        .map(|&(key, value)| (String::from(key), value))
+        .collect();
+
+    pipe::RenderPipelineDescriptor {
+        label: desc.label.map(Borrowed),
+        layout: desc.layout.map(|layout| layout.inner.as_core().id),
+        vertex: pipe::VertexState {
+            stage: pipe::ProgrammableStageDescriptor {
+                module: desc.vertex.module.inner.as_core().id,
+                entry_point: desc.vertex.entry_point.map(Borrowed),
+                constants: vert_constants,
+                zero_initialize_workgroup_memory: desc
+                    .vertex
+                    .compilation_options
+                    .zero_initialize_workgroup_memory,
+            },
+            buffers: Borrowed(vertex_buffers),
+        },
+        primitive: desc.primitive,
+        depth_stencil: desc.depth_stencil.clone(),
+        multisample: desc.multisample,
+        fragment: desc.fragment.as_ref().map(|frag| {
+            let frag_constants = frag
+                .compilation_options
+                .constants
+                .iter()
+                .map(|&(key, value)| (String::from(key), value))
+                .collect();
+            pipe::FragmentState {
+                stage: pipe::ProgrammableStageDescriptor {
+                    module: frag.module.inner.as_core().id,
+                    entry_point: frag.entry_point.map(Borrowed),
+                    constants: frag_constants,
+                    zero_initialize_workgroup_memory: frag
+                        .compilation_options
+                        .zero_initialize_workgroup_memory,
+                },
+                targets: Borrowed(frag.targets),
+            }
+        }),
+        multiview: desc.multiview,
+        cache: desc.cache.map(|cache| cache.inner.as_core().id),
+    }
+}
+
+fn map_compute_pipeline_descriptor<'a>(
+    desc: &crate::ComputePipelineDescriptor<'a>,
+) -> wgc::pipeline::ComputePipelineDescriptor<'a> {
+    use wgc::pipeline as pipe;
+
+    let constants = desc
+        .compilation_options
+        .constants
+        .iter()
+        .map(|&(key, value)| (String::from(key), value))
+        .collect();
+
+    pipe::ComputePipelineDescriptor {
+        label: desc.label.map(Borrowed),
+        layout: desc.layout.map(|pll| pll.inner.as_core().id),
+        stage: pipe::ProgrammableStageDescriptor {
+            module: desc.module.inner.as_core().id,
+            entry_point: desc.entry_point.map(Borrowed),
+            constants,
+            zero_initialize_workgroup_memory: desc
+                .compilation_options
+                .zero_initialize_workgroup_memory,
+        },
+        cache: desc.cache.map(|cache| cache.inner.as_core().id),
+    }
+}
+
+fn log_render_pipeline_error(cause: &wgc::pipeline::CreateRenderPipelineError) {
+    if let wgc::pipeline::CreateRenderPipelineError::Internal { stage, error } = cause {
+        log::error!("Shader translation error for stage {stage:?}: {error}");
+        log::error!("Please report it to https://github.com/gfx-rs/wgpu");
+    }
+}
+
+fn log_compute_pipeline_error(cause: &wgc::pipeline::CreateComputePipelineError) {
+    if let wgc::pipeline::CreateComputePipelineError::Internal(error) = cause {
+        log::error!(
+            "Shader translation error for stage {:?}: {}",
+            wgt::ShaderStages::COMPUTE,
+            error
+        );
+        log::error!("Please report it to https://github.com/gfx-rs/wgpu");
+    }
+}
+
 fn map_buffer_copy_view(view: crate::TexelCopyBufferInfo<'_>) -> wgc::command::TexelCopyBufferInfo {
     wgc::command::TexelCopyBufferInfo {
         buffer: view.buffer.inner.as_core().id,
@@ -514,6 +643,13 @@ pub struct CoreQuerySet {
     id: wgc::id::QuerySetId,
 }
 
+#[derive(Debug)]
+pub struct CoreHeap {
+    pub(crate) context: ContextWgpuCore,
+    id: wgc::id::HeapId,
+    error_sink: ErrorSink,
+}
+
 #[derive(Debug)]
 pub struct CorePipelineLayout {
     pub(crate) context: ContextWgpuCore,
@@ -670,6 +806,59 @@ fn default_error_handler(err: crate::Error) {
     panic!("wgpu error: {err}\n");
 }
 
+/// A future that completes once the function returned by [`CallbackFuture::resolver`] is called.
+///
+/// Used to wait on `wgpu-core` calls that report their result through a callback,
+/// which may be called from another thread.
+struct CallbackFuture<T> {
+    state: Arc<Mutex<CallbackFutureState<T>>>,
+}
+
+struct CallbackFutureState<T> {
+    result: Option<T>,
+    waker: Option<Waker>,
+}
+
+impl<T> CallbackFuture<T> {
+    fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(CallbackFutureState {
+                result: None,
+                waker: None,
+            })),
+        }
+    }
+
+    fn resolver(&self) -> impl FnOnce(T) {
+        let state = Arc::clone(&self.state);
+        move |result| {
+            let waker = {
+                let mut state = state.lock();
+                state.result = Some(result);
+                state.waker.take()
+            };
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<T> Future for CallbackFuture<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
 impl From<CreateShaderModuleError> for CompilationInfo {
     fn from(value: CreateShaderModuleError) -> Self {
         match value {
@@ -738,6 +927,7 @@ crate::cmp::impl_eq_ord_hash_proxy!(CoreExternalTexture => .id);
 crate::cmp::impl_eq_ord_hash_proxy!(CoreBlas => .id);
 crate::cmp::impl_eq_ord_hash_proxy!(CoreTlas => .id);
 crate::cmp::impl_eq_ord_hash_proxy!(CoreQuerySet => .id);
+crate::cmp::impl_eq_ord_hash_proxy!(CoreHeap => .id);
 crate::cmp::impl_eq_ord_hash_proxy!(CorePipelineLayout => .id);
 crate::cmp::impl_eq_ord_hash_proxy!(CoreRenderPipeline => .id);
 crate::cmp::impl_eq_ord_hash_proxy!(CoreComputePipeline => .id);
@@ -872,6 +1062,9 @@ impl dispatch::InstanceInterface for ContextWgpuCore {
                     ImplementedLanguageExtension::Packed4x8IntegerDotProduct => {
                         crate::WgslLanguageFeatures::Packed4x8IntegerDotProduct
                     }
+                    ImplementedLanguageExtension::UnrestrictedPointerParameters => {
+                        crate::WgslLanguageFeatures::UnrestrictedPointerParameters
+                    }
                     ImplementedLanguageExtension::PointerCompositeAccess => {
                         crate::WgslLanguageFeatures::PointerCompositeAccess
                     }
@@ -1017,6 +1210,7 @@ impl dispatch::DeviceInterface for CoreDevice {
                         .iter()
                         .map(|&(key, value)| (String::from(key), String::from(value)))
                         .collect(),
+                    include_resolver: None,
                 };
                 wgc::pipeline::ShaderModuleSource::Glsl(Borrowed(shader), options)
             }
@@ -1268,77 +1462,15 @@ impl dispatch::DeviceInterface for CoreDevice {
         &self,
         desc: &crate::RenderPipelineDescriptor<'_>,
     ) -> dispatch::DispatchRenderPipeline {
-        use wgc::pipeline as pipe;
-
-        let vertex_buffers: ArrayVec<_, { wgc::MAX_VERTEX_BUFFERS }> = desc
-            .vertex
-            .buffers
-            .iter()
-            .map(|vbuf| pipe::VertexBufferLayout {
-                array_stride: vbuf.array_stride,
-                step_mode: vbuf.step_mode,
-                attributes: Borrowed(vbuf.attributes),
-            })
-            .collect();
-
-        let vert_constants = desc
-            .vertex
-            .compilation_options
-            .constants
-            .iter()
-            .map(|&(key, value)| (String::from(key), value))
-            .collect();
-
-        let descriptor = pipe::RenderPipelineDescriptor {
-            label: desc.label.map(Borrowed),
-            layout: desc.layout.map(|layout| layout.inner.as_core().id),
-            vertex: pipe::VertexState {
-                stage: pipe::ProgrammableStageDescriptor {
-                    module: desc.vertex.module.inner.as_core().id,
-                    entry_point: desc.vertex.entry_point.map(Borrowed),
-                    constants: vert_constants,
-                    zero_initialize_workgroup_memory: desc
-                        .vertex
-                        .compilation_options
-                        .zero_initialize_workgroup_memory,
-                },
-                buffers: Borrowed(&vertex_buffers),
-            },
-            primitive: desc.primitive,
-            depth_stencil: desc.depth_stencil.clone(),
-            multisample: desc.multisample,
-            fragment: desc.fragment.as_ref().map(|frag| {
-                let frag_constants = frag
-                    .compilation_options
-                    .constants
-                    .iter()
-                    .map(|&(key, value)| (String::from(key), value))
-                    .collect();
-                pipe::FragmentState {
-                    stage: pipe::ProgrammableStageDescriptor {
-                        module: frag.module.inner.as_core().id,
-                        entry_point: frag.entry_point.map(Borrowed),
-                        constants: frag_constants,
-                        zero_initialize_workgroup_memory: frag
-                            .compilation_options
-                            .zero_initialize_workgroup_memory,
-                    },
-                    targets: Borrowed(frag.targets),
-                }
-            }),
-            multiview: desc.multiview,
-            cache: desc.cache.map(|cache| cache.inner.as_core().id),
-        };
+        let vertex_buffers = map_vertex_buffer_layouts(desc.vertex.buffers);
+        let descriptor = map_render_pipeline_descriptor(desc, &vertex_buffers);
 
         let (id, error) = self
             .context
             .0
             .device_create_render_pipeline(self.id, &descriptor, None);
         if let Some(cause) = error {
-            if let wgc::pipeline::CreateRenderPipelineError::Internal { stage, ref error } = cause {
-                log::error!("Shader translation error for stage {stage:?}: {error}");
-                log::error!("Please report it to https://github.com/gfx-rs/wgpu");
-            }
+            log_render_pipeline_error(&cause);
             self.context.handle_error(
                 &self.error_sink,
                 cause,
@@ -1354,6 +1486,46 @@ impl dispatch::DeviceInterface for CoreDevice {
         .into()
     }
 
+    fn create_render_pipeline_async(
+        &self,
+        desc: &crate::RenderPipelineDescriptor<'_>,
+    ) -> Pin<Box<dyn dispatch::CreateRenderPipelineFuture>> {
+        let vertex_buffers = map_vertex_buffer_layouts(desc.vertex.buffers);
+        let descriptor = map_render_pipeline_descriptor(desc, &vertex_buffers);
+
+        let future = CallbackFuture::new();
+        let resolve = future.resolver();
+        let context = self.context.clone();
+        let error_sink = Arc::clone(&self.error_sink);
+        let label = desc.label.map(String::from);
+        self.context.0.device_create_render_pipeline_async(
+            self.id,
+            &descriptor,
+            None,
+            Box::new(move |id, error| {
+                let error = error.and_then(|cause| {
+                    log_render_pipeline_error(&cause);
+                    context.make_error(
+                        cause.webgpu_error_type(),
+                        Box::new(cause),
+                        label.as_deref(),
+                        "Device::create_render_pipeline_async",
+                    )
+                });
+                let pipeline = CoreRenderPipeline {
+                    context,
+                    id,
+                    error_sink,
+                };
+                resolve(match error {
+                    Some(error) => Err(error),
+                    None => Ok(pipeline.into()),
+                });
+            }),
+        );
+        Box::pin(future)
+    }
+
     fn create_mesh_pipeline(
         &self,
         desc: &crate::MeshPipelineDescriptor<'_>,
@@ -1431,10 +1603,7 @@ impl dispatch::DeviceInterface for CoreDevice {
             .0
             .device_create_mesh_pipeline(self.id, &descriptor, None);
         if let Some(cause) = error {
-            if let wgc::pipeline::CreateRenderPipelineError::Internal { stage, ref error } = cause {
-                log::error!("Shader translation error for stage {stage:?}: {error}");
-                log::error!("Please report it to https://github.com/gfx-rs/wgpu");
-            }
+            log_render_pipeline_error(&cause);
             self.context.handle_error(
                 &self.error_sink,
                 cause,
@@ -1454,42 +1623,14 @@ impl dispatch::DeviceInterface for CoreDevice {
         &self,
         desc: &crate::ComputePipelineDescriptor<'_>,
     ) -> dispatch::DispatchComputePipeline {
-        use wgc::pipeline as pipe;
-
-        let constants = desc
-            .compilation_options
-            .constants
-            .iter()
-            .map(|&(key, value)| (String::from(key), value))
-            .collect();
-
-        let descriptor = pipe::ComputePipelineDescriptor {
-            label: desc.label.map(Borrowed),
-            layout: desc.layout.map(|pll| pll.inner.as_core().id),
-            stage: pipe::ProgrammableStageDescriptor {
-                module: desc.module.inner.as_core().id,
-                entry_point: desc.entry_point.map(Borrowed),
-                constants,
-                zero_initialize_workgroup_memory: desc
-                    .compilation_options
-                    .zero_initialize_workgroup_memory,
-            },
-            cache: desc.cache.map(|cache| cache.inner.as_core().id),
-        };
+        let descriptor = map_compute_pipeline_descriptor(desc);
 
         let (id, error) = self
             .context
             .0
             .device_create_compute_pipeline(self.id, &descriptor, None);
         if let Some(cause) = error {
-            if let wgc::pipeline::CreateComputePipelineError::Internal(ref error) = cause {
-                log::error!(
-                    "Shader translation error for stage {:?}: {}",
-                    wgt::ShaderStages::COMPUTE,
-                    error
-                );
-                log::error!("Please report it to https://github.com/gfx-rs/wgpu");
-            }
+            log_compute_pipeline_error(&cause);
             self.context.handle_error(
                 &self.error_sink,
                 cause,
@@ -1505,6 +1646,45 @@ impl dispatch::DeviceInterface for CoreDevice {
         .into()
     }
 
+    fn create_compute_pipeline_async(
+        &self,
+        desc: &crate::ComputePipelineDescriptor<'_>,
+    ) -> Pin<Box<dyn dispatch::CreateComputePipelineFuture>> {
+        let descriptor = map_compute_pipeline_descriptor(desc);
+
+        let future = CallbackFuture::new();
+        let resolve = future.resolver();
+        let context = self.context.clone();
+        let error_sink = Arc::clone(&self.error_sink);
+        let label = desc.label.map(String::from);
+        self.context.0.device_create_compute_pipeline_async(
+            self.id,
+            &descriptor,
+            None,
+            Box::new(move |id, error| {
+                let error = error.and_then(|cause| {
+                    log_compute_pipeline_error(&cause);
+                    context.make_error(
+                        cause.webgpu_error_type(),
+                        Box::new(cause),
+                        label.as_deref(),
+                        "Device::create_compute_pipeline_async",
+                    )
+                });
+                let pipeline = CoreComputePipeline {
+                    context,
+                    id,
+                    error_sink,
+                };
+                resolve(match error {
+                    Some(error) => Err(error),
+                    None => Ok(pipeline.into()),
+                });
+            }),
+        );
+        Box::pin(future)
+    }
+
     unsafe fn create_pipeline_cache(
         &self,
         desc: &crate::PipelineCacheDescriptor<'_>,
@@ -1536,6 +1716,35 @@ impl dispatch::DeviceInterface for CoreDevice {
         .into()
     }
 
+    fn derive_bind_group_layouts(
+        &self,
+        entry_points: &[(&crate::ShaderModule, &str)],
+    ) -> Result<Vec<Vec<crate::BindGroupLayoutEntry>>, crate::Error> {
+        let entry_points = entry_points
+            .iter()
+            .map(|&(module, entry_point)| (module.inner.as_core().id, entry_point))
+            .collect::<Vec<_>>();
+        self.context
+            .0
+            .device_derive_bind_group_layouts(self.id, &entry_points)
+            .map_err(|cause| {
+                // There are no layouts to return from a lost device either, so
+                // it is reported as an internal error rather than ignored.
+                let error_type = match cause.webgpu_error_type() {
+                    ErrorType::DeviceLost => ErrorType::Internal,
+                    error_type => error_type,
+                };
+                self.context
+                    .make_error(
+                        error_type,
+                        Box::new(cause),
+                        None,
+                        "Device::derive_bind_group_layouts",
+                    )
+                    .expect("only device lost errors are dropped")
+            })
+    }
+
     fn create_buffer(&self, desc: &crate::BufferDescriptor<'_>) -> dispatch::DispatchBuffer {
         let (id, error) = self.context.0.device_create_buffer(
             self.id,
@@ -1701,6 +1910,68 @@ impl dispatch::DeviceInterface for CoreDevice {
         .into()
     }
 
+    fn create_heap(&self, desc: &crate::HeapDescriptor<'_>) -> dispatch::DispatchHeap {
+        let (id, error) =
+            self.context
+                .0
+                .device_create_heap(self.id, &desc.map_label(|l| l.map(Borrowed)), None);
+        if let Some(cause) = error {
+            self.context
+                .handle_error(&self.error_sink, cause, desc.label, "Device::create_heap");
+        }
+        CoreHeap {
+            context: self.context.clone(),
+            id,
+            error_sink: Arc::clone(&self.error_sink),
+        }
+        .into()
+    }
+
+    fn get_buffer_memory_requirements(
+        &self,
+        desc: &crate::BufferDescriptor<'_>,
+    ) -> Option<crate::MemoryRequirements> {
+        match self
+            .context
+            .0
+            .device_get_buffer_memory_requirements(self.id, &desc.map_label(|l| l.map(Borrowed)))
+        {
+            Ok(requirements) => Some(requirements),
+            Err(cause) => {
+                self.context.handle_error(
+                    &self.error_sink,
+                    cause,
+                    desc.label,
+                    "Device::get_buffer_memory_requirements",
+                );
+                None
+            }
+        }
+    }
+
+    fn get_texture_memory_requirements(
+        &self,
+        desc: &crate::TextureDescriptor<'_>,
+    ) -> Option<crate::MemoryRequirements> {
+        let wgt_desc = desc.map_label_and_view_formats(|l| l.map(Borrowed), |v| v.to_vec());
+        match self
+            .context
+            .0
+            .device_get_texture_memory_requirements(self.id, &wgt_desc)
+        {
+            Ok(requirements) => Some(requirements),
+            Err(cause) => {
+                self.context.handle_error(
+                    &self.error_sink,
+                    cause,
+                    desc.label,
+                    "Device::get_texture_memory_requirements",
+                );
+                None
+            }
+        }
+    }
+
     fn create_command_encoder(
         &self,
         desc: &crate::CommandEncoderDescriptor<'_>,
@@ -1751,6 +2022,33 @@ impl dispatch::DeviceInterface for CoreDevice {
         .into()
     }
 
+    fn create_queue(&self, desc: &crate::QueueDescriptor<'_>) -> Option<dispatch::DispatchQueue> {
+        match self.context.0.device_create_queue(
+            self.id,
+            &desc.map_label(|l| l.map(Borrowed)),
+            None,
+        ) {
+            Ok(id) => Some(
+                CoreQueue {
+                    context: self.context.clone(),
+                    id,
+                    error_sink: Arc::clone(&self.error_sink),
+                }
+                .into(),
+            ),
+            Err(wgc::device::queue::CreateQueueError::Unavailable(_)) => None,
+            Err(cause) => {
+                self.context.handle_error(
+                    &self.error_sink,
+                    cause,
+                    desc.label,
+                    "Device::create_queue",
+                );
+                None
+            }
+        }
+    }
+
     fn set_device_lost_callback(&self, device_lost_callback: dispatch::BoxDeviceLostCallback) {
         self.context
             .0
@@ -1975,6 +2273,45 @@ impl dispatch::QueueInterface for CoreQueue {
         }
     }
 
+    fn update_sparse_residency(
+        &self,
+        buffers: &[crate::SparseBufferBinding<'_>],
+        textures: &[crate::SparseTextureBinding<'_>],
+    ) {
+        let buffers = buffers
+            .iter()
+            .map(|binding| wgt::SparseBufferBinding {
+                buffer: binding.buffer.inner.as_core().id,
+                offset: binding.offset,
+                size: binding.size,
+                resident: binding.resident,
+            })
+            .collect::<SmallVec<[_; 4]>>();
+        let textures = textures
+            .iter()
+            .map(|binding| wgt::SparseTextureBinding {
+                texture: binding.texture.inner.as_core().id,
+                mip_level: binding.mip_level,
+                array_layer: binding.array_layer,
+                origin: binding.origin,
+                size: binding.size,
+                resident: binding.resident,
+            })
+            .collect::<SmallVec<[_; 4]>>();
+
+        if let Err(cause) = self
+            .context
+            .0
+            .queue_update_sparse_residency(self.id, &buffers, &textures)
+        {
+            self.context.handle_error_nolabel(
+                &self.error_sink,
+                cause,
+                "Queue::update_sparse_residency",
+            );
+        }
+    }
+
     fn submit(
         &self,
         command_buffers: &mut dyn Iterator<Item = dispatch::DispatchCommandBuffer>,
@@ -1999,6 +2336,46 @@ impl dispatch::QueueInterface for CoreQueue {
         index
     }
 
+    fn create_command_encoder(
+        &self,
+        desc: &crate::CommandEncoderDescriptor<'_>,
+    ) -> dispatch::DispatchCommandEncoder {
+        let (id, error) = self.context.0.queue_create_command_encoder(
+            self.id,
+            &desc.map_label(|l| l.map(Borrowed)),
+            None,
+        );
+        if let Some(cause) = error {
+            self.context.handle_error(
+                &self.error_sink,
+                cause,
+                desc.label,
+                "Queue::create_command_encoder",
+            );
+        }
+
+        CoreCommandEncoder {
+            context: self.context.clone(),
+            id,
+            error_sink: Arc::clone(&self.error_sink),
+        }
+        .into()
+    }
+
+    fn wait_for_submission(&self, other: &dispatch::DispatchQueue, submission_index: u64) {
+        if let Err(cause) =
+            self.context
+                .0
+                .queue_wait_for_submission(self.id, other.as_core().id, submission_index)
+        {
+            self.context.handle_error_nolabel(
+                &self.error_sink,
+                cause,
+                "Queue::wait_for_submission",
+            );
+        }
+    }
+
     fn get_timestamp_period(&self) -> f32 {
         self.context.0.queue_get_timestamp_period(self.id)
     }
@@ -2268,6 +2645,65 @@ impl Drop for CoreQuerySet {
     }
 }
 
+impl dispatch::HeapInterface for CoreHeap {
+    fn create_buffer(
+        &self,
+        offset: crate::BufferAddress,
+        desc: &crate::BufferDescriptor<'_>,
+    ) -> dispatch::DispatchBuffer {
+        let (id, error) = self.context.0.heap_create_buffer(
+            self.id,
+            offset,
+            &desc.map_label(|l| l.map(Borrowed)),
+            None,
+        );
+        if let Some(cause) = error {
+            self.context
+                .handle_error(&self.error_sink, cause, desc.label, "Heap::create_buffer");
+        }
+
+        CoreBuffer {
+            context: self.context.clone(),
+            id,
+            error_sink: Arc::clone(&self.error_sink),
+        }
+        .into()
+    }
+
+    fn create_texture(
+        &self,
+        offset: crate::BufferAddress,
+        desc: &crate::TextureDescriptor<'_>,
+    ) -> dispatch::DispatchTexture {
+        let wgt_desc = desc.map_label_and_view_formats(|l| l.map(Borrowed), |v| v.to_vec());
+        let (id, error) = self
+            .context
+            .0
+            .heap_create_texture(self.id, offset, &wgt_desc, None);
+        if let Some(cause) = error {
+            self.context
+                .handle_error(&self.error_sink, cause, desc.label, "Heap::create_texture");
+        }
+
+        CoreTexture {
+            context: self.context.clone(),
+            id,
+            error_sink: Arc::clone(&self.error_sink),
+        }
+        .into()
+    }
+
+    fn generate_allocation_report(&self) -> Option<crate::AllocatorReport> {
+        self.context.0.heap_generate_allocation_report(self.id).ok()
+    }
+}
+
+impl Drop for CoreHeap {
+    fn drop(&mut self) {
+        self.context.0.heap_drop(self.id)
+    }
+}
+
 impl dispatch::PipelineLayoutInterface for CorePipelineLayout {}
 
 impl Drop for CorePipelineLayout {
@@ -2592,6 +3028,31 @@ impl dispatch::CommandEncoderInterface for CoreCommandEncoder {
         }
     }
 
+    fn aliasing_barrier<'a>(
+        &self,
+        buffers: &mut dyn Iterator<Item = &'a dispatch::DispatchBuffer>,
+        textures: &mut dyn Iterator<Item = &'a dispatch::DispatchTexture>,
+    ) {
+        let buffers = buffers
+            .map(|buffer| buffer.as_core().id)
+            .collect::<SmallVec<[_; 4]>>();
+        let textures = textures
+            .map(|texture| texture.as_core().id)
+            .collect::<SmallVec<[_; 4]>>();
+
+        if let Err(cause) = self
+            .context
+            .0
+            .command_encoder_aliasing_barrier(self.id, &buffers, &textures)
+        {
+            self.context.handle_error_nolabel(
+                &self.error_sink,
+                cause,
+                "CommandEncoder::aliasing_barrier",
+            );
+        }
+    }
+
     fn insert_debug_marker(&self, label: &str) {
         if let Err(cause) = self
             .context
@@ -39,6 +39,8 @@ trait_alias!(RequestAdapterFuture: Future<Output = Result<DispatchAdapter, wgt::
 trait_alias!(RequestDeviceFuture: Future<Output = Result<(DispatchDevice, DispatchQueue), crate::RequestDeviceError>> + WasmNotSend + 'static);
 trait_alias!(PopErrorScopeFuture: Future<Output = Option<crate::Error>> + WasmNotSend + 'static);
 trait_alias!(ShaderCompilationInfoFuture: Future<Output = crate::CompilationInfo> + WasmNotSend + 'static);
+trait_alias!(CreateRenderPipelineFuture: Future<Output = Result<DispatchRenderPipeline, crate::Error>> + WasmNotSend + 'static);
+trait_alias!(CreateComputePipelineFuture: Future<Output = Result<DispatchComputePipeline, crate::Error>> + WasmNotSend + 'static);
 
 // We can't use trait aliases here, as you can't convert from a dyn Trait to dyn Supertrait _yet_.
 #[cfg(send_sync)]
@@ -147,6 +149,10 @@ pub trait DeviceInterface: CommonTraits {
         &self,
         desc: &crate::RenderPipelineDescriptor<'_>,
     ) -> DispatchRenderPipeline;
+    fn create_render_pipeline_async(
+        &self,
+        desc: &crate::RenderPipelineDescriptor<'_>,
+    ) -> Pin<Box<dyn CreateRenderPipelineFuture>>;
     fn create_mesh_pipeline(
         &self,
         desc: &crate::MeshPipelineDescriptor<'_>,
@@ -155,10 +161,18 @@ pub trait DeviceInterface: CommonTraits {
         &self,
         desc: &crate::ComputePipelineDescriptor<'_>,
     ) -> DispatchComputePipeline;
+    fn create_compute_pipeline_async(
+        &self,
+        desc: &crate::ComputePipelineDescriptor<'_>,
+    ) -> Pin<Box<dyn CreateComputePipelineFuture>>;
     unsafe fn create_pipeline_cache(
         &self,
         desc: &crate::PipelineCacheDescriptor<'_>,
     ) -> DispatchPipelineCache;
+    fn derive_bind_group_layouts(
+        &self,
+        entry_points: &[(&crate::ShaderModule, &str)],
+    ) -> Result<Vec<Vec<crate::BindGroupLayoutEntry>>, crate::Error>;
     fn create_buffer(&self, desc: &crate::BufferDescriptor<'_>) -> DispatchBuffer;
     fn create_texture(&self, desc: &crate::TextureDescriptor<'_>) -> DispatchTexture;
     fn create_external_texture(
@@ -174,6 +188,15 @@ pub trait DeviceInterface: CommonTraits {
     fn create_tlas(&self, desc: &crate::CreateTlasDescriptor<'_>) -> DispatchTlas;
     fn create_sampler(&self, desc: &crate::SamplerDescriptor<'_>) -> DispatchSampler;
     fn create_query_set(&self, desc: &crate::QuerySetDescriptor<'_>) -> DispatchQuerySet;
+    fn create_heap(&self, desc: &crate::HeapDescriptor<'_>) -> DispatchHeap;
+    fn get_buffer_memory_requirements(
+        &self,
+        desc: &crate::BufferDescriptor<'_>,
+    ) -> Option<crate::MemoryRequirements>;
+    fn get_texture_memory_requirements(
+        &self,
+        desc: &crate::TextureDescriptor<'_>,
+    ) -> Option<crate::MemoryRequirements>;
     fn create_command_encoder(
         &self,
         desc: &crate::CommandEncoderDescriptor<'_>,
@@ -182,6 +205,7 @@ pub trait DeviceInterface: CommonTraits {
         &self,
         desc: &crate::RenderBundleEncoderDescriptor<'_>,
     ) -> DispatchRenderBundleEncoder;
+    fn create_queue(&self, desc: &crate::QueueDescriptor<'_>) -> Option<DispatchQueue>;
 
     fn set_device_lost_callback(&self, device_lost_callback: BoxDeviceLostCallback);
 
@@ -232,7 +256,18 @@ pub trait QueueInterface: CommonTraits {
         size: crate::Extent3d,
     );
 
+    fn update_sparse_residency(
+        &self,
+        buffers: &[crate::SparseBufferBinding<'_>],
+        textures: &[crate::SparseTextureBinding<'_>],
+    );
+
     fn submit(&self, command_buffers: &mut dyn Iterator<Item = DispatchCommandBuffer>) -> u64;
+    fn create_command_encoder(
+        &self,
+        desc: &crate::CommandEncoderDescriptor<'_>,
+    ) -> DispatchCommandEncoder;
+    fn wait_for_submission(&self, other: &DispatchQueue, submission_index: u64);
 
     fn get_timestamp_period(&self) -> f32;
     fn on_submitted_work_done(&self, callback: BoxSubmittedWorkDoneCallback);
@@ -275,6 +310,19 @@ pub trait BlasInterface: CommonTraits {
 }
 pub trait TlasInterface: CommonTraits {}
 pub trait QuerySetInterface: CommonTraits {}
+pub trait HeapInterface: CommonTraits {
+    fn create_buffer(
+        &self,
+        offset: crate::BufferAddress,
+        desc: &crate::BufferDescriptor<'_>,
+    ) -> DispatchBuffer;
+    fn create_texture(
+        &self,
+        offset: crate::BufferAddress,
+        desc: &crate::TextureDescriptor<'_>,
+    ) -> DispatchTexture;
+    fn generate_allocation_report(&self) -> Option<crate::AllocatorReport>;
+}
 pub trait PipelineLayoutInterface: CommonTraits {}
 pub trait RenderPipelineInterface: CommonTraits {
     fn get_bind_group_layout(&self, index: u32) -> DispatchBindGroupLayout;
@@ -328,6 +376,11 @@ pub trait CommandEncoderInterface: CommonTraits {
         offset: crate::BufferAddress,
         size: Option<crate::BufferAddress>,
     );
+    fn aliasing_barrier<'a>(
+        &self,
+        buffers: &mut dyn Iterator<Item = &'a DispatchBuffer>,
+        textures: &mut dyn Iterator<Item = &'a DispatchTexture>,
+    );
 
     fn insert_debug_marker(&self, label: &str);
     fn push_debug_group(&self, label: &str);
@@ -876,6 +929,7 @@ dispatch_types! {ref type DispatchExternalTexture: ExternalTextureInterface = Co
 dispatch_types! {ref type DispatchBlas: BlasInterface = CoreBlas, WebBlas, DynBlas}
 dispatch_types! {ref type DispatchTlas: TlasInterface = CoreTlas, WebTlas, DynTlas}
 dispatch_types! {ref type DispatchQuerySet: QuerySetInterface = CoreQuerySet, WebQuerySet, DynQuerySet}
+dispatch_types! {ref type DispatchHeap: HeapInterface = CoreHeap, WebHeap, DynHeap}
 dispatch_types! {ref type DispatchPipelineLayout: PipelineLayoutInterface = CorePipelineLayout, WebPipelineLayout, DynPipelineLayout}
 dispatch_types! {ref type DispatchRenderPipeline: RenderPipelineInterface = CoreRenderPipeline, WebRenderPipeline, DynRenderPipeline}
 dispatch_types! {ref type DispatchComputePipeline: ComputePipelineInterface = CoreComputePipeline, WebComputePipeline, DynComputePipeline}